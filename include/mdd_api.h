@@ -0,0 +1,94 @@
+#ifndef MDD_API_H
+#define MDD_API_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Release bundle owned by the caller; free it with `mdd_release_free`.
+typedef struct MddRelease MddRelease;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Message of the last error on this thread, or `NULL` if none.
+//
+// The pointer stays valid until the next failing call on the same thread;
+// do not free it.
+const char *mdd_last_error(void);
+
+// Parse a species CSV into a JSON array of `MddData` records.
+//
+// # Safety
+// `csv_data` must point to `len` readable bytes.
+char *mdd_parse_species_csv(const uint8_t *csv_data, uintptr_t len);
+
+// Parse a synonym CSV into a JSON array of `SynonymData` records.
+//
+// # Safety
+// `csv_data` must point to `len` readable bytes.
+char *mdd_parse_synonyms_csv(const uint8_t *csv_data, uintptr_t len);
+
+// Parse species and synonym CSVs into a release bundle.
+//
+// # Safety
+// `species_csv` and `synonym_csv` must point to `species_len` and
+// `synonym_len` readable bytes; `version` and `release_date` must be
+// NUL-terminated strings.
+struct MddRelease *mdd_release_from_csv(const uint8_t *species_csv,
+                                        uintptr_t species_len,
+                                        const uint8_t *synonym_csv,
+                                        uintptr_t synonym_len,
+                                        const char *version,
+                                        const char *release_date);
+
+// Load a JSON bundle written by `mdd json`.
+//
+// # Safety
+// `json_data` must point to `len` readable bytes.
+struct MddRelease *mdd_release_from_json(const uint8_t *json_data, uintptr_t len);
+
+// Serialize a release bundle to JSON.
+//
+// # Safety
+// `release` must be `NULL` or a pointer returned by this library and not
+// yet freed.
+char *mdd_release_to_json(const struct MddRelease *release);
+
+// Per-country statistics (`CountryMDDStats`) of a release bundle as JSON.
+//
+// # Safety
+// `release` must be `NULL` or a pointer returned by this library and not
+// yet freed.
+char *mdd_release_country_stats(const struct MddRelease *release);
+
+// Number of species in a release bundle; `0` for `NULL`.
+//
+// # Safety
+// `release` must be `NULL` or a pointer returned by this library and not
+// yet freed.
+uintptr_t mdd_release_species_count(const struct MddRelease *release);
+
+// Free a release bundle. `NULL` is ignored.
+//
+// # Safety
+// `release` must be `NULL` or a pointer returned by this library and not
+// yet freed.
+void mdd_release_free(struct MddRelease *release);
+
+// Free a string returned by this library. `NULL` is ignored.
+//
+// # Safety
+// `value` must be `NULL` or a string returned by this library and not yet
+// freed.
+void mdd_string_free(char *value);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MDD_API_H */
@@ -6,55 +6,199 @@
 //!
 //! Subcommands:
 //! * `json`  – Parse species + synonym CSVs and export JSON (optionally limit or prefix files).
-//! * `db`    – (Planned/placeholder) ingest JSON into a SQLite database.
-//! * `toml`  – Parse release metadata TOML plus referenced CSVs (future expansion).
-//! * `zip`   – Parse directly from a zipped archive (future/support tooling).
+//! * `db`    – Load an exported JSON bundle (`.json`, `.json.gz`, `.json.zst`, or `.json.br`) into a SQLite database.
+//! * `toml`  – Parse release metadata TOML plus the CSVs it references.
+//! * `diff`  – Compare two releases and write a JSON + Markdown changelog.
+//! * `changelog` – Write release notes grouping new species, splits, lumps, transfers, and IUCN updates.
+//! * `batch` – Parse every release archive and TOML of a directory into `v<version>` subdirectories plus a `releases.json` index.
+//! * `fetch` – Download a release archive from Zenodo/GitHub and parse it (`fetch` feature).
+//! * `gbif`  – Match species to GBIF Backbone taxon keys (`enrich` feature).
+//! * `iucn`  – Compare `iucnStatus` with the live IUCN Red List categories (`enrich` feature).
+//! * `wikidata` – Link species to Wikidata items and Wikipedia articles (`enrich` feature).
+//! * `validate` – Run data-quality checks over species + synonym CSVs.
+//! * `reconcile` – Match a list of scientific names against MDD species and synonyms.
+//! * `render` – Render one Markdown or HTML page per species from Tera templates (`render` feature).
+//! * `zip`   – Parse a release zip, tar.gz, or directory of release files.
+//! * `package` – Assemble a release TOML and its CSVs into the `MDD_v<version>.zip` read by `zip`.
+//!
+//! Global flags: `--verbose/-v` shows debug output, `--quiet/-q` limits
+//! output to warnings and errors, and `--error-format json` prints failures
+//! as JSON.
 //!
 //! Most file path arguments default to relative names to simplify quick starts;
-//! override them for production workflows.
+//! override them for production workflows. Commands reading CSVs take
+//! `--delimiter` (`,` by default; `tab` for TSV).
+
+use std::{net::IpAddr, path::PathBuf};
 
-use std::path::PathBuf;
+use clap::{
+    crate_authors, crate_description, crate_name, crate_version, Args, Parser, Subcommand,
+    ValueEnum,
+};
+use mdd_api::{
+    parser::options::CsvDialect,
+    writer::{compression::Compression, json::JsonStyle, naming::FieldNaming, sharded::ShardKey},
+};
 
-use clap::{crate_authors, crate_description, crate_name, crate_version, Args, Parser};
+use crate::error::ErrorFormat;
 
-/// Top-level CLI dispatcher enumerating supported subcommands.
+/// Top-level CLI: global output flags plus the subcommand to run.
 #[derive(Parser)]
 #[command(name = crate_name!(), version = crate_version!(), about = crate_description!(), author = crate_authors!())]
-pub enum Cli {
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Commands,
+    /// Show debug output.
+    #[arg(
+        long,
+        short,
+        global = true,
+        conflicts_with = "quiet",
+        help = "Show debug output"
+    )]
+    pub verbose: bool,
+    /// Only show warnings and errors.
+    #[arg(long, short, global = true, help = "Only show warnings and errors")]
+    pub quiet: bool,
+    /// Format of error messages on stderr.
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = ErrorFormat::Text,
+        help = "Format of error messages on stderr"
+    )]
+    pub error_format: ErrorFormat,
+}
+
+/// Supported subcommands.
+#[derive(Subcommand)]
+pub enum Commands {
     /// Parse MDD + synonym CSV files and export structured JSON (and optionally plain text outputs).
     #[command(name = "json", about = "Parse and export MDD data to JSON")]
     ToJson(JsonArgs),
-    /// Convert parsed JSON into a SQLite database (implementation may still be evolving).
-    #[command(name = "db", about = "Parse and export MDD data to SQLite database")]
+    /// Convert an exported JSON bundle into a normalized SQLite or DuckDB database.
+    #[command(
+        name = "db",
+        about = "Parse and export MDD data to SQLite or DuckDB database"
+    )]
     ToDb(DbArgs),
-    /// Parse release metadata from a TOML file (and potentially drive batch exports).
+    /// Parse the CSVs referenced by a release TOML and export JSON with its metadata.
     #[command(name = "toml", about = "Parse and export MDD data from TOML file")]
     FromToml(FromTomlArgs),
-    /// Read compressed (zip) inputs (placeholder / help documentation stub).
-    #[command(name = "zip", about = "Display help information")]
+    /// Compare two releases (archives, directories, SQLite exports, or JSON bundles).
+    #[command(name = "diff", about = "Compare two MDD releases")]
+    Diff(DiffArgs),
+    /// Write release notes between two releases (zips or JSON bundles).
+    #[command(
+        name = "changelog",
+        about = "Write release notes between two MDD releases"
+    )]
+    Changelog(ChangelogArgs),
+    /// Per-country species counts and changes across several releases.
+    #[command(
+        name = "trends",
+        about = "Compare country statistics across MDD releases"
+    )]
+    Trends(ReleaseSeriesArgs),
+    /// Per-species life histories (additions, renames, removals) across several releases.
+    #[command(name = "history", about = "Trace species across MDD releases")]
+    History(ReleaseSeriesArgs),
+    /// Parse every release archive and TOML of a directory into versioned subdirectories.
+    #[command(name = "batch", about = "Parse every MDD release in a directory")]
+    Batch(BatchArgs),
+    /// Download a release archive and parse it like the `zip` subcommand.
+    #[command(name = "fetch", about = "Download and parse an MDD release")]
+    Fetch(FetchArgs),
+    /// Match species against the GBIF Backbone and write their taxon keys.
+    #[command(name = "gbif", about = "Match species to GBIF Backbone taxon keys")]
+    Gbif(GbifArgs),
+    /// Compare `iucnStatus` with the live IUCN Red List categories.
+    #[command(
+        name = "iucn",
+        about = "Check IUCN categories against the Red List API"
+    )]
+    Iucn(IucnArgs),
+    /// Resolve species to Wikidata items and Wikipedia articles.
+    #[command(
+        name = "wikidata",
+        about = "Link species to Wikidata items and Wikipedia articles"
+    )]
+    Wikidata(WikidataArgs),
+    /// Check species + synonym CSVs for consistency problems.
+    #[command(name = "validate", about = "Run data-quality checks on MDD CSV files")]
+    Validate(ValidateArgs),
+    /// Match external scientific names against MDD.
+    #[command(name = "reconcile", about = "Reconcile a list of names against MDD")]
+    Reconcile(ReconcileArgs),
+    /// Write a release TOML (version, checksums, DOI) for a species and a synonym CSV.
+    #[command(
+        name = "init-release",
+        about = "Generate a release.toml from MDD CSV files"
+    )]
+    InitRelease(InitReleaseArgs),
+    /// Print headline numbers of a species CSV.
+    #[command(name = "stats", about = "Print a quick summary of an MDD CSV file")]
+    Stats(StatsArgs),
+    /// Write MDD → MSW3 and MDD → CMW name mapping tables.
+    #[command(
+        name = "crosswalk",
+        about = "Export MSW3, CMW, ITIS, and NCBI crosswalk tables"
+    )]
+    Crosswalk(CrosswalkArgs),
+    /// Write the common names of a species CSV as a table.
+    #[command(name = "vernacular", about = "Export the common name table")]
+    Vernacular(VernacularArgs),
+    /// Write JSON Schemas of the exported JSON structures.
+    #[command(name = "schema", about = "Write JSON Schemas of the output files")]
+    Schema(SchemaArgs),
+    /// Serve an exported JSON bundle over HTTP.
+    #[command(name = "serve", about = "Serve MDD data as a REST (and GraphQL) API")]
+    Serve(ServeArgs),
+    /// Render one Markdown or HTML page per species for a static site.
+    #[command(name = "render", about = "Render static species pages")]
+    Render(RenderArgs),
+    /// Read a release zip, tar.gz, or directory of release files.
+    #[command(name = "zip", about = "Parse an MDD release zip, tar.gz, or directory")]
     FromZip(FromZipArgs),
+    /// Assemble a release TOML and its CSVs into a distributable release zip.
+    #[command(name = "package", about = "Package release files into an MDD zip")]
+    Package(PackageArgs),
 }
 
 /// Arguments for the `json` subcommand.
 #[derive(Args)]
 pub struct JsonArgs {
-    /// Input MDD species CSV file.
-    #[arg(long, short, default_value = "data.csv", help = "Input MDD CSV file")]
+    /// Input MDD species CSV file (`-` for stdin), a directory holding both
+    /// CSVs, or the workbook with `--input-format xlsx`. The two CSVs are
+    /// swapped when their header rows show they were given the wrong way round.
+    #[arg(
+        long,
+        short,
+        default_value = "data.csv",
+        help = "Input MDD CSV file (- for stdin)"
+    )]
     pub input: PathBuf,
-    /// Input synonym CSV file.
+    /// Input synonym CSV file (`-` for stdin; unused with `--input-format xlsx`).
     #[arg(
         long,
         short,
         default_value = "synonyms.csv",
-        help = "Input synonyms CSV file"
+        help = "Input synonyms CSV file (- for stdin)"
     )]
     pub synonym: PathBuf,
-    /// Output directory for generated files.
+    /// Format of the input files.
+    #[arg(long, value_enum, default_value_t = InputFormat::Csv, help = "Input format")]
+    pub input_format: InputFormat,
+    #[command(flatten)]
+    pub csv: CsvInputArgs,
+    /// Output directory for generated files, or `-` to write only the
+    /// release bundle to stdout.
     #[arg(
         long,
         short,
         default_value = "../assets/data",
-        help = "Output directory"
+        help = "Output directory (- for the bundle on stdout)"
     )]
     pub output: PathBuf,
     /// Whether to also export plain text data (if supported by writers).
@@ -72,14 +216,612 @@ pub struct JsonArgs {
     /// Add a file name prefix to all exported artifacts.
     #[arg(long, help = "Add prefix to output files")]
     pub prefix: Option<String>,
+    /// Serialization format of the release bundle.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Json, help = "Output format")]
+    pub format: OutputFormat,
+    /// Split the release bundle into one JSON file per order or family.
+    #[arg(
+        long,
+        value_enum,
+        conflicts_with = "format",
+        help = "Split output by taxon"
+    )]
+    pub split_by: Option<SplitBy>,
+    /// Previous release bundle whose species and synonym slugs are kept.
+    #[arg(long, help = "Previous JSON bundle to keep slugs from")]
+    pub previous: Option<PathBuf>,
+    /// Compression of the JSON and NDJSON output; gzip for `--format json`
+    /// and none for `--format ndjson` if omitted.
+    #[arg(long, value_enum, help = "Output compression")]
+    pub compression: Option<CompressionKind>,
+    /// Compression level (gzip 0-9, zstd 1-22, brotli 0-11); format default if omitted.
+    #[arg(long, help = "Compression level")]
+    pub level: Option<u32>,
+    /// Species record fields (JSON names) kept in the `json` output, e.g.
+    /// `id,sciName,family,iucnStatus`; synonyms are left out.
+    #[arg(long, value_delimiter = ',', help = "Only write these species fields")]
+    pub fields: Vec<String>,
+    #[command(flatten)]
+    pub json: JsonOutputArgs,
+    /// Trim leading and trailing whitespace of every text column before bundling.
+    #[arg(long, help = "Trim whitespace of all text fields")]
+    pub trim: bool,
+    /// Skip `manifest.json`, which lists every written file with its size
+    /// and SHA-256 hash.
+    #[arg(long, help = "Do not write manifest.json")]
+    pub no_manifest: bool,
+    /// Directory remembering the input hashes and options of the last run;
+    /// an unchanged run whose outputs still exist is skipped.
+    #[arg(long, help = "Cache directory for skipping unchanged runs")]
+    pub cache_dir: Option<PathBuf>,
+    /// Rebuild even if the cache says the outputs are up to date.
+    #[arg(long, requires = "cache_dir", help = "Ignore the cache and rebuild")]
+    pub force: bool,
+}
+
+/// Compression format of the exported release bundle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompressionKind {
+    /// Uncompressed output only.
+    None,
+    /// `.gz`, readable everywhere.
+    Gzip,
+    /// `.zst`, fast to decode in data pipelines.
+    Zstd,
+    /// `.br`, smallest for static web hosting.
+    Brotli,
+}
+
+impl CompressionKind {
+    /// Compression used when `--compression` is omitted: gzip for the JSON
+    /// bundle, none for the other formats.
+    pub fn default_for(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Json => Self::Gzip,
+            OutputFormat::Ndjson | OutputFormat::PerSpecies => Self::None,
+        }
+    }
+
+    /// Library compression setting with `level`, or the format default.
+    pub fn to_compression(self, level: Option<u32>) -> Compression {
+        let compression = match self {
+            Self::None => Compression::None,
+            Self::Gzip => Compression::gzip(),
+            Self::Zstd => Compression::zstd(),
+            Self::Brotli => Compression::brotli(),
+        };
+        match level {
+            Some(level) => compression.with_level(level),
+            None => compression,
+        }
+    }
+}
+
+/// Output options shared by the commands writing JSON bundles.
+#[derive(Args)]
+pub struct JsonOutputArgs {
+    /// Pretty-print the JSON bundle with alphabetically sorted keys.
+    #[arg(long, help = "Pretty-print JSON with sorted keys")]
+    pub pretty: bool,
+    /// Spelling of the JSON keys.
+    #[arg(long, value_enum, default_value_t = FieldNames::Camel, help = "JSON key spelling")]
+    pub field_names: FieldNames,
+}
+
+impl JsonOutputArgs {
+    /// Stable pretty-printed JSON for `--pretty`, compact otherwise, with
+    /// keys spelled as `--field-names` selects.
+    pub fn json_style(&self) -> JsonStyle {
+        let style = if self.pretty {
+            JsonStyle::stable()
+        } else {
+            JsonStyle::new()
+        };
+        style.with_field_names(self.field_names.to_field_naming())
+    }
+}
+
+/// Spelling of the keys in JSON output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FieldNames {
+    /// camelCase serde names (`sciName`), readable by the parser.
+    Camel,
+    /// snake_case names (`sci_name`).
+    Snake,
+    /// MDD CSV headers for species and synonym records (`order`, `MDD_root_name`).
+    Original,
+}
+
+impl FieldNames {
+    /// Library naming profile.
+    pub fn to_field_naming(self) -> FieldNaming {
+        match self {
+            Self::Camel => FieldNaming::CamelCase,
+            Self::Snake => FieldNaming::SnakeCase,
+            Self::Original => FieldNaming::Original,
+        }
+    }
+}
+
+/// Format of the species and synonym input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    /// Separate species and synonym CSV files.
+    Csv,
+    /// One workbook (`.xlsx`, `.xls`, `.ods`) with a species and a synonym sheet.
+    Xlsx,
+}
+
+/// Serialization format of the exported release bundle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// A single JSON document (plus a compressed copy).
+    Json,
+    /// One species entry per line (newline-delimited JSON).
+    Ndjson,
+    /// One JSON file per species under `species/` plus an `index.json` manifest.
+    PerSpecies,
+}
+
+/// Taxonomic rank the release bundle is split by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SplitBy {
+    /// One shard per order.
+    Order,
+    /// One shard per family.
+    Family,
+}
+
+impl SplitBy {
+    /// Library shard key for this rank.
+    pub fn to_shard_key(self) -> ShardKey {
+        match self {
+            Self::Order => ShardKey::Order,
+            Self::Family => ShardKey::Family,
+        }
+    }
 }
 
 /// Arguments for the `db` subcommand (JSON to SQLite pipeline).
 #[derive(Args)]
 pub struct DbArgs {
-    /// Input JSON file containing previously parsed MDD data.
-    #[arg(long, short, default_value = "data.json", help = "Input MDD CSV file")]
+    /// Input JSON (or gzipped JSON) bundle produced by the `json`/`zip` subcommands.
+    #[arg(
+        long,
+        short,
+        default_value = "data.json",
+        help = "Input MDD JSON file (.json, optionally .gz/.zst/.br compressed)"
+    )]
+    pub input: PathBuf,
+    /// Output database path (default: `mdd.sqlite` / `mdd.duckdb`). An existing file is replaced.
+    #[arg(long, short, help = "Output database file")]
+    pub output: Option<PathBuf>,
+    /// Database engine of the export.
+    #[arg(long, value_enum, default_value_t = DbFormat::Sqlite, help = "Database format")]
+    pub format: DbFormat,
+}
+
+/// Database engine of the `db` export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DbFormat {
+    /// SQLite database with an FTS5 search index.
+    Sqlite,
+    /// DuckDB database with the same tables (`duckdb` feature).
+    Duckdb,
+}
+
+impl DbFormat {
+    /// Default output file name.
+    pub fn default_output(&self) -> &'static str {
+        match self {
+            Self::Sqlite => "mdd.sqlite",
+            Self::Duckdb => "mdd.duckdb",
+        }
+    }
+}
+
+/// Arguments for the `serve` subcommand.
+#[derive(Args)]
+pub struct ServeArgs {
+    /// JSON (or compressed JSON) bundle produced by the `json`/`zip` subcommands.
+    #[arg(
+        long,
+        short,
+        default_value = "data.json",
+        help = "Input MDD JSON file (.json, optionally .gz/.zst/.br compressed)"
+    )]
+    pub input: PathBuf,
+    /// Also serve the GraphQL API at `/graphql`.
+    #[arg(long, help = "Also serve a GraphQL API")]
+    pub graphql: bool,
+    /// Address to listen on.
+    #[arg(long, default_value = "127.0.0.1", help = "Address to listen on")]
+    pub host: IpAddr,
+    /// Port to listen on.
+    #[arg(long, short, default_value_t = 8080, help = "Port to listen on")]
+    pub port: u16,
+}
+
+/// Arguments for the `render` subcommand.
+#[derive(Args)]
+pub struct RenderArgs {
+    /// JSON (or compressed JSON) bundle produced by the `json`/`zip` subcommands.
+    #[arg(
+        long,
+        short,
+        default_value = "data.json",
+        help = "Input MDD JSON file (.json, optionally .gz/.zst/.br compressed)"
+    )]
+    pub input: PathBuf,
+    /// Output directory for `index.<ext>` and `species/`.
+    #[arg(long, short, default_value = "site", help = "Output directory")]
+    pub output: PathBuf,
+    /// Page format.
+    #[arg(long, value_enum, default_value_t = PageFormatKind::Md, help = "Page format")]
+    pub format: PageFormatKind,
+    /// Tera template replacing the built-in species page.
+    #[arg(long, help = "Species page template")]
+    pub template: Option<PathBuf>,
+    /// Tera template replacing the built-in index page.
+    #[arg(long, help = "Index page template")]
+    pub index_template: Option<PathBuf>,
+    /// Name species pages by slug instead of MDD id.
+    #[arg(long, help = "Name pages by species slug")]
+    pub slug: bool,
+}
+
+/// Format of rendered species pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PageFormatKind {
+    /// Markdown (`.md`), e.g. for static site generators.
+    Md,
+    /// Standalone HTML (`.html`).
+    Html,
+}
+
+/// Arguments for the `diff` subcommand.
+#[derive(Args)]
+pub struct DiffArgs {
+    /// Older release: a release zip, tar.gz, or directory, a SQLite export, or
+    /// a JSON bundle (optionally compressed).
+    #[arg(
+        long,
+        help = "Older MDD release archive, directory, SQLite, or JSON file"
+    )]
+    pub old: PathBuf,
+    /// Newer release: a release zip, tar.gz, or directory, a SQLite export, or
+    /// a JSON bundle (optionally compressed).
+    #[arg(
+        long,
+        help = "Newer MDD release archive, directory, SQLite, or JSON file"
+    )]
+    pub new: PathBuf,
+    /// Output directory for the JSON and Markdown diff and the distribution changes.
+    #[arg(long, short, default_value = ".", help = "Output directory")]
+    pub output: PathBuf,
+}
+
+/// Arguments for the `changelog` subcommand.
+#[derive(Args)]
+pub struct ChangelogArgs {
+    /// Older release: a release zip, tar.gz, or directory, a SQLite export, or a
+    /// JSON bundle (optionally compressed).
+    #[arg(
+        long,
+        help = "Older MDD release archive, directory, SQLite, or JSON file"
+    )]
+    pub old: PathBuf,
+    /// Newer release: a release zip, tar.gz, or directory, a SQLite export, or a
+    /// JSON bundle (optionally compressed).
+    #[arg(
+        long,
+        help = "Newer MDD release archive, directory, SQLite, or JSON file"
+    )]
+    pub new: PathBuf,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = ChangelogFormat::Md, help = "Changelog format")]
+    pub format: ChangelogFormat,
+    /// Output directory for `changelog.<ext>`.
+    #[arg(long, short, default_value = ".", help = "Output directory")]
+    pub output: PathBuf,
+}
+
+/// Arguments for the `trends` and `history` subcommands.
+#[derive(Args)]
+pub struct ReleaseSeriesArgs {
+    /// Releases, oldest first: release zips, tarballs, or directories, SQLite
+    /// exports, or JSON bundles.
+    #[arg(
+        long,
+        short,
+        required = true,
+        num_args = 1..,
+        help = "MDD release archive, directory, SQLite, or JSON file (repeat, oldest first)"
+    )]
+    pub input: Vec<PathBuf>,
+    /// Output directory for `country_trends.json` (`trends`), or
+    /// `species_history.csv` and `species_history_events.csv` (`history`).
+    #[arg(long, short, default_value = ".", help = "Output directory")]
+    pub output: PathBuf,
+}
+
+/// Arguments for the `batch` subcommand.
+#[derive(Args)]
+pub struct BatchArgs {
+    /// Directory of release zips, tarballs, and release TOMLs.
+    #[arg(
+        long,
+        short,
+        default_value = ".",
+        help = "Directory of MDD release archives and TOML files"
+    )]
+    pub input_dir: PathBuf,
+    /// Output directory for the `v<version>` subdirectories and `releases.json`.
+    #[arg(long, short, default_value = ".", help = "Output directory")]
+    pub output_dir: PathBuf,
+    /// Parse TOML releases without checking the CSVs against the recorded digests.
+    #[arg(long, help = "Skip checksum verification of TOML releases")]
+    pub no_verify: bool,
+    #[command(flatten)]
+    pub json: JsonOutputArgs,
+}
+
+/// Format of the release changelog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ChangelogFormat {
+    /// Markdown release notes.
+    Md,
+    /// The grouped changes as JSON.
+    Json,
+}
+
+/// Arguments for the `init-release` subcommand.
+#[derive(Args)]
+pub struct InitReleaseArgs {
+    /// Species CSV of the release, e.g. `MDD_v2.2_6815species.csv`.
+    #[arg(long = "mdd", help = "MDD species CSV file")]
+    pub mdd_file: PathBuf,
+    /// Synonym CSV of the release.
+    #[arg(long = "syn", help = "MDD synonyms CSV file")]
+    pub synonym_file: PathBuf,
+    /// Release version; inferred from the species file name when omitted.
+    #[arg(long = "version", help = "MDD version")]
+    pub mdd_version: Option<String>,
+    /// Release date (YYYY-MM-DD); today when omitted.
+    #[arg(long = "date", help = "MDD release date")]
+    pub release_date: Option<String>,
+    /// Release DOI; prompted for on a terminal when omitted.
+    #[arg(long, help = "Release DOI")]
+    pub doi: Option<String>,
+    /// Release remarks; prompted for on a terminal when omitted.
+    #[arg(long, help = "Release remarks")]
+    pub remarks: Option<String>,
+    /// Output TOML path.
+    #[arg(long, short, default_value = "release.toml", help = "Output TOML file")]
+    pub output: PathBuf,
+    /// Replace an existing output file.
+    #[arg(long, help = "Overwrite an existing release TOML")]
+    pub force: bool,
+}
+
+/// Arguments for the `stats` subcommand.
+#[derive(Args)]
+pub struct StatsArgs {
+    /// Input MDD species CSV file.
+    #[arg(long, short, default_value = "data.csv", help = "Input MDD CSV file")]
+    pub input: PathBuf,
+    #[command(flatten)]
+    pub csv: CsvInputArgs,
+    /// Also write the summary as JSON.
+    #[arg(long, short, help = "Output JSON file")]
+    pub output: Option<PathBuf>,
+}
+
+/// Arguments for the `crosswalk` subcommand.
+#[derive(Args)]
+pub struct CrosswalkArgs {
+    /// Input MDD species CSV file.
+    #[arg(long, short, default_value = "data.csv", help = "Input MDD CSV file")]
+    pub input: PathBuf,
+    #[command(flatten)]
+    pub csv: CsvInputArgs,
+    /// Output directory for the CSV and JSON tables.
+    #[arg(long, short, default_value = ".", help = "Output directory")]
+    pub output: PathBuf,
+    /// Extracted ITIS dump with `taxonomic_units` and `synonym_links`.
+    #[arg(long, help = "ITIS dump directory for an MDD -> TSN table")]
+    pub itis: Option<PathBuf>,
+    /// Extracted NCBI taxdump with `nodes.dmp` and `names.dmp`.
+    #[arg(long, help = "NCBI taxdump directory for an MDD -> taxid table")]
+    pub ncbi: Option<PathBuf>,
+}
+
+/// Arguments for the `vernacular` subcommand.
+#[derive(Args)]
+pub struct VernacularArgs {
+    /// Input MDD species CSV file.
+    #[arg(long, short, default_value = "data.csv", help = "Input MDD CSV file")]
+    pub input: PathBuf,
+    #[command(flatten)]
+    pub csv: CsvInputArgs,
+    /// Output directory for the CSV and JSON tables.
+    #[arg(long, short, default_value = ".", help = "Output directory")]
+    pub output: PathBuf,
+}
+
+/// Arguments for the `schema` subcommand.
+#[derive(Args)]
+pub struct SchemaArgs {
+    /// Output directory for the `<TypeName>.schema.json` files.
+    #[arg(long, short, default_value = ".", help = "Output directory")]
+    pub output: PathBuf,
+}
+
+/// Arguments for the `fetch` subcommand.
+#[derive(Args)]
+pub struct FetchArgs {
+    /// MDD version to download (e.g. `2.2`).
+    #[arg(long = "version", help = "MDD version to download")]
+    pub mdd_version: String,
+    /// Resolve a specific Zenodo DOI instead of searching by version.
+    #[arg(long, help = "Zenodo DOI of the release", conflicts_with = "github")]
+    pub doi: Option<String>,
+    /// Download from a GitHub release (`owner/repo`, tag `v<version>`) instead of Zenodo.
+    #[arg(long, help = "GitHub repository (owner/repo)")]
+    pub github: Option<String>,
+    /// Output directory for the downloaded archive and parsed content.
+    #[arg(long, short, default_value = ".", help = "Output directory")]
+    pub output: PathBuf,
+}
+
+/// Arguments for the `gbif` subcommand.
+#[derive(Args)]
+pub struct GbifArgs {
+    /// Input MDD species CSV file.
+    #[arg(long, short, default_value = "data.csv", help = "Input MDD CSV file")]
+    pub input: PathBuf,
+    #[command(flatten)]
+    pub csv: CsvInputArgs,
+    /// `Taxon.tsv` of a GBIF Backbone dump; the species-match API is used without it.
+    #[arg(long, help = "GBIF Backbone Taxon.tsv (offline matching)")]
+    pub backbone: Option<PathBuf>,
+    /// Output directory for `gbif_matches.json`.
+    #[arg(long, short, default_value = ".", help = "Output directory")]
+    pub output: PathBuf,
+    /// Milliseconds to wait between API requests.
+    #[arg(long, default_value_t = 100, help = "Delay between requests (ms)")]
+    pub delay: u64,
+    /// Only match the first `n` species.
+    #[arg(long, help = "Limit the number of species")]
+    pub limit: Option<usize>,
+}
+
+/// Arguments for the `iucn` subcommand.
+#[derive(Args)]
+pub struct IucnArgs {
+    /// Input MDD species CSV file.
+    #[arg(long, short, default_value = "data.csv", help = "Input MDD CSV file")]
+    pub input: PathBuf,
+    #[command(flatten)]
+    pub csv: CsvInputArgs,
+    /// IUCN Red List API token.
+    #[arg(
+        long,
+        env = "IUCN_API_TOKEN",
+        hide_env_values = true,
+        help = "IUCN Red List API token"
+    )]
+    pub token: String,
+    /// Output directory for `iucn_report.json` (and `iucn_enriched.json`).
+    #[arg(long, short, default_value = ".", help = "Output directory")]
+    pub output: PathBuf,
+    /// Also write the species records with their live category.
+    #[arg(long, help = "Write records with iucnStatusLive")]
+    pub patched: bool,
+    /// Milliseconds to wait between API requests.
+    #[arg(long, default_value_t = 500, help = "Delay between requests (ms)")]
+    pub delay: u64,
+    /// Only query the first `n` species.
+    #[arg(long, help = "Limit the number of species")]
+    pub limit: Option<usize>,
+}
+
+/// Arguments for the `wikidata` subcommand.
+#[derive(Args)]
+pub struct WikidataArgs {
+    /// Input MDD species CSV file.
+    #[arg(long, short, default_value = "data.csv", help = "Input MDD CSV file")]
+    pub input: PathBuf,
+    #[command(flatten)]
+    pub csv: CsvInputArgs,
+    /// Wikidata JSON dump; the Wikidata Query Service is used without it.
+    #[arg(long, help = "Wikidata JSON dump (offline linking)")]
+    pub dump: Option<PathBuf>,
+    /// Wikipedia language edition of the article links.
+    #[arg(long, default_value = "en", help = "Wikipedia language code")]
+    pub language: String,
+    /// Output directory for `wikidata_links.json`.
+    #[arg(long, short, default_value = ".", help = "Output directory")]
+    pub output: PathBuf,
+    /// Milliseconds to wait between SPARQL queries.
+    #[arg(long, default_value_t = 1000, help = "Delay between queries (ms)")]
+    pub delay: u64,
+    /// Only link the first `n` species.
+    #[arg(long, help = "Limit the number of species")]
+    pub limit: Option<usize>,
+}
+
+/// Arguments for the `validate` subcommand.
+#[derive(Args)]
+pub struct ValidateArgs {
+    /// Input MDD species CSV file, or a directory holding both CSVs.
+    #[arg(long, short, default_value = "data.csv", help = "Input MDD CSV file")]
     pub input: PathBuf,
+    /// Input synonym CSV file.
+    #[arg(
+        long,
+        short,
+        default_value = "synonyms.csv",
+        help = "Input synonyms CSV file"
+    )]
+    pub synonym: PathBuf,
+    #[command(flatten)]
+    pub csv: CsvInputArgs,
+    /// Output path of the JSON report.
+    #[arg(
+        long,
+        short,
+        default_value = "validation_report.json",
+        help = "Output JSON report"
+    )]
+    pub output: PathBuf,
+    /// Also write the grouped homonym report to this path.
+    #[arg(long, help = "Output JSON homonym report")]
+    pub homonyms: Option<PathBuf>,
+    /// Also write the flagged species report into this directory.
+    #[arg(long, help = "Output directory of the flagged species report")]
+    pub flagged: Option<PathBuf>,
+    /// Also write accepted-name candidates of the synonyms without a
+    /// species id into this directory.
+    #[arg(long, help = "Output directory of the synonym-only report")]
+    pub synonym_only: Option<PathBuf>,
+    /// GeoJSON FeatureCollection of country outlines (e.g. Natural Earth
+    /// admin-0) replacing the bundled simplified ones; synonym type
+    /// coordinates outside their `type_country` are reported (`geo` feature).
+    #[arg(long, help = "GeoJSON country outlines overriding the bundled ones")]
+    pub country_shapes: Option<PathBuf>,
+    /// Exit with a non-zero status on warnings, not only errors.
+    #[arg(long, help = "Treat warnings as errors")]
+    pub deny_warnings: bool,
+}
+
+/// Arguments for the `reconcile` subcommand.
+#[derive(Args)]
+pub struct ReconcileArgs {
+    /// Text file with one scientific name per line (`#` starts a comment).
+    #[arg(long, short, help = "Input names file")]
+    pub names: PathBuf,
+    /// Input MDD species CSV file.
+    #[arg(long, short, default_value = "data.csv", help = "Input MDD CSV file")]
+    pub input: PathBuf,
+    /// Input synonym CSV file.
+    #[arg(
+        long,
+        short,
+        default_value = "synonyms.csv",
+        help = "Input synonyms CSV file"
+    )]
+    pub synonym: PathBuf,
+    #[command(flatten)]
+    pub csv: CsvInputArgs,
+    /// Output CSV with one row per input name.
+    #[arg(
+        long,
+        short,
+        default_value = "reconciled.csv",
+        help = "Output CSV file"
+    )]
+    pub output: PathBuf,
 }
 
 /// Arguments for the `toml` subcommand (release metadata driven parsing).
@@ -94,15 +836,99 @@ pub struct FromTomlArgs {
     /// Whether to export plain text along with JSON (if supported).
     #[arg(long, short, help = "Export plain text data")]
     pub plain_text: bool,
+    /// Parse without checking the CSVs against the digests in the TOML.
+    #[arg(long, help = "Skip checksum verification")]
+    pub no_verify: bool,
 }
 
 /// Arguments for the `zip` subcommand (compressed source processing).
 #[derive(Args)]
 pub struct FromZipArgs {
-    /// Input ZIP archive containing release assets.
-    #[arg(long, short, default_value = "MDD.zip", help = "Input MDD ZIP file")]
+    /// Input release archive (`.zip` or `.tar.gz`) or directory holding the
+    /// release files.
+    #[arg(
+        long,
+        short,
+        default_value = "MDD.zip",
+        help = "Input MDD zip, tar.gz, or directory"
+    )]
     pub input: PathBuf,
     /// Output directory for decompressed / processed content.
     #[arg(long, short, default_value = ".", help = "Output directory")]
     pub output: PathBuf,
+    /// Parse archive entries in memory instead of extracting them to `output`.
+    #[arg(long, help = "Parse without extracting the archive")]
+    pub in_memory: bool,
+    #[command(flatten)]
+    pub json: JsonOutputArgs,
+    /// Glob matching the file name of the species CSV.
+    #[arg(
+        long,
+        default_value = "MDD_v*.csv",
+        help = "Species CSV file name pattern"
+    )]
+    pub species_pattern: String,
+    /// Glob matching the file name of the synonym CSV.
+    #[arg(
+        long,
+        default_value = "Species_Syn_v*.csv",
+        help = "Synonym CSV file name pattern"
+    )]
+    pub synonym_pattern: String,
+    /// Folder of the extracted release holding the CSVs; the whole release
+    /// is searched when it does not exist.
+    #[arg(long, default_value = "MDD", help = "Release folder holding the CSVs")]
+    pub release_dir: String,
+    /// Only match the CSVs by file name, never by their header row.
+    #[arg(long, help = "Do not classify CSVs by their header row")]
+    pub no_sniff: bool,
+}
+
+/// Arguments for the `package` subcommand.
+#[derive(Args)]
+pub struct PackageArgs {
+    /// Release TOML referencing the species and synonym CSVs.
+    #[arg(
+        long,
+        short,
+        default_value = "release.toml",
+        help = "Input release TOML file"
+    )]
+    pub input: PathBuf,
+    /// Output directory of `MDD_v<version>.zip`.
+    #[arg(long, short, default_value = ".", help = "Output directory")]
+    pub output: PathBuf,
+    /// Generated files (e.g. JSON exports) added next to the CSVs.
+    #[arg(long = "include", help = "Extra file to package (repeatable)")]
+    pub include: Vec<PathBuf>,
+    /// Package without checking the CSVs against the digests in the TOML.
+    #[arg(long, help = "Skip checksum verification")]
+    pub no_verify: bool,
+}
+
+/// Input options shared by the commands reading MDD CSVs.
+#[derive(Args)]
+pub struct CsvInputArgs {
+    /// Field delimiter of the input CSVs (`tab` or `\t` for TSV).
+    #[arg(long, default_value = ",", value_parser = parse_delimiter, help = "Input CSV delimiter")]
+    pub delimiter: u8,
+}
+
+impl CsvInputArgs {
+    /// The dialect of the input CSVs.
+    pub fn dialect(&self) -> CsvDialect {
+        CsvDialect::new().with_delimiter(self.delimiter)
+    }
+}
+
+/// Parse `--delimiter`: one ASCII character, or `tab` / `\t`.
+fn parse_delimiter(value: &str) -> Result<u8, String> {
+    match value {
+        "tab" | "\\t" | "\t" => Ok(b'\t'),
+        _ if value.len() == 1 && value.is_ascii() => Ok(value.as_bytes()[0]),
+        _ => Err(format!(
+            "expected a single ASCII character or `tab`, got `{}`",
+            value
+        )),
+    }
 }
@@ -0,0 +1,435 @@
+//! Human-readable changelog between two MDD releases.
+//!
+//! `Changelog` builds on `ReleaseDiff` and groups the changes the way MDD
+//! release notes describe them: new species, splits, lumps, transfers
+//! between genera, other name changes, removed species, and IUCN status
+//! updates. Splits and lumps are the `ReleaseDiff::events` of at least
+//! `Medium` confidence; `Low` ones are left to editor review, and their
+//! species are listed as new or removed.
+//!
+//! `to_markdown` writes publication-ready release notes; the changelog also
+//! serializes to JSON (`to_json`).
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    diff::{
+        display_name, ChangeKind, Confidence, ReleaseDiff, RenamedSpecies, SpeciesRef,
+        TaxonomicEventKind,
+    },
+    parser::ReleasedMddData,
+};
+
+/// Field holding the genus in `FieldChange::field`.
+const GENUS_FIELD: &str = "genus";
+/// Weakest split or lump confidence listed in the changelog.
+const MIN_CONFIDENCE: Confidence = Confidence::Medium;
+
+/// A species of the older release split into one or more new species.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Split {
+    /// The species in the older release.
+    pub source: SpeciesRef,
+    /// Other species carrying names previously treated under `source`.
+    pub species: Vec<SpeciesRef>,
+    pub confidence: Confidence,
+}
+
+/// Species of the older release merged into one species of the newer release.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Lump {
+    /// The species in the newer release.
+    pub target: SpeciesRef,
+    /// Other species whose names are now synonyms of `target`.
+    pub merged: Vec<SpeciesRef>,
+    pub confidence: Confidence,
+}
+
+/// A species moved to another genus while keeping its MDD id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transfer {
+    pub id: u32,
+    pub old_name: String,
+    pub new_name: String,
+    pub old_genus: String,
+    pub new_genus: String,
+}
+
+/// A change of the IUCN Red List category.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IucnUpdate {
+    pub id: u32,
+    /// Scientific name in the newer release.
+    pub sci_name: String,
+    pub old_status: String,
+    pub new_status: String,
+}
+
+/// Changes between two releases grouped by kind.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Changelog {
+    pub old_version: String,
+    pub new_version: String,
+    /// Release date of the newer release.
+    pub release_date: String,
+    /// Added species that are not part of a split.
+    pub new_species: Vec<SpeciesRef>,
+    pub splits: Vec<Split>,
+    pub lumps: Vec<Lump>,
+    /// Renamed species whose genus changed.
+    pub transfers: Vec<Transfer>,
+    /// Renamed species within the same genus (spelling, epithet changes).
+    pub renamed: Vec<RenamedSpecies>,
+    /// Removed species that are not part of a lump.
+    pub removed: Vec<SpeciesRef>,
+    pub iucn_updates: Vec<IucnUpdate>,
+}
+
+impl Changelog {
+    /// Compare two release bundles.
+    pub fn between(old: &ReleasedMddData, new: &ReleasedMddData) -> Self {
+        Self::from_diff(&ReleaseDiff::between(old, new))
+    }
+
+    /// Group the changes of `diff`. Splits, lumps, and the release date
+    /// need a diff built by `ReleaseDiff::between`.
+    pub fn from_diff(diff: &ReleaseDiff) -> Self {
+        let mut splits = Vec::new();
+        let mut lumps = Vec::new();
+        let mut split_ids = HashSet::new();
+        let mut lumped_ids = HashSet::new();
+        for event in diff.events_at_least(MIN_CONFIDENCE) {
+            match event.kind {
+                TaxonomicEventKind::Split => {
+                    let source = event.old[0].clone();
+                    let species: Vec<SpeciesRef> = event
+                        .new
+                        .iter()
+                        .filter(|species| species.id != source.id)
+                        .cloned()
+                        .collect();
+                    split_ids.extend(species.iter().map(|species| species.id));
+                    splits.push(Split {
+                        source,
+                        species,
+                        confidence: event.confidence,
+                    });
+                }
+                TaxonomicEventKind::Lump => {
+                    let target = event.new[0].clone();
+                    let merged: Vec<SpeciesRef> = event
+                        .old
+                        .iter()
+                        .filter(|species| species.id != target.id)
+                        .cloned()
+                        .collect();
+                    lumped_ids.extend(merged.iter().map(|species| species.id));
+                    lumps.push(Lump {
+                        target,
+                        merged,
+                        confidence: event.confidence,
+                    });
+                }
+            }
+        }
+        let new_species = diff
+            .added
+            .iter()
+            .filter(|species| !split_ids.contains(&species.id))
+            .cloned()
+            .collect();
+        let removed = diff
+            .removed
+            .iter()
+            .filter(|species| !lumped_ids.contains(&species.id))
+            .cloned()
+            .collect();
+
+        let genus_changes: HashMap<u32, (&str, &str)> = diff
+            .changed
+            .iter()
+            .filter_map(|species| {
+                let change = species
+                    .changes
+                    .iter()
+                    .find(|change| change.field == GENUS_FIELD)?;
+                Some((
+                    species.id,
+                    (change.old_value.as_str(), change.new_value.as_str()),
+                ))
+            })
+            .collect();
+        let mut transfers = Vec::new();
+        let mut renamed = Vec::new();
+        for species in &diff.renamed {
+            match genus_changes.get(&species.id) {
+                Some((old_genus, new_genus)) => transfers.push(Transfer {
+                    id: species.id,
+                    old_name: species.old_name.clone(),
+                    new_name: species.new_name.clone(),
+                    old_genus: old_genus.to_string(),
+                    new_genus: new_genus.to_string(),
+                }),
+                None => renamed.push(species.clone()),
+            }
+        }
+
+        let iucn_updates = diff
+            .changed
+            .iter()
+            .flat_map(|species| {
+                species
+                    .changes
+                    .iter()
+                    .filter(|change| change.kind == ChangeKind::IucnStatus)
+                    .map(|change| IucnUpdate {
+                        id: species.id,
+                        sci_name: species.sci_name.clone(),
+                        old_status: change.old_value.clone(),
+                        new_status: change.new_value.clone(),
+                    })
+            })
+            .collect();
+
+        Self {
+            old_version: diff.old_version.clone(),
+            new_version: diff.new_version.clone(),
+            release_date: diff
+                .patch
+                .as_ref()
+                .map(|patch| patch.metadata.get_release_date().to_string())
+                .unwrap_or_default(),
+            new_species,
+            splits,
+            lumps,
+            transfers,
+            renamed,
+            removed,
+            iucn_updates,
+        }
+    }
+
+    /// Returns `true` when no change falls into any group.
+    pub fn is_empty(&self) -> bool {
+        self.new_species.is_empty()
+            && self.splits.is_empty()
+            && self.lumps.is_empty()
+            && self.transfers.is_empty()
+            && self.renamed.is_empty()
+            && self.removed.is_empty()
+            && self.iucn_updates.is_empty()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).expect("Failed to serialize")
+    }
+
+    /// Render the changelog as Markdown release notes.
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# MDD v{}", self.new_version);
+        if !self.release_date.is_empty() {
+            md.push_str(&format!(" ({})", self.release_date));
+        }
+        md.push_str(&format!("\n\nChanges since v{}:\n\n", self.old_version));
+        md.push_str(&format!("* New species: {}\n", self.new_species.len()));
+        md.push_str(&format!(
+            "* Splits: {} (into {} species)\n",
+            self.splits.len(),
+            self.splits.iter().map(|s| s.species.len()).sum::<usize>()
+        ));
+        md.push_str(&format!(
+            "* Lumps: {} ({} species merged)\n",
+            self.lumps.len(),
+            self.lumps.iter().map(|l| l.merged.len()).sum::<usize>()
+        ));
+        md.push_str(&format!(
+            "* Transfers between genera: {}\n",
+            self.transfers.len()
+        ));
+        md.push_str(&format!("* Other name changes: {}\n", self.renamed.len()));
+        md.push_str(&format!("* Removed species: {}\n", self.removed.len()));
+        md.push_str(&format!(
+            "* IUCN status updates: {}\n",
+            self.iucn_updates.len()
+        ));
+
+        if !self.new_species.is_empty() {
+            md.push_str("\n## New species\n\n");
+            for species in &self.new_species {
+                md.push_str(&format!("* {}\n", species_line(species)));
+            }
+        }
+        if !self.splits.is_empty() {
+            md.push_str("\n## Splits\n\n");
+            for split in &self.splits {
+                md.push_str(&format!(
+                    "* {} split into {}\n",
+                    species_line(&split.source),
+                    species_list(&split.species)
+                ));
+            }
+        }
+        if !self.lumps.is_empty() {
+            md.push_str("\n## Lumps\n\n");
+            for lump in &self.lumps {
+                md.push_str(&format!(
+                    "* {} merged into {}\n",
+                    species_list(&lump.merged),
+                    species_line(&lump.target)
+                ));
+            }
+        }
+        if !self.transfers.is_empty() {
+            md.push_str("\n## Transfers between genera\n\n");
+            for transfer in &self.transfers {
+                md.push_str(&format!(
+                    "* *{}* → *{}* ({}; *{}* → *{}*)\n",
+                    display_name(&transfer.old_name),
+                    display_name(&transfer.new_name),
+                    transfer.id,
+                    transfer.old_genus,
+                    transfer.new_genus
+                ));
+            }
+        }
+        if !self.renamed.is_empty() {
+            md.push_str("\n## Other name changes\n\n");
+            for species in &self.renamed {
+                md.push_str(&format!(
+                    "* *{}* → *{}* ({})\n",
+                    display_name(&species.old_name),
+                    display_name(&species.new_name),
+                    species.id
+                ));
+            }
+        }
+        if !self.removed.is_empty() {
+            md.push_str("\n## Removed species\n\n");
+            for species in &self.removed {
+                md.push_str(&format!("* {}\n", species_line(species)));
+            }
+        }
+        if !self.iucn_updates.is_empty() {
+            md.push_str("\n## IUCN status updates\n\n");
+            md.push_str("| Species | MDD id | Old | New |\n");
+            md.push_str("|---------|--------|-----|-----|\n");
+            for update in &self.iucn_updates {
+                md.push_str(&format!(
+                    "| *{}* | {} | {} | {} |\n",
+                    display_name(&update.sci_name),
+                    update.id,
+                    update.old_status,
+                    update.new_status
+                ));
+            }
+        }
+        md
+    }
+}
+
+fn species_line(species: &SpeciesRef) -> String {
+    format!("*{}* ({})", display_name(&species.sci_name), species.id)
+}
+
+fn species_list(species: &[SpeciesRef]) -> String {
+    species
+        .iter()
+        .map(species_line)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::tests::{binomial, synonym};
+    use crate::parser::mdd::MddData;
+
+    fn species(id: u32, genus: &str, epithet: &str, iucn: &str) -> MddData {
+        MddData {
+            iucn_status: iucn.to_string(),
+            ..binomial(id, genus, epithet)
+        }
+    }
+
+    #[test]
+    fn test_changelog() {
+        let old = ReleasedMddData::from_parser(
+            vec![
+                species(1, "Panthera", "leo", "VU"),
+                species(2, "Canis", "lupus", "LC"),
+                species(3, "Canis", "familiaris", "NE"),
+                species(4, "Felis", "manul", "LC"),
+                species(5, "Sorex", "minutus", "LC"),
+            ],
+            vec![
+                synonym(10, 1, "Panthera", "leo"),
+                synonym(11, 1, "Panthera", "persica"),
+                synonym(20, 2, "Canis", "lupus"),
+                synonym(30, 3, "Canis", "familiaris"),
+                synonym(40, 4, "Felis", "manul"),
+                synonym(50, 5, "Sorex", "minutus"),
+            ],
+            "2.1",
+            "2025-01-01",
+        );
+        let new = ReleasedMddData::from_parser(
+            vec![
+                species(1, "Panthera", "leo", "EN"),
+                species(2, "Canis", "lupus", "LC"),
+                species(4, "Otocolobus", "manul", "LC"),
+                species(6, "Panthera", "persica", "EN"),
+                species(7, "Sorex", "novus", "DD"),
+            ],
+            vec![
+                synonym(10, 1, "Panthera", "leo"),
+                synonym(11, 6, "Panthera", "persica"),
+                synonym(20, 2, "Canis", "lupus"),
+                synonym(30, 2, "Canis", "familiaris"),
+                synonym(40, 4, "Felis", "manul"),
+                synonym(41, 4, "Otocolobus", "manul"),
+                synonym(70, 7, "Sorex", "novus"),
+            ],
+            "2.2",
+            "2025-06-01",
+        );
+        let changelog = Changelog::between(&old, &new);
+        assert_eq!(changelog.new_version, "2.2");
+        assert_eq!(changelog.splits.len(), 1);
+        assert_eq!(changelog.splits[0].source.id, 1);
+        assert_eq!(changelog.splits[0].species[0].id, 6);
+        assert_eq!(changelog.splits[0].confidence, Confidence::High);
+        assert_eq!(changelog.lumps.len(), 1);
+        assert_eq!(changelog.lumps[0].target.id, 2);
+        assert_eq!(changelog.lumps[0].merged[0].id, 3);
+        assert_eq!(changelog.new_species[0].id, 7);
+        assert_eq!(changelog.removed[0].id, 5);
+        assert_eq!(changelog.transfers[0].new_genus, "Otocolobus");
+        assert!(changelog.renamed.is_empty());
+        assert_eq!(changelog.iucn_updates.len(), 1);
+
+        let md = changelog.to_markdown();
+        assert!(md.starts_with("# MDD v2.2 (2025-06-01)"));
+        assert!(md.contains("* *Panthera leo* (1) split into *Panthera persica* (6)"));
+        assert!(md.contains("* *Canis familiaris* (3) merged into *Canis lupus* (2)"));
+        assert!(md.contains("| *Panthera leo* | 1 | VU | EN |"));
+    }
+
+    #[test]
+    fn test_identical_releases() {
+        let release = ReleasedMddData::from_parser(
+            vec![species(1, "Panthera", "leo", "VU")],
+            vec![synonym(10, 1, "Panthera", "leo")],
+            "2.1",
+            "2025-01-01",
+        );
+        assert!(Changelog::between(&release, &release).is_empty());
+    }
+}
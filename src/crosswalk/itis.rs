@@ -0,0 +1,252 @@
+//! MDD → ITIS TSN concordance from a local ITIS dump.
+//!
+//! `ItisDump` reads two tables of the pipe-delimited ITIS download
+//! (`itisMySQL*.tar.gz`): `taxonomic_units`, with one name per TSN, and
+//! `synonym_links`, pointing invalid names at their accepted TSN. Only
+//! species-rank names under Mammalia are indexed; invalid names count as
+//! mammals when their accepted TSN is one.
+//!
+//! ```rust, ignore
+//! use mdd_api::crosswalk::{itis::{ItisDump, ITIS_CROSSWALK_FILE}, write_table};
+//!
+//! let dump = ItisDump::from_dir(Path::new("itisMySQL"))?;
+//! write_table(&dump.crosswalk(&species), Path::new("."), ITIS_CROSSWALK_FILE)?;
+//! ```
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Read},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{match_species, IdMatchType, Lineage};
+use crate::helper::normalize_name;
+use crate::parser::mdd::MddData;
+
+/// File stem of the MDD → ITIS table.
+pub const ITIS_CROSSWALK_FILE: &str = "itis_crosswalk";
+/// ITIS table of names.
+pub const TAXONOMIC_UNITS_FILE: &str = "taxonomic_units";
+/// ITIS table linking invalid names to accepted names.
+pub const SYNONYM_LINKS_FILE: &str = "synonym_links";
+
+/// TSN of Mammalia.
+const MAMMALIA_TSN: u64 = 179913;
+/// `kingdom_id` of Animalia.
+const ANIMALIA_KINGDOM: &str = "5";
+/// `rank_id` of species in Animalia.
+const SPECIES_RANK: &str = "220";
+// Columns of `taxonomic_units`.
+const TSN: usize = 0;
+const NAME_USAGE: usize = 10;
+const PARENT_TSN: usize = 17;
+const KINGDOM_ID: usize = 20;
+const RANK_ID: usize = 21;
+const COMPLETE_NAME: usize = 25;
+
+/// A species-rank ITIS name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItisName {
+    pub tsn: u64,
+    /// Accepted TSN; the name's own TSN when it is valid.
+    pub accepted_tsn: u64,
+    pub complete_name: String,
+    /// `name_usage` is `valid` or `accepted`.
+    pub accepted: bool,
+}
+
+/// One MDD species and its ITIS TSN.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItisMapping {
+    pub mdd_id: u32,
+    pub sci_name: String,
+    /// TSN of the matched name.
+    pub itis_tsn: Option<u64>,
+    /// Accepted TSN; differs from `itis_tsn` when ITIS treats the matched
+    /// name as a synonym.
+    pub itis_accepted_tsn: Option<u64>,
+    pub itis_name: Option<String>,
+    pub match_type: IdMatchType,
+}
+
+/// Mammal species names of an ITIS dump, by normalized name.
+#[derive(Debug, Clone, Default)]
+pub struct ItisDump {
+    names: HashMap<String, Vec<ItisName>>,
+}
+
+impl ItisDump {
+    /// Read `taxonomic_units` and `synonym_links` (pipe-delimited, no header).
+    pub fn from_readers<R: Read, S: Read>(
+        taxonomic_units: R,
+        synonym_links: S,
+    ) -> io::Result<Self> {
+        let mut parents: HashMap<u64, u64> = HashMap::new();
+        let mut species = Vec::new();
+        for row in pipe_reader(taxonomic_units).byte_records() {
+            let row = row?;
+            let field = |index: usize| String::from_utf8_lossy(row.get(index).unwrap_or_default());
+            if field(KINGDOM_ID) != ANIMALIA_KINGDOM {
+                continue;
+            }
+            let Ok(tsn) = field(TSN).trim().parse::<u64>() else {
+                continue;
+            };
+            if let Ok(parent) = field(PARENT_TSN).trim().parse::<u64>() {
+                parents.insert(tsn, parent);
+            }
+            if field(RANK_ID).trim() == SPECIES_RANK {
+                let usage = field(NAME_USAGE);
+                species.push(ItisName {
+                    tsn,
+                    accepted_tsn: tsn,
+                    complete_name: field(COMPLETE_NAME).trim().to_string(),
+                    accepted: matches!(usage.trim(), "valid" | "accepted"),
+                });
+            }
+        }
+
+        let mut accepted: HashMap<u64, u64> = HashMap::new();
+        for row in pipe_reader(synonym_links).byte_records() {
+            let row = row?;
+            let tsn = |index: usize| {
+                String::from_utf8_lossy(row.get(index).unwrap_or_default())
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+            };
+            if let (Some(synonym), Some(accepted_tsn)) = (tsn(0), tsn(1)) {
+                accepted.insert(synonym, accepted_tsn);
+            }
+        }
+
+        let mut lineage = Lineage::new(&parents, MAMMALIA_TSN);
+        let mut dump = Self::default();
+        for mut name in species {
+            if !name.accepted {
+                name.accepted_tsn = accepted.get(&name.tsn).copied().unwrap_or(name.tsn);
+            }
+            if !lineage.descends(name.accepted_tsn) {
+                continue;
+            }
+            dump.names
+                .entry(normalize_name(&name.complete_name))
+                .or_default()
+                .push(name);
+        }
+        Ok(dump)
+    }
+
+    pub fn from_paths(taxonomic_units: &Path, synonym_links: &Path) -> io::Result<Self> {
+        Self::from_readers(
+            fs::File::open(taxonomic_units)?,
+            fs::File::open(synonym_links)?,
+        )
+    }
+
+    /// Read `taxonomic_units` and `synonym_links` from an extracted dump.
+    pub fn from_dir(dir: &Path) -> io::Result<Self> {
+        Self::from_paths(
+            &dir.join(TAXONOMIC_UNITS_FILE),
+            &dir.join(SYNONYM_LINKS_FILE),
+        )
+    }
+
+    /// Number of indexed names.
+    pub fn len(&self) -> usize {
+        self.names.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// ITIS names spelled `name` (case-insensitive).
+    pub fn lookup(&self, name: &str) -> &[ItisName] {
+        self.names
+            .get(&normalize_name(name))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Match one species.
+    pub fn match_species(&self, species: &MddData) -> ItisMapping {
+        let matched = match_species(species, |name| self.lookup(name), |name| name.accepted);
+        ItisMapping {
+            mdd_id: species.id,
+            sci_name: species.sci_name.clone(),
+            itis_tsn: matched.map(|(name, _)| name.tsn),
+            itis_accepted_tsn: matched.map(|(name, _)| name.accepted_tsn),
+            itis_name: matched.map(|(name, _)| name.complete_name.clone()),
+            match_type: matched.map_or(IdMatchType::Unmatched, |(_, match_type)| match_type),
+        }
+    }
+
+    /// Match every species, in input order.
+    pub fn crosswalk(&self, species: &[MddData]) -> Vec<ItisMapping> {
+        species.iter().map(|s| self.match_species(s)).collect()
+    }
+}
+
+fn pipe_reader<R: Read>(reader: R) -> csv::Reader<R> {
+    csv::ReaderBuilder::new()
+        .delimiter(b'|')
+        .has_headers(false)
+        .quoting(false)
+        .flexible(true)
+        .from_reader(reader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crosswalk::tests::species;
+
+    /// `taxonomic_units` rows: tsn, name_usage, parent_tsn, rank_id, complete_name.
+    fn unit(tsn: u64, usage: &str, parent: u64, rank: &str, name: &str) -> String {
+        let mut fields = vec![String::new(); 26];
+        fields[TSN] = tsn.to_string();
+        fields[NAME_USAGE] = usage.to_string();
+        fields[PARENT_TSN] = parent.to_string();
+        fields[KINGDOM_ID] = ANIMALIA_KINGDOM.to_string();
+        fields[RANK_ID] = rank.to_string();
+        fields[COMPLETE_NAME] = name.to_string();
+        fields.join("|")
+    }
+
+    #[test]
+    fn test_itis_crosswalk() {
+        let units = [
+            unit(MAMMALIA_TSN, "valid", 1, "60", "Mammalia"),
+            unit(180000, "valid", MAMMALIA_TSN, "180", "Lepus"),
+            unit(180001, "valid", 180000, "220", "Lepus americanus"),
+            unit(180002, "invalid", 0, "220", "Lepus washingtonii"),
+            unit(180003, "valid", 180000, "220", "Lepus timidus"),
+            unit(99, "valid", 1, "180", "Apis"),
+            unit(100, "valid", 99, "220", "Apis mellifera"),
+        ]
+        .join("\n");
+        let links = "180002|180001|2020-01-01\n";
+        let dump = ItisDump::from_readers(units.as_bytes(), links.as_bytes()).unwrap();
+        assert_eq!(dump.len(), 3);
+        assert!(dump.lookup("Apis mellifera").is_empty());
+
+        let mappings = dump.crosswalk(&[
+            species(1, "Lepus_americanus", "NA"),
+            species(2, "Lepus_washingtonii", "NA"),
+            species(3, "Lepus_tibetanus", "Lepus timidus"),
+            species(4, "Lepus_novus", "NA"),
+        ]);
+        assert_eq!(mappings[0].match_type, IdMatchType::Exact);
+        assert_eq!(mappings[0].itis_tsn, Some(180001));
+        assert_eq!(mappings[1].match_type, IdMatchType::Synonym);
+        assert_eq!(mappings[1].itis_accepted_tsn, Some(180001));
+        assert_eq!(mappings[2].match_type, IdMatchType::OriginalCombination);
+        assert_eq!(mappings[3].match_type, IdMatchType::Unmatched);
+        assert_eq!(mappings[3].itis_name, None);
+    }
+}
@@ -0,0 +1,297 @@
+//! MSW3 and CMW concordance tables.
+//!
+//! Every MDD species record carries the name it had in Mammal Species of the
+//! World, 3rd edition (`MSW3_sciName`, with `MSW3_matchtype` and
+//! `diffSinceMSW3`) and in the Checklist of the Mammals of the World
+//! (`CMW_sciName`, with the `diffSinceCMW` flag). `Crosswalk` lifts these
+//! columns into two standalone tables, one row per MDD species:
+//!
+//! | file                  | columns                                                   |
+//! |-----------------------|-----------------------------------------------------------|
+//! | `msw3_crosswalk.csv`  | `mddId`, `sciName`, `msw3SciName`, `msw3MatchType`, `diffSinceMsw3` |
+//! | `cmw_crosswalk.csv`   | `mddId`, `sciName`, `cmwSciName`, `diffSinceCmw`          |
+//!
+//! Each table is also written as a JSON array with the same field names.
+//! Names keep the release spelling (`Lepus_alleni`); `NA` and blank values
+//! become empty CSV cells and JSON `null`. Match types are kept verbatim
+//! (`sciname match`, `oldname match`, `manual`, `unmatched`).
+//!
+//! Identifiers of other taxonomies are matched from local dumps supplied by
+//! the user: `itis` maps species to ITIS TSNs and `ncbi` to NCBI Taxonomy
+//! taxids. Both match the MDD name first, then the original combination,
+//! and record the `IdMatchType` of every row (`itis_crosswalk.csv`,
+//! `ncbi_crosswalk.csv`).
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    helper::{normalize_name, MDD_MISSING_VALUE},
+    parser::mdd::MddData,
+};
+
+pub mod itis;
+pub mod ncbi;
+
+/// File stem of the MDD → MSW3 table.
+pub const MSW3_CROSSWALK_FILE: &str = "msw3_crosswalk";
+/// File stem of the MDD → CMW table.
+pub const CMW_CROSSWALK_FILE: &str = "cmw_crosswalk";
+
+/// One MDD species and its MSW3 name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Msw3Mapping {
+    pub mdd_id: u32,
+    pub sci_name: String,
+    /// `None` for species without an MSW3 counterpart.
+    pub msw3_sci_name: Option<String>,
+    /// `MSW3_matchtype`, verbatim.
+    pub msw3_match_type: String,
+    /// `diffSinceMSW3` description of the change since MSW3.
+    pub diff_since_msw3: Option<String>,
+}
+
+/// One MDD species and its CMW name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CmwMapping {
+    pub mdd_id: u32,
+    pub sci_name: String,
+    /// `None` for species without a CMW counterpart.
+    pub cmw_sci_name: Option<String>,
+    /// Whether the species changed since CMW (`diffSinceCMW` is `1`).
+    pub diff_since_cmw: bool,
+}
+
+/// How an MDD species was matched to an external taxonomy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IdMatchType {
+    /// The MDD name is an accepted name of the external taxonomy.
+    Exact,
+    /// The external taxonomy lists the MDD name as a synonym.
+    Synonym,
+    /// Only the original name combination of the species matched.
+    OriginalCombination,
+    Unmatched,
+}
+
+/// MSW3 and CMW mapping tables of one release.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Crosswalk {
+    pub msw3: Vec<Msw3Mapping>,
+    pub cmw: Vec<CmwMapping>,
+}
+
+impl Crosswalk {
+    pub fn new() -> Self {
+        Self {
+            msw3: Vec::new(),
+            cmw: Vec::new(),
+        }
+    }
+
+    /// Build both tables from species records, in input order.
+    pub fn from_species(species: &[MddData]) -> Self {
+        let mut crosswalk = Self::new();
+        for record in species {
+            crosswalk.msw3.push(Msw3Mapping {
+                mdd_id: record.id,
+                sci_name: record.sci_name.clone(),
+                msw3_sci_name: non_missing(&record.msw3_sci_name),
+                msw3_match_type: record.msw3_match_type.trim().to_string(),
+                diff_since_msw3: non_missing(&record.diff_since_msw3),
+            });
+            crosswalk.cmw.push(CmwMapping {
+                mdd_id: record.id,
+                sci_name: record.sci_name.clone(),
+                cmw_sci_name: non_missing(&record.cmw_sci_name),
+                diff_since_cmw: record.diff_since_cmw == 1,
+            });
+        }
+        crosswalk
+    }
+
+    /// Number of species per MSW3 match type.
+    pub fn msw3_match_counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for mapping in &self.msw3 {
+            *counts.entry(mapping.msw3_match_type.as_str()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Number of species changed since CMW.
+    pub fn cmw_changed_count(&self) -> usize {
+        self.cmw.iter().filter(|m| m.diff_since_cmw).count()
+    }
+
+    /// Write both tables as CSV and JSON into `output_dir`, returning the
+    /// written paths.
+    pub fn write_files(&self, output_dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        fs::create_dir_all(output_dir)?;
+        let mut paths = write_table(&self.msw3, output_dir, MSW3_CROSSWALK_FILE)?;
+        paths.extend(write_table(&self.cmw, output_dir, CMW_CROSSWALK_FILE)?);
+        Ok(paths)
+    }
+}
+
+/// Write `rows` as `<stem>.csv` and `<stem>.json` into `output_dir`,
+/// returning the written paths.
+pub fn write_table<T: Serialize>(
+    rows: &[T],
+    output_dir: &Path,
+    stem: &str,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let csv_path = write_csv_table(rows, output_dir, stem)?;
+    let json_path = output_dir.join(stem).with_extension("json");
+    fs::write(&json_path, serde_json::to_string(rows)?)?;
+    Ok(vec![csv_path, json_path])
+}
+
+/// Write `rows` as `<stem>.csv` into `output_dir`, returning the written path.
+pub fn write_csv_table<T: Serialize>(
+    rows: impl IntoIterator<Item = T>,
+    output_dir: &Path,
+    stem: &str,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let path = output_dir.join(stem).with_extension("csv");
+    let mut wtr = csv::Writer::from_path(&path)?;
+    for row in rows {
+        wtr.serialize(row)?;
+    }
+    wtr.flush()?;
+    Ok(path)
+}
+
+/// Best record for `species` among the records of a name index: an
+/// accepted record of the MDD name, any record of it, then a record of the
+/// original combination (accepted first).
+fn match_species<'a, T>(
+    species: &MddData,
+    lookup: impl Fn(&str) -> &'a [T],
+    is_accepted: impl Fn(&T) -> bool,
+) -> Option<(&'a T, IdMatchType)> {
+    let best = |records: &'a [T]| {
+        records
+            .iter()
+            .find(|record| is_accepted(record))
+            .or_else(|| records.first())
+    };
+    let records = lookup(&normalize_name(&species.sci_name));
+    if let Some(record) = best(records) {
+        let match_type = if is_accepted(record) {
+            IdMatchType::Exact
+        } else {
+            IdMatchType::Synonym
+        };
+        return Some((record, match_type));
+    }
+    let combination = species.original_combination()?;
+    if combination.infraspecific_epithet.is_some() {
+        return None;
+    }
+    let name = format!("{} {}", combination.genus, combination.specific_epithet);
+    best(lookup(&normalize_name(&name))).map(|record| (record, IdMatchType::OriginalCombination))
+}
+
+/// Whether ids of a parent table descend from `root`, memoized.
+pub(crate) struct Lineage<'a> {
+    parents: &'a HashMap<u64, u64>,
+    root: u64,
+    known: HashMap<u64, bool>,
+}
+
+impl<'a> Lineage<'a> {
+    pub(crate) fn new(parents: &'a HashMap<u64, u64>, root: u64) -> Self {
+        Self {
+            parents,
+            root,
+            known: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn descends(&mut self, id: u64) -> bool {
+        let mut path = Vec::new();
+        let mut current = id;
+        let result = loop {
+            if current == self.root {
+                break true;
+            }
+            if let Some(&known) = self.known.get(&current) {
+                break known;
+            }
+            // The path length guards against cycles in malformed dumps.
+            match self.parents.get(&current) {
+                Some(&parent) if parent != current && path.len() < self.parents.len() => {
+                    path.push(current);
+                    current = parent;
+                }
+                _ => break false,
+            }
+        };
+        for id in path {
+            self.known.insert(id, result);
+        }
+        result
+    }
+}
+
+fn non_missing(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || value == MDD_MISSING_VALUE {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    /// Species `id` named `sci_name`, described as `original`; shared with
+    /// the tests of the backbone crosswalks.
+    pub(super) fn species(id: u32, sci_name: &str, original: &str) -> MddData {
+        MddData {
+            id,
+            sci_name: sci_name.to_string(),
+            original_name_combination: original.to_string(),
+            ..MddData::new()
+        }
+    }
+
+    #[test]
+    fn test_crosswalk() {
+        let csv_data = fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let crosswalk = Crosswalk::from_species(&species);
+        assert_eq!(crosswalk.msw3.len(), 112);
+        assert_eq!(crosswalk.msw3_match_counts()["unmatched"], 29);
+        assert_eq!(crosswalk.cmw_changed_count(), 19);
+
+        let altamirae = &crosswalk.msw3[3];
+        assert_eq!(altamirae.sci_name, "Lepus_altamirae");
+        assert_eq!(altamirae.msw3_sci_name, None);
+
+        let output_dir = TempDir::new("crosswalk").unwrap();
+        let paths = crosswalk.write_files(output_dir.path()).unwrap();
+        assert_eq!(paths.len(), 4);
+        let mut reader = csv::Reader::from_path(&paths[0]).unwrap();
+        let rows: Vec<Msw3Mapping> = reader.deserialize().map(|row| row.unwrap()).collect();
+        assert_eq!(rows, crosswalk.msw3);
+        let cmw: Vec<CmwMapping> =
+            serde_json::from_str(&fs::read_to_string(&paths[3]).unwrap()).unwrap();
+        assert_eq!(cmw, crosswalk.cmw);
+    }
+}
@@ -0,0 +1,216 @@
+//! MDD → NCBI Taxonomy taxid concordance from a local taxdump.
+//!
+//! `NcbiTaxonomy` reads `nodes.dmp` and `names.dmp` of an extracted
+//! `taxdump.tar.gz`. Only species under Mammalia are indexed, by their
+//! scientific name and their synonyms (`synonym` and `equivalent name`
+//! classes); every name resolves to the taxid it belongs to.
+//!
+//! ```rust, ignore
+//! use mdd_api::crosswalk::{ncbi::{NcbiTaxonomy, NCBI_CROSSWALK_FILE}, write_table};
+//!
+//! let taxonomy = NcbiTaxonomy::from_dir(Path::new("taxdump"))?;
+//! write_table(&taxonomy.crosswalk(&species), Path::new("."), NCBI_CROSSWALK_FILE)?;
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{match_species, IdMatchType, Lineage};
+use crate::helper::normalize_name;
+use crate::parser::mdd::MddData;
+
+/// File stem of the MDD → NCBI table.
+pub const NCBI_CROSSWALK_FILE: &str = "ncbi_crosswalk";
+/// Taxdump table of taxa and their parents.
+pub const NODES_FILE: &str = "nodes.dmp";
+/// Taxdump table of names.
+pub const NAMES_FILE: &str = "names.dmp";
+
+/// Taxid of Mammalia.
+const MAMMALIA_TAXID: u64 = 40674;
+const SPECIES_RANK: &str = "species";
+const SCIENTIFIC_NAME: &str = "scientific name";
+/// Name classes indexed as synonyms.
+const SYNONYM_CLASSES: [&str; 2] = ["synonym", "equivalent name"];
+
+/// A name of an NCBI mammal species.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NcbiName {
+    pub taxid: u64,
+    pub name: String,
+    /// The scientific name of the taxid, not a synonym.
+    pub scientific: bool,
+}
+
+/// One MDD species and its NCBI taxid.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NcbiMapping {
+    pub mdd_id: u32,
+    pub sci_name: String,
+    pub ncbi_taxid: Option<u64>,
+    /// Scientific name of the taxid.
+    pub ncbi_name: Option<String>,
+    pub match_type: IdMatchType,
+}
+
+/// Mammal species names of an NCBI taxdump, by normalized name.
+#[derive(Debug, Clone, Default)]
+pub struct NcbiTaxonomy {
+    names: HashMap<String, Vec<NcbiName>>,
+    scientific_names: HashMap<u64, String>,
+}
+
+impl NcbiTaxonomy {
+    /// Read `nodes.dmp` and `names.dmp` (`\t|\t` separated rows ending in `\t|`).
+    pub fn from_readers<N: Read, M: Read>(nodes: N, names: M) -> io::Result<Self> {
+        let mut parents: HashMap<u64, u64> = HashMap::new();
+        let mut species = Vec::new();
+        for line in BufReader::new(nodes).lines() {
+            let line = line?;
+            let fields = dmp_fields(&line);
+            let (Some(taxid), Some(parent)) = (parse_taxid(&fields, 0), parse_taxid(&fields, 1))
+            else {
+                continue;
+            };
+            parents.insert(taxid, parent);
+            if fields.get(2) == Some(&SPECIES_RANK) {
+                species.push(taxid);
+            }
+        }
+        let mut lineage = Lineage::new(&parents, MAMMALIA_TAXID);
+        let mammals: HashSet<u64> = species
+            .into_iter()
+            .filter(|taxid| lineage.descends(*taxid))
+            .collect();
+
+        let mut taxonomy = Self::default();
+        for line in BufReader::new(names).lines() {
+            let line = line?;
+            let fields = dmp_fields(&line);
+            let Some(taxid) = parse_taxid(&fields, 0).filter(|taxid| mammals.contains(taxid))
+            else {
+                continue;
+            };
+            let (Some(name), Some(class)) = (fields.get(1), fields.get(3)) else {
+                continue;
+            };
+            let scientific = *class == SCIENTIFIC_NAME;
+            if !scientific && !SYNONYM_CLASSES.contains(class) {
+                continue;
+            }
+            if scientific {
+                taxonomy.scientific_names.insert(taxid, name.to_string());
+            }
+            taxonomy
+                .names
+                .entry(normalize_name(name))
+                .or_default()
+                .push(NcbiName {
+                    taxid,
+                    name: name.to_string(),
+                    scientific,
+                });
+        }
+        Ok(taxonomy)
+    }
+
+    pub fn from_paths(nodes: &Path, names: &Path) -> io::Result<Self> {
+        Self::from_readers(fs::File::open(nodes)?, fs::File::open(names)?)
+    }
+
+    /// Read `nodes.dmp` and `names.dmp` from an extracted taxdump.
+    pub fn from_dir(dir: &Path) -> io::Result<Self> {
+        Self::from_paths(&dir.join(NODES_FILE), &dir.join(NAMES_FILE))
+    }
+
+    /// Number of indexed names.
+    pub fn len(&self) -> usize {
+        self.names.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// NCBI names spelled `name` (case-insensitive).
+    pub fn lookup(&self, name: &str) -> &[NcbiName] {
+        self.names
+            .get(&normalize_name(name))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Match one species.
+    pub fn match_species(&self, species: &MddData) -> NcbiMapping {
+        let matched = match_species(species, |name| self.lookup(name), |name| name.scientific);
+        NcbiMapping {
+            mdd_id: species.id,
+            sci_name: species.sci_name.clone(),
+            ncbi_taxid: matched.map(|(name, _)| name.taxid),
+            ncbi_name: matched
+                .and_then(|(name, _)| self.scientific_names.get(&name.taxid))
+                .cloned(),
+            match_type: matched.map_or(IdMatchType::Unmatched, |(_, match_type)| match_type),
+        }
+    }
+
+    /// Match every species, in input order.
+    pub fn crosswalk(&self, species: &[MddData]) -> Vec<NcbiMapping> {
+        species.iter().map(|s| self.match_species(s)).collect()
+    }
+}
+
+/// Fields of a `.dmp` row.
+fn dmp_fields(line: &str) -> Vec<&str> {
+    line.trim_end_matches("\t|")
+        .split("\t|\t")
+        .map(str::trim)
+        .collect()
+}
+
+fn parse_taxid(fields: &[&str], index: usize) -> Option<u64> {
+    fields.get(index)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crosswalk::tests::species;
+
+    #[test]
+    fn test_ncbi_crosswalk() {
+        let nodes = "1\t|\t1\t|\tno rank\t|\n\
+                     40674\t|\t1\t|\tclass\t|\n\
+                     9979\t|\t40674\t|\tgenus\t|\n\
+                     9983\t|\t9979\t|\tspecies\t|\n\
+                     7459\t|\t1\t|\tgenus\t|\n\
+                     7460\t|\t7459\t|\tspecies\t|\n";
+        let names = "9983\t|\tLepus americanus\t|\t\t|\tscientific name\t|\n\
+                     9983\t|\tsnowshoe hare\t|\t\t|\tgenbank common name\t|\n\
+                     9983\t|\tLepus washingtonii\t|\t\t|\tsynonym\t|\n\
+                     7460\t|\tApis mellifera\t|\t\t|\tscientific name\t|\n";
+        let taxonomy = NcbiTaxonomy::from_readers(nodes.as_bytes(), names.as_bytes()).unwrap();
+        assert_eq!(taxonomy.len(), 2);
+        assert!(taxonomy.lookup("Apis mellifera").is_empty());
+
+        let mappings = taxonomy.crosswalk(&[
+            species(1, "Lepus_americanus", "NA"),
+            species(2, "Lepus_washingtonii", "NA"),
+            species(3, "Sylvilagus_americanus", "Lepus americanus"),
+            species(4, "Lepus_novus", "NA"),
+        ]);
+        assert_eq!(mappings[0].match_type, IdMatchType::Exact);
+        assert_eq!(mappings[0].ncbi_taxid, Some(9983));
+        assert_eq!(mappings[1].match_type, IdMatchType::Synonym);
+        assert_eq!(mappings[1].ncbi_name.as_deref(), Some("Lepus americanus"));
+        assert_eq!(mappings[2].match_type, IdMatchType::OriginalCombination);
+        assert_eq!(mappings[3].match_type, IdMatchType::Unmatched);
+    }
+}
@@ -0,0 +1,556 @@
+//! SQLite export of parsed MDD data (enabled with the `db` feature).
+//!
+//! The schema is normalized into a handful of tables:
+//! * `release_metadata` – key/value pairs from `MetaData` (version, counts, ...)
+//!   plus the `ProvenanceHeader` of the export (`generated_at`, `generator`,
+//!   `crate_version`).
+//! * `species` – one row per `MddData` record, keyed by MDD id.
+//! * `synonyms` – one row per `SynonymData` record; `species_id` references
+//!   `species(id)` and is NULL for synonym-only names.
+//! * `countries` / `country_species` – `CountryMDDStats` totals plus the
+//!   species-to-country join table (`predicted` marks `?` distributions).
+//! * `species_fts` – FTS5 full-text index with one row per species over the
+//!   scientific name, main and other common names, synonym names (original
+//!   combinations and root names), and taxonomy notes. Diacritics are folded,
+//!   so `SELECT species_id FROM species_fts WHERE species_fts MATCH 'riverine'`
+//!   works offline without loading the species table into memory.
+//!
+//! Species and synonym columns are derived from the serde field names
+//! (camelCase converted to snake_case), so the tables follow the JSON output
+//! without maintaining a second column list.
+//!
+//! `read_release` / `open_release` load the species, synonym, and metadata
+//! tables back into a `ReleasedMddData` (see also `MddStore::from_sqlite`).
+//! The tables do not record the release order, so species and synonyms come
+//! back sorted by id; derived tables (countries, search) are not read.
+
+use std::{collections::HashMap, path::Path};
+
+use rusqlite::{
+    params,
+    types::{Value as SqlValue, ValueRef},
+    Connection, OpenFlags, Transaction,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    parser::{
+        country::CountryMDDStats, error::MddError, mdd::MddData, synonyms::SynonymData,
+        ReleasedMddData, SimpleMDD,
+    },
+    writer::provenance::ProvenanceHeader,
+};
+
+pub(crate) const SPECIES_TABLE: &str = "species";
+pub(crate) const SYNONYM_TABLE: &str = "synonyms";
+const SEARCH_TABLE: &str = "species_fts";
+
+/// Writer for an MDD SQLite database.
+pub struct SqliteExporter {
+    conn: Connection,
+}
+
+impl SqliteExporter {
+    /// Create (or replace) the database at `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, MddError> {
+        let path = path.as_ref();
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Create an in-memory database, mostly useful for tests.
+    pub fn in_memory() -> Result<Self, MddError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self, MddError> {
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        Ok(Self { conn })
+    }
+
+    /// Access the underlying connection.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Write the release bundle plus country statistics in a single transaction.
+    pub fn write_release(
+        &mut self,
+        release: &ReleasedMddData,
+        country_stats: &CountryMDDStats,
+    ) -> Result<(), MddError> {
+        let tx = self.conn.transaction()?;
+        create_schema(&tx)?;
+        write_metadata(&tx, release)?;
+        for species in release.species() {
+            insert_record(&tx, SPECIES_TABLE, species)?;
+        }
+        for synonym in release
+            .data
+            .iter()
+            .flat_map(|entry| entry.synonyms())
+            .chain(release.synonym_only.iter())
+        {
+            insert_record(&tx, SYNONYM_TABLE, synonym)?;
+        }
+        write_country_stats(&tx, country_stats)?;
+        write_search_index(&tx, release)?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+/// Open the database at `path` read-only and load its release.
+pub fn open_release<P: AsRef<Path>>(path: P) -> Result<ReleasedMddData, MddError> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    read_release(&conn)
+}
+
+/// Load the release written by `SqliteExporter::write_release`: species,
+/// synonyms (attached by `species_id`), and metadata.
+pub fn read_release(conn: &Connection) -> Result<ReleasedMddData, MddError> {
+    let species: Vec<MddData> = read_records(conn, SPECIES_TABLE, "id", &MddData::new())?;
+    let synonyms: Vec<SynonymData> =
+        read_records(conn, SYNONYM_TABLE, "syn_id", &SynonymData::new())?;
+    let mut release = ReleasedMddData::new();
+    release.metadata = read_metadata(conn, &release.metadata)?;
+    let mut attached: HashMap<u32, Vec<SynonymData>> = HashMap::new();
+    for synonym in synonyms {
+        match synonym.species_id {
+            Some(id) => attached.entry(id).or_default().push(synonym),
+            None => release.synonym_only.push(synonym),
+        }
+    }
+    release.data = species
+        .into_iter()
+        .map(|species| {
+            let synonyms = attached.remove(&species.id).unwrap_or_default();
+            SimpleMDD::new(species, synonyms)
+        })
+        .collect();
+    Ok(release)
+}
+
+/// Read every row of `table` into records, mapping the columns back to the
+/// serde field names of `sample`.
+fn read_records<T: Serialize + DeserializeOwned>(
+    conn: &Connection,
+    table: &str,
+    key: &str,
+    sample: &T,
+) -> Result<Vec<T>, MddError> {
+    let fields: HashMap<String, String> = to_field_map(sample)?
+        .into_iter()
+        .map(|(field, _)| (sql_column_name(&field), field))
+        .collect();
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {} ORDER BY {}", table, key))?;
+    let columns: Vec<Option<String>> = stmt
+        .column_names()
+        .into_iter()
+        .map(|column| fields.get(column).cloned())
+        .collect();
+    let mut rows = stmt.query([])?;
+    let mut records = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mut map = Map::new();
+        for (index, field) in columns.iter().enumerate() {
+            if let Some(field) = field {
+                map.insert(field.clone(), from_sql_value(row.get_ref(index)?));
+            }
+        }
+        records.push(serde_json::from_value(Value::Object(map))?);
+    }
+    Ok(records)
+}
+
+/// Read `release_metadata`, typing each value like the field of `sample`.
+/// Provenance keys have no `MetaData` field and are skipped.
+fn read_metadata<T: Serialize + DeserializeOwned>(
+    conn: &Connection,
+    sample: &T,
+) -> Result<T, MddError> {
+    let fields: HashMap<String, (String, Value)> = to_field_map(sample)?
+        .into_iter()
+        .map(|(field, value)| (sql_column_name(&field), (field, value)))
+        .collect();
+    let mut stmt = conn.prepare("SELECT key, value FROM release_metadata")?;
+    let mut rows = stmt.query([])?;
+    let mut map = Map::new();
+    while let Some(row) = rows.next()? {
+        let key: String = row.get(0)?;
+        let value: String = row.get(1)?;
+        let (field, value) = match fields.get(&key) {
+            Some((field, Value::String(_))) => (field.clone(), Value::String(value)),
+            Some((field, _)) => (field.clone(), serde_json::from_str(&value)?),
+            // Optional fields are absent from the sample; they are all text.
+            None => (snake_to_camel(&key), Value::String(value)),
+        };
+        map.insert(field, value);
+    }
+    Ok(serde_json::from_value(Value::Object(map))?)
+}
+
+fn from_sql_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => Value::from(f),
+        ValueRef::Text(text) | ValueRef::Blob(text) => {
+            Value::String(String::from_utf8_lossy(text).into_owned())
+        }
+    }
+}
+
+fn snake_to_camel(column: &str) -> String {
+    let mut field = String::with_capacity(column.len());
+    let mut upper = false;
+    for c in column.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            field.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            field.push(c);
+        }
+    }
+    field
+}
+
+/// Convert a serde (camelCase) field name into a snake_case SQL column name.
+///
+/// Runs of capitals stay together, so `typeVoucherURIs` becomes
+/// `type_voucher_uris` and `CMW_sciName` becomes `cmw_sci_name`.
+pub fn sql_column_name(field: &str) -> String {
+    let mut column = String::with_capacity(field.len() + 4);
+    let mut prev_lower = false;
+    for c in field.chars() {
+        if c.is_uppercase() && prev_lower {
+            column.push('_');
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        column.extend(c.to_lowercase());
+    }
+    column
+}
+
+/// Serialize a record into its serde field map.
+pub(crate) fn to_field_map<T: Serialize>(record: &T) -> Result<Map<String, Value>, MddError> {
+    match serde_json::to_value(record)? {
+        Value::Object(map) => Ok(map),
+        _ => unreachable!("records serialize to JSON objects"),
+    }
+}
+
+fn column_definitions<T: Serialize>(sample: &T) -> Result<Vec<(String, &'static str)>, MddError> {
+    let columns = to_field_map(sample)?
+        .iter()
+        .map(|(field, value)| {
+            // Optional fields (only numeric ids today) serialize as null.
+            let sql_type = match value {
+                Value::String(_) => "TEXT",
+                _ => "INTEGER",
+            };
+            (sql_column_name(field), sql_type)
+        })
+        .collect();
+    Ok(columns)
+}
+
+fn create_schema(tx: &Transaction) -> Result<(), MddError> {
+    tx.execute_batch(&format!(
+        "{}
+CREATE VIRTUAL TABLE {SEARCH_TABLE} USING fts5(
+    species_id UNINDEXED,
+    sci_name,
+    common_names,
+    synonyms,
+    taxonomy_notes,
+    tokenize = 'unicode61 remove_diacritics 2'
+);",
+        table_schema()?
+    ))?;
+    Ok(())
+}
+
+/// `CREATE` statements of the metadata, species, synonym, and country tables
+/// and their indexes (everything but the search index).
+pub(crate) fn table_schema() -> Result<String, MddError> {
+    let species_cols = column_definitions(&MddData::new())?
+        .into_iter()
+        .map(|(name, ty)| {
+            if name == "id" {
+                "id INTEGER PRIMARY KEY".to_string()
+            } else {
+                format!("{} {}", name, ty)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",\n    ");
+    let synonym_cols = column_definitions(&SynonymData::new())?
+        .into_iter()
+        .map(|(name, ty)| match name.as_str() {
+            "syn_id" => "syn_id INTEGER PRIMARY KEY".to_string(),
+            "species_id" => "species_id INTEGER REFERENCES species(id)".to_string(),
+            _ => format!("{} {}", name, ty),
+        })
+        .collect::<Vec<_>>()
+        .join(",\n    ");
+    Ok(format!(
+        "CREATE TABLE release_metadata (
+    key TEXT PRIMARY KEY,
+    value TEXT
+);
+CREATE TABLE {SPECIES_TABLE} (
+    {species_cols}
+);
+CREATE TABLE {SYNONYM_TABLE} (
+    {synonym_cols}
+);
+CREATE TABLE countries (
+    code TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    total_orders INTEGER,
+    total_families INTEGER,
+    total_genera INTEGER,
+    total_living_species INTEGER,
+    total_extinct_species INTEGER
+);
+CREATE TABLE country_species (
+    country_code TEXT NOT NULL REFERENCES countries(code),
+    species_id INTEGER NOT NULL REFERENCES species(id),
+    predicted INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX idx_species_sci_name ON {SPECIES_TABLE}(sci_name);
+CREATE INDEX idx_species_family ON {SPECIES_TABLE}(family);
+CREATE INDEX idx_synonyms_species_id ON {SYNONYM_TABLE}(species_id);
+CREATE INDEX idx_country_species_species_id ON country_species(species_id);"
+    ))
+}
+
+fn write_metadata(tx: &Transaction, release: &ReleasedMddData) -> Result<(), MddError> {
+    let mut stmt = tx.prepare("INSERT INTO release_metadata (key, value) VALUES (?1, ?2)")?;
+    for (key, value) in metadata_rows(release)? {
+        stmt.execute(params![key, value])?;
+    }
+    Ok(())
+}
+
+/// `release_metadata` rows: the `MetaData` fields, then the keys of the
+/// `ProvenanceHeader` that `MetaData` does not already have.
+pub(crate) fn metadata_rows(release: &ReleasedMddData) -> Result<Vec<(String, String)>, MddError> {
+    let mut rows = Vec::new();
+    for (key, value) in to_field_map(&release.metadata)? {
+        let value = match value {
+            Value::String(s) => s,
+            Value::Null => continue,
+            other => other.to_string(),
+        };
+        rows.push((sql_column_name(&key), value));
+    }
+    for (key, value) in to_field_map(&ProvenanceHeader::from_release(release))? {
+        let key = sql_column_name(&key);
+        if let Value::String(value) = value {
+            if !rows.iter().any(|(existing, _)| *existing == key) {
+                rows.push((key, value));
+            }
+        }
+    }
+    Ok(rows)
+}
+
+fn insert_record<T: Serialize>(tx: &Transaction, table: &str, record: &T) -> Result<(), MddError> {
+    let fields = to_field_map(record)?;
+    let columns: Vec<String> = fields.keys().map(|k| sql_column_name(k)).collect();
+    let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+    let sql = format!(
+        "INSERT OR REPLACE INTO {} ({}) VALUES ({})",
+        table,
+        columns.join(", "),
+        placeholders.join(", ")
+    );
+    let values: Vec<SqlValue> = fields.into_values().map(to_sql_value).collect();
+    tx.prepare_cached(&sql)?
+        .execute(rusqlite::params_from_iter(values))?;
+    Ok(())
+}
+
+fn to_sql_value(value: Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(b as i64),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+fn write_country_stats(tx: &Transaction, stats: &CountryMDDStats) -> Result<(), MddError> {
+    let mut country_stmt = tx.prepare(
+        "INSERT INTO countries (code, name, total_orders, total_families, total_genera, \
+         total_living_species, total_extinct_species) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?;
+    let mut species_stmt = tx.prepare(
+        "INSERT INTO country_species (country_code, species_id, predicted) VALUES (?1, ?2, ?3)",
+    )?;
+    for (code, data) in &stats.country_data {
+        country_stmt.execute(params![
+            code,
+            data.name,
+            data.total_orders,
+            data.total_families,
+            data.total_genera,
+            data.total_living_species,
+            data.total_extinct_species,
+        ])?;
+        for (id, predicted) in data.species_list.iter().filter_map(|s| country_species(s)) {
+            species_stmt.execute(params![code, id, predicted])?;
+        }
+    }
+    Ok(())
+}
+
+/// Species id and `predicted` flag of a `species_list` entry (`id` or `id?`).
+pub(crate) fn country_species(entry: &str) -> Option<(u32, bool)> {
+    let id = entry.trim_end_matches('?').parse().ok()?;
+    Some((id, entry.ends_with('?')))
+}
+
+fn write_search_index(tx: &Transaction, release: &ReleasedMddData) -> Result<(), MddError> {
+    let mut stmt = tx.prepare(&format!(
+        "INSERT INTO {} (species_id, sci_name, common_names, synonyms, taxonomy_notes) \
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        SEARCH_TABLE
+    ))?;
+    for entry in &release.data {
+        let species = entry.species();
+        let common_names = std::iter::once(species.main_common_name.as_str())
+            .chain(species.common_names())
+            .filter(|name| !name.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join(" | ");
+        let synonyms = entry
+            .synonyms()
+            .iter()
+            .flat_map(|synonym| [synonym.original_combination(), synonym.root_name()])
+            .filter(|name| !name.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join(" | ");
+        stmt.execute(params![
+            species.id,
+            species.sci_name.replace('_', " "),
+            common_names,
+            synonyms,
+            species.taxonomy_notes,
+        ])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sql_column_name() {
+        assert_eq!(sql_column_name("sciName"), "sci_name");
+        assert_eq!(sql_column_name("typeVoucherURIs"), "type_voucher_uris");
+        assert_eq!(sql_column_name("CMW_sciName"), "cmw_sci_name");
+        assert_eq!(sql_column_name("MSW3_matchtype"), "msw3_matchtype");
+        assert_eq!(sql_column_name("diffSinceMSW3"), "diff_since_msw3");
+        assert_eq!(sql_column_name("typeSubregion2"), "type_subregion2");
+    }
+
+    #[test]
+    fn test_write_release() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let mut stats = CountryMDDStats::new();
+        stats.parse_country_data(&species);
+        let syn_csv = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let mut synonyms = SynonymData::new().from_csv(&syn_csv);
+        synonyms[0].species_id = Some(1001076);
+        synonyms[1].species_id = None;
+        let release = ReleasedMddData::from_parser(species, synonyms, "2.0", "2025-01-01");
+
+        let mut exporter = SqliteExporter::in_memory().unwrap();
+        exporter.write_release(&release, &stats).unwrap();
+        let conn = exporter.connection();
+        let count: u32 = conn
+            .query_row("SELECT COUNT(*) FROM species", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 112);
+        let version: String = conn
+            .query_row(
+                "SELECT value FROM release_metadata WHERE key = 'version'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(version, "2.0");
+        let crate_version: String = conn
+            .query_row(
+                "SELECT value FROM release_metadata WHERE key = 'crate_version'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(crate_version, env!("CARGO_PKG_VERSION"));
+        let name: String = conn
+            .query_row(
+                "SELECT sci_name FROM species WHERE id = 1001076",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(name, "Bunolagus_monticularis");
+        let linked: u32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM synonyms WHERE species_id = 1001076",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(linked, 1);
+        let unlinked: u32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM synonyms WHERE species_id IS NULL",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(unlinked, 1);
+
+        let search = |query: &str| -> Vec<u32> {
+            let mut stmt = conn
+                .prepare("SELECT species_id FROM species_fts WHERE species_fts MATCH ?1")
+                .unwrap();
+            stmt.query_map([query], |row| row.get(0))
+                .unwrap()
+                .map(Result::unwrap)
+                .collect()
+        };
+        assert_eq!(search("riverine"), vec![1001076]);
+        assert_eq!(search("sci_name:\"bunolagus monticularis\""), vec![1001076]);
+        let synonym = release.data[0].synonyms()[0].original_combination();
+        let word = synonym.split_whitespace().last().unwrap();
+        assert!(search(&format!("synonyms:{}", word)).contains(&1001076));
+
+        let loaded = read_release(conn).unwrap();
+        let to_value = |release: &ReleasedMddData| {
+            let mut value = serde_json::to_value(release).unwrap();
+            value["data"]
+                .as_array_mut()
+                .unwrap()
+                .sort_by_key(|entry| entry["mddId"].as_u64());
+            value
+        };
+        assert_eq!(to_value(&loaded), to_value(&release));
+    }
+}
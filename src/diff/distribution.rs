@@ -0,0 +1,225 @@
+//! Country distribution changes between two releases.
+//!
+//! `DistributionDiff` reads `countryDistribution` of both releases and lists
+//! the changes two ways for conservation users:
+//! * per species (matched by MDD id and present in both releases), the
+//!   countries added to or removed from its range;
+//! * per country, the species gained or lost, including species added to or
+//!   removed from the checklist.
+//!
+//! Countries are compared by code, as in `CountryTrends`: names resolve to
+//! their ISO 3166-1 alpha-2 code (`get_country_code`), and names without a
+//! code are kept as written. A predicted occurrence (`Peru?`)
+//! counts as an occurrence, so a predicted record becoming a confirmed one is
+//! not a change. The `domesticated` and `NA` markers list no countries.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::SpeciesRef;
+use crate::{
+    helper::country_code::get_country_code,
+    parser::{mdd::MddData, ReleasedMddData},
+};
+
+/// File stem of the distribution change artifact.
+pub const DISTRIBUTION_DIFF_FILE: &str = "distribution_changes";
+
+/// Range change of one species.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RangeChange {
+    pub id: u32,
+    /// Scientific name in the newer release.
+    pub sci_name: String,
+    /// Codes of the countries only listed in the newer release (range
+    /// expansion).
+    pub added_countries: Vec<String>,
+    /// Codes of the countries only listed in the older release (range
+    /// contraction).
+    pub removed_countries: Vec<String>,
+}
+
+/// Species gained and lost by one country.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountrySpeciesChange {
+    /// Country name as written in MDD, preferring the newer release.
+    pub name: String,
+    pub gained: Vec<SpeciesRef>,
+    pub lost: Vec<SpeciesRef>,
+}
+
+/// Country distribution changes between two releases.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistributionDiff {
+    pub old_version: String,
+    pub new_version: String,
+    /// Species whose country list changed, by MDD id.
+    pub species: Vec<RangeChange>,
+    /// Country code → species gained and lost, for countries with changes.
+    pub countries: BTreeMap<String, CountrySpeciesChange>,
+}
+
+impl DistributionDiff {
+    /// Compare the distributions of two release bundles.
+    pub fn between(old: &ReleasedMddData, new: &ReleasedMddData) -> Self {
+        let old_species: Vec<&MddData> = old.species().collect();
+        let new_species: Vec<&MddData> = new.species().collect();
+        let mut diff = Self::compare(&old_species, &new_species);
+        diff.old_version = old.get_version().to_string();
+        diff.new_version = new.get_version().to_string();
+        diff
+    }
+
+    /// Compare two species lists. Versions are left empty.
+    pub fn from_species(old: &[MddData], new: &[MddData]) -> Self {
+        let old: Vec<&MddData> = old.iter().collect();
+        let new: Vec<&MddData> = new.iter().collect();
+        Self::compare(&old, &new)
+    }
+
+    fn compare(old: &[&MddData], new: &[&MddData]) -> Self {
+        let old_by_id: BTreeMap<u32, &MddData> = old.iter().map(|d| (d.id, *d)).collect();
+        let new_by_id: BTreeMap<u32, &MddData> = new.iter().map(|d| (d.id, *d)).collect();
+        let ids: BTreeSet<u32> = old_by_id.keys().chain(new_by_id.keys()).copied().collect();
+        let mut diff = Self::default();
+        for id in ids {
+            let old_record = old_by_id.get(&id);
+            let new_record = new_by_id.get(&id);
+            let old_countries = old_record.map(|d| countries(d)).unwrap_or_default();
+            let new_countries = new_record.map(|d| countries(d)).unwrap_or_default();
+            let added: Vec<String> = new_countries
+                .keys()
+                .filter(|code| !old_countries.contains_key(*code))
+                .cloned()
+                .collect();
+            let removed: Vec<String> = old_countries
+                .keys()
+                .filter(|code| !new_countries.contains_key(*code))
+                .cloned()
+                .collect();
+            // The newer record names the species when it still exists.
+            let Some(record) = new_record.or(old_record) else {
+                continue;
+            };
+            for code in &added {
+                let change = diff.country(code);
+                change.name = new_countries[code].clone();
+                change.gained.push(SpeciesRef::from_mdd(record));
+            }
+            for code in &removed {
+                let change = diff.country(code);
+                if change.name.is_empty() {
+                    change.name = old_countries[code].clone();
+                }
+                change.lost.push(SpeciesRef::from_mdd(record));
+            }
+            let in_both = old_record.is_some() && new_record.is_some();
+            if in_both && !(added.is_empty() && removed.is_empty()) {
+                diff.species.push(RangeChange {
+                    id,
+                    sci_name: record.sci_name.clone(),
+                    added_countries: added,
+                    removed_countries: removed,
+                });
+            }
+        }
+        diff
+    }
+
+    fn country(&mut self, code: &str) -> &mut CountrySpeciesChange {
+        self.countries.entry(code.to_string()).or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.countries.is_empty()
+    }
+
+    /// Species that gained at least one country.
+    pub fn expansions(&self) -> impl Iterator<Item = &RangeChange> {
+        self.species
+            .iter()
+            .filter(|s| !s.added_countries.is_empty())
+    }
+
+    /// Species that lost at least one country.
+    pub fn contractions(&self) -> impl Iterator<Item = &RangeChange> {
+        self.species
+            .iter()
+            .filter(|s| !s.removed_countries.is_empty())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).expect("Failed to serialize")
+    }
+}
+
+/// Country code → name of the countries of a species, predicted
+/// occurrences included.
+fn countries(species: &MddData) -> BTreeMap<String, String> {
+    species
+        .countries()
+        .iter()
+        .map(|country| country.name.trim())
+        .filter(|name| !name.is_empty())
+        .map(|name| (get_country_code(name), name.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn species(id: u32, sci_name: &str, countries: &str) -> MddData {
+        MddData {
+            id,
+            sci_name: sci_name.to_string(),
+            country_distribution: countries.to_string(),
+            ..MddData::new()
+        }
+    }
+
+    #[test]
+    fn test_distribution_diff() {
+        let old = vec![
+            species(1, "Panthera_leo", "Kenya|Tanzania|Angola"),
+            species(2, "Canis_lupus", "Canada|Mexico?"),
+            species(3, "Felis_catus", "domesticated"),
+        ];
+        let new = vec![
+            species(1, "Panthera_leo", "Kenya|Tanzania|Uganda"),
+            species(2, "Canis_lupus", "Canada|Mexico"),
+            species(3, "Felis_catus", "domesticated"),
+            species(4, "Lepus_novus", "Kenya"),
+            species(5, "Sylvilagus_novus", "NA"),
+        ];
+        let diff = DistributionDiff::from_species(&old, &new);
+        let code = |name: &str| get_country_code(name);
+        assert_eq!(
+            diff.species,
+            vec![RangeChange {
+                id: 1,
+                sci_name: "Panthera_leo".to_string(),
+                added_countries: vec![code("Uganda")],
+                removed_countries: vec![code("Angola")],
+            }]
+        );
+        assert_eq!(diff.expansions().count(), 1);
+        assert_eq!(diff.contractions().count(), 1);
+        let kenya = &diff.countries[&code("Kenya")];
+        assert_eq!(kenya.name, "Kenya");
+        assert_eq!(kenya.gained[0].id, 4);
+        assert!(kenya.lost.is_empty());
+        assert_eq!(
+            diff.countries[&code("Angola")].lost[0].sci_name,
+            "Panthera_leo"
+        );
+        assert_eq!(diff.countries.len(), 3);
+        assert!(!diff.countries.contains_key("domesticated"));
+        assert!(!diff.countries.contains_key("NA"));
+        assert!(DistributionDiff::from_species(&old, &old).is_empty());
+    }
+}
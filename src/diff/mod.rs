@@ -0,0 +1,933 @@
+//! Compare two MDD releases.
+//!
+//! `ReleaseDiff` matches species by MDD id and reports additions, removals,
+//! renames (same id, new `sciName`), and field-level changes. Each field
+//! change is classified so taxonomy moves, IUCN status changes, and
+//! distribution updates can be summarized separately in release notes.
+//!
+//! The diff serializes to JSON (`to_json`) and renders as a Markdown
+//! changelog (`to_markdown`); `changelog::Changelog` groups it into release
+//! notes.
+//!
+//! Diffs of two bundles (`between`) also carry a `ReleasePatch` with the new
+//! and changed records, so clients holding the older release can download
+//! the diff instead of the full bundle and rebuild the newer one with
+//! `ReleasedMddData::apply_diff`.
+//!
+//! Bundle diffs are also annotated with likely splits and lumps
+//! (`TaxonomicEvent`), traced through the synonym table for editor review:
+//!
+//! * A split: the synonyms of one older species now belong to two or more
+//!   species, at least one of them new.
+//! * A lump: the synonyms of two or more older species, at least one of
+//!   them removed, now belong to one species.
+//!
+//! Events traced by synonym id are `High` confidence when the name record
+//! of the new (split) or removed (lumped) species itself moved, and
+//! `Medium` when only other synonyms did. Events only matched by genus and
+//! epithet, e.g. across releases that renumbered synonyms, are `Low`.
+//!
+//! `distribution::DistributionDiff` lists country distribution changes per
+//! species and per country as a separate artifact.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::parser::{
+    error::MddError, mdd::MddData, synonyms::SynonymData, MetaData, ReleasedMddData, SimpleMDD,
+};
+
+pub mod distribution;
+
+/// Fields describing the taxonomic placement of a species.
+const TAXONOMY_FIELDS: [&str; 17] = [
+    "subclass",
+    "infraclass",
+    "magnorder",
+    "superorder",
+    "taxonOrder",
+    "suborder",
+    "infraorder",
+    "parvorder",
+    "superfamily",
+    "family",
+    "subfamily",
+    "tribe",
+    "genus",
+    "subgenus",
+    "specificEpithet",
+    "authoritySpeciesAuthor",
+    "authoritySpeciesYear",
+];
+
+/// Fields describing where a species occurs.
+const DISTRIBUTION_FIELDS: [&str; 5] = [
+    "subregionDistribution",
+    "countryDistribution",
+    "continentDistribution",
+    "biogeographicRealm",
+    "distributionNotes",
+];
+
+/// Category of a field-level change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    /// Rank assignment or authority changed (e.g. moved to another genus).
+    Taxonomy,
+    /// IUCN Red List status changed.
+    IucnStatus,
+    /// Country, continent, realm, or subregion distribution changed.
+    Distribution,
+    /// Any other field (notes, citations, common names, ...).
+    Other,
+}
+
+impl ChangeKind {
+    /// Classify a serialized (camelCase) `MddData` field name.
+    pub fn from_field(field: &str) -> Self {
+        if TAXONOMY_FIELDS.contains(&field) {
+            Self::Taxonomy
+        } else if field == "iucnStatus" {
+            Self::IucnStatus
+        } else if DISTRIBUTION_FIELDS.contains(&field) {
+            Self::Distribution
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Minimal species reference used in added/removed lists.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeciesRef {
+    pub id: u32,
+    pub sci_name: String,
+}
+
+impl SpeciesRef {
+    pub(crate) fn from_mdd(data: &MddData) -> Self {
+        Self {
+            id: data.id,
+            sci_name: data.sci_name.clone(),
+        }
+    }
+}
+
+/// A species whose scientific name changed while keeping its MDD id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamedSpecies {
+    pub id: u32,
+    pub old_name: String,
+    pub new_name: String,
+}
+
+/// A single field whose value differs between releases.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    /// Serialized (camelCase) field name.
+    pub field: String,
+    pub kind: ChangeKind,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+/// All field changes for one species present in both releases.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeciesChange {
+    pub id: u32,
+    /// Scientific name in the newer release.
+    pub sci_name: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Structured difference between two MDD releases.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseDiff {
+    pub old_version: String,
+    pub new_version: String,
+    /// Species ids only present in the newer release.
+    pub added: Vec<SpeciesRef>,
+    /// Species ids only present in the older release.
+    pub removed: Vec<SpeciesRef>,
+    pub renamed: Vec<RenamedSpecies>,
+    /// Field changes (excluding `sciName`, reported in `renamed`).
+    pub changed: Vec<SpeciesChange>,
+    /// Likely splits and lumps; only set by `between`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<TaxonomicEvent>,
+    /// Records to rebuild the newer release; only set by `between`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<ReleasePatch>,
+}
+
+/// How strongly the synonym table supports a `TaxonomicEvent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Confidence {
+    /// Matched by genus and epithet only.
+    Low,
+    /// Synonym records moved, but not the name record of a new or removed species.
+    Medium,
+    /// The name record of a new or removed species moved.
+    High,
+}
+
+/// Kind of a `TaxonomicEvent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TaxonomicEventKind {
+    /// One older species divided into several species.
+    Split,
+    /// Several older species merged into one species.
+    Lump,
+}
+
+/// A likely split or lump between two releases.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxonomicEvent {
+    pub kind: TaxonomicEventKind,
+    /// Species in the older release: the split species, or every lumped
+    /// species (including the one kept, if any).
+    pub old: Vec<SpeciesRef>,
+    /// Species in the newer release: every species of the split (including
+    /// the one kept, if any), or the lumped species.
+    pub new: Vec<SpeciesRef>,
+    /// Strongest evidence linking a new (split) or removed (lumped) species.
+    pub confidence: Confidence,
+}
+
+/// Records that turn the older release into the newer one.
+///
+/// Removed species are the ones in `ReleaseDiff::removed`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleasePatch {
+    /// Metadata of the newer release.
+    pub metadata: MetaData,
+    /// Added species and species whose record changed in any field
+    /// (including slugs and basionym links), as in the newer release.
+    pub species: Vec<MddData>,
+    /// Added synonyms and synonyms whose record changed, as in the newer
+    /// release.
+    #[serde(default)]
+    pub synonyms: Vec<SynonymData>,
+    /// Ids of synonyms only present in the older release.
+    #[serde(default)]
+    pub removed_synonyms: Vec<u32>,
+    /// Species ids in the order of the newer release. Empty when that order
+    /// is the older order without the removed species, followed by the
+    /// added species.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub order: Vec<u32>,
+}
+
+impl ReleasePatch {
+    fn between(old: &ReleasedMddData, new: &ReleasedMddData) -> Self {
+        let old_species: HashMap<u32, &MddData> = old
+            .data
+            .iter()
+            .map(|entry| (entry.mdd_id(), entry.species()))
+            .collect();
+        let species = new
+            .species()
+            .filter(|record| match old_species.get(&record.id) {
+                Some(old_record) => to_value(*old_record) != to_value(*record),
+                None => true,
+            })
+            .cloned()
+            .collect();
+
+        let old_synonyms: HashMap<u32, &SynonymData> = old
+            .all_synonyms()
+            .map(|synonym| (synonym.syn_id, synonym))
+            .collect();
+        let new_ids: HashSet<u32> = new.all_synonyms().map(|synonym| synonym.syn_id).collect();
+        let synonyms = new
+            .all_synonyms()
+            .filter(|synonym| match old_synonyms.get(&synonym.syn_id) {
+                Some(old_synonym) => to_value(*old_synonym) != to_value(*synonym),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        let removed_synonyms = old
+            .all_synonyms()
+            .map(|synonym| synonym.syn_id)
+            .filter(|syn_id| !new_ids.contains(syn_id))
+            .collect();
+
+        let new_order: Vec<u32> = new.data.iter().map(|entry| entry.mdd_id()).collect();
+        let order = if default_order(old, &new_order) == new_order {
+            Vec::new()
+        } else {
+            new_order
+        };
+        Self {
+            metadata: new.metadata.clone(),
+            species,
+            synonyms,
+            removed_synonyms,
+            order,
+        }
+    }
+}
+
+impl ReleaseDiff {
+    /// Compare two release bundles.
+    pub fn between(old: &ReleasedMddData, new: &ReleasedMddData) -> Self {
+        let old_species: Vec<&MddData> = old.species().collect();
+        let new_species: Vec<&MddData> = new.species().collect();
+        let mut diff = Self::compare(&old_species, &new_species);
+        diff.old_version = old.get_version().to_string();
+        diff.new_version = new.get_version().to_string();
+        diff.events = taxonomic_events(old, new);
+        diff.patch = Some(ReleasePatch::between(old, new));
+        diff
+    }
+
+    /// Compare two species lists. Versions are left empty.
+    pub fn from_species(old: &[MddData], new: &[MddData]) -> Self {
+        let old: Vec<&MddData> = old.iter().collect();
+        let new: Vec<&MddData> = new.iter().collect();
+        Self::compare(&old, &new)
+    }
+
+    fn compare(old: &[&MddData], new: &[&MddData]) -> Self {
+        let old_by_id: BTreeMap<u32, &MddData> = old.iter().map(|d| (d.id, *d)).collect();
+        let new_by_id: BTreeMap<u32, &MddData> = new.iter().map(|d| (d.id, *d)).collect();
+        let mut diff = Self::default();
+
+        for (id, old_record) in &old_by_id {
+            if !new_by_id.contains_key(id) {
+                diff.removed.push(SpeciesRef::from_mdd(old_record));
+            }
+        }
+        for (id, new_record) in &new_by_id {
+            let old_record = match old_by_id.get(id) {
+                Some(record) => record,
+                None => {
+                    diff.added.push(SpeciesRef::from_mdd(new_record));
+                    continue;
+                }
+            };
+            if old_record.sci_name != new_record.sci_name {
+                diff.renamed.push(RenamedSpecies {
+                    id: *id,
+                    old_name: old_record.sci_name.clone(),
+                    new_name: new_record.sci_name.clone(),
+                });
+            }
+            let changes = field_changes(old_record, new_record);
+            if !changes.is_empty() {
+                diff.changed.push(SpeciesChange {
+                    id: *id,
+                    sci_name: new_record.sci_name.clone(),
+                    changes,
+                });
+            }
+        }
+        diff
+    }
+
+    /// Returns `true` when the releases contain identical species records.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.changed.is_empty()
+    }
+
+    /// Likely splits and lumps of at least `confidence`.
+    pub fn events_at_least(&self, confidence: Confidence) -> impl Iterator<Item = &TaxonomicEvent> {
+        self.events
+            .iter()
+            .filter(move |event| event.confidence >= confidence)
+    }
+
+    /// Number of field changes of the given kind across all species.
+    pub fn count_changes(&self, kind: ChangeKind) -> usize {
+        self.changed
+            .iter()
+            .flat_map(|s| &s.changes)
+            .filter(|c| c.kind == kind)
+            .count()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).expect("Failed to serialize")
+    }
+
+    /// Render the diff as a Markdown changelog.
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!(
+            "# MDD changes: v{} → v{}\n\n",
+            self.old_version, self.new_version
+        );
+        md.push_str(&format!("* Added species: {}\n", self.added.len()));
+        md.push_str(&format!("* Removed species: {}\n", self.removed.len()));
+        md.push_str(&format!("* Renamed species: {}\n", self.renamed.len()));
+        md.push_str(&format!(
+            "* Taxonomy changes: {}\n",
+            self.count_changes(ChangeKind::Taxonomy)
+        ));
+        md.push_str(&format!(
+            "* IUCN status changes: {}\n",
+            self.count_changes(ChangeKind::IucnStatus)
+        ));
+        md.push_str(&format!(
+            "* Distribution changes: {}\n",
+            self.count_changes(ChangeKind::Distribution)
+        ));
+
+        if !self.added.is_empty() {
+            md.push_str("\n## Added species\n\n");
+            for species in &self.added {
+                md.push_str(&format!(
+                    "* *{}* ({})\n",
+                    display_name(&species.sci_name),
+                    species.id
+                ));
+            }
+        }
+        if !self.removed.is_empty() {
+            md.push_str("\n## Removed species\n\n");
+            for species in &self.removed {
+                md.push_str(&format!(
+                    "* *{}* ({})\n",
+                    display_name(&species.sci_name),
+                    species.id
+                ));
+            }
+        }
+        if !self.renamed.is_empty() {
+            md.push_str("\n## Renamed species\n\n");
+            for species in &self.renamed {
+                md.push_str(&format!(
+                    "* *{}* → *{}* ({})\n",
+                    display_name(&species.old_name),
+                    display_name(&species.new_name),
+                    species.id
+                ));
+            }
+        }
+        if !self.events.is_empty() {
+            md.push_str("\n## Likely splits and lumps\n\n");
+            for event in &self.events {
+                let kind = match event.kind {
+                    TaxonomicEventKind::Split => "Split",
+                    TaxonomicEventKind::Lump => "Lump",
+                };
+                md.push_str(&format!(
+                    "* {} ({:?} confidence): {} → {}\n",
+                    kind,
+                    event.confidence,
+                    species_names(&event.old),
+                    species_names(&event.new)
+                ));
+            }
+        }
+        if !self.changed.is_empty() {
+            md.push_str("\n## Field changes\n");
+            for species in &self.changed {
+                md.push_str(&format!(
+                    "\n### *{}* ({})\n\n",
+                    display_name(&species.sci_name),
+                    species.id
+                ));
+                for change in &species.changes {
+                    md.push_str(&format!(
+                        "* `{}`: `{}` → `{}`\n",
+                        change.field, change.old_value, change.new_value
+                    ));
+                }
+            }
+        }
+        md
+    }
+}
+
+impl ReleasedMddData {
+    /// Rebuild the newer release of `diff` from this (older) release.
+    ///
+    /// Needs a diff built by `ReleaseDiff::between` (one with a `patch`).
+    /// Synonyms keep their position in this release; added ones follow the
+    /// existing synonyms of their species. Fails with
+    /// `MddError::DiffMismatch` when the diff was made from another release.
+    pub fn apply_diff(&self, diff: &ReleaseDiff) -> Result<Self, MddError> {
+        let patch = diff.patch.as_ref().ok_or_else(|| {
+            MddError::DiffMismatch("diff has no patch; build it with `between`".to_string())
+        })?;
+        if !diff.old_version.is_empty() && diff.old_version != self.get_version() {
+            return Err(MddError::DiffMismatch(format!(
+                "diff is from version {}, release is version {}",
+                diff.old_version,
+                self.get_version()
+            )));
+        }
+
+        let mut species: HashMap<u32, MddData> = self
+            .data
+            .iter()
+            .map(|entry| (entry.mdd_id(), entry.species().clone()))
+            .collect();
+        for removed in &diff.removed {
+            if species.remove(&removed.id).is_none() {
+                return Err(missing_species(removed.id));
+            }
+        }
+        for change in &diff.changed {
+            if !species.contains_key(&change.id) {
+                return Err(missing_species(change.id));
+            }
+        }
+        for record in &patch.species {
+            species.insert(record.id, record.clone());
+        }
+
+        let mut synonyms: Vec<SynonymData> = self.all_synonyms().cloned().collect();
+        let removed: HashSet<u32> = patch.removed_synonyms.iter().copied().collect();
+        synonyms.retain(|synonym| !removed.contains(&synonym.syn_id));
+        let positions: HashMap<u32, usize> = synonyms
+            .iter()
+            .enumerate()
+            .map(|(position, synonym)| (synonym.syn_id, position))
+            .collect();
+        for synonym in &patch.synonyms {
+            match positions.get(&synonym.syn_id) {
+                Some(&position) => synonyms[position] = synonym.clone(),
+                None => synonyms.push(synonym.clone()),
+            }
+        }
+
+        let order = if patch.order.is_empty() {
+            let removed: HashSet<u32> = diff.removed.iter().map(|removed| removed.id).collect();
+            let new_ids: Vec<u32> = self
+                .data
+                .iter()
+                .map(|entry| entry.mdd_id())
+                .filter(|id| !removed.contains(id))
+                .chain(patch.species.iter().map(|record| record.id))
+                .collect();
+            default_order(self, &new_ids)
+        } else {
+            patch.order.clone()
+        };
+        let mut by_species: HashMap<u32, Vec<SynonymData>> = HashMap::new();
+        let mut synonym_only = Vec::new();
+        for synonym in synonyms {
+            match synonym.species_id {
+                Some(id) => by_species.entry(id).or_default().push(synonym),
+                None => synonym_only.push(synonym),
+            }
+        }
+        let mut data = Vec::with_capacity(order.len());
+        for id in order {
+            let record = species.remove(&id).ok_or_else(|| missing_species(id))?;
+            let synonyms = by_species.remove(&id).unwrap_or_default();
+            data.push(SimpleMDD::new(record, synonyms));
+        }
+        if let Some(id) = species.keys().next() {
+            return Err(MddError::DiffMismatch(format!(
+                "species {} is missing from the patch order",
+                id
+            )));
+        }
+
+        Ok(Self {
+            metadata: patch.metadata.clone(),
+            data,
+            synonym_only,
+        })
+    }
+}
+
+/// Likely splits and lumps between two releases; see the module docs.
+fn taxonomic_events(old: &ReleasedMddData, new: &ReleasedMddData) -> Vec<TaxonomicEvent> {
+    let old_species: BTreeMap<u32, &SimpleMDD> = old
+        .data
+        .iter()
+        .map(|entry| (entry.mdd_id(), entry))
+        .collect();
+    let new_species: BTreeMap<u32, &SimpleMDD> = new
+        .data
+        .iter()
+        .map(|entry| (entry.mdd_id(), entry))
+        .collect();
+    let old_owner: HashMap<u32, u32> = old
+        .all_synonyms()
+        .filter_map(|synonym| Some((synonym.syn_id, synonym.species_id?)))
+        .collect();
+
+    // Synonym records that moved from an older to another newer species,
+    // keyed by (old id, new id), with the strongest evidence.
+    let mut moved: BTreeMap<(u32, u32), Confidence> = BTreeMap::new();
+    for (&new_id, entry) in &new_species {
+        let own_name = own_name_id(entry);
+        for synonym in entry.synonyms() {
+            let old_id = match old_owner.get(&synonym.syn_id) {
+                Some(&old_id) if old_id != new_id => old_id,
+                _ => continue,
+            };
+            let name_record = (!old_species.contains_key(&new_id)
+                && own_name == Some(synonym.syn_id))
+                || (!new_species.contains_key(&old_id)
+                    && own_name_id(old_species[&old_id]) == Some(synonym.syn_id));
+            let confidence = if name_record {
+                Confidence::High
+            } else {
+                Confidence::Medium
+            };
+            let strongest = moved.entry((old_id, new_id)).or_insert(confidence);
+            *strongest = (*strongest).max(confidence);
+        }
+    }
+
+    // Species in the same genus sharing an epithet, for releases whose
+    // synonym ids do not line up.
+    let traced: HashSet<u32> = moved
+        .keys()
+        .flat_map(|(old_id, new_id)| [*old_id, *new_id])
+        .collect();
+    let old_names: HashMap<(&str, &str), Vec<u32>> =
+        old_species
+            .iter()
+            .fold(HashMap::new(), |mut names, (&old_id, entry)| {
+                let species = entry.species();
+                names
+                    .entry((species.genus.as_str(), species.specific_epithet.as_str()))
+                    .or_default()
+                    .push(old_id);
+                names
+            });
+    for (&new_id, entry) in &new_species {
+        if traced.contains(&new_id) {
+            continue;
+        }
+        let is_new = !old_species.contains_key(&new_id);
+        for synonym in entry.synonyms() {
+            let name = (synonym.genus.as_str(), synonym.specific_epithet.as_str());
+            for &old_id in old_names.get(&name).into_iter().flatten() {
+                let is_removed = !new_species.contains_key(&old_id);
+                if old_id != new_id && (is_new || is_removed) && !traced.contains(&old_id) {
+                    moved.entry((old_id, new_id)).or_insert(Confidence::Low);
+                }
+            }
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut splits: BTreeMap<u32, Vec<(u32, Confidence)>> = BTreeMap::new();
+    let mut lumps: BTreeMap<u32, Vec<(u32, Confidence)>> = BTreeMap::new();
+    for (&(old_id, new_id), &confidence) in &moved {
+        splits.entry(old_id).or_default().push((new_id, confidence));
+        lumps.entry(new_id).or_default().push((old_id, confidence));
+    }
+    for (old_id, targets) in splits {
+        let confidence = targets
+            .iter()
+            .filter(|(new_id, _)| !old_species.contains_key(new_id))
+            .map(|(_, confidence)| *confidence)
+            .max();
+        let kept = new_species.contains_key(&old_id).then_some(old_id);
+        let ids: BTreeSet<u32> = kept
+            .into_iter()
+            .chain(targets.iter().map(|(new_id, _)| *new_id))
+            .collect();
+        if let (Some(confidence), true) = (confidence, ids.len() >= 2) {
+            events.push(TaxonomicEvent {
+                kind: TaxonomicEventKind::Split,
+                old: vec![SpeciesRef::from_mdd(old_species[&old_id].species())],
+                new: ids
+                    .iter()
+                    .map(|id| SpeciesRef::from_mdd(new_species[id].species()))
+                    .collect(),
+                confidence,
+            });
+        }
+    }
+    for (new_id, sources) in lumps {
+        let confidence = sources
+            .iter()
+            .filter(|(old_id, _)| !new_species.contains_key(old_id))
+            .map(|(_, confidence)| *confidence)
+            .max();
+        let kept = old_species.contains_key(&new_id).then_some(new_id);
+        let ids: BTreeSet<u32> = kept
+            .into_iter()
+            .chain(sources.iter().map(|(old_id, _)| *old_id))
+            .collect();
+        if let (Some(confidence), true) = (confidence, ids.len() >= 2) {
+            events.push(TaxonomicEvent {
+                kind: TaxonomicEventKind::Lump,
+                old: ids
+                    .iter()
+                    .map(|id| SpeciesRef::from_mdd(old_species[id].species()))
+                    .collect(),
+                new: vec![SpeciesRef::from_mdd(new_species[&new_id].species())],
+                confidence,
+            });
+        }
+    }
+    events
+}
+
+/// Id of the synonym record holding the valid name of a species.
+fn own_name_id(entry: &SimpleMDD) -> Option<u32> {
+    let species = entry.species();
+    entry
+        .synonyms()
+        .iter()
+        .find(|synonym| {
+            synonym.genus == species.genus && synonym.specific_epithet == species.specific_epithet
+        })
+        .map(|synonym| synonym.syn_id)
+}
+
+/// Species ids of `old` that are in `new_ids`, in their old order, followed
+/// by the ids of `new_ids` not in `old`.
+fn default_order(old: &ReleasedMddData, new_ids: &[u32]) -> Vec<u32> {
+    let kept: HashSet<u32> = new_ids.iter().copied().collect();
+    let old_ids: HashSet<u32> = old.data.iter().map(|entry| entry.mdd_id()).collect();
+    old.data
+        .iter()
+        .map(|entry| entry.mdd_id())
+        .filter(|id| kept.contains(id))
+        .chain(new_ids.iter().copied().filter(|id| !old_ids.contains(id)))
+        .collect()
+}
+
+fn missing_species(id: u32) -> MddError {
+    MddError::DiffMismatch(format!("species {} is not in the release", id))
+}
+
+fn to_value<T: Serialize>(record: &T) -> Value {
+    serde_json::to_value(record).expect("Failed to serialize MDD record")
+}
+
+/// MDD scientific names use `_` between name parts.
+pub(crate) fn display_name(sci_name: &str) -> String {
+    sci_name.replace('_', " ")
+}
+
+fn species_names(species: &[SpeciesRef]) -> String {
+    species
+        .iter()
+        .map(|species| format!("*{}* ({})", display_name(&species.sci_name), species.id))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn field_changes(old: &MddData, new: &MddData) -> Vec<FieldChange> {
+    let old_fields = to_field_map(old);
+    let new_fields = to_field_map(new);
+    old_fields
+        .into_iter()
+        // `slug` follows the name and `basionymSynonymId` the synonym table;
+        // neither is an MDD column.
+        .filter(|(field, _)| {
+            !matches!(
+                field.as_str(),
+                "id" | "sciName" | "slug" | "basionymSynonymId"
+            )
+        })
+        .filter_map(|(field, old_value)| {
+            let new_value = new_fields.get(&field)?;
+            if &old_value == new_value {
+                return None;
+            }
+            Some(FieldChange {
+                kind: ChangeKind::from_field(&field),
+                old_value: value_to_string(&old_value),
+                new_value: value_to_string(new_value),
+                field,
+            })
+        })
+        .collect()
+}
+
+fn to_field_map(data: &MddData) -> serde_json::Map<String, Value> {
+    match serde_json::to_value(data).expect("Failed to serialize MDD record") {
+        Value::Object(map) => map,
+        _ => unreachable!("MddData serializes to a JSON object"),
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Species `genus_epithet` with only its name fields set.
+    pub(crate) fn binomial(id: u32, genus: &str, epithet: &str) -> MddData {
+        MddData {
+            id,
+            sci_name: format!("{}_{}", genus, epithet),
+            genus: genus.to_string(),
+            specific_epithet: epithet.to_string(),
+            ..MddData::new()
+        }
+    }
+
+    /// Synonym `genus epithet` of species `species_id`.
+    pub(crate) fn synonym(syn_id: u32, species_id: u32, genus: &str, epithet: &str) -> SynonymData {
+        let mut synonym = SynonymData::new();
+        synonym.syn_id = syn_id;
+        synonym.species_id = Some(species_id);
+        synonym.genus = genus.to_string();
+        synonym.specific_epithet = epithet.to_string();
+        synonym
+    }
+
+    fn species(id: u32, sci_name: &str, family: &str, iucn: &str) -> MddData {
+        MddData {
+            id,
+            sci_name: sci_name.to_string(),
+            family: family.to_string(),
+            iucn_status: iucn.to_string(),
+            ..MddData::new()
+        }
+    }
+
+    #[test]
+    fn test_release_diff() {
+        let old = vec![
+            species(1, "Panthera_leo", "Felidae", "VU"),
+            species(2, "Canis_lupus", "Canidae", "LC"),
+            species(3, "Felis_catus", "Felidae", "NE"),
+        ];
+        let new = vec![
+            species(1, "Panthera_leo", "Felidae", "EN"),
+            species(3, "Felis_silvestris", "Felidae", "NE"),
+            species(4, "Vulpes_vulpes", "Canidae", "LC"),
+        ];
+        let diff = ReleaseDiff::from_species(&old, &new);
+        assert_eq!(diff.added[0].id, 4);
+        assert_eq!(diff.removed[0].sci_name, "Canis_lupus");
+        assert_eq!(diff.renamed[0].new_name, "Felis_silvestris");
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].changes[0].kind, ChangeKind::IucnStatus);
+        assert_eq!(diff.count_changes(ChangeKind::IucnStatus), 1);
+
+        let md = diff.to_markdown();
+        assert!(md.contains("* *Felis catus* → *Felis silvestris* (3)"));
+        assert!(md.contains("* `iucnStatus`: `VU` → `EN`"));
+    }
+
+    #[test]
+    fn test_apply_diff() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let syn_data = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let mut synonyms = SynonymData::new().from_csv(&syn_data);
+        for (i, synonym) in synonyms.iter_mut().enumerate() {
+            synonym.species_id = if i < 2 { Some(species[i].id) } else { None };
+        }
+        let old =
+            ReleasedMddData::from_parser(species.clone(), synonyms.clone(), "2.0", "2025-01-01");
+
+        let mut new_species = species;
+        new_species.remove(5);
+        new_species[0].iucn_status = "DD".to_string();
+        new_species[1].sci_name = "Pronolagus_renamed".to_string();
+        let mut added = new_species[10].clone();
+        added.id = 9999999;
+        added.sci_name = "Lepus_novus".to_string();
+        new_species.insert(3, added);
+        synonyms.remove(1);
+        synonyms[0].species_id = None;
+        let new = ReleasedMddData::from_parser(new_species, synonyms, "2.1", "2025-06-01");
+
+        let diff = ReleaseDiff::between(&old, &new);
+        let patch = diff.patch.as_ref().unwrap();
+        assert_eq!(patch.species.len(), 3);
+        assert_eq!(patch.removed_synonyms.len(), 1);
+        assert!(!patch.order.is_empty());
+
+        // Round-trip through JSON as a client would.
+        let diff: ReleaseDiff = serde_json::from_str(&diff.to_json()).unwrap();
+        let applied = old.apply_diff(&diff).unwrap();
+        assert_eq!(applied.to_json(), new.to_json());
+
+        assert!(matches!(
+            new.apply_diff(&diff),
+            Err(MddError::DiffMismatch(_))
+        ));
+        assert!(old
+            .apply_diff(&ReleaseDiff::from_species(&[], &[]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_taxonomic_events() {
+        let old = ReleasedMddData::from_parser(
+            vec![
+                binomial(1, "Sorex", "araneus"),
+                binomial(2, "Mus", "musculus"),
+                binomial(3, "Mus", "domesticus"),
+            ],
+            vec![
+                synonym(10, 1, "Sorex", "araneus"),
+                synonym(11, 1, "Sorex", "antinorii"),
+                synonym(20, 2, "Mus", "musculus"),
+                synonym(30, 3, "Mus", "domesticus"),
+            ],
+            "2.1",
+            "2025-01-01",
+        );
+        // The synonyms of Mus were renumbered in the newer release.
+        let new = ReleasedMddData::from_parser(
+            vec![
+                binomial(1, "Sorex", "araneus"),
+                binomial(2, "Mus", "musculus"),
+                binomial(5, "Sorex", "samniticus"),
+            ],
+            vec![
+                synonym(10, 1, "Sorex", "araneus"),
+                synonym(11, 5, "Sorex", "antinorii"),
+                synonym(21, 2, "Mus", "musculus"),
+                synonym(31, 2, "Mus", "domesticus"),
+                synonym(50, 5, "Sorex", "samniticus"),
+            ],
+            "2.2",
+            "2025-06-01",
+        );
+        let diff = ReleaseDiff::between(&old, &new);
+        assert_eq!(diff.events.len(), 2);
+        let split = &diff.events[0];
+        assert_eq!(split.kind, TaxonomicEventKind::Split);
+        assert_eq!(split.confidence, Confidence::Medium);
+        assert_eq!(split.old[0].id, 1);
+        let ids: Vec<u32> = split.new.iter().map(|species| species.id).collect();
+        assert_eq!(ids, vec![1, 5]);
+        let lump = &diff.events[1];
+        assert_eq!(lump.kind, TaxonomicEventKind::Lump);
+        assert_eq!(lump.confidence, Confidence::Low);
+        assert_eq!(lump.old.len(), 2);
+        assert_eq!(lump.new[0].id, 2);
+        assert_eq!(diff.events_at_least(Confidence::Medium).count(), 1);
+        assert!(diff.to_markdown().contains(
+            "* Lump (Low confidence): *Mus musculus* (2), *Mus domesticus* (3) → *Mus musculus* (2)"
+        ));
+    }
+
+    #[test]
+    fn test_identical_releases() {
+        let data = vec![species(1, "Panthera_leo", "Felidae", "VU")];
+        assert!(ReleaseDiff::from_species(&data, &data).is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+//! DuckDB export of parsed MDD data (enabled with the `duckdb` feature).
+//!
+//! The database mirrors the SQLite export of the `db` module table for table:
+//! `release_metadata`, `species`, `synonyms`, `countries`, and
+//! `country_species`, with the same column names and indexes, so queries
+//! written against one file run unchanged against the other. The FTS5
+//! `species_fts` index has no DuckDB counterpart and is not written; DuckDB's
+//! `fts` extension can build one from the `species` table if needed.
+//!
+//! ```rust, ignore
+//! use mdd_api::duckdb::DuckDbExporter;
+//!
+//! let mut exporter = DuckDbExporter::create("mdd.duckdb")?;
+//! exporter.write_release(&release, &country_stats)?;
+//! ```
+
+use std::path::Path;
+
+use ::duckdb::{params, params_from_iter, types::Value as DuckValue, Connection, Transaction};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    db::{
+        country_species, metadata_rows, sql_column_name, table_schema, to_field_map, SPECIES_TABLE,
+        SYNONYM_TABLE,
+    },
+    parser::{country::CountryMDDStats, error::MddError, ReleasedMddData},
+};
+
+/// Writer for an MDD DuckDB database.
+pub struct DuckDbExporter {
+    conn: Connection,
+}
+
+impl DuckDbExporter {
+    /// Create (or replace) the database at `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, MddError> {
+        let path = path.as_ref();
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(Self {
+            conn: Connection::open(path)?,
+        })
+    }
+
+    /// Create an in-memory database, mostly useful for tests.
+    pub fn in_memory() -> Result<Self, MddError> {
+        Ok(Self {
+            conn: Connection::open_in_memory()?,
+        })
+    }
+
+    /// Access the underlying connection.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Write the release bundle plus country statistics in a single transaction.
+    pub fn write_release(
+        &mut self,
+        release: &ReleasedMddData,
+        country_stats: &CountryMDDStats,
+    ) -> Result<(), MddError> {
+        let tx = self.conn.transaction()?;
+        tx.execute_batch(&table_schema()?)?;
+        let mut stmt = tx.prepare("INSERT INTO release_metadata (key, value) VALUES (?, ?)")?;
+        for (key, value) in metadata_rows(release)? {
+            stmt.execute(params![key, value])?;
+        }
+        for species in release.species() {
+            insert_record(&tx, SPECIES_TABLE, species)?;
+        }
+        for synonym in release
+            .data
+            .iter()
+            .flat_map(|entry| entry.synonyms())
+            .chain(release.synonym_only.iter())
+        {
+            insert_record(&tx, SYNONYM_TABLE, synonym)?;
+        }
+        write_country_stats(&tx, country_stats)?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+fn insert_record<T: Serialize>(tx: &Transaction, table: &str, record: &T) -> Result<(), MddError> {
+    let fields = to_field_map(record)?;
+    let columns: Vec<String> = fields.keys().map(|k| sql_column_name(k)).collect();
+    let sql = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        table,
+        columns.join(", "),
+        vec!["?"; columns.len()].join(", ")
+    );
+    let values: Vec<DuckValue> = fields.into_values().map(to_duck_value).collect();
+    tx.prepare_cached(&sql)?.execute(params_from_iter(values))?;
+    Ok(())
+}
+
+fn to_duck_value(value: Value) -> DuckValue {
+    match value {
+        Value::Null => DuckValue::Null,
+        Value::Bool(b) => DuckValue::Boolean(b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => DuckValue::BigInt(i),
+            None => DuckValue::Double(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => DuckValue::Text(s),
+        other => DuckValue::Text(other.to_string()),
+    }
+}
+
+fn write_country_stats(tx: &Transaction, stats: &CountryMDDStats) -> Result<(), MddError> {
+    let mut country_stmt = tx.prepare(
+        "INSERT INTO countries (code, name, total_orders, total_families, total_genera, \
+         total_living_species, total_extinct_species) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )?;
+    let mut species_stmt = tx.prepare(
+        "INSERT INTO country_species (country_code, species_id, predicted) VALUES (?, ?, ?)",
+    )?;
+    for (code, data) in &stats.country_data {
+        country_stmt.execute(params![
+            code,
+            data.name,
+            data.total_orders,
+            data.total_families,
+            data.total_genera,
+            data.total_living_species,
+            data.total_extinct_species,
+        ])?;
+        for (id, predicted) in data.species_list.iter().filter_map(|s| country_species(s)) {
+            species_stmt.execute(params![code, id, predicted])?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{mdd::MddData, synonyms::SynonymData};
+
+    #[test]
+    fn test_write_release() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let mut stats = CountryMDDStats::new();
+        stats.parse_country_data(&species);
+        let syn_csv = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let mut synonyms = SynonymData::new().from_csv(&syn_csv);
+        synonyms[0].species_id = Some(1001076);
+        let release = ReleasedMddData::from_parser(species, synonyms, "2.0", "2025-01-01");
+
+        let mut exporter = DuckDbExporter::in_memory().unwrap();
+        exporter.write_release(&release, &stats).unwrap();
+        let conn = exporter.connection();
+        let count: u32 = conn
+            .query_row("SELECT COUNT(*) FROM species", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 112);
+        let version: String = conn
+            .query_row(
+                "SELECT value FROM release_metadata WHERE key = 'version'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(version, "2.0");
+        let linked: u32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM synonyms WHERE species_id = 1001076",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(linked, 1);
+        let countries: u32 = conn
+            .query_row("SELECT COUNT(*) FROM countries", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(countries as usize, stats.country_data.len());
+    }
+}
@@ -0,0 +1,458 @@
+//! GBIF Backbone taxon keys.
+//!
+//! Species are matched against the GBIF Backbone Taxonomy by scientific name,
+//! either online through the species-match API (`GbifClient`) or offline from
+//! the `Taxon.tsv` of a backbone dump (`GbifBackbone`). `GbifEnrichment`
+//! collects one `GbifMatch` per species, with the GBIF taxon key, the
+//! accepted key when GBIF treats the name as a synonym, the match type, and
+//! the confidence, and is written as a sidecar JSON next to the release so
+//! occurrence data can be joined on `gbifTaxonKey`:
+//!
+//! ```rust, ignore
+//! use mdd_api::enrich::gbif::{GbifBackbone, GbifEnrichment};
+//!
+//! let backbone = GbifBackbone::from_path(Path::new("backbone/Taxon.tsv"))?;
+//! let enrichment = GbifEnrichment::build(&species, &backbone)?;
+//! std::fs::write("gbif_matches.json", enrichment.to_json())?;
+//! ```
+//!
+//! The backbone matcher only finds exact names; it reports a confidence of
+//! 100 for accepted names and 95 for names GBIF lists as synonyms.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{self, Read},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{http_client, send_with_retry, EnrichError, Failures, LookupFailure, DEFAULT_BACKOFF};
+use crate::{helper::normalize_name, parser::mdd::MddData};
+
+const GBIF_API: &str = "https://api.gbif.org/v1";
+const KINGDOM: &str = "Animalia";
+const CLASS: &str = "Mammalia";
+const SPECIES_RANK: &str = "species";
+const ACCEPTED_STATUS: &str = "ACCEPTED";
+/// Delay between API requests.
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+const EXACT_CONFIDENCE: u8 = 100;
+const SYNONYM_CONFIDENCE: u8 = 95;
+
+/// How the name was matched, as reported by GBIF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GbifMatchType {
+    Exact,
+    Fuzzy,
+    /// Only a higher taxon (usually the genus) matched.
+    HigherRank,
+    None,
+}
+
+impl GbifMatchType {
+    fn parse(value: &str) -> Self {
+        match value {
+            "EXACT" => Self::Exact,
+            "FUZZY" => Self::Fuzzy,
+            "HIGHERRANK" => Self::HigherRank,
+            _ => Self::None,
+        }
+    }
+}
+
+/// GBIF Backbone match of one species.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GbifMatch {
+    pub mdd_id: u32,
+    pub sci_name: String,
+    /// Key of the matched backbone name.
+    pub gbif_taxon_key: Option<u64>,
+    /// Key of the accepted taxon; differs from `gbif_taxon_key` when GBIF
+    /// treats the name as a synonym.
+    pub gbif_accepted_taxon_key: Option<u64>,
+    pub gbif_scientific_name: Option<String>,
+    /// Backbone taxonomic status (`ACCEPTED`, `SYNONYM`, `DOUBTFUL`, ...).
+    pub gbif_status: Option<String>,
+    pub match_type: GbifMatchType,
+    /// GBIF match confidence (0-100).
+    pub confidence: Option<u8>,
+}
+
+impl GbifMatch {
+    /// An unmatched species.
+    pub fn none(species: &MddData) -> Self {
+        Self {
+            mdd_id: species.id,
+            sci_name: species.sci_name.clone(),
+            gbif_taxon_key: None,
+            gbif_accepted_taxon_key: None,
+            gbif_scientific_name: None,
+            gbif_status: None,
+            match_type: GbifMatchType::None,
+            confidence: None,
+        }
+    }
+
+    /// Read a `species/match` API response.
+    pub fn from_response(species: &MddData, response: &Value) -> Self {
+        let match_type = GbifMatchType::parse(response["matchType"].as_str().unwrap_or_default());
+        let usage_key = response["usageKey"].as_u64();
+        let status = response["status"].as_str().map(|s| s.to_string());
+        Self {
+            gbif_taxon_key: usage_key,
+            gbif_accepted_taxon_key: response["acceptedUsageKey"].as_u64().or(usage_key),
+            gbif_scientific_name: response["scientificName"].as_str().map(|s| s.to_string()),
+            gbif_status: status,
+            match_type,
+            confidence: response["confidence"]
+                .as_u64()
+                .and_then(|c| u8::try_from(c).ok()),
+            ..Self::none(species)
+        }
+    }
+}
+
+/// Something that matches species against the GBIF Backbone.
+pub trait GbifSource {
+    /// Short name of the source, recorded in the sidecar.
+    fn name(&self) -> &'static str;
+
+    /// Match one species.
+    fn lookup(&self, species: &MddData) -> Result<GbifMatch, EnrichError>;
+}
+
+/// Blocking client for the GBIF species-match API.
+pub struct GbifClient {
+    client: reqwest::blocking::Client,
+    base_url: String,
+    delay: Duration,
+    backoff: Duration,
+}
+
+impl GbifClient {
+    pub fn new() -> Result<Self, EnrichError> {
+        Ok(Self {
+            client: http_client()?,
+            base_url: GBIF_API.to_string(),
+            delay: DEFAULT_DELAY,
+            backoff: DEFAULT_BACKOFF,
+        })
+    }
+
+    /// Query another API root, e.g. a mirror or a test server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Wait `delay` after every request.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Wait `backoff` before retrying a rate-limited or failed request,
+    /// doubling it for each further retry.
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+}
+
+impl GbifSource for GbifClient {
+    fn name(&self) -> &'static str {
+        "api"
+    }
+
+    fn lookup(&self, species: &MddData) -> Result<GbifMatch, EnrichError> {
+        let name = format!("{} {}", species.genus, species.specific_epithet);
+        let url = format!("{}/species/match", self.base_url);
+        let response: Value = send_with_retry(
+            || {
+                self.client.get(&url).query(&[
+                    ("name", name.as_str()),
+                    ("kingdom", KINGDOM),
+                    ("class", CLASS),
+                    ("rank", SPECIES_RANK),
+                ])
+            },
+            self.backoff,
+        )?
+        .error_for_status()?
+        .json()?;
+        thread::sleep(self.delay);
+        if !response.is_object() {
+            return Err(EnrichError::UnexpectedResponse(
+                "match is not an object".to_string(),
+            ));
+        }
+        Ok(GbifMatch::from_response(species, &response))
+    }
+}
+
+/// A species-rank name from the backbone `Taxon.tsv`.
+#[derive(Debug, Clone)]
+struct BackboneName {
+    key: u64,
+    accepted_key: Option<u64>,
+    scientific_name: String,
+    status: String,
+}
+
+/// Mammal species of a GBIF Backbone dump, indexed by canonical name.
+#[derive(Debug, Clone, Default)]
+pub struct GbifBackbone {
+    names: HashMap<String, Vec<BackboneName>>,
+}
+
+impl GbifBackbone {
+    /// Read the backbone `Taxon.tsv` (tab-separated, unquoted, with a header).
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, EnrichError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .quoting(false)
+            .flexible(true)
+            .from_reader(reader);
+        let headers = reader.headers().map_err(csv_error)?.clone();
+        let column = |name: &str| {
+            headers.iter().position(|h| h == name).ok_or_else(|| {
+                EnrichError::Io(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("backbone has no {} column", name),
+                ))
+            })
+        };
+        let key = column("taxonID")?;
+        let accepted = column("acceptedNameUsageID")?;
+        let scientific_name = column("scientificName")?;
+        let canonical_name = column("canonicalName")?;
+        let rank = column("taxonRank")?;
+        let status = column("taxonomicStatus")?;
+        let class = column("class")?;
+
+        let mut backbone = Self::default();
+        for row in reader.records() {
+            let row = row.map_err(csv_error)?;
+            let field = |index: usize| row.get(index).unwrap_or_default();
+            if field(class) != CLASS || field(rank) != SPECIES_RANK {
+                continue;
+            }
+            let Ok(taxon_key) = field(key).parse() else {
+                continue;
+            };
+            backbone
+                .names
+                .entry(normalize_name(field(canonical_name)))
+                .or_default()
+                .push(BackboneName {
+                    key: taxon_key,
+                    accepted_key: field(accepted).parse().ok(),
+                    scientific_name: field(scientific_name).to_string(),
+                    status: field(status).to_uppercase(),
+                });
+        }
+        Ok(backbone)
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self, EnrichError> {
+        Self::from_reader(fs::File::open(path).map_err(EnrichError::Io)?)
+    }
+
+    /// Number of indexed names.
+    pub fn len(&self) -> usize {
+        self.names.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+impl GbifSource for GbifBackbone {
+    fn name(&self) -> &'static str {
+        "backbone"
+    }
+
+    fn lookup(&self, species: &MddData) -> Result<GbifMatch, EnrichError> {
+        let name = format!("{} {}", species.genus, species.specific_epithet);
+        let Some(candidates) = self.names.get(&normalize_name(&name)) else {
+            return Ok(GbifMatch::none(species));
+        };
+        let best = candidates
+            .iter()
+            .find(|candidate| candidate.status == ACCEPTED_STATUS)
+            .unwrap_or(&candidates[0]);
+        let accepted = best.status == ACCEPTED_STATUS;
+        Ok(GbifMatch {
+            gbif_taxon_key: Some(best.key),
+            gbif_accepted_taxon_key: if accepted {
+                Some(best.key)
+            } else {
+                best.accepted_key
+            },
+            gbif_scientific_name: Some(best.scientific_name.clone()),
+            gbif_status: Some(best.status.clone()),
+            match_type: GbifMatchType::Exact,
+            confidence: Some(if accepted {
+                EXACT_CONFIDENCE
+            } else {
+                SYNONYM_CONFIDENCE
+            }),
+            ..GbifMatch::none(species)
+        })
+    }
+}
+
+/// Sidecar of GBIF matches for a set of species.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GbifEnrichment {
+    /// `api` or `backbone`.
+    pub source: String,
+    /// Number of species per match type.
+    pub counts: BTreeMap<GbifMatchType, usize>,
+    pub matches: Vec<GbifMatch>,
+    /// Species whose lookup failed after retrying; they have no match.
+    #[serde(default)]
+    pub failures: Vec<LookupFailure>,
+}
+
+impl GbifEnrichment {
+    /// Match every species with `source`. Failed lookups are recorded and
+    /// skipped; the run stops only when too many fail in a row.
+    pub fn build(species: &[MddData], source: &impl GbifSource) -> Result<Self, EnrichError> {
+        let mut enrichment = Self {
+            source: source.name().to_string(),
+            ..Self::default()
+        };
+        let mut failures = Failures::default();
+        for record in species {
+            log::debug!("Matching {} in GBIF", record.sci_name);
+            match source.lookup(record) {
+                Ok(gbif_match) => {
+                    failures.succeeded();
+                    *enrichment.counts.entry(gbif_match.match_type).or_default() += 1;
+                    enrichment.matches.push(gbif_match);
+                }
+                Err(err) => failures.record(record, err)?,
+            }
+        }
+        enrichment.failures = failures.into_vec();
+        Ok(enrichment)
+    }
+
+    /// GBIF taxon key of species `mdd_id`.
+    pub fn taxon_key(&self, mdd_id: u32) -> Option<u64> {
+        self.matches
+            .iter()
+            .find(|m| m.mdd_id == mdd_id)
+            .and_then(|m| m.gbif_taxon_key)
+    }
+
+    /// Species that did not match a species-rank name.
+    pub fn unmatched(&self) -> impl Iterator<Item = &GbifMatch> {
+        self.matches.iter().filter(|m| {
+            matches!(
+                m.match_type,
+                GbifMatchType::None | GbifMatchType::HigherRank
+            )
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).expect("Failed to serialize")
+    }
+}
+
+fn csv_error(err: csv::Error) -> EnrichError {
+    EnrichError::Io(err.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enrich::tests::{serve, species};
+
+    #[test]
+    fn test_gbif_match_from_response() {
+        let species = species();
+        let response: Value = serde_json::from_str(
+            r#"{"usageKey": 2436940, "acceptedUsageKey": 2436941,
+                "scientificName": "Bunolagus monticularis (Thomas, 1903)",
+                "status": "SYNONYM", "confidence": 98, "matchType": "FUZZY"}"#,
+        )
+        .unwrap();
+        let gbif_match = GbifMatch::from_response(&species[0], &response);
+        assert_eq!(gbif_match.mdd_id, species[0].id);
+        assert_eq!(gbif_match.gbif_taxon_key, Some(2436940));
+        assert_eq!(gbif_match.gbif_accepted_taxon_key, Some(2436941));
+        assert_eq!(gbif_match.match_type, GbifMatchType::Fuzzy);
+        assert_eq!(gbif_match.confidence, Some(98));
+
+        let none: Value =
+            serde_json::from_str(r#"{"matchType": "NONE", "confidence": 100}"#).unwrap();
+        let gbif_match = GbifMatch::from_response(&species[0], &none);
+        assert_eq!(gbif_match.match_type, GbifMatchType::None);
+        assert!(gbif_match.gbif_taxon_key.is_none());
+    }
+
+    #[test]
+    fn test_gbif_backbone() {
+        let species = species();
+        let taxon_tsv = format!(
+            "taxonID\tacceptedNameUsageID\tscientificName\tcanonicalName\ttaxonRank\ttaxonomicStatus\tkingdom\tclass\n\
+             1\t\t{name} (Thomas, 1903)\t{name}\tspecies\taccepted\tAnimalia\tMammalia\n\
+             2\t3\t{other} Lesson, 1827\t{other}\tspecies\tsynonym\tAnimalia\tMammalia\n\
+             3\t\t\"Quoted\" name\tPlantago major\tspecies\taccepted\tPlantae\tMagnoliopsida\n",
+            name = species[0].sci_name.replace('_', " "),
+            other = species[1].sci_name.replace('_', " "),
+        );
+        let backbone = GbifBackbone::from_reader(taxon_tsv.as_bytes()).unwrap();
+        assert_eq!(backbone.len(), 2);
+
+        let enrichment = GbifEnrichment::build(&species, &backbone).unwrap();
+        assert_eq!(enrichment.source, "backbone");
+        assert_eq!(enrichment.taxon_key(species[0].id), Some(1));
+        assert_eq!(enrichment.matches[0].confidence, Some(100));
+        assert_eq!(enrichment.matches[1].gbif_accepted_taxon_key, Some(3));
+        assert_eq!(enrichment.matches[1].confidence, Some(95));
+        assert_eq!(enrichment.unmatched().count(), 1);
+        assert_eq!(enrichment.counts[&GbifMatchType::Exact], 2);
+
+        let json: Value = serde_json::from_str(&enrichment.to_json()).unwrap();
+        assert_eq!(json["matches"][0]["gbifTaxonKey"], 1);
+        assert!(GbifBackbone::from_reader("taxonID\n1\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_build_records_failures() {
+        let species = species();
+        let exact = r#"{"usageKey": 1, "matchType": "EXACT", "confidence": 99}"#;
+        let url = serve(vec![
+            (429, ""),
+            (200, exact),
+            (500, ""),
+            (500, ""),
+            (500, ""),
+            (500, ""),
+            (200, exact),
+        ]);
+        let client = GbifClient::new()
+            .unwrap()
+            .with_base_url(url)
+            .with_delay(Duration::ZERO)
+            .with_backoff(Duration::ZERO);
+        let enrichment = GbifEnrichment::build(&species, &client).unwrap();
+        assert_eq!(enrichment.matches.len(), 2);
+        assert_eq!(enrichment.counts[&GbifMatchType::Exact], 2);
+        assert_eq!(enrichment.failures.len(), 1);
+        assert_eq!(enrichment.failures[0].mdd_id, species[1].id);
+    }
+}
@@ -0,0 +1,429 @@
+//! Live IUCN Red List categories.
+//!
+//! `IucnClient` queries the IUCN Red List API (v4, token required) for the
+//! latest global assessment of each species, looked up by genus and specific
+//! epithet. `IucnReport` compares the live categories with the verbatim
+//! `iucnStatus` of the CSV:
+//!
+//! | Outcome | CSV | Live |
+//! |---------|-----|------|
+//! | `Unchanged` | category | same category |
+//! | `Mismatch` | category | different category |
+//! | `Updated` | blank, `NE`, or unreadable | category |
+//! | `NotFound` | anything | no assessment |
+//! | `Failed` | anything | lookup failed after retrying |
+//!
+//! `IucnEnrichedSpecies` keeps the record as is and adds the live category
+//! as `iucnStatusLive`.
+//!
+//! ```rust, ignore
+//! use mdd_api::enrich::iucn::{IucnClient, IucnReport};
+//!
+//! let client = IucnClient::new(token)?;
+//! let live = client.assessments(&species)?;
+//! for failure in &live.failures {
+//!     eprintln!("{}: {}", failure.sci_name, failure.error);
+//! }
+//! let report = IucnReport::new(&species, &live);
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    thread,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{http_client, send_with_retry, EnrichError, Failures, LookupFailure, DEFAULT_BACKOFF};
+use crate::{helper::iucn::IucnStatus, parser::mdd::MddData};
+
+const IUCN_API: &str = "https://api.iucnredlist.org/api/v4";
+/// Scope code of global assessments.
+const GLOBAL_SCOPE: &str = "1";
+/// Delay between requests, keeping well under the API rate limit.
+const DEFAULT_DELAY: Duration = Duration::from_millis(500);
+/// Prefix of the 1994 Lower Risk subcategories (`LR/lc`, `LR/nt`, `LR/cd`).
+const LOWER_RISK_PREFIX: &str = "LR/";
+
+/// Latest assessment of a species on the Red List.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveAssessment {
+    pub category: IucnStatus,
+    pub year_published: Option<u16>,
+    pub assessment_id: Option<u64>,
+    pub sis_taxon_id: Option<u64>,
+}
+
+impl LiveAssessment {
+    /// Pick the latest global assessment from a `taxa/scientific_name`
+    /// response; `None` when the species has no readable assessment.
+    pub fn from_response(response: &Value) -> Option<Self> {
+        let assessments = response["assessments"].as_array()?;
+        let is_global = |assessment: &&Value| {
+            assessment["scopes"].as_array().is_none_or(|scopes| {
+                scopes
+                    .iter()
+                    .any(|scope| scope["code"].as_str() == Some(GLOBAL_SCOPE))
+            })
+        };
+        let latest = assessments
+            .iter()
+            .filter(is_global)
+            .find(|assessment| assessment["latest"].as_bool() == Some(true))
+            .or_else(|| {
+                assessments
+                    .iter()
+                    .filter(is_global)
+                    .max_by_key(|assessment| year_published(assessment))
+            })?;
+        Some(Self {
+            category: parse_category(latest["red_list_category_code"].as_str()?)?,
+            year_published: year_published(latest),
+            assessment_id: latest["assessment_id"].as_u64(),
+            sis_taxon_id: latest["sis_taxon_id"].as_u64(),
+        })
+    }
+}
+
+/// Assessments of a set of species by MDD id.
+#[derive(Debug, Clone, Default)]
+pub struct LiveAssessments {
+    /// Species the API does not know are left out.
+    pub assessments: HashMap<u32, LiveAssessment>,
+    /// Species whose lookup failed after retrying.
+    pub failures: Vec<LookupFailure>,
+}
+
+impl LiveAssessments {
+    pub fn get(&self, mdd_id: u32) -> Option<&LiveAssessment> {
+        self.assessments.get(&mdd_id)
+    }
+}
+
+/// Blocking client for the IUCN Red List API.
+pub struct IucnClient {
+    client: reqwest::blocking::Client,
+    token: String,
+    base_url: String,
+    delay: Duration,
+    backoff: Duration,
+}
+
+impl IucnClient {
+    /// Create a client authenticating with the API `token`.
+    pub fn new(token: impl Into<String>) -> Result<Self, EnrichError> {
+        Ok(Self {
+            client: http_client()?,
+            token: token.into(),
+            base_url: IUCN_API.to_string(),
+            delay: DEFAULT_DELAY,
+            backoff: DEFAULT_BACKOFF,
+        })
+    }
+
+    /// Query another API root, e.g. a mirror or a test server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Wait `delay` between requests in `assessments`.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Wait `backoff` before retrying a rate-limited or failed request,
+    /// doubling it for each further retry.
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Latest global assessment of `genus epithet`; `None` when the API does
+    /// not know the species.
+    pub fn assessment(
+        &self,
+        genus: &str,
+        epithet: &str,
+    ) -> Result<Option<LiveAssessment>, EnrichError> {
+        let url = format!("{}/taxa/scientific_name", self.base_url);
+        let response = send_with_retry(
+            || {
+                self.client
+                    .get(&url)
+                    .query(&[("genus_name", genus), ("species_name", epithet)])
+                    .bearer_auth(&self.token)
+            },
+            self.backoff,
+        )?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response: Value = response.error_for_status()?.json()?;
+        if !response["assessments"].is_array() {
+            return Err(EnrichError::UnexpectedResponse(
+                "missing assessments".to_string(),
+            ));
+        }
+        Ok(LiveAssessment::from_response(&response))
+    }
+
+    /// Assessments of every species. Failed lookups are recorded and
+    /// skipped; the run stops only when the token is rejected or too many
+    /// lookups fail in a row.
+    pub fn assessments(&self, species: &[MddData]) -> Result<LiveAssessments, EnrichError> {
+        let mut live = LiveAssessments::default();
+        let mut failures = Failures::default();
+        for (i, record) in species.iter().enumerate() {
+            if i > 0 {
+                thread::sleep(self.delay);
+            }
+            log::debug!("Querying IUCN for {}", record.sci_name);
+            match self.assessment(&record.genus, &record.specific_epithet) {
+                Ok(assessment) => {
+                    failures.succeeded();
+                    if let Some(assessment) = assessment {
+                        live.assessments.insert(record.id, assessment);
+                    }
+                }
+                Err(err) => failures.record(record, err)?,
+            }
+        }
+        live.failures = failures.into_vec();
+        Ok(live)
+    }
+}
+
+/// How the live category relates to `iucnStatus`, see the module docs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IucnOutcome {
+    Unchanged,
+    Mismatch,
+    Updated,
+    NotFound,
+    Failed,
+}
+
+/// Comparison of one species.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IucnComparison {
+    pub species_id: u32,
+    pub sci_name: String,
+    /// Verbatim `iucnStatus` of the CSV.
+    pub iucn_status: String,
+    pub iucn_status_live: Option<LiveAssessment>,
+    pub outcome: IucnOutcome,
+}
+
+/// Live categories compared with the CSV.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IucnReport {
+    pub total: usize,
+    /// Number of species per outcome.
+    pub counts: BTreeMap<IucnOutcome, usize>,
+    /// Species whose outcome is not `Unchanged`.
+    pub changes: Vec<IucnComparison>,
+    /// Why the `Failed` lookups failed.
+    pub failures: Vec<LookupFailure>,
+}
+
+impl IucnReport {
+    /// Compare `species` with the `live` assessments.
+    pub fn new(species: &[MddData], live: &LiveAssessments) -> Self {
+        let mut report = Self {
+            total: species.len(),
+            failures: live.failures.clone(),
+            ..Self::default()
+        };
+        let failed: HashSet<u32> = live.failures.iter().map(|f| f.mdd_id).collect();
+        for record in species {
+            let assessment = live.get(record.id);
+            let outcome = if failed.contains(&record.id) {
+                IucnOutcome::Failed
+            } else {
+                compare(&record.iucn_status, assessment)
+            };
+            *report.counts.entry(outcome).or_default() += 1;
+            if outcome != IucnOutcome::Unchanged {
+                report.changes.push(IucnComparison {
+                    species_id: record.id,
+                    sci_name: record.sci_name.clone(),
+                    iucn_status: record.iucn_status.clone(),
+                    iucn_status_live: assessment.cloned(),
+                    outcome,
+                });
+            }
+        }
+        report
+    }
+
+    /// Number of species with `outcome`.
+    pub fn count(&self, outcome: IucnOutcome) -> usize {
+        self.counts.get(&outcome).copied().unwrap_or_default()
+    }
+
+    /// Species whose CSV category differs from the live one.
+    pub fn mismatches(&self) -> impl Iterator<Item = &IucnComparison> {
+        self.changes
+            .iter()
+            .filter(|change| change.outcome == IucnOutcome::Mismatch)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).expect("Failed to serialize")
+    }
+}
+
+/// A species record with its live Red List category.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IucnEnrichedSpecies {
+    #[serde(flatten)]
+    pub species: MddData,
+    pub iucn_status_live: Option<IucnStatus>,
+    pub iucn_assessment_year: Option<u16>,
+}
+
+impl IucnEnrichedSpecies {
+    /// Add the live categories to `species`, keeping `iucnStatus` verbatim.
+    pub fn from_species(species: &[MddData], live: &LiveAssessments) -> Vec<Self> {
+        species
+            .iter()
+            .map(|record| {
+                let assessment = live.get(record.id);
+                Self {
+                    species: record.clone(),
+                    iucn_status_live: assessment.map(|a| a.category.clone()),
+                    iucn_assessment_year: assessment.and_then(|a| a.year_published),
+                }
+            })
+            .collect()
+    }
+}
+
+fn compare(iucn_status: &str, live: Option<&LiveAssessment>) -> IucnOutcome {
+    let live = match live {
+        Some(live) => &live.category,
+        None => return IucnOutcome::NotFound,
+    };
+    match iucn_status.parse::<IucnStatus>() {
+        Ok(status) if &status == live => IucnOutcome::Unchanged,
+        Ok(IucnStatus::NE) | Err(_) => IucnOutcome::Updated,
+        Ok(_) => IucnOutcome::Mismatch,
+    }
+}
+
+/// Parse a category code, mapping `LR/lc` to `LC` and the other Lower Risk
+/// subcategories to `NT`.
+fn parse_category(code: &str) -> Option<IucnStatus> {
+    match code.strip_prefix(LOWER_RISK_PREFIX) {
+        Some(subcategory) if subcategory.eq_ignore_ascii_case("lc") => Some(IucnStatus::LC),
+        Some(_) => Some(IucnStatus::NT),
+        None => code.parse().ok(),
+    }
+}
+
+fn year_published(assessment: &Value) -> Option<u16> {
+    match &assessment["year_published"] {
+        Value::String(year) => year.trim().parse().ok(),
+        year => year.as_u64().and_then(|year| u16::try_from(year).ok()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enrich::tests::{serve, species};
+
+    #[test]
+    fn test_live_assessment_from_response() {
+        let response: Value = serde_json::from_str(
+            r#"{
+                "taxon": {"sis_id": 4089},
+                "assessments": [
+                    {"year_published": "2008", "latest": false, "red_list_category_code": "EN",
+                     "assessment_id": 1, "sis_taxon_id": 4089, "scopes": [{"code": "1"}]},
+                    {"year_published": "2019", "latest": true, "red_list_category_code": "CR",
+                     "assessment_id": 2, "sis_taxon_id": 4089, "scopes": [{"code": "1"}]},
+                    {"year_published": "2021", "latest": true, "red_list_category_code": "LC",
+                     "assessment_id": 3, "sis_taxon_id": 4089, "scopes": [{"code": "2"}]}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let assessment = LiveAssessment::from_response(&response).unwrap();
+        assert_eq!(assessment.category, IucnStatus::CR);
+        assert_eq!(assessment.year_published, Some(2019));
+        assert_eq!(assessment.assessment_id, Some(2));
+        assert_eq!(parse_category("LR/cd"), Some(IucnStatus::NT));
+        assert!(LiveAssessment::from_response(&serde_json::json!({"assessments": []})).is_none());
+    }
+
+    #[test]
+    fn test_iucn_report() {
+        let mut species = species();
+        species[2].iucn_status = "NE".to_string();
+        let assessment = |category| LiveAssessment {
+            category,
+            year_published: Some(2024),
+            assessment_id: None,
+            sis_taxon_id: None,
+        };
+        let mut live = LiveAssessments {
+            assessments: HashMap::from([
+                (species[0].id, assessment(IucnStatus::EN)),
+                (species[2].id, assessment(IucnStatus::LC)),
+            ]),
+            failures: Vec::new(),
+        };
+        let report = IucnReport::new(&species, &live);
+        assert_eq!(report.total, 3);
+        assert_eq!(report.count(IucnOutcome::Mismatch), 1);
+        assert_eq!(report.count(IucnOutcome::NotFound), 1);
+        assert_eq!(report.count(IucnOutcome::Updated), 1);
+
+        live.failures.push(LookupFailure {
+            mdd_id: species[1].id,
+            sci_name: species[1].sci_name.clone(),
+            error: "HTTP error".to_string(),
+        });
+        let report = IucnReport::new(&species, &live);
+        assert_eq!(report.count(IucnOutcome::NotFound), 0);
+        assert_eq!(report.count(IucnOutcome::Failed), 1);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.mismatches().next().unwrap().iucn_status, "CR");
+
+        let enriched = IucnEnrichedSpecies::from_species(&species, &live);
+        let json = serde_json::to_value(&enriched[0]).unwrap();
+        assert_eq!(json["iucnStatus"], "CR");
+        assert_eq!(json["iucnStatusLive"], "EN");
+        assert_eq!(json["sciName"], "Bunolagus_monticularis");
+    }
+
+    #[test]
+    fn test_assessments_record_failures() {
+        let found = r#"{"assessments": [{"year_published": "2019", "latest": true,
+            "red_list_category_code": "CR", "scopes": [{"code": "1"}]}]}"#;
+        let url = serve(vec![(503, ""), (200, found), (404, ""), (400, "")]);
+        let client = IucnClient::new("token")
+            .unwrap()
+            .with_base_url(url)
+            .with_delay(Duration::ZERO)
+            .with_backoff(Duration::ZERO);
+        let live = client.assessments(&species()).unwrap();
+        assert_eq!(live.assessments.len(), 1);
+        assert_eq!(live.failures.len(), 1);
+        assert_eq!(live.failures[0].mdd_id, species()[2].id);
+
+        let url = serve(vec![(401, "")]);
+        let client = IucnClient::new("token").unwrap().with_base_url(url);
+        assert!(client.assessments(&species()).is_err());
+    }
+}
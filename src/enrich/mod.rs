@@ -0,0 +1,275 @@
+//! Enrichment from external services (enabled with the `enrich` feature).
+//!
+//! Enrichers look species up in an external database and report where it
+//! disagrees with the release. The verbatim MDD fields are never changed;
+//! live values are added next to them in separate records.
+//!
+//! * `iucn` – current Red List categories from the IUCN Red List API,
+//!   compared with `iucnStatus`.
+//! * `gbif` – GBIF Backbone taxon keys from the species-match API or an
+//!   offline backbone dump, written as a sidecar JSON.
+//! * `wikidata` – Wikidata QIDs and Wikipedia article URLs from the Wikidata
+//!   Query Service or a JSON dump, written as a sidecar JSON.
+//!
+//! Requests that hit the rate limit (429), a server error (5xx), or a
+//! connection failure are retried with exponential backoff. A species whose
+//! lookup still fails is recorded as a `LookupFailure` and the run goes on,
+//! unless the service rejects the credentials or too many lookups fail in a
+//! row.
+
+use std::{fmt, thread, time::Duration};
+
+use reqwest::{
+    blocking::{RequestBuilder, Response},
+    header::RETRY_AFTER,
+    StatusCode,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::parser::mdd::MddData;
+
+pub mod gbif;
+pub mod iucn;
+pub mod wikidata;
+
+const USER_AGENT: &str = concat!("mdd_api/", env!("CARGO_PKG_VERSION"));
+/// Attempts per request, including the first one.
+const MAX_ATTEMPTS: u32 = 4;
+/// Wait before the first retry, doubled for each further retry.
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(2);
+/// Failed lookups in a row after which the service is considered down.
+const MAX_CONSECUTIVE_FAILURES: usize = 10;
+
+/// Errors produced while querying an external service.
+#[derive(Debug)]
+pub enum EnrichError {
+    Http(Box<reqwest::Error>),
+    Io(std::io::Error),
+    /// The API response did not have the expected shape.
+    UnexpectedResponse(String),
+    /// Not a Wikipedia language code such as `en` or `zh-yue`.
+    InvalidLanguage(String),
+}
+
+impl fmt::Display for EnrichError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "HTTP error: {}", e),
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::UnexpectedResponse(msg) => write!(f, "Unexpected API response: {}", msg),
+            Self::InvalidLanguage(language) => {
+                write!(f, "Invalid Wikipedia language code: {:?}", language)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnrichError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Http(e) => Some(e.as_ref()),
+            Self::Io(e) => Some(e),
+            Self::UnexpectedResponse(_) | Self::InvalidLanguage(_) => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for EnrichError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Http(Box::new(err))
+    }
+}
+
+impl EnrichError {
+    /// Whether the service rejected the credentials, so that every further
+    /// request would fail too.
+    fn is_unauthorized(&self) -> bool {
+        match self {
+            Self::Http(e) => matches!(
+                e.status(),
+                Some(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN)
+            ),
+            _ => false,
+        }
+    }
+}
+
+/// A species whose lookup failed after retrying.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LookupFailure {
+    pub mdd_id: u32,
+    pub sci_name: String,
+    pub error: String,
+}
+
+/// Failed lookups of a run.
+#[derive(Debug, Default)]
+struct Failures {
+    failures: Vec<LookupFailure>,
+    consecutive: usize,
+}
+
+impl Failures {
+    /// Record the failed lookup of `species`; gives the error back when the
+    /// run should stop.
+    fn record(&mut self, species: &MddData, err: EnrichError) -> Result<(), EnrichError> {
+        self.consecutive += 1;
+        if err.is_unauthorized() || self.consecutive >= MAX_CONSECUTIVE_FAILURES {
+            return Err(err);
+        }
+        log::warn!("Failed to look up {}: {}", species.sci_name, err);
+        self.failures.push(LookupFailure {
+            mdd_id: species.id,
+            sci_name: species.sci_name.clone(),
+            error: err.to_string(),
+        });
+        Ok(())
+    }
+
+    fn succeeded(&mut self) {
+        self.consecutive = 0;
+    }
+
+    fn into_vec(self) -> Vec<LookupFailure> {
+        self.failures
+    }
+}
+
+/// Send the request built by `request`, retrying rate limits, server
+/// errors, and connection failures. The wait starts at `backoff` and
+/// doubles, unless the response asks for a `Retry-After` delay. The last
+/// response is returned as is, so callers still check its status.
+fn send_with_retry(
+    request: impl Fn() -> RequestBuilder,
+    backoff: Duration,
+) -> Result<Response, EnrichError> {
+    let mut wait = backoff;
+    let mut attempt = 1;
+    loop {
+        let result = request().send();
+        let retry_after = match &result {
+            Ok(response) if is_transient(response.status()) => {
+                Some(retry_after(response).unwrap_or(wait))
+            }
+            Err(e) if e.is_timeout() || e.is_connect() => Some(wait),
+            _ => None,
+        };
+        match retry_after {
+            Some(delay) if attempt < MAX_ATTEMPTS => {
+                log::debug!(
+                    "Retrying in {:?} (attempt {}/{})",
+                    delay,
+                    attempt + 1,
+                    MAX_ATTEMPTS
+                );
+                thread::sleep(delay);
+                wait *= 2;
+                attempt += 1;
+            }
+            _ => return Ok(result?),
+        }
+    }
+}
+
+fn is_transient(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Delay of a `Retry-After: <seconds>` header.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let seconds = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    seconds.trim().parse().ok().map(Duration::from_secs)
+}
+
+fn http_client() -> Result<reqwest::blocking::Client, EnrichError> {
+    Ok(reqwest::blocking::Client::builder()
+        .user_agent(USER_AGENT)
+        .build()?)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// Serve one canned HTTP response per connection, in order, and return
+    /// the base URL of the server.
+    pub(crate) fn serve(responses: Vec<(u16, &'static str)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut request_body = vec![0; content_length];
+                reader.read_exact(&mut request_body).unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Status\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+        url
+    }
+
+    /// The first three species of the test CSV.
+    pub(crate) fn species() -> Vec<MddData> {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let mut species = MddData::new().from_csv(&csv_data);
+        species.truncate(3);
+        species
+    }
+
+    #[test]
+    fn test_send_with_retry() {
+        let url = serve(vec![(429, ""), (503, ""), (200, "{}")]);
+        let client = http_client().unwrap();
+        let response = send_with_retry(|| client.get(&url), Duration::ZERO).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let statuses = vec![(500, ""); MAX_ATTEMPTS as usize];
+        let url = serve(statuses);
+        let response = send_with_retry(|| client.get(&url), Duration::ZERO).unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_failures() {
+        let species = species();
+        let mut failures = Failures::default();
+        let err = || EnrichError::UnexpectedResponse("bad".to_string());
+        failures.record(&species[0], err()).unwrap();
+        failures.succeeded();
+        for _ in 1..MAX_CONSECUTIVE_FAILURES {
+            failures.record(&species[1], err()).unwrap();
+        }
+        assert!(failures.record(&species[1], err()).is_err());
+        let failures = failures.into_vec();
+        assert_eq!(failures.len(), MAX_CONSECUTIVE_FAILURES);
+        assert_eq!(failures[0].mdd_id, species[0].id);
+        assert_eq!(failures[0].error, "Unexpected API response: bad");
+    }
+}
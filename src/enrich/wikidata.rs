@@ -0,0 +1,512 @@
+//! Wikidata items and Wikipedia articles.
+//!
+//! Species are resolved to Wikidata items by their taxon name (`P225`) at
+//! species rank (`P105` = `Q7432`), either online through the Wikidata Query
+//! Service (`WikidataClient`) or offline from a Wikidata JSON dump
+//! (`WikidataDump`). Each item brings the article of one Wikipedia language
+//! edition, if there is one. `WikidataLinks` collects one `WikiLink` per
+//! species and is written as a sidecar JSON, so the website can show
+//! encyclopedia links without matching names itself:
+//!
+//! ```rust, ignore
+//! use mdd_api::enrich::wikidata::{WikidataClient, WikidataLinks};
+//!
+//! let client = WikidataClient::new()?.with_language("en")?;
+//! let links = WikidataLinks::build(&species, &client)?;
+//! std::fs::write("wikidata_links.json", links.to_json())?;
+//! ```
+//!
+//! When several items carry the same taxon name, the one with an article is
+//! kept, then the lowest QID.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{http_client, send_with_retry, EnrichError, DEFAULT_BACKOFF};
+use crate::{helper::normalize_name, parser::mdd::MddData};
+
+const SPARQL_ENDPOINT: &str = "https://query.wikidata.org/sparql";
+const ENTITY_PREFIX: &str = "http://www.wikidata.org/entity/";
+const TAXON_NAME: &str = "P225";
+const TAXON_RANK: &str = "P105";
+const SPECIES_RANK: &str = "Q7432";
+const DEFAULT_LANGUAGE: &str = "en";
+/// Names per SPARQL query.
+const DEFAULT_BATCH_SIZE: usize = 200;
+/// Delay between queries.
+const DEFAULT_DELAY: Duration = Duration::from_millis(1000);
+
+lazy_static::lazy_static! {
+    // Wikipedia language codes: `en`, `ast`, `zh-yue`, `be-tarask`, ...
+    static ref LANGUAGE_REGEX: Regex = Regex::new(r"^[a-z]{2,3}(-[A-Za-z0-9]+)*$").expect("Failed to compile language regex");
+}
+
+/// Wikidata item of a species and its Wikipedia article.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WikiLink {
+    pub mdd_id: u32,
+    pub sci_name: String,
+    /// QID, e.g. `Q1365834`.
+    pub wikidata_id: Option<String>,
+    pub wikipedia_url: Option<String>,
+}
+
+/// A resolved taxon name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WikidataItem {
+    pub qid: String,
+    pub wikipedia_url: Option<String>,
+}
+
+impl WikidataItem {
+    /// Whether `self` should replace `other` for the same name.
+    fn is_preferred_over(&self, other: &Self) -> bool {
+        match (&self.wikipedia_url, &other.wikipedia_url) {
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            _ => qid_number(&self.qid) < qid_number(&other.qid),
+        }
+    }
+}
+
+/// Something that resolves taxon names to Wikidata items.
+pub trait WikidataSource {
+    /// Short name of the source, recorded in the sidecar.
+    fn name(&self) -> &'static str;
+
+    /// Wikipedia language edition of the article URLs.
+    fn language(&self) -> &str;
+
+    /// Resolve binomial names; the result is keyed by the normalized name
+    /// and omits names without an item.
+    fn resolve(&self, names: &[String]) -> Result<HashMap<String, WikidataItem>, EnrichError>;
+}
+
+/// Blocking client for the Wikidata Query Service.
+pub struct WikidataClient {
+    client: reqwest::blocking::Client,
+    endpoint: String,
+    language: String,
+    batch_size: usize,
+    delay: Duration,
+    backoff: Duration,
+}
+
+impl WikidataClient {
+    pub fn new() -> Result<Self, EnrichError> {
+        Ok(Self {
+            client: http_client()?,
+            endpoint: SPARQL_ENDPOINT.to_string(),
+            language: DEFAULT_LANGUAGE.to_string(),
+            batch_size: DEFAULT_BATCH_SIZE,
+            delay: DEFAULT_DELAY,
+            backoff: DEFAULT_BACKOFF,
+        })
+    }
+
+    /// Query another SPARQL endpoint, e.g. a mirror or a test server.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Link articles of another Wikipedia edition, e.g. `de`. The code is
+    /// checked because it is written into the SPARQL query.
+    pub fn with_language(mut self, language: impl Into<String>) -> Result<Self, EnrichError> {
+        self.language = check_language(&language.into())?.to_string();
+        Ok(self)
+    }
+
+    /// Send at most `batch_size` names per query.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Wait `delay` after every query.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Wait `backoff` before retrying a rate-limited or failed query,
+    /// doubling it for each further retry.
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+}
+
+impl WikidataSource for WikidataClient {
+    fn name(&self) -> &'static str {
+        "sparql"
+    }
+
+    fn language(&self) -> &str {
+        &self.language
+    }
+
+    fn resolve(&self, names: &[String]) -> Result<HashMap<String, WikidataItem>, EnrichError> {
+        let mut items = HashMap::new();
+        for batch in names.chunks(self.batch_size) {
+            log::debug!("Querying Wikidata for {} names", batch.len());
+            let query = sparql_query(batch, &self.language);
+            let response: Value = send_with_retry(
+                || {
+                    self.client
+                        .post(&self.endpoint)
+                        .header(reqwest::header::ACCEPT, "application/sparql-results+json")
+                        .form(&[("query", &query)])
+                },
+                self.backoff,
+            )?
+            .error_for_status()?
+            .json()?;
+            thread::sleep(self.delay);
+            for (name, item) in parse_bindings(&response)? {
+                insert_item(&mut items, name, item);
+            }
+        }
+        Ok(items)
+    }
+}
+
+/// Mammal species items of a Wikidata JSON dump.
+#[derive(Debug, Clone, Default)]
+pub struct WikidataDump {
+    language: String,
+    items: HashMap<String, WikidataItem>,
+}
+
+impl WikidataDump {
+    /// Read a `latest-all.json` style dump (a JSON array with one entity per
+    /// line), keeping species-rank items and their `language` article.
+    /// Every species-rank taxon is kept, so pre-filtering the dump to
+    /// mammals keeps memory down.
+    pub fn from_reader<R: Read>(reader: R, language: &str) -> Result<Self, EnrichError> {
+        let language = check_language(language)?;
+        let site = format!("{}wiki", language);
+        let mut dump = Self {
+            language: language.to_string(),
+            ..Self::default()
+        };
+        for line in BufReader::new(reader).lines() {
+            let line = line.map_err(EnrichError::Io)?;
+            let line = line.trim().trim_end_matches(',');
+            if line.is_empty() || line == "[" || line == "]" {
+                continue;
+            }
+            let entity: Value = serde_json::from_str(line).map_err(|e| {
+                EnrichError::UnexpectedResponse(format!("dump entity is not JSON: {}", e))
+            })?;
+            let claims = &entity["claims"];
+            let is_species = claim_values(&claims[TAXON_RANK])
+                .any(|value| value["id"].as_str() == Some(SPECIES_RANK));
+            let Some(qid) = entity["id"].as_str().filter(|_| is_species) else {
+                continue;
+            };
+            let wikipedia_url = entity["sitelinks"][&site]["title"]
+                .as_str()
+                .and_then(|title| article_url(language, title));
+            for name in claim_values(&claims[TAXON_NAME]).filter_map(Value::as_str) {
+                let item = WikidataItem {
+                    qid: qid.to_string(),
+                    wikipedia_url: wikipedia_url.clone(),
+                };
+                insert_item(&mut dump.items, normalize_name(name), item);
+            }
+        }
+        Ok(dump)
+    }
+
+    pub fn from_path(path: &Path, language: &str) -> Result<Self, EnrichError> {
+        Self::from_reader(fs::File::open(path).map_err(EnrichError::Io)?, language)
+    }
+
+    /// Number of indexed names.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl WikidataSource for WikidataDump {
+    fn name(&self) -> &'static str {
+        "dump"
+    }
+
+    fn language(&self) -> &str {
+        &self.language
+    }
+
+    fn resolve(&self, names: &[String]) -> Result<HashMap<String, WikidataItem>, EnrichError> {
+        Ok(names
+            .iter()
+            .filter_map(|name| {
+                let name = normalize_name(name);
+                let item = self.items.get(&name)?.clone();
+                Some((name, item))
+            })
+            .collect())
+    }
+}
+
+/// Sidecar of Wikidata and Wikipedia links for a set of species.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WikidataLinks {
+    /// `sparql` or `dump`.
+    pub source: String,
+    /// Wikipedia language edition of `wikipediaUrl`.
+    pub language: String,
+    pub links: Vec<WikiLink>,
+}
+
+impl WikidataLinks {
+    /// Resolve every species with `source`.
+    pub fn build(species: &[MddData], source: &impl WikidataSource) -> Result<Self, EnrichError> {
+        let names: Vec<String> = species.iter().map(binomial).collect();
+        let items = source.resolve(&names)?;
+        let links = species
+            .iter()
+            .zip(&names)
+            .map(|(record, name)| {
+                let item = items.get(&normalize_name(name));
+                WikiLink {
+                    mdd_id: record.id,
+                    sci_name: record.sci_name.clone(),
+                    wikidata_id: item.map(|item| item.qid.clone()),
+                    wikipedia_url: item.and_then(|item| item.wikipedia_url.clone()),
+                }
+            })
+            .collect();
+        Ok(Self {
+            source: source.name().to_string(),
+            language: source.language().to_string(),
+            links,
+        })
+    }
+
+    /// QID of species `mdd_id`.
+    pub fn wikidata_id(&self, mdd_id: u32) -> Option<&str> {
+        self.links
+            .iter()
+            .find(|link| link.mdd_id == mdd_id)
+            .and_then(|link| link.wikidata_id.as_deref())
+    }
+
+    /// Species without a Wikidata item.
+    pub fn unmatched(&self) -> impl Iterator<Item = &WikiLink> {
+        self.links.iter().filter(|link| link.wikidata_id.is_none())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).expect("Failed to serialize")
+    }
+}
+
+/// SPARQL query resolving `names` to species items and `language` articles.
+fn sparql_query(names: &[String], language: &str) -> String {
+    let values: Vec<String> = names
+        .iter()
+        .map(|name| format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect();
+    format!(
+        "SELECT ?item ?name ?article WHERE {{ \
+         VALUES ?name {{ {} }} \
+         ?item wdt:{} ?name ; wdt:{} wd:{} . \
+         OPTIONAL {{ ?article schema:about ?item ; \
+         schema:isPartOf <https://{}.wikipedia.org/> . }} }}",
+        values.join(" "),
+        TAXON_NAME,
+        TAXON_RANK,
+        SPECIES_RANK,
+        language
+    )
+}
+
+/// Read the bindings of a SPARQL JSON result.
+fn parse_bindings(response: &Value) -> Result<Vec<(String, WikidataItem)>, EnrichError> {
+    let bindings = response["results"]["bindings"].as_array().ok_or_else(|| {
+        EnrichError::UnexpectedResponse("SPARQL result has no bindings".to_string())
+    })?;
+    Ok(bindings
+        .iter()
+        .filter_map(|binding| {
+            let name = binding["name"]["value"].as_str()?;
+            let qid = binding["item"]["value"]
+                .as_str()?
+                .strip_prefix(ENTITY_PREFIX)?;
+            let item = WikidataItem {
+                qid: qid.to_string(),
+                wikipedia_url: binding["article"]["value"].as_str().map(|s| s.to_string()),
+            };
+            Some((normalize_name(name), item))
+        })
+        .collect())
+}
+
+fn insert_item(items: &mut HashMap<String, WikidataItem>, name: String, item: WikidataItem) {
+    match items.get(&name) {
+        Some(current) if !item.is_preferred_over(current) => {}
+        _ => {
+            items.insert(name, item);
+        }
+    }
+}
+
+/// Values of the main snaks of a claim list.
+fn claim_values(claims: &Value) -> impl Iterator<Item = &Value> {
+    claims
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|claim| &claim["mainsnak"]["datavalue"]["value"])
+}
+
+/// URL of the article `title`, percent-encoded.
+fn article_url(language: &str, title: &str) -> Option<String> {
+    let url = format!(
+        "https://{}.wikipedia.org/wiki/{}",
+        language,
+        title.replace(' ', "_")
+    );
+    reqwest::Url::parse(&url).ok().map(String::from)
+}
+
+fn check_language(language: &str) -> Result<&str, EnrichError> {
+    if LANGUAGE_REGEX.is_match(language) {
+        Ok(language)
+    } else {
+        Err(EnrichError::InvalidLanguage(language.to_string()))
+    }
+}
+
+fn binomial(species: &MddData) -> String {
+    format!("{} {}", species.genus, species.specific_epithet)
+}
+
+fn qid_number(qid: &str) -> u64 {
+    qid.trim_start_matches('Q').parse().unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enrich::tests::{serve, species};
+
+    #[test]
+    fn test_parse_bindings() {
+        let response: Value = serde_json::from_str(
+            r#"{"results": {"bindings": [
+                {"item": {"value": "http://www.wikidata.org/entity/Q1365834"},
+                 "name": {"value": "Bunolagus monticularis"},
+                 "article": {"value": "https://en.wikipedia.org/wiki/Riverine_rabbit"}},
+                {"item": {"value": "http://www.wikidata.org/entity/Q42"},
+                 "name": {"value": "Bunolagus monticularis"}}
+            ]}}"#,
+        )
+        .unwrap();
+        let mut items = HashMap::new();
+        for (name, item) in parse_bindings(&response).unwrap() {
+            insert_item(&mut items, name, item);
+        }
+        let item = &items["bunolagus monticularis"];
+        assert_eq!(item.qid, "Q1365834");
+        assert!(item.wikipedia_url.is_some());
+        assert!(parse_bindings(&Value::Null).is_err());
+
+        let query = sparql_query(&["Bunolagus monticularis".to_string()], "de");
+        assert!(query.contains("\"Bunolagus monticularis\""));
+        assert!(query.contains("<https://de.wikipedia.org/>"));
+    }
+
+    #[test]
+    fn test_wikidata_dump() {
+        let species = species();
+        let entity = |qid: &str, name: &str, rank: &str, title: Option<&str>| {
+            let sitelinks = match title {
+                Some(title) => serde_json::json!({ "enwiki": { "title": title } }),
+                None => serde_json::json!({}),
+            };
+            serde_json::json!({
+                "id": qid,
+                "claims": {
+                    "P225": [{ "mainsnak": { "datavalue": { "value": name } } }],
+                    "P105": [{ "mainsnak": { "datavalue": { "value": { "id": rank } } } }]
+                },
+                "sitelinks": sitelinks
+            })
+            .to_string()
+        };
+        let name = species[0].sci_name.replace('_', " ");
+        let other = species[1].sci_name.replace('_', " ");
+        let dump = format!(
+            "[\n{},\n{},\n{}\n]\n",
+            entity("Q1365834", &name, SPECIES_RANK, Some("Riverine rabbit")),
+            entity("Q7", &other, SPECIES_RANK, None),
+            entity("Q8", "Lepus", "Q34740", Some("Lepus")),
+        );
+        let dump = WikidataDump::from_reader(dump.as_bytes(), "en").unwrap();
+        assert_eq!(dump.len(), 2);
+
+        let links = WikidataLinks::build(&species, &dump).unwrap();
+        assert_eq!(links.source, "dump");
+        assert_eq!(links.wikidata_id(species[0].id), Some("Q1365834"));
+        assert_eq!(
+            links.links[0].wikipedia_url.as_deref(),
+            Some("https://en.wikipedia.org/wiki/Riverine_rabbit")
+        );
+        assert_eq!(links.links[1].wikidata_id.as_deref(), Some("Q7"));
+        assert!(links.links[1].wikipedia_url.is_none());
+        assert_eq!(links.unmatched().count(), 1);
+
+        let json: Value = serde_json::from_str(&links.to_json()).unwrap();
+        assert_eq!(json["links"][0]["wikidataId"], "Q1365834");
+        assert!(WikidataDump::from_reader("[\nnot json\n]".as_bytes(), "en").is_err());
+        assert!(WikidataDump::from_reader("[]".as_bytes(), "en/").is_err());
+    }
+
+    #[test]
+    fn test_check_language() {
+        for language in ["en", "ast", "zh-yue", "be-tarask", "zh-Hant"] {
+            assert!(check_language(language).is_ok(), "{}", language);
+        }
+        for language in ["", "e", "EN", "english", "en-", "en.org/> . }", "de wiki"] {
+            assert!(check_language(language).is_err(), "{}", language);
+        }
+        let client = WikidataClient::new().unwrap();
+        assert!(client.with_language("de>").is_err());
+    }
+
+    #[test]
+    fn test_resolve_retries() {
+        let bindings = r#"{"results": {"bindings": [
+            {"item": {"value": "http://www.wikidata.org/entity/Q1365834"},
+             "name": {"value": "Bunolagus monticularis"}}]}}"#;
+        let url = serve(vec![(429, ""), (502, ""), (200, bindings)]);
+        let client = WikidataClient::new()
+            .unwrap()
+            .with_endpoint(url)
+            .with_delay(Duration::ZERO)
+            .with_backoff(Duration::ZERO);
+        let items = client
+            .resolve(&["Bunolagus monticularis".to_string()])
+            .unwrap();
+        assert_eq!(items["bunolagus monticularis"].qid, "Q1365834");
+    }
+}
@@ -0,0 +1,294 @@
+//! Failures of the `mdd` CLI and how they are reported.
+//!
+//! Every subcommand returns `Result<(), CliError>`. The error kind selects
+//! the process exit code, so scripts and orchestrators can react without
+//! parsing messages:
+//!
+//! | code | kind       | e.g.                                                     |
+//! |------|------------|----------------------------------------------------------|
+//! | 1    | `other`    | network failures, checksum mismatches, server errors     |
+//! | 2    | `badInput` | malformed CSV / JSON / TOML, invalid arguments           |
+//! | 3    | `schema`   | CSV headers that do not match the MDD layout             |
+//! | 4    | `io`       | files that cannot be opened, created, or written         |
+//!
+//! Argument errors caught by clap also exit with 2. `mdd validate` keeps its
+//! own status (1 when the report has errors). A panic is a bug and exits
+//! with 101.
+//!
+//! The error is logged to stderr by default. With `--error-format json` it is
+//! printed to stderr as one JSON object instead:
+//!
+//! ```text
+//! {"error":{"kind":"schema","code":3,"message":"Failed to parse MDD CSV data: CSV header mismatch: missing columns: sciName"}}
+//! ```
+
+use std::{error::Error, fmt, io, process::ExitCode};
+
+use clap::ValueEnum;
+use mdd_api::parser::error::MddError;
+use serde::Serialize;
+
+/// Category of a CLI failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorKind {
+    Other,
+    BadInput,
+    Schema,
+    Io,
+}
+
+impl ErrorKind {
+    /// Process exit code of the kind.
+    pub fn code(self) -> u8 {
+        match self {
+            Self::Other => 1,
+            Self::BadInput => 2,
+            Self::Schema => 3,
+            Self::Io => 4,
+        }
+    }
+}
+
+/// How a failure is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ErrorFormat {
+    /// A log line.
+    #[default]
+    Text,
+    /// One JSON object on stderr.
+    Json,
+}
+
+/// A failed CLI run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CliError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl CliError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    pub fn bad_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::BadInput, message)
+    }
+
+    /// Prefix the message with what was being done.
+    pub fn context(mut self, context: impl fmt::Display) -> Self {
+        self.message = format!("{}: {}", context, self.message);
+        self
+    }
+
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::from(self.kind.code())
+    }
+
+    /// Print the error to stderr in `format`.
+    pub fn report(&self, format: ErrorFormat) {
+        match format {
+            ErrorFormat::Text => log::error!("{}", self.message),
+            ErrorFormat::Json => eprintln!("{}", self.to_json()),
+        }
+    }
+
+    /// The `--error-format json` object.
+    fn to_json(&self) -> String {
+        #[derive(Serialize)]
+        struct Report<'a> {
+            kind: ErrorKind,
+            code: u8,
+            message: &'a str,
+        }
+        let report = Report {
+            kind: self.kind,
+            code: self.kind.code(),
+            message: &self.message,
+        };
+        serde_json::json!({ "error": report }).to_string()
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for CliError {}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        Self::new(ErrorKind::Io, e.to_string())
+    }
+}
+
+impl From<MddError> for CliError {
+    fn from(e: MddError) -> Self {
+        let kind = match &e {
+            MddError::Schema(_) => ErrorKind::Schema,
+            MddError::Io(_) => ErrorKind::Io,
+            #[cfg(feature = "db")]
+            MddError::Sqlite(_) => ErrorKind::Io,
+            #[cfg(feature = "duckdb")]
+            MddError::DuckDb(_) => ErrorKind::Io,
+            _ => ErrorKind::BadInput,
+        };
+        Self::new(kind, e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for CliError {
+    fn from(e: serde_json::Error) -> Self {
+        let kind = if e.is_io() {
+            ErrorKind::Io
+        } else {
+            ErrorKind::BadInput
+        };
+        Self::new(kind, e.to_string())
+    }
+}
+
+impl From<csv::Error> for CliError {
+    fn from(e: csv::Error) -> Self {
+        let kind = if e.is_io_error() {
+            ErrorKind::Io
+        } else {
+            ErrorKind::BadInput
+        };
+        Self::new(kind, e.to_string())
+    }
+}
+
+/// Errors of the library writers, classified by their concrete type.
+impl From<Box<dyn Error>> for CliError {
+    fn from(e: Box<dyn Error>) -> Self {
+        let e = match e.downcast::<MddError>() {
+            Ok(e) => return (*e).into(),
+            Err(e) => e,
+        };
+        let e = match e.downcast::<io::Error>() {
+            Ok(e) => return (*e).into(),
+            Err(e) => e,
+        };
+        match e.downcast::<serde_json::Error>() {
+            Ok(e) => (*e).into(),
+            Err(e) => Self::new(ErrorKind::Other, e.to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "fetch")]
+impl From<mdd_api::fetch::FetchError> for CliError {
+    fn from(e: mdd_api::fetch::FetchError) -> Self {
+        use mdd_api::fetch::FetchError;
+
+        let kind = match &e {
+            FetchError::Io(_) => ErrorKind::Io,
+            FetchError::NotFound(_) => ErrorKind::BadInput,
+            _ => ErrorKind::Other,
+        };
+        Self::new(kind, e.to_string())
+    }
+}
+
+#[cfg(feature = "enrich")]
+impl From<mdd_api::enrich::EnrichError> for CliError {
+    fn from(e: mdd_api::enrich::EnrichError) -> Self {
+        use mdd_api::enrich::EnrichError;
+
+        let kind = match &e {
+            EnrichError::Io(_) => ErrorKind::Io,
+            EnrichError::InvalidLanguage(_) => ErrorKind::BadInput,
+            _ => ErrorKind::Other,
+        };
+        Self::new(kind, e.to_string())
+    }
+}
+
+#[cfg(feature = "render")]
+impl From<mdd_api::render::RenderError> for CliError {
+    fn from(e: mdd_api::render::RenderError) -> Self {
+        use mdd_api::render::RenderError;
+
+        let kind = match &e {
+            RenderError::Template(_) => ErrorKind::BadInput,
+            RenderError::Io(_) => ErrorKind::Io,
+        };
+        Self::new(kind, e.to_string())
+    }
+}
+
+/// Attach what was being done to the error of a result.
+pub trait Context<T> {
+    fn context(self, context: impl fmt::Display) -> Result<T, CliError>;
+
+    /// Like `context`, building the message only on failure.
+    fn with_context<C: fmt::Display>(self, context: impl FnOnce() -> C) -> Result<T, CliError>;
+}
+
+impl<T, E: Into<CliError>> Context<T> for Result<T, E> {
+    fn context(self, context: impl fmt::Display) -> Result<T, CliError> {
+        self.map_err(|e| e.into().context(context))
+    }
+
+    fn with_context<C: fmt::Display>(self, context: impl FnOnce() -> C) -> Result<T, CliError> {
+        self.map_err(|e| e.into().context(context()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mdd_api::parser::schema::HeaderDiff;
+
+    use super::*;
+
+    #[test]
+    fn test_exit_codes() {
+        let other: Box<dyn Error> = "network down".into();
+        let bad_json = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let schema = MddError::Schema(HeaderDiff {
+            missing: vec!["sciName".to_string()],
+            ..HeaderDiff::default()
+        });
+        let missing_file = io::Error::new(io::ErrorKind::NotFound, "no such file");
+        let cases = [
+            (CliError::from(other), ErrorKind::Other, 1),
+            (CliError::from(bad_json), ErrorKind::BadInput, 2),
+            (CliError::bad_input("bad version"), ErrorKind::BadInput, 2),
+            (CliError::from(schema), ErrorKind::Schema, 3),
+            (CliError::from(missing_file), ErrorKind::Io, 4),
+        ];
+        for (error, kind, code) in cases {
+            assert_eq!(error.kind, kind, "{}", error);
+            assert_eq!(error.kind.code(), code);
+            assert_eq!(error.exit_code(), ExitCode::from(code));
+        }
+
+        let boxed: Box<dyn Error> = Box::new(MddError::InvalidVersion("x".to_string()));
+        assert_eq!(CliError::from(boxed).kind, ErrorKind::BadInput);
+    }
+
+    #[test]
+    fn test_json_report() {
+        let error = Err::<(), _>(io::Error::other("disk full"))
+            .context("Failed to write species.json")
+            .unwrap_err();
+        let json: serde_json::Value = serde_json::from_str(&error.to_json()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "error": {
+                    "kind": "io",
+                    "code": 4,
+                    "message": "Failed to write species.json: disk full"
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! Download MDD release archives (enabled with the `fetch` feature).
+//!
+//! Releases are resolved on Zenodo, either from a DOI
+//! (`10.5281/zenodo.<record>`) or by searching for a record whose version
+//! matches, or from a GitHub release tagged `v<version>`. The first `.zip`
+//! asset is downloaded and its checksum verified (Zenodo publishes MD5,
+//! GitHub publishes SHA-256 digests) before the bytes are handed back to the
+//! caller, typically to be written to disk and parsed like a `mdd zip` input.
+
+use std::{fmt, io::Read};
+
+use md5::Md5;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::helper::checksum::to_hex;
+
+const ZENODO_API: &str = "https://zenodo.org/api/records";
+const GITHUB_API: &str = "https://api.github.com/repos";
+const ZENODO_DOI_PREFIX: &str = "10.5281/zenodo.";
+const ZIP_EXT: &str = ".zip";
+const USER_AGENT: &str = concat!("mdd_api/", env!("CARGO_PKG_VERSION"));
+
+/// Where to look for a release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReleaseSource {
+    /// Search Zenodo for a record with a matching `version`.
+    Zenodo,
+    /// A specific Zenodo DOI, e.g. `10.5281/zenodo.17033774`.
+    ZenodoDoi(String),
+    /// GitHub release tagged `v<version>` in `owner/repo`.
+    GitHub { owner: String, repo: String },
+}
+
+/// Expected digest of a downloaded file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Checksum {
+    Md5(String),
+    Sha256(String),
+}
+
+impl Checksum {
+    /// Parse `algorithm:hex` strings as used by Zenodo (`md5:...`) and GitHub (`sha256:...`).
+    pub fn parse(value: &str) -> Option<Self> {
+        let (algorithm, digest) = value.split_once(':')?;
+        let digest = digest.trim().to_ascii_lowercase();
+        match algorithm.trim().to_ascii_lowercase().as_str() {
+            "md5" => Some(Self::Md5(digest)),
+            "sha256" => Some(Self::Sha256(digest)),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` when `bytes` hash to the expected digest.
+    pub fn verify(&self, bytes: &[u8]) -> bool {
+        match self {
+            Self::Md5(expected) => to_hex(&Md5::digest(bytes)) == *expected,
+            Self::Sha256(expected) => to_hex(&Sha256::digest(bytes)) == *expected,
+        }
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Md5(digest) => write!(f, "md5:{}", digest),
+            Self::Sha256(digest) => write!(f, "sha256:{}", digest),
+        }
+    }
+}
+
+/// A downloadable release archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseAsset {
+    /// Release version as reported by the source.
+    pub version: String,
+    /// Archive file name.
+    pub file_name: String,
+    pub url: String,
+    /// Published checksum, when the source provides one.
+    pub checksum: Option<Checksum>,
+}
+
+/// Errors produced while resolving or downloading a release.
+#[derive(Debug)]
+pub enum FetchError {
+    Http(Box<reqwest::Error>),
+    Io(std::io::Error),
+    /// No release (or no zip asset) matched the request.
+    NotFound(String),
+    /// The API response did not have the expected shape.
+    UnexpectedResponse(String),
+    ChecksumMismatch {
+        file_name: String,
+        expected: Checksum,
+    },
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "HTTP error: {}", e),
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::NotFound(what) => write!(f, "Release not found: {}", what),
+            Self::UnexpectedResponse(msg) => write!(f, "Unexpected API response: {}", msg),
+            Self::ChecksumMismatch {
+                file_name,
+                expected,
+            } => write!(
+                f,
+                "Checksum mismatch for {} (expected {})",
+                file_name, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Http(e) => Some(e.as_ref()),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Http(Box::new(err))
+    }
+}
+
+impl From<std::io::Error> for FetchError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Blocking client for locating and downloading MDD releases.
+pub struct ReleaseFetcher {
+    client: reqwest::blocking::Client,
+}
+
+impl ReleaseFetcher {
+    pub fn new() -> Result<Self, FetchError> {
+        let client = reqwest::blocking::Client::builder()
+            .user_agent(USER_AGENT)
+            .build()?;
+        Ok(Self { client })
+    }
+
+    /// Resolve the zip asset for `version` from `source`.
+    pub fn resolve(
+        &self,
+        version: &str,
+        source: &ReleaseSource,
+    ) -> Result<ReleaseAsset, FetchError> {
+        match source {
+            ReleaseSource::Zenodo => {
+                let url = format!(
+                    "{}?q=metadata.version:\"{}\"&all_versions=true&sort=mostrecent",
+                    ZENODO_API, version
+                );
+                let response = self.get_json(&url)?;
+                let hits = response["hits"]["hits"]
+                    .as_array()
+                    .ok_or_else(|| FetchError::UnexpectedResponse("missing hits".to_string()))?;
+                hits.iter()
+                    .filter(|record| record["metadata"]["version"].as_str() == Some(version))
+                    .find_map(zenodo_zip_asset)
+                    .ok_or_else(|| FetchError::NotFound(format!("Zenodo version {}", version)))
+            }
+            ReleaseSource::ZenodoDoi(doi) => {
+                let record_id = zenodo_record_id(doi)
+                    .ok_or_else(|| FetchError::NotFound(format!("not a Zenodo DOI: {}", doi)))?;
+                let record = self.get_json(&format!("{}/{}", ZENODO_API, record_id))?;
+                zenodo_zip_asset(&record)
+                    .ok_or_else(|| FetchError::NotFound(format!("zip asset in {}", doi)))
+            }
+            ReleaseSource::GitHub { owner, repo } => {
+                let url = format!(
+                    "{}/{}/{}/releases/tags/v{}",
+                    GITHUB_API, owner, repo, version
+                );
+                let release = self.get_json(&url)?;
+                github_zip_asset(&release, version).ok_or_else(|| {
+                    FetchError::NotFound(format!("{}/{} release v{}", owner, repo, version))
+                })
+            }
+        }
+    }
+
+    /// Download `asset` and verify its checksum when one is published.
+    pub fn download(&self, asset: &ReleaseAsset) -> Result<Vec<u8>, FetchError> {
+        let mut response = self.client.get(&asset.url).send()?.error_for_status()?;
+        let mut bytes = Vec::new();
+        response.read_to_end(&mut bytes)?;
+        if let Some(checksum) = &asset.checksum {
+            if !checksum.verify(&bytes) {
+                return Err(FetchError::ChecksumMismatch {
+                    file_name: asset.file_name.clone(),
+                    expected: checksum.clone(),
+                });
+            }
+        }
+        Ok(bytes)
+    }
+
+    fn get_json(&self, url: &str) -> Result<Value, FetchError> {
+        Ok(self.client.get(url).send()?.error_for_status()?.json()?)
+    }
+}
+
+/// Extract the numeric Zenodo record id from a DOI or DOI URL.
+pub fn zenodo_record_id(doi: &str) -> Option<&str> {
+    let (_, id) = doi.split_once(ZENODO_DOI_PREFIX)?;
+    if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) {
+        Some(id)
+    } else {
+        None
+    }
+}
+
+/// First zip file of a Zenodo record JSON.
+fn zenodo_zip_asset(record: &Value) -> Option<ReleaseAsset> {
+    let version = record["metadata"]["version"].as_str().unwrap_or_default();
+    record["files"].as_array()?.iter().find_map(|file| {
+        let file_name = file["key"].as_str()?;
+        if !file_name.ends_with(ZIP_EXT) {
+            return None;
+        }
+        Some(ReleaseAsset {
+            version: version.to_string(),
+            file_name: file_name.to_string(),
+            url: file["links"]["self"].as_str()?.to_string(),
+            checksum: file["checksum"].as_str().and_then(Checksum::parse),
+        })
+    })
+}
+
+/// First zip asset of a GitHub release JSON.
+fn github_zip_asset(release: &Value, version: &str) -> Option<ReleaseAsset> {
+    release["assets"].as_array()?.iter().find_map(|asset| {
+        let file_name = asset["name"].as_str()?;
+        if !file_name.ends_with(ZIP_EXT) {
+            return None;
+        }
+        Some(ReleaseAsset {
+            version: version.to_string(),
+            file_name: file_name.to_string(),
+            url: asset["browser_download_url"].as_str()?.to_string(),
+            checksum: asset["digest"].as_str().and_then(Checksum::parse),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zenodo_record_id() {
+        assert_eq!(
+            zenodo_record_id("https://doi.org/10.5281/zenodo.17033774"),
+            Some("17033774")
+        );
+        assert_eq!(zenodo_record_id("10.1234/other.1"), None);
+    }
+
+    #[test]
+    fn test_checksum() {
+        let md5 = Checksum::parse("md5:5D41402ABC4B2A76B9719D911017C592").unwrap();
+        assert!(md5.verify(b"hello"));
+        let sha = Checksum::parse(
+            "sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+        )
+        .unwrap();
+        assert!(sha.verify(b"hello"));
+        assert!(!sha.verify(b"world"));
+        assert!(Checksum::parse("crc32:1234").is_none());
+    }
+
+    #[test]
+    fn test_zenodo_zip_asset() {
+        let record: Value = serde_json::from_str(
+            r#"{
+                "metadata": {"version": "2.2"},
+                "files": [
+                    {"key": "README.md", "checksum": "md5:00", "links": {"self": "https://example.org/readme"}},
+                    {"key": "MDD.zip", "checksum": "md5:abc", "links": {"self": "https://example.org/MDD.zip/content"}}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let asset = zenodo_zip_asset(&record).unwrap();
+        assert_eq!(asset.file_name, "MDD.zip");
+        assert_eq!(asset.version, "2.2");
+        assert_eq!(asset.checksum, Some(Checksum::Md5("abc".to_string())));
+    }
+}
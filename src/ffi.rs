@@ -0,0 +1,330 @@
+//! C ABI (enabled with the `ffi` feature).
+//!
+//! `extern "C"` functions for languages that cannot link Rust directly (R,
+//! Julia, Swift / Kotlin on mobile). Inputs are UTF-8 buffers with a length;
+//! outputs are JSON text in the serde shapes of the JSON bundle. The header
+//! `include/mdd_api.h` is generated with cbindgen (see `cbindgen.toml`):
+//!
+//! ```text
+//! cargo rustc --lib --release --features ffi --crate-type cdylib
+//! cbindgen --config cbindgen.toml --output include/mdd_api.h
+//! ```
+//!
+//! ```c
+//! MddRelease *release = mdd_release_from_csv(species, species_len,
+//!                                            synonyms, synonyms_len,
+//!                                            "2.2", "2025-06-01");
+//! if (release == NULL) {
+//!     fprintf(stderr, "%s\n", mdd_last_error());
+//! }
+//! char *json = mdd_release_to_json(release);
+//! mdd_string_free(json);
+//! mdd_release_free(release);
+//! ```
+//!
+//! Ownership: every `char *` returned here must be released with
+//! `mdd_string_free` and every `MddRelease *` with `mdd_release_free`.
+//! Functions that fail return `NULL` and record a message for
+//! `mdd_last_error` on the calling thread. Panics are caught at the boundary
+//! and reported the same way, as unwinding into C is undefined behavior.
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use serde::Serialize;
+
+use crate::parser::{
+    country::CountryMDDStats, error::MddError, mdd::MddData, synonyms::SynonymData, ReleasedMddData,
+};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Release bundle owned by the caller; free it with `mdd_release_free`.
+pub struct MddRelease {
+    inner: ReleasedMddData,
+}
+
+/// Message of the last error on this thread, or `NULL` if none.
+///
+/// The pointer stays valid until the next failing call on the same thread;
+/// do not free it.
+#[no_mangle]
+pub extern "C" fn mdd_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Parse a species CSV into a JSON array of `MddData` records.
+///
+/// # Safety
+/// `csv_data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn mdd_parse_species_csv(csv_data: *const u8, len: usize) -> *mut c_char {
+    ffi_result(catch_panic(|| {
+        let species = MddData::new().try_from_csv(read_str(csv_data, len)?)?;
+        into_json(&species)
+    }))
+}
+
+/// Parse a synonym CSV into a JSON array of `SynonymData` records.
+///
+/// # Safety
+/// `csv_data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn mdd_parse_synonyms_csv(csv_data: *const u8, len: usize) -> *mut c_char {
+    ffi_result(catch_panic(|| {
+        let synonyms = SynonymData::new().try_from_csv(read_str(csv_data, len)?)?;
+        into_json(&synonyms)
+    }))
+}
+
+/// Parse species and synonym CSVs into a release bundle.
+///
+/// # Safety
+/// `species_csv` and `synonym_csv` must point to `species_len` and
+/// `synonym_len` readable bytes; `version` and `release_date` must be
+/// NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn mdd_release_from_csv(
+    species_csv: *const u8,
+    species_len: usize,
+    synonym_csv: *const u8,
+    synonym_len: usize,
+    version: *const c_char,
+    release_date: *const c_char,
+) -> *mut MddRelease {
+    let release = catch_panic(|| {
+        let species = MddData::new().try_from_csv(read_str(species_csv, species_len)?)?;
+        let synonyms = SynonymData::new().try_from_csv(read_str(synonym_csv, synonym_len)?)?;
+        Ok(ReleasedMddData::from_parser(
+            species,
+            synonyms,
+            read_c_str(version)?,
+            read_c_str(release_date)?,
+        ))
+    });
+    ffi_release(release)
+}
+
+/// Load a JSON bundle written by `mdd json`.
+///
+/// # Safety
+/// `json_data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn mdd_release_from_json(
+    json_data: *const u8,
+    len: usize,
+) -> *mut MddRelease {
+    ffi_release(catch_panic(|| {
+        ReleasedMddData::try_from_json(read_str(json_data, len)?)
+    }))
+}
+
+/// Serialize a release bundle to JSON.
+///
+/// # Safety
+/// `release` must be `NULL` or a pointer returned by this library and not
+/// yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn mdd_release_to_json(release: *const MddRelease) -> *mut c_char {
+    ffi_result(catch_panic(|| into_json(&as_release(release)?.inner)))
+}
+
+/// Per-country statistics (`CountryMDDStats`) of a release bundle as JSON.
+///
+/// # Safety
+/// `release` must be `NULL` or a pointer returned by this library and not
+/// yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn mdd_release_country_stats(release: *const MddRelease) -> *mut c_char {
+    ffi_result(catch_panic(|| {
+        let release = as_release(release)?;
+        let species: Vec<MddData> = release.inner.species().cloned().collect();
+        let mut stats = CountryMDDStats::new();
+        stats.parse_country_data(&species);
+        into_json(&stats)
+    }))
+}
+
+/// Number of species in a release bundle; `0` for `NULL`.
+///
+/// # Safety
+/// `release` must be `NULL` or a pointer returned by this library and not
+/// yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn mdd_release_species_count(release: *const MddRelease) -> usize {
+    release
+        .as_ref()
+        .map_or(0, |release| release.inner.data.len())
+}
+
+/// Free a release bundle. `NULL` is ignored.
+///
+/// # Safety
+/// `release` must be `NULL` or a pointer returned by this library and not
+/// yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn mdd_release_free(release: *mut MddRelease) {
+    if !release.is_null() {
+        drop(Box::from_raw(release));
+    }
+}
+
+/// Free a string returned by this library. `NULL` is ignored.
+///
+/// # Safety
+/// `value` must be `NULL` or a string returned by this library and not yet
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn mdd_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
+
+/// Run `f`, turning a panic into an error instead of unwinding into C.
+fn catch_panic<T, F>(f: F) -> Result<T, MddError>
+where
+    F: FnOnce() -> Result<T, MddError>,
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        Err(MddError::Io(std::io::Error::other(format!(
+            "internal error: {}",
+            message
+        ))))
+    })
+}
+
+fn set_last_error(err: &MddError) {
+    // Messages come from our own errors, so an inner NUL is not expected.
+    let message = CString::new(err.to_string().replace('\0', " ")).ok();
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+}
+
+fn ffi_result(result: Result<CString, MddError>) -> *mut c_char {
+    match result {
+        Ok(value) => value.into_raw(),
+        Err(err) => {
+            set_last_error(&err);
+            ptr::null_mut()
+        }
+    }
+}
+
+fn ffi_release(result: Result<ReleasedMddData, MddError>) -> *mut MddRelease {
+    match result {
+        Ok(inner) => Box::into_raw(Box::new(MddRelease { inner })),
+        Err(err) => {
+            set_last_error(&err);
+            ptr::null_mut()
+        }
+    }
+}
+
+fn into_json<T: Serialize + ?Sized>(value: &T) -> Result<CString, MddError> {
+    // JSON escapes control characters, so the text has no NUL bytes.
+    let json = serde_json::to_string(value)?;
+    Ok(CString::new(json).expect("JSON has no NUL bytes"))
+}
+
+unsafe fn read_str<'a>(data: *const u8, len: usize) -> Result<&'a str, MddError> {
+    if data.is_null() {
+        return Err(null_pointer());
+    }
+    std::str::from_utf8(slice::from_raw_parts(data, len)).map_err(invalid_utf8)
+}
+
+unsafe fn read_c_str<'a>(value: *const c_char) -> Result<&'a str, MddError> {
+    if value.is_null() {
+        return Err(null_pointer());
+    }
+    CStr::from_ptr(value).to_str().map_err(invalid_utf8)
+}
+
+unsafe fn as_release<'a>(release: *const MddRelease) -> Result<&'a MddRelease, MddError> {
+    release.as_ref().ok_or_else(null_pointer)
+}
+
+fn null_pointer() -> MddError {
+    MddError::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        "null pointer",
+    ))
+}
+
+fn invalid_utf8(err: std::str::Utf8Error) -> MddError {
+    MddError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn take_string(value: *mut c_char) -> String {
+        assert!(!value.is_null());
+        let text = CStr::from_ptr(value).to_str().unwrap().to_string();
+        mdd_string_free(value);
+        text
+    }
+
+    #[test]
+    fn test_ffi_roundtrip() {
+        let species_csv = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let synonym_csv = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        unsafe {
+            let json = take_string(mdd_parse_species_csv(
+                species_csv.as_ptr(),
+                species_csv.len(),
+            ));
+            let species: serde_json::Value = serde_json::from_str(&json).unwrap();
+            assert_eq!(species[0]["sciName"], "Bunolagus_monticularis");
+
+            let release = mdd_release_from_csv(
+                species_csv.as_ptr(),
+                species_csv.len(),
+                synonym_csv.as_ptr(),
+                synonym_csv.len(),
+                c"2.0".as_ptr(),
+                c"2025-01-01".as_ptr(),
+            );
+            assert_eq!(mdd_release_species_count(release), 112);
+            let json = take_string(mdd_release_to_json(release));
+            let stats = take_string(mdd_release_country_stats(release));
+            assert!(stats.contains("\"ZA\""));
+            mdd_release_free(release);
+
+            let restored = mdd_release_from_json(json.as_ptr(), json.len());
+            assert_eq!(mdd_release_species_count(restored), 112);
+            mdd_release_free(restored);
+
+            let bad = "not,a\nspecies,csv";
+            assert!(mdd_parse_species_csv(bad.as_ptr(), bad.len()).is_null());
+            assert!(!mdd_last_error().is_null());
+            assert!(mdd_release_to_json(ptr::null()).is_null());
+            let message = CStr::from_ptr(mdd_last_error()).to_str().unwrap();
+            assert!(message.contains("null pointer"));
+        }
+    }
+
+    #[test]
+    fn test_panics_are_caught() {
+        let result = ffi_result(catch_panic(|| panic!("boom")));
+        assert!(result.is_null());
+        let message = unsafe { CStr::from_ptr(mdd_last_error()) };
+        assert_eq!(message.to_str().unwrap(), "I/O error: internal error: boom");
+    }
+}
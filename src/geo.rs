@@ -0,0 +1,379 @@
+//! Species filtering by type locality (enabled with the `geo` feature).
+//!
+//! `BoundingBox` and `Region` are `Area`s; `ReleasedMddData::species_within`
+//! returns the species whose type locality falls inside one:
+//!
+//! ```rust, ignore
+//! use mdd_api::geo::{BoundingBox, Region};
+//!
+//! let andes = BoundingBox::new(-23.0, -80.0, 5.0, -60.0)?;
+//! let peru = Region::from_geojson(&std::fs::read_to_string("peru.geojson")?)?;
+//! for species in release.species_within(&andes) {
+//!     println!("{}", species.sci_name);
+//! }
+//! ```
+//!
+//! Type locality coordinates are read as in `parser::detail::TypeLocality`;
+//! species without both coordinates are never inside an area. `Region` reads
+//! a GeoJSON `Polygon`, `MultiPolygon`, `Feature`, or `FeatureCollection`
+//! (any of whose polygons may match) with longitude-latitude positions, and
+//! honors polygon holes. Edges are straight lines in longitude-latitude
+//! space; polygons crossing the antimeridian must be split, as RFC 7946
+//! recommends. A bounding box whose west edge is east of its east edge
+//! wraps across the antimeridian.
+
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::{
+    helper::coords::{MAX_LATITUDE, MAX_LONGITUDE},
+    parser::{detail::TypeLocality, mdd::MddData, ReleasedMddData},
+};
+
+/// A longitude-latitude position.
+type Position = (f64, f64);
+
+/// An area that can contain a point.
+pub trait Area {
+    /// Whether the point at `latitude`, `longitude` (decimal degrees) is
+    /// inside the area or on its boundary.
+    fn contains(&self, latitude: f64, longitude: f64) -> bool;
+
+    /// Whether the type locality of `species` is inside the area.
+    fn contains_species(&self, species: &MddData) -> bool {
+        TypeLocality::from_species(species)
+            .point()
+            .is_some_and(|(latitude, longitude)| self.contains(latitude, longitude))
+    }
+}
+
+/// Why an area could not be built.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoError {
+    /// A latitude or longitude out of range, or south above north.
+    InvalidBounds(String),
+    /// The text is not JSON.
+    Json(String),
+    /// The JSON is not a GeoJSON polygon, multipolygon, feature, or feature
+    /// collection with at least one polygon.
+    InvalidGeoJson(String),
+}
+
+impl fmt::Display for GeoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidBounds(msg) => write!(f, "invalid bounding box: {}", msg),
+            Self::Json(msg) => write!(f, "invalid JSON: {}", msg),
+            Self::InvalidGeoJson(msg) => write!(f, "invalid GeoJSON: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for GeoError {}
+
+/// A latitude-longitude rectangle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+}
+
+impl BoundingBox {
+    /// Box from its south-west and north-east corners. `west > east` wraps
+    /// across the antimeridian.
+    pub fn new(south: f64, west: f64, north: f64, east: f64) -> Result<Self, GeoError> {
+        if [south, north].iter().any(|lat| lat.abs() > MAX_LATITUDE) {
+            return Err(GeoError::InvalidBounds(format!(
+                "latitude out of range in {}, {}",
+                south, north
+            )));
+        }
+        if [west, east].iter().any(|lon| lon.abs() > MAX_LONGITUDE) {
+            return Err(GeoError::InvalidBounds(format!(
+                "longitude out of range in {}, {}",
+                west, east
+            )));
+        }
+        if south > north {
+            return Err(GeoError::InvalidBounds(format!(
+                "south {} is north of north {}",
+                south, north
+            )));
+        }
+        Ok(Self {
+            south,
+            west,
+            north,
+            east,
+        })
+    }
+}
+
+impl Area for BoundingBox {
+    fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        if latitude < self.south || latitude > self.north {
+            return false;
+        }
+        if self.west <= self.east {
+            (self.west..=self.east).contains(&longitude)
+        } else {
+            longitude >= self.west || longitude <= self.east
+        }
+    }
+}
+
+/// A polygon with optional holes.
+#[derive(Debug, Clone, PartialEq)]
+struct Polygon {
+    exterior: Vec<Position>,
+    holes: Vec<Vec<Position>>,
+}
+
+impl Polygon {
+    fn contains(&self, point: Position) -> bool {
+        ring_contains(&self.exterior, point)
+            && !self
+                .holes
+                .iter()
+                .any(|hole| ring_contains(hole, point) && !on_boundary(hole, point))
+    }
+}
+
+/// One or more polygons read from GeoJSON, e.g. a country outline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+    polygons: Vec<Polygon>,
+}
+
+impl Region {
+    /// Read a GeoJSON `Polygon`, `MultiPolygon`, `Feature`, or
+    /// `FeatureCollection`.
+    pub fn from_geojson(geojson: &str) -> Result<Self, GeoError> {
+        let value: Value =
+            serde_json::from_str(geojson).map_err(|e| GeoError::Json(e.to_string()))?;
+        Self::from_geojson_value(&value)
+    }
+
+    /// Read an already parsed GeoJSON object.
+    pub fn from_geojson_value(value: &Value) -> Result<Self, GeoError> {
+        let mut polygons = Vec::new();
+        collect_polygons(value, &mut polygons)?;
+        if polygons.is_empty() {
+            return Err(GeoError::InvalidGeoJson("no polygon found".to_string()));
+        }
+        Ok(Self { polygons })
+    }
+
+    /// Number of polygons in the region.
+    pub fn len(&self) -> usize {
+        self.polygons.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.polygons.is_empty()
+    }
+}
+
+impl Area for Region {
+    fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        self.polygons
+            .iter()
+            .any(|polygon| polygon.contains((longitude, latitude)))
+    }
+}
+
+impl ReleasedMddData {
+    /// Species whose type locality is inside `area`, in release order.
+    pub fn species_within<A: Area + ?Sized>(&self, area: &A) -> Vec<&MddData> {
+        self.species()
+            .filter(|species| area.contains_species(species))
+            .collect()
+    }
+}
+
+fn collect_polygons(value: &Value, polygons: &mut Vec<Polygon>) -> Result<(), GeoError> {
+    let kind = value
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or_else(|| GeoError::InvalidGeoJson("missing \"type\"".to_string()))?;
+    match kind {
+        "FeatureCollection" => {
+            let features = value
+                .get("features")
+                .and_then(Value::as_array)
+                .ok_or_else(|| GeoError::InvalidGeoJson("missing \"features\"".to_string()))?;
+            for feature in features {
+                collect_polygons(feature, polygons)?;
+            }
+        }
+        // Features without geometry, or with points and lines, are skipped.
+        "Feature" => match value.get("geometry") {
+            Some(geometry) if !geometry.is_null() => collect_polygons(geometry, polygons)?,
+            _ => {}
+        },
+        "GeometryCollection" => {
+            if let Some(geometries) = value.get("geometries").and_then(Value::as_array) {
+                for geometry in geometries {
+                    collect_polygons(geometry, polygons)?;
+                }
+            }
+        }
+        "Polygon" => polygons.push(parse_polygon(coordinates(value)?)?),
+        "MultiPolygon" => {
+            let parts = coordinates(value)?
+                .as_array()
+                .ok_or_else(|| invalid_coordinates("MultiPolygon"))?;
+            for part in parts {
+                polygons.push(parse_polygon(part)?);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn coordinates(geometry: &Value) -> Result<&Value, GeoError> {
+    geometry
+        .get("coordinates")
+        .ok_or_else(|| GeoError::InvalidGeoJson("missing \"coordinates\"".to_string()))
+}
+
+fn parse_polygon(value: &Value) -> Result<Polygon, GeoError> {
+    let rings = value
+        .as_array()
+        .ok_or_else(|| invalid_coordinates("Polygon"))?
+        .iter()
+        .map(parse_ring)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut rings = rings.into_iter();
+    let exterior = rings
+        .next()
+        .ok_or_else(|| GeoError::InvalidGeoJson("polygon without rings".to_string()))?;
+    Ok(Polygon {
+        exterior,
+        holes: rings.collect(),
+    })
+}
+
+fn parse_ring(value: &Value) -> Result<Vec<Position>, GeoError> {
+    let ring = value
+        .as_array()
+        .ok_or_else(|| invalid_coordinates("linear ring"))?
+        .iter()
+        .map(|position| match position.as_array().map(Vec::as_slice) {
+            Some([lon, lat, ..]) => match (lon.as_f64(), lat.as_f64()) {
+                (Some(lon), Some(lat)) => Ok((lon, lat)),
+                _ => Err(invalid_coordinates("position")),
+            },
+            _ => Err(invalid_coordinates("position")),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // A closed ring repeats its first position; three distinct ones are needed.
+    if ring.len() < 4 {
+        return Err(GeoError::InvalidGeoJson(format!(
+            "linear ring with {} positions",
+            ring.len()
+        )));
+    }
+    Ok(ring)
+}
+
+fn invalid_coordinates(what: &str) -> GeoError {
+    GeoError::InvalidGeoJson(format!("invalid {} coordinates", what))
+}
+
+/// Even-odd ray casting; points on an edge count as inside.
+fn ring_contains(ring: &[Position], point: Position) -> bool {
+    if on_boundary(ring, point) {
+        return true;
+    }
+    let (x, y) = point;
+    let mut inside = false;
+    for (&(x1, y1), &(x2, y2)) in ring.iter().zip(ring.iter().cycle().skip(1)) {
+        if (y1 > y) != (y2 > y) && x < x1 + (y - y1) * (x2 - x1) / (y2 - y1) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+fn on_boundary(ring: &[Position], point: Position) -> bool {
+    let (x, y) = point;
+    ring.iter()
+        .zip(ring.iter().cycle().skip(1))
+        .any(|(&(x1, y1), &(x2, y2))| {
+            let cross = (x2 - x1) * (y - y1) - (y2 - y1) * (x - x1);
+            cross.abs() < f64::EPSILON
+                && x >= x1.min(x2)
+                && x <= x1.max(x2)
+                && y >= y1.min(y2)
+                && y <= y1.max(y2)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn species(id: u32, latitude: &str, longitude: &str) -> MddData {
+        MddData {
+            id,
+            sci_name: format!("Species_{}", id),
+            type_locality_latitude: latitude.to_string(),
+            type_locality_longitude: longitude.to_string(),
+            ..MddData::new()
+        }
+    }
+
+    #[test]
+    fn test_species_within() {
+        let release = ReleasedMddData::from_parser(
+            vec![
+                species(1, "-12.0", "-75.0"),
+                species(2, "0.5", "0.5"),
+                species(3, "1.5", "1.5"),
+                species(4, "", ""),
+                species(5, "10", "179.5"),
+            ],
+            Vec::new(),
+            "2.0",
+            "2025-01-01",
+        );
+        let ids = |species: Vec<&MddData>| species.iter().map(|s| s.id).collect::<Vec<_>>();
+
+        let andes = BoundingBox::new(-23.0, -80.0, 5.0, -60.0).unwrap();
+        assert_eq!(ids(release.species_within(&andes)), [1]);
+        let pacific = BoundingBox::new(0.0, 170.0, 20.0, -170.0).unwrap();
+        assert_eq!(ids(release.species_within(&pacific)), [5]);
+        assert!(BoundingBox::new(10.0, 0.0, 5.0, 1.0).is_err());
+
+        // A 4 x 4 degree square with a 1 x 1 hole at (1..2, 1..2).
+        let geojson = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"name": "Square"}, "geometry": {
+                "type": "Polygon",
+                "coordinates": [
+                    [[0, 0], [4, 0], [4, 4], [0, 4], [0, 0]],
+                    [[1, 1], [2, 1], [2, 2], [1, 2], [1, 1]]
+                ]
+            }},
+            {"type": "Feature", "properties": {}, "geometry": null}
+        ]}"#;
+        let region = Region::from_geojson(geojson).unwrap();
+        assert_eq!(region.len(), 1);
+        assert_eq!(ids(release.species_within(&region)), [2]);
+        assert!(region.contains(0.0, 2.0));
+        assert!(region.contains(1.0, 1.5));
+
+        assert!(matches!(
+            Region::from_geojson(r#"{"type": "Point", "coordinates": [0, 0]}"#),
+            Err(GeoError::InvalidGeoJson(_))
+        ));
+        assert!(matches!(
+            Region::from_geojson("not json"),
+            Err(GeoError::Json(_))
+        ));
+    }
+}
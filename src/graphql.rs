@@ -0,0 +1,380 @@
+//! GraphQL API over an in-memory release bundle (enabled with the `graphql` feature).
+//!
+//! `build_schema` wraps one `ReleasedMddData` in a read-only schema, so a
+//! small deployment can serve MDD without a separate backend:
+//!
+//! ```graphql
+//! {
+//!   metadata { version speciesCount }
+//!   species(filter: { family: "Leporidae", country: "Mexico" }, limit: 10) {
+//!     totalCount
+//!     items { id sciName iucnStatus }
+//!   }
+//!   synonyms(speciesId: 1001076) { items { synId species author year } }
+//!   taxonomy(rank: ORDER, name: "Lagomorpha") { name speciesCount children { name } }
+//!   countryStats(codes: ["US"]) { code name totalLivingSpecies }
+//! }
+//! ```
+//!
+//! Species filters are the ones of `query::SpeciesQuery`. List queries take
+//! `offset` and `limit` (see `query::Page`) and return the number of matches
+//! next to the page. Field names are the camelCase Rust field names,
+//! so a few differ from the JSON bundle (`cmwSciName` for `CMW_sciName`).
+//!
+//! `router` mounts the schema at `/graphql` (POST for queries, GET for the
+//! GraphiQL page); merge it with `rest::router` and run it with `rest::serve`.
+
+use async_graphql::{
+    http::GraphiQLSource, ComplexObject, EmptyMutation, EmptySubscription, InputObject, Object,
+    Schema, SimpleObject,
+};
+use axum::{
+    extract::State,
+    response::{Html, IntoResponse},
+    routing::get,
+    Json, Router,
+};
+
+use crate::{
+    parser::{
+        country::{CountryData, CountryMDDStats},
+        mdd::MddData,
+        synonyms::SynonymData,
+        taxonomy::{TaxonNode, TaxonRank, TaxonomyTree},
+        MetaData, ReleasedMddData,
+    },
+    query::{Page, SpeciesQuery},
+    store::MddStore,
+};
+
+/// Path the schema is served at.
+pub const GRAPHQL_PATH: &str = "/graphql";
+
+/// Schema type served by `router`.
+pub type MddSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Build the schema over a release bundle.
+pub fn build_schema(release: ReleasedMddData) -> MddSchema {
+    Schema::new(QueryRoot::new(release), EmptyMutation, EmptySubscription)
+}
+
+/// Axum router serving `schema` at `GRAPHQL_PATH`.
+pub fn router(schema: MddSchema) -> Router {
+    Router::new()
+        .route(GRAPHQL_PATH, get(graphiql).post(execute))
+        .with_state(schema)
+}
+
+async fn execute(
+    State(schema): State<MddSchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(schema.execute(request).await)
+}
+
+async fn graphiql() -> impl IntoResponse {
+    Html(GraphiQLSource::build().endpoint(GRAPHQL_PATH).finish())
+}
+
+/// Species filters, all optional; every given filter must match.
+#[derive(Debug, Clone, Default, InputObject)]
+pub struct SpeciesFilter {
+    pub order: Option<String>,
+    pub family: Option<String>,
+    pub genus: Option<String>,
+    pub country: Option<String>,
+    pub continent: Option<String>,
+    pub realm: Option<String>,
+    /// IUCN status codes, e.g. `["EN", "CR"]`.
+    pub iucn: Option<Vec<String>>,
+    pub extinct: Option<bool>,
+    pub domestic: Option<bool>,
+}
+
+impl SpeciesFilter {
+    fn to_query(&self) -> SpeciesQuery {
+        let mut query = SpeciesQuery::new();
+        if let Some(order) = &self.order {
+            query = query.order(order);
+        }
+        if let Some(family) = &self.family {
+            query = query.family(family);
+        }
+        if let Some(genus) = &self.genus {
+            query = query.genus(genus);
+        }
+        if let Some(country) = &self.country {
+            query = query.country(country);
+        }
+        if let Some(continent) = &self.continent {
+            query = query.continent(continent);
+        }
+        if let Some(realm) = &self.realm {
+            query = query.realm(realm);
+        }
+        if let Some(codes) = &self.iucn {
+            query = query.iucn_in(codes);
+        }
+        if let Some(extinct) = self.extinct {
+            query = query.extinct(extinct);
+        }
+        if let Some(domestic) = self.domestic {
+            query = query.domestic(domestic);
+        }
+        query
+    }
+}
+
+/// One page of species.
+#[derive(SimpleObject)]
+pub struct SpeciesPage<'a> {
+    /// Number of species matching the filter.
+    pub total_count: usize,
+    pub items: Vec<&'a MddData>,
+}
+
+impl<'a> From<Page<&'a MddData>> for SpeciesPage<'a> {
+    fn from(page: Page<&'a MddData>) -> Self {
+        Self {
+            total_count: page.total_count,
+            items: page.items,
+        }
+    }
+}
+
+/// One page of synonyms.
+#[derive(SimpleObject)]
+pub struct SynonymPage<'a> {
+    /// Number of synonyms matching the filter.
+    pub total_count: usize,
+    pub items: Vec<&'a SynonymData>,
+}
+
+impl<'a> From<Page<&'a SynonymData>> for SynonymPage<'a> {
+    fn from(page: Page<&'a SynonymData>) -> Self {
+        Self {
+            total_count: page.total_count,
+            items: page.items,
+        }
+    }
+}
+
+/// Statistics of one country with its code.
+#[derive(SimpleObject)]
+pub struct CountryStat<'a> {
+    pub code: &'a str,
+    #[graphql(flatten)]
+    pub data: &'a CountryData,
+}
+
+/// Root of the read-only schema.
+pub struct QueryRoot {
+    store: MddStore,
+    taxonomy: TaxonomyTree,
+    country_stats: CountryMDDStats,
+}
+
+impl QueryRoot {
+    pub fn new(release: ReleasedMddData) -> Self {
+        let species: Vec<MddData> = release.species().cloned().collect();
+        let mut country_stats = CountryMDDStats::new();
+        country_stats.parse_country_data(&species);
+        Self {
+            taxonomy: TaxonomyTree::from_mdd(&species),
+            country_stats,
+            store: MddStore::new(release),
+        }
+    }
+}
+
+#[Object]
+impl QueryRoot {
+    /// Release version, date, and headline counts.
+    async fn metadata(&self) -> &MetaData {
+        &self.store.release().metadata
+    }
+
+    /// Species matching `filter`, in release order.
+    async fn species(
+        &self,
+        filter: Option<SpeciesFilter>,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> SpeciesPage<'_> {
+        let query = filter.unwrap_or_default().to_query();
+        let matches = self
+            .store
+            .release()
+            .species()
+            .filter(|species| query.matches(species));
+        Page::new(matches, offset, limit).into()
+    }
+
+    /// A species by MDD id.
+    async fn species_by_id(&self, id: u32) -> Option<&MddData> {
+        self.store.species(id)
+    }
+
+    /// A species by scientific name, with a space or `_` between the parts.
+    async fn species_by_name(&self, sci_name: String) -> Option<&MddData> {
+        self.store.species_by_name(&sci_name)
+    }
+
+    /// Synonyms, optionally of one species (`speciesId`) or with a given
+    /// validity (e.g. `species`, `synonym`).
+    async fn synonyms(
+        &self,
+        species_id: Option<u32>,
+        validity: Option<String>,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> SynonymPage<'_> {
+        let synonyms: Box<dyn Iterator<Item = &SynonymData>> = match species_id {
+            Some(species_id) => Box::new(
+                self.store
+                    .synonym_ids(species_id)
+                    .iter()
+                    .filter_map(|syn_id| self.store.synonym(*syn_id)),
+            ),
+            None => Box::new(self.store.release().all_synonyms()),
+        };
+        let matches = synonyms.filter(|synonym| match &validity {
+            Some(validity) => synonym.validity.eq_ignore_ascii_case(validity),
+            None => true,
+        });
+        Page::new(matches, offset, limit).into()
+    }
+
+    /// Taxonomy tree from the subclasses down, or the subtree of the taxon
+    /// of `rank` named `name`.
+    async fn taxonomy(&self, rank: Option<TaxonRank>, name: Option<String>) -> Vec<&TaxonNode> {
+        match (rank, name) {
+            (Some(rank), Some(name)) => self.taxonomy.subtree(rank, &name).into_iter().collect(),
+            (Some(rank), None) => self.taxonomy.nodes_at(rank),
+            _ => self.taxonomy.roots.iter().collect(),
+        }
+    }
+
+    /// Per-country statistics, for all countries or the given codes.
+    async fn country_stats(&self, codes: Option<Vec<String>>) -> Vec<CountryStat<'_>> {
+        self.country_stats
+            .country_data
+            .iter()
+            .filter(|(code, _)| match &codes {
+                Some(codes) => codes.iter().any(|c| c.eq_ignore_ascii_case(code)),
+                None => true,
+            })
+            .map(|(code, data)| CountryStat { code, data })
+            .collect()
+    }
+}
+
+// Fields with a getter of the same name are skipped by the derives and
+// resolved here, since the derived resolvers would clash with the getters.
+#[ComplexObject]
+impl MddData {
+    /// Synonym id of the original description.
+    #[graphql(name = "basionymSynonymId")]
+    async fn resolve_basionym_synonym_id(&self) -> Option<u32> {
+        self.basionym_synonym_id()
+    }
+}
+
+#[ComplexObject]
+impl SynonymData {
+    #[graphql(name = "rootName")]
+    async fn resolve_root_name(&self) -> &str {
+        self.root_name()
+    }
+
+    #[graphql(name = "nomenclatureStatus")]
+    async fn resolve_nomenclature_status(&self) -> &str {
+        self.nomenclature_status()
+    }
+
+    #[graphql(name = "validity")]
+    async fn resolve_validity(&self) -> &str {
+        self.validity()
+    }
+
+    #[graphql(name = "originalCombination")]
+    async fn resolve_original_combination(&self) -> &str {
+        self.original_combination()
+    }
+
+    #[graphql(name = "slug")]
+    async fn resolve_slug(&self) -> &str {
+        self.slug()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> MddSchema {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let syn_data = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let mut synonyms = SynonymData::new().from_csv(&syn_data);
+        // The synonym fixture belongs to other species: attach one to the
+        // first species and keep the rest as synonym-only names.
+        for (i, synonym) in synonyms.iter_mut().enumerate() {
+            synonym.species_id = if i == 0 { Some(species[0].id) } else { None };
+        }
+        build_schema(ReleasedMddData::from_parser(
+            species,
+            synonyms,
+            "2.0",
+            "2025-01-01",
+        ))
+    }
+
+    fn run(schema: &MddSchema, query: &str) -> serde_json::Value {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let response = runtime.block_on(schema.execute(query));
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        response.data.into_json().unwrap()
+    }
+
+    #[test]
+    fn test_graphql_queries() {
+        let schema = schema();
+        let data = run(
+            &schema,
+            r#"{
+                metadata { version speciesCount }
+                species(filter: { family: "ochotonidae" }, offset: 1, limit: 2) {
+                    totalCount
+                    items { family }
+                }
+                speciesByName(sciName: "Lepus alleni") { id }
+                taxonomy(rank: FAMILY) { name }
+            }"#,
+        );
+        assert_eq!(data["metadata"]["version"], "2.0");
+        assert_eq!(data["metadata"]["speciesCount"], 112);
+        let items = data["species"]["items"].as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(data["species"]["totalCount"].as_u64().unwrap() > 2);
+        assert_eq!(items[0]["family"], "Ochotonidae");
+        assert!(data["speciesByName"]["id"].is_u64());
+        assert_eq!(data["taxonomy"].as_array().unwrap().len(), 3);
+
+        let data = run(
+            &schema,
+            r#"{
+                synonyms(limit: 1000) { totalCount items { synId rootName validity } }
+                attached: synonyms(speciesId: 1001076) { items { rootName } }
+                countryStats(codes: ["us"]) { code name totalLivingSpecies }
+            }"#,
+        );
+        assert_eq!(data["synonyms"]["totalCount"], 5);
+        assert_eq!(data["synonyms"]["items"][0]["rootName"], "latidens");
+        assert_eq!(data["attached"]["items"].as_array().unwrap().len(), 1);
+        let countries = data["countryStats"].as_array().unwrap();
+        assert_eq!(countries.len(), 1);
+        assert_eq!(countries[0]["code"], "US");
+        assert!(countries[0]["totalLivingSpecies"].as_u64().unwrap() > 0);
+    }
+}
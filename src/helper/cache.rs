@@ -0,0 +1,123 @@
+//! Fingerprints of previous runs, for skipping unchanged work.
+//!
+//! `RunCache` keeps one small JSON entry per command in a cache directory.
+//! An entry holds the fingerprint of the inputs (SHA-256 of every input file
+//! plus the options the outputs depend on) and the outputs written by that
+//! run. A later run with the same fingerprint whose outputs all still exist
+//! can be skipped.
+//!
+//! ```rust, ignore
+//! use mdd_api::helper::cache::{fingerprint, RunCache};
+//!
+//! let cache = RunCache::new(Path::new(".mdd-cache"));
+//! let key = fingerprint(&[species_csv, synonym_csv], "json ./out gzip")?;
+//! if !cache.is_fresh("json", &key) {
+//!     let outputs = write_outputs();
+//!     cache.store("json", &key, &outputs)?;
+//! }
+//! ```
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::checksum::{file_sha256, sha256_hex};
+
+/// A cached run of one command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    /// Fingerprint of the inputs and options.
+    pub key: String,
+    /// Files and directories written by the run.
+    pub outputs: Vec<PathBuf>,
+}
+
+/// Directory of `CacheEntry` files, one per command.
+#[derive(Debug, Clone)]
+pub struct RunCache {
+    dir: PathBuf,
+}
+
+impl RunCache {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// Path of the entry of `name`.
+    pub fn entry_path(&self, name: &str) -> PathBuf {
+        self.dir.join(name).with_extension("json")
+    }
+
+    /// The stored entry of `name`; `None` if missing or unreadable.
+    pub fn entry(&self, name: &str) -> Option<CacheEntry> {
+        let content = fs::read_to_string(self.entry_path(name)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Whether the last run of `name` had fingerprint `key` and its outputs
+    /// still exist.
+    pub fn is_fresh(&self, name: &str, key: &str) -> bool {
+        self.entry(name).is_some_and(|entry| {
+            entry.key == key && entry.outputs.iter().all(|output| output.exists())
+        })
+    }
+
+    /// Record a run of `name` with fingerprint `key` that wrote `outputs`.
+    pub fn store(&self, name: &str, key: &str, outputs: &[PathBuf]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let entry = CacheEntry {
+            key: key.to_string(),
+            outputs: outputs.to_vec(),
+        };
+        let json = serde_json::to_string_pretty(&entry).map_err(io::Error::other)?;
+        fs::write(self.entry_path(name), json)
+    }
+}
+
+/// Fingerprint of the content of `inputs` (in order) and `options`, as hex.
+/// Paths do not contribute, so moving an input keeps the fingerprint.
+pub fn fingerprint<P: AsRef<Path>>(inputs: &[P], options: &str) -> io::Result<String> {
+    let mut material = String::new();
+    for input in inputs {
+        material.push_str(&file_sha256(input.as_ref())?);
+        material.push('\n');
+    }
+    material.push_str(options);
+    Ok(sha256_hex(material.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_run_cache() {
+        let dir = TempDir::new("cache").unwrap();
+        let input = dir.path().join("data.csv");
+        fs::write(&input, "id\n1\n").unwrap();
+        let output = dir.path().join("data.json");
+        let key = fingerprint(&[&input], "gzip").unwrap();
+        assert_ne!(key, fingerprint(&[&input], "zstd").unwrap());
+
+        let cache = RunCache::new(&dir.path().join("cache"));
+        assert!(!cache.is_fresh("json", &key));
+        cache
+            .store("json", &key, std::slice::from_ref(&output))
+            .unwrap();
+        assert!(!cache.is_fresh("json", &key));
+        fs::write(&output, "[]").unwrap();
+        assert!(cache.is_fresh("json", &key));
+
+        fs::write(&input, "id\n2\n").unwrap();
+        let changed = fingerprint(&[&input], "gzip").unwrap();
+        assert!(!cache.is_fresh("json", &changed));
+    }
+}
@@ -0,0 +1,58 @@
+//! SHA-256 checksums of release files.
+//!
+//! Digests are lower-case hexadecimal. Values carrying an algorithm prefix,
+//! as Zenodo and GitHub publish them (`sha256:2cf24d...`), compare equal to
+//! the bare digest.
+
+use std::{fs, io, path::Path};
+
+use sha2::{Digest, Sha256};
+
+/// Algorithm prefix of SHA-256 checksums.
+pub const SHA256_PREFIX: &str = "sha256:";
+
+/// Lower-case hexadecimal encoding of `bytes`.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SHA-256 digest of `bytes`, as hex.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    to_hex(&Sha256::digest(bytes))
+}
+
+/// SHA-256 digest of the file at `path`, as hex, read in chunks.
+pub fn file_sha256(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// Whether a recorded digest (optionally `sha256:`-prefixed, any case)
+/// matches `actual`.
+pub fn sha256_matches(expected: &str, actual: &str) -> bool {
+    let expected = expected.trim();
+    let expected = expected.strip_prefix(SHA256_PREFIX).unwrap_or(expected);
+    expected.eq_ignore_ascii_case(actual)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256() {
+        let expected = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        assert_eq!(sha256_hex(b"hello"), expected);
+        let dir = tempdir::TempDir::new("checksum").unwrap();
+        let path = dir.path().join("hello.txt");
+        fs::write(&path, "hello").unwrap();
+        assert_eq!(file_sha256(&path).unwrap(), expected);
+        assert!(sha256_matches(
+            &format!("sha256:{}", expected.to_uppercase()),
+            expected
+        ));
+        assert!(!sha256_matches("00", expected));
+    }
+}
@@ -0,0 +1,304 @@
+//! Parsing and validation of textual type locality coordinates.
+//!
+//! MDD stores latitude and longitude as free text. Most values are decimal
+//! degrees, but older records carry other notations. `parse_coordinate`
+//! accepts:
+//! * decimal degrees, optionally signed (`-12.5`, `+45`);
+//! * degrees, minutes, and seconds with the usual symbols (`12°30'15"`,
+//!   `12° 30.5′`, `12:30:15`);
+//! * a hemisphere letter before or after the value (`12.5S`, `W 70°`), which
+//!   sets the sign;
+//! * approximate values, marked with `ca.`, `c.`, `circa`, `approx.`, `~`, or
+//!   a trailing `?`.
+//!
+//! Blank and `NA` values parse to `None`. Range checks are left to
+//! `validate_coordinates`, which also flags pairs that look swapped.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::MDD_MISSING_VALUE;
+
+/// Largest absolute latitude.
+pub const MAX_LATITUDE: f64 = 90.0;
+/// Largest absolute longitude.
+pub const MAX_LONGITUDE: f64 = 180.0;
+
+const APPROXIMATE_PREFIXES: [&str; 7] = ["circa", "approx.", "approx", "ca.", "ca ", "c.", "~"];
+const DEGREE_SYMBOLS: [char; 3] = ['°', 'º', '˚'];
+const MINUTE_SYMBOLS: [char; 3] = ['\'', '′', '’'];
+const SECOND_SYMBOLS: [char; 3] = ['"', '″', '”'];
+
+/// Latitude or longitude.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Axis {
+    Latitude,
+    Longitude,
+}
+
+impl Axis {
+    /// Largest absolute value on this axis.
+    pub fn limit(&self) -> f64 {
+        match self {
+            Self::Latitude => MAX_LATITUDE,
+            Self::Longitude => MAX_LONGITUDE,
+        }
+    }
+}
+
+impl fmt::Display for Axis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Latitude => write!(f, "latitude"),
+            Self::Longitude => write!(f, "longitude"),
+        }
+    }
+}
+
+/// How much a parsed value can be trusted, best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CoordinateConfidence {
+    /// Plain decimal degrees.
+    Exact,
+    /// Converted from another notation (DMS, hemisphere letters, symbols).
+    Converted,
+    /// Marked as approximate in the source.
+    Approximate,
+}
+
+/// A parsed coordinate in decimal degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Coordinate {
+    pub value: f64,
+    pub confidence: CoordinateConfidence,
+}
+
+/// Why a coordinate could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoordinateError {
+    /// The text is not a coordinate in any supported notation.
+    Unparseable,
+    /// Minutes or seconds of 60 or more.
+    InvalidMinutes,
+    /// A hemisphere letter of the other axis, e.g. `E` on a latitude.
+    WrongHemisphere(char),
+}
+
+impl fmt::Display for CoordinateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unparseable => write!(f, "not a coordinate"),
+            Self::InvalidMinutes => write!(f, "minutes or seconds of 60 or more"),
+            Self::WrongHemisphere(letter) => write!(f, "hemisphere {} on the wrong axis", letter),
+        }
+    }
+}
+
+impl std::error::Error for CoordinateError {}
+
+/// Parse a textual coordinate on `axis`. Returns `Ok(None)` for blank or
+/// `NA` values; the value is not range-checked.
+pub fn parse_coordinate(text: &str, axis: Axis) -> Result<Option<Coordinate>, CoordinateError> {
+    let mut text = text.trim();
+    if text.is_empty() || text == MDD_MISSING_VALUE {
+        return Ok(None);
+    }
+
+    let mut approximate = false;
+    if let Some(rest) = text.strip_suffix('?') {
+        approximate = true;
+        text = rest.trim_end();
+    }
+    let lower = text.to_lowercase();
+    if let Some(prefix) = APPROXIMATE_PREFIXES.iter().find(|p| lower.starts_with(*p)) {
+        approximate = true;
+        text = text[prefix.len()..].trim_start();
+    }
+
+    let (text, hemisphere) = split_hemisphere(text);
+    let negative_hemisphere = match (hemisphere, axis) {
+        (None, _) => false,
+        (Some('N'), Axis::Latitude) | (Some('E'), Axis::Longitude) => false,
+        (Some('S'), Axis::Latitude) | (Some('W'), Axis::Longitude) => true,
+        (Some(letter), _) => return Err(CoordinateError::WrongHemisphere(letter)),
+    };
+
+    let (negative_sign, text) = if let Some(rest) = text.strip_prefix(['-', '−']) {
+        (true, rest)
+    } else {
+        (false, text.strip_prefix('+').unwrap_or(text))
+    };
+    let (parts, has_symbols) = split_parts(text.trim())?;
+    let value = match parts.as_slice() {
+        [degrees] => *degrees,
+        [degrees, minutes] if *minutes < 60.0 => degrees + minutes / 60.0,
+        [degrees, minutes, seconds] if *minutes < 60.0 && *seconds < 60.0 => {
+            degrees + minutes / 60.0 + seconds / 3600.0
+        }
+        [_, _] | [_, _, _] => return Err(CoordinateError::InvalidMinutes),
+        _ => return Err(CoordinateError::Unparseable),
+    };
+    let value = if negative_sign || negative_hemisphere {
+        -value
+    } else {
+        value
+    };
+
+    let confidence = if approximate {
+        CoordinateConfidence::Approximate
+    } else if hemisphere.is_some() || has_symbols || parts.len() > 1 {
+        CoordinateConfidence::Converted
+    } else {
+        CoordinateConfidence::Exact
+    };
+    Ok(Some(Coordinate { value, confidence }))
+}
+
+/// Strip a leading or trailing hemisphere letter, returned upper-cased.
+fn split_hemisphere(text: &str) -> (&str, Option<char>) {
+    let is_hemisphere = |c: char| matches!(c.to_ascii_uppercase(), 'N' | 'S' | 'E' | 'W');
+    if let Some(last) = text.chars().last().filter(|c| is_hemisphere(*c)) {
+        return (
+            text[..text.len() - 1].trim_end(),
+            Some(last.to_ascii_uppercase()),
+        );
+    }
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(first), Some(next)) if is_hemisphere(first) && !next.is_alphabetic() => {
+            (text[1..].trim_start(), Some(first.to_ascii_uppercase()))
+        }
+        _ => (text, None),
+    }
+}
+
+/// Numbers of a (possibly DMS) value and whether notation symbols were used.
+fn split_parts(text: &str) -> Result<(Vec<f64>, bool), CoordinateError> {
+    let mut parts = Vec::new();
+    let mut has_symbols = false;
+    let mut number = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        let is_symbol = DEGREE_SYMBOLS.contains(&c)
+            || MINUTE_SYMBOLS.contains(&c)
+            || SECOND_SYMBOLS.contains(&c);
+        if !(is_symbol || c == ':' || c.is_whitespace()) {
+            return Err(CoordinateError::Unparseable);
+        }
+        has_symbols |= is_symbol;
+        if !number.is_empty() {
+            parts.push(number.parse().map_err(|_| CoordinateError::Unparseable)?);
+            number.clear();
+        }
+    }
+    if !number.is_empty() {
+        parts.push(number.parse().map_err(|_| CoordinateError::Unparseable)?);
+    }
+    Ok((parts, has_symbols))
+}
+
+/// Problem found by `validate_coordinates`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CoordinateIssue {
+    /// The value is outside ±90 (latitude) or ±180 (longitude).
+    OutOfRange { axis: Axis, value: f64 },
+    /// The latitude is out of range but the pair fits once swapped.
+    Swapped,
+}
+
+/// Check a parsed latitude / longitude pair for out-of-range and swapped values.
+pub fn validate_coordinates(latitude: Option<f64>, longitude: Option<f64>) -> Vec<CoordinateIssue> {
+    let mut issues = Vec::new();
+    if let Some(value) = latitude.filter(|v| v.abs() > MAX_LATITUDE) {
+        issues.push(CoordinateIssue::OutOfRange {
+            axis: Axis::Latitude,
+            value,
+        });
+    }
+    if let Some(value) = longitude.filter(|v| v.abs() > MAX_LONGITUDE) {
+        issues.push(CoordinateIssue::OutOfRange {
+            axis: Axis::Longitude,
+            value,
+        });
+    }
+    if let (Some(lat), Some(lon)) = (latitude, longitude) {
+        if lat.abs() > MAX_LATITUDE && lat.abs() <= MAX_LONGITUDE && lon.abs() <= MAX_LATITUDE {
+            issues.push(CoordinateIssue::Swapped);
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str, axis: Axis) -> (f64, CoordinateConfidence) {
+        let coordinate = parse_coordinate(text, axis).unwrap().unwrap();
+        (
+            (coordinate.value * 1e6).round() / 1e6,
+            coordinate.confidence,
+        )
+    }
+
+    #[test]
+    fn test_parse_coordinate() {
+        use CoordinateConfidence::*;
+
+        assert_eq!(parse("-12.5", Axis::Latitude), (-12.5, Exact));
+        assert_eq!(parse(" +45 ", Axis::Longitude), (45.0, Exact));
+        assert_eq!(parse("12°30'S", Axis::Latitude), (-12.5, Converted));
+        assert_eq!(parse("W 70° 15′ 36″", Axis::Longitude), (-70.26, Converted));
+        assert_eq!(parse("12:30:00", Axis::Latitude), (12.5, Converted));
+        assert_eq!(parse("12.5°", Axis::Latitude), (12.5, Converted));
+        assert_eq!(parse("ca. 3.2N", Axis::Latitude), (3.2, Approximate));
+        assert_eq!(parse("~-70", Axis::Longitude), (-70.0, Approximate));
+        assert_eq!(parse("35.1?", Axis::Longitude), (35.1, Approximate));
+
+        assert_eq!(parse_coordinate("", Axis::Latitude), Ok(None));
+        assert_eq!(parse_coordinate("NA", Axis::Latitude), Ok(None));
+        assert_eq!(
+            parse_coordinate("12°E", Axis::Latitude),
+            Err(CoordinateError::WrongHemisphere('E'))
+        );
+        assert_eq!(
+            parse_coordinate("12°75'", Axis::Latitude),
+            Err(CoordinateError::InvalidMinutes)
+        );
+        assert_eq!(
+            parse_coordinate("near Nairobi", Axis::Latitude),
+            Err(CoordinateError::Unparseable)
+        );
+    }
+
+    #[test]
+    fn test_validate_coordinates() {
+        assert!(validate_coordinates(Some(-1.5), Some(36.8)).is_empty());
+        assert!(validate_coordinates(None, Some(36.8)).is_empty());
+        assert_eq!(
+            validate_coordinates(Some(120.0), Some(-3.0)),
+            vec![
+                CoordinateIssue::OutOfRange {
+                    axis: Axis::Latitude,
+                    value: 120.0
+                },
+                CoordinateIssue::Swapped
+            ]
+        );
+        assert_eq!(
+            validate_coordinates(Some(10.0), Some(200.0)),
+            vec![CoordinateIssue::OutOfRange {
+                axis: Axis::Longitude,
+                value: 200.0
+            }]
+        );
+    }
+}
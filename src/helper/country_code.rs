@@ -1,9 +1,12 @@
 /// List of countries with their respective alpha-2 codes.
 /// This list is based on the ISO 3166-1 alpha-2 standard.
+/// `CountryRegionCode` also resolves names to the alpha-3 and numeric forms.
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+use crate::helper::slug::slugify;
+
 /// List of (alpha-2 code, country name) tuples based on ISO 3166-1 alpha-2.
 pub const COUNTRY_AND_CODES: [(&str, &str); 249] = [
     ("AF", "Afghanistan"),
@@ -257,6 +260,284 @@ pub const COUNTRY_AND_CODES: [(&str, &str); 249] = [
     ("ZW", "Zimbabwe"),
 ];
 
+/// ISO 3166-1 (alpha-2, alpha-3, numeric) codes, in the order of `COUNTRY_AND_CODES`.
+pub const ISO_3166_CODES: [(&str, &str, &str); 249] = [
+    ("AF", "AFG", "004"),
+    ("AX", "ALA", "248"),
+    ("AL", "ALB", "008"),
+    ("DZ", "DZA", "012"),
+    ("AS", "ASM", "016"),
+    ("AD", "AND", "020"),
+    ("AO", "AGO", "024"),
+    ("AI", "AIA", "660"),
+    ("AQ", "ATA", "010"),
+    ("AG", "ATG", "028"),
+    ("AR", "ARG", "032"),
+    ("AM", "ARM", "051"),
+    ("AW", "ABW", "533"),
+    ("AU", "AUS", "036"),
+    ("AT", "AUT", "040"),
+    ("AZ", "AZE", "031"),
+    ("BS", "BHS", "044"),
+    ("BH", "BHR", "048"),
+    ("BD", "BGD", "050"),
+    ("BB", "BRB", "052"),
+    ("BY", "BLR", "112"),
+    ("BE", "BEL", "056"),
+    ("BZ", "BLZ", "084"),
+    ("BJ", "BEN", "204"),
+    ("BM", "BMU", "060"),
+    ("BT", "BTN", "064"),
+    ("BO", "BOL", "068"),
+    ("BQ", "BES", "535"),
+    ("BA", "BIH", "070"),
+    ("BW", "BWA", "072"),
+    ("BV", "BVT", "074"),
+    ("BR", "BRA", "076"),
+    ("IO", "IOT", "086"),
+    ("BN", "BRN", "096"),
+    ("BG", "BGR", "100"),
+    ("BF", "BFA", "854"),
+    ("BI", "BDI", "108"),
+    ("CV", "CPV", "132"),
+    ("KH", "KHM", "116"),
+    ("CM", "CMR", "120"),
+    ("CA", "CAN", "124"),
+    ("KY", "CYM", "136"),
+    ("CF", "CAF", "140"),
+    ("TD", "TCD", "148"),
+    ("CL", "CHL", "152"),
+    ("CN", "CHN", "156"),
+    ("CX", "CXR", "162"),
+    ("CC", "CCK", "166"),
+    ("CO", "COL", "170"),
+    ("KM", "COM", "174"),
+    ("CG", "COG", "178"),
+    ("CD", "COD", "180"),
+    ("CK", "COK", "184"),
+    ("CR", "CRI", "188"),
+    ("CI", "CIV", "384"),
+    ("HR", "HRV", "191"),
+    ("CU", "CUB", "192"),
+    ("CW", "CUW", "531"),
+    ("CY", "CYP", "196"),
+    ("CZ", "CZE", "203"),
+    ("DK", "DNK", "208"),
+    ("DJ", "DJI", "262"),
+    ("DM", "DMA", "212"),
+    ("DO", "DOM", "214"),
+    ("EC", "ECU", "218"),
+    ("EG", "EGY", "818"),
+    ("SV", "SLV", "222"),
+    ("GQ", "GNQ", "226"),
+    ("ER", "ERI", "232"),
+    ("EE", "EST", "233"),
+    ("SZ", "SWZ", "748"),
+    ("ET", "ETH", "231"),
+    ("FK", "FLK", "238"),
+    ("FO", "FRO", "234"),
+    ("FJ", "FJI", "242"),
+    ("FI", "FIN", "246"),
+    ("FR", "FRA", "250"),
+    ("GF", "GUF", "254"),
+    ("PF", "PYF", "258"),
+    ("TF", "ATF", "260"),
+    ("GA", "GAB", "266"),
+    ("GM", "GMB", "270"),
+    ("GE", "GEO", "268"),
+    ("DE", "DEU", "276"),
+    ("GH", "GHA", "288"),
+    ("GI", "GIB", "292"),
+    ("GR", "GRC", "300"),
+    ("GL", "GRL", "304"),
+    ("GD", "GRD", "308"),
+    ("GP", "GLP", "312"),
+    ("GU", "GUM", "316"),
+    ("GT", "GTM", "320"),
+    ("GG", "GGY", "831"),
+    ("GN", "GIN", "324"),
+    ("GW", "GNB", "624"),
+    ("GY", "GUY", "328"),
+    ("HT", "HTI", "332"),
+    ("HM", "HMD", "334"),
+    ("VA", "VAT", "336"),
+    ("HN", "HND", "340"),
+    ("HK", "HKG", "344"),
+    ("HU", "HUN", "348"),
+    ("IS", "ISL", "352"),
+    ("IN", "IND", "356"),
+    ("ID", "IDN", "360"),
+    ("IR", "IRN", "364"),
+    ("IQ", "IRQ", "368"),
+    ("IE", "IRL", "372"),
+    ("IM", "IMN", "833"),
+    ("IL", "ISR", "376"),
+    ("IT", "ITA", "380"),
+    ("JM", "JAM", "388"),
+    ("JP", "JPN", "392"),
+    ("JE", "JEY", "832"),
+    ("JO", "JOR", "400"),
+    ("KZ", "KAZ", "398"),
+    ("KE", "KEN", "404"),
+    ("KI", "KIR", "296"),
+    ("KP", "PRK", "408"),
+    ("KR", "KOR", "410"),
+    ("KW", "KWT", "414"),
+    ("KG", "KGZ", "417"),
+    ("LA", "LAO", "418"),
+    ("LV", "LVA", "428"),
+    ("LB", "LBN", "422"),
+    ("LS", "LSO", "426"),
+    ("LR", "LBR", "430"),
+    ("LY", "LBY", "434"),
+    ("LI", "LIE", "438"),
+    ("LT", "LTU", "440"),
+    ("LU", "LUX", "442"),
+    ("MO", "MAC", "446"),
+    ("MG", "MDG", "450"),
+    ("MW", "MWI", "454"),
+    ("MY", "MYS", "458"),
+    ("MV", "MDV", "462"),
+    ("ML", "MLI", "466"),
+    ("MT", "MLT", "470"),
+    ("MH", "MHL", "584"),
+    ("MQ", "MTQ", "474"),
+    ("MR", "MRT", "478"),
+    ("MU", "MUS", "480"),
+    ("YT", "MYT", "175"),
+    ("MX", "MEX", "484"),
+    ("FM", "FSM", "583"),
+    ("MD", "MDA", "498"),
+    ("MC", "MCO", "492"),
+    ("MN", "MNG", "496"),
+    ("ME", "MNE", "499"),
+    ("MS", "MSR", "500"),
+    ("MA", "MAR", "504"),
+    ("MZ", "MOZ", "508"),
+    ("MM", "MMR", "104"),
+    ("NA", "NAM", "516"),
+    ("NR", "NRU", "520"),
+    ("NP", "NPL", "524"),
+    ("NL", "NLD", "528"),
+    ("NC", "NCL", "540"),
+    ("NZ", "NZL", "554"),
+    ("NI", "NIC", "558"),
+    ("NE", "NER", "562"),
+    ("NG", "NGA", "566"),
+    ("NU", "NIU", "570"),
+    ("NF", "NFK", "574"),
+    ("MK", "MKD", "807"),
+    ("MP", "MNP", "580"),
+    ("NO", "NOR", "578"),
+    ("OM", "OMN", "512"),
+    ("PK", "PAK", "586"),
+    ("PW", "PLW", "585"),
+    ("PS", "PSE", "275"),
+    ("PA", "PAN", "591"),
+    ("PG", "PNG", "598"),
+    ("PY", "PRY", "600"),
+    ("PE", "PER", "604"),
+    ("PH", "PHL", "608"),
+    ("PN", "PCN", "612"),
+    ("PL", "POL", "616"),
+    ("PT", "PRT", "620"),
+    ("PR", "PRI", "630"),
+    ("QA", "QAT", "634"),
+    ("RE", "REU", "638"),
+    ("RO", "ROU", "642"),
+    ("RU", "RUS", "643"),
+    ("RW", "RWA", "646"),
+    ("BL", "BLM", "652"),
+    ("SH", "SHN", "654"),
+    ("KN", "KNA", "659"),
+    ("LC", "LCA", "662"),
+    ("MF", "MAF", "663"),
+    ("PM", "SPM", "666"),
+    ("VC", "VCT", "670"),
+    ("WS", "WSM", "882"),
+    ("SM", "SMR", "674"),
+    ("ST", "STP", "678"),
+    ("SA", "SAU", "682"),
+    ("SN", "SEN", "686"),
+    ("RS", "SRB", "688"),
+    ("SC", "SYC", "690"),
+    ("SL", "SLE", "694"),
+    ("SG", "SGP", "702"),
+    ("SX", "SXM", "534"),
+    ("SK", "SVK", "703"),
+    ("SI", "SVN", "705"),
+    ("SB", "SLB", "090"),
+    ("SO", "SOM", "706"),
+    ("ZA", "ZAF", "710"),
+    ("GS", "SGS", "239"),
+    ("SS", "SSD", "728"),
+    ("ES", "ESP", "724"),
+    ("LK", "LKA", "144"),
+    ("SD", "SDN", "729"),
+    ("SR", "SUR", "740"),
+    ("SJ", "SJM", "744"),
+    ("SE", "SWE", "752"),
+    ("CH", "CHE", "756"),
+    ("SY", "SYR", "760"),
+    ("TW", "TWN", "158"),
+    ("TJ", "TJK", "762"),
+    ("TZ", "TZA", "834"),
+    ("TH", "THA", "764"),
+    ("TL", "TLS", "626"),
+    ("TG", "TGO", "768"),
+    ("TK", "TKL", "772"),
+    ("TO", "TON", "776"),
+    ("TT", "TTO", "780"),
+    ("TN", "TUN", "788"),
+    ("TR", "TUR", "792"),
+    ("TM", "TKM", "795"),
+    ("TC", "TCA", "796"),
+    ("TV", "TUV", "798"),
+    ("UG", "UGA", "800"),
+    ("UA", "UKR", "804"),
+    ("AE", "ARE", "784"),
+    ("GB", "GBR", "826"),
+    ("UM", "UMI", "581"),
+    ("US", "USA", "840"),
+    ("UY", "URY", "858"),
+    ("UZ", "UZB", "860"),
+    ("VU", "VUT", "548"),
+    ("VE", "VEN", "862"),
+    ("VN", "VNM", "704"),
+    ("VG", "VGB", "092"),
+    ("VI", "VIR", "850"),
+    ("WF", "WLF", "876"),
+    ("EH", "ESH", "732"),
+    ("YE", "YEM", "887"),
+    ("ZM", "ZMB", "894"),
+    ("ZW", "ZWE", "716"),
+];
+
+/// Historical and alternate country names (alpha-2 code, name) accepted by
+/// `CountryRegionCode::code_for_name` in addition to the names above.
+pub const ALTERNATE_COUNTRY_NAMES: [(&str, &str); 20] = [
+    ("BF", "Upper Volta"),
+    ("BJ", "Dahomey"),
+    ("CD", "Zaire"),
+    ("CD", "DR Congo"),
+    ("CG", "Congo-Brazzaville"),
+    ("CI", "Ivory Coast"),
+    ("GB", "Great Britain"),
+    ("GB", "UK"),
+    ("KH", "Kampuchea"),
+    ("LK", "Ceylon"),
+    ("MK", "Macedonia"),
+    ("MM", "Burma"),
+    ("NL", "Holland"),
+    ("SZ", "Swaziland"),
+    ("TR", "Türkiye"),
+    ("TW", "Formosa"),
+    ("TZ", "Zanzibar"),
+    ("US", "USA"),
+    ("VA", "Vatican City"),
+    ("ZW", "Rhodesia"),
+];
+
 // Country names found in the MDD data that are not in the ISO list.
 pub const NON_STANDARD_COUNTRY_CODES: [(&str, &str); 50] = [
     ("IN", "Andaman and Nicobar Islands"),
@@ -392,6 +673,13 @@ lazy_static::lazy_static! {
             .collect()
     };
 
+    /// A static map from alpha-2 codes to ISO country names.
+    static ref COUNTRY_NAME_MAP: HashMap<String, String> = {
+        COUNTRY_AND_CODES.iter()
+            .map(|&(code, name)| (code.to_string(), name.to_string()))
+            .collect()
+    };
+
     /// A static map that maps non-standard country names to their respective alpha-2 codes.
     static ref NON_STANDARD_COUNTRY_MAP: HashMap<String, String> = {
         NON_STANDARD_COUNTRY_CODES.iter()
@@ -406,6 +694,46 @@ lazy_static::lazy_static! {
             .collect()
     };
 
+    /// A static map from alpha-2 codes to all three ISO 3166-1 codes.
+    static ref ISO_CODE_MAP: HashMap<String, IsoCountryCode> = {
+        ISO_3166_CODES.iter()
+            .map(|&(alpha2, alpha3, numeric)| {
+                let code = IsoCountryCode {
+                    alpha2: alpha2.to_string(),
+                    alpha3: alpha3.to_string(),
+                    numeric: numeric.to_string(),
+                };
+                (alpha2.to_string(), code)
+            })
+            .collect()
+    };
+
+    /// A static map from alpha-3 and numeric codes to alpha-2 codes.
+    static ref ISO_ALPHA2_MAP: HashMap<String, String> = {
+        ISO_3166_CODES.iter()
+            .flat_map(|&(alpha2, alpha3, numeric)| {
+                [
+                    (alpha3.to_string(), alpha2.to_string()),
+                    (numeric.to_string(), alpha2.to_string()),
+                ]
+            })
+            .collect()
+    };
+
+    /// A static map from normalized country names (ISO, non-standard,
+    /// alternate, and U.S. state names) to ISO alpha-2 codes.
+    static ref ISO_NAME_MAP: HashMap<String, String> = {
+        let states = US_STATE_NAMES.iter().map(|&name| ("US", name));
+        COUNTRY_AND_CODES.iter()
+            .chain(NON_STANDARD_COUNTRY_CODES.iter())
+            .chain(ALTERNATE_COUNTRY_NAMES.iter())
+            .copied()
+            .chain(states)
+            .filter(|(code, _)| ISO_CODE_MAP.contains_key(*code))
+            .map(|(code, name)| (normalize_name(name), code.to_string()))
+            .collect()
+    };
+
     /// A static map that combines both standard and non-standard country names.
     static ref ALL_COUNTRY_REGION_MAP: HashMap<String, String> = {
         let mut map = COUNTRY_MAP.clone();
@@ -421,12 +749,12 @@ lazy_static::lazy_static! {
 pub fn get_country_code(country_name: &str) -> String {
     // If not found, check the non-standard country map
     if let Some(code) = ALL_COUNTRY_REGION_MAP.get(country_name) {
-        return code.to_string();
+        code.to_string()
     } else {
         // If still not found, return the country name as is
         // This is useful for cases where the country name is not in the list
         // and we want to keep it as a fallback.
-        return country_name.to_string();
+        country_name.to_string()
     }
 }
 
@@ -445,11 +773,62 @@ fn get_country_region_map() -> &'static HashMap<String, String> {
     &ALL_COUNTRY_REGION_MAP
 }
 
+/// Lower-case, fold accents, spell out `&`, and drop a leading "the", so
+/// "Côte d'Ivoire", "Cote d'Ivoire", and "cote d ivoire" compare equal.
+fn normalize_name(name: &str) -> String {
+    let slug = slugify(&name.replace('&', " and "));
+    match slug.strip_prefix("the-") {
+        Some(rest) => rest.to_string(),
+        None => slug,
+    }
+}
+
+/// ISO 3166-1 code forms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IsoCodeForm {
+    /// Two-letter code, e.g. `CI`.
+    Alpha2,
+    /// Three-letter code, e.g. `CIV`.
+    Alpha3,
+    /// Three-digit code, e.g. `384`.
+    Numeric,
+}
+
+/// The three ISO 3166-1 codes of a country.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsoCountryCode {
+    pub alpha2: String,
+    pub alpha3: String,
+    /// Numeric code with leading zeros, e.g. `004`.
+    pub numeric: String,
+}
+
+impl IsoCountryCode {
+    pub fn get(&self, form: IsoCodeForm) -> &str {
+        match form {
+            IsoCodeForm::Alpha2 => &self.alpha2,
+            IsoCodeForm::Alpha3 => &self.alpha3,
+            IsoCodeForm::Numeric => &self.numeric,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CountryRegionCode {
     pub region_to_code: HashMap<String, String>,
     pub code_to_region: HashMap<String, String>,
+    /// ISO 3166-1 codes keyed by alpha-2 code.
+    #[serde(default)]
+    pub iso_codes: HashMap<String, IsoCountryCode>,
+}
+
+impl Default for CountryRegionCode {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CountryRegionCode {
@@ -462,6 +841,7 @@ impl CountryRegionCode {
         Self {
             region_to_code,
             code_to_region,
+            iso_codes: ISO_CODE_MAP.clone(),
         }
     }
 
@@ -473,6 +853,64 @@ impl CountryRegionCode {
         self.code_to_region.get(code)
     }
 
+    /// ISO 3166-1 codes of a country name.
+    ///
+    /// Accepts ISO names, the spellings used by MDD, historical or alternate
+    /// names (e.g. "Ivory Coast"), and U.S. state names (resolving to `US`).
+    /// Case, accents, `&` vs "and", and a leading "the" are ignored.
+    /// Non-country regions (e.g. "Canary Islands") have no ISO code.
+    pub fn iso_code_for_name(&self, name: &str) -> Option<&IsoCountryCode> {
+        let alpha2 = ISO_NAME_MAP.get(&normalize_name(name))?;
+        self.iso_codes.get(alpha2)
+    }
+
+    /// ISO 3166-1 code of a country name in the requested form.
+    pub fn code_for_name(&self, name: &str, form: IsoCodeForm) -> Option<&str> {
+        self.iso_code_for_name(name).map(|code| code.get(form))
+    }
+
+    /// Country name for an alpha-2, alpha-3, or numeric code (case-insensitive,
+    /// numeric codes with or without leading zeros).
+    ///
+    /// ISO codes resolve to the ISO display name; other codes (e.g. MDD region
+    /// codes such as `ALS`) fall back to `get_region`.
+    pub fn name_for_code(&self, code: &str) -> Option<&str> {
+        let code = code.trim().to_uppercase();
+        let iso_key = if !code.is_empty() && code.chars().all(|c| c.is_ascii_digit()) {
+            format!("{:0>3}", code)
+        } else {
+            code.clone()
+        };
+        let alpha2 = if self.iso_codes.contains_key(&iso_key) {
+            Some(&iso_key)
+        } else {
+            ISO_ALPHA2_MAP.get(&iso_key)
+        };
+        alpha2
+            .and_then(|alpha2| COUNTRY_NAME_MAP.get(alpha2))
+            .map(String::as_str)
+            .or_else(|| self.get_region(&code).map(String::as_str))
+    }
+
+    /// ISO alpha-2 code for an alpha-2, alpha-3, or numeric code.
+    ///
+    /// Unlike `name_for_code`, MDD region codes do not resolve.
+    pub fn alpha2_for_code(&self, code: &str) -> Option<&str> {
+        let code = code.trim().to_uppercase();
+        let iso_key = if !code.is_empty() && code.chars().all(|c| c.is_ascii_digit()) {
+            format!("{:0>3}", code)
+        } else {
+            code
+        };
+        match self.iso_codes.get(&iso_key) {
+            Some(iso_code) => Some(iso_code.alpha2.as_str()),
+            None => ISO_ALPHA2_MAP
+                .get(&iso_key)
+                .and_then(|alpha2| self.iso_codes.get(alpha2))
+                .map(|iso_code| iso_code.alpha2.as_str()),
+        }
+    }
+
     pub fn to_json(&self) -> String {
         serde_json::to_string(self).expect("Failed to serialize CountryRegionCode")
     }
@@ -482,3 +920,51 @@ impl CountryRegionCode {
         std::fs::write(path, json).expect("Failed to write CountryRegionCode to file");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iso_code_lookup() {
+        let codes = CountryRegionCode::new();
+        for name in [
+            "Côte d'Ivoire",
+            "Cote d'Ivoire",
+            "Ivory Coast",
+            "ivory coast",
+        ] {
+            assert_eq!(codes.code_for_name(name, IsoCodeForm::Alpha3), Some("CIV"));
+        }
+        assert_eq!(
+            codes.code_for_name("Trinidad and Tobago", IsoCodeForm::Numeric),
+            Some("780")
+        );
+        assert_eq!(
+            codes.code_for_name("Trinidad & Tobago", IsoCodeForm::Alpha2),
+            Some("TT")
+        );
+        assert_eq!(
+            codes.code_for_name("The Gambia", IsoCodeForm::Alpha2),
+            Some("GM")
+        );
+        assert_eq!(
+            codes.code_for_name("Texas", IsoCodeForm::Alpha3),
+            Some("USA")
+        );
+        assert_eq!(
+            codes.code_for_name("Canary Islands", IsoCodeForm::Alpha2),
+            None
+        );
+
+        assert_eq!(codes.name_for_code("civ"), Some("Côte d'Ivoire"));
+        assert_eq!(codes.name_for_code("CI"), Some("Côte d'Ivoire"));
+        assert_eq!(codes.name_for_code("4"), Some("Afghanistan"));
+        assert_eq!(codes.name_for_code("ALS"), Some("Alaska"));
+        assert_eq!(codes.name_for_code("ZZZ"), None);
+        assert_eq!(codes.alpha2_for_code("usa"), Some("US"));
+        assert_eq!(codes.alpha2_for_code("840"), Some("US"));
+        assert_eq!(codes.alpha2_for_code("ALS"), None);
+        assert_eq!(codes.iso_codes.len(), 249);
+    }
+}
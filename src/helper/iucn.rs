@@ -0,0 +1,194 @@
+//! IUCN Red List category codes as used in the MDD `iucnStatus` column.
+//!
+//! Categories are ordered by extinction risk, `LC` < `NT` < `VU` < `EN` <
+//! `CR` < `EW` < `EX`, followed by `DD`, `NE`, and unknown values, which say
+//! nothing about risk. Values that are not exactly a category code are kept
+//! verbatim in `Unknown` by `IucnStatus::from_verbatim` and serde, so a record
+//! round-trips to the string it was read from. `FromStr` is lenient instead
+//! and resolves annotated values such as `"lc (as Lepus victoriae)"`.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// IUCN Red List category.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IucnStatus {
+    /// Least Concern.
+    LC,
+    /// Near Threatened.
+    NT,
+    /// Vulnerable.
+    VU,
+    /// Endangered.
+    EN,
+    /// Critically Endangered.
+    CR,
+    /// Extinct in the Wild.
+    EW,
+    /// Extinct.
+    EX,
+    /// Data Deficient.
+    DD,
+    /// Not Evaluated.
+    NE,
+    /// Any other value, verbatim (e.g. an empty status).
+    Unknown(String),
+}
+
+impl IucnStatus {
+    /// All categories, ordered by extinction risk, then `DD` and `NE`.
+    pub const CATEGORIES: [Self; 9] = [
+        Self::LC,
+        Self::NT,
+        Self::VU,
+        Self::EN,
+        Self::CR,
+        Self::EW,
+        Self::EX,
+        Self::DD,
+        Self::NE,
+    ];
+
+    /// Parse a status, keeping values that are not exactly a category code
+    /// (e.g. `"lc"` or `"LC (as Lepus victoriae)"`) in `Unknown`, so that
+    /// `code()` returns the value unchanged.
+    pub fn from_verbatim(value: &str) -> Self {
+        match value.parse::<Self>() {
+            Ok(status) if status.code() == value => status,
+            _ => Self::Unknown(value.to_string()),
+        }
+    }
+
+    /// The two-letter category code, or the verbatim value of `Unknown`.
+    pub fn code(&self) -> &str {
+        match self {
+            Self::LC => "LC",
+            Self::NT => "NT",
+            Self::VU => "VU",
+            Self::EN => "EN",
+            Self::CR => "CR",
+            Self::EW => "EW",
+            Self::EX => "EX",
+            Self::DD => "DD",
+            Self::NE => "NE",
+            Self::Unknown(value) => value,
+        }
+    }
+
+    /// Whether the category is one of the threatened categories (VU, EN, CR).
+    pub fn is_threatened(&self) -> bool {
+        matches!(self, Self::VU | Self::EN | Self::CR)
+    }
+
+    /// Whether the category is Extinct or Extinct in the Wild.
+    pub fn is_extinct(&self) -> bool {
+        matches!(self, Self::EW | Self::EX)
+    }
+
+    /// Whether the species was assessed with adequate data, i.e. the
+    /// category is on the risk scale (LC through EX).
+    pub fn is_assessed(&self) -> bool {
+        !matches!(self, Self::DD | Self::NE | Self::Unknown(_))
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown(_))
+    }
+}
+
+impl fmt::Display for IucnStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for IucnStatus {
+    type Err = String;
+
+    /// Parse a category code. Only the first whitespace-delimited token is
+    /// considered, so annotated values such as `"LC (as Lepus victoriae)"`
+    /// resolve to their category.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.split_whitespace().next().unwrap_or("");
+        match code.to_uppercase().as_str() {
+            "LC" => Ok(Self::LC),
+            "NT" => Ok(Self::NT),
+            "VU" => Ok(Self::VU),
+            "EN" => Ok(Self::EN),
+            "CR" => Ok(Self::CR),
+            "EW" => Ok(Self::EW),
+            "EX" => Ok(Self::EX),
+            "DD" => Ok(Self::DD),
+            "NE" => Ok(Self::NE),
+            _ => Err(format!("Unknown IUCN status: '{}'", s)),
+        }
+    }
+}
+
+impl Serialize for IucnStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for IucnStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(Self::from_verbatim(&value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_iucn_status() {
+        assert_eq!("CR".parse::<IucnStatus>(), Ok(IucnStatus::CR));
+        assert_eq!(
+            "LC (as Lepus victoriae)".parse::<IucnStatus>(),
+            Ok(IucnStatus::LC)
+        );
+        assert!("XX".parse::<IucnStatus>().is_err());
+        assert_eq!(
+            IucnStatus::from_verbatim("XX"),
+            IucnStatus::Unknown("XX".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_verbatim_round_trip() {
+        assert_eq!(IucnStatus::from_verbatim("LC"), IucnStatus::LC);
+        for value in ["lc", "LC (as Lepus victoriae)", " LC"] {
+            let status = IucnStatus::from_verbatim(value);
+            assert!(status.is_unknown());
+            assert_eq!(status.code(), value);
+            let json = serde_json::to_string(&status).unwrap();
+            assert_eq!(serde_json::from_str::<IucnStatus>(&json).unwrap(), status);
+        }
+    }
+
+    #[test]
+    fn test_iucn_status_order_and_serde() {
+        assert!(IucnStatus::LC < IucnStatus::VU);
+        assert!(IucnStatus::CR < IucnStatus::EX);
+        assert!(IucnStatus::EX < IucnStatus::DD);
+        let threatened: Vec<&str> = IucnStatus::CATEGORIES
+            .iter()
+            .filter(|status| status.is_threatened())
+            .map(IucnStatus::code)
+            .collect();
+        assert_eq!(threatened, ["VU", "EN", "CR"]);
+        assert!(IucnStatus::EW.is_extinct());
+        assert!(!IucnStatus::DD.is_assessed());
+
+        let json = serde_json::to_string(&[IucnStatus::EN, IucnStatus::from_verbatim("")]).unwrap();
+        assert_eq!(json, r#"["EN",""]"#);
+        let parsed: Vec<IucnStatus> = serde_json::from_str(r#"["CR","NA"]"#).unwrap();
+        assert_eq!(
+            parsed,
+            [IucnStatus::CR, IucnStatus::Unknown("NA".to_string())]
+        );
+    }
+}
@@ -1,3 +1,53 @@
+pub mod cache;
+pub mod checksum;
+pub mod coords;
 pub mod country_code;
+pub mod iucn;
+pub mod slug;
+pub mod voucher;
 
 pub const MDD_LIST_SEPARATOR: &str = "|";
+/// Placeholder used by MDD for empty or not applicable values.
+pub const MDD_MISSING_VALUE: &str = "NA";
+
+/// Split an MDD multi-value field on `MDD_LIST_SEPARATOR`, trimming each
+/// value and dropping empty entries. A field equal to `MDD_MISSING_VALUE`
+/// yields no values.
+pub fn split_mdd_list(value: &str) -> impl Iterator<Item = &str> {
+    let value = if value.trim() == MDD_MISSING_VALUE {
+        ""
+    } else {
+        value
+    };
+    value
+        .split(MDD_LIST_SEPARATOR)
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+/// Name lookup key: lower-cased, `_` read as a space, and whitespace
+/// collapsed, so `Bunolagus_monticularis` and ` bunolagus  Monticularis`
+/// compare equal. Authority strings are compared through it as well.
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.replace('_', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Levenshtein edit distance between two strings, counted in characters.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
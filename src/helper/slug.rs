@@ -0,0 +1,185 @@
+//! URL-safe slugs for species and synonym records.
+//!
+//! Slugs are built from the name and year of description, e.g.
+//! `Panthera_leo` (1758) → `panthera-leo-1758`. Species and synonyms use
+//! separate namespaces. Within a namespace a slug is only given once; a
+//! later record with the same base slug gets its id appended
+//! (`panthera-leo-1758-1006040`). Which of the colliding records keeps the
+//! bare slug therefore depends on record order.
+//!
+//! `SlugAssigner::with_previous` keeps the slugs of a previous release for
+//! every id that still exists (`mdd json --previous`), so permalinks survive
+//! renames and reorderings; only then are slugs independent of record order.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::parser::{mdd::MddData, synonyms::SynonymData};
+
+/// Lower-case `text`, fold common accented Latin letters to ASCII, and join
+/// the remaining alphanumeric runs with `-`.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    let mut pending_dash = false;
+    for c in text.chars().flat_map(char::to_lowercase) {
+        let folded = fold_ascii(c);
+        if folded.is_empty() {
+            pending_dash = !slug.is_empty();
+            continue;
+        }
+        if pending_dash {
+            slug.push('-');
+            pending_dash = false;
+        }
+        slug.push_str(folded);
+    }
+    slug
+}
+
+pub(crate) fn fold_ascii(c: char) -> &'static str {
+    const ALNUM: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+    if c.is_ascii_alphanumeric() {
+        let idx = ALNUM.find(c).expect("ASCII alphanumeric");
+        return &ALNUM[idx..idx + 1];
+    }
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => "a",
+        'æ' => "ae",
+        'ç' => "c",
+        'è' | 'é' | 'ê' | 'ë' => "e",
+        'ì' | 'í' | 'î' | 'ï' => "i",
+        'ñ' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => "o",
+        'œ' => "oe",
+        'ß' => "ss",
+        'ù' | 'ú' | 'û' | 'ü' => "u",
+        'ý' | 'ÿ' => "y",
+        _ => "",
+    }
+}
+
+/// Base slug of a species: scientific name plus year of description.
+pub fn species_slug(species: &MddData) -> String {
+    if species.authority_species_year > 0 {
+        slugify(&format!(
+            "{} {}",
+            species.sci_name, species.authority_species_year
+        ))
+    } else {
+        slugify(&species.sci_name)
+    }
+}
+
+/// Base slug of a synonym: original combination (or root name) plus year.
+pub fn synonym_slug(synonym: &SynonymData) -> String {
+    let name = if synonym.original_combination.trim().is_empty() {
+        &synonym.root_name
+    } else {
+        &synonym.original_combination
+    };
+    slugify(&format!("{} {}", name, synonym.year))
+}
+
+/// Hands out unique slugs within one namespace.
+#[derive(Debug, Clone, Default)]
+pub struct SlugAssigner {
+    used: HashSet<String>,
+    reserved: HashMap<u32, String>,
+}
+
+impl SlugAssigner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve previously published slugs by record id.
+    ///
+    /// Only reserve ids present in the current records, otherwise slugs of
+    /// removed records stay blocked.
+    pub fn with_previous<I>(previous: I) -> Self
+    where
+        I: IntoIterator<Item = (u32, String)>,
+    {
+        let mut assigner = Self::new();
+        for (id, slug) in previous {
+            if slug.is_empty() || assigner.used.contains(&slug) {
+                continue;
+            }
+            assigner.used.insert(slug.clone());
+            assigner.reserved.insert(id, slug);
+        }
+        assigner
+    }
+
+    /// Unique slug for record `id` with the given base slug.
+    pub fn assign(&mut self, id: u32, base: &str) -> String {
+        if let Some(slug) = self.reserved.get(&id) {
+            return slug.clone();
+        }
+        let slug = if base.is_empty() {
+            id.to_string()
+        } else if self.used.contains(base) {
+            format!("{}-{}", base, id)
+        } else {
+            base.to_string()
+        };
+        self.used.insert(slug.clone());
+        self.reserved.insert(id, slug.clone());
+        slug
+    }
+}
+
+/// Set `slug` on every species record.
+pub fn assign_species_slugs(species: &mut [MddData], assigner: &mut SlugAssigner) {
+    for record in species {
+        record.slug = assigner.assign(record.id, &species_slug(record));
+    }
+}
+
+/// Set `slug` on every synonym record.
+pub fn assign_synonym_slugs(synonyms: &mut [SynonymData], assigner: &mut SlugAssigner) {
+    for record in synonyms {
+        record.slug = assigner.assign(record.syn_id, &synonym_slug(record));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Panthera_leo 1758"), "panthera-leo-1758");
+        assert_eq!(slugify("  Mus (Coelomys) pahari "), "mus-coelomys-pahari");
+        assert_eq!(slugify("Cervus élaphus"), "cervus-elaphus");
+        assert_eq!(slugify("--"), "");
+    }
+
+    #[test]
+    fn test_assign_slugs() {
+        let mut species = vec![
+            MddData {
+                id: 1,
+                sci_name: "Panthera_leo".to_string(),
+                authority_species_year: 1758,
+                ..MddData::new()
+            },
+            MddData {
+                id: 2,
+                sci_name: "Panthera_leo".to_string(),
+                authority_species_year: 1758,
+                ..MddData::new()
+            },
+        ];
+        assign_species_slugs(&mut species, &mut SlugAssigner::new());
+        assert_eq!(species[0].slug, "panthera-leo-1758");
+        assert_eq!(species[1].slug, "panthera-leo-1758-2");
+
+        // A renamed species keeps its published slug.
+        let previous = vec![(2, "panthera-leo-1758".to_string())];
+        let mut assigner = SlugAssigner::with_previous(previous);
+        species[1].sci_name = "Panthera_spelaea".to_string();
+        assign_species_slugs(&mut species, &mut assigner);
+        assert_eq!(species[1].slug, "panthera-leo-1758");
+        assert_eq!(species[0].slug, "panthera-leo-1758-1");
+    }
+}
@@ -0,0 +1,326 @@
+//! Type voucher identifiers and museum codes.
+//!
+//! `typeVoucher` holds one or more specimen identifiers separated by commas
+//! or semicolons, written in several styles:
+//!
+//! ```text
+//! USNM:MAMM:93691                 Darwin Core triplet (institution:collection:number)
+//! RMNH.MAM.17930                  dotted triplet
+//! MNHN-ZM-MO-1992-2010            hyphenated institution and collection prefix
+//! AMNH M-43174, ZIN S. 12476      institution code and catalog number
+//! BM(NH) 1901.2.3.4               historical code, normalized to BMNH
+//! USNM:MAMM:1163 (= MCZ:Mamm:5460)
+//! AMG (number not known)
+//! ```
+//!
+//! `Voucher::parse_list` splits the field and `Voucher::parse` reads one
+//! identifier. Institution codes are checked against `INSTITUTION_CODES`, a
+//! bundled list of mammal collections following GRSciColl codes, after
+//! mapping historical variants with `INSTITUTION_ALIASES`.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::MDD_MISSING_VALUE;
+
+/// List of (institution code, institution name) tuples of collections
+/// holding mammal type specimens, following GRSciColl codes.
+pub const INSTITUTION_CODES: [(&str, &str); 52] = [
+    ("AM", "Australian Museum"),
+    ("AMG", "Albany Museum, Grahamstown"),
+    ("AMNH", "American Museum of Natural History"),
+    ("ANSP", "Academy of Natural Sciences of Drexel University"),
+    ("BMNH", "Natural History Museum, London"),
+    ("CAS", "California Academy of Sciences"),
+    ("CM", "Carnegie Museum of Natural History"),
+    ("CMN", "Canadian Museum of Nature"),
+    (
+        "CVULA",
+        "Colección de Vertebrados, Universidad de Los Andes",
+    ),
+    ("EBD", "Estación Biológica de Doñana"),
+    ("FMNH", "Field Museum of Natural History"),
+    ("HZM", "Harrison Zoological Museum"),
+    ("IOZ", "Institute of Zoology, Chinese Academy of Sciences"),
+    ("KIZ", "Kunming Institute of Zoology"),
+    ("KU", "University of Kansas Biodiversity Institute"),
+    ("LACM", "Natural History Museum of Los Angeles County"),
+    (
+        "LSUMZ",
+        "Louisiana State University Museum of Natural Science",
+    ),
+    ("MCZ", "Museum of Comparative Zoology, Harvard University"),
+    ("MHNG", "Muséum d'histoire naturelle de Genève"),
+    ("MNCN", "Museo Nacional de Ciencias Naturales"),
+    ("MNHN", "Muséum national d'Histoire naturelle"),
+    ("MSB", "Museum of Southwestern Biology"),
+    (
+        "MVZ",
+        "Museum of Vertebrate Zoology, University of California",
+    ),
+    ("MZB", "Museum Zoologicum Bogoriense"),
+    ("MZUSP", "Museu de Zoologia da Universidade de São Paulo"),
+    ("NHMW", "Naturhistorisches Museum Wien"),
+    ("NMK", "National Museums of Kenya"),
+    ("NMV", "Museums Victoria"),
+    ("NMZB", "Natural History Museum of Zimbabwe"),
+    ("OMNH", "Sam Noble Oklahoma Museum of Natural History"),
+    ("RMCA", "Royal Museum for Central Africa"),
+    ("RMNH", "Naturalis Biodiversity Center"),
+    ("ROM", "Royal Ontario Museum"),
+    ("SAM", "South African Museum, Iziko Museums"),
+    ("SMF", "Senckenberg Naturmuseum Frankfurt"),
+    ("SMNS", "Staatliches Museum für Naturkunde Stuttgart"),
+    ("TM", "Ditsong National Museum of Natural History"),
+    ("TTU", "Museum of Texas Tech University"),
+    ("UAM", "University of Alaska Museum"),
+    ("UFPE", "Universidade Federal de Pernambuco"),
+    ("UMMZ", "University of Michigan Museum of Zoology"),
+    (
+        "USNM",
+        "National Museum of Natural History, Smithsonian Institution",
+    ),
+    ("WAM", "Western Australian Museum"),
+    ("YPM", "Yale Peabody Museum"),
+    ("ZFMK", "Zoologisches Forschungsmuseum Alexander Koenig"),
+    ("ZIN", "Zoological Institute, Russian Academy of Sciences"),
+    ("ZMA", "Zoological Museum Amsterdam"),
+    ("ZMB", "Museum für Naturkunde Berlin"),
+    ("ZMMU", "Zoological Museum of Moscow State University"),
+    ("ZMUC", "Natural History Museum of Denmark"),
+    ("ZSI", "Zoological Survey of India"),
+    ("ZSM", "Zoologische Staatssammlung München"),
+];
+
+/// List of (variant, institution code) tuples for historical or alternative
+/// codes of the same institution.
+pub const INSTITUTION_ALIASES: [(&str, &str); 4] = [
+    ("BM", "BMNH"),
+    ("BM(NH)", "BMNH"),
+    ("NHMUK", "BMNH"),
+    ("NMNH", "USNM"),
+];
+
+const UNKNOWN_NUMBER: &str = "number not known";
+
+/// One specimen identifier from `typeVoucher`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Voucher {
+    /// Institution code with aliases resolved, e.g. `BMNH`.
+    pub institution_code: String,
+    /// Collection code of a triplet, e.g. `Mamm` or `ZM-MO`.
+    pub collection_code: Option<String>,
+    /// Catalog number; `None` when the number is not known.
+    pub catalog_number: Option<String>,
+    /// Other identifiers of the same specimen from `(= ...)` remarks.
+    pub equivalents: Vec<String>,
+}
+
+impl Voucher {
+    /// Parse one identifier; `None` when it does not start with an
+    /// institution code.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (main, remarks) = match text.find(" (") {
+            Some(index) => (&text[..index], &text[index..]),
+            None => (text, ""),
+        };
+        let (institution, collection, number) = split_identifier(main.trim())?;
+        if !is_code(institution) {
+            return None;
+        }
+        let mut equivalents = Vec::new();
+        let mut number_known = true;
+        for remark in remarks.split(')') {
+            let remark = remark.trim().trim_start_matches('(').trim();
+            if let Some(equivalent) = remark.strip_prefix('=') {
+                equivalents.push(equivalent.trim().trim_matches('"').to_string());
+            } else if remark == UNKNOWN_NUMBER {
+                number_known = false;
+            }
+        }
+        Some(Self {
+            institution_code: normalize_institution_code(institution),
+            collection_code: collection.map(|c| c.to_string()),
+            catalog_number: Some(number)
+                .filter(|number| number_known && !number.is_empty())
+                .map(|number| number.to_string()),
+            equivalents,
+        })
+    }
+
+    /// Parse a whole `typeVoucher` value, skipping identifiers that cannot be
+    /// read. `NA` yields no vouchers.
+    pub fn parse_list(type_voucher: &str) -> Vec<Self> {
+        split_vouchers(type_voucher)
+            .into_iter()
+            .filter_map(Self::parse)
+            .collect()
+    }
+
+    /// Whether the institution code is in `INSTITUTION_CODES`.
+    pub fn is_known_institution(&self) -> bool {
+        institution_name(&self.institution_code).is_some()
+    }
+}
+
+impl fmt::Display for Voucher {
+    /// Renders `institution:collection:number`, or `institution number`
+    /// without a collection code.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let number = self.catalog_number.as_deref().unwrap_or("");
+        match &self.collection_code {
+            Some(collection) => write!(f, "{}:{}:{}", self.institution_code, collection, number),
+            None if number.is_empty() => write!(f, "{}", self.institution_code),
+            None => write!(f, "{} {}", self.institution_code, number),
+        }
+    }
+}
+
+/// Resolve historical codes such as `BM(NH)` to the current code.
+pub fn normalize_institution_code(code: &str) -> String {
+    let code = code.trim();
+    INSTITUTION_ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(code))
+        .map_or_else(|| code.to_uppercase(), |(_, code)| code.to_string())
+}
+
+/// Name of the institution with `code` (aliases resolved), if it is bundled.
+pub fn institution_name(code: &str) -> Option<&'static str> {
+    let code = normalize_institution_code(code);
+    INSTITUTION_CODES
+        .iter()
+        .find(|(known, _)| *known == code)
+        .map(|(_, name)| *name)
+}
+
+/// Split `main` into institution, collection, and catalog number.
+fn split_identifier(main: &str) -> Option<(&str, Option<&str>, &str)> {
+    if main.contains(':') {
+        let mut parts = main.splitn(3, ':');
+        let institution = parts.next()?;
+        let second = parts.next().unwrap_or("");
+        return Some(match parts.next() {
+            Some(number) => (institution, Some(second), number),
+            None => (institution, None, second),
+        });
+    }
+    if let Some((institution, number)) = main.split_once(' ') {
+        return Some((institution, None, number.trim()));
+    }
+    for separator in ['.', '-'] {
+        let mut parts = main.split(separator);
+        let institution = parts.next()?;
+        let segments: Vec<&str> = parts.collect();
+        let collection_len = segments
+            .iter()
+            .take_while(|segment| is_collection(segment))
+            .count();
+        if collection_len > 0 && collection_len < segments.len() {
+            let collection_end = institution.len()
+                + segments[..collection_len]
+                    .iter()
+                    .map(|segment| segment.len() + 1)
+                    .sum::<usize>();
+            return Some((
+                institution,
+                Some(&main[institution.len() + 1..collection_end]),
+                &main[collection_end + 1..],
+            ));
+        }
+    }
+    Some((main, None, ""))
+}
+
+/// Split on commas and semicolons outside parentheses; `NA` yields nothing.
+pub(crate) fn split_vouchers(type_voucher: &str) -> Vec<&str> {
+    let type_voucher = type_voucher.trim();
+    if type_voucher.is_empty() || type_voucher == MDD_MISSING_VALUE {
+        return Vec::new();
+    }
+    let mut vouchers = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in type_voucher.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' | ';' if depth == 0 => {
+                vouchers.push(type_voucher[start..i].trim());
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    vouchers.push(type_voucher[start..].trim());
+    vouchers.retain(|voucher| !voucher.is_empty());
+    vouchers
+}
+
+/// Institution codes are upper-case ASCII letters, optionally with a
+/// parenthesized part as in `BM(NH)`.
+fn is_code(code: &str) -> bool {
+    code.len() >= 2
+        && code.starts_with(|c: char| c.is_ascii_uppercase())
+        && code
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c == '(' || c == ')')
+}
+
+fn is_collection(segment: &str) -> bool {
+    !segment.is_empty() && segment.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_voucher() {
+        let voucher =
+            Voucher::parse("USNM:MAMM:1163 (= MCZ:Mamm:5460) (= USNM:MAMM:A2045)").unwrap();
+        assert_eq!(voucher.institution_code, "USNM");
+        assert_eq!(voucher.collection_code.as_deref(), Some("MAMM"));
+        assert_eq!(voucher.catalog_number.as_deref(), Some("1163"));
+        assert_eq!(
+            voucher.equivalents,
+            vec!["MCZ:Mamm:5460", "USNM:MAMM:A2045"]
+        );
+
+        let voucher = Voucher::parse("BM(NH) 1901.2.3.4").unwrap();
+        assert_eq!(voucher.institution_code, "BMNH");
+        assert_eq!(voucher.catalog_number.as_deref(), Some("1901.2.3.4"));
+        assert!(voucher.is_known_institution());
+        assert_eq!(voucher.to_string(), "BMNH 1901.2.3.4");
+
+        let voucher = Voucher::parse("MNHN-ZM-MO-1992-2010").unwrap();
+        assert_eq!(voucher.collection_code.as_deref(), Some("ZM-MO"));
+        assert_eq!(voucher.catalog_number.as_deref(), Some("1992-2010"));
+        let voucher = Voucher::parse("RMNH.MAM.39322.a").unwrap();
+        assert_eq!(voucher.to_string(), "RMNH:MAM:39322.a");
+        let voucher = Voucher::parse("ZIN S. 12476").unwrap();
+        assert_eq!(voucher.catalog_number.as_deref(), Some("S. 12476"));
+        let voucher = Voucher::parse("AMG (number not known)").unwrap();
+        assert_eq!(voucher.catalog_number, None);
+        assert!(Voucher::parse("Laâyoune REF 021/2019 Awserd").is_none());
+    }
+
+    #[test]
+    fn test_parse_voucher_list() {
+        let vouchers = Voucher::parse_list("AMNH MO-1869, AMNH MS-2412");
+        assert_eq!(vouchers.len(), 2);
+        assert_eq!(vouchers[1].catalog_number.as_deref(), Some("MS-2412"));
+        let vouchers = Voucher::parse_list("USNM:MAMM:1163 (= MCZ:Mamm:5460); AMG 1234");
+        assert_eq!(vouchers.len(), 2);
+        assert_eq!(vouchers[1].institution_code, "AMG");
+        assert!(Voucher::parse_list("NA").is_empty());
+        assert_eq!(
+            institution_name("bm(nh)"),
+            Some("Natural History Museum, London")
+        );
+        assert!(institution_name("IPHAS").is_none());
+    }
+}
@@ -6,15 +6,121 @@
 //!
 //! ## Modules
 //! * `parser` – Low-level record parsers (`MddData`, `SynonymData`) and higher
-//!   level bundles (`ReleasedMddData`, `AllMddData`, `CountryMDDStats`).
-//! * `helper` – Utility helpers (country code normalization, constants).
+//!   level bundles (`ReleasedMddData`, `AllMddData`, `CountryMDDStats`,
+//!   `IsoCountryStats`, `IucnStats`).
+//! * `helper` – Utility helpers (country code normalization, slugs, coordinate
+//!   and type voucher parsing, checksums, run caches, constants).
+//! * `nomenclature` – Structured authorship (`nomenclature::authorship::Authorship`)
+//!   parsed from authority strings and `nominalNames` entries
+//!   (`nomenclature::nominal::NominalName`), with lossless rendering.
 //! * `writer` – Output helpers for serializing and writing processed data.
+//! * `diff` – Release-to-release comparison (`ReleaseDiff`) with JSON and
+//!   Markdown output, likely splits and lumps (`TaxonomicEvent`) with a
+//!   confidence level, and `ReleasedMddData::apply_diff` to rebuild the newer
+//!   release from the older one and a diff. `diff::distribution` lists
+//!   countries added to or removed from each species' range and species
+//!   gained or lost per country.
+//! * `changelog` – `Changelog` grouping a release diff into new species,
+//!   splits, lumps, transfers between genera, and IUCN updates for
+//!   Markdown release notes.
+//! * `query` – `SpeciesQuery` builder for chained, lazily applied record filters,
+//!   and `Page` for paginated results.
+//! * `quality` – Data-quality checks (`quality::validate`) producing a
+//!   `QualityReport` with per-issue severity; `quality::homonyms` groups
+//!   duplicate names and senior homonym conflicts for editors.
+//! * `search` – `SearchIndex` with exact, prefix, and fuzzy (Levenshtein)
+//!   lookup over scientific, common, and synonym names.
+//! * `reconcile` – `Reconciler` resolving external name lists to MDD species
+//!   (exact, via synonym, fuzzy, genus only, unmatched).
+//! * `crosswalk` – `Crosswalk` concordance tables from MDD ids to MSW3 names
+//!   (with match type) and CMW names (with the change flag), as CSV and JSON;
+//!   `crosswalk::itis` / `crosswalk::ncbi` match species to ITIS TSNs and NCBI
+//!   taxids from local dump files.
+//! * `vernacular` – `VernacularIndex` table of main and other common names
+//!   with optional language tags, case- and diacritic-insensitive lookup of
+//!   species ids, and CSV / JSON export.
+//! * `registry` (`archive` feature) – `ReleaseRegistry` listing the releases (zips, release TOMLs,
+//!   CSV pairs) under a directory by version, and loading any or the latest.
+//! * `longitudinal` – `SpeciesHistory` aligning the species of several
+//!   releases by id and name into per-species life histories (first
+//!   appearance, renames, renumbering, removal), with CSV export.
+//! * `store` – `MddStore`, a release bundle with prebuilt id, name, genus,
+//!   family, and synonym indexes for repeated lookups.
+//! * `stats` – Description-date and authorship statistics
+//!   (`stats::authority::AuthorityStats`), continent / realm richness
+//!   (`stats::distribution::RegionStats`), and headline counts
+//!   (`stats::summary::DatasetSummary`).
+//! * `geo` – `ReleasedMddData::species_within` returning species whose type
+//!   locality falls inside a `BoundingBox` or a GeoJSON `Region` (requires
+//!   the `geo` feature).
+//! * `render` – `PageRenderer` writing one Markdown or HTML page per species
+//!   plus an index from overridable Tera templates (requires the `render`
+//!   feature).
+//! * `rest` – Read-only REST endpoints (axum) over an in-memory
+//!   `ReleasedMddData` (requires the `server` feature).
+//! * `graphql` – GraphQL schema and axum router over an in-memory
+//!   `ReleasedMddData` (species, synonyms, taxonomy, country statistics;
+//!   requires the `graphql` feature).
+//! * `wasm` – `wasm-bindgen` wrappers (`parse_mdd_csv`, `parse_synonyms_csv`,
+//!   `search`, `MddSearch`) for browser apps (requires the `wasm` feature).
+//! * `python` – PyO3 classes (`MddData`, `SynonymData`, `ReleasedMddData`,
+//!   `CountryMDDStats`) with CSV parsing and dict / record output for Python
+//!   workflows (requires the `python` feature).
+//! * `ffi` – `extern "C"` functions (parse from a buffer, JSON output, free)
+//!   with a cbindgen header in `include/mdd_api.h` (requires the `ffi` feature).
+//! * `db` – SQLite export of a release bundle and `db::read_release` to load it
+//!   back (requires the `db` feature).
+//! * `duckdb` – DuckDB export with the same tables as the SQLite export
+//!   (requires the `duckdb` feature).
+//! * `enrich` – Lookups in external services: `enrich::iucn` compares live IUCN
+//!   Red List categories with `iucnStatus`, `enrich::gbif` matches species to
+//!   GBIF Backbone taxon keys, `enrich::wikidata` links species to Wikidata
+//!   items and Wikipedia articles (requires the `enrich` feature).
 //!
 //! ## Design Principles
 //! * Preserve original text fields verbatim (no lossy normalization).
 //! * Defer opinionated typing (e.g., coordinates, enumerations) to downstream callers.
 //! * Provide predictable JSON via `serde` rename rules (camelCase alignment).
 //! * Keep dependencies minimal.
+//! * Never print from library code; diagnostics go through the `log` facade
+//!   so embedders decide what is shown.
+//!
+//! ## Feature Flags
+//! * `archive` (default) – release zips: `ReleasedMddData::from_zip_reader`,
+//!   `registry`, `writer::dwca`, and the `mdd zip` subcommand.
+//! * `zstd` (default) – Zstandard `Compression` (builds the C library).
+//! * `compact` – `parser::compact::MddDataCompact`, species records whose
+//!   repeated columns (ranks, authors, distributions) share interned `Arc<str>`
+//!   storage for in-memory servers and WASM.
+//! * `db` (default) – `db::SqliteExporter` and the `mdd db` subcommand, via
+//!   `rusqlite` with a bundled SQLite.
+//! * `duckdb` – the `duckdb` module and `mdd db --format duckdb`, with a bundled
+//!   DuckDB (slow to compile); enables `db`.
+//! * `enrich` – the `enrich` module (`reqwest`) and the `mdd iucn` / `mdd gbif` /
+//!   `mdd wikidata` subcommands; the IUCN Red List API needs a token.
+//! * `ffi` – `ffi` C ABI for R, Julia, and mobile integrations; build a
+//!   `cdylib` / `staticlib` with `cargo rustc --crate-type`.
+//! * `fetch` – `fetch::ReleaseFetcher` and the `mdd fetch` subcommand for
+//!   downloading checksum-verified release archives from Zenodo or GitHub.
+//! * `geo` – the `geo` module; no extra dependencies.
+//! * `parquet` – `writer::parquet` species and synonym tables in Apache
+//!   Parquet with a stable, field-derived column schema.
+//! * `schema` – `writer::json_schema` JSON Schemas (via `schemars`) of the
+//!   exported JSON structures and the `mdd schema` subcommand.
+//! * `progress` – progress bars (`indicatif`) for CSV parsing and zip
+//!   extraction in the `mdd` CLI.
+//! * `typed` – `parser::typed::MddRecordTyped`, an opt-in typed projection
+//!   (numeric coordinates, `IucnStatus`, boolean flags) over `MddData`.
+//! * `xlsx` – `parser::xlsx::MddWorkbook` reading species and synonym sheets
+//!   from Excel / ODS workbooks (via `calamine`) and `mdd json --input-format xlsx`.
+//! * `python` – the `python` module (`pyo3`); build wheels with maturin.
+//! * `render` – the `render` module (`tera`) and the `mdd render` subcommand.
+//! * `server` – `rest` read-only REST API (`axum`, `tokio`) and `mdd serve`.
+//! * `graphql` – `graphql` schema (`async-graphql`) mounted by `mdd serve --graphql`;
+//!   implies `server`.
+//! * `wasm` – `wasm` JavaScript bindings. Without default features the library
+//!   builds for `wasm32-unknown-unknown`; file-based helpers compile there but
+//!   return I/O errors at runtime.
 //!
 //! ## Quick Start
 //! ```rust, ignore
@@ -33,6 +139,42 @@
 //! ```
 //!
 //! See the README for more detailed workflow guidance.
+pub mod changelog;
+pub mod crosswalk;
+#[cfg(feature = "db")]
+pub mod db;
+pub mod diff;
+#[cfg(feature = "duckdb")]
+pub mod duckdb;
+#[cfg(feature = "enrich")]
+pub mod enrich;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "geo")]
+pub mod geo;
+#[cfg(feature = "graphql")]
+pub mod graphql;
 pub mod helper;
+pub mod longitudinal;
+pub mod nomenclature;
 pub mod parser;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod quality;
+pub mod query;
+pub mod reconcile;
+#[cfg(feature = "archive")]
+pub mod registry;
+#[cfg(feature = "render")]
+pub mod render;
+#[cfg(feature = "server")]
+pub mod rest;
+pub mod search;
+pub mod stats;
+pub mod store;
+pub mod vernacular;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub mod writer;
@@ -0,0 +1,78 @@
+//! Logging and progress reporting for the `mdd` CLI.
+//!
+//! The library reports through the `log` facade only; this module installs
+//! the logger the binary uses. Messages go to stderr so command output
+//! written to stdout stays clean:
+//! * default – info and above;
+//! * `--verbose` – debug and above;
+//! * `--quiet` – warnings and errors only, no progress bars.
+//!
+//! With the `progress` feature, file reads are wrapped in `indicatif`
+//! progress bars while info output is enabled.
+
+use std::{fs, io::Read};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// Minimal stderr logger.
+struct CliLogger {
+    level: LevelFilter,
+}
+
+impl Log for CliLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        match record.level() {
+            Level::Info => eprintln!("{}", record.args()),
+            level => eprintln!("{}: {}", level, record.args()),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the CLI logger with the level selected by `--verbose` / `--quiet`.
+pub fn init(verbose: bool, quiet: bool) {
+    let level = if quiet {
+        LevelFilter::Warn
+    } else if verbose {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    };
+    log::set_boxed_logger(Box::new(CliLogger { level })).expect("Failed to initialize logger");
+    log::set_max_level(level);
+}
+
+/// A readable and seekable file source, optionally reporting progress.
+pub trait ReadSeek: Read + std::io::Seek {}
+
+impl<T: Read + std::io::Seek> ReadSeek for T {}
+
+/// Wraps `file` in a progress bar labelled `message` (with the `progress` feature).
+#[cfg(feature = "progress")]
+pub fn progress_reader(file: fs::File, message: &str) -> Box<dyn ReadSeek> {
+    if !log::log_enabled!(Level::Info) {
+        return Box::new(file);
+    }
+    let len = file.metadata().map(|m| m.len()).unwrap_or_default();
+    let bar = indicatif::ProgressBar::new(len).with_message(message.to_string());
+    bar.set_style(
+        indicatif::ProgressStyle::with_template("{msg} [{bar:40}] {bytes}/{total_bytes}")
+            .expect("Invalid progress bar template")
+            .progress_chars("=> "),
+    );
+    Box::new(bar.wrap_read(file))
+}
+
+/// Returns `file` unchanged (progress bars need the `progress` feature).
+#[cfg(not(feature = "progress"))]
+pub fn progress_reader(file: fs::File, _message: &str) -> Box<dyn ReadSeek> {
+    Box::new(file)
+}
@@ -0,0 +1,550 @@
+//! Species life histories across historical releases.
+//!
+//! `SpeciesHistory` lines up the species of several releases, oldest first,
+//! into one `SpeciesLifeHistory` per lineage. A species is aligned with the
+//! previous releases by MDD id, and by `sciName` when its id is new (e.g.
+//! releases that renumbered species), so each lineage records:
+//!
+//! * `Added` – the release the species first appears in.
+//! * `Renamed` – a new `sciName` under the same lineage.
+//! * `Renumbered` – a new MDD id matched by name.
+//! * `Removed` – the first release no longer listing the species.
+//! * `Reinstated` – a removed species listed again.
+//!
+//! Histories are queried by any id or name a species ever had, by the
+//! release a species was added in or removed from, and are written as
+//! `species_history.csv` (one row per lineage) and
+//! `species_history_events.csv` (one row per event).
+//!
+//! ```rust, ignore
+//! use mdd_api::longitudinal::SpeciesHistory;
+//!
+//! let history = SpeciesHistory::from_releases(&releases);
+//! let lion = history.by_name("Panthera leo").next().unwrap();
+//! println!("{} first listed in {}", lion.sci_name, lion.first_version);
+//! history.write_files(Path::new("."))?;
+//! ```
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    crosswalk::write_csv_table,
+    helper::normalize_name,
+    parser::{mdd::MddData, ReleasedMddData},
+};
+
+/// File stem of the per-lineage history table.
+pub const HISTORY_FILE: &str = "species_history";
+/// File stem of the per-event history table.
+pub const HISTORY_EVENTS_FILE: &str = "species_history_events";
+
+/// Life histories of the species of several releases.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeciesHistory {
+    /// Release versions in input order.
+    pub versions: Vec<String>,
+    /// One history per lineage, in order of first appearance.
+    pub species: Vec<SpeciesLifeHistory>,
+}
+
+/// The releases one species lineage is listed in and how it changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeciesLifeHistory {
+    /// MDD id in the newest release listing the species.
+    pub id: u32,
+    /// Scientific name in the newest release listing the species.
+    pub sci_name: String,
+    pub first_version: String,
+    pub last_version: String,
+    /// Release the species was last removed in; `None` while it is listed
+    /// in the newest release.
+    pub removed_in: Option<String>,
+    /// Number of releases listing the species.
+    pub release_count: usize,
+    /// Events in release order, starting with `Added`.
+    pub events: Vec<HistoryEvent>,
+}
+
+/// One change of a lineage between a release and the one before it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEvent {
+    pub version: String,
+    pub kind: HistoryEventKind,
+    /// MDD id after the event (before it, for `Removed`).
+    pub id: u32,
+    /// Scientific name after the event (before it, for `Removed`).
+    pub sci_name: String,
+    /// Id before a `Renumbered` event.
+    pub previous_id: Option<u32>,
+    /// Name before a `Renamed` event.
+    pub previous_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HistoryEventKind {
+    Added,
+    Renamed,
+    Renumbered,
+    Removed,
+    Reinstated,
+}
+
+/// A row of `species_history.csv`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRow {
+    pub id: u32,
+    pub sci_name: String,
+    pub first_version: String,
+    pub last_version: String,
+    pub removed_in: Option<String>,
+    pub release_count: usize,
+    /// Earlier names, oldest first, `|`-separated.
+    pub previous_names: String,
+    /// Earlier ids, oldest first, `|`-separated.
+    pub previous_ids: String,
+}
+
+/// A row of `species_history_events.csv`, keyed by the lineage's latest id
+/// and name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEventRow {
+    pub species_id: u32,
+    pub species_name: String,
+    pub version: String,
+    pub kind: HistoryEventKind,
+    pub id: u32,
+    pub sci_name: String,
+    pub previous_id: Option<u32>,
+    pub previous_name: Option<String>,
+}
+
+impl SpeciesHistory {
+    /// Align the species of `releases`, which are expected oldest first.
+    pub fn from_releases(releases: &[ReleasedMddData]) -> Self {
+        let mut builder = HistoryBuilder::default();
+        for release in releases {
+            let species: Vec<&MddData> = release.species().collect();
+            builder.add_release(release.get_version(), &species);
+        }
+        builder.finish()
+    }
+
+    /// Load every release of `registry`, oldest first, and align them.
+    #[cfg(feature = "archive")]
+    pub fn from_registry(
+        registry: &crate::registry::ReleaseRegistry,
+    ) -> Result<Self, crate::parser::error::MddError> {
+        let releases = registry
+            .entries()
+            .map(|entry| entry.load())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_releases(&releases))
+    }
+
+    pub fn len(&self) -> usize {
+        self.species.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.species.is_empty()
+    }
+
+    /// The lineage that had MDD id `id` in any release.
+    pub fn get(&self, id: u32) -> Option<&SpeciesLifeHistory> {
+        self.species
+            .iter()
+            .find(|history| history.ids().any(|known| known == id))
+    }
+
+    /// Lineages that were called `name` in any release, ignoring case.
+    pub fn by_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a SpeciesLifeHistory> {
+        let name = name.trim();
+        self.species.iter().filter(move |history| {
+            history
+                .names()
+                .any(|known| known.eq_ignore_ascii_case(name))
+        })
+    }
+
+    /// Lineages first listed in `version`.
+    pub fn added_in<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> impl Iterator<Item = &'a SpeciesLifeHistory> {
+        self.species
+            .iter()
+            .filter(move |history| history.first_version == version)
+    }
+
+    /// Lineages removed in `version`, including ones reinstated later.
+    pub fn removed_in<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> impl Iterator<Item = &'a SpeciesLifeHistory> {
+        self.species.iter().filter(move |history| {
+            history
+                .events
+                .iter()
+                .any(|event| event.kind == HistoryEventKind::Removed && event.version == version)
+        })
+    }
+
+    /// Lineages renamed in any release.
+    pub fn renamed(&self) -> impl Iterator<Item = &SpeciesLifeHistory> {
+        self.species
+            .iter()
+            .filter(|history| history.has_event(HistoryEventKind::Renamed))
+    }
+
+    /// Lineages listed in the newest release.
+    pub fn current(&self) -> impl Iterator<Item = &SpeciesLifeHistory> {
+        self.species
+            .iter()
+            .filter(|history| history.removed_in.is_none())
+    }
+
+    /// One row per lineage.
+    pub fn rows(&self) -> Vec<HistoryRow> {
+        self.species.iter().map(SpeciesLifeHistory::row).collect()
+    }
+
+    /// One row per event, lineage by lineage.
+    pub fn event_rows(&self) -> Vec<HistoryEventRow> {
+        self.species
+            .iter()
+            .flat_map(|history| {
+                history.events.iter().map(|event| HistoryEventRow {
+                    species_id: history.id,
+                    species_name: history.sci_name.clone(),
+                    version: event.version.clone(),
+                    kind: event.kind,
+                    id: event.id,
+                    sci_name: event.sci_name.clone(),
+                    previous_id: event.previous_id,
+                    previous_name: event.previous_name.clone(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Write `species_history.csv` and `species_history_events.csv` into
+    /// `output_dir`, returning the written paths.
+    pub fn write_files(&self, output_dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        fs::create_dir_all(output_dir)?;
+        Ok(vec![
+            write_csv_table(self.rows(), output_dir, HISTORY_FILE)?,
+            write_csv_table(self.event_rows(), output_dir, HISTORY_EVENTS_FILE)?,
+        ])
+    }
+}
+
+impl SpeciesLifeHistory {
+    /// Earlier names, oldest first.
+    pub fn previous_names(&self) -> impl Iterator<Item = &str> {
+        self.events
+            .iter()
+            .filter_map(|event| event.previous_name.as_deref())
+    }
+
+    /// Earlier ids, oldest first.
+    pub fn previous_ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.events.iter().filter_map(|event| event.previous_id)
+    }
+
+    /// Every name of the lineage, latest first.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.sci_name.as_str()).chain(self.previous_names())
+    }
+
+    /// Every id of the lineage, latest first.
+    pub fn ids(&self) -> impl Iterator<Item = u32> + '_ {
+        std::iter::once(self.id).chain(self.previous_ids())
+    }
+
+    pub fn has_event(&self, kind: HistoryEventKind) -> bool {
+        self.events.iter().any(|event| event.kind == kind)
+    }
+
+    fn row(&self) -> HistoryRow {
+        HistoryRow {
+            id: self.id,
+            sci_name: self.sci_name.clone(),
+            first_version: self.first_version.clone(),
+            last_version: self.last_version.clone(),
+            removed_in: self.removed_in.clone(),
+            release_count: self.release_count,
+            previous_names: self.previous_names().collect::<Vec<_>>().join("|"),
+            previous_ids: self
+                .previous_ids()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join("|"),
+        }
+    }
+}
+
+/// Lineages under construction with lookups by their latest id and name.
+#[derive(Default)]
+struct HistoryBuilder {
+    versions: Vec<String>,
+    species: Vec<SpeciesLifeHistory>,
+    by_id: HashMap<u32, usize>,
+    by_name: HashMap<String, usize>,
+}
+
+impl HistoryBuilder {
+    fn add_release(&mut self, version: &str, records: &[&MddData]) {
+        let mut claimed = vec![false; self.species.len()];
+        let mut matches: Vec<Option<usize>> = vec![None; records.len()];
+        // Ids first, so a name reused by a new species cannot take over the
+        // lineage of the species that still carries its id.
+        for (record, found) in records.iter().zip(matches.iter_mut()) {
+            if record.id == 0 {
+                continue;
+            }
+            if let Some(&index) = self.by_id.get(&record.id) {
+                if !claimed[index] {
+                    claimed[index] = true;
+                    *found = Some(index);
+                }
+            }
+        }
+        for (record, found) in records.iter().zip(matches.iter_mut()) {
+            if found.is_some() {
+                continue;
+            }
+            if let Some(&index) = self.by_name.get(&normalize_name(&record.sci_name)) {
+                if !claimed[index] {
+                    claimed[index] = true;
+                    *found = Some(index);
+                }
+            }
+        }
+
+        let listed_before: Vec<bool> = self
+            .species
+            .iter()
+            .map(|history| self.versions.last() == Some(&history.last_version))
+            .collect();
+        for (record, found) in records.iter().zip(matches) {
+            let index = match found {
+                Some(index) => {
+                    self.update(index, version, record, listed_before[index]);
+                    index
+                }
+                None => self.push(version, record),
+            };
+            self.by_name.insert(normalize_name(&record.sci_name), index);
+            if record.id != 0 {
+                self.by_id.insert(record.id, index);
+            }
+        }
+        for (index, listed) in listed_before.into_iter().enumerate() {
+            if listed && !claimed[index] {
+                let history = &mut self.species[index];
+                history.removed_in = Some(version.to_string());
+                history.events.push(HistoryEvent {
+                    version: version.to_string(),
+                    kind: HistoryEventKind::Removed,
+                    id: history.id,
+                    sci_name: history.sci_name.clone(),
+                    previous_id: None,
+                    previous_name: None,
+                });
+            }
+        }
+        self.versions.push(version.to_string());
+    }
+
+    fn push(&mut self, version: &str, record: &MddData) -> usize {
+        self.species.push(SpeciesLifeHistory {
+            id: record.id,
+            sci_name: record.sci_name.clone(),
+            first_version: version.to_string(),
+            last_version: version.to_string(),
+            removed_in: None,
+            release_count: 1,
+            events: vec![event(version, HistoryEventKind::Added, record)],
+        });
+        self.species.len() - 1
+    }
+
+    fn update(&mut self, index: usize, version: &str, record: &MddData, listed_before: bool) {
+        let history = &mut self.species[index];
+        if !listed_before {
+            history.removed_in = None;
+            history
+                .events
+                .push(event(version, HistoryEventKind::Reinstated, record));
+        }
+        if record.id != 0 && history.id != 0 && record.id != history.id {
+            history.events.push(HistoryEvent {
+                previous_id: Some(history.id),
+                ..event(version, HistoryEventKind::Renumbered, record)
+            });
+        }
+        if record.sci_name != history.sci_name {
+            history.events.push(HistoryEvent {
+                previous_name: Some(history.sci_name.clone()),
+                ..event(version, HistoryEventKind::Renamed, record)
+            });
+        }
+        if record.id != 0 {
+            history.id = record.id;
+        }
+        history.sci_name = record.sci_name.clone();
+        history.last_version = version.to_string();
+        history.release_count += 1;
+    }
+
+    fn finish(self) -> SpeciesHistory {
+        SpeciesHistory {
+            versions: self.versions,
+            species: self.species,
+        }
+    }
+}
+
+fn event(version: &str, kind: HistoryEventKind, record: &MddData) -> HistoryEvent {
+    HistoryEvent {
+        version: version.to_string(),
+        kind,
+        id: record.id,
+        sci_name: record.sci_name.clone(),
+        previous_id: None,
+        previous_name: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(version: &str, species: &[(u32, &str)]) -> ReleasedMddData {
+        let species = species
+            .iter()
+            .map(|(id, sci_name)| MddData {
+                id: *id,
+                sci_name: sci_name.to_string(),
+                ..MddData::new()
+            })
+            .collect();
+        ReleasedMddData::from_parser(species, Vec::new(), version, "")
+    }
+
+    fn history() -> SpeciesHistory {
+        SpeciesHistory::from_releases(&[
+            release(
+                "1.0",
+                &[
+                    (1, "Panthera leo"),
+                    (2, "Felis lybica"),
+                    (3, "Mus musculus"),
+                ],
+            ),
+            release(
+                "1.1",
+                &[
+                    (1, "Panthera leo"),
+                    (2, "Felis silvestris"),
+                    (10, "Mus musculus"),
+                ],
+            ),
+            release(
+                "2.0",
+                &[
+                    (1, "Panthera leo"),
+                    (2, "Felis silvestris"),
+                    (4, "Rattus rattus"),
+                ],
+            ),
+            release(
+                "2.1",
+                &[
+                    (1, "Panthera leo"),
+                    (10, "Mus musculus"),
+                    (4, "Rattus rattus"),
+                ],
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_species_history() {
+        let history = history();
+        assert_eq!(history.versions, ["1.0", "1.1", "2.0", "2.1"]);
+        assert_eq!(history.len(), 4);
+
+        let lion = history.get(1).unwrap();
+        assert_eq!(lion.release_count, 4);
+        assert_eq!(lion.events.len(), 1);
+
+        let cat = history.by_name("felis lybica").next().unwrap();
+        assert_eq!(cat.sci_name, "Felis silvestris");
+        assert_eq!(cat.previous_names().collect::<Vec<_>>(), ["Felis lybica"]);
+        assert_eq!(cat.last_version, "2.0");
+        assert_eq!(cat.removed_in.as_deref(), Some("2.1"));
+        assert_eq!(history.renamed().count(), 1);
+
+        // Renumbered by name, removed, then reinstated by id.
+        let mouse = history.get(3).unwrap();
+        assert_eq!(mouse.id, 10);
+        let kinds: Vec<_> = mouse.events.iter().map(|event| event.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                HistoryEventKind::Added,
+                HistoryEventKind::Renumbered,
+                HistoryEventKind::Removed,
+                HistoryEventKind::Reinstated,
+            ]
+        );
+        assert_eq!(mouse.removed_in, None);
+        assert_eq!(mouse.release_count, 3);
+
+        assert_eq!(
+            history.added_in("2.0").map(|h| h.id).collect::<Vec<_>>(),
+            [4]
+        );
+        assert_eq!(history.removed_in("2.0").count(), 1);
+        assert_eq!(history.current().count(), 3);
+    }
+
+    #[test]
+    fn test_write_history() {
+        let history = history();
+        let dir = tempdir::TempDir::new("species_history").unwrap();
+        let paths = history.write_files(dir.path()).unwrap();
+        assert_eq!(paths.len(), 2);
+
+        let table = fs::read_to_string(&paths[0]).unwrap();
+        let mut lines = table.lines();
+        assert_eq!(
+            lines.next(),
+            Some("id,sciName,firstVersion,lastVersion,removedIn,releaseCount,previousNames,previousIds")
+        );
+        assert!(table.contains("2,Felis silvestris,1.0,2.0,2.1,3,Felis lybica,"));
+        assert!(table.contains("10,Mus musculus,1.0,2.1,,3,,3"));
+
+        let events = fs::read_to_string(&paths[1]).unwrap();
+        assert_eq!(events.lines().count(), 1 + history.event_rows().len());
+        assert!(events.contains("10,Mus musculus,1.1,renumbered,10,Mus musculus,3,"));
+    }
+}
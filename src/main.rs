@@ -6,33 +6,318 @@
 //!
 //! ## Subcommands
 //! * `json` – Parse species + synonym CSV files directly.
-//! * `zip`  – Extract an MDD release archive (`MDD_v*.csv`, `Species_Syn_v*.csv`, optional `release.toml`) then parse.
-//! * `toml` – (Placeholder) drive parsing via a release metadata TOML file.
-//! * `db`   – (Placeholder) export into a SQLite database.
+//! * `zip`  – Extract an MDD release archive (`MDD_v*.csv`, `Species_Syn_v*.csv`, optional `release.toml`) then parse (`archive` feature).
+//! * `toml` – Parse the CSVs referenced by a release metadata TOML, embedding its version, date, and DOI.
+//! * `package` – Assemble a release TOML and its CSVs into the `MDD_v<version>.zip` read by `zip` (`archive` feature).
+//! * `diff` – Compare two exported JSON bundles (added/removed/renamed species, field changes).
+//! * `changelog` – Write release notes grouping new species, splits, lumps, transfers, and IUCN updates.
+//! * `trends` – Per-country species counts and added/removed species across several releases.
+//! * `history` – Per-species life histories (first appearance, renames, removal) across several releases, as CSV.
+//! * `batch` – Parse every release archive and TOML of a directory into `v<version>` subdirectories plus a `releases.json` index.
+//! * `fetch` – Download a release zip from Zenodo/GitHub, verify its checksum, then parse it like `zip` (`fetch` feature).
+//! * `gbif` – Match species to GBIF Backbone taxon keys (species-match API or an offline backbone dump) and write a sidecar JSON (`enrich` feature).
+//! * `iucn` – Compare `iucnStatus` with the live IUCN Red List categories and write a mismatch report (`enrich` feature).
+//! * `wikidata` – Link species to Wikidata QIDs and Wikipedia article URLs (SPARQL or an offline dump) and write a sidecar JSON (`enrich` feature).
+//! * `db`   – Load an exported JSON bundle into a SQLite database (`db` feature).
+//! * `validate` – Run data-quality checks over the CSVs and write a JSON report.
+//! * `reconcile` – Match a list of scientific names against MDD species and synonyms.
+//! * `init-release` – Write a `release.toml` (version, date, DOI, remarks, SHA-256 digests) for a species and a synonym CSV.
+//! * `stats` – Print species, order, family, IUCN, and country counts of a species CSV.
+//! * `crosswalk` – Write MDD → MSW3 and MDD → CMW name mapping tables (and, from local
+//!   dumps, MDD → ITIS TSN and NCBI taxid tables) as CSV and JSON.
+//! * `vernacular` – Write the common names of a species CSV, with language tags, as CSV and JSON.
+//! * `serve` – Serve an exported JSON bundle as a read-only REST API (`server` feature), plus a
+//!   GraphQL API with GraphiQL (`graphql` feature).
+//! * `render` – Render one Markdown or HTML page per species plus an index from overridable Tera templates (`render` feature).
+//! * `schema` – Write JSON Schemas of the bundle, record, and country statistics JSON (`schema` feature).
+//!
+//! ## Global Flags
+//! * `--verbose/-v` show debug output
+//! * `--quiet/-q` only show warnings and errors
+//! * `--error-format <text|json>` how failures are printed to stderr (default: `text`)
+//!
+//! Failures exit with 2 for bad input, 3 for a CSV schema mismatch, and 4 for
+//! I/O errors; see the `error` module.
+//!
+//! Progress messages are logged to stderr; with the `progress` feature, CSV
+//! parsing and zip extraction show progress bars.
+//!
+//! ## JSON Output Flags
+//! Accepted by `json`, `batch`, and `zip`:
+//! * `--pretty` pretty-print the JSON bundle with alphabetically sorted keys
+//!   so releases diff cleanly in git
+//! * `--field-names <camel|snake|original>` spell the JSON keys in camelCase
+//!   (default), snake_case, or as the MDD CSV headers of species and synonym
+//!   records
 //!
 //! ## JSON (`json`) Arguments
-//! * `--input/-i` species CSV path (default: `data.csv`)
-//! * `--synonym/-s` synonym CSV path (default: `synonyms.csv`)
-//! * `--output/-o` output directory (default: `../assets/data`)
+//! * `--input/-i` species CSV path (default: `data.csv`; `-` reads stdin)
+//! * `--synonym/-s` synonym CSV path (default: `synonyms.csv`; `-` reads stdin)
+//! * `--delimiter <char>` field delimiter of the CSVs (default: `,`; `tab` for TSV)
+//! * `--input-format <csv|xlsx>` with `xlsx`, `--input` is a workbook (`.xlsx`,
+//!   `.xls`, `.ods`) whose species and synonym sheets are found by their
+//!   headers, and `--synonym` is ignored (`xlsx` feature)
+//! * `--output/-o` output directory (default: `../assets/data`); `-` writes
+//!   only the release bundle (`json` or `ndjson`, compressed per
+//!   `--compression`) to stdout, without statistics or manifest
 //! * `--plain-text/-p` also emit plain‑text (if supported)
 //! * `--mdd=<ver>` override MDD version
 //! * `--date <YYYY-MM-DD>` override release date
 //! * `--limit <n>` limit number of species (debugging)
 //! * `--prefix <str>` prefix output filenames
+//! * `--format <json|ndjson|per-species>` write one JSON document (default),
+//!   one species entry per line to `<prefix>.ndjson`, or one file per species
+//!   under `species/` plus an `index.json` manifest
+//! * `--split-by <order|family>` write one JSON file per order or family under
+//!   `shards/` plus a `shards.json` index instead of the single bundle
+//!   (conflicts with `--format`)
+//! * `--previous <path>` previous JSON bundle (`.json`, optionally compressed);
+//!   records whose id is unchanged keep its slugs
+//! * `--compression <none|gzip|zstd|brotli>` compression of the JSON / NDJSON
+//!   output, adding `.gz`, `.zst`, or `.br` (default: `gzip` for `--format
+//!   json`, `none` for `ndjson`); with `none` or `--plain-text` the
+//!   uncompressed `.json` is written too
+//! * `--level <n>` compression level (gzip 0-9, zstd 1-22, brotli 0-11)
+//! * `--fields <a,b,...>` with `--format json`, write only these species
+//!   fields (JSON names, e.g. `id,sciName,family,iucnStatus`) and no synonyms
+//! * `--pretty` / `--field-names`, see JSON Output Flags
+//! * `--trim` trim leading and trailing whitespace of every text field before bundling
+//! * `--no-manifest` skip `manifest.json`, which otherwise lists every written
+//!   file (bundle, statistics, region codes, shards, species files) with its
+//!   size and SHA-256 hash, plus the release version
+//! * `--cache-dir <dir>` remember the input hashes and options of each run;
+//!   a run with unchanged inputs and options whose outputs still exist is
+//!   skipped
+//! * `--force` with `--cache-dir`, rebuild even if the outputs are up to date
+//!
+//! ```text
+//! curl -sL "$MDD_CSV_URL" | mdd json -i - -s synonyms.csv -o - --compression none | jq '.metadata'
+//! ```
+//!
+//! ## TOML (`toml`) Arguments
+//! * `--input/-i` release TOML path (default: `data.toml`); `mdd_file` and
+//!   `synonym_file` are resolved relative to its directory
+//! * `--output/-o` output directory (default: `.`)
+//! * `--plain-text/-p` also emit plain‑text JSON
+//! * `--no-verify` skip checking the CSVs against `mdd_sha256` / `synonym_sha256`
+//!   (a mismatch otherwise aborts with exit code `1`)
+//!
+//! ## Diff (`diff`) Arguments
+//! * `--old` older release: zip, tar.gz, or directory (`archive` feature),
+//!   SQLite export (`db` feature), or JSON bundle (`.json`, `.json.gz`,
+//!   `.json.zst`, or `.json.br`)
+//! * `--new` newer release, in the same forms as `--old`
+//! * `--output/-o` output directory for `release_diff.json`, `release_diff.md`,
+//!   and `distribution_changes.json` (default: `.`)
+//!
+//! `release_diff.json` includes the patch that `ReleasedMddData::apply_diff`
+//! applies to the older bundle to rebuild the newer one, and both outputs list
+//! likely splits and lumps with a confidence level for editor review.
+//! `distribution_changes.json` lists the countries added to or removed from
+//! each species' range and the species gained or lost per country.
+//!
+//! ## Changelog (`changelog`) Arguments
+//! * `--old` older release zip, tar.gz, or directory (`archive` feature),
+//!   SQLite export (`.sqlite`/`.db`, `db` feature), or JSON bundle (optionally
+//!   compressed)
+//! * `--new` newer release zip, tar.gz, or directory (`archive` feature),
+//!   SQLite export (`.sqlite`/`.db`, `db` feature), or JSON bundle (optionally
+//!   compressed)
+//! * `--format <md|json>` changelog format (default: `md`)
+//! * `--output/-o` output directory for `changelog.md` / `changelog.json` (default: `.`)
+//!
+//! ## Trends (`trends`) Arguments
+//! * `--input/-i` release zip, tar.gz, or directory, SQLite export, or JSON
+//!   bundle; repeat for each release, oldest first
+//! * `--output/-o` output directory for `country_trends.json` (default: `.`)
+//!
+//! ## History (`history`) Arguments
+//! * `--input/-i` release zip, tar.gz, or directory, SQLite export, or JSON
+//!   bundle; repeat for each release, oldest first
+//! * `--output/-o` output directory for `species_history.csv` and
+//!   `species_history_events.csv` (default: `.`)
+//!
+//! ## Batch (`batch`) Arguments
+//! * `--input-dir/-i` directory of release zips and tarballs (`archive`
+//!   feature) and release TOMLs (default: `.`); subdirectories are not
+//!   searched
+//! * `--output-dir/-o` output directory (default: `.`); each release is
+//!   written to `v<version>/` like `zip --in-memory` / `toml`, and
+//!   `releases.json` lists them, oldest first
+//! * `--no-verify` skip checking TOML releases against their digests
+//! * `--pretty` / `--field-names`, see JSON Output Flags
+//!
+//! ## Fetch (`fetch`) Arguments
+//! * `--version <ver>` MDD version to download
+//! * `--doi <doi>` resolve a specific Zenodo DOI instead of searching by version
+//! * `--github <owner/repo>` download the GitHub release tagged `v<ver>` instead
+//! * `--output/-o` download + extraction directory (default: `.`)
+//!
+//! ## GBIF (`gbif`) Arguments
+//! * `--input/-i` species CSV path (default: `data.csv`)
+//! * `--delimiter <char>` field delimiter of the CSV (default: `,`)
+//! * `--backbone <path>` match offline against the `Taxon.tsv` of a GBIF Backbone
+//!   dump instead of the species-match API
+//! * `--output/-o` directory for `gbif_matches.json` (default: `.`)
+//! * `--delay <ms>` wait between API requests (default: `100`)
+//! * `--limit <n>` only match the first `n` species
+//!
+//! `gbif_matches.json` holds the GBIF taxon key, accepted key, match type, and
+//! confidence per species (`enrich` feature).
+//!
+//! ## IUCN (`iucn`) Arguments
+//! * `--input/-i` species CSV path (default: `data.csv`)
+//! * `--delimiter <char>` field delimiter of the CSV (default: `,`)
+//! * `--token <token>` IUCN Red List API token (default: `$IUCN_API_TOKEN`)
+//! * `--output/-o` directory for `iucn_report.json` (default: `.`)
+//! * `--patched` also write `iucn_enriched.json`, the records with `iucnStatusLive`
+//! * `--delay <ms>` wait between requests (default: `500`)
+//! * `--limit <n>` only query the first `n` species
+//!
+//! The report counts unchanged, mismatched, updated (blank or `NE` in the CSV),
+//! and not found species and lists every species that is not unchanged
+//! (`enrich` feature).
+//!
+//! ## Wikidata (`wikidata`) Arguments
+//! * `--input/-i` species CSV path (default: `data.csv`)
+//! * `--delimiter <char>` field delimiter of the CSV (default: `,`)
+//! * `--dump <path>` link offline from a Wikidata JSON dump instead of the
+//!   Wikidata Query Service
+//! * `--language <code>` Wikipedia edition of the article links (default: `en`)
+//! * `--output/-o` directory for `wikidata_links.json` (default: `.`)
+//! * `--delay <ms>` wait between SPARQL queries (default: `1000`)
+//! * `--limit <n>` only link the first `n` species
+//!
+//! `wikidata_links.json` holds the Wikidata QID and Wikipedia URL per species
+//! (`enrich` feature).
+//!
+//! ## SQLite (`db`) Arguments
+//! * `--input/-i` JSON bundle produced by `json`/`zip` (`.json`, optionally `.gz`/`.zst`/`.br`, default: `data.json`)
+//! * `--output/-o` database path, replaced if present (default: `mdd.sqlite`, or
+//!   `mdd.duckdb` with `--format duckdb`)
+//! * `--format <sqlite|duckdb>` database engine (default: `sqlite`); `duckdb`
+//!   writes the same tables without the search index (`duckdb` feature)
+//!
+//! ## Validate (`validate`) Arguments
+//! * `--input/-i` species CSV path (default: `data.csv`)
+//! * `--synonym/-s` synonym CSV path (default: `synonyms.csv`)
+//! * `--delimiter <char>` field delimiter of the CSVs (default: `,`)
+//! * `--output/-o` JSON report path (default: `validation_report.json`)
+//! * `--homonyms <path>` also write duplicate names and senior homonym conflicts,
+//!   grouped for editorial review, as JSON
+//! * `--flagged <dir>` also write the flagged species (`flagged == 1`) grouped by
+//!   order and family, with a reason guessed from the taxonomy notes, as
+//!   `flagged_records.json` and `flagged_records.csv`
+//! * `--synonym-only <dir>` also match the synonyms without a species id to
+//!   accepted species by genus and epithet, writing the candidates with their
+//!   confidence as `synonym_only.json` and the unmatched ones as
+//!   `synonym_only_unresolved.csv`; unmatched synonyms are reported as
+//!   `unresolved-synonym` notes
+//! * `--country-shapes <geojson>` country outlines (a FeatureCollection with
+//!   `ISO_A2` or `NAME` properties, e.g. Natural Earth admin-0) used instead of
+//!   the bundled simplified ones to flag synonym type coordinates outside
+//!   their `type_country`, usually sign errors or swapped latitude and
+//!   longitude; with the `geo` feature the check always runs
+//! * `--deny-warnings` exit non-zero on warnings too
+//!
+//! The summary is printed to stdout. The process exits with `1` when errors
+//! (or, with `--deny-warnings`, warnings) were found.
+//!
+//! ## Reconcile (`reconcile`) Arguments
+//! * `--names/-n` text file with one name per line (blank lines and `#` comments skipped)
+//! * `--input/-i` species CSV path (default: `data.csv`)
+//! * `--synonym/-s` synonym CSV path (default: `synonyms.csv`)
+//! * `--delimiter <char>` field delimiter of the CSVs (default: `,`)
+//! * `--output/-o` CSV with the match type and accepted name per input (default: `reconciled.csv`)
+//!
+//! ## Init release (`init-release`) Arguments
+//! * `--mdd` species CSV path, e.g. `MDD_v2.2_6815species.csv`
+//! * `--syn` synonym CSV path
+//! * `--version <ver>` release version (default: inferred from the `--mdd` file name)
+//! * `--date <YYYY-MM-DD>` release date (default: today)
+//! * `--doi <doi>` / `--remarks <text>` prompted for on a terminal when omitted
+//! * `--output/-o` TOML path (default: `release.toml`); CSV paths are stored relative to it
+//! * `--force` overwrite an existing TOML
+//!
+//! ## Stats (`stats`) Arguments
+//! * `--input/-i` species CSV path (default: `data.csv`)
+//! * `--delimiter <char>` field delimiter of the CSV (default: `,`)
+//! * `--output/-o` also write the summary as JSON to this path
+//!
+//! ## Crosswalk (`crosswalk`) Arguments
+//! * `--input/-i` species CSV path (default: `data.csv`)
+//! * `--delimiter <char>` field delimiter of the CSV (default: `,`)
+//! * `--output/-o` directory for `msw3_crosswalk.{csv,json}`,
+//!   `cmw_crosswalk.{csv,json}`, and `manifest.json` (default: `.`)
+//! * `--itis <dir>` extracted ITIS dump; also writes `itis_crosswalk.{csv,json}`
+//! * `--ncbi <dir>` extracted NCBI taxdump; also writes `ncbi_crosswalk.{csv,json}`
+//!
+//! ## Vernacular (`vernacular`) Arguments
+//! * `--input/-i` species CSV path (default: `data.csv`)
+//! * `--delimiter <char>` field delimiter of the CSV (default: `,`)
+//! * `--output/-o` directory for `vernacular_names.{csv,json}` and
+//!   `manifest.json` (default: `.`)
+//!
+//! ## Serve (`serve`) Arguments
+//! * `--input/-i` JSON bundle produced by `json`/`zip` (`.json`, optionally `.gz`/`.zst`/`.br`, default: `data.json`)
+//! * `--graphql` also serve the GraphQL API at `/graphql` (GET opens GraphiQL)
+//! * `--host` address to listen on (default: `127.0.0.1`)
+//! * `--port/-p` port to listen on (default: `8080`)
+//!
+//! Endpoints: `/species`, `/species/{id}`, `/synonyms`, `/countries/{code}/species`,
+//! and `/search?q=`, with `family`, `order`, `genus`, `country`, `iucn`, `offset`,
+//! and `limit` query-string filters (see `mdd_api::rest`).
+//!
+//! ## Schema (`schema`) Arguments
+//! * `--output/-o` directory for `ReleasedMddData.schema.json`, `MddData.schema.json`,
+//!   `SynonymData.schema.json`, and `CountryMDDStats.schema.json` (default: `.`)
+//!
+//! ## Render (`render`) Arguments
+//! * `--input/-i` JSON bundle produced by `json`/`zip` (`.json`, optionally `.gz`/`.zst`/`.br`, default: `data.json`)
+//! * `--output/-o` directory for `index.<ext>`, `species/`, and `manifest.json`
+//!   (default: `site`)
+//! * `--format <md|html>` page format (default: `md`)
+//! * `--template <path>` Tera template replacing the species page
+//! * `--index-template <path>` Tera template replacing the index page
+//! * `--slug` name pages by species slug instead of MDD id
+//!
+//! See `mdd_api::render` for the template variables (`render` feature).
 //!
 //! ## ZIP (`zip`) Arguments
-//! * `--input/-i` release archive path (default: `MDD.zip`)
+//! * `--input/-i` release `.zip`, `.tar.gz`, or directory of release files
+//!   (default: `MDD.zip`)
 //! * `--output/-o` extraction + output directory (default: `.`)
+//! * `--in-memory` read the archive entries directly instead of extracting them
+//!   (directories are always read in place)
+//! * `--species-pattern` / `--synonym-pattern` file name globs of the CSVs
+//!   (default: `MDD_v*.csv` / `Species_Syn_v*.csv`)
+//! * `--release-dir` folder holding the CSVs (default: `MDD`; the whole
+//!   release is searched when it does not exist)
+//! * `--no-sniff` never classify unmatched CSVs by their header row
+//! * `--pretty` / `--field-names`, see JSON Output Flags
+//!
+//! ## Package (`package`) Arguments
+//! * `--input/-i` release TOML referencing the species and synonym CSVs
+//!   (default: `release.toml`)
+//! * `--output/-o` output directory of `MDD_v<version>.zip` (default: `.`)
+//! * `--include <path>` extra file (e.g. a JSON export) packaged next to the
+//!   CSVs; repeatable
+//! * `--no-verify` skip checking the CSVs against the digests in the TOML
+//!
+//! The CSVs are renamed to `MDD/MDD_v<version>_<n>species.csv` and
+//! `MDD/Species_Syn_v<version>.csv`, and the packaged `MDD/release.toml`
+//! points at them with fresh SHA-256 digests.
 //!
 //! ## Zip Quick Start
 //! Minimal end‑to‑end example (also shown in README):
 //!
 //! ```text
 //! mdd zip --input MDD_2025_1.zip --output ./out
-//! # Produces JSON + stats (as implemented) under ./out
+//! # Produces JSON + stats (as implemented) under ./out, plus a manifest.json
+//! # recording the release, generation time, and crate version of every file
 //! ```
 //!
-//! Programmatic parsing mirrors the `ZipParser` steps: open archive, locate the
+//! Programmatic parsing mirrors the `ArchiveParser` steps: open archive, locate the
 //! `MDD_v*.csv` and `Species_Syn_v*.csv` entries, read to string, then feed into
 //! `MddData::from_csv` and `SynonymData::from_csv` followed by
 //! `ReleasedMddData::from_parser`.
@@ -40,390 +325,2691 @@
 //! (Future work may stabilize a public helper around this flow.)
 //!
 use std::{
+    collections::BTreeMap,
     fs,
+    io::{BufReader, IsTerminal, Read, Write},
     path::{Path, PathBuf},
+    process::ExitCode,
 };
 
-use args::{Cli, JsonArgs};
+use args::{
+    BatchArgs, ChangelogArgs, ChangelogFormat, Cli, Commands, CompressionKind, CrosswalkArgs,
+    DbFormat, DiffArgs, FromTomlArgs, InitReleaseArgs, InputFormat, JsonArgs, OutputFormat,
+    ReconcileArgs, ReleaseSeriesArgs, SplitBy, StatsArgs, ValidateArgs, VernacularArgs,
+};
 use chrono::DateTime;
 use clap::Parser;
+use error::{CliError, Context, ErrorKind};
+use log::{debug, info};
 use mdd_api::{
-    helper::country_code::CountryRegionCode,
+    changelog::Changelog,
+    crosswalk::{
+        itis::{ItisDump, ITIS_CROSSWALK_FILE},
+        ncbi::{NcbiTaxonomy, NCBI_CROSSWALK_FILE},
+        write_table, Crosswalk, IdMatchType,
+    },
+    diff::{
+        distribution::{DistributionDiff, DISTRIBUTION_DIFF_FILE},
+        ReleaseDiff,
+    },
+    helper::{
+        cache::{fingerprint, RunCache},
+        country_code::CountryRegionCode,
+    },
+    longitudinal::SpeciesHistory,
     parser::{
-        country::CountryMDDStats, mdd::MddData, metadata::ReleaseToml, synonyms::SynonymData,
+        archive::{read_header_line, version_from_file_name, ArchiveKind, EntryPatterns},
+        country::{CountryMDDStats, IsoCountryStats},
+        iucn::IucnStats,
+        mdd::MddData,
+        metadata::{ReleaseMetadata, ReleaseToml},
+        options::{CsvDialect, ParseOptions},
+        pipeline::Pipeline,
+        reader::{MddCsvReader, SynonymCsvReader},
+        schema::{detect_csv_kind, CsvKind},
+        synonyms::SynonymData,
+        version::ReleaseVersion,
         ReleasedMddData,
     },
+    quality::{
+        flagged::FlaggedReport, homonyms::find_homonyms, synonym_only::SynonymOnlyReport,
+        validate::validate_records, QualityReport,
+    },
+    reconcile::{summarize, Reconciler},
+    stats::{authority::AuthorityStats, distribution::RegionStats, summary::DatasetSummary},
+    vernacular::VernacularIndex,
+    writer::{
+        compression::Compression,
+        json::JsonStyle,
+        ndjson::{self, NdjsonWriter},
+        per_species::{PerSpeciesWriter, SpeciesFileNaming, SPECIES_DIR},
+        provenance::{Manifest, ProvenanceHeader},
+        release_index::{ReleaseIndex, ReleaseIndexEntry},
+        sharded::{ShardKey, ShardedWriter, SHARD_DIR},
+        taxon_summary::TaxonSummaries,
+    },
 };
-use regex::Regex;
 
-use crate::args::FromZipArgs;
+#[cfg(feature = "archive")]
+use crate::args::{FromZipArgs, PackageArgs};
+#[cfg(feature = "geo")]
+use mdd_api::quality::type_country::{check_type_countries, CountryShapes};
 
 mod args;
+mod error;
+mod logger;
 
 /// The default output file name for the JSON data.
 const DEFAULT_OUTPUT_FNAME: &str = "data";
 /// The default output file name for the country statistics.
 const DEFAULT_COUNTRY_STATS_FNAME: &str = "country_stats";
+/// The default output file name for the ISO-keyed country statistics.
+const DEFAULT_COUNTRY_ISO_STATS_FNAME: &str = "country_iso_stats";
+/// The default output file name for the IUCN statistics.
+const DEFAULT_IUCN_STATS_FNAME: &str = "iucn_stats";
+/// The default output file name for the description-date statistics.
+const DEFAULT_AUTHORITY_STATS_FNAME: &str = "authority_stats";
+/// The default output file name for the continent statistics.
+const DEFAULT_CONTINENT_STATS_FNAME: &str = "continent_stats";
+/// The default output file name for the biogeographic realm statistics.
+const DEFAULT_REALM_STATS_FNAME: &str = "realm_stats";
 /// The default output file name for the country region codes.
 const DEFAULT_COUNTRY_REGION_FNAME: &str = "country_region_code";
 /// The default JSON file extension.
 const JSON_EXT: &str = "json";
-/// The default gzip file extension.
-const GZIP_EXT: &str = "json.gz";
 /// The default prefix for the output file name.
 const DEFAULT_PREFIX: &str = "mdd";
+/// The default output file name for release diffs.
+const DEFAULT_DIFF_FNAME: &str = "release_diff";
+/// The default output file name for release changelogs.
+const DEFAULT_CHANGELOG_FNAME: &str = "changelog";
+/// The default output file name for country trends across releases.
+const DEFAULT_COUNTRY_TRENDS_FNAME: &str = "country_trends";
+/// The Markdown file extension.
+const MARKDOWN_EXT: &str = "md";
+/// The cache entry of the `json` command.
+const JSON_CACHE_ENTRY: &str = "json";
+/// Path argument standing for stdin (inputs) or stdout (outputs).
+const STDIO_PATH: &str = "-";
 
 /// The main function of the CLI.
-fn main() {
-    let args = Cli::parse();
-    match args {
-        Cli::ToJson(args) => {
-            let parser = JsonParser::from_args(&args);
-            parser.parse_to_json();
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    logger::init(cli.verbose, cli.quiet);
+    match run(cli.command) {
+        Ok(code) => code,
+        Err(e) => {
+            e.report(cli.error_format);
+            e.exit_code()
+        }
+    }
+}
+
+/// Runs one subcommand, returning its exit status.
+fn run(command: Commands) -> Result<ExitCode, CliError> {
+    match command {
+        #[cfg(not(feature = "xlsx"))]
+        Commands::ToJson(args) if args.input_format == InputFormat::Xlsx => {
+            return Err(CliError::bad_input(
+                "Reading spreadsheets requires the `xlsx` feature",
+            ));
+        }
+        Commands::ToJson(args) => {
+            let (input, synonym) = match args.input_format {
+                InputFormat::Csv => resolve_csv_inputs(&args.input, &args.synonym)?,
+                _ => (args.input.clone(), args.synonym.clone()),
+            };
+            let mut parser = JsonParser::from_args(&args);
+            parser.input_path = &input;
+            parser.synonym_path = &synonym;
+            parser.parse_to_json()?;
+        }
+        #[cfg(feature = "archive")]
+        Commands::FromZip(args) => {
+            let parser = ArchiveParser::from_args(&args)?;
+            parser.parse_to_json()?;
+        }
+        #[cfg(not(feature = "archive"))]
+        Commands::FromZip(_) => {
+            return Err(CliError::bad_input(
+                "Reading release archives requires the `archive` feature",
+            ));
+        }
+        #[cfg(feature = "archive")]
+        Commands::Package(args) => {
+            let packager = Packager::from_args(&args);
+            packager.run()?;
+        }
+        #[cfg(not(feature = "archive"))]
+        Commands::Package(_) => {
+            return Err(CliError::bad_input(
+                "Packaging release archives requires the `archive` feature",
+            ));
+        }
+        Commands::Diff(args) => {
+            let runner = DiffRunner::from_args(&args);
+            runner.run()?;
         }
-        Cli::FromZip(args) => {
-            let parser = ZipParser::from_args(&args);
-            parser.parse_to_json();
+        Commands::Changelog(args) => {
+            let runner = ChangelogRunner::from_args(&args);
+            runner.run()?;
         }
-        Cli::FromToml(_) => {
-            println!("Not implemented");
+        Commands::Trends(args) => {
+            let runner = ReleaseSeriesRunner::from_args(&args);
+            runner.trends()?;
         }
-        Cli::ToDb(_) => {
-            println!("Not implemented");
+        Commands::History(args) => {
+            let runner = ReleaseSeriesRunner::from_args(&args);
+            runner.history()?;
+        }
+        Commands::Batch(args) => {
+            let runner = BatchRunner::from_args(&args);
+            runner.run()?;
+        }
+        #[cfg(feature = "fetch")]
+        Commands::Fetch(args) => {
+            let fetcher = Fetcher::from_args(&args)?;
+            fetcher.fetch_and_parse()?;
+        }
+        #[cfg(not(feature = "fetch"))]
+        Commands::Fetch(_) => {
+            return Err(CliError::bad_input(
+                "Downloading releases requires the `fetch` feature",
+            ));
+        }
+        #[cfg(feature = "enrich")]
+        Commands::Gbif(args) => {
+            let runner = GbifRunner::from_args(&args);
+            runner.run()?;
+        }
+        #[cfg(not(feature = "enrich"))]
+        Commands::Gbif(_) => {
+            return Err(CliError::bad_input(
+                "Matching against GBIF requires the `enrich` feature",
+            ));
+        }
+        #[cfg(feature = "enrich")]
+        Commands::Iucn(args) => {
+            let runner = IucnRunner::from_args(&args);
+            runner.run()?;
+        }
+        #[cfg(not(feature = "enrich"))]
+        Commands::Iucn(_) => {
+            return Err(CliError::bad_input(
+                "Querying the IUCN Red List requires the `enrich` feature",
+            ));
+        }
+        #[cfg(feature = "enrich")]
+        Commands::Wikidata(args) => {
+            let runner = WikidataRunner::from_args(&args);
+            runner.run()?;
+        }
+        #[cfg(not(feature = "enrich"))]
+        Commands::Wikidata(_) => {
+            return Err(CliError::bad_input(
+                "Linking Wikidata items requires the `enrich` feature",
+            ));
+        }
+        Commands::Validate(args) => {
+            let (input, synonym) = resolve_csv_inputs(&args.input, &args.synonym)?;
+            let mut validator = Validator::from_args(&args);
+            validator.input_path = &input;
+            validator.synonym_path = &synonym;
+            return validator.run();
+        }
+        Commands::Reconcile(args) => {
+            let runner = ReconcileRunner::from_args(&args);
+            runner.run()?;
+        }
+        Commands::InitRelease(args) => {
+            let runner = ReleaseInitializer::from_args(&args);
+            runner.run()?;
+        }
+        Commands::Stats(args) => {
+            let runner = StatsRunner::from_args(&args);
+            runner.run()?;
+        }
+        Commands::Crosswalk(args) => {
+            let runner = CrosswalkRunner::from_args(&args);
+            runner.run()?;
+        }
+        Commands::Vernacular(args) => {
+            let runner = VernacularRunner::from_args(&args);
+            runner.run()?;
+        }
+        #[cfg(feature = "schema")]
+        Commands::Schema(args) => {
+            let paths = mdd_api::writer::json_schema::write_schemas(&args.output)
+                .context("Failed to write JSON schemas")?;
+            info!("{} schemas written to: {:?}", paths.len(), args.output);
+        }
+        #[cfg(not(feature = "schema"))]
+        Commands::Schema(_) => {
+            return Err(CliError::bad_input(
+                "JSON Schema generation requires the `schema` feature",
+            ));
+        }
+        #[cfg(not(feature = "graphql"))]
+        Commands::Serve(args) if args.graphql => {
+            return Err(CliError::bad_input(
+                "Serving a GraphQL API requires the `graphql` feature",
+            ));
+        }
+        #[cfg(feature = "server")]
+        Commands::Serve(args) => {
+            let server = Server::from_args(&args);
+            server.run()?;
+        }
+        #[cfg(not(feature = "server"))]
+        Commands::Serve(_) => {
+            return Err(CliError::bad_input(
+                "Serving an API requires the `server` feature",
+            ));
+        }
+        #[cfg(feature = "render")]
+        Commands::Render(args) => {
+            let runner = PageRunner::from_args(&args);
+            runner.run()?;
+        }
+        #[cfg(not(feature = "render"))]
+        Commands::Render(_) => {
+            return Err(CliError::bad_input(
+                "Rendering species pages requires the `render` feature",
+            ));
+        }
+        Commands::FromToml(args) => {
+            let parser = TomlParser::from_args(&args);
+            parser.parse_to_json()?;
+        }
+        #[cfg(not(feature = "duckdb"))]
+        Commands::ToDb(args) if args.format == DbFormat::Duckdb => {
+            return Err(CliError::bad_input(
+                "DuckDB export requires the `duckdb` feature",
+            ));
+        }
+        #[cfg(feature = "db")]
+        Commands::ToDb(args) => {
+            let exporter = DbExporter::from_args(&args);
+            exporter.export()?;
+        }
+        #[cfg(not(feature = "db"))]
+        Commands::ToDb(_) => {
+            return Err(CliError::bad_input(
+                "SQLite export requires the `db` feature",
+            ));
         }
     }
+    Ok(ExitCode::SUCCESS)
 }
 
-/// A parser for extracting MDD data from a zip file.
-struct ZipParser<'a> {
-    /// The path to the input zip file.
-    input_path: &'a Path,
+/// Downloads a release archive and hands it to `ArchiveParser`.
+#[cfg(feature = "fetch")]
+struct Fetcher<'a> {
+    /// The release version to download.
+    version: &'a str,
+    /// Where to resolve the release.
+    source: mdd_api::fetch::ReleaseSource,
     /// The path to the output directory.
     output_path: &'a Path,
 }
 
-impl<'a> ZipParser<'a> {
-    /// Creates a new `ZipParser` from the command-line arguments.
-    fn from_args(args: &'a FromZipArgs) -> Self {
-        Self {
-            input_path: &args.input,
-            output_path: &args.output,
-        }
-    }
+#[cfg(feature = "fetch")]
+impl<'a> Fetcher<'a> {
+    /// Creates a new `Fetcher` from the command-line arguments.
+    fn from_args(args: &'a args::FetchArgs) -> Result<Self, CliError> {
+        use mdd_api::fetch::ReleaseSource;
 
-    /// Parses the MDD data from the zip file and converts it to a JSON file.
-    fn parse_to_json(&self) {
-        self.extract_zip_file();
-        // We will find the MDD file prefix with MDD_v in the file name.
-        // and synonym file with prefix "Species_Syn_v"
-        println!("Extracting files...");
-        let glob_files = glob::glob(&format!(
-            "{}/MDD/*.csv",
-            self.output_path
-                .to_str()
-                .expect("Failed to convert Path to str")
-        ));
-        println!("Finding MDD and synonym files...");
-        let files = match glob_files {
-            Ok(files) => files.filter_map(Result::ok).collect::<Vec<PathBuf>>(),
-            Err(e) => panic!("Failed to find MDD files with pattern: {}", e),
-        };
-        println!("Found {} MDD files.", files.len());
-        println!("Finding release.toml file...");
-        let meta_path = self.find_release_toml_file(self.output_path);
-        let meta = if let Some(meta_path) = meta_path {
-            let metadata =
-                ReleaseToml::from_file(&meta_path).expect("Failed to read release.toml file");
-            println!("Found release.toml file.");
-            Some(metadata)
-        } else {
-            println!("No release.toml file found. Using default metadata.");
-            None
+        let source = match (&args.doi, &args.github) {
+            (Some(doi), _) => ReleaseSource::ZenodoDoi(doi.clone()),
+            (None, Some(repo)) => {
+                let (owner, repo) = repo.split_once('/').ok_or_else(|| {
+                    CliError::bad_input("GitHub repository must be formatted as owner/repo")
+                })?;
+                ReleaseSource::GitHub {
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                }
+            }
+            (None, None) => ReleaseSource::Zenodo,
         };
+        Ok(Self {
+            version: &args.mdd_version,
+            source,
+            output_path: &args.output,
+        })
+    }
 
-        let mdd_file = self.find_mdd_file(&files);
-        let syn_file = self.find_synonym_file(&files);
-        if mdd_file.is_none() || syn_file.is_none() {
-            panic!("MDD or synonym file not found in the zip archive. Please check the zip file.");
+    /// Downloads the release zip into the output directory and parses it.
+    fn fetch_and_parse(&self) -> Result<(), CliError> {
+        let fetcher =
+            mdd_api::fetch::ReleaseFetcher::new().context("Failed to create HTTP client")?;
+        info!("Resolving MDD v{}...", self.version);
+        let asset = fetcher
+            .resolve(self.version, &self.source)
+            .context("Failed to resolve release")?;
+        info!("Downloading {} from {}", asset.file_name, asset.url);
+        let bytes = fetcher
+            .download(&asset)
+            .context("Failed to download release")?;
+        if asset.checksum.is_some() {
+            info!("Checksum verified.");
         }
+        create_output_dir(self.output_path)?;
+        let zip_path = self.output_path.join(&asset.file_name);
+        fs::write(&zip_path, bytes)
+            .with_context(|| format!("Failed to write release archive {:?}", zip_path))?;
 
-        let mut json_parser = JsonParser::from_path(
-            mdd_file.as_ref().expect("MDD file not found"),
-            syn_file.as_ref().expect("Synonym file not found"),
-            self.output_path,
-        );
-        if let Some(meta) = meta {
-            json_parser.update_release_data(&meta.metadata.release_date, &meta.metadata.version);
-        }
-        json_parser.parse_to_json();
+        let parser = ArchiveParser {
+            input_path: &zip_path,
+            output_path: self.output_path,
+            in_memory: false,
+            json_style: JsonStyle::new(),
+            patterns: EntryPatterns::default(),
+            release_dir: mdd_api::parser::archive::RELEASE_DIR,
+        };
+        parser.parse_to_json()
     }
+}
 
-    /// Extracts the contents of the zip file to the output directory.
-    fn extract_zip_file(&self) {
-        let zip = std::fs::File::open(self.input_path).expect("Failed to open zip file");
-        let mut archive = zip::ZipArchive::new(zip).expect("Failed to read zip file");
-        // We extract the file for now to keep it simple.
-        archive
-            .extract(&self.output_path)
-            .expect("Failed to extract zip file");
-    }
+/// Matches the species of a CSV against the GBIF Backbone.
+#[cfg(feature = "enrich")]
+struct GbifRunner<'a> {
+    /// The path to the input MDD CSV file.
+    input_path: &'a Path,
+    /// The dialect of the input CSV.
+    dialect: CsvDialect,
+    /// The path to an offline backbone `Taxon.tsv`.
+    backbone_path: Option<&'a Path>,
+    /// The path to the output directory.
+    output_path: &'a Path,
+    /// The delay between API requests.
+    delay: std::time::Duration,
+    /// The maximum number of species to match.
+    limit: Option<usize>,
+}
 
-    /// Finds the release.toml file in the extracted files.
-    fn find_release_toml_file(&self, output_path: &Path) -> Option<PathBuf> {
-        for file in glob::glob(&format!("{}/**/release.toml", output_path.display())).unwrap() {
-            if let Ok(path) = file {
-                return Some(path);
-            }
+#[cfg(feature = "enrich")]
+impl<'a> GbifRunner<'a> {
+    /// Creates a new `GbifRunner` from the command-line arguments.
+    fn from_args(args: &'a args::GbifArgs) -> Self {
+        Self {
+            input_path: &args.input,
+            dialect: args.csv.dialect(),
+            backbone_path: args.backbone.as_deref(),
+            output_path: &args.output,
+            delay: std::time::Duration::from_millis(args.delay),
+            limit: args.limit,
         }
-        None
     }
 
-    /// Finds the MDD file in the extracted files.
-    fn find_mdd_file(&self, files: &[PathBuf]) -> Option<PathBuf> {
-        for file in files {
-            if file
-                .file_name()
-                .expect("Failed to get file name")
-                .to_str()
-                .expect("Failed to convert OsStr to str")
-                .starts_with("MDD_v")
-            {
-                return Some(file.to_path_buf());
-            }
-        }
-        None
-    }
+    /// Matches every species and writes `gbif_matches.json`.
+    fn run(&self) -> Result<(), CliError> {
+        use mdd_api::enrich::gbif::{GbifBackbone, GbifClient, GbifEnrichment, GbifMatchType};
 
-    /// Finds the synonym file in the extracted files.
-    fn find_synonym_file(&self, files: &[PathBuf]) -> Option<PathBuf> {
-        for file in files {
-            if file
-                .file_name()
-                .expect("Failed to get file name")
-                .to_str()
-                .expect("Failed to convert OsStr to str")
-                .starts_with("Species_Syn_v")
-            {
-                return Some(file.to_path_buf());
+        let mdd_file = open_file(self.input_path, "Parsing species")?;
+        let mut species = parse_species(mdd_file, &self.dialect)?;
+        if let Some(limit) = self.limit {
+            species.truncate(limit);
+        }
+        let enrichment = match self.backbone_path {
+            Some(path) => {
+                info!("Reading GBIF Backbone from {:?}", path);
+                let backbone =
+                    GbifBackbone::from_path(path).context("Failed to read GBIF Backbone")?;
+                GbifEnrichment::build(&species, &backbone)
+            }
+            None => {
+                info!("Matching {} species with the GBIF API...", species.len());
+                let client = GbifClient::new()
+                    .context("Failed to create HTTP client")?
+                    .with_delay(self.delay);
+                GbifEnrichment::build(&species, &client)
             }
         }
-        None
+        .context("Failed to match species in GBIF")?;
+        let count = |match_type| enrichment.counts.get(&match_type).copied().unwrap_or(0);
+        info!(
+            "{} exact, {} fuzzy, {} higher rank, {} unmatched, {} failed",
+            count(GbifMatchType::Exact),
+            count(GbifMatchType::Fuzzy),
+            count(GbifMatchType::HigherRank),
+            count(GbifMatchType::None),
+            enrichment.failures.len()
+        );
+        create_output_dir(self.output_path)?;
+        let output_path = self.output_path.join("gbif_matches.json");
+        write_file(&output_path, enrichment.to_json())?;
+        info!("Matches written to {:?}", output_path);
+        Ok(())
     }
 }
 
-/// A parser for converting MDD data from a CSV file to a JSON file.
-struct JsonParser<'a> {
+/// Compares the categories of a species CSV with the IUCN Red List API.
+#[cfg(feature = "enrich")]
+struct IucnRunner<'a> {
     /// The path to the input MDD CSV file.
     input_path: &'a Path,
-    /// The path to the input synonym CSV file.
-    synonym_path: &'a Path,
+    /// The dialect of the input CSV.
+    dialect: CsvDialect,
+    /// The IUCN Red List API token.
+    token: &'a str,
     /// The path to the output directory.
     output_path: &'a Path,
-    /// Whether to write the output as plain text.
-    plain_text: bool,
-    /// The version of the MDD data.
-    mdd_version: Option<String>,
-    /// The release date of the MDD data.
-    release_date: Option<String>,
-    /// The maximum number of records to parse.
+    /// Whether to write the records with their live category.
+    patched: bool,
+    /// The delay between API requests.
+    delay: std::time::Duration,
+    /// The maximum number of species to query.
     limit: Option<usize>,
-    /// The prefix for the output file name.
-    prefix: Option<&'a str>,
 }
 
-impl<'a> JsonParser<'a> {
-    /// Creates a new `JsonParser` from the given paths.
-    fn from_path(input_path: &'a Path, synonym_path: &'a Path, output_path: &'a Path) -> Self {
-        Self {
-            input_path,
-            synonym_path,
-            output_path,
-            plain_text: true,
-            mdd_version: None,
-            release_date: None,
-            limit: None,
-            prefix: Some(DEFAULT_PREFIX),
-        }
-    }
-
-    /// Updates the release data of the `JsonParser`.
-    fn update_release_data(&mut self, date: &str, version: &str) {
-        self.release_date = Some(date.to_string());
-        self.mdd_version = Some(version.to_string());
-    }
-
-    /// Creates a new `JsonParser` from the command-line arguments.
-    fn from_args(args: &'a JsonArgs) -> Self {
+#[cfg(feature = "enrich")]
+impl<'a> IucnRunner<'a> {
+    /// Creates a new `IucnRunner` from the command-line arguments.
+    fn from_args(args: &'a args::IucnArgs) -> Self {
         Self {
             input_path: &args.input,
-            synonym_path: &args.synonym,
+            dialect: args.csv.dialect(),
+            token: &args.token,
             output_path: &args.output,
-            plain_text: args.plain_text,
-            mdd_version: args.mdd_version.clone(),
-            release_date: args.release_date.clone(),
+            patched: args.patched,
+            delay: std::time::Duration::from_millis(args.delay),
             limit: args.limit,
-            prefix: args.prefix.as_deref(),
         }
     }
 
-    /// Parses the MDD data from the CSV file and converts it to a JSON file.
-    fn parse_to_json(&self) {
-        let mdd_data = std::fs::read_to_string(self.input_path).expect("Failed to read MDD file");
-        let syn_data =
-            std::fs::read_to_string(self.synonym_path).expect("Failed to read synonym file");
-
-        println!("Parsing MDD data from: {:?}", self.input_path);
-        let parser = MddData::new();
-        let mut mdd_data = parser.from_csv(&mdd_data);
-        println!("Found MDD data records: {}", mdd_data.len());
-
-        println!("Parsing synonym data from: {:?}", self.synonym_path);
-        let synonyms = SynonymData::new();
-        let mut synonym_data = synonyms.from_csv(&syn_data);
-        println!("Found synonym data records: {}", synonym_data.len());
-
-        if synonym_data.is_empty() {
-            println!("No synonym data found");
-        }
-
-        println!("Creating country mammal diversity statistics from MDD records");
-        let mut country_stats = CountryMDDStats::new();
-        country_stats.parse_country_data(&mdd_data);
-        println!(
-            "Total countries and regions: {}, Total domesticated species: {}, Total widespread species: {}",
-            country_stats.total_countries,
-            country_stats.domesticated.len(),
-            country_stats.widespread.len()
-        );
+    /// Queries every species and writes the comparison report.
+    fn run(&self) -> Result<(), CliError> {
+        use mdd_api::enrich::iucn::{IucnClient, IucnEnrichedSpecies, IucnOutcome, IucnReport};
 
-        if self.limit.is_some() {
-            self.limit_mdd_data(&mut mdd_data, self.limit.unwrap());
-            self.limit_synonym_data(&mut synonym_data, self.limit.unwrap());
+        let mdd_file = open_file(self.input_path, "Parsing species")?;
+        let mut species = parse_species(mdd_file, &self.dialect)?;
+        if let Some(limit) = self.limit {
+            species.truncate(limit);
         }
-        let mdd_version = self.get_version();
-        let release_date = self.get_release_date();
-        println!(
-            "Using MDD version: {}, release date: {}",
-            mdd_version, release_date
+        let client = IucnClient::new(self.token)
+            .context("Failed to create HTTP client")?
+            .with_delay(self.delay);
+        info!(
+            "Querying the IUCN Red List for {} species...",
+            species.len()
         );
-        let all_data =
-            ReleasedMddData::from_parser(mdd_data, synonym_data, &mdd_version, &release_date);
-        println!("MDD v{} data parsed successfully", mdd_version);
-        println!("Total MDD records: {}", all_data.data.len());
-        println!(
-            "Total synonym only records: {}",
-            all_data.synonym_only.len()
+        let live = client
+            .assessments(&species)
+            .context("Failed to query the IUCN Red List")?;
+        let report = IucnReport::new(&species, &live);
+        info!(
+            "{} unchanged, {} mismatched, {} updated, {} not found, {} failed",
+            report.count(IucnOutcome::Unchanged),
+            report.count(IucnOutcome::Mismatch),
+            report.count(IucnOutcome::Updated),
+            report.count(IucnOutcome::NotFound),
+            report.count(IucnOutcome::Failed)
         );
-        let json = all_data.to_json();
-        fs::create_dir_all(self.output_path).unwrap_or_else(|_| {
-            panic!("Failed to create output directory: {:?}", self.output_path)
-        });
-        if self.plain_text {
-            self.write_plain_text(&json);
-            self.write_gzip(&json);
-            println!("Output written to: {:?}", self.get_output_path(false));
-        } else {
-            self.write_gzip(&json);
+        create_output_dir(self.output_path)?;
+        let report_path = self.output_path.join("iucn_report.json");
+        write_file(&report_path, report.to_json())?;
+        info!("Report written to {:?}", report_path);
+        if self.patched {
+            let enriched = IucnEnrichedSpecies::from_species(&species, &live);
+            let enriched_path = self.output_path.join("iucn_enriched.json");
+            write_json_to_file(&enriched_path, &enriched)?;
+            info!("Records written to {:?}", enriched_path);
         }
-
-        // Write country statistics to JSON file
-        country_stats.write_to_json_file(
-            &self
-                .output_path
-                .join(DEFAULT_COUNTRY_STATS_FNAME)
-                .with_extension(JSON_EXT),
-        );
-
-        CountryRegionCode::new().write_to_file(
-            &self
-                .output_path
-                .join(DEFAULT_COUNTRY_REGION_FNAME)
-                .with_extension(JSON_EXT),
-        );
+        Ok(())
     }
+}
 
-    /// Returns the version of the MDD data.
-    ///
-    /// We use the version if specified.
-    /// Otherwise, we will infer from the file name.
-    /// MDD species file_stem example: MDD_v2.2_6815species.
-    /// In this case, the version is 2.2.
-    fn get_version(&self) -> String {
-        match &self.mdd_version {
-            Some(version) => version.clone(),
-            None => {
-                let file_stem = self
-                    .input_path
-                    .file_stem()
-                    .expect("Invalid file name")
-                    .to_str()
-                    .expect("Failed to convert OsStr to str");
-                // Use regex to capture the version number
-                let re =
-                    Regex::new(r"MDD_v(\d+\.\d+)").expect("Failed to compile MDD version regex");
-                if let Some(caps) = re.captures(file_stem) {
-                    caps.get(1)
-                        .map_or("unknown".to_string(), |m| m.as_str().to_string())
-                } else {
-                    "unknown".to_string()
-                }
-            }
+/// Links the species of a CSV to Wikidata items and Wikipedia articles.
+#[cfg(feature = "enrich")]
+struct WikidataRunner<'a> {
+    /// The path to the input MDD CSV file.
+    input_path: &'a Path,
+    /// The dialect of the input CSV.
+    dialect: CsvDialect,
+    /// The path to an offline Wikidata JSON dump.
+    dump_path: Option<&'a Path>,
+    /// The Wikipedia language edition.
+    language: &'a str,
+    /// The path to the output directory.
+    output_path: &'a Path,
+    /// The delay between SPARQL queries.
+    delay: std::time::Duration,
+    /// The maximum number of species to link.
+    limit: Option<usize>,
+}
+
+#[cfg(feature = "enrich")]
+impl<'a> WikidataRunner<'a> {
+    /// Creates a new `WikidataRunner` from the command-line arguments.
+    fn from_args(args: &'a args::WikidataArgs) -> Self {
+        Self {
+            input_path: &args.input,
+            dialect: args.csv.dialect(),
+            dump_path: args.dump.as_deref(),
+            language: &args.language,
+            output_path: &args.output,
+            delay: std::time::Duration::from_millis(args.delay),
+            limit: args.limit,
         }
     }
 
-    /// Returns the release date of the MDD data.
-    ///
-    /// We infer release date from the metadata if not specified.
-    fn get_release_date(&self) -> String {
-        match &self.release_date {
-            Some(date) => date.clone(),
+    /// Links every species and writes `wikidata_links.json`.
+    fn run(&self) -> Result<(), CliError> {
+        use mdd_api::enrich::wikidata::{WikidataClient, WikidataDump, WikidataLinks};
+
+        let mdd_file = open_file(self.input_path, "Parsing species")?;
+        let mut species = parse_species(mdd_file, &self.dialect)?;
+        if let Some(limit) = self.limit {
+            species.truncate(limit);
+        }
+        let links = match self.dump_path {
+            Some(path) => {
+                info!("Reading Wikidata dump from {:?}", path);
+                let dump = WikidataDump::from_path(path, self.language)
+                    .context("Failed to read Wikidata dump")?;
+                WikidataLinks::build(&species, &dump)
+            }
             None => {
-                let file_meta =
-                    fs::metadata(self.input_path).expect("Failed to read file metadata");
-                let modified_time = file_meta
-                    .created()
-                    .expect("Failed to get file modified time");
-                let date = DateTime::<chrono::Local>::from(modified_time);
-                date.format("%B %e, %Y").to_string()
+                info!("Linking {} species with Wikidata...", species.len());
+                let client = WikidataClient::new()
+                    .context("Failed to create HTTP client")?
+                    .with_language(self.language)?
+                    .with_delay(self.delay);
+                WikidataLinks::build(&species, &client)
             }
         }
+        .context("Failed to link species to Wikidata")?;
+        let with_article = links
+            .links
+            .iter()
+            .filter(|link| link.wikipedia_url.is_some())
+            .count();
+        info!(
+            "{} linked, {} with a Wikipedia article, {} unmatched",
+            links.links.len() - links.unmatched().count(),
+            with_article,
+            links.unmatched().count()
+        );
+        create_output_dir(self.output_path)?;
+        let output_path = self.output_path.join("wikidata_links.json");
+        write_file(&output_path, links.to_json())?;
+        info!("Links written to {:?}", output_path);
+        Ok(())
     }
+}
 
-    /// Limits the number of MDD data records.
-    fn limit_mdd_data(&self, data: &mut Vec<MddData>, limit: usize) {
-        data.truncate(limit);
-    }
+/// A parser for MDD data in a release zip, tar.gz, or directory.
+#[cfg(feature = "archive")]
+struct ArchiveParser<'a> {
+    /// The path to the input archive or release directory.
+    input_path: &'a Path,
+    /// The path to the output directory.
+    output_path: &'a Path,
+    /// Whether to read the archive entries in memory instead of extracting them.
+    in_memory: bool,
+    /// The layout of the JSON bundle.
+    json_style: JsonStyle,
+    /// The file name patterns of the species and synonym CSVs.
+    patterns: EntryPatterns,
+    /// The folder of the release holding the CSVs.
+    release_dir: &'a str,
+}
 
-    /// Limits the number of synonym data records.
-    fn limit_synonym_data(&self, data: &mut Vec<SynonymData>, limit: usize) {
-        data.truncate(limit);
+#[cfg(feature = "archive")]
+impl<'a> ArchiveParser<'a> {
+    /// Creates a new `ArchiveParser` from the command-line arguments.
+    fn from_args(args: &'a FromZipArgs) -> Result<Self, CliError> {
+        let patterns = EntryPatterns::new()
+            .with_species_pattern(&args.species_pattern)?
+            .with_synonym_pattern(&args.synonym_pattern)?
+            .with_header_sniffing(!args.no_sniff);
+        Ok(Self {
+            input_path: &args.input,
+            output_path: &args.output,
+            in_memory: args.in_memory,
+            json_style: args.json.json_style(),
+            patterns,
+            release_dir: &args.release_dir,
+        })
     }
 
-    /// Writes the given data to a plain text file.
-    fn write_plain_text(&self, data: &str) {
-        let output = self.get_output_path(false);
-        std::fs::write(output, data).expect("Unable to write file");
-    }
+    /// Parses the MDD data from the archive and converts it to a JSON file.
+    fn parse_to_json(&self) -> Result<(), CliError> {
+        let kind = self.archive_kind()?;
+        if self.in_memory && kind != ArchiveKind::Directory {
+            return self.parse_in_memory(kind);
+        }
+        // Directories are read in place; archives are extracted first.
+        let release_dir = match kind {
+            ArchiveKind::Directory => self.input_path,
+            ArchiveKind::Zip => {
+                self.extract_zip_file()?;
+                self.output_path
+            }
+            ArchiveKind::TarGz => {
+                self.extract_tar_gz_file()?;
+                self.output_path
+            }
+        };
+        // We will find the MDD file prefix with MDD_v in the file name.
+        // and synonym file with prefix "Species_Syn_v"
+        debug!("Finding MDD and synonym files...");
+        let files = self.find_csv_files(release_dir)?;
+        info!("Found {} MDD files.", files.len());
+        debug!("Finding release.toml file...");
+        let meta_path = self.find_release_toml_file(release_dir);
+        let meta = if let Some(meta_path) = meta_path {
+            let metadata =
+                ReleaseToml::from_file(&meta_path).context("Failed to read release.toml file")?;
+            debug!("Found release.toml file.");
+            Some(metadata)
+        } else {
+            info!("No release.toml file found. Using default metadata.");
+            None
+        };
+
+        let (mdd_file, syn_file) = self
+            .patterns
+            .find_release_files(&files)
+            .with_context(|| format!("Release files not found in {:?}", self.input_path))?;
+
+        let mut json_parser = JsonParser::from_path(&mdd_file, &syn_file, self.output_path);
+        if let Some(meta) = meta {
+            json_parser.update_from_release_toml(&meta);
+        }
+        json_parser.output.json_style = self.json_style;
+        json_parser.parse_to_json()
+    }
+
+    /// Container of the input, from its path.
+    fn archive_kind(&self) -> Result<ArchiveKind, CliError> {
+        ArchiveKind::from_path(self.input_path).ok_or_else(|| {
+            CliError::bad_input(format!(
+                "{:?} is not a .zip, .tar.gz, or release directory",
+                self.input_path
+            ))
+        })
+    }
+
+    /// Parses the archive entries directly, without extracting them to disk.
+    fn parse_in_memory(&self, kind: ArchiveKind) -> Result<(), CliError> {
+        info!("Reading archive in memory: {:?}", self.input_path);
+        let archive = BufReader::new(open_file(self.input_path, "Reading archive")?);
+        let all_data = match kind {
+            ArchiveKind::TarGz => ReleasedMddData::from_tar_gz_reader_with(archive, &self.patterns),
+            _ => ReleasedMddData::from_zip_reader_with(archive, &self.patterns),
+        }
+        .context("Failed to parse archive")?;
+        info!(
+            "MDD v{} data parsed successfully ({} species)",
+            all_data.get_version(),
+            all_data.data.len()
+        );
+        let species: Vec<MddData> = all_data.species().cloned().collect();
+        let mut country_stats = CountryMDDStats::new();
+        country_stats.parse_country_data(&species);
+
+        let mut output = ReleaseOutput::new(self.output_path);
+        output.json_style = self.json_style;
+        output.write_release(&all_data, &country_stats)?;
+        Ok(())
+    }
+
+    /// Extracts the contents of the zip file to the output directory.
+    fn extract_zip_file(&self) -> Result<(), CliError> {
+        info!("Extracting files...");
+        let zip = open_file(self.input_path, "Extracting")?;
+        let mut archive = zip::ZipArchive::new(zip)
+            .map_err(|e| CliError::bad_input(format!("Failed to read zip file: {}", e)))?;
+        // We extract the file for now to keep it simple.
+        archive
+            .extract(self.output_path)
+            .map_err(|e| CliError::bad_input(format!("Failed to extract zip file: {}", e)))
+    }
+
+    /// Extracts the contents of the tar.gz file to the output directory.
+    fn extract_tar_gz_file(&self) -> Result<(), CliError> {
+        info!("Extracting files...");
+        let tarball = open_file(self.input_path, "Extracting")?;
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(tarball));
+        archive
+            .unpack(self.output_path)
+            .map_err(|e| CliError::bad_input(format!("Failed to extract tar.gz file: {}", e)))
+    }
+
+    /// CSVs in the release folder of `release_dir`, or anywhere in it when
+    /// it has no such folder (e.g. a checkout of the release files).
+    fn find_csv_files(&self, release_dir: &Path) -> Result<Vec<PathBuf>, CliError> {
+        let nested = release_dir.join(self.release_dir);
+        let pattern = if nested.is_dir() {
+            format!("{}/*.csv", nested.display())
+        } else {
+            debug!("No {:?} folder, searching all CSVs", nested);
+            format!("{}/**/*.csv", release_dir.display())
+        };
+        Ok(glob::glob(&pattern)
+            .map_err(|e| {
+                CliError::bad_input(format!("Failed to find MDD files with pattern: {}", e))
+            })?
+            .filter_map(Result::ok)
+            .collect())
+    }
+
+    /// Finds the release.toml file in the release files.
+    fn find_release_toml_file(&self, release_dir: &Path) -> Option<PathBuf> {
+        glob::glob(&format!("{}/**/release.toml", release_dir.display()))
+            .ok()?
+            .flatten()
+            .next()
+    }
+}
+
+/// Packages a release TOML and its CSVs into a release zip.
+#[cfg(feature = "archive")]
+struct Packager<'a> {
+    /// The path to the input release TOML.
+    input_path: &'a Path,
+    /// The path to the output directory.
+    output_path: &'a Path,
+    /// Extra files packaged next to the CSVs.
+    include: &'a [PathBuf],
+    /// Whether the CSVs are checked against the digests in the TOML.
+    verify: bool,
+}
+
+#[cfg(feature = "archive")]
+impl<'a> Packager<'a> {
+    /// Creates a new `Packager` from the command-line arguments.
+    fn from_args(args: &'a PackageArgs) -> Self {
+        Self {
+            input_path: &args.input,
+            output_path: &args.output,
+            include: &args.include,
+            verify: !args.no_verify,
+        }
+    }
+
+    /// Writes `MDD_v<version>.zip` to the output directory.
+    fn run(&self) -> Result<(), CliError> {
+        use mdd_api::writer::package::ReleasePackage;
+
+        let mut package =
+            ReleasePackage::from_toml_file(self.input_path).context("Failed to read release")?;
+        if self.verify {
+            verify_release_files(self.input_path, package.toml())?;
+        }
+        for path in self.include {
+            package = package.with_file(path);
+        }
+        info!("Packaging {}...", package.archive_name());
+        let output_path = package
+            .write_to_dir(self.output_path)
+            .context("Failed to package release")?;
+        info!("Release archive written to {:?}", output_path);
+        Ok(())
+    }
+}
+
+/// Runs data-quality checks over species and synonym CSVs.
+struct Validator<'a> {
+    /// The path to the input MDD CSV file.
+    input_path: &'a Path,
+    /// The path to the input synonym CSV file.
+    synonym_path: &'a Path,
+    /// The path to the JSON report.
+    output_path: &'a Path,
+    /// The path to the optional homonym report.
+    homonyms_path: Option<&'a Path>,
+    /// The directory of the optional flagged species report.
+    flagged_path: Option<&'a Path>,
+    /// The directory of the optional synonym-only report.
+    synonym_only_path: Option<&'a Path>,
+    /// The path to the optional country outlines.
+    country_shapes_path: Option<&'a Path>,
+    /// Whether warnings make the run fail.
+    deny_warnings: bool,
+    /// The dialect of the input CSVs.
+    dialect: CsvDialect,
+}
+
+impl<'a> Validator<'a> {
+    /// Creates a new `Validator` from the command-line arguments.
+    fn from_args(args: &'a ValidateArgs) -> Self {
+        Self {
+            input_path: &args.input,
+            synonym_path: &args.synonym,
+            output_path: &args.output,
+            homonyms_path: args.homonyms.as_deref(),
+            flagged_path: args.flagged.as_deref(),
+            synonym_only_path: args.synonym_only.as_deref(),
+            country_shapes_path: args.country_shapes.as_deref(),
+            deny_warnings: args.deny_warnings,
+            dialect: args.csv.dialect(),
+        }
+    }
+
+    /// Checks the CSVs, writes the JSON report, and returns its status.
+    ///
+    /// Rows that fail to parse are reported as `parse-error` issues instead
+    /// of aborting the run.
+    fn run(&self) -> Result<ExitCode, CliError> {
+        let options = ParseOptions::lenient().with_dialect(self.dialect.clone());
+        let mdd_csv = read_file(self.input_path)?;
+        let (species, species_report) = MddData::new()
+            .from_csv_with(&mdd_csv, &options)
+            .context("Failed to parse MDD CSV data")?;
+        let syn_csv = read_file(self.synonym_path)?;
+        let (synonyms, synonym_report) = SynonymData::new()
+            .from_csv_with(&syn_csv, &options)
+            .context("Failed to parse synonym CSV data")?;
+
+        let mut report = QualityReport::new();
+        report.push_skipped_rows("species", &species_report);
+        report.push_skipped_rows("synonyms", &synonym_report);
+        let checks = validate_records(&species, &synonyms);
+        report.species_checked = checks.species_checked;
+        report.synonyms_checked = checks.synonyms_checked;
+        report.issues.extend(checks.issues);
+        self.check_type_countries(&synonyms, &mut report)?;
+        let synonym_only = self
+            .synonym_only_path
+            .map(|_| SynonymOnlyReport::from_records(&species, &synonyms));
+        if let Some(synonym_only) = &synonym_only {
+            report.issues.extend(synonym_only.issues());
+        }
+
+        print!("{}", report.summary());
+        write_file(self.output_path, report.to_json())?;
+        info!("Report written to {:?}", self.output_path);
+        if let Some(path) = self.homonyms_path {
+            let homonyms = find_homonyms(&species, &synonyms);
+            write_file(path, homonyms.to_json())?;
+            info!("Homonym report written to {:?}", path);
+        }
+        if let Some(dir) = self.flagged_path {
+            let flagged = FlaggedReport::from_species(&species);
+            let paths = flagged
+                .write_files(dir)
+                .context("Failed to write flagged species report")?;
+            info!(
+                "Flagged species report ({} species) written to {:?}",
+                flagged.total_flagged, paths
+            );
+        }
+        if let (Some(dir), Some(synonym_only)) = (self.synonym_only_path, &synonym_only) {
+            let paths = synonym_only
+                .write_files(dir)
+                .context("Failed to write synonym-only report")?;
+            info!(
+                "Synonym-only report ({} of {} matched) written to {:?}",
+                synonym_only.resolved, synonym_only.total, paths
+            );
+        }
+        Ok(ExitCode::from(report.exit_code(self.deny_warnings) as u8))
+    }
+
+    /// Flags synonym type coordinates outside their `type_country`, using
+    /// `--country-shapes` or else the bundled outlines.
+    #[cfg(feature = "geo")]
+    fn check_type_countries(
+        &self,
+        synonyms: &[SynonymData],
+        report: &mut QualityReport,
+    ) -> Result<(), CliError> {
+        let shapes = match self.country_shapes_path {
+            Some(path) => {
+                let shapes = CountryShapes::from_geojson(&read_file(path)?).map_err(|e| {
+                    CliError::bad_input(format!("Failed to read {:?}: {}", path, e))
+                })?;
+                info!("Read {} country outlines from {:?}", shapes.len(), path);
+                shapes
+            }
+            None => CountryShapes::bundled(),
+        };
+        check_type_countries(synonyms, &shapes, report);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "geo"))]
+    fn check_type_countries(
+        &self,
+        _synonyms: &[SynonymData],
+        _report: &mut QualityReport,
+    ) -> Result<(), CliError> {
+        match self.country_shapes_path {
+            Some(_) => Err(CliError::bad_input(
+                "Type country checks require the `geo` feature",
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writes a release TOML for a species and a synonym CSV.
+struct ReleaseInitializer<'a> {
+    /// The path to the input MDD CSV file.
+    mdd_path: &'a Path,
+    /// The path to the input synonym CSV file.
+    synonym_path: &'a Path,
+    /// The path to the output TOML file.
+    output_path: &'a Path,
+    /// The release version, if not inferred from the file name.
+    mdd_version: Option<&'a str>,
+    /// The release date, today if not given.
+    release_date: Option<&'a str>,
+    /// The release DOI, prompted for if not given.
+    doi: Option<&'a str>,
+    /// The release remarks, prompted for if not given.
+    remarks: Option<&'a str>,
+    /// Whether an existing TOML is replaced.
+    force: bool,
+}
+
+impl<'a> ReleaseInitializer<'a> {
+    /// Creates a new `ReleaseInitializer` from the command-line arguments.
+    fn from_args(args: &'a InitReleaseArgs) -> Self {
+        Self {
+            mdd_path: &args.mdd_file,
+            synonym_path: &args.synonym_file,
+            output_path: &args.output,
+            mdd_version: args.mdd_version.as_deref(),
+            release_date: args.release_date.as_deref(),
+            doi: args.doi.as_deref(),
+            remarks: args.remarks.as_deref(),
+            force: args.force,
+        }
+    }
+
+    fn run(&self) -> Result<(), CliError> {
+        if self.output_path.exists() && !self.force {
+            return Err(CliError::bad_input(format!(
+                "{:?} already exists; use --force to overwrite it",
+                self.output_path
+            )));
+        }
+        let base_dir = match self.output_path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        create_output_dir(base_dir)?;
+        info!(
+            "Computing checksums of {:?} and {:?}",
+            self.mdd_path, self.synonym_path
+        );
+        let mut metadata =
+            ReleaseMetadata::from_release_files(self.mdd_path, self.synonym_path, base_dir)
+                .context("Failed to read release files")?;
+        if let Some(version) = self.mdd_version {
+            metadata.version = version.to_string();
+        }
+        if metadata.version.is_empty() {
+            return Err(CliError::bad_input(format!(
+                "Cannot infer the version from {:?}; pass --version",
+                self.mdd_path
+            )));
+        }
+        metadata.release_date = match self.release_date {
+            Some(date) => date.to_string(),
+            None => chrono::Local::now().format("%Y-%m-%d").to_string(),
+        };
+        metadata.doi = self.doi.map(str::to_string).or_else(|| prompt("DOI"));
+        metadata.remarks = self
+            .remarks
+            .map(str::to_string)
+            .or_else(|| prompt("Remarks"));
+
+        let toml = ReleaseToml { metadata }
+            .try_to_toml()
+            .context("Failed to serialize release TOML")?;
+        write_file(self.output_path, toml)?;
+        info!("Release metadata written to {:?}", self.output_path);
+        Ok(())
+    }
+}
+
+/// Asks for an optional value on an interactive terminal; empty answers and
+/// non-interactive input yield `None`.
+fn prompt(label: &str) -> Option<String> {
+    if !std::io::stdin().is_terminal() {
+        return None;
+    }
+    eprint!("{} (optional): ", label);
+    std::io::stderr().flush().ok()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).ok()?;
+    let answer = answer.trim();
+    if answer.is_empty() {
+        None
+    } else {
+        Some(answer.to_string())
+    }
+}
+
+/// Prints headline numbers of a species CSV.
+struct StatsRunner<'a> {
+    /// The path to the input MDD CSV file.
+    input_path: &'a Path,
+    /// The path to the optional JSON output.
+    output_path: Option<&'a Path>,
+    /// The dialect of the input CSV.
+    dialect: CsvDialect,
+}
+
+impl<'a> StatsRunner<'a> {
+    /// Creates a new `StatsRunner` from the command-line arguments.
+    fn from_args(args: &'a StatsArgs) -> Self {
+        Self {
+            input_path: &args.input,
+            output_path: args.output.as_deref(),
+            dialect: args.csv.dialect(),
+        }
+    }
+
+    fn run(&self) -> Result<(), CliError> {
+        let mdd_file = open_file(self.input_path, "Parsing species")?;
+        let summary = DatasetSummary::from_csv_borrowed(BufReader::new(mdd_file), &self.dialect)
+            .context("Failed to parse MDD CSV data")?;
+        print!("{}", summary.summary());
+        if let Some(path) = self.output_path {
+            write_json_to_file(path, &summary)?;
+            info!("Summary written to {:?}", path);
+        }
+        Ok(())
+    }
+}
+
+/// Writes the MSW3 and CMW crosswalk tables of a species CSV.
+struct CrosswalkRunner<'a> {
+    /// The path to the input MDD CSV file.
+    input_path: &'a Path,
+    /// The path to the output directory.
+    output_path: &'a Path,
+    /// The dialect of the input CSV.
+    dialect: CsvDialect,
+    /// The extracted ITIS dump, if any.
+    itis_dir: Option<&'a Path>,
+    /// The extracted NCBI taxdump, if any.
+    ncbi_dir: Option<&'a Path>,
+}
+
+impl<'a> CrosswalkRunner<'a> {
+    /// Creates a new `CrosswalkRunner` from the command-line arguments.
+    fn from_args(args: &'a CrosswalkArgs) -> Self {
+        Self {
+            input_path: &args.input,
+            output_path: &args.output,
+            dialect: args.csv.dialect(),
+            itis_dir: args.itis.as_deref(),
+            ncbi_dir: args.ncbi.as_deref(),
+        }
+    }
+
+    fn run(&self) -> Result<(), CliError> {
+        let mdd_file = open_file(self.input_path, "Parsing species")?;
+        let mdd_data = parse_species(mdd_file, &self.dialect)?;
+        let crosswalk = Crosswalk::from_species(&mdd_data);
+        for (match_type, count) in crosswalk.msw3_match_counts() {
+            info!("MSW3 {}: {}", match_type, count);
+        }
+        info!("Changed since CMW: {}", crosswalk.cmw_changed_count());
+        let mut paths = crosswalk
+            .write_files(self.output_path)
+            .context("Failed to write crosswalk tables")?;
+        if let Some(dir) = self.itis_dir {
+            let dump = ItisDump::from_dir(dir).context("Failed to read ITIS dump")?;
+            info!("{} ITIS mammal species names loaded", dump.len());
+            let mappings = dump.crosswalk(&mdd_data);
+            log_match_counts("ITIS", mappings.iter().map(|m| m.match_type));
+            paths.extend(
+                write_table(&mappings, self.output_path, ITIS_CROSSWALK_FILE)
+                    .context("Failed to write ITIS crosswalk")?,
+            );
+        }
+        if let Some(dir) = self.ncbi_dir {
+            let taxonomy = NcbiTaxonomy::from_dir(dir).context("Failed to read NCBI taxdump")?;
+            info!("{} NCBI mammal species names loaded", taxonomy.len());
+            let mappings = taxonomy.crosswalk(&mdd_data);
+            log_match_counts("NCBI", mappings.iter().map(|m| m.match_type));
+            paths.extend(
+                write_table(&mappings, self.output_path, NCBI_CROSSWALK_FILE)
+                    .context("Failed to write NCBI crosswalk")?,
+            );
+        }
+        info!(
+            "{} crosswalk files written to: {:?}",
+            paths.len(),
+            self.output_path
+        );
+        write_manifest(csv_provenance(self.input_path), self.output_path, &paths)?;
+        Ok(())
+    }
+}
+
+/// Writes the common name table of a species CSV.
+struct VernacularRunner<'a> {
+    /// The path to the input MDD CSV file.
+    input_path: &'a Path,
+    /// The path to the output directory.
+    output_path: &'a Path,
+    /// The dialect of the input CSV.
+    dialect: CsvDialect,
+}
+
+impl<'a> VernacularRunner<'a> {
+    /// Creates a new `VernacularRunner` from the command-line arguments.
+    fn from_args(args: &'a VernacularArgs) -> Self {
+        Self {
+            input_path: &args.input,
+            output_path: &args.output,
+            dialect: args.csv.dialect(),
+        }
+    }
+
+    fn run(&self) -> Result<(), CliError> {
+        let mdd_file = open_file(self.input_path, "Parsing species")?;
+        let mdd_data = parse_species(mdd_file, &self.dialect)?;
+        let index = VernacularIndex::from_species(&mdd_data);
+        info!("Common names: {}", index.len());
+        for language in index.languages() {
+            let count = index
+                .names()
+                .iter()
+                .filter(|name| name.language.as_deref() == Some(language))
+                .count();
+            info!("Tagged {}: {}", language, count);
+        }
+        let paths = index
+            .write_files(self.output_path)
+            .context("Failed to write common name table")?;
+        info!(
+            "{} common name files written to: {:?}",
+            paths.len(),
+            self.output_path
+        );
+        write_manifest(csv_provenance(self.input_path), self.output_path, &paths)?;
+        Ok(())
+    }
+}
+
+/// Logs the number of species per ID match type.
+fn log_match_counts(source: &str, match_types: impl Iterator<Item = IdMatchType>) {
+    let mut counts: BTreeMap<IdMatchType, usize> = BTreeMap::new();
+    for match_type in match_types {
+        *counts.entry(match_type).or_default() += 1;
+    }
+    for (match_type, count) in counts {
+        info!("{} {:?}: {}", source, match_type, count);
+    }
+}
+
+/// Matches a list of names against MDD species and synonyms.
+struct ReconcileRunner<'a> {
+    /// The path to the names file.
+    names_path: &'a Path,
+    /// The path to the input MDD CSV file.
+    input_path: &'a Path,
+    /// The path to the input synonym CSV file.
+    synonym_path: &'a Path,
+    /// The path to the output CSV file.
+    output_path: &'a Path,
+    /// The dialect of the input CSVs.
+    dialect: CsvDialect,
+}
+
+impl<'a> ReconcileRunner<'a> {
+    /// Creates a new `ReconcileRunner` from the command-line arguments.
+    fn from_args(args: &'a ReconcileArgs) -> Self {
+        Self {
+            names_path: &args.names,
+            input_path: &args.input,
+            synonym_path: &args.synonym,
+            output_path: &args.output,
+            dialect: args.csv.dialect(),
+        }
+    }
+
+    /// Reconciles every name and writes one CSV row per input name.
+    fn run(&self) -> Result<(), CliError> {
+        let names = read_file(self.names_path)?;
+        let names: Vec<&str> = names
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+        let mdd_file = open_file(self.input_path, "Parsing species")?;
+        let species = parse_species(mdd_file, &self.dialect)?;
+        let syn_file = open_file(self.synonym_path, "Parsing synonyms")?;
+        let synonyms: Vec<SynonymData> =
+            SynonymCsvReader::with_dialect(BufReader::new(syn_file), &self.dialect)
+                .and_then(|records| records.collect())
+                .context("Failed to parse synonym CSV data")?;
+
+        info!("Reconciling {} names", names.len());
+        let results = Reconciler::new(&species, &synonyms).reconcile_all(names);
+        for (match_type, count) in summarize(&results) {
+            info!("{:?}: {}", match_type, count);
+        }
+        let mut writer = csv::Writer::from_path(self.output_path)
+            .with_context(|| format!("Failed to create {:?}", self.output_path))?;
+        for result in &results {
+            writer
+                .serialize(result)
+                .context("Failed to write reconciliation row")?;
+        }
+        writer
+            .flush()
+            .with_context(|| format!("Failed to write {:?}", self.output_path))?;
+        info!("Output written to: {:?}", self.output_path);
+        Ok(())
+    }
+}
+
+/// A parser driven by a release metadata TOML file.
+struct TomlParser<'a> {
+    /// The path to the release TOML file.
+    input_path: &'a Path,
+    /// The path to the output directory.
+    output_path: &'a Path,
+    /// Whether to write the output as plain text.
+    plain_text: bool,
+    /// Whether the CSVs are checked against the recorded digests.
+    verify: bool,
+}
+
+impl<'a> TomlParser<'a> {
+    /// Creates a new `TomlParser` from the command-line arguments.
+    fn from_args(args: &'a FromTomlArgs) -> Self {
+        Self {
+            input_path: &args.input,
+            output_path: &args.output,
+            plain_text: args.plain_text,
+            verify: !args.no_verify,
+        }
+    }
+
+    /// Parses the CSV files referenced by the release TOML and converts them to JSON.
+    ///
+    /// `mdd_file` and `synonym_file` are resolved relative to the TOML location
+    /// and checked against `mdd_sha256` / `synonym_sha256` when recorded.
+    fn parse_to_json(&self) -> Result<(), CliError> {
+        let meta =
+            ReleaseToml::from_file(self.input_path).context("Failed to read release TOML")?;
+        let base_dir = self.input_path.parent().unwrap_or(Path::new("."));
+        let mdd_path = meta.metadata.mdd_path(base_dir);
+        let syn_path = meta.metadata.synonym_path(base_dir);
+        if self.verify {
+            verify_release_files(self.input_path, &meta)?;
+        }
+        info!(
+            "Parsing {} v{} from release TOML: {:?}",
+            meta.metadata.name, meta.metadata.version, self.input_path
+        );
+
+        let mut json_parser = JsonParser::from_path(&mdd_path, &syn_path, self.output_path);
+        json_parser.output.plain_text = self.plain_text;
+        json_parser.update_from_release_toml(&meta);
+        json_parser.parse_to_json()
+    }
+}
+
+/// Checks the CSVs of the release TOML at `path` against its recorded digests.
+fn verify_release_files(path: &Path, meta: &ReleaseToml) -> Result<(), CliError> {
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    let mismatches = meta
+        .verify(base_dir)
+        .context("Failed to verify release files")?;
+    for mismatch in &mismatches {
+        log::error!(
+            "Checksum mismatch for {:?}: expected {}, found {}",
+            mismatch.path,
+            mismatch.expected,
+            mismatch.actual
+        );
+    }
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+    Err(CliError::new(
+        ErrorKind::Other,
+        format!(
+            "{} release file(s) do not match the checksums in {:?}",
+            mismatches.len(),
+            path
+        ),
+    ))
+}
+
+/// A parser for converting MDD data from a CSV file to a JSON file.
+struct JsonParser<'a> {
+    /// The path to the input MDD CSV file (or workbook).
+    input_path: &'a Path,
+    /// The path to the input synonym CSV file.
+    synonym_path: &'a Path,
+    /// The format of the input files.
+    input_format: InputFormat,
+    /// The dialect of the input CSVs.
+    dialect: CsvDialect,
+    /// The version of the MDD data.
+    mdd_version: Option<String>,
+    /// The release date of the MDD data.
+    release_date: Option<String>,
+    /// The maximum number of records to parse.
+    limit: Option<usize>,
+    /// The release DOI to embed in the metadata, if known.
+    doi: Option<String>,
+    /// Previous release bundle whose slugs are kept.
+    previous: Option<&'a Path>,
+    /// Transforms applied to the records before bundling.
+    pipeline: Pipeline,
+    /// Where and how the release is written.
+    output: ReleaseOutput<'a>,
+    /// Cache of the last run, skipping unchanged inputs.
+    cache: Option<RunCache>,
+    /// Whether to rebuild even when the cache is fresh.
+    force: bool,
+}
+
+impl<'a> JsonParser<'a> {
+    /// Creates a new `JsonParser` from the given paths.
+    fn from_path(input_path: &'a Path, synonym_path: &'a Path, output_path: &'a Path) -> Self {
+        Self {
+            input_path,
+            synonym_path,
+            input_format: InputFormat::Csv,
+            dialect: CsvDialect::default(),
+            mdd_version: None,
+            release_date: None,
+            limit: None,
+            doi: None,
+            previous: None,
+            pipeline: Pipeline::new(),
+            output: ReleaseOutput::new(output_path),
+            cache: None,
+            force: false,
+        }
+    }
+
+    /// Updates the release data of the `JsonParser`.
+    fn update_release_data(&mut self, date: &str, version: &str) {
+        self.release_date = Some(date.to_string());
+        self.mdd_version = Some(version.to_string());
+    }
+
+    /// Updates the release data from a release TOML, including the DOI.
+    fn update_from_release_toml(&mut self, meta: &ReleaseToml) {
+        self.update_release_data(&meta.metadata.release_date, &meta.metadata.version);
+        self.doi = meta.metadata.doi.clone();
+    }
+
+    /// Creates a new `JsonParser` from the command-line arguments.
+    fn from_args(args: &'a JsonArgs) -> Self {
+        Self {
+            input_path: &args.input,
+            synonym_path: &args.synonym,
+            input_format: args.input_format,
+            dialect: args.csv.dialect(),
+            mdd_version: args.mdd_version.clone(),
+            release_date: args.release_date.clone(),
+            limit: args.limit,
+            doi: None,
+            previous: args.previous.as_deref(),
+            pipeline: if args.trim {
+                Pipeline::new().with_trimmed_whitespace()
+            } else {
+                Pipeline::new()
+            },
+            output: ReleaseOutput::from_args(args),
+            cache: args.cache_dir.as_deref().map(RunCache::new),
+            force: args.force,
+        }
+    }
+
+    /// Parses the MDD data from the CSV file and converts it to a JSON file.
+    fn parse_to_json(&self) -> Result<(), CliError> {
+        let cache = match &self.cache {
+            Some(_) if self.uses_stdio() => {
+                log::warn!("--cache-dir is ignored when reading stdin or writing stdout");
+                None
+            }
+            cache => cache.as_ref(),
+        };
+        let cached = match cache {
+            Some(cache) => Some((cache, self.cache_key()?)),
+            None => None,
+        };
+        if let Some((cache, key)) = &cached {
+            if !self.force && cache.is_fresh(JSON_CACHE_ENTRY, key) {
+                info!(
+                    "Inputs and options unchanged, outputs in {:?} are up to date (use --force to rebuild)",
+                    self.output.output_path
+                );
+                return Ok(());
+            }
+        }
+        let (all_data, country_stats) = self.build_release()?;
+        let outputs = self.output.write_release(&all_data, &country_stats)?;
+        if let Some((cache, key)) = cached {
+            cache
+                .store(JSON_CACHE_ENTRY, &key, &outputs)
+                .context("Failed to write cache")?;
+        }
+        Ok(())
+    }
+
+    /// Reads the input files and builds the release bundle and its country
+    /// statistics.
+    fn build_release(&self) -> Result<(ReleasedMddData, CountryMDDStats), CliError> {
+        let (mut mdd_data, mut synonym_data) = match self.input_format {
+            InputFormat::Csv => self.read_csv()?,
+            #[cfg(feature = "xlsx")]
+            InputFormat::Xlsx => self.read_workbook()?,
+            #[cfg(not(feature = "xlsx"))]
+            InputFormat::Xlsx => unreachable!("rejected before parsing"),
+        };
+        self.pipeline.apply(&mut mdd_data, &mut synonym_data);
+        info!("Found MDD data records: {}", mdd_data.len());
+        info!("Found synonym data records: {}", synonym_data.len());
+
+        if synonym_data.is_empty() {
+            info!("No synonym data found");
+        }
+
+        info!("Creating country mammal diversity statistics from MDD records");
+        let mut country_stats = CountryMDDStats::new();
+        country_stats.parse_country_data(&mdd_data);
+        info!(
+            "Total countries and regions: {}, Total domesticated species: {}, Total widespread species: {}, Total endemic species: {}",
+            country_stats.total_countries,
+            country_stats.domesticated.len(),
+            country_stats.widespread.len(),
+            country_stats.total_endemics
+        );
+
+        if let Some(limit) = self.limit {
+            self.limit_mdd_data(&mut mdd_data, limit);
+            self.limit_synonym_data(&mut synonym_data, limit);
+        }
+        let mdd_version = self.get_version();
+        let release_date = self.get_release_date()?;
+        info!(
+            "Using MDD version: {}, release date: {}",
+            mdd_version, release_date
+        );
+        let mut all_data =
+            ReleasedMddData::from_parser(mdd_data, synonym_data, &mdd_version, &release_date);
+        if let Some(doi) = &self.doi {
+            all_data.set_doi(doi);
+        }
+        if let Some(previous) = self.previous {
+            info!("Keeping slugs from previous release: {:?}", previous);
+            all_data.assign_slugs(Some(&read_release_bundle(previous)?));
+        }
+        info!("MDD v{} data parsed successfully", mdd_version);
+        info!("Total MDD records: {}", all_data.data.len());
+        info!(
+            "Total synonym only records: {}",
+            all_data.synonym_only.len()
+        );
+        Ok((all_data, country_stats))
+    }
+
+    /// Whether the input is read from stdin or the output written to stdout.
+    fn uses_stdio(&self) -> bool {
+        is_stdio(self.input_path)
+            || is_stdio(self.synonym_path)
+            || is_stdio(self.output.output_path)
+    }
+
+    /// Fingerprint of the input files and of every option the outputs
+    /// depend on.
+    fn cache_key(&self) -> Result<String, CliError> {
+        let mut inputs = vec![self.input_path];
+        if self.input_format == InputFormat::Csv {
+            inputs.push(self.synonym_path);
+        }
+        inputs.extend(self.previous);
+        let options = format!(
+            "{:?} {:?}",
+            (
+                self.output.output_path,
+                self.output.plain_text,
+                self.get_version(),
+                self.get_release_date()?,
+                self.limit,
+                self.output.prefix,
+                &self.doi,
+            ),
+            (
+                &self.dialect,
+                self.output.format,
+                self.output.split_by,
+                self.output.compression,
+                &self.pipeline,
+                &self.output.fields,
+                self.output.json_style,
+                self.output.manifest,
+            )
+        );
+        fingerprint(&inputs, &options).context("Failed to hash input files")
+    }
+
+    /// Reads the species and synonym CSV files.
+    fn read_csv(&self) -> Result<(Vec<MddData>, Vec<SynonymData>), CliError> {
+        if is_stdio(self.input_path) && is_stdio(self.synonym_path) {
+            return Err(CliError::bad_input(
+                "Only one of --input and --synonym can read from stdin",
+            ));
+        }
+        let mdd_file = open_input(self.input_path, "Parsing species")?;
+        let syn_file = open_input(self.synonym_path, "Parsing synonyms")?;
+
+        info!("Parsing MDD data from: {:?}", self.input_path);
+        let mdd_data = parse_species(mdd_file, &self.dialect)?;
+
+        info!("Parsing synonym data from: {:?}", self.synonym_path);
+        // Like `SynonymData::from_csv`, unparseable synonym rows become default records.
+        let synonym_data: Vec<SynonymData> =
+            SynonymCsvReader::with_dialect(BufReader::new(syn_file), &self.dialect)
+                .context("Failed to parse synonym CSV data")?
+                .map(|record| record.unwrap_or_default())
+                .collect();
+        Ok((mdd_data, synonym_data))
+    }
+
+    /// Reads the species and synonym sheets of the input workbook.
+    #[cfg(feature = "xlsx")]
+    fn read_workbook(&self) -> Result<(Vec<MddData>, Vec<SynonymData>), CliError> {
+        if is_stdio(self.input_path) {
+            return Err(CliError::bad_input(
+                "Workbooks cannot be read from stdin; use --input-format csv",
+            ));
+        }
+        info!("Reading workbook: {:?}", self.input_path);
+        let workbook = mdd_api::parser::xlsx::MddWorkbook::open(self.input_path)
+            .context("Failed to read workbook")?;
+        info!("Parsing MDD data from sheet: {}", workbook.species_sheet);
+        let mdd_data = workbook.species().context("Failed to parse MDD sheet")?;
+        match &workbook.synonym_sheet {
+            Some(sheet) => info!("Parsing synonym data from sheet: {}", sheet),
+            None => log::warn!("No synonym sheet found in {:?}", self.input_path),
+        }
+        let synonym_data = workbook
+            .synonyms()
+            .context("Failed to parse synonym sheet")?;
+        Ok((mdd_data, synonym_data))
+    }
+
+    /// Returns the version of the MDD data.
+    ///
+    /// We use the version if specified.
+    /// Otherwise, we will infer from the file name.
+    /// MDD species file_stem example: MDD_v2.2_6815species.
+    /// In this case, the version is 2.2.
+    fn get_version(&self) -> String {
+        match &self.mdd_version {
+            Some(version) => {
+                if let Err(e) = version.parse::<ReleaseVersion>() {
+                    log::warn!("{}; releases are numbered like 2.2 or 2024.1", e);
+                }
+                version.clone()
+            }
+            None => {
+                let file_stem = self.input_path.file_stem().and_then(|stem| stem.to_str());
+                file_stem
+                    .and_then(version_from_file_name)
+                    .unwrap_or_else(|| "unknown".to_string())
+            }
+        }
+    }
+
+    /// Returns the release date of the MDD data.
+    ///
+    /// We infer release date from the metadata if not specified.
+    fn get_release_date(&self) -> Result<String, CliError> {
+        let date = match &self.release_date {
+            Some(date) => date.clone(),
+            None if is_stdio(self.input_path) => {
+                chrono::Local::now().format("%B %e, %Y").to_string()
+            }
+            None => {
+                let created = fs::metadata(self.input_path)
+                    .and_then(|meta| meta.created())
+                    .with_context(|| {
+                        format!("Failed to read the creation time of {:?}", self.input_path)
+                    })?;
+                let date = DateTime::<chrono::Local>::from(created);
+                date.format("%B %e, %Y").to_string()
+            }
+        };
+        Ok(date)
+    }
+
+    /// Limits the number of MDD data records.
+    fn limit_mdd_data(&self, data: &mut Vec<MddData>, limit: usize) {
+        data.truncate(limit);
+    }
+
+    /// Limits the number of synonym data records.
+    fn limit_synonym_data(&self, data: &mut Vec<SynonymData>, limit: usize) {
+        data.truncate(limit);
+    }
+}
+
+/// Where and how a release bundle and its statistics are written.
+struct ReleaseOutput<'a> {
+    /// The path to the output directory.
+    output_path: &'a Path,
+    /// Whether to write the output as plain text.
+    plain_text: bool,
+    /// The prefix for the output file name.
+    prefix: Option<&'a str>,
+    /// The serialization format of the release bundle.
+    format: OutputFormat,
+    /// The rank the release bundle is split by, if sharded.
+    split_by: Option<ShardKey>,
+    /// Compression of the release bundle.
+    compression: Compression,
+    /// Species fields kept in the JSON output; all when empty.
+    fields: Vec<&'a str>,
+    /// The layout of the JSON bundle.
+    json_style: JsonStyle,
+    /// Whether to write `manifest.json` listing the written files.
+    manifest: bool,
+}
+
+impl<'a> ReleaseOutput<'a> {
+    /// Creates the default output settings for the output directory.
+    fn new(output_path: &'a Path) -> Self {
+        Self {
+            output_path,
+            plain_text: true,
+            prefix: Some(DEFAULT_PREFIX),
+            format: OutputFormat::Json,
+            split_by: None,
+            compression: Compression::default(),
+            fields: Vec::new(),
+            json_style: JsonStyle::new(),
+            manifest: true,
+        }
+    }
+
+    /// Creates the output settings from the command-line arguments.
+    fn from_args(args: &'a JsonArgs) -> Self {
+        Self {
+            output_path: &args.output,
+            plain_text: args.plain_text,
+            prefix: args.prefix.as_deref(),
+            format: args.format,
+            split_by: args.split_by.map(SplitBy::to_shard_key),
+            compression: args
+                .compression
+                .unwrap_or_else(|| CompressionKind::default_for(args.format))
+                .to_compression(args.level),
+            fields: args.fields.iter().map(String::as_str).collect(),
+            json_style: args.json.json_style(),
+            manifest: !args.no_manifest,
+        }
+    }
+
+    /// Writes the release bundle, country, IUCN, authority, continent, and realm
+    /// statistics, country region codes, and a `manifest.json` listing them,
+    /// returning the written files and directories.
+    fn write_release(
+        &self,
+        all_data: &ReleasedMddData,
+        country_stats: &CountryMDDStats,
+    ) -> Result<Vec<PathBuf>, CliError> {
+        if is_stdio(self.output_path) {
+            self.write_stdout(all_data)?;
+            return Ok(Vec::new());
+        }
+        create_output_dir(self.output_path)?;
+        if !self.fields.is_empty() && (self.split_by.is_some() || self.format != OutputFormat::Json)
+        {
+            log::warn!("--fields only applies to single-file JSON output; writing full records");
+        }
+        let mut outputs = match (self.split_by, self.format) {
+            (Some(split_by), _) => {
+                let index = ShardedWriter::new(self.output_path, split_by)
+                    .write(all_data)
+                    .context("Failed to write shards")?;
+                info!("Shards written, index: {:?}", index);
+                vec![index, self.output_path.join(SHARD_DIR)]
+            }
+            (None, OutputFormat::Json) if !self.fields.is_empty() => {
+                self.write_json(&self.project(all_data)?)?
+            }
+            (None, OutputFormat::Json) => self.write_json(all_data)?,
+            (None, OutputFormat::Ndjson) => {
+                let output = ndjson::write_release_to_file(
+                    all_data,
+                    self.output_path,
+                    self.get_file_name(),
+                    self.compression,
+                )
+                .context("Failed to write NDJSON output")?;
+                info!("Output written to: {:?}", output);
+                vec![output]
+            }
+            (None, OutputFormat::PerSpecies) => {
+                let writer = PerSpeciesWriter::new(self.output_path, SpeciesFileNaming::Id);
+                let index = writer
+                    .write(all_data)
+                    .context("Failed to write species files")?;
+                info!("Species files written, index: {:?}", index);
+                vec![index, self.output_path.join(SPECIES_DIR)]
+            }
+        };
+
+        // Write country statistics to JSON file
+        let country_stats_path = self.output_file(DEFAULT_COUNTRY_STATS_FNAME);
+        write_json_to_file(&country_stats_path, country_stats)?;
+        outputs.push(country_stats_path);
+        outputs.extend(self.write_stats(all_data)?);
+
+        let region_code_path = self.output_file(DEFAULT_COUNTRY_REGION_FNAME);
+        write_file(&region_code_path, CountryRegionCode::new().to_json())?;
+        outputs.push(region_code_path);
+
+        if self.manifest {
+            let provenance = ProvenanceHeader::from_release(all_data);
+            outputs.push(write_manifest(provenance, self.output_path, &outputs)?);
+        }
+        Ok(outputs)
+    }
+
+    /// Streams the release bundle (JSON or NDJSON) to stdout through the
+    /// selected compression. Statistics, region codes, and the manifest need
+    /// an output directory and are not written.
+    fn write_stdout(&self, all_data: &ReleasedMddData) -> Result<(), CliError> {
+        if self.split_by.is_some() || self.format == OutputFormat::PerSpecies {
+            return Err(CliError::bad_input(
+                "--split-by and --format per-species write several files; use an output directory",
+            ));
+        }
+        let projected = if self.fields.is_empty() {
+            None
+        } else {
+            Some(self.project(all_data)?)
+        };
+        let stdout = std::io::stdout();
+        if self.compression != Compression::None && stdout.is_terminal() {
+            log::warn!(
+                "Writing compressed output to a terminal; use --compression none for plain JSON"
+            );
+        }
+        info!("Writing the release bundle to stdout; statistics and manifest are skipped");
+        let sink = std::io::BufWriter::new(stdout.lock());
+        let written = self
+            .compression
+            .write_with(sink, |writer| match self.format {
+                OutputFormat::Ndjson => NdjsonWriter::new(writer)
+                    .write_release(all_data)
+                    .map(|_| ()),
+                _ if projected.is_some() => self
+                    .json_style
+                    .write(writer, &projected)
+                    .map_err(std::io::Error::from),
+                _ => self
+                    .json_style
+                    .write(writer, all_data)
+                    .map_err(std::io::Error::from),
+            })
+            .and_then(|mut sink| sink.flush());
+        match written {
+            // The reader stopped early (e.g. `| head`); nothing left to do.
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+            written => written.context("Failed to write to stdout"),
+        }
+    }
+
+    /// Keeps only the `--fields` of every species record.
+    fn project(&self, all_data: &ReleasedMddData) -> Result<serde_json::Value, CliError> {
+        all_data.project(&self.fields).map_err(|e| {
+            CliError::bad_input(format!(
+                "Failed to project fields: {} (available: {})",
+                e,
+                MddData::json_field_names().join(", ")
+            ))
+        })
+    }
+
+    /// Writes the statistics derived from the species of the release bundle,
+    /// returning the written paths.
+    fn write_stats(&self, all_data: &ReleasedMddData) -> Result<Vec<PathBuf>, CliError> {
+        let species: Vec<MddData> = all_data.species().cloned().collect();
+        let mut iucn_stats = IucnStats::new();
+        iucn_stats.parse_iucn_data(&species);
+        info!(
+            "IUCN assessed species: {}, threatened: {}",
+            iucn_stats.total_species, iucn_stats.total_threatened
+        );
+        let iucn_path = self.output_file(DEFAULT_IUCN_STATS_FNAME);
+        write_json_to_file(&iucn_path, &iucn_stats)?;
 
-    /// Writes the given data to a gzip file.
-    fn write_gzip(&self, data: &str) {
-        let output = self.get_output_path(true);
-        let file = std::fs::File::create(output).expect("Unable to create file");
-        let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
-        std::io::Write::write_all(&mut encoder, data.as_bytes()).expect("Unable to write file");
+        let mut authority_stats = AuthorityStats::new();
+        authority_stats.parse_authority_data(&species);
+        info!(
+            "Species described {}-{}, undated: {}",
+            authority_stats.first_year,
+            authority_stats.last_year,
+            authority_stats.undated.len()
+        );
+        let authority_path = self.output_file(DEFAULT_AUTHORITY_STATS_FNAME);
+        write_json_to_file(&authority_path, &authority_stats)?;
+
+        let mut continent_stats = RegionStats::new();
+        continent_stats.parse_continent_data(&species);
+        let mut realm_stats = RegionStats::new();
+        realm_stats.parse_realm_data(&species);
+        info!(
+            "Total continents: {}, Total realms: {}",
+            continent_stats.total_regions, realm_stats.total_regions
+        );
+        let continent_path = self.output_file(DEFAULT_CONTINENT_STATS_FNAME);
+        write_json_to_file(&continent_path, &continent_stats)?;
+        let realm_path = self.output_file(DEFAULT_REALM_STATS_FNAME);
+        write_json_to_file(&realm_path, &realm_stats)?;
+
+        let mut country_iso_stats = IsoCountryStats::new();
+        country_iso_stats.parse_country_data(&species);
+        info!(
+            "ISO countries: {}, unmatched country names: {}",
+            country_iso_stats.total_countries,
+            country_iso_stats.unmatched.len()
+        );
+        let country_iso_path = self.output_file(DEFAULT_COUNTRY_ISO_STATS_FNAME);
+        write_json_to_file(&country_iso_path, &country_iso_stats)?;
+
+        let summaries = TaxonSummaries::from_species(&species);
+        info!(
+            "Family summaries: {}, genus summaries: {}",
+            summaries.families.len(),
+            summaries.genera.len()
+        );
+        let summary_paths = summaries
+            .write(self.output_path)
+            .context("Failed to write taxon summaries")?;
+
+        let mut outputs = vec![
+            iucn_path,
+            authority_path,
+            continent_path,
+            realm_path,
+            country_iso_path,
+        ];
+        outputs.extend(summary_paths);
+        Ok(outputs)
+    }
+
+    /// Returns the path of the JSON output `file_name` in the output directory.
+    fn output_file(&self, file_name: &str) -> PathBuf {
+        self.output_path.join(file_name).with_extension(JSON_EXT)
+    }
+
+    /// Streams the release bundle as compressed JSON, plus plain JSON if
+    /// requested or uncompressed, returning the written paths.
+    fn write_json<T: serde::Serialize>(&self, value: &T) -> Result<Vec<PathBuf>, CliError> {
+        let mut outputs = Vec::new();
+        if self.compression != Compression::None {
+            outputs.push(self.write_json_file(value, self.compression)?);
+        }
+        if self.plain_text || self.compression == Compression::None {
+            let output = self.write_json_file(value, Compression::None)?;
+            info!("Output written to: {:?}", output);
+            outputs.push(output);
+        }
+        Ok(outputs)
+    }
+
+    /// Streams `value` in `self.json_style` to the output file of `compression`.
+    fn write_json_file<T: serde::Serialize>(
+        &self,
+        value: &T,
+        compression: Compression,
+    ) -> Result<PathBuf, CliError> {
+        let output = self.get_output_path(compression);
+        compression
+            .create_file_with(&output, |writer| {
+                self.json_style
+                    .write(writer, value)
+                    .map_err(std::io::Error::from)
+            })
+            .with_context(|| format!("Failed to write {:?}", output))?;
+        Ok(output)
     }
 
-    /// Returns the output path for the JSON file.
-    fn get_output_path(&self, is_gunzip: bool) -> PathBuf {
-        let fname = match self.prefix {
+    /// Returns the output file name without extension.
+    fn get_file_name(&self) -> &str {
+        match self.prefix {
             Some(prefix) => prefix,
             None => DEFAULT_OUTPUT_FNAME,
+        }
+    }
+
+    /// Returns the output path for the JSON file, e.g. `data.json.gz`.
+    fn get_output_path(&self, compression: Compression) -> PathBuf {
+        let output = self.output_path.join(self.get_file_name());
+        match compression.extension() {
+            Some(ext) => output.with_extension(format!("{}.{}", JSON_EXT, ext)),
+            None => output.with_extension(JSON_EXT),
+        }
+    }
+}
+
+/// Loads an exported JSON bundle and writes it into a SQLite or DuckDB database.
+#[cfg(feature = "db")]
+struct DbExporter<'a> {
+    input_path: &'a Path,
+    output_path: PathBuf,
+    format: DbFormat,
+}
+
+#[cfg(feature = "db")]
+impl<'a> DbExporter<'a> {
+    fn from_args(args: &'a args::DbArgs) -> Self {
+        Self {
+            input_path: &args.input,
+            output_path: args
+                .output
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(args.format.default_output())),
+            format: args.format,
+        }
+    }
+
+    fn export(&self) -> Result<(), CliError> {
+        let release = read_release_bundle(self.input_path)?;
+        let species: Vec<MddData> = release.species().cloned().collect();
+        let mut country_stats = CountryMDDStats::new();
+        country_stats.parse_country_data(&species);
+        match self.format {
+            DbFormat::Sqlite => {
+                let mut exporter = mdd_api::db::SqliteExporter::create(&self.output_path)
+                    .context("Failed to create SQLite database")?;
+                exporter
+                    .write_release(&release, &country_stats)
+                    .context("Failed to write SQLite database")?;
+            }
+            #[cfg(feature = "duckdb")]
+            DbFormat::Duckdb => {
+                let mut exporter = mdd_api::duckdb::DuckDbExporter::create(&self.output_path)
+                    .context("Failed to create DuckDB database")?;
+                exporter
+                    .write_release(&release, &country_stats)
+                    .context("Failed to write DuckDB database")?;
+            }
+            #[cfg(not(feature = "duckdb"))]
+            DbFormat::Duckdb => unreachable!("rejected before exporting"),
+        }
+        info!(
+            "Exported {} species and {} synonym-only names to {}",
+            release.data.len(),
+            release.synonym_only.len(),
+            self.output_path.display()
+        );
+        Ok(())
+    }
+}
+
+/// Compares two exported JSON bundles and writes the diff as JSON and Markdown.
+struct DiffRunner<'a> {
+    /// The older release bundle.
+    old_path: &'a Path,
+    /// The newer release bundle.
+    new_path: &'a Path,
+    /// The path to the output directory.
+    output_path: &'a Path,
+}
+
+impl<'a> DiffRunner<'a> {
+    /// Creates a new `DiffRunner` from the command-line arguments.
+    fn from_args(args: &'a DiffArgs) -> Self {
+        Self {
+            old_path: &args.old,
+            new_path: &args.new,
+            output_path: &args.output,
+        }
+    }
+
+    fn run(&self) -> Result<(), CliError> {
+        let old = read_release(self.old_path)?;
+        let new = read_release(self.new_path)?;
+        let diff = ReleaseDiff::between(&old, &new);
+        info!(
+            "MDD v{} → v{}: {} added, {} removed, {} renamed, {} species with field changes, {} likely splits or lumps",
+            diff.old_version,
+            diff.new_version,
+            diff.added.len(),
+            diff.removed.len(),
+            diff.renamed.len(),
+            diff.changed.len(),
+            diff.events.len()
+        );
+        create_output_dir(self.output_path)?;
+        let output = self.output_path.join(DEFAULT_DIFF_FNAME);
+        write_file(&output.with_extension(JSON_EXT), diff.to_json())?;
+        write_file(&output.with_extension(MARKDOWN_EXT), diff.to_markdown())?;
+        info!(
+            "Output written to: {:?}",
+            output.with_extension(MARKDOWN_EXT)
+        );
+        let distribution = DistributionDiff::between(&old, &new);
+        info!(
+            "{} range expansions, {} range contractions, {} countries with species gained or lost",
+            distribution.expansions().count(),
+            distribution.contractions().count(),
+            distribution.countries.len()
+        );
+        let output = self
+            .output_path
+            .join(DISTRIBUTION_DIFF_FILE)
+            .with_extension(JSON_EXT);
+        write_file(&output, distribution.to_json())?;
+        info!("Output written to: {:?}", output);
+        Ok(())
+    }
+}
+
+/// Writes release notes between two releases.
+struct ChangelogRunner<'a> {
+    /// The older release zip or bundle.
+    old_path: &'a Path,
+    /// The newer release zip or bundle.
+    new_path: &'a Path,
+    /// The changelog format.
+    format: ChangelogFormat,
+    /// The path to the output directory.
+    output_path: &'a Path,
+}
+
+impl<'a> ChangelogRunner<'a> {
+    /// Creates a new `ChangelogRunner` from the command-line arguments.
+    fn from_args(args: &'a ChangelogArgs) -> Self {
+        Self {
+            old_path: &args.old,
+            new_path: &args.new,
+            format: args.format,
+            output_path: &args.output,
+        }
+    }
+
+    fn run(&self) -> Result<(), CliError> {
+        let old = read_release(self.old_path)?;
+        let new = read_release(self.new_path)?;
+        let changelog = Changelog::between(&old, &new);
+        info!(
+            "MDD v{} → v{}: {} new species, {} splits, {} lumps, {} transfers, {} IUCN updates",
+            changelog.old_version,
+            changelog.new_version,
+            changelog.new_species.len(),
+            changelog.splits.len(),
+            changelog.lumps.len(),
+            changelog.transfers.len(),
+            changelog.iucn_updates.len()
+        );
+        create_output_dir(self.output_path)?;
+        let (content, extension) = match self.format {
+            ChangelogFormat::Md => (changelog.to_markdown(), MARKDOWN_EXT),
+            ChangelogFormat::Json => (changelog.to_json(), JSON_EXT),
         };
-        let output = self.output_path.join(fname);
-        if is_gunzip {
-            output.with_extension(GZIP_EXT)
+        let output = self
+            .output_path
+            .join(DEFAULT_CHANGELOG_FNAME)
+            .with_extension(extension);
+        write_file(&output, content)?;
+        info!("Output written to: {:?}", output);
+        Ok(())
+    }
+}
+
+/// Compares several releases, for the `trends` and `history` subcommands.
+struct ReleaseSeriesRunner<'a> {
+    /// The releases, oldest first.
+    input_paths: &'a [PathBuf],
+    /// The path to the output directory.
+    output_path: &'a Path,
+}
+
+impl<'a> ReleaseSeriesRunner<'a> {
+    /// Creates a new `ReleaseSeriesRunner` from the command-line arguments.
+    fn from_args(args: &'a ReleaseSeriesArgs) -> Self {
+        Self {
+            input_paths: &args.input,
+            output_path: &args.output,
+        }
+    }
+
+    /// Reads the releases, oldest first.
+    fn read_releases(&self) -> Result<Vec<ReleasedMddData>, CliError> {
+        self.input_paths
+            .iter()
+            .map(|path| read_release(path))
+            .collect()
+    }
+
+    /// Writes the country trends of the releases.
+    fn trends(&self) -> Result<(), CliError> {
+        let releases = self.read_releases()?;
+        let trends = CountryMDDStats::trends(&releases);
+        info!(
+            "Country trends of {} releases ({}) over {} countries",
+            trends.versions.len(),
+            trends.versions.join(", "),
+            trends.countries.len()
+        );
+        create_output_dir(self.output_path)?;
+        let output = self
+            .output_path
+            .join(DEFAULT_COUNTRY_TRENDS_FNAME)
+            .with_extension(JSON_EXT);
+        write_json_to_file(&output, &trends)?;
+        info!("Output written to: {:?}", output);
+        Ok(())
+    }
+
+    /// Writes the species life histories of the releases.
+    fn history(&self) -> Result<(), CliError> {
+        let releases = self.read_releases()?;
+        let history = SpeciesHistory::from_releases(&releases);
+        info!(
+            "Species history of {} releases ({}): {} species, {} renamed, {} no longer listed",
+            history.versions.len(),
+            history.versions.join(", "),
+            history.len(),
+            history.renamed().count(),
+            history.len() - history.current().count()
+        );
+        for output in history.write_files(self.output_path)? {
+            info!("Output written to: {:?}", output);
+        }
+        Ok(())
+    }
+}
+
+/// Parses every release of a directory into versioned output directories.
+struct BatchRunner<'a> {
+    /// The directory of release archives and TOMLs.
+    input_dir: &'a Path,
+    /// The path to the output directory.
+    output_dir: &'a Path,
+    /// Whether TOML releases are checked against the recorded digests.
+    verify: bool,
+    /// The layout of the JSON bundles.
+    json_style: JsonStyle,
+}
+
+impl<'a> BatchRunner<'a> {
+    /// Creates a new `BatchRunner` from the command-line arguments.
+    fn from_args(args: &'a BatchArgs) -> Self {
+        Self {
+            input_dir: &args.input_dir,
+            output_dir: &args.output_dir,
+            verify: !args.no_verify,
+            json_style: args.json.json_style(),
+        }
+    }
+
+    fn run(&self) -> Result<(), CliError> {
+        let sources = self.find_sources()?;
+        if sources.is_empty() {
+            return Err(CliError::bad_input(format!(
+                "No release archive or TOML file found in {:?}",
+                self.input_dir
+            )));
+        }
+        info!("Found {} releases in {:?}", sources.len(), self.input_dir);
+        let mut index = ReleaseIndex::new();
+        for source in &sources {
+            let (release, country_stats) = self
+                .read_source(source)
+                .with_context(|| format!("Failed to parse {:?}", source))?;
+            let version = release.get_version();
+            if version.is_empty() {
+                return Err(CliError::bad_input(format!(
+                    "Cannot determine the release version of {:?}",
+                    source
+                )));
+            }
+            let file_name = source
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if let Some(entry) = index.get(version) {
+                return Err(CliError::bad_input(format!(
+                    "Version {} found in both {} and {}",
+                    version, entry.source, file_name
+                )));
+            }
+            let dir_name = format!("v{}", version);
+            let output_path = self.output_dir.join(&dir_name);
+            info!("Writing MDD v{} to {:?}", version, output_path);
+            let mut output = ReleaseOutput::new(&output_path);
+            output.json_style = self.json_style;
+            output.write_release(&release, &country_stats)?;
+            index.add(ReleaseIndexEntry::from_release(
+                &release, &file_name, &dir_name,
+            ));
+        }
+        let index_path = index
+            .write(self.output_dir)
+            .context("Failed to write release index")?;
+        info!(
+            "{} releases written, latest v{}, index at {:?}",
+            index.releases.len(),
+            index.latest.as_deref().unwrap_or_default(),
+            index_path
+        );
+        Ok(())
+    }
+
+    /// Release zips, tarballs, and TOMLs directly in the input directory,
+    /// sorted by name.
+    fn find_sources(&self) -> Result<Vec<PathBuf>, CliError> {
+        let entries = fs::read_dir(self.input_dir)
+            .with_context(|| format!("Failed to read directory {:?}", self.input_dir))?;
+        let mut sources = Vec::new();
+        for entry in entries {
+            let path = entry
+                .with_context(|| format!("Failed to read directory {:?}", self.input_dir))?
+                .path();
+            let is_toml = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+            if is_toml {
+                sources.push(path);
+                continue;
+            }
+            match ArchiveKind::from_path(&path) {
+                #[cfg(feature = "archive")]
+                Some(ArchiveKind::Zip | ArchiveKind::TarGz) => sources.push(path),
+                #[cfg(not(feature = "archive"))]
+                Some(ArchiveKind::Zip | ArchiveKind::TarGz) => log::warn!(
+                    "Skipping {:?}: reading release archives requires the `archive` feature",
+                    path
+                ),
+                _ => {}
+            }
+        }
+        sources.sort();
+        Ok(sources)
+    }
+
+    /// Parses a release archive or TOML with its country statistics.
+    fn read_source(&self, path: &Path) -> Result<(ReleasedMddData, CountryMDDStats), CliError> {
+        let is_toml = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+        if !is_toml {
+            let release = read_release(path)?;
+            let species: Vec<MddData> = release.species().cloned().collect();
+            let mut country_stats = CountryMDDStats::new();
+            country_stats.parse_country_data(&species);
+            return Ok((release, country_stats));
+        }
+        let meta = ReleaseToml::from_file(path).context("Failed to read release TOML")?;
+        if self.verify {
+            verify_release_files(path, &meta)?;
+        }
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        let mdd_path = meta.metadata.mdd_path(base_dir);
+        let syn_path = meta.metadata.synonym_path(base_dir);
+        let mut json_parser = JsonParser::from_path(&mdd_path, &syn_path, self.output_dir);
+        json_parser.update_from_release_toml(&meta);
+        json_parser.build_release()
+    }
+}
+
+/// Serves a JSON bundle over HTTP.
+#[cfg(feature = "server")]
+struct Server<'a> {
+    /// The path to the JSON bundle.
+    input_path: &'a Path,
+    /// Whether to also serve the GraphQL API.
+    #[cfg(feature = "graphql")]
+    graphql: bool,
+    /// The address to listen on.
+    addr: std::net::SocketAddr,
+}
+
+#[cfg(feature = "server")]
+impl<'a> Server<'a> {
+    /// Creates a new `Server` from the command-line arguments.
+    fn from_args(args: &'a args::ServeArgs) -> Self {
+        Self {
+            input_path: &args.input,
+            #[cfg(feature = "graphql")]
+            graphql: args.graphql,
+            addr: std::net::SocketAddr::new(args.host, args.port),
+        }
+    }
+
+    fn run(&self) -> Result<(), CliError> {
+        use mdd_api::rest::{self, RestState};
+
+        let release = read_release_bundle(self.input_path)?;
+        #[cfg(feature = "graphql")]
+        let graphql_router = self.graphql.then(|| {
+            use mdd_api::graphql::{self, GRAPHQL_PATH};
+
+            info!(
+                "GraphQL API listening on http://{}{}",
+                self.addr, GRAPHQL_PATH
+            );
+            graphql::router(graphql::build_schema(release.clone()))
+        });
+        let router = rest::router(std::sync::Arc::new(RestState::new(release)));
+        #[cfg(feature = "graphql")]
+        let router = match graphql_router {
+            Some(graphql_router) => router.merge(graphql_router),
+            None => router,
+        };
+        info!("REST API listening on http://{}", self.addr);
+        let runtime =
+            tokio::runtime::Runtime::new().context("Failed to start the async runtime")?;
+        runtime
+            .block_on(rest::serve(router, self.addr))
+            .with_context(|| format!("Failed to serve on {}", self.addr))
+    }
+}
+
+/// Renders a JSON bundle as static species pages.
+#[cfg(feature = "render")]
+struct PageRunner<'a> {
+    /// The path to the JSON bundle.
+    input_path: &'a Path,
+    /// The path to the output directory.
+    output_path: &'a Path,
+    /// The page format.
+    format: args::PageFormatKind,
+    /// The path to a custom species template.
+    template_path: Option<&'a Path>,
+    /// The path to a custom index template.
+    index_template_path: Option<&'a Path>,
+    /// Whether to name pages by slug.
+    slug: bool,
+}
+
+#[cfg(feature = "render")]
+impl<'a> PageRunner<'a> {
+    /// Creates a new `PageRunner` from the command-line arguments.
+    fn from_args(args: &'a args::RenderArgs) -> Self {
+        Self {
+            input_path: &args.input,
+            output_path: &args.output,
+            format: args.format,
+            template_path: args.template.as_deref(),
+            index_template_path: args.index_template.as_deref(),
+            slug: args.slug,
+        }
+    }
+
+    /// Writes every species page and the index page.
+    fn run(&self) -> Result<(), CliError> {
+        use mdd_api::render::{PageFormat, PageRenderer};
+
+        let release = read_release_bundle(self.input_path)?;
+        let format = match self.format {
+            args::PageFormatKind::Md => PageFormat::Markdown,
+            args::PageFormatKind::Html => PageFormat::Html,
+        };
+        let naming = if self.slug {
+            SpeciesFileNaming::Slug
         } else {
-            output.with_extension(JSON_EXT)
+            SpeciesFileNaming::Id
+        };
+        let mut renderer = PageRenderer::new(format).with_naming(naming);
+        if let Some(path) = self.template_path {
+            let template = read_file(path)?;
+            renderer = renderer
+                .with_species_template(&template)
+                .with_context(|| format!("Invalid species template {:?}", path))?;
         }
+        if let Some(path) = self.index_template_path {
+            let template = read_file(path)?;
+            renderer = renderer
+                .with_index_template(&template)
+                .with_context(|| format!("Invalid index template {:?}", path))?;
+        }
+        let index_path = renderer
+            .write(&release, self.output_path)
+            .context("Failed to render species pages")?;
+        info!(
+            "{} species pages written, index at {:?}",
+            release.data.len(),
+            index_path
+        );
+        let outputs = [index_path, self.output_path.join(SPECIES_DIR)];
+        write_manifest(
+            ProvenanceHeader::from_release(&release),
+            self.output_path,
+            &outputs,
+        )?;
+        Ok(())
+    }
+}
+
+/// Species and synonym CSVs behind `--input` / `--synonym`.
+///
+/// A directory `--input` is searched for both CSVs (by file name, then by
+/// header row), and two files given the wrong way round are swapped after
+/// sniffing their header rows.
+fn resolve_csv_inputs(input: &Path, synonym: &Path) -> Result<(PathBuf, PathBuf), CliError> {
+    if input.is_dir() {
+        let files: Vec<PathBuf> = glob::glob(&format!("{}/**/*.csv", input.display()))
+            .map_err(|e| CliError::bad_input(format!("Failed to search {:?}: {}", input, e)))?
+            .filter_map(Result::ok)
+            .collect();
+        let (mdd_path, synonym_path) = EntryPatterns::default()
+            .find_release_files(&files)
+            .with_context(|| format!("Release CSVs not found in {:?}", input))?;
+        info!("Using {:?} and {:?}", mdd_path, synonym_path);
+        return Ok((mdd_path, synonym_path));
+    }
+    if !is_stdio(input)
+        && !is_stdio(synonym)
+        && sniff_csv_kind(input) == CsvKind::Synonym
+        && sniff_csv_kind(synonym) == CsvKind::Species
+    {
+        info!(
+            "{:?} is the synonym CSV and {:?} the species CSV, swapping them",
+            input, synonym
+        );
+        return Ok((synonym.to_path_buf(), input.to_path_buf()));
+    }
+    Ok((input.to_path_buf(), synonym.to_path_buf()))
+}
+
+/// Layout of the CSV at `path` from its header row; `Unknown` when it
+/// cannot be read, leaving the error to the parser.
+fn sniff_csv_kind(path: &Path) -> CsvKind {
+    fs::File::open(path)
+        .ok()
+        .and_then(read_header_line)
+        .map_or(CsvKind::Unknown, |header| detect_csv_kind(&header))
+}
+
+/// Whether `path` is `-`, standing for stdin or stdout.
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO_PATH
+}
+
+/// Opens the file at `path` with a progress bar labelled `message`.
+fn open_file(path: &Path, message: &str) -> Result<Box<dyn logger::ReadSeek>, CliError> {
+    let file = fs::File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    Ok(logger::progress_reader(file, message))
+}
+
+/// Opens the input file at `path` (with a progress bar labelled `message`),
+/// or stdin for `-`.
+fn open_input(path: &Path, message: &str) -> Result<Box<dyn Read>, CliError> {
+    if is_stdio(path) {
+        return Ok(Box::new(std::io::stdin().lock()));
+    }
+    Ok(Box::new(open_file(path, message)?))
+}
+
+/// Reads the text file at `path`.
+fn read_file(path: &Path) -> Result<String, CliError> {
+    fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))
+}
+
+/// Writes `contents` to `path`.
+fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), CliError> {
+    fs::write(path, contents).with_context(|| format!("Failed to write {:?}", path))
+}
+
+/// Writes `value` as compact JSON to `path`.
+fn write_json_to_file<T: serde::Serialize + ?Sized>(
+    path: &Path,
+    value: &T,
+) -> Result<(), CliError> {
+    let json = serde_json::to_string(value).context("Failed to serialize JSON")?;
+    write_file(path, json)
+}
+
+/// Writes `manifest.json` into `output_dir`, listing `outputs` (files, or
+/// directories whose files are all listed) with their hashes and `provenance`.
+fn write_manifest(
+    provenance: ProvenanceHeader,
+    output_dir: &Path,
+    outputs: &[PathBuf],
+) -> Result<PathBuf, CliError> {
+    let mut manifest = Manifest::new(provenance);
+    for output in outputs {
+        let added = if output.is_dir() {
+            manifest.add_dir(output_dir, output)
+        } else {
+            manifest.add_file(output_dir, output)
+        };
+        added.with_context(|| format!("Failed to read output file {:?}", output))?;
+    }
+    let path = manifest
+        .write(output_dir)
+        .context("Failed to write manifest")?;
+    debug!("Manifest written to: {:?}", path);
+    Ok(path)
+}
+
+/// Provenance of the tables derived from the species CSV at `path`, with the
+/// version inferred from its file name.
+fn csv_provenance(path: &Path) -> ProvenanceHeader {
+    let version = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(version_from_file_name)
+        .unwrap_or_else(|| "unknown".to_string());
+    ProvenanceHeader::new(&version, "")
+}
+
+/// Creates the output directory `path` and its parents.
+fn create_output_dir(path: &Path) -> Result<(), CliError> {
+    fs::create_dir_all(path)
+        .with_context(|| format!("Failed to create output directory {:?}", path))
+}
+
+/// Parses a species CSV in `dialect`, aborting on the first bad row.
+fn parse_species<R: Read>(reader: R, dialect: &CsvDialect) -> Result<Vec<MddData>, CliError> {
+    MddCsvReader::with_dialect(BufReader::new(reader), dialect)
+        .and_then(|records| records.collect())
+        .context("Failed to parse MDD CSV data")
+}
+
+/// Reads a release zip, tar.gz, or directory (`archive` feature), a SQLite
+/// export (`db` feature), or a JSON bundle.
+fn read_release(path: &Path) -> Result<ReleasedMddData, CliError> {
+    #[cfg(feature = "db")]
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("sqlite") || ext.eq_ignore_ascii_case("db"))
+    {
+        return mdd_api::db::open_release(path)
+            .with_context(|| format!("Failed to read SQLite export {:?}", path));
+    }
+    let Some(kind) = ArchiveKind::from_path(path) else {
+        return read_release_bundle(path);
+    };
+    #[cfg(feature = "archive")]
+    {
+        let release = match kind {
+            ArchiveKind::Zip => ReleasedMddData::from_zip_reader(BufReader::new(open_file(
+                path,
+                "Reading archive",
+            )?)),
+            ArchiveKind::TarGz => ReleasedMddData::from_tar_gz_reader(BufReader::new(open_file(
+                path,
+                "Reading archive",
+            )?)),
+            ArchiveKind::Directory => ReleasedMddData::from_dir(path),
+        };
+        release.with_context(|| format!("Failed to parse release {:?}", path))
+    }
+    #[cfg(not(feature = "archive"))]
+    Err(CliError::bad_input(format!(
+        "Reading {:?} releases requires the `archive` feature",
+        kind
+    )))
+}
+
+/// Reads a `ReleasedMddData` bundle from a `.json` file, optionally compressed
+/// (`.gz`, `.zst`, or `.br`).
+fn read_release_bundle(path: &Path) -> Result<ReleasedMddData, CliError> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let json = Compression::from_path(path)
+        .decompress(&bytes)
+        .with_context(|| format!("Failed to decompress {:?}", path))?;
+    ReleasedMddData::try_from_json(&String::from_utf8_lossy(&json))
+        .with_context(|| format!("Failed to parse JSON bundle {:?}", path))
+}
+
+#[cfg(all(test, feature = "archive"))]
+mod tests {
+    use mdd_api::writer::provenance::MANIFEST_FILE;
+
+    use super::*;
+
+    #[test]
+    fn test_read_release() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        builder
+            .append_path_with_name("tests/data/test_data.csv", "MDD/MDD_v2.2_112species.csv")
+            .unwrap();
+        builder
+            .append_path_with_name("tests/data/syndata.csv", "MDD/Species_Syn_v2.2.csv")
+            .unwrap();
+        let tarball = builder.into_inner().unwrap().finish().unwrap();
+        let dir = tempdir::TempDir::new("read_release").unwrap();
+        let path = dir.path().join("MDD_v2.2.tar.gz");
+        fs::write(&path, tarball).unwrap();
+
+        let release = read_release(&path).unwrap();
+        assert_eq!(release.data.len(), 112);
+        assert_eq!(release.get_version(), "2.2");
+        let release = read_release(Path::new("tests/data")).unwrap();
+        assert_eq!(release.data.len(), 112);
+    }
+
+    #[test]
+    fn test_table_manifest() {
+        let dir = tempdir::TempDir::new("table_manifest").unwrap();
+        let input = dir.path().join("MDD_v2.2_112species.csv");
+        fs::copy("tests/data/test_data.csv", &input).unwrap();
+        let output = dir.path().join("out");
+        let runner = VernacularRunner {
+            input_path: &input,
+            output_path: &output,
+            dialect: CsvDialect::new(),
+        };
+        runner.run().unwrap();
+
+        let manifest: Manifest =
+            serde_json::from_str(&fs::read_to_string(output.join(MANIFEST_FILE)).unwrap()).unwrap();
+        assert_eq!(manifest.provenance.version, "2.2");
+        let files: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(files, ["vernacular_names.csv", "vernacular_names.json"]);
     }
 }
@@ -0,0 +1,216 @@
+//! Authorship of a scientific name.
+//!
+//! MDD stores the authority as an author string, a year, and a parentheses
+//! flag (set when the species is no longer in its original genus). The author
+//! string follows the usual zoological conventions:
+//!
+//! ```text
+//! Hemprich & Ehrenberg                     two authors
+//! J. A. Chapman, Cramer, & T. J. Robinson  three or more, serial comma
+//! J. T. Pearson in Horsfield               described in a work by another author
+//! Gray ex Blyth                            name proposed by Gray, published by Blyth
+//! ```
+//!
+//! `Authorship::parse` splits these into author lists and `Display` renders
+//! them back, e.g. `(J. T. Pearson in Horsfield, 1840)`. The parsed author
+//! string is kept as well, so separators such as `A, B & C` (no serial
+//! comma) and suffixes such as `Smith, Jr.` are rendered exactly as written.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::parser::{mdd::MddData, synonyms::SynonymData};
+
+const EX_SEPARATOR: &str = " ex ";
+const IN_SEPARATOR: &str = " in ";
+/// Name suffixes written after a comma, e.g. `Smith, Jr.`.
+const NAME_SUFFIXES: [&str; 5] = ["Jr.", "Sr.", "II", "III", "IV"];
+
+/// Parsed authorship of a name.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Authorship {
+    /// Authors the name is attributed to.
+    pub authors: Vec<String>,
+    /// Authors of the work the name was published in (`A in B`).
+    pub in_authors: Vec<String>,
+    /// Authors who proposed the name before it was made available (`A ex B`).
+    pub ex_authors: Vec<String>,
+    pub year: Option<u16>,
+    /// Whether the authority is written in parentheses.
+    pub in_parentheses: bool,
+    /// The author string as parsed, with whitespace collapsed. Empty for
+    /// authorships built from author lists, which are then joined with `&`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub verbatim: String,
+}
+
+impl Authorship {
+    /// Parse an author string with its year and parentheses flag.
+    pub fn parse(author: &str, year: Option<u16>, in_parentheses: bool) -> Self {
+        let author = author.split_whitespace().collect::<Vec<_>>().join(" ");
+        let (ex_part, rest) = match author.split_once(EX_SEPARATOR) {
+            Some((ex_part, rest)) => (ex_part, rest),
+            None => ("", author.as_str()),
+        };
+        let (main_part, in_part) = rest.split_once(IN_SEPARATOR).unwrap_or((rest, ""));
+        Self {
+            authors: split_authors(main_part),
+            in_authors: split_authors(in_part),
+            ex_authors: split_authors(ex_part),
+            year,
+            in_parentheses,
+            verbatim: author.clone(),
+        }
+    }
+
+    /// Authorship of a species record; a zero year means no year.
+    pub fn from_species(species: &MddData) -> Self {
+        let year = Some(species.authority_species_year).filter(|year| *year > 0);
+        Self::parse(
+            &species.authority_species_author,
+            year,
+            species.authority_parentheses == 1,
+        )
+    }
+
+    /// Authorship of a synonym record, reading the year from its leading digits.
+    pub fn from_synonym(synonym: &SynonymData) -> Self {
+        let year = synonym
+            .year
+            .trim()
+            .get(..4)
+            .and_then(|year| year.parse().ok());
+        Self::parse(&synonym.author, year, synonym.authority_parentheses == 1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.authors.is_empty() && self.year.is_none()
+    }
+
+    /// The author string without year and parentheses, as stored in MDD.
+    pub fn author_string(&self) -> String {
+        if !self.verbatim.is_empty() {
+            return self.verbatim.clone();
+        }
+        let mut author = String::new();
+        if !self.ex_authors.is_empty() {
+            author.push_str(&join_authors(&self.ex_authors));
+            author.push_str(EX_SEPARATOR);
+        }
+        author.push_str(&join_authors(&self.authors));
+        if !self.in_authors.is_empty() {
+            author.push_str(IN_SEPARATOR);
+            author.push_str(&join_authors(&self.in_authors));
+        }
+        author
+    }
+}
+
+impl fmt::Display for Authorship {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut authority = self.author_string();
+        if let Some(year) = self.year {
+            if !authority.is_empty() {
+                authority.push_str(", ");
+            }
+            authority.push_str(&year.to_string());
+        }
+        if self.in_parentheses && !authority.is_empty() {
+            write!(f, "({})", authority)
+        } else {
+            write!(f, "{}", authority)
+        }
+    }
+}
+
+/// Split `A`, `A & B`, or `A, B, & C` into author names, keeping suffixes
+/// such as `Smith, Jr.` with their name.
+fn split_authors(authors: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let parts = authors
+        .split(", ")
+        .flat_map(|part| part.split(" & "))
+        .map(|name| name.trim().trim_start_matches("& ").trim())
+        .filter(|name| !name.is_empty());
+    for name in parts {
+        match names.last_mut() {
+            Some(last) if NAME_SUFFIXES.contains(&name) => {
+                last.push_str(", ");
+                last.push_str(name);
+            }
+            _ => names.push(name.to_string()),
+        }
+    }
+    names
+}
+
+/// Join author names with `&` before the last, using a serial comma for
+/// three or more.
+fn join_authors(authors: &[String]) -> String {
+    match authors {
+        [] => String::new(),
+        [author] => author.clone(),
+        [first, second] => format!("{} & {}", first, second),
+        [rest @ .., last] => format!("{}, & {}", rest.join(", "), last),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_authorship() {
+        let authorship = Authorship::parse("J. T. Pearson in Horsfield", Some(1840), true);
+        assert_eq!(authorship.authors, vec!["J. T. Pearson"]);
+        assert_eq!(authorship.in_authors, vec!["Horsfield"]);
+        assert_eq!(authorship.to_string(), "(J. T. Pearson in Horsfield, 1840)");
+
+        let authorship =
+            Authorship::parse("J. A. Chapman, Cramer, & T. J. Robinson", Some(1992), false);
+        assert_eq!(authorship.authors.len(), 3);
+        assert_eq!(authorship.authors[2], "T. J. Robinson");
+
+        let authorship = Authorship::parse("Gray ex Blyth & Hodgson", None, false);
+        assert_eq!(authorship.ex_authors, vec!["Gray"]);
+        assert_eq!(authorship.authors, vec!["Blyth", "Hodgson"]);
+        assert_eq!(authorship.to_string(), "Gray ex Blyth & Hodgson");
+        assert_eq!(Authorship::parse("", None, true).to_string(), "");
+
+        let authorship = Authorship {
+            authors: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            ..Authorship::default()
+        };
+        assert_eq!(authorship.author_string(), "A, B, & C");
+    }
+
+    #[test]
+    fn test_author_string_is_lossless() {
+        for author in [
+            "A & B",
+            "A, B & C",
+            "A, B, & C",
+            "Smith, Jr.",
+            "Gray ex Blyth",
+        ] {
+            let authorship = Authorship::parse(author, Some(1900), true);
+            assert_eq!(authorship.author_string(), author);
+            assert_eq!(authorship.to_string(), format!("({}, 1900)", author));
+        }
+        let authorship = Authorship::parse("A, B & C", None, false);
+        assert_eq!(authorship.authors, vec!["A", "B", "C"]);
+        let authorship = Authorship::parse("Smith, Jr. & Jones", None, false);
+        assert_eq!(authorship.authors, vec!["Smith, Jr.", "Jones"]);
+    }
+
+    #[test]
+    fn test_authorship_round_trip() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        for species in MddData::new().from_csv(&csv_data) {
+            let authorship = Authorship::from_species(&species);
+            assert_eq!(authorship.author_string(), species.authority_species_author);
+        }
+    }
+}
@@ -0,0 +1,194 @@
+//! Original name combinations and basionym links.
+//!
+//! `originalNameCombination` holds the name a species was first described
+//! under, e.g. `Rattus latidens`, `Ochotona (Pika) coreanus`, or
+//! `Lepus brasiliensis var. gabbi`. `Combination::parse` splits it into
+//! genus, subgenus, and epithets.
+//!
+//! The synonym table lists the same name as a usage with its root name,
+//! author, and year. `link_basionyms` finds that row for every species, so
+//! `MddData::basionym_synonym_id()` leads from a current species to the
+//! record of its original description:
+//! 1. the root name must equal the last epithet of the original combination
+//!    (or the specific epithet when no combination is recorded);
+//! 2. author (case and spacing ignored) and year must match the species
+//!    authority;
+//! 3. a usage attached to the species wins; otherwise a unique match among
+//!    all synonyms is taken.
+
+use std::{collections::HashMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    helper::normalize_name,
+    parser::{mdd::MddData, synonyms::SynonymData},
+};
+
+/// A genus-species(-subspecies) combination split into its parts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Combination {
+    pub genus: String,
+    /// Subgenus written in parentheses after the genus, without them.
+    pub subgenus: Option<String>,
+    pub specific_epithet: String,
+    /// Rank marker before the infraspecific epithet (`var.`, `subsp.`).
+    pub infraspecific_rank: Option<String>,
+    pub infraspecific_epithet: Option<String>,
+}
+
+impl Combination {
+    /// Parse a combination; `None` when it has no genus and epithet (empty or `NA`).
+    pub fn parse(name: &str) -> Option<Self> {
+        let mut words = name.split_whitespace().peekable();
+        let genus = words.next()?.to_string();
+        let subgenus = match words.peek() {
+            Some(word) if word.starts_with('(') && word.ends_with(')') => {
+                let subgenus = word.trim_matches(|c| c == '(' || c == ')').to_string();
+                words.next();
+                Some(subgenus)
+            }
+            _ => None,
+        };
+        let specific_epithet = words.next()?.to_string();
+        let infraspecific_rank = match words.peek() {
+            Some(word) if word.ends_with('.') => words.next().map(|rank| rank.to_string()),
+            _ => None,
+        };
+        let rest: Vec<&str> = words.collect();
+        let infraspecific_epithet = if rest.is_empty() {
+            None
+        } else {
+            Some(rest.join(" "))
+        };
+        Some(Self {
+            genus,
+            subgenus,
+            specific_epithet,
+            infraspecific_rank,
+            infraspecific_epithet,
+        })
+    }
+
+    /// The epithet the name was described under: the infraspecific epithet
+    /// when present, otherwise the specific epithet.
+    pub fn terminal_epithet(&self) -> &str {
+        self.infraspecific_epithet
+            .as_deref()
+            .unwrap_or(&self.specific_epithet)
+    }
+}
+
+impl fmt::Display for Combination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.genus)?;
+        if let Some(subgenus) = &self.subgenus {
+            write!(f, " ({})", subgenus)?;
+        }
+        write!(f, " {}", self.specific_epithet)?;
+        if let Some(rank) = &self.infraspecific_rank {
+            write!(f, " {}", rank)?;
+        }
+        if let Some(epithet) = &self.infraspecific_epithet {
+            write!(f, " {}", epithet)?;
+        }
+        Ok(())
+    }
+}
+
+/// Set the basionym synonym id of every species, see the module docs.
+pub fn link_basionyms(species: &mut [MddData], synonyms: &[SynonymData]) {
+    let mut index: HashMap<(String, String, u16), Vec<&SynonymData>> = HashMap::new();
+    for synonym in synonyms {
+        let year = match synonym.year.trim().get(..4).and_then(|y| y.parse().ok()) {
+            Some(year) => year,
+            None => continue,
+        };
+        let key = (
+            synonym.root_name.trim().to_lowercase(),
+            normalize_name(&synonym.author),
+            year,
+        );
+        index.entry(key).or_default().push(synonym);
+    }
+    for record in species {
+        let root = match record.original_combination() {
+            Some(combination) => combination.terminal_epithet().to_lowercase(),
+            None => record.specific_epithet.trim().to_lowercase(),
+        };
+        let key = (
+            root,
+            normalize_name(&record.authority_species_author),
+            record.authority_species_year,
+        );
+        record.basionym_synonym_id = index.get(&key).and_then(|candidates| {
+            candidates
+                .iter()
+                .find(|s| s.species_id == Some(record.id))
+                .or(match candidates.as_slice() {
+                    [only] => Some(only),
+                    _ => None,
+                })
+                .map(|s| s.syn_id)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_combination() {
+        let combination = Combination::parse("Ochotona (Pika) hyperborea mantchurica").unwrap();
+        assert_eq!(combination.genus, "Ochotona");
+        assert_eq!(combination.subgenus.as_deref(), Some("Pika"));
+        assert_eq!(combination.terminal_epithet(), "mantchurica");
+
+        let combination = Combination::parse("Lepus brasiliensis var. gabbi").unwrap();
+        assert_eq!(combination.infraspecific_rank.as_deref(), Some("var."));
+        assert_eq!(combination.to_string(), "Lepus brasiliensis var. gabbi");
+        assert!(Combination::parse("Lepus").is_none());
+
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        for species in MddData::new().from_csv(&csv_data) {
+            let combination = species.original_combination().unwrap();
+            assert_eq!(combination.to_string(), species.original_name_combination);
+        }
+    }
+
+    #[test]
+    fn test_link_basionyms() {
+        let syn_csv = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let synonyms = SynonymData::new().from_csv(&syn_csv);
+        let mut species = vec![
+            MddData {
+                id: 1003499,
+                specific_epithet: "latidens".to_string(),
+                authority_species_author: "Sanborn".to_string(),
+                authority_species_year: 1952,
+                original_name_combination: "Rattus latidens".to_string(),
+                ..MddData::new()
+            },
+            MddData {
+                id: 1003127,
+                specific_epithet: "sevia".to_string(),
+                authority_species_author: "Tate  &  Archbold".to_string(),
+                authority_species_year: 1935,
+                ..MddData::new()
+            },
+            MddData {
+                id: 1,
+                specific_epithet: "sevia".to_string(),
+                authority_species_author: "Tate".to_string(),
+                authority_species_year: 1900,
+                ..MddData::new()
+            },
+        ];
+        link_basionyms(&mut species, &synonyms);
+        assert_eq!(species[0].basionym_synonym_id(), Some(100022090));
+        assert_eq!(species[1].basionym_synonym_id(), Some(100022091));
+        assert_eq!(species[2].basionym_synonym_id(), None);
+    }
+}
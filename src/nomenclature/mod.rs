@@ -0,0 +1,21 @@
+//! Structured views over the nomenclatural text fields of MDD records.
+//!
+//! MDD keeps authorities and original combinations as verbatim text. The
+//! parsers in this module split them into parts without changing the source
+//! records, and render the parts back to the verbatim form.
+//!
+//! * `authorship` – `Authorship` (authors, `ex` and `in` authors, year,
+//!   parentheses) parsed from an authority string.
+//! * `combination` – `Combination` (genus, subgenus, epithets) parsed from an
+//!   original name combination, and `link_basionyms` tying species to the
+//!   synonym row of their original description.
+//! * `nominal` – `NominalName` entries (name, authority, status marker) split
+//!   from `nominalNames`, and `NominalReport` cross-referencing them with the
+//!   synonym table.
+//! * `references` – `SynonymGraph` of the `variant_of` and `senior_homonym`
+//!   links between synonyms, with broken references and traversal helpers.
+
+pub mod authorship;
+pub mod combination;
+pub mod nominal;
+pub mod references;
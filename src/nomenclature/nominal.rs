@@ -0,0 +1,254 @@
+//! Nominal names of a species.
+//!
+//! `nominalNames` lists every name based on a type of the species in one
+//! string, entries separated by `|`, each with its authority and an optional
+//! status marker in brackets:
+//!
+//! ```text
+//! alleni Mearns, 1890|borealis H. R. Schinz, 1845 [preoccupied]
+//! typicus Hilzheimer, 1908 [nomen novum | preoccupied]
+//! ```
+//!
+//! `NominalName::parse_list` splits the string into entries (a `|` inside
+//! brackets belongs to the marker) and `Display` renders an entry back.
+//!
+//! The synonym table should carry a row for every entry. `NominalReport`
+//! cross-references both sources per species, matching an entry to a synonym
+//! attached to the species by root name, author (case and spacing ignored),
+//! and year, and lists the names found in only one of them. Synonym rows with
+//! the `name_combination` status are later combinations of a nominal name,
+//! not names of their own, and are not expected in `nominalNames`.
+
+use std::{collections::HashMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use super::authorship::Authorship;
+use crate::{
+    helper::{normalize_name, MDD_MISSING_VALUE},
+    parser::{mdd::MddData, synonyms::SynonymData},
+};
+
+const ENTRY_SEPARATOR: char = '|';
+const NAME_COMBINATION_STATUS: &str = "name_combination";
+
+/// One entry of `nominalNames`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NominalName {
+    /// Root name as published, e.g. `alleni`.
+    pub name: String,
+    pub authorship: Authorship,
+    /// Text of the bracketed marker without the brackets, e.g. `preoccupied`.
+    pub status: Option<String>,
+}
+
+impl NominalName {
+    /// Parse one entry; `None` when it is empty or `NA`.
+    pub fn parse(entry: &str) -> Option<Self> {
+        let entry = entry.trim();
+        if entry.is_empty() || entry == MDD_MISSING_VALUE {
+            return None;
+        }
+        let (entry, status) = match entry.strip_suffix(']').and_then(|e| e.rsplit_once(" [")) {
+            Some((entry, status)) => (entry.trim_end(), Some(status.trim().to_string())),
+            None => (entry, None),
+        };
+        let (name, authority) = entry.split_once(' ').unwrap_or((entry, ""));
+        let authority = authority.trim();
+        let (authority, in_parentheses) = match authority
+            .strip_prefix('(')
+            .and_then(|a| a.strip_suffix(')'))
+        {
+            Some(authority) => (authority, true),
+            None => (authority, false),
+        };
+        let (author, year) = match authority.rsplit_once(", ") {
+            Some((author, year)) => match year.trim().parse() {
+                Ok(year) => (author, Some(year)),
+                Err(_) => (authority, None),
+            },
+            None => (authority, None),
+        };
+        Some(Self {
+            name: name.to_string(),
+            authorship: Authorship::parse(author, year, in_parentheses),
+            status,
+        })
+    }
+
+    /// Parse a whole `nominalNames` value.
+    pub fn parse_list(nominal_names: &str) -> Vec<Self> {
+        split_entries(nominal_names)
+            .into_iter()
+            .filter_map(Self::parse)
+            .collect()
+    }
+}
+
+impl fmt::Display for NominalName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.authorship.is_empty() {
+            write!(f, " {}", self.authorship)?;
+        }
+        if let Some(status) = &self.status {
+            write!(f, " [{}]", status)?;
+        }
+        Ok(())
+    }
+}
+
+/// A `nominalNames` entry and the synonym row it was matched to.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NominalMatch {
+    pub species_id: u32,
+    pub nominal_name: NominalName,
+    /// `None` when no synonym row of the species matches.
+    pub syn_id: Option<u32>,
+}
+
+/// Cross-reference of `nominalNames` against the synonym table.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NominalReport {
+    /// Every parsed entry, in species and entry order.
+    pub entries: Vec<NominalMatch>,
+    /// Ids of synonym rows attached to a species (other than name
+    /// combinations) that no entry of its `nominalNames` matched.
+    pub unlisted_synonyms: Vec<u32>,
+}
+
+impl NominalReport {
+    /// Cross-reference the nominal names of `species` with `synonyms`.
+    pub fn new(species: &[MddData], synonyms: &[SynonymData]) -> Self {
+        let mut by_species: HashMap<u32, Vec<&SynonymData>> = HashMap::new();
+        for synonym in synonyms {
+            if synonym.nomenclature_status().trim() == NAME_COMBINATION_STATUS {
+                continue;
+            }
+            if let Some(species_id) = synonym.species_id {
+                by_species.entry(species_id).or_default().push(synonym);
+            }
+        }
+        let mut report = Self::default();
+        for record in species {
+            let mut candidates = by_species.remove(&record.id).unwrap_or_default();
+            for nominal_name in record.nominal_name_entries() {
+                let position = candidates
+                    .iter()
+                    .position(|synonym| matches(&nominal_name, synonym));
+                report.entries.push(NominalMatch {
+                    species_id: record.id,
+                    syn_id: position.map(|position| candidates.remove(position).syn_id),
+                    nominal_name,
+                });
+            }
+            report
+                .unlisted_synonyms
+                .extend(candidates.iter().map(|synonym| synonym.syn_id));
+        }
+        report
+    }
+
+    /// Entries without a matching synonym row.
+    pub fn missing_synonyms(&self) -> impl Iterator<Item = &NominalMatch> {
+        self.entries.iter().filter(|entry| entry.syn_id.is_none())
+    }
+
+    /// Whether both sources list the same names.
+    pub fn is_consistent(&self) -> bool {
+        self.unlisted_synonyms.is_empty() && self.missing_synonyms().next().is_none()
+    }
+}
+
+fn matches(nominal_name: &NominalName, synonym: &SynonymData) -> bool {
+    let authorship = synonym.authorship();
+    nominal_name
+        .name
+        .eq_ignore_ascii_case(synonym.root_name().trim())
+        && nominal_name.authorship.year == authorship.year
+        && normalize_name(&nominal_name.authorship.author_string())
+            == normalize_name(&authorship.author_string())
+}
+
+/// Split on `|` outside brackets.
+fn split_entries(nominal_names: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in nominal_names.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            ENTRY_SEPARATOR if depth == 0 => {
+                entries.push(&nominal_names[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    entries.push(&nominal_names[start..]);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nominal_names() {
+        let names = NominalName::parse_list(
+            "monticularis (O. Thomas, 1903)|typicus Hilzheimer, 1908 [nomen novum | preoccupied]",
+        );
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[0].name, "monticularis");
+        assert_eq!(names[0].authorship.authors, vec!["O. Thomas"]);
+        assert!(names[0].authorship.in_parentheses);
+        assert_eq!(names[1].authorship.year, Some(1908));
+        assert_eq!(
+            names[1].status.as_deref(),
+            Some("nomen novum | preoccupied")
+        );
+        assert!(NominalName::parse_list("NA").is_empty());
+
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        for species in MddData::new().from_csv(&csv_data) {
+            let rendered: Vec<String> = species
+                .nominal_name_entries()
+                .iter()
+                .map(|name| name.to_string())
+                .collect();
+            assert_eq!(rendered.join("|"), species.nominal_names);
+        }
+    }
+
+    #[test]
+    fn test_nominal_report() {
+        let syn_csv = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let synonyms = SynonymData::new().from_csv(&syn_csv);
+        let species = vec![
+            MddData {
+                id: 1003499,
+                nominal_names: "latidens (Sanborn, 1952)".to_string(),
+                ..MddData::new()
+            },
+            MddData {
+                id: 1003127,
+                nominal_names: "sevia (Tate & Archbold, 1935)|papuanus Tate, 1936".to_string(),
+                ..MddData::new()
+            },
+        ];
+        let report = NominalReport::new(&species, &synonyms);
+        assert_eq!(report.entries[0].syn_id, Some(100022090));
+        assert_eq!(report.entries[1].syn_id, Some(100022091));
+        let missing: Vec<&str> = report
+            .missing_synonyms()
+            .map(|entry| entry.nominal_name.name.as_str())
+            .collect();
+        assert_eq!(missing, vec!["papuanus"]);
+        assert_eq!(report.unlisted_synonyms, vec![100022092]);
+        assert!(!report.is_consistent());
+    }
+}
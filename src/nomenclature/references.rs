@@ -0,0 +1,346 @@
+//! Links between synonym rows declared in `variant_of` and `senior_homonym`.
+//!
+//! Both columns name another synonym as free text: a bare synonym id, a
+//! name with its authorship and the id in brackets
+//! (`Rattus latidens Sanborn, 1952 [100022090]`), or only a name
+//! (`Mus minutus Pallas, 1771`). `SynonymGraph` resolves every reference
+//! to a synonym id, in that order of preference, and keeps the references
+//! that match no synonym (or the row itself) as `BrokenReference`s.
+//!
+//! Names are compared case-insensitively, with `_`, commas, and parentheses
+//! ignored, against the original combination (or root name) with and without
+//! its authorship. When several rows share a name, another row is preferred
+//! over the referencing one, then the oldest. `quality::homonyms` resolves
+//! `senior_homonym` with the same `ReferenceResolver`.
+//!
+//! ```rust, ignore
+//! let graph = SynonymGraph::new(&synonyms);
+//! for variant in graph.variants_of(100022090) { ... }
+//! let chain = graph.senior_homonym_chain(junior_id);
+//! ```
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    helper::{normalize_name, MDD_MISSING_VALUE},
+    parser::{synonyms::SynonymData, ReleasedMddData},
+};
+
+/// Column a reference was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReferenceKind {
+    /// `variant_of`: the row is a spelling variant of the referenced name.
+    VariantOf,
+    /// `senior_homonym`: the referenced name is the senior homonym of the row.
+    SeniorHomonym,
+}
+
+/// A resolved reference from one synonym to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NameReference {
+    pub kind: ReferenceKind,
+    pub from: u32,
+    pub to: u32,
+}
+
+/// A reference that matches no other synonym.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenReference {
+    pub kind: ReferenceKind,
+    pub syn_id: u32,
+    /// The column value verbatim.
+    pub reference: String,
+    /// Whether the reference resolved to the row itself.
+    pub self_reference: bool,
+}
+
+/// Resolves the free-text references of `variant_of` and `senior_homonym`.
+pub struct ReferenceResolver<'a> {
+    by_id: HashMap<u32, &'a SynonymData>,
+    by_name: HashMap<String, Vec<&'a SynonymData>>,
+}
+
+impl<'a> ReferenceResolver<'a> {
+    pub fn new<I>(synonyms: I) -> Self
+    where
+        I: IntoIterator<Item = &'a SynonymData>,
+    {
+        let mut by_id = HashMap::new();
+        let mut by_name: HashMap<String, Vec<&SynonymData>> = HashMap::new();
+        for synonym in synonyms {
+            by_id.insert(synonym.syn_id, synonym);
+            let name = display_name(synonym);
+            let keys = [
+                normalize(name),
+                normalize(&format!("{} {}", name, authorship(synonym))),
+            ];
+            for key in keys {
+                by_name.entry(key).or_default().push(synonym);
+            }
+        }
+        Self { by_id, by_name }
+    }
+
+    /// The synonym `reference` points at, as written on the row `from`;
+    /// `None` for blank and unmatched references.
+    pub fn resolve(&self, reference: &str, from: u32) -> Option<&'a SynonymData> {
+        let reference = reference.trim();
+        if is_blank(reference) {
+            return None;
+        }
+        if let Ok(id) = reference.parse::<u32>() {
+            return self.by_id.get(&id).copied();
+        }
+        let bracketed = reference
+            .strip_suffix(']')
+            .and_then(|r| r.rsplit_once('['))
+            .and_then(|(name, id)| Some((name, id.trim().parse::<u32>().ok()?)));
+        let name = match bracketed {
+            Some((_, id)) if self.by_id.contains_key(&id) => return self.by_id.get(&id).copied(),
+            Some((name, _)) => name,
+            None => reference,
+        };
+        self.by_name.get(&normalize(name)).and_then(|candidates| {
+            // Prefer another record over the referencing one, then the oldest.
+            candidates
+                .iter()
+                .min_by_key(|c| (c.syn_id == from, year(c).unwrap_or(u16::MAX)))
+                .copied()
+        })
+    }
+}
+
+/// Graph of resolved `variant_of` and `senior_homonym` references.
+#[derive(Debug, Clone, Default)]
+pub struct SynonymGraph {
+    variant_of: BTreeMap<u32, u32>,
+    senior_homonym: BTreeMap<u32, u32>,
+    variants: BTreeMap<u32, Vec<u32>>,
+    juniors: BTreeMap<u32, Vec<u32>>,
+    broken: Vec<BrokenReference>,
+}
+
+impl SynonymGraph {
+    /// Resolve the references of `synonyms` among themselves.
+    pub fn new<'a, I>(synonyms: I) -> Self
+    where
+        I: IntoIterator<Item = &'a SynonymData>,
+    {
+        let synonyms: Vec<&SynonymData> = synonyms.into_iter().collect();
+        let resolver = ReferenceResolver::new(synonyms.iter().copied());
+        let mut graph = Self::default();
+        for synonym in synonyms {
+            graph.insert(&resolver, synonym, ReferenceKind::VariantOf);
+            graph.insert(&resolver, synonym, ReferenceKind::SeniorHomonym);
+        }
+        graph
+    }
+
+    /// Graph of every synonym of a release, attached or not.
+    pub fn from_release(release: &ReleasedMddData) -> Self {
+        Self::new(release.all_synonyms())
+    }
+
+    fn insert(&mut self, resolver: &ReferenceResolver, synonym: &SynonymData, kind: ReferenceKind) {
+        let reference = match kind {
+            ReferenceKind::VariantOf => &synonym.variant_of,
+            ReferenceKind::SeniorHomonym => &synonym.senior_homonym,
+        };
+        if is_blank(reference) {
+            return;
+        }
+        let target = resolver
+            .resolve(reference, synonym.syn_id)
+            .map(|target| target.syn_id);
+        let to = match target {
+            Some(to) if to != synonym.syn_id => to,
+            _ => {
+                self.broken.push(BrokenReference {
+                    kind,
+                    syn_id: synonym.syn_id,
+                    reference: reference.trim().to_string(),
+                    self_reference: target.is_some(),
+                });
+                return;
+            }
+        };
+        let (forward, backward) = match kind {
+            ReferenceKind::VariantOf => (&mut self.variant_of, &mut self.variants),
+            ReferenceKind::SeniorHomonym => (&mut self.senior_homonym, &mut self.juniors),
+        };
+        forward.insert(synonym.syn_id, to);
+        backward.entry(to).or_default().push(synonym.syn_id);
+    }
+
+    /// The name `syn_id` is a variant of.
+    pub fn variant_of(&self, syn_id: u32) -> Option<u32> {
+        self.variant_of.get(&syn_id).copied()
+    }
+
+    /// The declared senior homonym of `syn_id`.
+    pub fn senior_homonym(&self, syn_id: u32) -> Option<u32> {
+        self.senior_homonym.get(&syn_id).copied()
+    }
+
+    /// Every variant of `syn_id`, including variants of variants, nearest
+    /// first.
+    pub fn variants_of(&self, syn_id: u32) -> Vec<u32> {
+        collect_reachable(&self.variants, syn_id)
+    }
+
+    /// Names declaring `syn_id` as their senior homonym, directly or
+    /// through another junior, nearest first.
+    pub fn junior_homonyms_of(&self, syn_id: u32) -> Vec<u32> {
+        collect_reachable(&self.juniors, syn_id)
+    }
+
+    /// Senior homonyms of `syn_id`, following each declared senior to its own
+    /// senior. Stops before revisiting a name, so cycles end the chain.
+    pub fn senior_homonym_chain(&self, syn_id: u32) -> Vec<u32> {
+        let mut chain = Vec::new();
+        let mut seen = HashSet::from([syn_id]);
+        let mut current = syn_id;
+        while let Some(senior) = self.senior_homonym(current) {
+            if !seen.insert(senior) {
+                break;
+            }
+            chain.push(senior);
+            current = senior;
+        }
+        chain
+    }
+
+    /// Every resolved reference, variants first, ordered by referencing id.
+    pub fn edges(&self) -> impl Iterator<Item = NameReference> + '_ {
+        let edges = |kind, map: &'_ BTreeMap<u32, u32>| {
+            map.iter()
+                .map(move |(&from, &to)| NameReference { kind, from, to })
+                .collect::<Vec<_>>()
+        };
+        edges(ReferenceKind::VariantOf, &self.variant_of)
+            .into_iter()
+            .chain(edges(ReferenceKind::SeniorHomonym, &self.senior_homonym))
+    }
+
+    /// References that match no other synonym, in input order.
+    pub fn broken(&self) -> &[BrokenReference] {
+        &self.broken
+    }
+}
+
+impl ReleasedMddData {
+    /// `variant_of` and `senior_homonym` links between the synonyms of this release.
+    pub fn synonym_graph(&self) -> SynonymGraph {
+        SynonymGraph::from_release(self)
+    }
+}
+
+/// Breadth-first walk over `edges` from `start`, without `start` itself.
+fn collect_reachable(edges: &BTreeMap<u32, Vec<u32>>, start: u32) -> Vec<u32> {
+    let mut seen = HashSet::from([start]);
+    let mut found = Vec::new();
+    let mut next = 0;
+    let mut current = start;
+    loop {
+        for &id in edges.get(&current).map(Vec::as_slice).unwrap_or_default() {
+            if seen.insert(id) {
+                found.push(id);
+            }
+        }
+        match found.get(next) {
+            Some(&id) => current = id,
+            None => return found,
+        }
+        next += 1;
+    }
+}
+
+pub(crate) fn is_blank(value: &str) -> bool {
+    let value = value.trim();
+    value.is_empty() || value == MDD_MISSING_VALUE
+}
+
+/// Original combination, falling back to the root name.
+pub(crate) fn display_name(synonym: &SynonymData) -> &str {
+    if is_blank(&synonym.original_combination) {
+        &synonym.root_name
+    } else {
+        &synonym.original_combination
+    }
+}
+
+/// `Author, year`, or whichever part is present.
+pub(crate) fn authorship(synonym: &SynonymData) -> String {
+    let parts: Vec<&str> = [synonym.author.trim(), synonym.year.trim()]
+        .into_iter()
+        .filter(|part| !is_blank(part))
+        .collect();
+    parts.join(", ")
+}
+
+/// Year of description, read from the leading four digits.
+pub(crate) fn year(synonym: &SynonymData) -> Option<u16> {
+    synonym.year.trim().get(..4)?.parse().ok()
+}
+
+/// `normalize_name` with commas and parentheses dropped, so cited names
+/// match with or without the punctuation around the authority.
+pub(crate) fn normalize(name: &str) -> String {
+    normalize_name(&name.replace([',', '(', ')'], " "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::synonyms::fixtures::synonym;
+
+    #[test]
+    fn test_synonym_graph() {
+        let data = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let synonyms = SynonymData::new().from_csv(&data);
+        let graph = SynonymGraph::new(&synonyms);
+        // `Rattus latidens Sanborn, 1952 [100022090]`
+        assert_eq!(graph.variant_of(100040650), Some(100022090));
+        assert_eq!(graph.variants_of(100022090), vec![100040650]);
+
+        let mut lapsus = synonym(4, "Mus minutos", "Pallas", "1771");
+        lapsus.variant_of = "Mus minutus Pallas, 1771".to_string();
+        let mut variant = synonym(5, "Mus minuta", "Pallas", "1771");
+        variant.variant_of = "4".to_string();
+        let mut junior = synonym(2, "Mus minutus", "Rafinesque", "1814");
+        junior.senior_homonym = "Mus minutus Pallas, 1771".to_string();
+        let mut youngest = synonym(3, "Mus minutus", "Gray", "1843");
+        youngest.senior_homonym = "Mus minutus Rafinesque, 1814 [2]".to_string();
+        let mut broken = synonym(6, "Felis catus", "Linnaeus", "1758");
+        broken.senior_homonym = "Felis domestica Erxleben, 1777".to_string();
+        let mut self_variant = synonym(7, "Sorex pusillus", "Gmelin", "1774");
+        self_variant.variant_of = "7".to_string();
+        let synonyms = vec![
+            synonym(1, "Mus minutus", "Pallas", "1771"),
+            junior,
+            youngest,
+            lapsus,
+            variant,
+            broken,
+            self_variant,
+        ];
+
+        let graph = SynonymGraph::new(&synonyms);
+        assert_eq!(graph.variants_of(1), vec![4, 5]);
+        assert_eq!(graph.senior_homonym_chain(3), vec![2, 1]);
+        assert_eq!(graph.junior_homonyms_of(1), vec![2, 3]);
+        assert!(graph.senior_homonym_chain(1).is_empty());
+        assert_eq!(graph.edges().count(), 4);
+        let broken: Vec<(u32, bool)> = graph
+            .broken()
+            .iter()
+            .map(|b| (b.syn_id, b.self_reference))
+            .collect();
+        assert_eq!(broken, vec![(6, false), (7, true)]);
+    }
+}
@@ -0,0 +1,578 @@
+//! Parse MDD release archives without extracting them.
+//!
+//! A release zip (or tar.gz) contains the species CSV (`MDD_v*.csv`), the synonym CSV
+//! (`Species_Syn_v*.csv`) and, in recent releases, a `release.toml`. Entries
+//! are located by file name anywhere in the archive and streamed straight
+//! from the `ZipArchive` into the CSV readers; tarballs are read sequentially,
+//! so their release entries are buffered first. `ArchiveKind` tells zips,
+//! tarballs, and release directories apart by path.
+//!
+//! `EntryPatterns` configures the file name globs of the two CSVs and falls
+//! back to classifying CSVs by their header row, so renamed or reorganized
+//! archives still parse.
+//!
+//! Reading archives requires the `archive` feature; the file name helpers are
+//! always available.
+
+#[cfg(feature = "archive")]
+use std::io::{Read, Seek};
+use std::{
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+
+use super::{
+    error::MddError,
+    schema::{detect_csv_kind, CsvKind},
+    version::ReleaseVersion,
+};
+#[cfg(feature = "archive")]
+use super::{
+    mdd::MddData,
+    metadata::{ReleaseMetadata, ReleaseToml, DEFAULT_RELEASE_NAME},
+    synonyms::SynonymData,
+    ReleasedMddData,
+};
+
+/// File name prefix of the species CSV in a release archive.
+pub const MDD_FILE_PREFIX: &str = "MDD_v";
+/// File name prefix of the synonym CSV in a release archive.
+pub const SYNONYM_FILE_PREFIX: &str = "Species_Syn_v";
+/// File name of the release metadata in a release archive.
+pub const RELEASE_TOML_FILE: &str = "release.toml";
+/// Folder holding the release files in a release archive; `mdd zip`
+/// extracts the CSVs from `<output>/MDD/`.
+pub const RELEASE_DIR: &str = "MDD";
+
+const CSV_EXT: &str = ".csv";
+
+/// Whether `file_name` (a base name) is read as the species CSV of a release archive.
+pub fn is_species_file_name(file_name: &str) -> bool {
+    file_name.starts_with(MDD_FILE_PREFIX) && file_name.ends_with(CSV_EXT)
+}
+
+/// Whether `file_name` (a base name) is read as the synonym CSV of a release archive.
+pub fn is_synonym_file_name(file_name: &str) -> bool {
+    file_name.starts_with(SYNONYM_FILE_PREFIX) && file_name.ends_with(CSV_EXT)
+}
+
+/// Infer the MDD version from a species file name, e.g. `MDD_v2.2_6815species.csv` → `2.2`.
+pub fn version_from_file_name(file_name: &str) -> Option<String> {
+    release_version_from_file_name(file_name).map(|version| version.to_string())
+}
+
+/// Parsed form of `version_from_file_name`; `MDD_v2.2.1_...` keeps its patch number.
+pub fn release_version_from_file_name(file_name: &str) -> Option<ReleaseVersion> {
+    let re = Regex::new(r"MDD_v(\d+\.\d+(?:\.\d+)?)").expect("Failed to compile MDD version regex");
+    re.captures(file_name)
+        .and_then(|caps| caps.get(1))
+        .and_then(|m| m.as_str().parse().ok())
+}
+
+/// Container of a release, as told by its path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    /// Zip archive (`*.zip`), the format of the official releases.
+    Zip,
+    /// Gzipped tarball (`*.tar.gz` or `*.tgz`).
+    TarGz,
+    /// Directory holding the release files, e.g. a git checkout.
+    Directory,
+}
+
+impl ArchiveKind {
+    /// Kind of the release at `path`: a directory when it is one, otherwise
+    /// by file extension. `None` for any other file.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        if path.is_dir() {
+            return Some(Self::Directory);
+        }
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else {
+            None
+        }
+    }
+}
+
+/// File name patterns locating the species and synonym CSVs of a release.
+///
+/// Patterns are globs matched against base names (`MDD_v*.csv` and
+/// `Species_Syn_v*.csv` by default). When a CSV is not found by name and
+/// header sniffing is on, the remaining CSVs are classified by their header
+/// row (`schema::detect_csv_kind`), so renamed files still parse.
+#[derive(Debug, Clone)]
+pub struct EntryPatterns {
+    species: glob::Pattern,
+    synonym: glob::Pattern,
+    sniff_headers: bool,
+}
+
+impl Default for EntryPatterns {
+    fn default() -> Self {
+        Self {
+            species: glob::Pattern::new(&format!("{}*{}", MDD_FILE_PREFIX, CSV_EXT))
+                .expect("Failed to compile species file pattern"),
+            synonym: glob::Pattern::new(&format!("{}*{}", SYNONYM_FILE_PREFIX, CSV_EXT))
+                .expect("Failed to compile synonym file pattern"),
+            sniff_headers: true,
+        }
+    }
+}
+
+impl EntryPatterns {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match the species CSV with the glob `pattern`, e.g. `mdd_*.csv`.
+    pub fn with_species_pattern(mut self, pattern: &str) -> Result<Self, MddError> {
+        self.species = compile_pattern(pattern)?;
+        Ok(self)
+    }
+
+    /// Match the synonym CSV with the glob `pattern`.
+    pub fn with_synonym_pattern(mut self, pattern: &str) -> Result<Self, MddError> {
+        self.synonym = compile_pattern(pattern)?;
+        Ok(self)
+    }
+
+    /// Whether CSVs not matched by name are classified by their header row.
+    pub fn with_header_sniffing(mut self, sniff_headers: bool) -> Self {
+        self.sniff_headers = sniff_headers;
+        self
+    }
+
+    /// Whether `file_name` (a base name) matches the species pattern.
+    pub fn is_species(&self, file_name: &str) -> bool {
+        self.species.matches(file_name)
+    }
+
+    /// Whether `file_name` (a base name) matches the synonym pattern.
+    pub fn is_synonym(&self, file_name: &str) -> bool {
+        self.synonym.matches(file_name)
+    }
+
+    /// The species and synonym CSVs among `files`, by name first and then,
+    /// for the ones still missing, by header row.
+    pub fn find_release_files(&self, files: &[PathBuf]) -> Result<(PathBuf, PathBuf), MddError> {
+        let names: Vec<String> = files
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        let (mdd_name, syn_name) = self.locate(&names, |name| {
+            let file = std::fs::File::open(name).ok()?;
+            read_header_line(file)
+        })?;
+        Ok((PathBuf::from(mdd_name), PathBuf::from(syn_name)))
+    }
+
+    /// Full names of the species and synonym CSVs among `names`;
+    /// `read_header` returns the first line of a CSV for sniffing.
+    fn locate<F>(&self, names: &[String], mut read_header: F) -> Result<(String, String), MddError>
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let mut mdd_name = find_entry(names, |name| self.is_species(name));
+        let mut syn_name = find_entry(names, |name| self.is_synonym(name));
+        if self.sniff_headers && (mdd_name.is_none() || syn_name.is_none()) {
+            let candidates = names.iter().filter(|name| {
+                let base_name = base_name(name);
+                !is_resource_fork(name)
+                    && base_name.to_lowercase().ends_with(CSV_EXT)
+                    && Some(*name) != mdd_name.as_ref()
+                    && Some(*name) != syn_name.as_ref()
+            });
+            for name in candidates.collect::<Vec<_>>() {
+                match read_header(name).as_deref().map(detect_csv_kind) {
+                    Some(CsvKind::Species) if mdd_name.is_none() => mdd_name = Some(name.clone()),
+                    Some(CsvKind::Synonym) if syn_name.is_none() => syn_name = Some(name.clone()),
+                    _ => (),
+                }
+            }
+        }
+        let mdd_name = mdd_name
+            .ok_or_else(|| MddError::MissingArchiveEntry(self.species.as_str().to_string()))?;
+        let syn_name = syn_name
+            .ok_or_else(|| MddError::MissingArchiveEntry(self.synonym.as_str().to_string()))?;
+        Ok((mdd_name, syn_name))
+    }
+}
+
+fn compile_pattern(pattern: &str) -> Result<glob::Pattern, MddError> {
+    glob::Pattern::new(pattern).map_err(|e| MddError::InvalidPattern(format!("{}: {}", pattern, e)))
+}
+
+/// First line of `reader`, for header sniffing.
+pub fn read_header_line<R: std::io::Read>(reader: R) -> Option<String> {
+    let mut line = String::new();
+    BufReader::new(reader).read_line(&mut line).ok()?;
+    Some(line)
+}
+
+#[cfg(feature = "archive")]
+impl ReleasedMddData {
+    /// Parse a release zip directly from `reader`, leaving the filesystem untouched.
+    ///
+    /// Version, release date, and DOI come from `release.toml` when the
+    /// archive has one; otherwise the version is inferred from the species
+    /// file name and the release date is left empty.
+    pub fn from_zip_reader<R: Read + Seek>(reader: R) -> Result<Self, MddError> {
+        Self::from_zip_reader_with(reader, &EntryPatterns::default())
+    }
+
+    /// `from_zip_reader` locating the CSVs with `patterns`.
+    pub fn from_zip_reader_with<R: Read + Seek>(
+        reader: R,
+        patterns: &EntryPatterns,
+    ) -> Result<Self, MddError> {
+        let mut archive = zip::ZipArchive::new(reader)?;
+        let (meta, mdd_name, syn_name) = archive_metadata(&mut archive, patterns)?;
+        let species = MddData::new().from_reader(archive.by_name(&mdd_name)?)?;
+        let synonyms = SynonymData::new().from_reader(archive.by_name(&syn_name)?)?;
+        Ok(Self::from_archive_parts(species, synonyms, &meta))
+    }
+
+    /// Parse a gzipped release tarball from `reader`, like `from_zip_reader`.
+    ///
+    /// Tar entries can only be read in order, so the CSV and TOML entries are
+    /// buffered in memory before parsing.
+    pub fn from_tar_gz_reader<R: Read>(reader: R) -> Result<Self, MddError> {
+        Self::from_tar_gz_reader_with(reader, &EntryPatterns::default())
+    }
+
+    /// `from_tar_gz_reader` locating the CSVs with `patterns`.
+    pub fn from_tar_gz_reader_with<R: Read>(
+        reader: R,
+        patterns: &EntryPatterns,
+    ) -> Result<Self, MddError> {
+        let entries = tar_gz_entries(reader)?;
+        let (meta, mdd_name, syn_name) = entries_metadata(&entries, patterns)?;
+        let species = MddData::new().from_reader(entry_bytes(&entries, &mdd_name))?;
+        let synonyms = SynonymData::new().from_reader(entry_bytes(&entries, &syn_name))?;
+        Ok(Self::from_archive_parts(species, synonyms, &meta))
+    }
+
+    /// Parse a release directory, e.g. an extracted archive or a git
+    /// checkout, like `from_zip_reader`. The CSVs and `release.toml` are
+    /// looked up at any depth.
+    pub fn from_dir(dir: &Path) -> Result<Self, MddError> {
+        Self::from_dir_with(dir, &EntryPatterns::default())
+    }
+
+    /// `from_dir` locating the CSVs with `patterns`.
+    pub fn from_dir_with(dir: &Path, patterns: &EntryPatterns) -> Result<Self, MddError> {
+        let mut files = Vec::new();
+        collect_files(dir, &mut files)?;
+        files.sort();
+        let names: Vec<String> = files
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        let (mdd_name, syn_name) = patterns.locate(&names, |name| {
+            read_header_line(std::fs::File::open(name).ok()?)
+        })?;
+        let toml = match find_entry(&names, |name| name == RELEASE_TOML_FILE) {
+            Some(toml_name) => Some(std::fs::read_to_string(toml_name)?),
+            None => None,
+        };
+        let meta = release_metadata(toml.as_deref(), &mdd_name, &syn_name)?;
+        let species =
+            MddData::new().from_reader(BufReader::new(std::fs::File::open(&mdd_name)?))?;
+        let synonyms =
+            SynonymData::new().from_reader(BufReader::new(std::fs::File::open(&syn_name)?))?;
+        Ok(Self::from_archive_parts(species, synonyms, &meta))
+    }
+
+    fn from_archive_parts(
+        species: Vec<MddData>,
+        synonyms: Vec<SynonymData>,
+        meta: &ReleaseMetadata,
+    ) -> Self {
+        let mut release = Self::from_parser(species, synonyms, &meta.version, &meta.release_date);
+        if let Some(doi) = meta.doi.as_deref() {
+            release.set_doi(doi);
+        }
+        release
+    }
+}
+
+/// Read the release metadata of a zip without parsing its CSVs.
+///
+/// Returns the archive's `release.toml` metadata when present; otherwise
+/// the version is inferred from the species file name (`unknown` when it
+/// cannot be) and the release date is empty. `mdd_file` and `synonym_file`
+/// hold the entry names of the CSVs either way.
+#[cfg(feature = "archive")]
+pub fn read_zip_metadata<R: Read + Seek>(reader: R) -> Result<ReleaseMetadata, MddError> {
+    let mut archive = zip::ZipArchive::new(reader)?;
+    Ok(archive_metadata(&mut archive, &EntryPatterns::default())?.0)
+}
+
+/// Tar.gz counterpart of `read_zip_metadata`.
+#[cfg(feature = "archive")]
+pub fn read_tar_gz_metadata<R: Read>(reader: R) -> Result<ReleaseMetadata, MddError> {
+    Ok(entries_metadata(&tar_gz_entries(reader)?, &EntryPatterns::default())?.0)
+}
+
+/// Release metadata plus the entry names of the species and synonym CSVs.
+#[cfg(feature = "archive")]
+fn archive_metadata<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    patterns: &EntryPatterns,
+) -> Result<(ReleaseMetadata, String, String), MddError> {
+    let names: Vec<String> = archive.file_names().map(String::from).collect();
+    let (mdd_name, syn_name) =
+        patterns.locate(&names, |name| read_header_line(archive.by_name(name).ok()?))?;
+    let toml = match find_entry(&names, |name| name == RELEASE_TOML_FILE) {
+        Some(toml_name) => {
+            let mut content = String::new();
+            archive.by_name(&toml_name)?.read_to_string(&mut content)?;
+            Some(content)
+        }
+        None => None,
+    };
+    let meta = release_metadata(toml.as_deref(), &mdd_name, &syn_name)?;
+    Ok((meta, mdd_name, syn_name))
+}
+
+/// `archive_metadata` over buffered tar entries.
+#[cfg(feature = "archive")]
+fn entries_metadata(
+    entries: &[(String, Vec<u8>)],
+    patterns: &EntryPatterns,
+) -> Result<(ReleaseMetadata, String, String), MddError> {
+    let names: Vec<String> = entries.iter().map(|(name, _)| name.clone()).collect();
+    let (mdd_name, syn_name) =
+        patterns.locate(&names, |name| read_header_line(entry_bytes(entries, name)))?;
+    let toml = match find_entry(&names, |name| name == RELEASE_TOML_FILE) {
+        Some(toml_name) => Some(
+            String::from_utf8(entry_bytes(entries, &toml_name).to_vec()).map_err(|e| {
+                MddError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+            })?,
+        ),
+        None => None,
+    };
+    let meta = release_metadata(toml.as_deref(), &mdd_name, &syn_name)?;
+    Ok((meta, mdd_name, syn_name))
+}
+
+/// Metadata from the `release.toml` content, or inferred from the species
+/// entry name without one.
+#[cfg(feature = "archive")]
+fn release_metadata(
+    toml: Option<&str>,
+    mdd_name: &str,
+    syn_name: &str,
+) -> Result<ReleaseMetadata, MddError> {
+    let mut meta = match toml {
+        Some(content) => ReleaseToml::from_toml(content)?.metadata,
+        None => ReleaseMetadata {
+            name: DEFAULT_RELEASE_NAME.to_string(),
+            version: version_from_file_name(mdd_name).unwrap_or_else(|| "unknown".to_string()),
+            ..Default::default()
+        },
+    };
+    meta.mdd_file = mdd_name.to_string();
+    meta.synonym_file = syn_name.to_string();
+    Ok(meta)
+}
+
+/// Full name of the first entry whose base name satisfies `predicate`.
+fn find_entry<F>(names: &[String], predicate: F) -> Option<String>
+where
+    F: Fn(&str) -> bool,
+{
+    names
+        .iter()
+        .find(|full_name| !is_resource_fork(full_name) && predicate(base_name(full_name)))
+        .cloned()
+}
+
+/// Last component of an entry name or path.
+fn base_name(full_name: &str) -> &str {
+    full_name.rsplit(['/', '\\']).next().unwrap_or(full_name)
+}
+
+/// macOS resource fork copies (`__MACOSX/._MDD_v...`).
+fn is_resource_fork(full_name: &str) -> bool {
+    full_name.starts_with("__MACOSX") || full_name.contains("/__MACOSX/")
+}
+
+/// Files under `dir`, recursively.
+#[cfg(feature = "archive")]
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// CSV and TOML entries of a tar.gz, in archive order.
+#[cfg(feature = "archive")]
+fn tar_gz_entries<R: Read>(reader: R) -> Result<Vec<(String, Vec<u8>)>, MddError> {
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(reader));
+    let mut entries = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().to_string();
+        let base_name = base_name(&name).to_lowercase();
+        if base_name.ends_with(CSV_EXT) || base_name == RELEASE_TOML_FILE {
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            entries.push((name, content));
+        }
+    }
+    Ok(entries)
+}
+
+/// Content of the buffered entry `name`, which must be one of `entries`.
+#[cfg(feature = "archive")]
+fn entry_bytes<'a>(entries: &'a [(String, Vec<u8>)], name: &str) -> &'a [u8] {
+    entries
+        .iter()
+        .find(|(entry_name, _)| entry_name == name)
+        .map(|(_, content)| content.as_slice())
+        .unwrap_or_default()
+}
+
+#[cfg(all(test, feature = "archive"))]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use zip::write::SimpleFileOptions;
+
+    use super::*;
+
+    fn build_zip(with_toml: bool) -> Cursor<Vec<u8>> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        zip.start_file("MDD/MDD_v2.2_112species.csv", options)
+            .unwrap();
+        zip.write_all(&std::fs::read("tests/data/test_data.csv").unwrap())
+            .unwrap();
+        zip.start_file("MDD/Species_Syn_v2.2.csv", options).unwrap();
+        zip.write_all(&std::fs::read("tests/data/syndata.csv").unwrap())
+            .unwrap();
+        if with_toml {
+            zip.start_file("MDD/release.toml", options).unwrap();
+            zip.write_all(&std::fs::read("tests/data/release.toml").unwrap())
+                .unwrap();
+        }
+        zip.finish().unwrap()
+    }
+
+    #[test]
+    fn test_from_zip_reader() {
+        let release = ReleasedMddData::from_zip_reader(build_zip(true)).unwrap();
+        assert_eq!(release.data.len(), 112);
+        assert_eq!(release.get_version(), "2.2.1");
+        assert_eq!(release.get_release_date(), "2024-06-01");
+        assert_eq!(
+            release.get_doi(),
+            Some("https://doi.org/10.5281/zenodo.17033774")
+        );
+
+        let release = ReleasedMddData::from_zip_reader(build_zip(false)).unwrap();
+        assert_eq!(release.get_version(), "2.2");
+        let meta = read_zip_metadata(build_zip(true)).unwrap();
+        assert_eq!(meta.version, "2.2.1");
+        assert_eq!(meta.mdd_file, "MDD/MDD_v2.2_112species.csv");
+        assert_eq!(
+            release_version_from_file_name("MDD_v2.2.1_6815species.csv"),
+            Some(ReleaseVersion::new(2, 2, Some(1)))
+        );
+        assert_eq!(release.get_doi(), None);
+    }
+
+    #[test]
+    fn test_from_tar_gz_reader() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        builder
+            .append_path_with_name("tests/data/test_data.csv", "MDD/MDD_v2.2_112species.csv")
+            .unwrap();
+        builder
+            .append_path_with_name("tests/data/syndata.csv", "MDD/Species_Syn_v2.2.csv")
+            .unwrap();
+        let tarball = builder.into_inner().unwrap().finish().unwrap();
+
+        let release = ReleasedMddData::from_tar_gz_reader(tarball.as_slice()).unwrap();
+        assert_eq!(release.data.len(), 112);
+        assert_eq!(release.get_version(), "2.2");
+        let meta = read_tar_gz_metadata(tarball.as_slice()).unwrap();
+        assert_eq!(meta.synonym_file, "MDD/Species_Syn_v2.2.csv");
+
+        assert_eq!(
+            ArchiveKind::from_path(Path::new("MDD_v2.2.tar.gz")),
+            Some(ArchiveKind::TarGz)
+        );
+        assert_eq!(
+            ArchiveKind::from_path(Path::new("tests/data")),
+            Some(ArchiveKind::Directory)
+        );
+        assert_eq!(ArchiveKind::from_path(Path::new("MDD.csv")), None);
+    }
+
+    #[test]
+    fn test_from_dir() {
+        let release = ReleasedMddData::from_dir(Path::new("tests/data")).unwrap();
+        assert_eq!(release.data.len(), 112);
+        // `tests/data/release.toml` names other files, but supplies the metadata.
+        assert_eq!(release.get_version(), "2.2.1");
+    }
+
+    #[test]
+    fn test_entry_patterns() {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        zip.start_file("release/species.csv", options).unwrap();
+        zip.write_all(&std::fs::read("tests/data/test_data.csv").unwrap())
+            .unwrap();
+        zip.start_file("release/mdd_synonyms.csv", options).unwrap();
+        zip.write_all(&std::fs::read("tests/data/syndata.csv").unwrap())
+            .unwrap();
+        let archive = zip.finish().unwrap();
+
+        // Both CSVs are found by their header row.
+        let release = ReleasedMddData::from_zip_reader(archive.clone()).unwrap();
+        assert_eq!(release.data.len(), 112);
+        assert_eq!(release.get_version(), "unknown");
+
+        let patterns = EntryPatterns::new()
+            .with_synonym_pattern("*_synonyms.csv")
+            .unwrap()
+            .with_header_sniffing(false);
+        assert!(patterns.is_synonym("mdd_synonyms.csv"));
+        let result = ReleasedMddData::from_zip_reader_with(archive, &patterns);
+        assert!(matches!(result, Err(MddError::MissingArchiveEntry(p)) if p == "MDD_v*.csv"));
+        assert!(matches!(
+            EntryPatterns::new().with_species_pattern("[MDD"),
+            Err(MddError::InvalidPattern(_))
+        ));
+    }
+
+    #[test]
+    fn test_missing_entry() {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("README.md", SimpleFileOptions::default())
+            .unwrap();
+        let result = ReleasedMddData::from_zip_reader(zip.finish().unwrap());
+        assert!(matches!(result, Err(MddError::MissingArchiveEntry(_))));
+    }
+}
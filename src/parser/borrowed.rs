@@ -0,0 +1,294 @@
+//! Zero-copy species records.
+//!
+//! `MddDataRef` has the columns of `MddData` as `&str` slices borrowed from
+//! the CSV reader's record buffer. `MddDataRef::from_csv_borrowed` reads a
+//! species CSV through one reused buffer and hands each row to a callback,
+//! so a pass over the file allocates nothing per field. Code that only
+//! aggregates (counts, statistics, checks) can stream releases of any size;
+//! call `to_mdd` for the rows worth keeping.
+//!
+//! The rows cannot outlive the callback because the buffer is overwritten by
+//! the next row. `MddData::as_borrowed` gives the same view of an owned
+//! record, so aggregation code can be written once against `MddDataRef`.
+//!
+//! ```rust, ignore
+//! use mdd_api::parser::borrowed::MddDataRef;
+//!
+//! let mut extinct = 0;
+//! MddDataRef::from_csv_borrowed(File::open("MDD.csv")?, |species| {
+//!     extinct += u32::from(species.extinct == 1);
+//! })?;
+//! ```
+
+use std::io::Read;
+
+use serde::Deserialize;
+
+use super::{
+    error::MddError,
+    mdd::{country_tokens, CountryToken, MddData},
+    options::CsvDialect,
+    reader::SpeciesHeaders,
+};
+
+/// A species row borrowing its text from the CSV record buffer.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MddDataRef<'a> {
+    pub id: u32,
+    pub sci_name: &'a str,
+    pub main_common_name: &'a str,
+    pub other_common_names: &'a str,
+    pub phylosort: u16,
+    pub subclass: &'a str,
+    pub infraclass: &'a str,
+    pub magnorder: &'a str,
+    pub superorder: &'a str,
+    #[serde(alias = "order")]
+    pub taxon_order: &'a str,
+    pub suborder: &'a str,
+    pub infraorder: &'a str,
+    pub parvorder: &'a str,
+    pub superfamily: &'a str,
+    pub family: &'a str,
+    pub subfamily: &'a str,
+    pub tribe: &'a str,
+    pub genus: &'a str,
+    pub subgenus: &'a str,
+    pub specific_epithet: &'a str,
+    pub authority_species_author: &'a str,
+    pub authority_species_year: u16,
+    pub authority_parentheses: u8,
+    pub original_name_combination: &'a str,
+    pub authority_species_citation: &'a str,
+    pub authority_species_link: &'a str,
+    pub type_voucher: &'a str,
+    pub type_kind: &'a str,
+    #[serde(rename = "typeVoucherURIs")]
+    pub type_voucher_uri: &'a str,
+    pub type_locality: &'a str,
+    pub type_locality_latitude: &'a str,
+    pub type_locality_longitude: &'a str,
+    pub nominal_names: &'a str,
+    pub taxonomy_notes: &'a str,
+    pub taxonomy_notes_citation: &'a str,
+    pub distribution_notes: &'a str,
+    pub distribution_notes_citation: &'a str,
+    pub subregion_distribution: &'a str,
+    pub country_distribution: &'a str,
+    pub continent_distribution: &'a str,
+    pub biogeographic_realm: &'a str,
+    pub iucn_status: &'a str,
+    pub extinct: u8,
+    pub domestic: u8,
+    pub flagged: u8,
+    #[serde(rename = "CMW_sciName")]
+    pub cmw_sci_name: &'a str,
+    #[serde(rename = "diffSinceCMW")]
+    pub diff_since_cmw: u8,
+    #[serde(rename = "MSW3_matchtype")]
+    pub msw3_match_type: &'a str,
+    #[serde(rename = "MSW3_sciName")]
+    pub msw3_sci_name: &'a str,
+    #[serde(rename = "diffSinceMSW3")]
+    pub diff_since_msw3: &'a str,
+}
+
+impl<'a> MddDataRef<'a> {
+    /// Call `f` with every row of a species CSV, returning the number of rows.
+    /// Stops at the first malformed row.
+    pub fn from_csv_borrowed<R, F>(reader: R, f: F) -> Result<u64, MddError>
+    where
+        R: Read,
+        F: FnMut(MddDataRef<'_>),
+    {
+        Self::from_csv_borrowed_with(reader, &CsvDialect::default(), f)
+    }
+
+    /// `from_csv_borrowed` for CSV in `dialect`.
+    pub fn from_csv_borrowed_with<R, F>(
+        reader: R,
+        dialect: &CsvDialect,
+        mut f: F,
+    ) -> Result<u64, MddError>
+    where
+        R: Read,
+        F: FnMut(MddDataRef<'_>),
+    {
+        let mut rdr = dialect.reader_builder().from_reader(reader);
+        let raw_headers = rdr.headers().map_err(|e| MddError::from_csv(e, None))?;
+        let SpeciesHeaders {
+            headers, padding, ..
+        } = SpeciesHeaders::new(raw_headers, dialect, None)?;
+        let mut record = csv::StringRecord::new();
+        let mut rows = 0;
+        while rdr
+            .read_record(&mut record)
+            .map_err(|e| MddError::from_csv(e, Some(&headers)))?
+        {
+            padding.iter().for_each(|value| record.push_field(value));
+            let species: MddDataRef<'_> = record
+                .deserialize(Some(&headers))
+                .map_err(|e| MddError::from_csv(e, Some(&headers)))?;
+            f(species);
+            rows += 1;
+        }
+        Ok(rows)
+    }
+
+    /// Countries listed in `country_distribution` (see `MddData::countries`).
+    pub fn countries(&self) -> Vec<CountryToken<'a>> {
+        country_tokens(self.country_distribution)
+    }
+
+    /// Owned copy of the row; `slug` is left empty.
+    pub fn to_mdd(&self) -> MddData {
+        MddData {
+            id: self.id,
+            sci_name: self.sci_name.to_string(),
+            main_common_name: self.main_common_name.to_string(),
+            other_common_names: self.other_common_names.to_string(),
+            phylosort: self.phylosort,
+            subclass: self.subclass.to_string(),
+            infraclass: self.infraclass.to_string(),
+            magnorder: self.magnorder.to_string(),
+            superorder: self.superorder.to_string(),
+            taxon_order: self.taxon_order.to_string(),
+            suborder: self.suborder.to_string(),
+            infraorder: self.infraorder.to_string(),
+            parvorder: self.parvorder.to_string(),
+            superfamily: self.superfamily.to_string(),
+            family: self.family.to_string(),
+            subfamily: self.subfamily.to_string(),
+            tribe: self.tribe.to_string(),
+            genus: self.genus.to_string(),
+            subgenus: self.subgenus.to_string(),
+            specific_epithet: self.specific_epithet.to_string(),
+            authority_species_author: self.authority_species_author.to_string(),
+            authority_species_year: self.authority_species_year,
+            authority_parentheses: self.authority_parentheses,
+            original_name_combination: self.original_name_combination.to_string(),
+            authority_species_citation: self.authority_species_citation.to_string(),
+            authority_species_link: self.authority_species_link.to_string(),
+            type_voucher: self.type_voucher.to_string(),
+            type_kind: self.type_kind.to_string(),
+            type_voucher_uri: self.type_voucher_uri.to_string(),
+            type_locality: self.type_locality.to_string(),
+            type_locality_latitude: self.type_locality_latitude.to_string(),
+            type_locality_longitude: self.type_locality_longitude.to_string(),
+            nominal_names: self.nominal_names.to_string(),
+            taxonomy_notes: self.taxonomy_notes.to_string(),
+            taxonomy_notes_citation: self.taxonomy_notes_citation.to_string(),
+            distribution_notes: self.distribution_notes.to_string(),
+            distribution_notes_citation: self.distribution_notes_citation.to_string(),
+            subregion_distribution: self.subregion_distribution.to_string(),
+            country_distribution: self.country_distribution.to_string(),
+            continent_distribution: self.continent_distribution.to_string(),
+            biogeographic_realm: self.biogeographic_realm.to_string(),
+            iucn_status: self.iucn_status.to_string(),
+            extinct: self.extinct,
+            domestic: self.domestic,
+            flagged: self.flagged,
+            cmw_sci_name: self.cmw_sci_name.to_string(),
+            diff_since_cmw: self.diff_since_cmw,
+            msw3_match_type: self.msw3_match_type.to_string(),
+            msw3_sci_name: self.msw3_sci_name.to_string(),
+            diff_since_msw3: self.diff_since_msw3.to_string(),
+            ..MddData::new()
+        }
+    }
+}
+
+impl MddData {
+    /// The record as an `MddDataRef` borrowing its fields.
+    pub fn as_borrowed(&self) -> MddDataRef<'_> {
+        MddDataRef {
+            id: self.id,
+            sci_name: &self.sci_name,
+            main_common_name: &self.main_common_name,
+            other_common_names: &self.other_common_names,
+            phylosort: self.phylosort,
+            subclass: &self.subclass,
+            infraclass: &self.infraclass,
+            magnorder: &self.magnorder,
+            superorder: &self.superorder,
+            taxon_order: &self.taxon_order,
+            suborder: &self.suborder,
+            infraorder: &self.infraorder,
+            parvorder: &self.parvorder,
+            superfamily: &self.superfamily,
+            family: &self.family,
+            subfamily: &self.subfamily,
+            tribe: &self.tribe,
+            genus: &self.genus,
+            subgenus: &self.subgenus,
+            specific_epithet: &self.specific_epithet,
+            authority_species_author: &self.authority_species_author,
+            authority_species_year: self.authority_species_year,
+            authority_parentheses: self.authority_parentheses,
+            original_name_combination: &self.original_name_combination,
+            authority_species_citation: &self.authority_species_citation,
+            authority_species_link: &self.authority_species_link,
+            type_voucher: &self.type_voucher,
+            type_kind: &self.type_kind,
+            type_voucher_uri: &self.type_voucher_uri,
+            type_locality: &self.type_locality,
+            type_locality_latitude: &self.type_locality_latitude,
+            type_locality_longitude: &self.type_locality_longitude,
+            nominal_names: &self.nominal_names,
+            taxonomy_notes: &self.taxonomy_notes,
+            taxonomy_notes_citation: &self.taxonomy_notes_citation,
+            distribution_notes: &self.distribution_notes,
+            distribution_notes_citation: &self.distribution_notes_citation,
+            subregion_distribution: &self.subregion_distribution,
+            country_distribution: &self.country_distribution,
+            continent_distribution: &self.continent_distribution,
+            biogeographic_realm: &self.biogeographic_realm,
+            iucn_status: &self.iucn_status,
+            extinct: self.extinct,
+            domestic: self.domestic,
+            flagged: self.flagged,
+            cmw_sci_name: &self.cmw_sci_name,
+            diff_since_cmw: self.diff_since_cmw,
+            msw3_match_type: &self.msw3_match_type,
+            msw3_sci_name: &self.msw3_sci_name,
+            diff_since_msw3: &self.diff_since_msw3,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_csv_borrowed() {
+        let file = std::fs::File::open("tests/data/test_data.csv").unwrap();
+        let mut flagged = 0;
+        let mut first = None;
+        let rows = MddDataRef::from_csv_borrowed(std::io::BufReader::new(file), |species| {
+            flagged += u32::from(species.flagged == 1);
+            first.get_or_insert_with(|| species.to_mdd());
+        })
+        .unwrap();
+        assert_eq!(rows, 112);
+        assert_eq!(flagged, 12);
+
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let owned = MddData::new().from_csv(&csv_data);
+        assert_eq!(first.unwrap().to_json(), owned[0].to_json());
+        assert_eq!(owned[0].as_borrowed().countries(), owned[0].countries());
+    }
+
+    #[test]
+    fn test_from_csv_borrowed_v1() {
+        let file = std::fs::File::open("tests/data/v1_test_data.csv").unwrap();
+        let mut names = Vec::new();
+        let rows = MddDataRef::from_csv_borrowed(file, |species| {
+            names.push(species.sci_name.to_string());
+        })
+        .unwrap();
+        assert_eq!(rows, 3);
+        assert_eq!(names[0], "Bunolagus_monticularis");
+    }
+}
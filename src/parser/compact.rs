@@ -0,0 +1,276 @@
+//! Compact, interned species records (enabled with the `compact` feature).
+//!
+//! Rank names, authors, IUCN categories, and distribution strings repeat
+//! across thousands of records: a release has a few dozen orders and about
+//! 150 families for some 6,800 species. `MddDataCompact` stores those columns
+//! as `Arc<str>` handed out by an `Interner`, so every record of a family
+//! points at one allocation, and the remaining text as `Box<str>`. This
+//! lowers the resident size of in-memory servers and WASM builds that keep a
+//! whole release loaded.
+//!
+//! Conversion is lossless: `to_mdd` rebuilds the verbatim `MddData`, and both
+//! serialize to the same JSON. Deserialized compact records are not interned;
+//! use `from_mdd` with a shared `Interner` for that.
+//!
+//! ```rust, ignore
+//! use mdd_api::parser::compact::MddDataCompact;
+//!
+//! let (compact, interner) = MddDataCompact::from_records(&species);
+//! println!("{} records share {} strings", compact.len(), interner.len());
+//! ```
+
+use std::{collections::HashSet, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use super::mdd::MddData;
+
+/// Hands out one shared `Arc<str>` per distinct string.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    strings: HashSet<Arc<str>>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The shared copy of `value`, allocated on first use.
+    pub fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(shared) = self.strings.get(value) {
+            return Arc::clone(shared);
+        }
+        let shared: Arc<str> = Arc::from(value);
+        self.strings.insert(Arc::clone(&shared));
+        shared
+    }
+
+    /// Number of distinct strings.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+/// `MddData` with shared storage for repeated columns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MddDataCompact {
+    pub id: u32,
+    pub sci_name: Box<str>,
+    pub main_common_name: Box<str>,
+    pub other_common_names: Box<str>,
+    pub phylosort: u16,
+    pub subclass: Arc<str>,
+    pub infraclass: Arc<str>,
+    pub magnorder: Arc<str>,
+    pub superorder: Arc<str>,
+    #[serde(alias = "order")]
+    pub taxon_order: Arc<str>,
+    pub suborder: Arc<str>,
+    pub infraorder: Arc<str>,
+    pub parvorder: Arc<str>,
+    pub superfamily: Arc<str>,
+    pub family: Arc<str>,
+    pub subfamily: Arc<str>,
+    pub tribe: Arc<str>,
+    pub genus: Arc<str>,
+    pub subgenus: Arc<str>,
+    pub specific_epithet: Box<str>,
+    pub authority_species_author: Arc<str>,
+    pub authority_species_year: u16,
+    pub authority_parentheses: u8,
+    pub original_name_combination: Box<str>,
+    pub authority_species_citation: Box<str>,
+    pub authority_species_link: Box<str>,
+    pub type_voucher: Box<str>,
+    pub type_kind: Arc<str>,
+    #[serde(rename = "typeVoucherURIs")]
+    pub type_voucher_uri: Box<str>,
+    pub type_locality: Box<str>,
+    pub type_locality_latitude: Box<str>,
+    pub type_locality_longitude: Box<str>,
+    pub nominal_names: Box<str>,
+    pub taxonomy_notes: Box<str>,
+    pub taxonomy_notes_citation: Box<str>,
+    pub distribution_notes: Box<str>,
+    pub distribution_notes_citation: Box<str>,
+    pub subregion_distribution: Arc<str>,
+    pub country_distribution: Arc<str>,
+    pub continent_distribution: Arc<str>,
+    pub biogeographic_realm: Arc<str>,
+    pub iucn_status: Arc<str>,
+    pub extinct: u8,
+    pub domestic: u8,
+    pub flagged: u8,
+    #[serde(rename = "CMW_sciName")]
+    pub cmw_sci_name: Box<str>,
+    #[serde(rename = "diffSinceCMW")]
+    pub diff_since_cmw: u8,
+    #[serde(rename = "MSW3_matchtype")]
+    pub msw3_match_type: Arc<str>,
+    #[serde(rename = "MSW3_sciName")]
+    pub msw3_sci_name: Box<str>,
+    #[serde(rename = "diffSinceMSW3")]
+    pub diff_since_msw3: Arc<str>,
+    #[serde(default)]
+    pub slug: Box<str>,
+    #[serde(default)]
+    pub(crate) basionym_synonym_id: Option<u32>,
+}
+
+impl MddDataCompact {
+    /// Compact `record`, interning its repeated columns in `interner`.
+    pub fn from_mdd(record: &MddData, interner: &mut Interner) -> Self {
+        Self {
+            id: record.id,
+            sci_name: record.sci_name.as_str().into(),
+            main_common_name: record.main_common_name.as_str().into(),
+            other_common_names: record.other_common_names.as_str().into(),
+            phylosort: record.phylosort,
+            subclass: interner.intern(&record.subclass),
+            infraclass: interner.intern(&record.infraclass),
+            magnorder: interner.intern(&record.magnorder),
+            superorder: interner.intern(&record.superorder),
+            taxon_order: interner.intern(&record.taxon_order),
+            suborder: interner.intern(&record.suborder),
+            infraorder: interner.intern(&record.infraorder),
+            parvorder: interner.intern(&record.parvorder),
+            superfamily: interner.intern(&record.superfamily),
+            family: interner.intern(&record.family),
+            subfamily: interner.intern(&record.subfamily),
+            tribe: interner.intern(&record.tribe),
+            genus: interner.intern(&record.genus),
+            subgenus: interner.intern(&record.subgenus),
+            specific_epithet: record.specific_epithet.as_str().into(),
+            authority_species_author: interner.intern(&record.authority_species_author),
+            authority_species_year: record.authority_species_year,
+            authority_parentheses: record.authority_parentheses,
+            original_name_combination: record.original_name_combination.as_str().into(),
+            authority_species_citation: record.authority_species_citation.as_str().into(),
+            authority_species_link: record.authority_species_link.as_str().into(),
+            type_voucher: record.type_voucher.as_str().into(),
+            type_kind: interner.intern(&record.type_kind),
+            type_voucher_uri: record.type_voucher_uri.as_str().into(),
+            type_locality: record.type_locality.as_str().into(),
+            type_locality_latitude: record.type_locality_latitude.as_str().into(),
+            type_locality_longitude: record.type_locality_longitude.as_str().into(),
+            nominal_names: record.nominal_names.as_str().into(),
+            taxonomy_notes: record.taxonomy_notes.as_str().into(),
+            taxonomy_notes_citation: record.taxonomy_notes_citation.as_str().into(),
+            distribution_notes: record.distribution_notes.as_str().into(),
+            distribution_notes_citation: record.distribution_notes_citation.as_str().into(),
+            subregion_distribution: interner.intern(&record.subregion_distribution),
+            country_distribution: interner.intern(&record.country_distribution),
+            continent_distribution: interner.intern(&record.continent_distribution),
+            biogeographic_realm: interner.intern(&record.biogeographic_realm),
+            iucn_status: interner.intern(&record.iucn_status),
+            extinct: record.extinct,
+            domestic: record.domestic,
+            flagged: record.flagged,
+            cmw_sci_name: record.cmw_sci_name.as_str().into(),
+            diff_since_cmw: record.diff_since_cmw,
+            msw3_match_type: interner.intern(&record.msw3_match_type),
+            msw3_sci_name: record.msw3_sci_name.as_str().into(),
+            diff_since_msw3: interner.intern(&record.diff_since_msw3),
+            slug: record.slug.as_str().into(),
+            basionym_synonym_id: record.basionym_synonym_id,
+        }
+    }
+
+    /// Compact `records` with a fresh interner, returned for reuse.
+    pub fn from_records(records: &[MddData]) -> (Vec<Self>, Interner) {
+        let mut interner = Interner::new();
+        let compact = records
+            .iter()
+            .map(|record| Self::from_mdd(record, &mut interner))
+            .collect();
+        (compact, interner)
+    }
+
+    /// The verbatim `MddData` record.
+    pub fn to_mdd(&self) -> MddData {
+        MddData {
+            id: self.id,
+            sci_name: self.sci_name.to_string(),
+            main_common_name: self.main_common_name.to_string(),
+            other_common_names: self.other_common_names.to_string(),
+            phylosort: self.phylosort,
+            subclass: self.subclass.to_string(),
+            infraclass: self.infraclass.to_string(),
+            magnorder: self.magnorder.to_string(),
+            superorder: self.superorder.to_string(),
+            taxon_order: self.taxon_order.to_string(),
+            suborder: self.suborder.to_string(),
+            infraorder: self.infraorder.to_string(),
+            parvorder: self.parvorder.to_string(),
+            superfamily: self.superfamily.to_string(),
+            family: self.family.to_string(),
+            subfamily: self.subfamily.to_string(),
+            tribe: self.tribe.to_string(),
+            genus: self.genus.to_string(),
+            subgenus: self.subgenus.to_string(),
+            specific_epithet: self.specific_epithet.to_string(),
+            authority_species_author: self.authority_species_author.to_string(),
+            authority_species_year: self.authority_species_year,
+            authority_parentheses: self.authority_parentheses,
+            original_name_combination: self.original_name_combination.to_string(),
+            authority_species_citation: self.authority_species_citation.to_string(),
+            authority_species_link: self.authority_species_link.to_string(),
+            type_voucher: self.type_voucher.to_string(),
+            type_kind: self.type_kind.to_string(),
+            type_voucher_uri: self.type_voucher_uri.to_string(),
+            type_locality: self.type_locality.to_string(),
+            type_locality_latitude: self.type_locality_latitude.to_string(),
+            type_locality_longitude: self.type_locality_longitude.to_string(),
+            nominal_names: self.nominal_names.to_string(),
+            taxonomy_notes: self.taxonomy_notes.to_string(),
+            taxonomy_notes_citation: self.taxonomy_notes_citation.to_string(),
+            distribution_notes: self.distribution_notes.to_string(),
+            distribution_notes_citation: self.distribution_notes_citation.to_string(),
+            subregion_distribution: self.subregion_distribution.to_string(),
+            country_distribution: self.country_distribution.to_string(),
+            continent_distribution: self.continent_distribution.to_string(),
+            biogeographic_realm: self.biogeographic_realm.to_string(),
+            iucn_status: self.iucn_status.to_string(),
+            extinct: self.extinct,
+            domestic: self.domestic,
+            flagged: self.flagged,
+            cmw_sci_name: self.cmw_sci_name.to_string(),
+            diff_since_cmw: self.diff_since_cmw,
+            msw3_match_type: self.msw3_match_type.to_string(),
+            msw3_sci_name: self.msw3_sci_name.to_string(),
+            diff_since_msw3: self.diff_since_msw3.to_string(),
+            slug: self.slug.to_string(),
+            basionym_synonym_id: self.basionym_synonym_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_round_trip() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let records = MddData::new().from_csv(&csv_data);
+        let (compact, interner) = MddDataCompact::from_records(&records);
+        assert_eq!(compact.len(), records.len());
+        let mut families = Interner::new();
+        for record in &records {
+            families.intern(&record.family);
+        }
+        assert_eq!(families.len(), 3);
+        assert!(interner.len() > families.len());
+        assert!(Arc::ptr_eq(&compact[0].family, &compact[1].family));
+        for (record, compact) in records.iter().zip(&compact) {
+            assert_eq!(compact.to_mdd().to_json(), record.to_json());
+            assert_eq!(serde_json::to_string(compact).unwrap(), record.to_json());
+        }
+    }
+}
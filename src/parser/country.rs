@@ -6,28 +6,49 @@
 //!   widespread placeholder entries (value equal to "NA").
 //! * Tracks predicted distributions (those ending with a `?`) by appending a
 //!   `?` to the stored species ID so callers can distinguish them later.
-//! * Emits warnings for unknown country/region names (falling back to the raw
-//!   name when a standardized code can't be resolved).
+//! * Marks species whose distribution resolves to exactly one country code
+//!   (predicted occurrences included) as endemic to that country.
+//! * Logs warnings through the `log` facade for unknown country/region names
+//!   (falling back to the raw name when a standardized code can't be resolved).
 //!
 //! The resulting `CountryMDDStats` structure is designed for downstream JSON
 //! consumption in UI or API layers and keeps counts plus ID lists rather than
 //! duplicating full species data.
+//!
+//! `IsoCountryStats` is the same aggregation keyed by ISO 3166-1 alpha-2
+//! code instead of the verbatim country string. Names without an ISO code
+//! (e.g. "Canary Islands") are listed in `unmatched` rather than mixed in with
+//! the countries. It also groups species by the state / province codes of the
+//! `subregion_distribution` field (`USA(AZ,NM?)|MEX(SON)`), attaching each
+//! subregion to its country.
+//!
+//! `CountryTrends` lines up `CountryMDDStats` of several releases: the
+//! species count of every country per release and the species it gained and
+//! lost between consecutive releases, for "diversity over time" charts.
 
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     path::Path,
 };
 
+use log::warn;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    helper::{country_code, MDD_LIST_SEPARATOR},
-    parser::mdd::MddData,
+    helper::{
+        country_code::{self, CountryRegionCode},
+        MDD_LIST_SEPARATOR, MDD_MISSING_VALUE,
+    },
+    parser::{
+        mdd::{strip_uncertainty, MddData},
+        ReleasedMddData,
+    },
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CountryMDDStats {
     /// Total number of countries / regions represented (size of `country_data`).
     pub total_countries: u32,
@@ -35,18 +56,28 @@ pub struct CountryMDDStats {
     pub domesticated: Vec<u32>,
     /// IDs of species whose distribution was marked as widespread/unspecified (value == "NA").
     pub widespread: Vec<u32>,
+    /// Number of species endemic to a single country / region.
+    #[serde(default)]
+    pub total_endemics: u32,
     /// Map of country code to `CountryData` record.
     /// The key is standardized country/region code (or raw name when unrecognized).
     /// Predicted distribution rows store species IDs with a trailing `?`.
     pub country_data: BTreeMap<String, CountryData>,
 }
 
+impl Default for CountryMDDStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CountryMDDStats {
     pub fn new() -> Self {
         Self {
             total_countries: 0,
             domesticated: Vec::new(),
             widespread: Vec::new(),
+            total_endemics: 0,
             country_data: BTreeMap::new(),
         }
     }
@@ -55,6 +86,7 @@ impl CountryMDDStats {
     /// It parses the country distribution and aggregates statistics for each country.
     /// It excludes domesticated species and widespread species (e.g., "NA" country list).
     /// If the country list does not match any known country code, it uses the country name as the code.
+    /// Species found in exactly one country are also recorded as endemic to it.
     pub fn parse_country_data(&mut self, mdd_data: &[MddData]) {
         // We use country code as the key for country_data.
         let mut records: HashMap<String, CountryRecord> = HashMap::new();
@@ -63,29 +95,40 @@ impl CountryMDDStats {
                 continue;
             }
 
-            // Update domesticated species count.
-            if species.country_distribution.to_lowercase() == "domesticated" {
-                self.domesticated.push(species.id);
-                continue; // Skip domesticated species.
-            }
-
-            // Update widespread species count.
-            if species.country_distribution.to_lowercase() == "na" {
-                self.widespread.push(species.id);
-                continue; // Skip widespread species.
+            // Skip domesticated and widespread species.
+            if record_excluded(species, &mut self.domesticated, &mut self.widespread) {
+                continue;
             }
 
-            if species.country_distribution.contains('|') {
+            let country_codes = if species.country_distribution.contains('|') {
                 // Multiple countries distribution.
-                self.parse_multiple_countries(&mut records, &species.country_distribution, species);
+                self.parse_multiple_countries(&mut records, &species.country_distribution, species)
             } else {
-                self.update_record(&species.country_distribution, &mut records, species);
+                self.update_record(&species.country_distribution, &mut records, species)
+                    .into_iter()
+                    .collect()
+            };
+            // Duplicates such as "Kenya|Kenya?" or several U.S. states resolve to one code.
+            if country_codes.len() == 1 {
+                let code = country_codes.iter().next().expect("One country code");
+                if let Some(record) = records.get_mut(code) {
+                    record.endemic_species_ids.push(species.id);
+                }
             }
         }
         self.update_data(&mut records);
         self.check_missing_country_code();
     }
 
+    /// Endemic species IDs per country code, for countries with at least one endemic.
+    pub fn endemics(&self) -> BTreeMap<&str, &[u32]> {
+        self.country_data
+            .iter()
+            .filter(|(_, data)| !data.endemic_species_list.is_empty())
+            .map(|(code, data)| (code.as_str(), data.endemic_species_list.as_slice()))
+            .collect()
+    }
+
     pub fn write_to_json_file(&self, file_path: &Path) {
         let json_data = self.to_json();
         std::fs::write(file_path, json_data).expect("Failed to write CountryMDDStats to JSON file");
@@ -123,13 +166,18 @@ impl CountryMDDStats {
         }
         // Update total countries count.
         self.total_countries = self.country_data.len() as u32;
+        self.total_endemics = self
+            .country_data
+            .values()
+            .map(|data| data.total_endemic_species)
+            .sum();
     }
 
     fn check_missing_country_code(&self) {
         for (code, record) in &self.country_data {
             if code.is_empty() {
-                eprintln!(
-                    "Warning: Empty country code found in MDD data for species IDs: {:?}. \
+                warn!(
+                    "Empty country code found in MDD data for species IDs: {:?}. \
                     This will be skipped.",
                     record.name
                 );
@@ -142,28 +190,29 @@ impl CountryMDDStats {
         records: &mut HashMap<String, CountryRecord>,
         distribution: &str,
         data: &MddData,
-    ) {
-        let countries = distribution.split(MDD_LIST_SEPARATOR);
-        for country in countries {
-            self.update_record(country, records, data);
-        }
+    ) -> HashSet<String> {
+        distribution
+            .split(MDD_LIST_SEPARATOR)
+            .filter_map(|country| self.update_record(country, records, data))
+            .collect()
     }
 
+    /// Adds the species to the country's record and returns the resolved country code.
     fn update_record(
         &mut self,
         country_name: &str,
         records: &mut HashMap<String, CountryRecord>,
         data: &MddData,
-    ) {
+    ) -> Option<String> {
         let country_name = country_name.trim();
         if country_name.is_empty() {
-            eprintln!(
-                "Warning: Empty country name found in MDD data for species ID: {}. \
+            warn!(
+                "Empty country name found in MDD data for species ID: {}. \
                 It could be due to trailing spaces. \
-                 This will be skipped.",
+                This will be skipped.",
                 data.id
             );
-            return;
+            return None;
         }
         let predicted = country_name.ends_with('?');
         let country_name = if predicted {
@@ -173,21 +222,325 @@ impl CountryMDDStats {
         };
 
         if !country_code::is_known_country_region(&country_name) {
-            eprintln!(
-                "Warning: '{}' does not match any known country code.",
-                country_name
-            );
+            warn!("'{}' does not match any known country code.", country_name);
         }
         let country_code = country_code::get_country_code(&country_name);
         let record = records
-            .entry(country_code)
+            .entry(country_code.clone())
             .or_insert_with(|| CountryRecord::new(country_name));
         record.update(data, predicted);
+        Some(country_code)
+    }
+}
+
+/// Country statistics of several releases, oldest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountryTrends {
+    /// Release versions in input order.
+    pub versions: Vec<String>,
+    /// Map of country code to its trend, for every country listed in any release.
+    pub countries: BTreeMap<String, CountryTrend>,
+}
+
+/// Species counts and changes of one country, aligned with `CountryTrends::versions`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountryTrend {
+    /// Country name in the newest release listing the country.
+    pub name: String,
+    /// Distinct species (predicted occurrences included) per release; 0 when
+    /// the release does not list the country.
+    pub species_counts: Vec<u32>,
+    /// Endemic species per release.
+    pub endemic_counts: Vec<u32>,
+    /// Species added and removed between each release and the next.
+    pub changes: Vec<CountryChange>,
+}
+
+/// Species IDs a country gained and lost between two releases.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountryChange {
+    pub from_version: String,
+    pub to_version: String,
+    pub added: Vec<u32>,
+    pub removed: Vec<u32>,
+}
+
+impl CountryMDDStats {
+    /// Parse the country statistics of each release and line them up.
+    /// Releases are expected oldest first.
+    pub fn trends(releases: &[ReleasedMddData]) -> CountryTrends {
+        let stats: Vec<(String, CountryMDDStats)> = releases
+            .iter()
+            .map(|release| {
+                let species: Vec<MddData> = release.species().cloned().collect();
+                let mut stats = CountryMDDStats::new();
+                stats.parse_country_data(&species);
+                (release.get_version().to_string(), stats)
+            })
+            .collect();
+        CountryTrends::from_stats(&stats)
+    }
+}
+
+impl CountryTrends {
+    /// Line up `(version, statistics)` pairs, oldest first.
+    pub fn from_stats(releases: &[(String, CountryMDDStats)]) -> Self {
+        let versions: Vec<String> = releases.iter().map(|(v, _)| v.clone()).collect();
+        let codes: BTreeSet<&String> = releases
+            .iter()
+            .flat_map(|(_, stats)| stats.country_data.keys())
+            .collect();
+        let countries = codes
+            .into_iter()
+            .map(|code| {
+                let data: Vec<Option<&CountryData>> = releases
+                    .iter()
+                    .map(|(_, stats)| stats.country_data.get(code))
+                    .collect();
+                (code.clone(), CountryTrend::new(&versions, &data))
+            })
+            .collect();
+        Self {
+            versions,
+            countries,
+        }
     }
 }
 
+impl CountryTrend {
+    fn new(versions: &[String], data: &[Option<&CountryData>]) -> Self {
+        let species: Vec<BTreeSet<u32>> = data
+            .iter()
+            .map(|data| data.map(CountryData::species_ids).unwrap_or_default())
+            .collect();
+        let changes = versions
+            .windows(2)
+            .zip(species.windows(2))
+            .map(|(versions, species)| CountryChange {
+                from_version: versions[0].clone(),
+                to_version: versions[1].clone(),
+                added: species[1].difference(&species[0]).copied().collect(),
+                removed: species[0].difference(&species[1]).copied().collect(),
+            })
+            .collect();
+        Self {
+            name: data
+                .iter()
+                .rev()
+                .flatten()
+                .map(|data| data.name.clone())
+                .next()
+                .unwrap_or_default(),
+            species_counts: species.iter().map(|ids| ids.len() as u32).collect(),
+            endemic_counts: data
+                .iter()
+                .map(|data| data.map_or(0, |data| data.total_endemic_species))
+                .collect(),
+            changes,
+        }
+    }
+}
+
+/// Country statistics keyed by ISO 3166-1 alpha-2 code.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct IsoCountryStats {
+    /// Number of ISO countries represented (size of `country_data`).
+    pub total_countries: u32,
+    /// IDs of species that were classified as domesticated.
+    pub domesticated: Vec<u32>,
+    /// IDs of species whose distribution was marked as widespread (value == "NA").
+    pub widespread: Vec<u32>,
+    /// Number of species endemic to a single ISO country.
+    pub total_endemics: u32,
+    /// Map of ISO alpha-2 code to `IsoCountryData` record.
+    pub country_data: BTreeMap<String, IsoCountryData>,
+    /// Country names without an ISO code, with the IDs of their species.
+    /// Predicted occurrences store species IDs with a trailing `?`.
+    pub unmatched: BTreeMap<String, Vec<String>>,
+    /// Subregion country codes (e.g. `ALS`) that are not an ISO code or not
+    /// in the species' country distribution, with the IDs of their species.
+    pub unmatched_subregions: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for IsoCountryStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IsoCountryStats {
+    pub fn new() -> Self {
+        Self {
+            total_countries: 0,
+            domesticated: Vec::new(),
+            widespread: Vec::new(),
+            total_endemics: 0,
+            country_data: BTreeMap::new(),
+            unmatched: BTreeMap::new(),
+            unmatched_subregions: BTreeMap::new(),
+        }
+    }
+
+    /// Parses the MDD data and updates the statistics.
+    /// Domesticated and widespread species are excluded as in `CountryMDDStats`.
+    /// A species is endemic when all of its countries resolve to the same ISO
+    /// code.
+    pub fn parse_country_data(&mut self, mdd_data: &[MddData]) {
+        let codes = CountryRegionCode::new();
+        let mut records: HashMap<String, CountryRecord> = HashMap::new();
+        let mut subregions: HashMap<String, BTreeMap<String, Vec<String>>> = HashMap::new();
+        for species in mdd_data {
+            let distribution = species.country_distribution.trim();
+            if distribution.is_empty() {
+                continue;
+            }
+            if record_excluded(species, &mut self.domesticated, &mut self.widespread) {
+                continue;
+            }
+
+            // Several names can map to one ISO code (e.g. "Texas|United States?"),
+            // so the species is counted once per code, confirmed over predicted.
+            let mut alpha2_codes: BTreeMap<String, (&str, bool)> = BTreeMap::new();
+            let mut has_unmatched = false;
+            for country in distribution.split(MDD_LIST_SEPARATOR) {
+                let (name, predicted) = split_predicted(country);
+                if name.is_empty() {
+                    continue;
+                }
+                match codes.iso_code_for_name(name) {
+                    Some(iso_code) => {
+                        let name = codes.name_for_code(&iso_code.alpha2).unwrap_or(name);
+                        alpha2_codes
+                            .entry(iso_code.alpha2.clone())
+                            .and_modify(|(_, is_predicted)| *is_predicted &= predicted)
+                            .or_insert((name, predicted));
+                    }
+                    None => {
+                        let id = species_id(species.id, predicted);
+                        self.unmatched.entry(name.to_string()).or_default().push(id);
+                        has_unmatched = true;
+                    }
+                }
+            }
+            for (code, (name, predicted)) in &alpha2_codes {
+                records
+                    .entry(code.clone())
+                    .or_insert_with(|| CountryRecord::new(name.to_string()))
+                    .update(species, *predicted);
+            }
+            if alpha2_codes.len() == 1 && !has_unmatched {
+                let code = alpha2_codes.keys().next().expect("One country code");
+                if let Some(record) = records.get_mut(code) {
+                    record.endemic_species_ids.push(species.id);
+                }
+            }
+
+            for captures in SUBREGION_REGEX.captures_iter(&species.subregion_distribution) {
+                let country = &captures[1];
+                let alpha2 = codes
+                    .alpha2_for_code(country)
+                    .filter(|alpha2| alpha2_codes.contains_key(*alpha2));
+                for subregion in captures[2].split(',') {
+                    let (subregion, predicted) = split_predicted(subregion);
+                    if subregion.is_empty() {
+                        continue;
+                    }
+                    let id = species_id(species.id, predicted);
+                    match alpha2 {
+                        Some(alpha2) => subregions
+                            .entry(alpha2.to_string())
+                            .or_default()
+                            .entry(subregion.to_string())
+                            .or_default()
+                            .push(id),
+                        None => self
+                            .unmatched_subregions
+                            .entry(country.to_string())
+                            .or_default()
+                            .push(id),
+                    }
+                }
+            }
+        }
+
+        for (code, record) in records {
+            let subregions = subregions.remove(&code).unwrap_or_default();
+            self.country_data.insert(
+                code,
+                IsoCountryData {
+                    data: CountryData::from_record(&record),
+                    subregions,
+                },
+            );
+        }
+        self.total_countries = self.country_data.len() as u32;
+        self.total_endemics = self
+            .country_data
+            .values()
+            .map(|country| country.data.total_endemic_species)
+            .sum();
+        if !self.unmatched.is_empty() {
+            warn!(
+                "Country names without an ISO code: {:?}",
+                self.unmatched.keys().collect::<Vec<_>>()
+            );
+        }
+    }
+}
+
+/// `CountryData` of an ISO country with its subregion breakdown.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct IsoCountryData {
+    #[serde(flatten)]
+    pub data: CountryData,
+    /// Species IDs per subregion code (e.g. `AZ`), from `subregion_distribution`.
+    /// Predicted occurrences store species IDs with a trailing `?`.
+    pub subregions: BTreeMap<String, Vec<String>>,
+}
+
+/// Strips the trailing `?` of a predicted occurrence.
+fn split_predicted(value: &str) -> (&str, bool) {
+    strip_uncertainty(value.trim())
+}
+
+/// Records domesticated and widespread ("NA") species, returning whether the
+/// species should be left out of the per-country counts.
+fn record_excluded(
+    species: &MddData,
+    domesticated: &mut Vec<u32>,
+    widespread: &mut Vec<u32>,
+) -> bool {
+    let distribution = species.country_distribution.trim();
+    if distribution.eq_ignore_ascii_case("domesticated") {
+        domesticated.push(species.id);
+        true
+    } else if distribution.eq_ignore_ascii_case(MDD_MISSING_VALUE) {
+        widespread.push(species.id);
+        true
+    } else {
+        false
+    }
+}
+
+fn species_id(id: u32, predicted: bool) -> String {
+    if predicted {
+        format!("{}?", id)
+    } else {
+        id.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 pub struct CountryData {
     pub name: String,
     pub total_orders: u32,
@@ -200,6 +553,18 @@ pub struct CountryData {
     /// This is used to generate the species list for the country.
     /// The caller will know the extinction status from the MDD record.
     pub species_list: Vec<String>,
+    /// Number of species found only in this country.
+    #[serde(default)]
+    pub total_endemic_species: u32,
+    /// MDD species IDs of the endemic species.
+    #[serde(default)]
+    pub endemic_species_list: Vec<u32>,
+}
+
+impl Default for CountryData {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CountryData {
@@ -212,9 +577,19 @@ impl CountryData {
             total_living_species: 0,
             total_extinct_species: 0,
             species_list: Vec::new(),
+            total_endemic_species: 0,
+            endemic_species_list: Vec::new(),
         }
     }
 
+    /// Distinct species IDs of `species_list`, predicted occurrences included.
+    pub fn species_ids(&self) -> BTreeSet<u32> {
+        self.species_list
+            .iter()
+            .filter_map(|id| id.trim_end_matches('?').parse().ok())
+            .collect()
+    }
+
     fn from_record(record: &CountryRecord) -> Self {
         Self {
             name: record.name.clone(),
@@ -229,6 +604,8 @@ impl CountryData {
                 .chain(record.extinct_species_ids.iter())
                 .map(|id| id.to_string())
                 .collect(),
+            total_endemic_species: record.endemic_species_ids.len() as u32,
+            endemic_species_list: record.endemic_species_ids.clone(),
         }
     }
 }
@@ -243,6 +620,8 @@ struct CountryRecord {
     // List of species mdd IDs in the country.
     living_species_ids: Vec<String>,
     extinct_species_ids: Vec<String>,
+    // MDD IDs of species found in this country only.
+    endemic_species_ids: Vec<u32>,
 }
 
 impl CountryRecord {
@@ -254,6 +633,7 @@ impl CountryRecord {
             genera: HashSet::new(),
             living_species_ids: Vec::new(),
             extinct_species_ids: Vec::new(),
+            endemic_species_ids: Vec::new(),
         }
     }
 
@@ -293,4 +673,98 @@ impl CountryRecord {
 // We create lazy static regex to match country codes.
 lazy_static::lazy_static! {
     static ref COUNTRY_CODE_REGEX: Regex = Regex::new(r"^[A-Z]{2}$").expect("Failed to compile country code regex");
+    // Country code and its subregion list, e.g. `USA(AZ,NM?)`.
+    static ref SUBREGION_REGEX: Regex = Regex::new(r"([A-Za-z]{3})\(([^)]*)\)").expect("Failed to compile subregion regex");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn species(id: u32, distribution: &str) -> MddData {
+        MddData {
+            id,
+            country_distribution: distribution.to_string(),
+            ..MddData::new()
+        }
+    }
+
+    #[test]
+    fn test_endemics() {
+        let data = vec![
+            species(1, "Kenya"),
+            species(2, "Kenya|Tanzania?"),
+            species(3, "Madagascar?"),
+            species(4, "Kenya|Kenya?"),
+            species(5, "domesticated"),
+            species(6, "NA"),
+        ];
+        let mut stats = CountryMDDStats::new();
+        stats.parse_country_data(&data);
+
+        let endemics = stats.endemics();
+        assert_eq!(endemics.len(), 2);
+        assert_eq!(stats.total_endemics, 3);
+        let kenya = country_code::get_country_code("Kenya");
+        assert_eq!(endemics[kenya.as_str()], &[1, 4]);
+        let kenya_data = &stats.country_data[&kenya];
+        assert_eq!(kenya_data.total_endemic_species, 2);
+        let tanzania = country_code::get_country_code("Tanzania");
+        assert_eq!(stats.country_data[&tanzania].total_endemic_species, 0);
+    }
+
+    #[test]
+    fn test_iso_country_stats() {
+        let mut data = vec![
+            species(1, "United States|Mexico"),
+            species(2, "Texas|United States?"),
+            species(3, "Kenya|Canary Islands"),
+            species(4, "domesticated"),
+        ];
+        data[0].subregion_distribution = "USA(AZ,NM?)|MEX(SON)".to_string();
+        data[1].subregion_distribution = "USA(TX)|CAN(BC)".to_string();
+        let mut stats = IsoCountryStats::new();
+        stats.parse_country_data(&data);
+
+        assert_eq!(stats.total_countries, 3);
+        assert_eq!(stats.domesticated, vec![4]);
+        assert_eq!(stats.unmatched["Canary Islands"], vec!["3"]);
+        assert_eq!(stats.unmatched_subregions["CAN"], vec!["2"]);
+        assert_eq!(stats.total_endemics, 1);
+
+        let us = &stats.country_data["US"];
+        // "Texas" and "United States?" both map to US; the confirmed entry wins.
+        assert_eq!(us.data.species_list, vec!["1", "2"]);
+        assert_eq!(us.data.endemic_species_list, vec![2]);
+        assert_eq!(us.subregions["AZ"], vec!["1"]);
+        assert_eq!(us.subregions["NM"], vec!["1?"]);
+        assert_eq!(us.subregions["TX"], vec!["2"]);
+        assert_eq!(stats.country_data["MX"].subregions["SON"], vec!["1"]);
+        assert!(stats.country_data["KE"].subregions.is_empty());
+    }
+
+    #[test]
+    fn test_country_trends() {
+        let parse = |version: &str, data: &[MddData]| {
+            let mut stats = CountryMDDStats::new();
+            stats.parse_country_data(data);
+            (version.to_string(), stats)
+        };
+        let trends = CountryTrends::from_stats(&[
+            parse("1.0", &[species(1, "Kenya"), species(2, "Kenya|Kenya?")]),
+            parse("2.0", &[species(1, "Kenya|Tanzania"), species(3, "Kenya")]),
+            parse("3.0", &[species(3, "Tanzania?")]),
+        ]);
+        assert_eq!(trends.versions, ["1.0", "2.0", "3.0"]);
+        let kenya = &trends.countries[&country_code::get_country_code("Kenya")];
+        assert_eq!(kenya.species_counts, [2, 2, 0]);
+        assert_eq!(kenya.endemic_counts, [2, 1, 0]);
+        assert_eq!(kenya.changes[0].added, [3]);
+        assert_eq!(kenya.changes[0].removed, [2]);
+        assert_eq!(kenya.changes[1].removed, [1, 3]);
+        let tanzania = &trends.countries[&country_code::get_country_code("Tanzania")];
+        assert_eq!(tanzania.species_counts, [0, 1, 1]);
+        assert_eq!(tanzania.changes[1].to_version, "3.0");
+        assert_eq!(tanzania.changes[1].added, [3]);
+    }
 }
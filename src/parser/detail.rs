@@ -0,0 +1,165 @@
+//! Everything a species page needs, in one serializable value.
+//!
+//! `ReleasedMddData::species_detail` joins a species record with its synonyms
+//! and subspecies and adds the values consumers otherwise derive by hand: the
+//! parsed `countryDistribution`, the type locality as decimal coordinates, and
+//! the species slug.
+
+use serde::{Deserialize, Serialize};
+
+use crate::helper::{
+    coords::{parse_coordinate, Axis, Coordinate},
+    slug::species_slug,
+};
+
+use super::{
+    mdd::MddData,
+    synonyms::{Subspecies, SynonymData},
+    ReleasedMddData, SimpleMDD,
+};
+
+/// A species with its synonyms and derived fields.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeciesDetail {
+    /// The assigned slug, or the base slug when the bundle has none.
+    pub slug: String,
+    pub species: MddData,
+    pub synonyms: Vec<SynonymData>,
+    pub subspecies: Vec<Subspecies>,
+    pub countries: Vec<CountryOccurrence>,
+    pub type_locality: TypeLocality,
+}
+
+/// A country of `countryDistribution`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountryOccurrence {
+    pub name: String,
+    /// `true` when the occurrence is predicted (`?` in MDD).
+    pub predicted: bool,
+}
+
+/// Type locality coordinates in decimal degrees. A value that is blank, not
+/// a coordinate, or out of range is `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeLocality {
+    pub latitude: Option<Coordinate>,
+    pub longitude: Option<Coordinate>,
+}
+
+impl TypeLocality {
+    /// Parse a verbatim latitude and longitude.
+    pub fn parse(latitude: &str, longitude: &str) -> Self {
+        Self {
+            latitude: coordinate(latitude, Axis::Latitude),
+            longitude: coordinate(longitude, Axis::Longitude),
+        }
+    }
+
+    pub fn from_species(species: &MddData) -> Self {
+        Self::parse(
+            &species.type_locality_latitude,
+            &species.type_locality_longitude,
+        )
+    }
+
+    pub fn from_synonym(synonym: &SynonymData) -> Self {
+        Self::parse(&synonym.type_latitude, &synonym.type_longitude)
+    }
+
+    /// `(latitude, longitude)` when both are known.
+    pub fn point(&self) -> Option<(f64, f64)> {
+        Some((self.latitude?.value, self.longitude?.value))
+    }
+}
+
+impl SpeciesDetail {
+    pub fn from_entry(entry: &SimpleMDD) -> Self {
+        let species = entry.species();
+        let slug = if species.slug.is_empty() {
+            species_slug(species)
+        } else {
+            species.slug.clone()
+        };
+        Self {
+            slug,
+            countries: species
+                .countries()
+                .iter()
+                .map(|country| CountryOccurrence {
+                    name: country.name.to_string(),
+                    predicted: country.predicted,
+                })
+                .collect(),
+            type_locality: TypeLocality::from_species(species),
+            species: species.clone(),
+            synonyms: entry.synonyms().to_vec(),
+            subspecies: entry.subspecies().to_vec(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).expect("Failed to serialize")
+    }
+}
+
+impl ReleasedMddData {
+    /// Detail of the species with MDD id `species_id`, if in the release.
+    pub fn species_detail(&self, species_id: u32) -> Option<SpeciesDetail> {
+        self.data
+            .iter()
+            .find(|entry| entry.mdd_id() == species_id)
+            .map(SpeciesDetail::from_entry)
+    }
+}
+
+fn coordinate(value: &str, axis: Axis) -> Option<Coordinate> {
+    parse_coordinate(value, axis)
+        .ok()
+        .flatten()
+        .filter(|coordinate| coordinate.value.abs() <= axis.limit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_species_detail() {
+        let species = MddData {
+            id: 1,
+            sci_name: "Ochotona_princeps".to_string(),
+            authority_species_year: 1828,
+            country_distribution: "Canada|United States?".to_string(),
+            type_locality_latitude: "52°N".to_string(),
+            type_locality_longitude: "-117.5".to_string(),
+            ..MddData::new()
+        };
+        let synonym = SynonymData {
+            syn_id: 10,
+            species_id: Some(1),
+            ..SynonymData::new()
+        };
+        let release =
+            ReleasedMddData::from_parser(vec![species], vec![synonym], "2.0", "2025-01-01");
+
+        let detail = release.species_detail(1).unwrap();
+        assert_eq!(detail.slug, "ochotona-princeps-1828");
+        assert_eq!(detail.synonyms.len(), 1);
+        assert_eq!(
+            detail.countries[1],
+            CountryOccurrence {
+                name: "United States".to_string(),
+                predicted: true
+            }
+        );
+        assert_eq!(detail.type_locality.point(), Some((52.0, -117.5)));
+        assert!(release.species_detail(2).is_none());
+
+        let json = detail.to_json();
+        let parsed: SpeciesDetail = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.to_json(), json);
+    }
+}
@@ -0,0 +1,212 @@
+//! Structured error type returned by the fallible (`try_*`) parser APIs.
+//!
+//! The convenience parsers (`MddData::from_csv`, `ReleasedMddData::from_json`,
+//! etc.) still panic on malformed input to keep quick scripts short. Library
+//! consumers that need to recover from, or report, bad rows should use the
+//! `try_*` variants which return `MddError` instead.
+
+use std::fmt;
+
+use super::schema::HeaderDiff;
+
+/// Errors produced while parsing MDD release assets.
+#[derive(Debug)]
+pub enum MddError {
+    /// A CSV record could not be read or deserialized.
+    Csv {
+        /// 1-based line number of the offending record (the header is line 1), when known.
+        row: Option<u64>,
+        /// Header name of the offending column, when the reader can attribute the failure.
+        column: Option<String>,
+        /// Description of the underlying failure.
+        cause: String,
+    },
+    /// The CSV header does not contain every column of the known layout.
+    Schema(HeaderDiff),
+    /// Reading an input file failed.
+    Io(std::io::Error),
+    /// JSON (de)serialization failed.
+    Json(serde_json::Error),
+    /// The release TOML could not be deserialized.
+    TomlDe(toml::de::Error),
+    /// The release metadata could not be serialized to TOML.
+    TomlSer(toml::ser::Error),
+    /// A release archive could not be read.
+    #[cfg(feature = "archive")]
+    Zip(zip::result::ZipError),
+    /// A release archive does not contain a required entry (file name pattern).
+    MissingArchiveEntry(String),
+    /// A release version (from metadata or a file name) could not be parsed.
+    InvalidVersion(String),
+    /// A release diff cannot be applied to the given release.
+    DiffMismatch(String),
+    /// Release files cannot be packaged into a release archive.
+    InvalidPackage(String),
+    /// A file name glob pattern could not be compiled.
+    InvalidPattern(String),
+    /// A projected field is not a JSON field of the record.
+    UnknownField(String),
+    /// A spreadsheet could not be read.
+    #[cfg(feature = "xlsx")]
+    Xlsx(calamine::Error),
+    /// A spreadsheet does not contain a required sheet.
+    #[cfg(feature = "xlsx")]
+    MissingSheet(String),
+    /// Reading or writing the SQLite export failed.
+    #[cfg(feature = "db")]
+    Sqlite(rusqlite::Error),
+    /// Writing the DuckDB export failed.
+    #[cfg(feature = "duckdb")]
+    DuckDb(duckdb::Error),
+}
+
+impl MddError {
+    /// Converts a `csv::Error` into `MddError::Csv`, resolving the failing field
+    /// index into its header name when `headers` is supplied.
+    pub(crate) fn from_csv(err: csv::Error, headers: Option<&csv::StringRecord>) -> Self {
+        let row = err.position().map(|pos| pos.line());
+        match err.kind() {
+            csv::ErrorKind::Deserialize { err: de_err, .. } => {
+                let column = de_err
+                    .field()
+                    .and_then(|idx| headers.and_then(|h| h.get(idx as usize)))
+                    .map(|name| name.to_string());
+                Self::Csv {
+                    row,
+                    column,
+                    cause: de_err.kind().to_string(),
+                }
+            }
+            _ => Self::Csv {
+                row,
+                column: None,
+                cause: err.to_string(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for MddError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Csv { row, column, cause } => {
+                write!(f, "CSV parse error")?;
+                if let Some(row) = row {
+                    write!(f, " at row {}", row)?;
+                }
+                if let Some(column) = column {
+                    write!(f, " in column '{}'", column)?;
+                }
+                write!(f, ": {}", cause)
+            }
+            Self::Schema(diff) => write!(f, "CSV header mismatch: {}", diff),
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::Json(e) => write!(f, "JSON error: {}", e),
+            Self::TomlDe(e) => write!(f, "TOML parse error: {}", e),
+            Self::TomlSer(e) => write!(f, "TOML serialization error: {}", e),
+            #[cfg(feature = "archive")]
+            Self::Zip(e) => write!(f, "Zip archive error: {}", e),
+            Self::MissingArchiveEntry(name) => write!(f, "Archive entry not found: {}", name),
+            Self::InvalidVersion(cause) => write!(f, "Invalid release version: {}", cause),
+            Self::DiffMismatch(cause) => write!(f, "Diff does not apply: {}", cause),
+            Self::InvalidPackage(cause) => write!(f, "Invalid release package: {}", cause),
+            Self::InvalidPattern(cause) => write!(f, "Invalid file name pattern: {}", cause),
+            Self::UnknownField(name) => write!(f, "Unknown field: {}", name),
+            #[cfg(feature = "xlsx")]
+            Self::Xlsx(e) => write!(f, "Spreadsheet error: {}", e),
+            #[cfg(feature = "xlsx")]
+            Self::MissingSheet(name) => write!(f, "Sheet not found: {}", name),
+            #[cfg(feature = "db")]
+            Self::Sqlite(e) => write!(f, "SQLite error: {}", e),
+            #[cfg(feature = "duckdb")]
+            Self::DuckDb(e) => write!(f, "DuckDB error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MddError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Csv { .. }
+            | Self::Schema(_)
+            | Self::MissingArchiveEntry(_)
+            | Self::InvalidVersion(_)
+            | Self::DiffMismatch(_)
+            | Self::InvalidPackage(_)
+            | Self::InvalidPattern(_)
+            | Self::UnknownField(_) => None,
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+            Self::TomlDe(e) => Some(e),
+            Self::TomlSer(e) => Some(e),
+            #[cfg(feature = "archive")]
+            Self::Zip(e) => Some(e),
+            #[cfg(feature = "xlsx")]
+            Self::Xlsx(e) => Some(e),
+            #[cfg(feature = "xlsx")]
+            Self::MissingSheet(_) => None,
+            #[cfg(feature = "db")]
+            Self::Sqlite(e) => Some(e),
+            #[cfg(feature = "duckdb")]
+            Self::DuckDb(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for MddError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for MddError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl From<toml::de::Error> for MddError {
+    fn from(err: toml::de::Error) -> Self {
+        Self::TomlDe(err)
+    }
+}
+
+impl From<toml::ser::Error> for MddError {
+    fn from(err: toml::ser::Error) -> Self {
+        Self::TomlSer(err)
+    }
+}
+
+#[cfg(feature = "xlsx")]
+impl From<calamine::Error> for MddError {
+    fn from(err: calamine::Error) -> Self {
+        Self::Xlsx(err)
+    }
+}
+
+#[cfg(feature = "archive")]
+impl From<zip::result::ZipError> for MddError {
+    fn from(err: zip::result::ZipError) -> Self {
+        Self::Zip(err)
+    }
+}
+
+impl From<csv::Error> for MddError {
+    fn from(err: csv::Error) -> Self {
+        Self::from_csv(err, None)
+    }
+}
+
+#[cfg(feature = "db")]
+impl From<rusqlite::Error> for MddError {
+    fn from(err: rusqlite::Error) -> Self {
+        Self::Sqlite(err)
+    }
+}
+
+#[cfg(feature = "duckdb")]
+impl From<duckdb::Error> for MddError {
+    fn from(err: duckdb::Error) -> Self {
+        Self::DuckDb(err)
+    }
+}
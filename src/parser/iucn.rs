@@ -0,0 +1,130 @@
+//! IUCN Red List aggregation of MDD species records.
+//!
+//! `IucnStats` is the Red List counterpart of `CountryMDDStats`. It parses the
+//! `iucn_status` field of every `MddData` record and counts species per
+//! category:
+//! * overall, plus the number of threatened species (VU, EN, CR);
+//! * per order and per family;
+//! * per biogeographic realm, splitting multi-realm distributions so a
+//!   species counts once in every realm it occurs in (uncertain `?` realms
+//!   included).
+//!
+//! Annotated statuses such as `LC (as Lepus victoriae)` count under their
+//! category. Species with an empty or unrecognized status are excluded from
+//! the counts and listed in `unrecognized` instead.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{helper::iucn::IucnStatus, parser::mdd::MddData};
+
+/// Number of species per IUCN category code (e.g. `"LC"`).
+pub type CategoryCounts = BTreeMap<String, u32>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IucnStats {
+    /// Number of species with a recognized IUCN category.
+    pub total_species: u32,
+    /// Number of species listed as VU, EN, or CR.
+    pub total_threatened: u32,
+    /// Species per category across all records.
+    pub categories: CategoryCounts,
+    /// Species per category for each order.
+    pub by_order: BTreeMap<String, CategoryCounts>,
+    /// Species per category for each family.
+    pub by_family: BTreeMap<String, CategoryCounts>,
+    /// Species per category for each biogeographic realm.
+    pub by_realm: BTreeMap<String, CategoryCounts>,
+    /// IDs of species whose status is empty or not an IUCN category.
+    pub unrecognized: Vec<u32>,
+}
+
+impl Default for IucnStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IucnStats {
+    pub fn new() -> Self {
+        Self {
+            total_species: 0,
+            total_threatened: 0,
+            categories: BTreeMap::new(),
+            by_order: BTreeMap::new(),
+            by_family: BTreeMap::new(),
+            by_realm: BTreeMap::new(),
+            unrecognized: Vec::new(),
+        }
+    }
+
+    /// Parses the MDD data and updates the IUCN statistics.
+    pub fn parse_iucn_data(&mut self, mdd_data: &[MddData]) {
+        for species in mdd_data {
+            let status = match species.iucn_status.parse::<IucnStatus>() {
+                Ok(status) => status,
+                Err(_) => {
+                    self.unrecognized.push(species.id);
+                    continue;
+                }
+            };
+            let code = status.code();
+            self.total_species += 1;
+            if status.is_threatened() {
+                self.total_threatened += 1;
+            }
+            increment(&mut self.categories, code);
+            increment(
+                self.by_order
+                    .entry(species.taxon_order.clone())
+                    .or_default(),
+                code,
+            );
+            increment(
+                self.by_family.entry(species.family.clone()).or_default(),
+                code,
+            );
+            let mut realms: Vec<&str> = species.realms().iter().map(|r| r.value).collect();
+            realms.sort_unstable();
+            realms.dedup();
+            for realm in realms {
+                increment(self.by_realm.entry(realm.to_string()).or_default(), code);
+            }
+        }
+    }
+
+    /// Species count of `category` across all records.
+    pub fn count(&self, category: IucnStatus) -> u32 {
+        self.categories
+            .get(category.code())
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+fn increment(counts: &mut CategoryCounts, code: &str) {
+    *counts.entry(code.to_string()).or_insert(0) += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_iucn_data() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let mut stats = IucnStats::new();
+        stats.parse_iucn_data(&species);
+        assert_eq!(stats.total_species, 112);
+        assert!(stats.unrecognized.is_empty());
+        // Annotated `LC (as ...)` values count as LC.
+        assert_eq!(stats.count(IucnStatus::LC), 62);
+        assert_eq!(stats.total_threatened, 20);
+        assert_eq!(stats.by_order["Lagomorpha"].values().sum::<u32>(), 112);
+        let realm_total: u32 = stats.by_realm["Palearctic"].values().sum();
+        assert!(realm_total >= 52);
+    }
+}
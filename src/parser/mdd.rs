@@ -1,7 +1,22 @@
 //! Parse MDD csv data into a structured format.
 
+use std::io::Read;
+
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    helper::{iucn::IucnStatus, split_mdd_list},
+    nomenclature::{authorship::Authorship, combination::Combination, nominal::NominalName},
+};
+
+use super::{
+    error::MddError,
+    options::{CsvDialect, ParseOptions, ParseReport},
+    read_species_records_with,
+    reader::MddCsvReader,
+    schema::{read_header_row, HeaderDiff, MddSchema},
+};
+
 /// Primary record representing a single species row from the Mammal Diversity Database (MDD)
 /// CSV export.
 ///
@@ -23,6 +38,12 @@ use serde::{Deserialize, Serialize};
 ///   contain composite, approximate, or blank entries.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(name = "Species", complex)
+)]
 pub struct MddData {
     /// Unique numeric identifier for the species record (MDD internal ID).
     pub id: u32,
@@ -134,6 +155,20 @@ pub struct MddData {
     /// Description of differences relative to MSW3 (`diffSinceMSW3`).
     #[serde(rename = "diffSinceMSW3")]
     pub diff_since_msw3: String,
+    /// URL-safe permalink (see `helper::slug`); not an MDD column, empty until assigned.
+    #[serde(default)]
+    pub slug: String,
+    /// Synonym id of the original description (see `nomenclature::combination`);
+    /// not an MDD column, set when bundled into a release.
+    #[serde(default)]
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub(crate) basionym_synonym_id: Option<u32>,
+}
+
+impl Default for MddData {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MddData {
@@ -189,24 +224,276 @@ impl MddData {
             msw3_match_type: "".to_string(),
             msw3_sci_name: "".to_string(),
             diff_since_msw3: "".to_string(),
+            slug: "".to_string(),
+            basionym_synonym_id: None,
         }
     }
 
-    /// Parse csv data to json.
-    /// Return in String json format.
+    /// Parse csv data into MDD records.
+    ///
+    /// Panics on the first malformed row. Use `try_from_csv` to handle
+    /// errors instead.
     pub fn from_csv(&self, csv_data: &str) -> Vec<MddData> {
-        let mut rdr = csv::Reader::from_reader(csv_data.as_bytes());
-        let mut records = Vec::new();
-        for result in rdr.deserialize() {
-            let record: Self = result.unwrap();
-            records.push(record);
-        }
-        records
+        self.try_from_csv(csv_data)
+            .unwrap_or_else(|e| panic!("Failed to parse MDD CSV data: {}", e))
+    }
+
+    /// Parse csv data into MDD records, returning an `MddError` describing
+    /// the first row (and column, when known) that fails to deserialize.
+    ///
+    /// The `MddSchema` is detected from the header row, so v1.x releases
+    /// parse too.
+    pub fn try_from_csv(&self, csv_data: &str) -> Result<Vec<MddData>, MddError> {
+        let csv_data = CsvDialect::new().clean_header(csv_data);
+        read_species_records_with(csv_data.as_bytes(), &ParseOptions::default())
+            .map(|(records, _)| records)
+    }
+
+    /// Parse MDD records from any reader (file, zip entry, gzip stream, ...)
+    /// without loading the whole CSV into a string first.
+    ///
+    /// Use `MddCsvReader` directly to process records one at a time. The
+    /// `MddSchema` is detected from the header row, so v1.x releases parse
+    /// too.
+    pub fn from_reader<R: Read>(&self, reader: R) -> Result<Vec<MddData>, MddError> {
+        MddCsvReader::new(reader)?.collect()
+    }
+
+    /// Parse csv data according to `options`.
+    ///
+    /// In lenient mode (`strict: false`) unparseable rows are skipped and
+    /// listed in the returned `ParseReport` together with their raw text.
+    /// Input in another `options.dialect` (e.g. TSV) is converted first.
+    pub fn from_csv_with(
+        &self,
+        csv_data: &str,
+        options: &ParseOptions,
+    ) -> Result<(Vec<MddData>, ParseReport), MddError> {
+        let csv_data = options.dialect.to_standard(csv_data)?;
+        read_species_records_with(csv_data.as_bytes(), options)
+    }
+
+    /// Compare the header row of `csv_data` against the known MDD species layout.
+    ///
+    /// The header row is mapped through the detected `MddSchema` first. The
+    /// returned `HeaderDiff` lists missing, unexpected, and reordered
+    /// columns. Only missing columns prevent parsing.
+    pub fn validate_headers(&self, csv_data: &str) -> Result<HeaderDiff, MddError> {
+        let csv_data = CsvDialect::new().clean_header(csv_data);
+        let headers = read_header_row(csv_data.as_bytes())?;
+        let headers: Vec<&str> = headers.iter().map(|h| h.as_str()).collect();
+        let schema = MddSchema::detect(&headers).unwrap_or(MddSchema::V2);
+        Ok(schema.header_diff(&headers))
     }
 
     pub fn to_json(&self) -> String {
         serde_json::to_string(&self).expect("Failed to serialize")
     }
+
+    /// Serialize only `fields` (JSON names such as `sciName`), in the given
+    /// order.
+    pub fn to_json_with_fields(&self, fields: &[&str]) -> Result<String, MddError> {
+        Ok(serde_json::to_string(&self.project(fields)?)?)
+    }
+
+    /// JSON object of the record restricted to `fields`, in the given order;
+    /// fails on a name that is not a field.
+    pub fn project(
+        &self,
+        fields: &[&str],
+    ) -> Result<serde_json::Map<String, serde_json::Value>, MddError> {
+        let serde_json::Value::Object(record) = serde_json::to_value(self)? else {
+            unreachable!("MddData serializes to an object");
+        };
+        fields
+            .iter()
+            .map(|field| match record.get(*field) {
+                Some(value) => Ok((field.to_string(), value.clone())),
+                None => Err(MddError::UnknownField(field.to_string())),
+            })
+            .collect()
+    }
+
+    /// JSON names of the record fields, in serialization order.
+    pub fn json_field_names() -> Vec<String> {
+        match serde_json::to_value(Self::new()) {
+            Ok(serde_json::Value::Object(record)) => record.keys().cloned().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Red List category of `iucn_status`; values that are not a category
+    /// code are kept in `IucnStatus::Unknown`.
+    pub fn iucn_category(&self) -> IucnStatus {
+        IucnStatus::from_verbatim(&self.iucn_status)
+    }
+
+    /// Countries listed in `country_distribution`.
+    ///
+    /// A trailing `?` marks a predicted occurrence and is reported through
+    /// `CountryToken::predicted`. The `domesticated` and `NA` labels are not
+    /// countries and yield an empty list.
+    pub fn countries(&self) -> Vec<CountryToken<'_>> {
+        country_tokens(&self.country_distribution)
+    }
+
+    /// Alternate common names from `other_common_names`.
+    pub fn common_names(&self) -> Vec<&str> {
+        split_mdd_list(&self.other_common_names).collect()
+    }
+
+    /// Continents from `continent_distribution`.
+    pub fn continents(&self) -> Vec<ListToken<'_>> {
+        split_tokens(&self.continent_distribution)
+    }
+
+    /// Biogeographic realms from `biogeographic_realm`.
+    pub fn realms(&self) -> Vec<ListToken<'_>> {
+        split_tokens(&self.biogeographic_realm)
+    }
+
+    /// Type voucher URIs from `type_voucher_uri`.
+    pub fn type_voucher_uris(&self) -> Vec<&str> {
+        split_mdd_list(&self.type_voucher_uri).collect()
+    }
+
+    /// Structured species authority (author, year, and parentheses columns).
+    pub fn authorship(&self) -> Authorship {
+        Authorship::from_species(self)
+    }
+
+    /// Call `f` on every text column (the derived `slug` excluded), e.g. to
+    /// clean up species records in a `Pipeline` transform.
+    pub fn for_each_text_field(&mut self, mut f: impl FnMut(&mut String)) {
+        for field in [
+            &mut self.sci_name,
+            &mut self.main_common_name,
+            &mut self.other_common_names,
+            &mut self.subclass,
+            &mut self.infraclass,
+            &mut self.magnorder,
+            &mut self.superorder,
+            &mut self.taxon_order,
+            &mut self.suborder,
+            &mut self.infraorder,
+            &mut self.parvorder,
+            &mut self.superfamily,
+            &mut self.family,
+            &mut self.subfamily,
+            &mut self.tribe,
+            &mut self.genus,
+            &mut self.subgenus,
+            &mut self.specific_epithet,
+            &mut self.authority_species_author,
+            &mut self.original_name_combination,
+            &mut self.authority_species_citation,
+            &mut self.authority_species_link,
+            &mut self.type_voucher,
+            &mut self.type_kind,
+            &mut self.type_voucher_uri,
+            &mut self.type_locality,
+            &mut self.type_locality_latitude,
+            &mut self.type_locality_longitude,
+            &mut self.nominal_names,
+            &mut self.taxonomy_notes,
+            &mut self.taxonomy_notes_citation,
+            &mut self.distribution_notes,
+            &mut self.distribution_notes_citation,
+            &mut self.subregion_distribution,
+            &mut self.country_distribution,
+            &mut self.continent_distribution,
+            &mut self.biogeographic_realm,
+            &mut self.iucn_status,
+            &mut self.cmw_sci_name,
+            &mut self.msw3_match_type,
+            &mut self.msw3_sci_name,
+            &mut self.diff_since_msw3,
+        ] {
+            f(field);
+        }
+    }
+
+    /// Parsed `original_name_combination`, if one is recorded.
+    pub fn original_combination(&self) -> Option<Combination> {
+        Combination::parse(&self.original_name_combination)
+    }
+
+    /// Entries of `nominal_names` with their authority and status marker.
+    pub fn nominal_name_entries(&self) -> Vec<NominalName> {
+        NominalName::parse_list(&self.nominal_names)
+    }
+
+    /// Id of the synonym row describing the original name, once linked by
+    /// `ReleasedMddData::from_parser` (or `nomenclature::combination::link_basionyms`).
+    pub fn basionym_synonym_id(&self) -> Option<u32> {
+        self.basionym_synonym_id
+    }
+}
+
+/// A country parsed from `MddData::country_distribution`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountryToken<'a> {
+    /// Country or region name without the uncertainty marker.
+    pub name: &'a str,
+    /// `true` when the occurrence is predicted (source value ends with `?`).
+    pub predicted: bool,
+}
+
+/// A single value of a pipe-separated MDD field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListToken<'a> {
+    /// Value without the uncertainty marker.
+    pub value: &'a str,
+    /// `true` when the source value ends with `?`.
+    pub uncertain: bool,
+}
+
+fn split_tokens(value: &str) -> Vec<ListToken<'_>> {
+    split_mdd_list(value)
+        .map(|token| {
+            let (value, uncertain) = strip_uncertainty(token);
+            ListToken { value, uncertain }
+        })
+        .collect()
+}
+
+/// Countries of a `countryDistribution` value; none for `domesticated`.
+pub(super) fn country_tokens(value: &str) -> Vec<CountryToken<'_>> {
+    if value.trim().eq_ignore_ascii_case("domesticated") {
+        return Vec::new();
+    }
+    split_mdd_list(value)
+        .map(|token| {
+            let (name, predicted) = strip_uncertainty(token);
+            CountryToken { name, predicted }
+        })
+        .collect()
+}
+
+pub(super) fn strip_uncertainty(token: &str) -> (&str, bool) {
+    match token.strip_suffix('?') {
+        Some(stripped) => (stripped.trim_end(), true),
+        None => (token, false),
+    }
+}
+
+/// Records shared by the tests of the name-based indexes.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::MddData;
+
+    /// Species `id` named `sci_name`, with its main and other common names.
+    pub(crate) fn species(id: u32, sci_name: &str, common: &str, other: &str) -> MddData {
+        MddData {
+            id,
+            sci_name: sci_name.to_string(),
+            main_common_name: common.to_string(),
+            other_common_names: other.to_string(),
+            ..MddData::new()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -224,4 +511,126 @@ mod tests {
         // let data = AllMddData::from_json(&json_data);
         assert_eq!(json_data.len(), 112);
     }
+
+    #[test]
+    fn test_try_from_csv_reports_bad_row() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let mut lines: Vec<String> = csv_data.lines().map(|l| l.to_string()).collect();
+        // Corrupt the `id` column (second column) of the second data row.
+        lines[2] = lines[2].replacen(",1001077,", ",not-a-number,", 1);
+        let result = MddData::new().try_from_csv(&lines.join("\n"));
+        match result {
+            Err(MddError::Csv { row, column, .. }) => {
+                assert_eq!(row, Some(3));
+                assert_eq!(column.as_deref(), Some("id"));
+            }
+            _ => panic!("Expected a CSV error"),
+        }
+    }
+
+    #[test]
+    fn test_multi_value_accessors() {
+        let mut record = MddData::new();
+        record.country_distribution = "China|India|Bhutan?".to_string();
+        record.other_common_names = "Northwest African Hare|Sardinian Hare|".to_string();
+        record.biogeographic_realm = "Palearctic|Afrotropic".to_string();
+        record.type_voucher_uri = "http://a.org/1 | http://a.org/2".to_string();
+        let countries = record.countries();
+        assert_eq!(countries.len(), 3);
+        assert_eq!(
+            countries[2],
+            CountryToken {
+                name: "Bhutan",
+                predicted: true
+            }
+        );
+        assert_eq!(
+            record.common_names(),
+            vec!["Northwest African Hare", "Sardinian Hare"]
+        );
+        assert_eq!(record.realms()[1].value, "Afrotropic");
+        assert_eq!(
+            record.type_voucher_uris(),
+            vec!["http://a.org/1", "http://a.org/2"]
+        );
+
+        record.country_distribution = "NA".to_string();
+        assert!(record.countries().is_empty());
+        record.other_common_names = "NA".to_string();
+        assert!(record.common_names().is_empty());
+    }
+
+    #[test]
+    fn test_to_json_with_fields() {
+        let record = MddData {
+            id: 1006040,
+            sci_name: "Panthera_leo".to_string(),
+            family: "Felidae".to_string(),
+            ..MddData::new()
+        };
+        assert_eq!(
+            record.to_json_with_fields(&["sciName", "id"]).unwrap(),
+            r#"{"sciName":"Panthera_leo","id":1006040}"#
+        );
+        assert!(matches!(
+            record.to_json_with_fields(&["scientificName"]),
+            Err(MddError::UnknownField(name)) if name == "scientificName"
+        ));
+        let names = MddData::json_field_names();
+        assert_eq!(names[0], "id");
+        assert!(names.iter().any(|name| name == "MSW3_sciName"));
+    }
+
+    #[test]
+    fn test_validate_headers() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let parser = MddData::new();
+        assert!(parser.validate_headers(&csv_data).unwrap().is_empty());
+
+        let renamed = csv_data.replacen("iucnStatus", "redListStatus", 1);
+        let diff = parser.validate_headers(&renamed).unwrap();
+        assert_eq!(diff.missing, vec!["iucnStatus"]);
+        assert_eq!(diff.unexpected, vec!["redListStatus"]);
+        assert!(matches!(
+            parser.try_from_csv(&renamed),
+            Err(MddError::Schema(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_v1_csv() {
+        let csv_data = std::fs::read_to_string("tests/data/v1_test_data.csv").unwrap();
+        let parser = MddData::new();
+        assert!(parser.validate_headers(&csv_data).unwrap().is_compatible());
+        let records = parser.try_from_csv(&csv_data).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].sci_name, "Bunolagus_monticularis");
+        assert_eq!(records[0].taxon_order, "Lagomorpha");
+        assert!(records[0].type_voucher.starts_with("BMNH"));
+        assert_eq!(records[0].id, 0);
+
+        let (lenient, report) = parser
+            .from_csv_with(&csv_data, &ParseOptions::lenient())
+            .unwrap();
+        assert_eq!(lenient.len(), 3);
+        assert!(report.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_lenient_parsing_skips_bad_rows() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let mut lines: Vec<String> = csv_data.lines().map(|l| l.to_string()).collect();
+        lines[2] = lines[2].replacen(",1001077,", ",not-a-number,", 1);
+        let (records, report) = MddData::new()
+            .from_csv_with(&lines.join("\n"), &ParseOptions::lenient())
+            .unwrap();
+        assert_eq!(records.len(), 111);
+        assert_eq!(report.total_rows, 112);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].row, Some(3));
+        assert_eq!(report.skipped[0].field.as_deref(), Some("id"));
+        assert!(report.skipped[0]
+            .raw
+            .starts_with("Caprolagus_hispidus,not-a-number"));
+    }
 }
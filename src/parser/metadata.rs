@@ -1,6 +1,15 @@
 //! Module to parse metadata information in the MDD release files.
+use std::path::{Path, PathBuf};
+
 use serde::{Deserialize, Serialize};
 
+use crate::helper::checksum::{file_sha256, sha256_matches};
+
+use super::{archive::version_from_file_name, error::MddError, version::ReleaseVersion};
+
+/// Release name written by `ReleaseMetadata::from_release_files`.
+pub const DEFAULT_RELEASE_NAME: &str = "MDD";
+
 /// Metadata about the MDD release.
 /// This metadata parse the version, release date, and other information
 /// from TOML file.
@@ -12,10 +21,13 @@ use serde::{Deserialize, Serialize};
 /// mdd_file = "mdd_2024_1.csv"
 /// synonym_file = "synonyms_2024_1.csv"
 /// remarks = "This is a sample release."
+/// mdd_sha256 = "9f86d0..."
+/// synonym_sha256 = "2c26b4..."
 /// ```
 ///
 /// Additional notes:
-/// * `doi` and `remarks` are optional and will deserialize to `None` if absent.
+/// * `doi`, `remarks`, and the SHA-256 digests are optional and will deserialize to
+///   `None` if absent.
 /// * The parent struct (`ReleaseToml`) wraps this under the `[metadata]` table.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ReleaseToml {
@@ -23,9 +35,7 @@ pub struct ReleaseToml {
 }
 
 impl ReleaseToml {
-    pub fn from_file<P: AsRef<std::path::Path>>(
-        path: P,
-    ) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, MddError> {
         let content = std::fs::read_to_string(path)?;
         let metadata: Self = toml::from_str(&content)?;
         Ok(metadata)
@@ -36,10 +46,60 @@ impl ReleaseToml {
     }
 
     pub fn to_toml(&self) -> String {
-        toml::to_string(self).expect("Failed to serialize to TOML")
+        self.try_to_toml().expect("Failed to serialize to TOML")
+    }
+
+    /// Parsed release version, see `ReleaseMetadata::release_version`.
+    pub fn release_version(&self) -> Result<ReleaseVersion, MddError> {
+        self.metadata.release_version()
+    }
+
+    /// Fallible variant of `to_toml`.
+    pub fn try_to_toml(&self) -> Result<String, MddError> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// Hash the species and synonym CSVs (resolved against `dir`, see
+    /// `ReleaseMetadata::mdd_path`) and return the files whose digest differs
+    /// from the recorded one. Files without a recorded digest are not checked;
+    /// a missing file is an `MddError::Io`.
+    pub fn verify(&self, dir: &Path) -> Result<Vec<ChecksumMismatch>, MddError> {
+        let files = [
+            (self.metadata.mdd_path(dir), &self.metadata.mdd_sha256),
+            (
+                self.metadata.synonym_path(dir),
+                &self.metadata.synonym_sha256,
+            ),
+        ];
+        let mut mismatches = Vec::new();
+        for (path, expected) in files {
+            let expected = match expected {
+                Some(expected) => expected,
+                None => continue,
+            };
+            let actual = file_sha256(&path)?;
+            if !sha256_matches(expected, &actual) {
+                mismatches.push(ChecksumMismatch {
+                    path,
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+        Ok(mismatches)
     }
 }
 
+/// A release file whose content does not match its recorded digest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumMismatch {
+    pub path: PathBuf,
+    /// Digest recorded in the release TOML, verbatim.
+    pub expected: String,
+    /// SHA-256 digest of the file on disk.
+    pub actual: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct ReleaseMetadata {
     /// The name of the release.
@@ -56,6 +116,12 @@ pub struct ReleaseMetadata {
     pub doi: Option<String>,
     /// Optional remarks or description for the release.
     pub remarks: Option<String>,
+    /// SHA-256 digest (hex) of the species CSV.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mdd_sha256: Option<String>,
+    /// SHA-256 digest (hex) of the synonym CSV.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synonym_sha256: Option<String>,
 }
 
 impl ReleaseMetadata {
@@ -76,13 +142,67 @@ impl ReleaseMetadata {
             synonym_file,
             doi,
             remarks,
+            mdd_sha256: None,
+            synonym_sha256: None,
         }
     }
+
+    /// Metadata template for a species and a synonym CSV.
+    ///
+    /// The version is inferred from the species file name (empty when it does
+    /// not follow `MDD_v<version>`), both files are checksummed, and their
+    /// paths are stored relative to `base_dir` (the directory the TOML is
+    /// written to) when they are inside it, otherwise as absolute paths. The
+    /// release date, DOI, and remarks are left for the caller.
+    pub fn from_release_files(
+        mdd_path: &Path,
+        synonym_path: &Path,
+        base_dir: &Path,
+    ) -> Result<Self, MddError> {
+        let file_name = mdd_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(Self {
+            name: DEFAULT_RELEASE_NAME.to_string(),
+            version: version_from_file_name(&file_name).unwrap_or_default(),
+            mdd_file: relative_path(mdd_path, base_dir)?,
+            synonym_file: relative_path(synonym_path, base_dir)?,
+            mdd_sha256: Some(file_sha256(mdd_path)?),
+            synonym_sha256: Some(file_sha256(synonym_path)?),
+            ..Default::default()
+        })
+    }
+
+    /// `version` parsed as a `ReleaseVersion`, for sorting and comparing releases.
+    pub fn release_version(&self) -> Result<ReleaseVersion, MddError> {
+        self.version.parse()
+    }
+
+    /// Path to the species CSV, resolved against `base_dir` (usually the
+    /// directory holding the release TOML) unless `mdd_file` is absolute.
+    pub fn mdd_path(&self, base_dir: &Path) -> PathBuf {
+        base_dir.join(&self.mdd_file)
+    }
+
+    /// Path to the synonym CSV, resolved the same way as `mdd_path`.
+    pub fn synonym_path(&self, base_dir: &Path) -> PathBuf {
+        base_dir.join(&self.synonym_file)
+    }
+}
+
+/// `path` relative to `base_dir` when inside it, otherwise absolute.
+fn relative_path(path: &Path, base_dir: &Path) -> Result<String, MddError> {
+    let path = path.canonicalize()?;
+    let base_dir = base_dir.canonicalize()?;
+    let path = path.strip_prefix(&base_dir).unwrap_or(&path);
+    Ok(path.to_string_lossy().to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::helper::checksum::sha256_hex;
 
     #[test]
     fn test_release_metadata() {
@@ -108,5 +228,56 @@ mod tests {
             Some("This is a sample release.".into())
         );
         assert_eq!(metadata.metadata.doi, Some("10.1234/mdd.2024.1".into()));
+        assert_eq!(
+            metadata.release_version().unwrap(),
+            ReleaseVersion::new(2024, 1, None)
+        );
+    }
+
+    #[test]
+    fn test_resolve_release_files() {
+        let metadata = ReleaseMetadata {
+            mdd_file: "MDD_v2.2.csv".into(),
+            synonym_file: "/data/Species_Syn_v2.2.csv".into(),
+            ..Default::default()
+        };
+        let base = Path::new("releases/2.2");
+        assert_eq!(
+            metadata.mdd_path(base),
+            PathBuf::from("releases/2.2/MDD_v2.2.csv")
+        );
+        assert_eq!(
+            metadata.synonym_path(base),
+            PathBuf::from("/data/Species_Syn_v2.2.csv")
+        );
+    }
+
+    #[test]
+    fn test_metadata_from_release_files() {
+        let dir = tempdir::TempDir::new("release_files").unwrap();
+        let mdd_path = dir.path().join("MDD_v2.2_6815species.csv");
+        let syn_path = dir.path().join("Species_Syn_v2.2.csv");
+        std::fs::write(&mdd_path, "hello").unwrap();
+        std::fs::write(&syn_path, "").unwrap();
+
+        let metadata =
+            ReleaseMetadata::from_release_files(&mdd_path, &syn_path, dir.path()).unwrap();
+        assert_eq!(metadata.version, "2.2");
+        assert_eq!(metadata.mdd_file, "MDD_v2.2_6815species.csv");
+        assert_eq!(
+            metadata.mdd_sha256.as_deref(),
+            Some("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824")
+        );
+        let toml = ReleaseToml { metadata }.to_toml();
+        let parsed = ReleaseToml::from_toml(&toml).unwrap();
+        assert_eq!(parsed.metadata.synonym_file, "Species_Syn_v2.2.csv");
+        assert!(parsed.verify(dir.path()).unwrap().is_empty());
+
+        // A swapped asset is reported with both digests.
+        std::fs::write(&syn_path, "tampered").unwrap();
+        let mismatches = parsed.verify(dir.path()).unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].path, syn_path);
+        assert_eq!(mismatches[0].actual, sha256_hex(b"tampered"));
     }
 }
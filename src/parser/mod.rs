@@ -4,22 +4,167 @@
 //! * `ReleasedMddData` – concise species records + attached synonyms + release metadata.
 //! * `AllMddData` – full raw `MddData` rows plus all synonym rows.
 //! * `MetaData` – aggregate counts (species, genera, families, orders, etc.).
+//! * `archive` – `ReleasedMddData::from_zip_reader` for parsing release zips in memory
+//!   (`archive` feature).
+//! * `detail::SpeciesDetail` – one species with its synonyms, parsed countries, type
+//!   locality coordinates, and slug (`ReleasedMddData::species_detail`).
+//! * `borrowed::MddDataRef` – zero-copy species rows streamed from a CSV through a callback.
+//! * `pipeline::Pipeline` – user species / synonym transforms applied before bundling.
+//! * `compact::MddDataCompact` – species records with interned (`Arc<str>`) repeated
+//!   columns (`compact` feature).
+//! * `reader::{MddCsvReader, SynonymCsvReader}` – streaming record iterators over any `Read`.
+//! * `synonym_index::SynonymIndex` – synonyms grouped per species, with orphan detection.
+//! * `iucn::IucnStats` – species counts per IUCN category, overall and per order, family, and realm.
+//! * `taxonomy::TaxonomyTree` – nested subclass → species hierarchy with counts.
+//! * `xlsx::MddWorkbook` – species and synonym sheets of an Excel / ODS workbook
+//!   (`xlsx` feature).
 //!
 //! It also provides helpers to construct these from parser outputs or from
 //! serialized JSON / gzipped JSON for distribution.
 
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::{
+    helper::slug::{assign_species_slugs, assign_synonym_slugs, species_slug, SlugAssigner},
+    nomenclature::combination::link_basionyms,
+    writer::{compression::Compression, json::JsonStyle},
+};
+use error::MddError;
 use flate2::bufread::GzDecoder;
 use mdd::MddData;
-use serde::{Deserialize, Serialize};
-use synonyms::SynonymData;
-
+use options::{CsvDialect, ParseOptions, ParseReport, SkippedRow};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use synonyms::{Subspecies, SynonymData};
+
+pub mod archive;
+pub mod borrowed;
+#[cfg(feature = "compact")]
+pub mod compact;
 pub mod country;
+pub mod detail;
+pub mod error;
+pub mod iucn;
 pub mod mdd;
 pub mod metadata;
+pub mod options;
+pub mod pipeline;
+pub mod reader;
+pub mod schema;
+pub mod synonym_index;
 pub mod synonyms;
+pub mod taxonomy;
+#[cfg(feature = "typed")]
+pub mod typed;
+pub mod version;
+#[cfg(feature = "xlsx")]
+pub mod xlsx;
+
+/// Deserialize every record of a CSV byte slice, stopping at the first bad row.
+///
+/// Errors carry the row number and, when available, the offending column name.
+pub(crate) fn read_csv_records<T: DeserializeOwned>(data: &[u8]) -> Result<Vec<T>, MddError> {
+    read_csv_records_with(data, &ParseOptions::default()).map(|(records, _)| records)
+}
+
+/// Deserialize CSV records according to `options`.
+///
+/// In strict mode the first bad row is returned as an error. In lenient mode
+/// bad rows are skipped and described in the returned `ParseReport`.
+pub(crate) fn read_csv_records_with<T: DeserializeOwned>(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Result<(Vec<T>, ParseReport), MddError> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .flexible(!options.strict)
+        .from_reader(data);
+    let headers = rdr
+        .headers()
+        .map_err(|e| MddError::from_csv(e, None))?
+        .clone();
+    read_records(rdr, &headers, &[], options)
+}
+
+/// Deserialize species CSV records according to `options`, reading the
+/// header row of older releases through their `MddSchema`.
+pub(crate) fn read_species_records_with(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Result<(Vec<MddData>, ParseReport), MddError> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .flexible(!options.strict)
+        .from_reader(data);
+    let raw_headers = rdr.headers().map_err(|e| MddError::from_csv(e, None))?;
+    let species_headers = reader::SpeciesHeaders::new(raw_headers, &CsvDialect::new(), None)?;
+    read_records(
+        rdr,
+        &species_headers.headers,
+        &species_headers.padding,
+        options,
+    )
+}
+
+/// Deserialize the rows of `rdr` with `headers`, appending `padding` to each
+/// row for the columns the input lacks.
+fn read_records<T: DeserializeOwned>(
+    mut rdr: csv::Reader<&[u8]>,
+    headers: &csv::StringRecord,
+    padding: &[&str],
+    options: &ParseOptions,
+) -> Result<(Vec<T>, ParseReport), MddError> {
+    let mut records = Vec::new();
+    let mut report = ParseReport::default();
+    for result in rdr.records() {
+        report.total_rows += 1;
+        let (err, raw) = match result {
+            Ok(mut record) => {
+                let len = record.len();
+                padding.iter().for_each(|value| record.push_field(value));
+                match record.deserialize::<T>(Some(headers)) {
+                    Ok(parsed) => {
+                        report.parsed_rows += 1;
+                        records.push(parsed);
+                        continue;
+                    }
+                    Err(e) => {
+                        record.truncate(len);
+                        (MddError::from_csv(e, Some(headers)), raw_record(&record))
+                    }
+                }
+            }
+            Err(e) => (MddError::from_csv(e, Some(headers)), String::new()),
+        };
+        if options.strict {
+            return Err(err);
+        }
+        if let MddError::Csv { row, column, cause } = err {
+            report.skipped.push(SkippedRow {
+                row,
+                field: column,
+                cause,
+                raw,
+            });
+        }
+    }
+    Ok((records, report))
+}
+
+/// Re-serialize a record as a single CSV line for error reporting.
+fn raw_record(record: &csv::StringRecord) -> String {
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+    if wtr.write_record(record).is_err() {
+        return record.iter().collect::<Vec<_>>().join(",");
+    }
+    let bytes = wtr.into_inner().unwrap_or_default();
+    String::from_utf8_lossy(&bytes).trim_end().to_string()
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ReleasedMddData {
     pub metadata: MetaData,
     pub data: Vec<SimpleMDD>,
@@ -36,26 +181,44 @@ impl ReleasedMddData {
     }
 
     pub fn from_gz_bytes(bytes: &[u8]) -> Self {
+        Self::try_from_gz_bytes(bytes).expect("Failed to deserialize")
+    }
+
+    /// Fallible variant of `from_gz_bytes`.
+    pub fn try_from_gz_bytes(bytes: &[u8]) -> Result<Self, MddError> {
         let data = GzDecoder::new(bytes);
-        serde_json::from_reader(data).expect("Failed to deserialize")
+        Ok(serde_json::from_reader(data)?)
     }
 
     pub fn from_json(json_data: &str) -> Self {
-        serde_json::from_str(json_data).expect("Failed to deserialize")
+        Self::try_from_json(json_data).expect("Failed to deserialize")
+    }
+
+    /// Fallible variant of `from_json`.
+    pub fn try_from_json(json_data: &str) -> Result<Self, MddError> {
+        Ok(serde_json::from_str(json_data)?)
     }
 
+    /// Bundle parsed records into a release, assigning fresh slugs and
+    /// linking species to their basionym synonyms.
+    ///
+    /// Call `assign_slugs` with the previous release afterwards to keep
+    /// published slugs stable.
     pub fn from_parser(
-        mdd_data: Vec<MddData>,
-        synonym_data: Vec<SynonymData>,
+        mut mdd_data: Vec<MddData>,
+        mut synonym_data: Vec<SynonymData>,
         version: &str,
         release_date: &str,
     ) -> Self {
+        assign_species_slugs(&mut mdd_data, &mut SlugAssigner::new());
+        assign_synonym_slugs(&mut synonym_data, &mut SlugAssigner::new());
+        link_basionyms(&mut mdd_data, &synonym_data);
         let mut simple_mdd = Vec::new();
         // Get the synonyms that have no species id
         let synonym_only = synonym_data
             .iter()
             .filter(|s| s.species_id.is_none())
-            .map(|s| s.clone())
+            .cloned()
             .collect();
 
         // iter over the mdd data and get all the synonyms that match the species id
@@ -64,7 +227,7 @@ impl ReleasedMddData {
             let synonyms: Vec<SynonymData> = synonym_data
                 .iter()
                 .filter(|s| s.species_id == Some(mdd.id))
-                .map(|s| s.clone())
+                .cloned()
                 .collect();
             simple_mdd.push(SimpleMDD::new(mdd, synonyms));
         }
@@ -76,10 +239,97 @@ impl ReleasedMddData {
         }
     }
 
+    /// Re-assign species and synonym slugs, reusing the slugs `previous`
+    /// published for records whose id still exists.
+    pub fn assign_slugs(&mut self, previous: Option<&ReleasedMddData>) {
+        let species_ids: HashSet<u32> = self.data.iter().map(|d| d.mdd_id).collect();
+        let synonym_ids: HashSet<u32> = self.all_synonyms().map(|s| s.syn_id).collect();
+        let (mut species_slugs, mut synonym_slugs) = match previous {
+            Some(previous) => (
+                SlugAssigner::with_previous(
+                    previous
+                        .data
+                        .iter()
+                        .filter(|d| species_ids.contains(&d.mdd_id))
+                        .map(|d| (d.mdd_id, d.species_data.slug.clone())),
+                ),
+                SlugAssigner::with_previous(
+                    previous
+                        .all_synonyms()
+                        .filter(|s| synonym_ids.contains(&s.syn_id))
+                        .map(|s| (s.syn_id, s.slug.clone())),
+                ),
+            ),
+            None => (SlugAssigner::new(), SlugAssigner::new()),
+        };
+        for entry in &mut self.data {
+            let species = &mut entry.species_data;
+            species.slug = species_slugs.assign(species.id, &species_slug(species));
+            assign_synonym_slugs(&mut entry.synonyms, &mut synonym_slugs);
+        }
+        assign_synonym_slugs(&mut self.synonym_only, &mut synonym_slugs);
+    }
+
+    /// Synonyms attached to species followed by the synonym-only names.
+    pub(crate) fn all_synonyms(&self) -> impl Iterator<Item = &SynonymData> {
+        self.data
+            .iter()
+            .flat_map(|d| d.synonyms.iter())
+            .chain(self.synonym_only.iter())
+    }
+
+    /// Full species records of the release, in release order.
+    pub fn species(&self) -> impl Iterator<Item = &MddData> {
+        self.data.iter().map(|d| d.species())
+    }
+
     pub fn to_json(&self) -> String {
         serde_json::to_string(&self).expect("Failed to serialize")
     }
 
+    /// Stream the bundle as compact JSON into `writer`, without building the
+    /// string first. Wrap unbuffered sinks such as files in a `BufWriter`.
+    pub fn write_json<W: Write>(&self, writer: W) -> Result<(), MddError> {
+        Ok(serde_json::to_writer(writer, self)?)
+    }
+
+    /// Stream the bundle as JSON to `path` through `compression`, so that
+    /// neither the JSON text nor the compressed output is held in memory.
+    pub fn write_json_file(&self, path: &Path, compression: Compression) -> Result<(), MddError> {
+        compression.create_file_with(path, |writer| {
+            serde_json::to_writer(writer, self).map_err(io::Error::from)
+        })?;
+        Ok(())
+    }
+
+    /// Pretty-printed JSON with the keys of every object sorted, so release
+    /// files diff cleanly between versions (see `writer::json::JsonStyle`).
+    pub fn to_json_pretty(&self) -> String {
+        JsonStyle::stable()
+            .to_string(self)
+            .expect("Failed to serialize")
+    }
+
+    /// Slim bundle for list views: the metadata plus one object per species
+    /// holding only `fields` of its record (see `MddData::project`).
+    /// Synonyms are left out.
+    pub fn to_json_with_fields(&self, fields: &[&str]) -> Result<String, MddError> {
+        Ok(serde_json::to_string(&self.project(fields)?)?)
+    }
+
+    /// The `to_json_with_fields` bundle as a JSON value.
+    pub fn project(&self, fields: &[&str]) -> Result<serde_json::Value, MddError> {
+        let data = self
+            .data
+            .iter()
+            .map(|entry| entry.species_data.project(fields))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(serde_json::to_value(ProjectedRelease {
+            metadata: &self.metadata,
+            data,
+        })?)
+    }
+
     pub fn get_data(&self) -> (Vec<String>, Vec<String>) {
         let mdd = self.data.iter().map(|d| d.to_json()).collect();
         let synonyms = self.synonym_only.iter().map(|s| s.to_json()).collect();
@@ -93,25 +343,83 @@ impl ReleasedMddData {
     pub fn get_release_date(&self) -> &str {
         &self.metadata.release_date
     }
+
+    pub fn get_doi(&self) -> Option<&str> {
+        self.metadata.doi.as_deref()
+    }
+
+    /// Record the release DOI in the bundle metadata.
+    pub fn set_doi(&mut self, doi: &str) {
+        self.metadata.doi = Some(doi.to_string());
+    }
+
+    /// Subspecies-rank names of the species with `species_id`; empty when the
+    /// species is not in the release.
+    pub fn subspecies_of(&self, species_id: u32) -> &[Subspecies] {
+        self.data
+            .iter()
+            .find(|entry| entry.mdd_id == species_id)
+            .map(SimpleMDD::subspecies)
+            .unwrap_or_default()
+    }
+}
+
+impl Default for ReleasedMddData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `ReleasedMddData::to_json_with_fields` output.
+#[derive(Serialize)]
+struct ProjectedRelease<'a> {
+    metadata: &'a MetaData,
+    data: Vec<serde_json::Map<String, serde_json::Value>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SimpleMDD {
     mdd_id: u32,
     species_data: MddData,
     synonyms: Vec<SynonymData>,
+    /// Subspecies-rank names among `synonyms`; missing in bundles written
+    /// before the list was added.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    subspecies: Vec<Subspecies>,
 }
 
 impl SimpleMDD {
-    fn new(species: MddData, synonyms: Vec<SynonymData>) -> Self {
+    pub(crate) fn new(species: MddData, synonyms: Vec<SynonymData>) -> Self {
         Self {
             mdd_id: species.id,
             species_data: species,
+            subspecies: Subspecies::from_synonyms(&synonyms),
             synonyms,
         }
     }
 
+    /// MDD species id of this entry.
+    pub fn mdd_id(&self) -> u32 {
+        self.mdd_id
+    }
+
+    /// The species record.
+    pub fn species(&self) -> &MddData {
+        &self.species_data
+    }
+
+    /// Synonyms attached to the species.
+    pub fn synonyms(&self) -> &[SynonymData] {
+        &self.synonyms
+    }
+
+    /// Subspecies-rank names of the species, in synonym order.
+    pub fn subspecies(&self) -> &[Subspecies] {
+        &self.subspecies
+    }
+
     fn to_json(&self) -> String {
         serde_json::to_string(&self).expect("Failed to serialize")
     }
@@ -119,6 +427,8 @@ impl SimpleMDD {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 pub struct MetaData {
     version: String,
     release_date: String,
@@ -131,6 +441,9 @@ pub struct MetaData {
     genus_count: u32,
     family_count: u32,
     order_count: u32,
+    /// Release DOI, when provided by the release TOML.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    doi: Option<String>,
 }
 
 impl MetaData {
@@ -147,6 +460,7 @@ impl MetaData {
             genus_count: 0,
             family_count: 0,
             order_count: 0,
+            doi: None,
         }
     }
 
@@ -190,8 +504,13 @@ impl MetaData {
             genus_count,
             family_count,
             order_count,
+            doi: None,
         }
     }
+
+    pub fn get_release_date(&self) -> &str {
+        &self.release_date
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -214,13 +533,23 @@ impl AllMddData {
     }
 
     pub fn from_json(json_data: &str) -> Self {
-        serde_json::from_str(json_data).expect("Failed to deserialize")
+        Self::try_from_json(json_data).expect("Failed to deserialize")
+    }
+
+    /// Fallible variant of `from_json`.
+    pub fn try_from_json(json_data: &str) -> Result<Self, MddError> {
+        Ok(serde_json::from_str(json_data)?)
     }
 
     /// Create a new AllMddData object from a Gzipped byte array.
     pub fn from_gz_bytes(bytes: &[u8]) -> Self {
+        Self::try_from_gz_bytes(bytes).expect("Failed to deserialize")
+    }
+
+    /// Fallible variant of `from_gz_bytes`.
+    pub fn try_from_gz_bytes(bytes: &[u8]) -> Result<Self, MddError> {
         let data = GzDecoder::new(bytes);
-        serde_json::from_reader(data).expect("Failed to deserialize")
+        Ok(serde_json::from_reader(data)?)
     }
 
     pub fn from_parser(mdd_data: Vec<MddData>, synonym_data: Vec<SynonymData>) -> Self {
@@ -254,6 +583,11 @@ impl AllMddData {
         (mdd, synonyms)
     }
 
+    /// The species records.
+    pub fn get_species(&self) -> &[MddData] {
+        &self.data
+    }
+
     pub fn get_mdd_data(&self) -> Vec<String> {
         self.data.iter().map(|d| d.to_json()).collect()
     }
@@ -266,3 +600,9 @@ impl AllMddData {
         &self.release_date
     }
 }
+
+impl Default for AllMddData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
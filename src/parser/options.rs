@@ -0,0 +1,199 @@
+//! Parsing options and row-level reports for the CSV parsers.
+//!
+//! `ParseOptions` is accepted by `MddData::from_csv_with` and
+//! `SynonymData::from_csv_with`. In strict mode (the default) the first bad
+//! row aborts parsing with an `MddError`. In lenient mode unparseable rows are
+//! skipped and recorded in a `ParseReport`, which lets pipelines ingest
+//! partially broken, community-edited CSVs and review the residue later.
+//!
+//! `CsvDialect` describes derivative files that are not plain comma-separated
+//! CSV (TSV exports, `;` separators, a UTF-8 byte order mark). It is part of
+//! `ParseOptions` and is accepted by the streaming readers and `MddCsvWriter`.
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use super::error::MddError;
+
+/// UTF-8 byte order mark written by some spreadsheet exports.
+const UTF8_BOM: char = '\u{feff}';
+
+/// Options controlling how CSV input is parsed.
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Abort on the first malformed row (`true`) or skip and report it (`false`).
+    pub strict: bool,
+    /// Delimiter, quoting, and trimming of the input.
+    pub dialect: CsvDialect,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            strict: true,
+            dialect: CsvDialect::default(),
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Options that skip malformed rows instead of failing.
+    pub fn lenient() -> Self {
+        Self {
+            strict: false,
+            ..Self::default()
+        }
+    }
+
+    /// Use `dialect` for the input.
+    pub fn with_dialect(mut self, dialect: CsvDialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+}
+
+/// Delimiter, quoting, and cleanup of a CSV file.
+///
+/// The default is the release format: comma-separated, `"` quotes, fields
+/// kept verbatim, and a leading byte order mark dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvDialect {
+    /// Field separator, e.g. `b','` or `b'\t'`.
+    pub delimiter: u8,
+    /// Quote character.
+    pub quote: u8,
+    /// Trim leading and trailing whitespace of headers and fields.
+    pub trim: bool,
+    /// Drop a UTF-8 byte order mark before the header row.
+    pub strip_bom: bool,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvDialect {
+    pub fn new() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            trim: false,
+            strip_bom: true,
+        }
+    }
+
+    /// Tab-separated values.
+    pub fn tsv() -> Self {
+        Self::new().with_delimiter(b'\t')
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn with_quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    pub fn with_trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    pub fn with_strip_bom(mut self, strip_bom: bool) -> Self {
+        self.strip_bom = strip_bom;
+        self
+    }
+
+    /// `csv` reader settings for this dialect.
+    pub fn reader_builder(&self) -> csv::ReaderBuilder {
+        let mut builder = csv::ReaderBuilder::new();
+        builder.delimiter(self.delimiter).quote(self.quote);
+        if self.trim {
+            builder.trim(csv::Trim::All);
+        }
+        builder
+    }
+
+    /// `csv` writer settings for this dialect.
+    pub fn writer_builder(&self) -> csv::WriterBuilder {
+        let mut builder = csv::WriterBuilder::new();
+        builder.delimiter(self.delimiter).quote(self.quote);
+        builder
+    }
+
+    /// Drop a leading byte order mark from a header name, if enabled.
+    pub(crate) fn clean_header<'a>(&self, header: &'a str) -> &'a str {
+        if self.strip_bom {
+            header.strip_prefix(UTF8_BOM).unwrap_or(header)
+        } else {
+            header
+        }
+    }
+
+    /// Rewrite `csv_data` as comma-separated, `"`-quoted CSV, the layout the
+    /// string parsers expect. Input already in that layout is only stripped
+    /// of its byte order mark and is not copied.
+    pub fn to_standard<'a>(&self, csv_data: &'a str) -> Result<Cow<'a, str>, MddError> {
+        let csv_data = self.clean_header(csv_data);
+        let standard = Self::new();
+        if self.delimiter == standard.delimiter && self.quote == standard.quote && !self.trim {
+            return Ok(Cow::Borrowed(csv_data));
+        }
+        let mut rdr = self
+            .reader_builder()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(csv_data.as_bytes());
+        let mut wtr = csv::WriterBuilder::new()
+            .flexible(true)
+            .from_writer(Vec::new());
+        for record in rdr.records() {
+            let record = record.map_err(|e| MddError::from_csv(e, None))?;
+            wtr.write_record(&record)
+                .map_err(|e| MddError::from_csv(e, None))?;
+        }
+        let bytes = wtr.into_inner().map_err(|e| MddError::Io(e.into_error()))?;
+        Ok(Cow::Owned(
+            String::from_utf8(bytes).expect("CSV records are valid UTF-8"),
+        ))
+    }
+}
+
+/// A row that was skipped in lenient mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedRow {
+    /// 1-based line number of the record (the header is line 1), when known.
+    pub row: Option<u64>,
+    /// Header name of the field that failed to deserialize, when known.
+    pub field: Option<String>,
+    /// Description of the failure.
+    pub cause: String,
+    /// The raw record text, re-serialized as a CSV line.
+    pub raw: String,
+}
+
+/// Summary of a parsing run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseReport {
+    /// Number of data rows encountered (excluding the header).
+    pub total_rows: usize,
+    /// Number of rows successfully parsed.
+    pub parsed_rows: usize,
+    /// Rows that were skipped, in input order.
+    pub skipped: Vec<SkippedRow>,
+}
+
+impl ParseReport {
+    /// Returns `true` when no rows were skipped.
+    pub fn is_clean(&self) -> bool {
+        self.skipped.is_empty()
+    }
+}
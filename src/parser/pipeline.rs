@@ -0,0 +1,172 @@
+//! Record transforms applied between parsing and bundling.
+//!
+//! A `Pipeline` holds user transforms for species (`Fn(&mut MddData)`) and
+//! synonym (`Fn(&mut SynonymData)`) records. They run in registration
+//! order on every record after the CSVs are parsed and before
+//! `ReleasedMddData::from_parser` assigns slugs and links basionyms, so
+//! corrections are reflected in slugs, metadata counts, and every writer.
+//!
+//! ```rust, ignore
+//! use mdd_api::parser::pipeline::Pipeline;
+//!
+//! let pipeline = Pipeline::new()
+//!     .with_trimmed_whitespace()
+//!     .with_species_transform(|species| {
+//!         if species.iucn_status == "NE" {
+//!             species.iucn_status = "DD".to_string();
+//!         }
+//!     });
+//! let release = pipeline.parse_csv(&species_csv, &synonym_csv, "2.2", "2025-06-01")?;
+//! ```
+
+use std::fmt;
+
+use super::{error::MddError, mdd::MddData, synonyms::SynonymData, ReleasedMddData};
+
+/// A transform of one species record.
+pub type SpeciesTransform = Box<dyn Fn(&mut MddData) + Send + Sync>;
+/// A transform of one synonym record.
+pub type SynonymTransform = Box<dyn Fn(&mut SynonymData) + Send + Sync>;
+
+/// Ordered species and synonym transforms.
+#[derive(Default)]
+pub struct Pipeline {
+    species: Vec<SpeciesTransform>,
+    synonyms: Vec<SynonymTransform>,
+}
+
+impl fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("species", &self.species.len())
+            .field("synonyms", &self.synonyms.len())
+            .finish()
+    }
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a transform run on every species record.
+    pub fn with_species_transform(
+        mut self,
+        transform: impl Fn(&mut MddData) + Send + Sync + 'static,
+    ) -> Self {
+        self.species.push(Box::new(transform));
+        self
+    }
+
+    /// Add a transform run on every synonym record.
+    pub fn with_synonym_transform(
+        mut self,
+        transform: impl Fn(&mut SynonymData) + Send + Sync + 'static,
+    ) -> Self {
+        self.synonyms.push(Box::new(transform));
+        self
+    }
+
+    /// Add `trim_species` and `trim_synonym`.
+    pub fn with_trimmed_whitespace(self) -> Self {
+        self.with_species_transform(trim_species)
+            .with_synonym_transform(trim_synonym)
+    }
+
+    /// Whether no transform is registered.
+    pub fn is_empty(&self) -> bool {
+        self.species.is_empty() && self.synonyms.is_empty()
+    }
+
+    /// Run the species transforms on `species`.
+    pub fn transform_species(&self, species: &mut MddData) {
+        for transform in &self.species {
+            transform(species);
+        }
+    }
+
+    /// Run the synonym transforms on `synonym`.
+    pub fn transform_synonym(&self, synonym: &mut SynonymData) {
+        for transform in &self.synonyms {
+            transform(synonym);
+        }
+    }
+
+    /// Run the transforms on every record.
+    pub fn apply(&self, species: &mut [MddData], synonyms: &mut [SynonymData]) {
+        species.iter_mut().for_each(|s| self.transform_species(s));
+        synonyms.iter_mut().for_each(|s| self.transform_synonym(s));
+    }
+
+    /// Transform the records, then bundle them with `ReleasedMddData::from_parser`.
+    pub fn bundle(
+        &self,
+        mut species: Vec<MddData>,
+        mut synonyms: Vec<SynonymData>,
+        version: &str,
+        release_date: &str,
+    ) -> ReleasedMddData {
+        self.apply(&mut species, &mut synonyms);
+        ReleasedMddData::from_parser(species, synonyms, version, release_date)
+    }
+
+    /// Parse a species and a synonym CSV, transform, and bundle them.
+    pub fn parse_csv(
+        &self,
+        species_csv: &str,
+        synonym_csv: &str,
+        version: &str,
+        release_date: &str,
+    ) -> Result<ReleasedMddData, MddError> {
+        let species = MddData::new().try_from_csv(species_csv)?;
+        let synonyms = SynonymData::new().try_from_csv(synonym_csv)?;
+        Ok(self.bundle(species, synonyms, version, release_date))
+    }
+}
+
+/// Trim leading and trailing whitespace of every text column of a species.
+pub fn trim_species(species: &mut MddData) {
+    species.for_each_text_field(trim_in_place);
+}
+
+/// Trim leading and trailing whitespace of every text column of a synonym.
+pub fn trim_synonym(synonym: &mut SynonymData) {
+    synonym.for_each_text_field(trim_in_place);
+}
+
+fn trim_in_place(field: &mut String) {
+    let trimmed = field.trim();
+    if trimmed.len() != field.len() {
+        *field = trimmed.to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline() {
+        let species_csv = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let synonym_csv = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let pipeline = Pipeline::new()
+            .with_species_transform(|species| species.main_common_name.push(' '))
+            .with_trimmed_whitespace()
+            .with_species_transform(|species| {
+                species.main_common_name = species.main_common_name.to_uppercase()
+            });
+        assert!(!pipeline.is_empty());
+        let release = pipeline
+            .parse_csv(&species_csv, &synonym_csv, "2.2", "2025-06-01")
+            .unwrap();
+        let species = release.data[0].species();
+        assert_eq!(species.main_common_name, "RIVERINE RABBIT");
+
+        let mut synonym = SynonymData::new();
+        synonym.root_name = " leo\t".to_string();
+        Pipeline::new()
+            .with_trimmed_whitespace()
+            .transform_synonym(&mut synonym);
+        assert_eq!(synonym.root_name(), "leo");
+    }
+}
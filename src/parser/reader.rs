@@ -0,0 +1,279 @@
+//! Streaming CSV readers.
+//!
+//! `MddCsvReader` and `SynonymCsvReader` wrap any `std::io::Read` (a file, a
+//! zip entry, a gzip decoder, ...) and yield one parsed record at a time, so
+//! callers never hold the raw CSV text in memory. The header row is checked
+//! against the known layout when the reader is created. `with_dialect` reads
+//! TSV and other `CsvDialect`s; species CSVs of older releases are mapped to
+//! the current layout through their `MddSchema`.
+
+use std::{io::Read, marker::PhantomData};
+
+use serde::de::DeserializeOwned;
+
+use super::{
+    error::MddError,
+    mdd::MddData,
+    options::CsvDialect,
+    schema::{diff_headers, normalize_synonym_column, MddSchema, SYNONYM_COLUMNS},
+    synonyms::SynonymData,
+};
+
+/// Iterator over deserialized records of a CSV stream.
+struct CsvRecords<R, T> {
+    records: csv::StringRecordsIntoIter<R>,
+    headers: csv::StringRecord,
+    /// Values appended to each record for columns missing from the input
+    /// (see `MddSchema::missing_columns`).
+    padding: Vec<&'static str>,
+    _record: PhantomData<T>,
+}
+
+impl<R: Read, T: DeserializeOwned> CsvRecords<R, T> {
+    /// Read the header row, validate it, and optionally rename the columns
+    /// the way the parser expects them.
+    fn new<F>(
+        reader: R,
+        dialect: &CsvDialect,
+        expected: &[&str],
+        normalize: F,
+    ) -> Result<Self, MddError>
+    where
+        F: Fn(&str) -> String,
+    {
+        let mut rdr = dialect.reader_builder().from_reader(reader);
+        let headers = checked_headers(&mut rdr, dialect, expected, normalize)?;
+        Ok(Self {
+            records: rdr.into_records(),
+            headers,
+            padding: Vec::new(),
+            _record: PhantomData,
+        })
+    }
+
+    fn next_record(&mut self) -> Option<Result<T, MddError>> {
+        let mut record = match self.records.next()? {
+            Ok(record) => record,
+            Err(e) => return Some(Err(MddError::from_csv(e, Some(&self.headers)))),
+        };
+        for value in &self.padding {
+            record.push_field(value);
+        }
+        Some(
+            record
+                .deserialize(Some(&self.headers))
+                .map_err(|e| MddError::from_csv(e, Some(&self.headers))),
+        )
+    }
+}
+
+/// Read the header row of `rdr`, validate it against `expected`, and return
+/// the headers renamed with `normalize`.
+fn checked_headers<R, F>(
+    rdr: &mut csv::Reader<R>,
+    dialect: &CsvDialect,
+    expected: &[&str],
+    normalize: F,
+) -> Result<csv::StringRecord, MddError>
+where
+    R: Read,
+    F: Fn(&str) -> String,
+{
+    let raw_headers = rdr.headers().map_err(|e| MddError::from_csv(e, None))?;
+    let actual: Vec<&str> = raw_headers
+        .iter()
+        .map(|header| dialect.clean_header(header))
+        .collect();
+    let diff = diff_headers(expected, &actual, &normalize);
+    if !diff.is_compatible() {
+        return Err(MddError::Schema(diff));
+    }
+    Ok(actual.iter().map(|h| normalize(h)).collect())
+}
+
+/// Header row of a species CSV renamed to the current layout.
+pub(super) struct SpeciesHeaders {
+    pub(super) schema: MddSchema,
+    pub(super) headers: csv::StringRecord,
+    /// Values appended to each record for the columns the schema lacks.
+    pub(super) padding: Vec<&'static str>,
+}
+
+impl SpeciesHeaders {
+    /// Map `raw_headers` of `schema`, or of the schema detected from them
+    /// when `None`, failing with `MddError::Schema` if required columns are
+    /// missing.
+    pub(super) fn new(
+        raw_headers: &csv::StringRecord,
+        dialect: &CsvDialect,
+        schema: Option<MddSchema>,
+    ) -> Result<Self, MddError> {
+        let actual: Vec<&str> = raw_headers
+            .iter()
+            .map(|header| dialect.clean_header(header))
+            .collect();
+        let schema = schema
+            .or_else(|| MddSchema::detect(&actual))
+            .unwrap_or(MddSchema::V2);
+        let diff = schema.header_diff(&actual);
+        if !diff.is_compatible() {
+            return Err(MddError::Schema(diff));
+        }
+        let headers: csv::StringRecord = schema.map_headers(&actual).into_iter().collect();
+        let padding = headers
+            .iter()
+            .skip(actual.len())
+            .map(|column| schema.default_value(column))
+            .collect();
+        Ok(Self {
+            schema,
+            headers,
+            padding,
+        })
+    }
+}
+
+/// Streaming reader over an MDD species CSV.
+///
+/// The `MddSchema` is detected from the header row, so v1.x releases are
+/// read into the current layout as well.
+pub struct MddCsvReader<R> {
+    inner: CsvRecords<R, MddData>,
+    schema: MddSchema,
+}
+
+impl<R: Read> MddCsvReader<R> {
+    /// Wrap `reader`, failing with `MddError::Schema` if required columns are missing.
+    pub fn new(reader: R) -> Result<Self, MddError> {
+        Self::with_dialect(reader, &CsvDialect::default())
+    }
+
+    /// Wrap `reader` holding CSV in `dialect`.
+    pub fn with_dialect(reader: R, dialect: &CsvDialect) -> Result<Self, MddError> {
+        Self::with_schema(reader, dialect, None)
+    }
+
+    /// Wrap `reader` holding a species CSV of `schema`, or of the schema
+    /// detected from its header row when `None`.
+    pub fn with_schema(
+        reader: R,
+        dialect: &CsvDialect,
+        schema: Option<MddSchema>,
+    ) -> Result<Self, MddError> {
+        let mut rdr = dialect.reader_builder().from_reader(reader);
+        let raw_headers = rdr.headers().map_err(|e| MddError::from_csv(e, None))?;
+        let SpeciesHeaders {
+            schema,
+            headers,
+            padding,
+        } = SpeciesHeaders::new(raw_headers, dialect, schema)?;
+        let inner = CsvRecords {
+            records: rdr.into_records(),
+            headers,
+            padding,
+            _record: PhantomData,
+        };
+        Ok(Self { inner, schema })
+    }
+
+    /// Layout of the CSV being read.
+    pub fn schema(&self) -> MddSchema {
+        self.schema
+    }
+}
+
+impl<R: Read> Iterator for MddCsvReader<R> {
+    type Item = Result<MddData, MddError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_record()
+    }
+}
+
+/// Streaming reader over an MDD synonym CSV.
+///
+/// `MDD_` prefixes are stripped from the headers as in `SynonymData::from_csv`.
+pub struct SynonymCsvReader<R> {
+    inner: CsvRecords<R, SynonymData>,
+}
+
+impl<R: Read> SynonymCsvReader<R> {
+    /// Wrap `reader`, failing with `MddError::Schema` if required columns are missing.
+    pub fn new(reader: R) -> Result<Self, MddError> {
+        Self::with_dialect(reader, &CsvDialect::default())
+    }
+
+    /// Wrap `reader` holding CSV in `dialect`.
+    pub fn with_dialect(reader: R, dialect: &CsvDialect) -> Result<Self, MddError> {
+        let inner = CsvRecords::new(reader, dialect, &SYNONYM_COLUMNS, normalize_synonym_column)?;
+        Ok(Self { inner })
+    }
+}
+
+impl<R: Read> Iterator for SynonymCsvReader<R> {
+    type Item = Result<SynonymData, MddError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_record()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_mdd_records() {
+        let file = std::fs::File::open("tests/data/test_data.csv").unwrap();
+        let reader = MddCsvReader::new(std::io::BufReader::new(file)).unwrap();
+        let records: Vec<MddData> = reader.collect::<Result<_, _>>().unwrap();
+        assert_eq!(records.len(), 112);
+        assert_eq!(records[0].id, 1001076);
+    }
+
+    #[test]
+    fn test_stream_v1_records() {
+        let file = std::fs::File::open("tests/data/v1_test_data.csv").unwrap();
+        let reader = MddCsvReader::new(file).unwrap();
+        assert_eq!(reader.schema(), MddSchema::V1);
+        let records: Vec<MddData> = reader.collect::<Result<_, _>>().unwrap();
+        assert_eq!(records.len(), 3);
+        let first = &records[0];
+        assert_eq!(first.sci_name, "Bunolagus_monticularis");
+        assert_eq!(first.taxon_order, "Lagomorpha");
+        assert_eq!(first.authority_species_year, 1903);
+        assert!(first.type_voucher.starts_with("BMNH"));
+        assert_eq!(first.iucn_status, "CR");
+        // Columns v1.0 lacks are read as defaults.
+        assert_eq!(first.id, 0);
+        assert_eq!(first.phylosort, 0);
+        assert_eq!(first.type_kind, "");
+        assert_eq!(first.subregion_distribution, "");
+    }
+
+    #[test]
+    fn test_stream_synonym_records() {
+        let file = std::fs::File::open("tests/data/syndata.csv").unwrap();
+        let mut reader = SynonymCsvReader::new(file).unwrap();
+        let first = reader.next().unwrap().unwrap();
+        assert_eq!(first.syn_id, 100022090);
+        assert_eq!(reader.count(), 4);
+    }
+
+    #[test]
+    fn test_stream_reports_row_errors() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let mut lines: Vec<String> = csv_data.lines().map(String::from).collect();
+        lines[2] = lines[2].replacen(",1001077,", ",not-a-number,", 1);
+        let data = lines.join("\n");
+        let results: Vec<_> = MddCsvReader::new(data.as_bytes()).unwrap().collect();
+        assert!(results[0].is_ok());
+        match &results[1] {
+            Err(MddError::Csv { row, column, .. }) => {
+                assert_eq!(*row, Some(3));
+                assert_eq!(column.as_deref(), Some("id"));
+            }
+            other => panic!("expected CSV error, got {:?}", other.as_ref().map(|d| d.id)),
+        }
+    }
+}
@@ -0,0 +1,424 @@
+//! Known MDD CSV layouts and header validation.
+//!
+//! Serde reports a missing or renamed column as an opaque "missing field"
+//! error on the first row. Comparing the header row against the known layout
+//! first gives users an actionable diff that points at the release-format
+//! change that broke parsing.
+
+use convert_case::Casing;
+use serde::{Deserialize, Serialize};
+
+/// Column headers of the MDD species CSV, in release order.
+pub const MDD_COLUMNS: [&str; 50] = [
+    "sciName",
+    "id",
+    "phylosort",
+    "mainCommonName",
+    "otherCommonNames",
+    "subclass",
+    "infraclass",
+    "magnorder",
+    "superorder",
+    "order",
+    "suborder",
+    "infraorder",
+    "parvorder",
+    "superfamily",
+    "family",
+    "subfamily",
+    "tribe",
+    "genus",
+    "subgenus",
+    "specificEpithet",
+    "authoritySpeciesAuthor",
+    "authoritySpeciesYear",
+    "authorityParentheses",
+    "originalNameCombination",
+    "authoritySpeciesCitation",
+    "authoritySpeciesLink",
+    "typeVoucher",
+    "typeKind",
+    "typeVoucherURIs",
+    "typeLocality",
+    "typeLocalityLatitude",
+    "typeLocalityLongitude",
+    "nominalNames",
+    "taxonomyNotes",
+    "taxonomyNotesCitation",
+    "distributionNotes",
+    "distributionNotesCitation",
+    "subregionDistribution",
+    "countryDistribution",
+    "continentDistribution",
+    "biogeographicRealm",
+    "iucnStatus",
+    "extinct",
+    "domestic",
+    "flagged",
+    "CMW_sciName",
+    "diffSinceCMW",
+    "MSW3_matchtype",
+    "MSW3_sciName",
+    "diffSinceMSW3",
+];
+
+/// Column headers of the MDD synonym CSV, in release order.
+pub const SYNONYM_COLUMNS: [&str; 43] = [
+    "MDD_syn_ID",
+    "MDD_species",
+    "MDD_root_name",
+    "MDD_author",
+    "MDD_year",
+    "MDD_authority_parentheses",
+    "MDD_nomenclature_status",
+    "MDD_validity",
+    "MDD_original_combination",
+    "MDD_original_rank",
+    "MDD_authority_citation",
+    "MDD_unchecked_authority_citation",
+    "MDD_sourced_unverified_citations",
+    "MDD_citation_group",
+    "MDD_citation_kind",
+    "MDD_authority_page",
+    "MDD_authority_link",
+    "MDD_authority_page_link",
+    "MDD_unchecked_authority_page_link",
+    "MDD_old_type_locality",
+    "MDD_original_type_locality",
+    "MDD_unchecked_type_locality",
+    "MDD_emended_type_locality",
+    "MDD_type_latitude",
+    "MDD_type_longitude",
+    "MDD_type_country",
+    "MDD_type_subregion",
+    "MDD_type_subregion2",
+    "MDD_holotype",
+    "MDD_type_kind",
+    "MDD_type_specimen_link",
+    "MDD_order",
+    "MDD_family",
+    "MDD_genus",
+    "MDD_specificEpithet",
+    "MDD_subspecificEpithet",
+    "MDD_variant_of",
+    "MDD_senior_homonym",
+    "MDD_variant_name_citations",
+    "Hesp_id",
+    "MDD_species_id",
+    "MDD_name_usages",
+    "MDD_comments",
+];
+
+/// V1 column names renamed in v2, as `(v1, v2)` pairs.
+const V1_RENAMED_COLUMNS: [(&str, &str); 2] = [
+    ("holotypeVoucher", "typeVoucher"),
+    ("holotypeVoucherURIs", "typeVoucherURIs"),
+];
+
+/// V2 columns that v1.x files may lack, with the value they are read as.
+/// v1.0 has no identifiers or ranks above order; `typeKind` and
+/// `subregionDistribution` were added later.
+const V1_MISSING_COLUMNS: [(&str, &str); 8] = [
+    ("id", "0"),
+    ("phylosort", "0"),
+    ("subclass", ""),
+    ("infraclass", ""),
+    ("magnorder", ""),
+    ("superorder", ""),
+    ("typeKind", ""),
+    ("subregionDistribution", ""),
+];
+
+/// Species CSV layout of a generation of MDD releases.
+///
+/// Records are always deserialized into the current (`V2`) layout: older
+/// headers are renamed through a per-version mapping and columns a version
+/// lacks are read as empty. `detect` picks the schema from a header row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MddSchema {
+    /// v1.x releases: `holotypeVoucher` / `holotypeVoucherURIs` instead of
+    /// `typeVoucher` / `typeVoucherURIs`, capitalized headers (`SciName`,
+    /// `IUCNStatus`), and, depending on the release, no `id`, `phylosort`,
+    /// ranks above order, `typeKind`, or `subregionDistribution`.
+    V1,
+    /// v2.x releases, `MDD_COLUMNS`.
+    V2,
+}
+
+impl MddSchema {
+    /// First schema, newest first, whose mapping of `headers` contains every
+    /// column of `MDD_COLUMNS`, counting the columns the schema may lack as
+    /// present.
+    pub fn detect(headers: &[&str]) -> Option<Self> {
+        [Self::V2, Self::V1]
+            .into_iter()
+            .find(|schema| schema.header_diff(headers).is_compatible())
+    }
+
+    /// `MDD_COLUMNS` spelling of the column `header` of this schema.
+    pub fn map_column(&self, header: &str) -> String {
+        let header = header.trim();
+        match self {
+            Self::V2 => header.to_string(),
+            Self::V1 => V1_RENAMED_COLUMNS
+                .iter()
+                .find(|(v1, _)| v1.eq_ignore_ascii_case(header))
+                .map(|(_, v2)| *v2)
+                .or_else(|| {
+                    MDD_COLUMNS
+                        .iter()
+                        .find(|column| column.eq_ignore_ascii_case(header))
+                        .copied()
+                })
+                .unwrap_or(header)
+                .to_string(),
+        }
+    }
+
+    /// `MDD_COLUMNS` this schema may lack, with the value they are read as.
+    pub fn missing_columns(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::V1 => &V1_MISSING_COLUMNS,
+            Self::V2 => &[],
+        }
+    }
+
+    /// `headers` renamed with `map_column`, followed by the missing columns
+    /// they do not already contain.
+    pub fn map_headers(&self, headers: &[&str]) -> Vec<String> {
+        let mut mapped: Vec<String> = headers.iter().map(|h| self.map_column(h)).collect();
+        for (column, _) in self.missing_columns() {
+            if !mapped.iter().any(|h| h == column) {
+                mapped.push(column.to_string());
+            }
+        }
+        mapped
+    }
+
+    /// Value a missing `column` is read as (see `missing_columns`).
+    pub(crate) fn default_value(&self, column: &str) -> &'static str {
+        self.missing_columns()
+            .iter()
+            .find(|(missing, _)| *missing == column)
+            .map_or("", |(_, value)| value)
+    }
+
+    /// Compare `headers`, mapped to the current layout, against `MDD_COLUMNS`.
+    pub fn header_diff(&self, headers: &[&str]) -> HeaderDiff {
+        let mapped = self.map_headers(headers);
+        let mapped: Vec<&str> = mapped.iter().map(String::as_str).collect();
+        diff_headers(&MDD_COLUMNS, &mapped, |c| c.to_string())
+    }
+}
+
+/// Difference between an observed header row and a known layout.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderDiff {
+    /// Expected columns absent from the input. Parsing cannot succeed while this is non-empty.
+    pub missing: Vec<String>,
+    /// Input columns not part of the known layout. These are ignored by the parser.
+    pub unexpected: Vec<String>,
+    /// Known columns present in the input but at a different relative position.
+    pub reordered: Vec<String>,
+}
+
+impl HeaderDiff {
+    /// Returns `true` when the header matches the known layout exactly.
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty() && self.reordered.is_empty()
+    }
+
+    /// Returns `true` when every required column is present.
+    pub fn is_compatible(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+impl std::fmt::Display for HeaderDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if !self.missing.is_empty() {
+            parts.push(format!("missing columns: {}", self.missing.join(", ")));
+        }
+        if !self.unexpected.is_empty() {
+            parts.push(format!(
+                "unexpected columns: {}",
+                self.unexpected.join(", ")
+            ));
+        }
+        if !self.reordered.is_empty() {
+            parts.push(format!("reordered columns: {}", self.reordered.join(", ")));
+        }
+        if parts.is_empty() {
+            write!(f, "header matches the known layout")
+        } else {
+            write!(f, "{}", parts.join("; "))
+        }
+    }
+}
+
+/// Release CSV told apart by its header row, see `detect_csv_kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CsvKind {
+    /// Species CSV (`MDD_COLUMNS`).
+    Species,
+    /// Synonym CSV (`SYNONYM_COLUMNS`).
+    Synonym,
+    /// Neither layout.
+    Unknown,
+}
+
+/// Classify CSV text, or just its header line, by the columns only one
+/// layout has: `sciName` and `specificEpithet` for species (v1.x files have
+/// no `phylosort`), `MDD_syn_ID` and
+/// `MDD_root_name` for synonyms (normalized like the synonym parser, so
+/// `syn_id` or `rootName` count too). Tab separated headers are recognized.
+pub fn detect_csv_kind(csv_data: &str) -> CsvKind {
+    let line = csv_data.lines().next().unwrap_or_default();
+    let line = line.trim_start_matches('\u{feff}');
+    let delimiter = if line.matches('\t').count() > line.matches(',').count() {
+        b'\t'
+    } else {
+        b','
+    };
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .delimiter(delimiter)
+        .from_reader(line.as_bytes());
+    let columns: Vec<String> = match rdr.records().next() {
+        Some(Ok(record)) => record.iter().map(|c| c.trim().to_string()).collect(),
+        _ => return CsvKind::Unknown,
+    };
+    // Case-insensitive, as v1.x headers are capitalized (`SciName`).
+    let has = |column: &str| columns.iter().any(|c| c.eq_ignore_ascii_case(column));
+    let synonym_columns: Vec<String> = columns
+        .iter()
+        .map(|c| normalize_synonym_column(c))
+        .collect();
+    let has_synonym = |column: &str| synonym_columns.iter().any(|c| c == column);
+    if has("sciName") && has("specificEpithet") {
+        CsvKind::Species
+    } else if has_synonym("synId") && has_synonym("rootName") {
+        CsvKind::Synonym
+    } else {
+        CsvKind::Unknown
+    }
+}
+
+/// Normalize a synonym header the same way the synonym parser does:
+/// strip the `MDD_` prefix and convert to camelCase.
+pub(crate) fn normalize_synonym_column(col: &str) -> String {
+    col.trim()
+        .trim_start_matches("MDD_")
+        .to_case(convert_case::Case::Camel)
+}
+
+/// Compare `actual` headers against `expected`, matching names through `normalize`.
+///
+/// Reported names use the spelling of the side they come from: missing
+/// columns use the expected spelling, unexpected and reordered columns use
+/// the input spelling.
+pub(crate) fn diff_headers<F>(expected: &[&str], actual: &[&str], normalize: F) -> HeaderDiff
+where
+    F: Fn(&str) -> String,
+{
+    let expected_norm: Vec<String> = expected.iter().map(|c| normalize(c)).collect();
+    let actual_norm: Vec<String> = actual.iter().map(|c| normalize(c)).collect();
+
+    let missing = expected
+        .iter()
+        .zip(&expected_norm)
+        .filter(|(_, n)| !actual_norm.contains(n))
+        .map(|(c, _)| c.to_string())
+        .collect();
+    let unexpected = actual
+        .iter()
+        .zip(&actual_norm)
+        .filter(|(_, n)| !expected_norm.contains(n))
+        .map(|(c, _)| c.trim().to_string())
+        .collect();
+
+    // Compare the relative order of the shared columns only, so a single
+    // inserted or removed column does not flag everything after it.
+    let shared_expected: Vec<&String> = expected_norm
+        .iter()
+        .filter(|n| actual_norm.contains(n))
+        .collect();
+    let reordered = actual
+        .iter()
+        .zip(&actual_norm)
+        .filter(|(_, n)| expected_norm.contains(n))
+        .enumerate()
+        .filter(|(i, (_, n))| shared_expected.get(*i) != Some(n))
+        .map(|(_, (c, _))| c.trim().to_string())
+        .collect();
+
+    HeaderDiff {
+        missing,
+        unexpected,
+        reordered,
+    }
+}
+
+/// Read the header row of `csv_data`.
+pub(crate) fn read_header_row(csv_data: &[u8]) -> Result<Vec<String>, csv::Error> {
+    let mut rdr = csv::Reader::from_reader(csv_data);
+    Ok(rdr.headers()?.iter().map(|h| h.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_headers() {
+        let expected = ["a", "b", "c", "d"];
+        let actual = ["a", "c", "b", "x"];
+        let diff = diff_headers(&expected, &actual, |c| c.to_string());
+        assert_eq!(diff.missing, vec!["d"]);
+        assert_eq!(diff.unexpected, vec!["x"]);
+        assert_eq!(diff.reordered, vec!["c", "b"]);
+        assert!(!diff.is_compatible());
+    }
+
+    #[test]
+    fn test_detect_csv_kind() {
+        let species = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        assert_eq!(detect_csv_kind(&species), CsvKind::Species);
+        let v1 = std::fs::read_to_string("tests/data/v1_test_data.csv").unwrap();
+        assert_eq!(detect_csv_kind(&v1), CsvKind::Species);
+        let synonyms = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        assert_eq!(detect_csv_kind(&synonyms), CsvKind::Synonym);
+        assert_eq!(
+            detect_csv_kind("\u{feff}syn_id\troot_name\tauthor\n"),
+            CsvKind::Synonym
+        );
+        assert_eq!(detect_csv_kind("sciName,id\n"), CsvKind::Unknown);
+        assert_eq!(detect_csv_kind(""), CsvKind::Unknown);
+    }
+
+    #[test]
+    fn test_detect_schema() {
+        assert_eq!(MddSchema::detect(&MDD_COLUMNS), Some(MddSchema::V2));
+        let v1 = std::fs::read_to_string("tests/data/v1_test_data.csv").unwrap();
+        let v1 = read_header_row(v1.as_bytes()).unwrap();
+        let v1: Vec<&str> = v1.iter().map(String::as_str).collect();
+        assert_eq!(MddSchema::detect(&v1), Some(MddSchema::V1));
+        assert!(MddSchema::V1.header_diff(&v1).is_compatible());
+        assert_eq!(MddSchema::V1.map_column("IUCNStatus"), "iucnStatus");
+        assert_eq!(MddSchema::V1.map_column("HolotypeVoucher"), "typeVoucher");
+        assert_eq!(MddSchema::V1.default_value("id"), "0");
+        assert_eq!(MddSchema::detect(&["sciName", "id"]), None);
+    }
+
+    #[test]
+    fn test_diff_headers_ignores_shifted_columns() {
+        let expected = ["a", "b", "c"];
+        let actual = ["new", "a", "b", "c"];
+        let diff = diff_headers(&expected, &actual, |c| c.to_string());
+        assert!(diff.missing.is_empty());
+        assert!(diff.reordered.is_empty());
+        assert_eq!(diff.unexpected, vec!["new"]);
+    }
+}
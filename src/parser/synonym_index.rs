@@ -0,0 +1,176 @@
+//! Synonyms grouped by the species they belong to.
+//!
+//! `SynonymIndex` joins synonym rows to species ids so consumers do not need
+//! to re-implement the join. Rows fall into three buckets:
+//! * grouped – `species_id` points at a species in the species table;
+//! * unattached – no `species_id` (the `synonym_only` rows of a release);
+//! * orphans – `species_id` is set but no such species exists, which usually
+//!   means the species and synonym files come from different releases.
+//!
+//! `ReleasedMddData::from_parser` drops orphaned rows, so build the index from
+//! the parsed CSV records with `SynonymIndex::new` when orphan detection matters.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use super::{
+    mdd::MddData,
+    synonyms::{SynonymData, SynonymValidity},
+    ReleasedMddData,
+};
+
+/// Name usages of one species, split by validity class.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SynonymGroup<'a> {
+    pub species_id: u32,
+    /// Usages that are the valid species name (normally exactly one).
+    pub valid: Vec<&'a SynonymData>,
+    pub synonyms: Vec<&'a SynonymData>,
+    pub unavailable: Vec<&'a SynonymData>,
+}
+
+impl SynonymGroup<'_> {
+    /// Total number of usages in the group.
+    pub fn len(&self) -> usize {
+        self.valid.len() + self.synonyms.len() + self.unavailable.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Index of synonym rows by species id.
+#[derive(Debug, Clone, Default)]
+pub struct SynonymIndex<'a> {
+    groups: BTreeMap<u32, Vec<&'a SynonymData>>,
+    unattached: Vec<&'a SynonymData>,
+    orphans: Vec<&'a SynonymData>,
+}
+
+impl<'a> SynonymIndex<'a> {
+    /// Index `synonyms` against the ids of `species`.
+    pub fn new(species: &[MddData], synonyms: &'a [SynonymData]) -> Self {
+        let ids: HashSet<u32> = species.iter().map(|s| s.id).collect();
+        let mut index = Self::default();
+        for synonym in synonyms {
+            index.insert(synonym, &ids);
+        }
+        index
+    }
+
+    /// Index the synonyms bundled in a release.
+    pub fn from_release(release: &'a ReleasedMddData) -> Self {
+        let ids: HashSet<u32> = release.data.iter().map(|d| d.mdd_id()).collect();
+        let mut index = Self::default();
+        let synonyms = release
+            .data
+            .iter()
+            .flat_map(|d| d.synonyms())
+            .chain(release.synonym_only.iter());
+        for synonym in synonyms {
+            index.insert(synonym, &ids);
+        }
+        index
+    }
+
+    fn insert(&mut self, synonym: &'a SynonymData, species_ids: &HashSet<u32>) {
+        match synonym.species_id {
+            Some(id) if species_ids.contains(&id) => {
+                self.groups.entry(id).or_default().push(synonym)
+            }
+            Some(_) => self.orphans.push(synonym),
+            None => self.unattached.push(synonym),
+        }
+    }
+
+    /// All usages of a species, in input order.
+    pub fn get(&self, species_id: u32) -> &[&'a SynonymData] {
+        self.groups
+            .get(&species_id)
+            .map(|g| g.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Usages of a species split by validity class.
+    pub fn group(&self, species_id: u32) -> Option<SynonymGroup<'a>> {
+        let usages = self.groups.get(&species_id)?;
+        let mut group = SynonymGroup {
+            species_id,
+            ..Default::default()
+        };
+        for usage in usages {
+            match usage.validity_class() {
+                SynonymValidity::Valid => group.valid.push(usage),
+                SynonymValidity::Synonym => group.synonyms.push(usage),
+                SynonymValidity::Unavailable => group.unavailable.push(usage),
+            }
+        }
+        Some(group)
+    }
+
+    /// Iterate over all groups ordered by species id.
+    pub fn groups(&self) -> impl Iterator<Item = SynonymGroup<'a>> + '_ {
+        self.groups.keys().filter_map(|id| self.group(*id))
+    }
+
+    /// Rows without a `species_id`.
+    pub fn unattached(&self) -> &[&'a SynonymData] {
+        &self.unattached
+    }
+
+    /// Rows whose `species_id` does not match any species.
+    pub fn orphans(&self) -> &[&'a SynonymData] {
+        &self.orphans
+    }
+
+    /// Number of species with at least one usage.
+    pub fn species_count(&self) -> usize {
+        self.groups.len()
+    }
+}
+
+impl ReleasedMddData {
+    /// Synonyms of this release grouped by species.
+    pub fn synonym_index(&self) -> SynonymIndex<'_> {
+        SynonymIndex::from_release(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synonym_index() {
+        let syn_csv = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let synonyms = SynonymData::new().from_csv(&syn_csv);
+        let species = vec![MddData {
+            id: 1003499,
+            ..MddData::new()
+        }];
+        let index = SynonymIndex::new(&species, &synonyms);
+
+        let group = index.group(1003499).unwrap();
+        assert_eq!(group.valid.len(), 1);
+        assert_eq!(group.synonyms[0].syn_id, 100040650);
+        assert_eq!(group.len(), index.get(1003499).len());
+        assert_eq!(index.species_count(), 1);
+        // The remaining rows point at species missing from this table.
+        assert_eq!(index.orphans().len(), synonyms.len() - group.len());
+        assert!(index.unattached().is_empty());
+        assert!(index.group(1).is_none());
+    }
+
+    #[test]
+    fn test_validity_class() {
+        let mut synonym = SynonymData::new();
+        synonym.validity = "synonym".to_string();
+        synonym.nomenclature_status = "nomen_nudum".to_string();
+        assert_eq!(synonym.validity_class(), SynonymValidity::Unavailable);
+        synonym.nomenclature_status = "available".to_string();
+        assert_eq!(synonym.validity_class(), SynonymValidity::Synonym);
+    }
+}
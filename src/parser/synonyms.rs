@@ -6,9 +6,101 @@
 //! remaining portion to camelCase so that serialized JSON aligns with other
 //! structs in this crate.
 
+use std::io::Read;
+
 use convert_case::Casing;
 use serde::{Deserialize, Serialize};
 
+use crate::{helper::MDD_MISSING_VALUE, nomenclature::authorship::Authorship};
+
+use super::{
+    error::MddError,
+    options::{CsvDialect, ParseOptions, ParseReport},
+    read_csv_records, read_csv_records_with,
+    reader::SynonymCsvReader,
+    schema::{
+        diff_headers, normalize_synonym_column, read_header_row, HeaderDiff, SYNONYM_COLUMNS,
+    },
+};
+
+/// `MDD_validity` value marking the usage that is the valid species name.
+const VALID_NAME_VALIDITY: &str = "species";
+
+/// `MDD_original_rank` value of names described as subspecies.
+const SUBSPECIES_RANK: &str = "subspecies";
+
+/// `MDD_nomenclature_status` values for names that are not available under the Code.
+const UNAVAILABLE_STATUSES: [&str; 7] = [
+    "unavailable",
+    "nomen_nudum",
+    "incorrect_subsequent_spelling",
+    "incorrect_original_spelling",
+    "not_intended_as_a_scientific_name",
+    "not_published_with_a_generic_name",
+    "infrasubspecific",
+];
+
+/// Coarse validity classification of a name usage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum SynonymValidity {
+    /// The usage is the valid name of its species (`MDD_validity` = `species`).
+    Valid,
+    /// An available name treated as a synonym (including subspecies and combinations).
+    Synonym,
+    /// A name that is not available (nomen nudum, misspelling, ...).
+    Unavailable,
+}
+
+/// A subspecies-rank name of a species, as listed in the bundled
+/// per-species JSON.
+///
+/// `name` is the trinomial under the current species (`MDD_species` plus the
+/// subspecific epithet, or the root name when the epithet column is empty);
+/// `originalCombination` keeps the name as published.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Subspecies {
+    /// `syn_id` of the usage the entry was built from.
+    pub syn_id: u32,
+    pub name: String,
+    pub epithet: String,
+    pub original_combination: String,
+    pub authority: String,
+    pub validity: SynonymValidity,
+}
+
+impl Subspecies {
+    /// Entry for `synonym`; `None` unless it is a subspecies-rank name.
+    pub fn from_synonym(synonym: &SynonymData) -> Option<Self> {
+        if !synonym.is_subspecies() {
+            return None;
+        }
+        let epithet = match synonym.infraspecific_epithet() {
+            "" => synonym.root_name.trim(),
+            epithet => epithet,
+        };
+        Some(Self {
+            syn_id: synonym.syn_id,
+            name: format!("{} {}", synonym.species.trim(), epithet),
+            epithet: epithet.to_string(),
+            original_combination: synonym.original_combination.clone(),
+            authority: synonym.authorship().to_string(),
+            validity: synonym.validity_class(),
+        })
+    }
+
+    /// Subspecies-rank names among `synonyms`, in input order.
+    pub fn from_synonyms<'a>(synonyms: impl IntoIterator<Item = &'a SynonymData>) -> Vec<Self> {
+        synonyms
+            .into_iter()
+            .filter_map(Self::from_synonym)
+            .collect()
+    }
+}
+
 /// Representation of a single synonym (or name usage) row from the MDD synonyms
 /// CSV.
 ///
@@ -21,6 +113,12 @@ use serde::{Deserialize, Serialize};
 /// * Authority / citation fields retain upstream capitalization and punctuation.
 #[derive(Debug, Serialize, Default, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(name = "Synonym", complex)
+)]
 pub struct SynonymData {
     /// Unique synonym identifier (internal to MDD synonym table).
     pub syn_id: u32,
@@ -28,48 +126,57 @@ pub struct SynonymData {
     pub hesp_id: u32,
     /// Foreign key linking to an MDD species record; absent if not attached to a living/accepted taxon.
     pub species_id: Option<u32>,
-    // Below are raw text columns retained verbatim. We keep them private and provide JSON via serde.
-    species: String,
-    root_name: String,
-    author: String,
-    year: String,
-    authority_parentheses: u8,
-    nomenclature_status: String,
-    validity: String,
-    original_combination: String,
-    original_rank: String,
-    authority_citation: String,
-    unchecked_authority_citation: String,
-    sourced_unverified_citations: String,
-    citation_group: String,
-    citation_kind: String,
-    authority_page: String,
-    authority_link: String,
-    authority_page_link: String,
-    unchecked_authority_page_link: String,
-    old_type_locality: String,
-    original_type_locality: String,
-    unchecked_type_locality: String,
-    emended_type_locality: String,
-    type_latitude: String,
-    type_longitude: String,
-    type_country: String,
-    type_subregion: String,
-    type_subregion2: String,
-    holotype: String,
-    type_kind: String,
-    type_specimen_link: String,
+    // Below are raw text columns retained verbatim. They are crate-visible (for
+    // writers such as `writer::dwca`) but not public; JSON is provided via serde.
+    pub(crate) species: String,
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub(crate) root_name: String,
+    pub(crate) author: String,
+    pub(crate) year: String,
+    pub(crate) authority_parentheses: u8,
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub(crate) nomenclature_status: String,
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub(crate) validity: String,
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub(crate) original_combination: String,
+    pub(crate) original_rank: String,
+    pub(crate) authority_citation: String,
+    pub(crate) unchecked_authority_citation: String,
+    pub(crate) sourced_unverified_citations: String,
+    pub(crate) citation_group: String,
+    pub(crate) citation_kind: String,
+    pub(crate) authority_page: String,
+    pub(crate) authority_link: String,
+    pub(crate) authority_page_link: String,
+    pub(crate) unchecked_authority_page_link: String,
+    pub(crate) old_type_locality: String,
+    pub(crate) original_type_locality: String,
+    pub(crate) unchecked_type_locality: String,
+    pub(crate) emended_type_locality: String,
+    pub(crate) type_latitude: String,
+    pub(crate) type_longitude: String,
+    pub(crate) type_country: String,
+    pub(crate) type_subregion: String,
+    pub(crate) type_subregion2: String,
+    pub(crate) holotype: String,
+    pub(crate) type_kind: String,
+    pub(crate) type_specimen_link: String,
     #[serde(alias = "order")]
-    taxon_order: String,
-    family: String,
-    genus: String,
-    specific_epithet: String,
-    subspecific_epithet: String,
-    variant_of: String,
-    senior_homonym: String,
-    variant_name_citations: String,
-    name_usages: String,
-    comments: String,
+    pub(crate) taxon_order: String,
+    pub(crate) family: String,
+    pub(crate) genus: String,
+    pub(crate) specific_epithet: String,
+    pub(crate) subspecific_epithet: String,
+    pub(crate) variant_of: String,
+    pub(crate) senior_homonym: String,
+    pub(crate) variant_name_citations: String,
+    pub(crate) name_usages: String,
+    pub(crate) comments: String,
+    /// URL-safe permalink (see `helper::slug`); not an MDD column, empty until assigned.
+    #[serde(default)]
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub(crate) slug: String,
 }
 
 impl SynonymData {
@@ -118,11 +225,16 @@ impl SynonymData {
             variant_name_citations: "".to_string(),
             name_usages: "".to_string(),
             comments: "".to_string(),
+            slug: "".to_string(),
         }
     }
 
+    /// Parse synonym csv data.
+    ///
+    /// Rows that fail to deserialize are replaced by a default record. Use
+    /// `try_from_csv` to surface those rows as errors instead.
     pub fn from_csv(&self, csv_data: &str) -> Vec<SynonymData> {
-        let data = self.clean_colnames(csv_data);
+        let data = self.clean_colnames(CsvDialect::new().clean_header(csv_data));
         let mut rdr = csv::Reader::from_reader(data.as_slice());
         let mut records = Vec::new();
         for result in rdr.deserialize() {
@@ -132,6 +244,176 @@ impl SynonymData {
         records
     }
 
+    /// Parse synonym csv data, returning an `MddError` describing the first
+    /// row (and column, when known) that fails to deserialize.
+    pub fn try_from_csv(&self, csv_data: &str) -> Result<Vec<SynonymData>, MddError> {
+        let csv_data = CsvDialect::new().clean_header(csv_data);
+        self.check_headers(csv_data)?;
+        let data = self.clean_colnames(csv_data);
+        read_csv_records(&data)
+    }
+
+    /// Parse synonym records from any reader, failing on the first bad row.
+    ///
+    /// Use `SynonymCsvReader` directly to process records one at a time.
+    pub fn from_reader<R: Read>(&self, reader: R) -> Result<Vec<SynonymData>, MddError> {
+        SynonymCsvReader::new(reader)?.collect()
+    }
+
+    /// Parse synonym csv data according to `options`.
+    ///
+    /// In lenient mode (`strict: false`) unparseable rows are skipped and
+    /// listed in the returned `ParseReport` together with their raw text.
+    /// Input in another `options.dialect` (e.g. TSV) is converted first.
+    pub fn from_csv_with(
+        &self,
+        csv_data: &str,
+        options: &ParseOptions,
+    ) -> Result<(Vec<SynonymData>, ParseReport), MddError> {
+        let csv_data = options.dialect.to_standard(csv_data)?;
+        self.check_headers(&csv_data)?;
+        let data = self.clean_colnames(&csv_data);
+        read_csv_records_with(&data, options)
+    }
+
+    /// Compare the header row of `csv_data` against the known synonym layout.
+    ///
+    /// Column names are matched after stripping the `MDD_` prefix and
+    /// converting to camelCase, mirroring how the parser maps them.
+    pub fn validate_headers(&self, csv_data: &str) -> Result<HeaderDiff, MddError> {
+        let csv_data = CsvDialect::new().clean_header(csv_data);
+        let headers = read_header_row(csv_data.as_bytes())?;
+        let headers: Vec<&str> = headers.iter().map(|h| h.as_str()).collect();
+        Ok(diff_headers(
+            &SYNONYM_COLUMNS,
+            &headers,
+            normalize_synonym_column,
+        ))
+    }
+
+    fn check_headers(&self, csv_data: &str) -> Result<(), MddError> {
+        let diff = self.validate_headers(csv_data)?;
+        if diff.is_compatible() {
+            Ok(())
+        } else {
+            Err(MddError::Schema(diff))
+        }
+    }
+
+    /// Current MDD species name the usage is attached to (`MDD_species`).
+    pub fn species_name(&self) -> &str {
+        &self.species
+    }
+
+    /// Root of the name as published (`MDD_root_name`), e.g. `latidens`.
+    pub fn root_name(&self) -> &str {
+        &self.root_name
+    }
+
+    /// Genus and epithet the name was originally published in (`MDD_original_combination`).
+    pub fn original_combination(&self) -> &str {
+        &self.original_combination
+    }
+
+    /// Subspecific epithet of the name (`MDD_subspecificEpithet`); empty for
+    /// binomials.
+    pub fn infraspecific_epithet(&self) -> &str {
+        let epithet = self.subspecific_epithet.trim();
+        if epithet == MDD_MISSING_VALUE {
+            ""
+        } else {
+            epithet
+        }
+    }
+
+    /// Whether the name is a trinomial: described at subspecies rank or
+    /// carrying a subspecific epithet.
+    pub fn is_subspecies(&self) -> bool {
+        self.original_rank
+            .trim()
+            .eq_ignore_ascii_case(SUBSPECIES_RANK)
+            || !self.infraspecific_epithet().is_empty()
+    }
+
+    /// URL-safe permalink assigned by `helper::slug`.
+    pub fn slug(&self) -> &str {
+        &self.slug
+    }
+
+    /// Verbatim `MDD_validity` value (e.g. `species`, `synonym`, `nomen_dubium`).
+    pub fn validity(&self) -> &str {
+        &self.validity
+    }
+
+    /// Verbatim `MDD_nomenclature_status` value (e.g. `available`, `nomen_nudum`).
+    pub fn nomenclature_status(&self) -> &str {
+        &self.nomenclature_status
+    }
+
+    /// Classify the usage as the valid name, a synonym, or an unavailable name.
+    pub fn validity_class(&self) -> SynonymValidity {
+        if UNAVAILABLE_STATUSES.contains(&self.nomenclature_status.trim()) {
+            SynonymValidity::Unavailable
+        } else if self.validity.trim() == VALID_NAME_VALIDITY {
+            SynonymValidity::Valid
+        } else {
+            SynonymValidity::Synonym
+        }
+    }
+
+    /// Structured authority (author, year, and parentheses columns).
+    pub fn authorship(&self) -> Authorship {
+        Authorship::from_synonym(self)
+    }
+
+    /// Call `f` on every text column (the derived `slug` excluded), e.g. to
+    /// clean up synonym records in a `Pipeline` transform.
+    pub fn for_each_text_field(&mut self, mut f: impl FnMut(&mut String)) {
+        for field in [
+            &mut self.species,
+            &mut self.root_name,
+            &mut self.author,
+            &mut self.year,
+            &mut self.nomenclature_status,
+            &mut self.validity,
+            &mut self.original_combination,
+            &mut self.original_rank,
+            &mut self.authority_citation,
+            &mut self.unchecked_authority_citation,
+            &mut self.sourced_unverified_citations,
+            &mut self.citation_group,
+            &mut self.citation_kind,
+            &mut self.authority_page,
+            &mut self.authority_link,
+            &mut self.authority_page_link,
+            &mut self.unchecked_authority_page_link,
+            &mut self.old_type_locality,
+            &mut self.original_type_locality,
+            &mut self.unchecked_type_locality,
+            &mut self.emended_type_locality,
+            &mut self.type_latitude,
+            &mut self.type_longitude,
+            &mut self.type_country,
+            &mut self.type_subregion,
+            &mut self.type_subregion2,
+            &mut self.holotype,
+            &mut self.type_kind,
+            &mut self.type_specimen_link,
+            &mut self.taxon_order,
+            &mut self.family,
+            &mut self.genus,
+            &mut self.specific_epithet,
+            &mut self.subspecific_epithet,
+            &mut self.variant_of,
+            &mut self.senior_homonym,
+            &mut self.variant_name_citations,
+            &mut self.name_usages,
+            &mut self.comments,
+        ] {
+            f(field);
+        }
+    }
+
     pub fn to_json(&self) -> String {
         serde_json::to_string(&self).expect("Failed to serialize")
     }
@@ -160,9 +442,28 @@ impl SynonymData {
     }
 }
 
+/// Records shared by the tests of the synonym name checks.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::SynonymData;
+
+    /// Synonym `syn_id` of `combination`, named after its last word, with its
+    /// authority.
+    pub(crate) fn synonym(syn_id: u32, combination: &str, author: &str, year: &str) -> SynonymData {
+        let mut synonym = SynonymData::new();
+        synonym.syn_id = syn_id;
+        synonym.root_name = combination.split(' ').next_back().unwrap().to_string();
+        synonym.original_combination = combination.to_string();
+        synonym.author = author.to_string();
+        synonym.year = year.to_string();
+        synonym
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::{mdd::MddData, ReleasedMddData};
 
     #[test]
     fn test_remove_mdd_prefix() {
@@ -183,4 +484,58 @@ mod tests {
         let records = synonym_data.from_csv(&data);
         assert!(!records.is_empty());
     }
+
+    #[test]
+    fn test_try_parsing_synonym_csv() {
+        let data = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let records = SynonymData::new().try_from_csv(&data).unwrap();
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[0].species_id, Some(1003499));
+    }
+
+    #[test]
+    fn test_validate_synonym_headers() {
+        let data = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let parser = SynonymData::new();
+        assert!(parser.validate_headers(&data).unwrap().is_empty());
+
+        let missing = data.replacen("MDD_validity,", "", 1);
+        let diff = parser.validate_headers(&missing).unwrap();
+        assert_eq!(diff.missing, vec!["MDD_validity"]);
+    }
+    #[test]
+    fn test_subspecies() {
+        let data = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let mut records = SynonymData::new().try_from_csv(&data).unwrap();
+        assert!(Subspecies::from_synonyms(&records).is_empty());
+
+        // Pogonomelomys sevia tatei, described as a subspecies.
+        let tatei = &mut records[3];
+        tatei.original_rank = "subspecies".to_string();
+        tatei.original_combination = "Pogonomelomys sevia tatei".to_string();
+        assert!(tatei.is_subspecies());
+        assert_eq!(tatei.infraspecific_epithet(), "");
+        let subspecies = Subspecies::from_synonyms(&records);
+        assert_eq!(subspecies.len(), 1);
+        assert_eq!(subspecies[0].syn_id, 100022092);
+        assert_eq!(subspecies[0].name, "Abeomelomys sevia tatei");
+        assert_eq!(subspecies[0].epithet, "tatei");
+        assert_eq!(subspecies[0].validity, SynonymValidity::Synonym);
+
+        records[0].subspecific_epithet = "luzonensis".to_string();
+        let subspecies = Subspecies::from_synonym(&records[0]).unwrap();
+        assert_eq!(subspecies.name, "Abditomys latidens luzonensis");
+
+        let species = vec![MddData {
+            id: 1003127,
+            ..MddData::new()
+        }];
+        let release = ReleasedMddData::from_parser(species, records, "2.0", "2025-01-01");
+        assert_eq!(release.subspecies_of(1003127)[0].epithet, "tatei");
+        assert!(release.subspecies_of(1003499).is_empty());
+        let json = release.to_json();
+        assert!(json.contains(r#""subspecies":[{"synId":100022092,"#));
+        let parsed = ReleasedMddData::from_json(&json);
+        assert_eq!(parsed.subspecies_of(1003127).len(), 1);
+    }
 }
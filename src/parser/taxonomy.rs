@@ -0,0 +1,227 @@
+//! Nested taxonomic hierarchy built from MDD species records.
+//!
+//! `TaxonomyTree::from_mdd` groups rows into subclass → order → family →
+//! genus → species nodes. Every node carries the number of species (and
+//! extinct species) below it, so the serialized tree can drive a taxonomy
+//! browser without recounting on the client.
+//!
+//! Children keep MDD phylogenetic order (`phylosort`) rather than
+//! alphabetical order.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::mdd::MddData;
+
+/// Ranks represented in the tree, from the root down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "graphql", derive(async_graphql::Enum))]
+pub enum TaxonRank {
+    Subclass,
+    Order,
+    Family,
+    Genus,
+    Species,
+}
+
+impl TaxonRank {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Subclass => "subclass",
+            Self::Order => "order",
+            Self::Family => "family",
+            Self::Genus => "genus",
+            Self::Species => "species",
+        }
+    }
+}
+
+impl fmt::Display for TaxonRank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for TaxonRank {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "subclass" => Ok(Self::Subclass),
+            "order" => Ok(Self::Order),
+            "family" => Ok(Self::Family),
+            "genus" => Ok(Self::Genus),
+            "species" => Ok(Self::Species),
+            other => Err(format!("Unknown taxonomic rank: {}", other)),
+        }
+    }
+}
+
+/// A node of the taxonomy tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct TaxonNode {
+    pub rank: TaxonRank,
+    /// Taxon name; species use the MDD `sciName` (with `_`).
+    pub name: String,
+    /// MDD id, only set on species nodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u32>,
+    /// Number of species at or below this node.
+    pub species_count: u32,
+    /// Number of extinct species at or below this node.
+    pub extinct_count: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TaxonNode>,
+}
+
+impl TaxonNode {
+    fn new(rank: TaxonRank, name: &str) -> Self {
+        Self {
+            rank,
+            name: name.to_string(),
+            id: None,
+            species_count: 0,
+            extinct_count: 0,
+            children: Vec::new(),
+        }
+    }
+
+    /// Child with the given rank and name, inserted if absent.
+    fn child_mut(&mut self, rank: TaxonRank, name: &str) -> &mut TaxonNode {
+        let pos = match self
+            .children
+            .iter()
+            .position(|c| c.rank == rank && c.name == name)
+        {
+            Some(pos) => pos,
+            None => {
+                self.children.push(TaxonNode::new(rank, name));
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[pos]
+    }
+
+    /// Depth-first search for a node of `rank` named `name` (case-insensitive),
+    /// including this node.
+    pub fn find(&self, rank: TaxonRank, name: &str) -> Option<&TaxonNode> {
+        if self.rank == rank && self.name.eq_ignore_ascii_case(name) {
+            return Some(self);
+        }
+        if self.rank >= rank {
+            return None;
+        }
+        self.children.iter().find_map(|c| c.find(rank, name))
+    }
+
+    /// Number of direct children.
+    pub fn child_count(&self) -> usize {
+        self.children.len()
+    }
+}
+
+/// Nested taxonomy of an MDD release.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct TaxonomyTree {
+    /// Subclass nodes (Prototheria, Theria, ...).
+    pub roots: Vec<TaxonNode>,
+    pub species_count: u32,
+}
+
+impl TaxonomyTree {
+    pub fn new() -> Self {
+        Self {
+            roots: Vec::new(),
+            species_count: 0,
+        }
+    }
+
+    /// Build the tree from species records.
+    pub fn from_mdd(data: &[MddData]) -> Self {
+        let mut records: Vec<&MddData> = data.iter().collect();
+        records.sort_by_key(|d| d.phylosort);
+
+        // A synthetic root keeps the insertion logic uniform.
+        let mut root = TaxonNode::new(TaxonRank::Subclass, "");
+        for record in records {
+            let extinct = u32::from(record.extinct == 1);
+            root.species_count += 1;
+            root.extinct_count += extinct;
+            let mut node = &mut root;
+            for (rank, name) in [
+                (TaxonRank::Subclass, &record.subclass),
+                (TaxonRank::Order, &record.taxon_order),
+                (TaxonRank::Family, &record.family),
+                (TaxonRank::Genus, &record.genus),
+                (TaxonRank::Species, &record.sci_name),
+            ] {
+                node = node.child_mut(rank, name);
+                node.species_count += 1;
+                node.extinct_count += extinct;
+            }
+            node.id = Some(record.id);
+        }
+
+        Self {
+            species_count: root.species_count,
+            roots: root.children,
+        }
+    }
+
+    /// Find the subtree rooted at the taxon of `rank` named `name`.
+    pub fn subtree(&self, rank: TaxonRank, name: &str) -> Option<&TaxonNode> {
+        self.roots.iter().find_map(|r| r.find(rank, name))
+    }
+
+    /// All nodes of the given rank, in tree order.
+    pub fn nodes_at(&self, rank: TaxonRank) -> Vec<&TaxonNode> {
+        let mut nodes = Vec::new();
+        let mut stack: Vec<&TaxonNode> = self.roots.iter().rev().collect();
+        while let Some(node) = stack.pop() {
+            if node.rank == rank {
+                nodes.push(node);
+            } else if node.rank < rank {
+                stack.extend(node.children.iter().rev());
+            }
+        }
+        nodes
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).expect("Failed to serialize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_taxonomy_tree() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let data = MddData::new().from_csv(&csv_data);
+        let tree = TaxonomyTree::from_mdd(&data);
+        assert_eq!(tree.species_count, 112);
+        assert_eq!(tree.nodes_at(TaxonRank::Order).len(), 1);
+        assert_eq!(tree.nodes_at(TaxonRank::Family).len(), 3);
+        assert_eq!(tree.nodes_at(TaxonRank::Genus).len(), 12);
+        assert_eq!(tree.nodes_at(TaxonRank::Species).len(), 112);
+
+        let genus = tree.subtree(TaxonRank::Genus, "bunolagus").unwrap();
+        assert_eq!(genus.species_count, genus.child_count() as u32);
+        assert_eq!(genus.children[0].id, Some(1001076));
+        assert!(tree.subtree(TaxonRank::Family, "Felidae").is_none());
+    }
+
+    #[test]
+    fn test_parse_rank() {
+        assert_eq!("Family".parse::<TaxonRank>(), Ok(TaxonRank::Family));
+        assert!("tribe".parse::<TaxonRank>().is_err());
+    }
+}
@@ -0,0 +1,260 @@
+//! Typed view over `MddData` (enabled with the `typed` feature).
+//!
+//! `MddData` keeps every column verbatim. `MddRecordTyped` is an opt-in
+//! projection with numeric coordinates, an `IucnStatus` enum, boolean flags,
+//! an optional description year, and type vouchers split into institution
+//! code and catalog number (`helper::voucher::Voucher`). Coordinates are read with
+//! `helper::coords::parse_coordinate` (decimal, DMS, hemisphere letters,
+//! `ca.` prefixes) and must be within range. Conversion never fails as a whole;
+//! values that cannot be converted become `None` (or `false`) and are listed
+//! in `TypedConversion::failed_fields` so callers can decide how strict to be.
+
+use serde::{Deserialize, Serialize};
+
+use crate::helper::{
+    coords::{parse_coordinate, Axis, CoordinateConfidence},
+    iucn::IucnStatus,
+    voucher::{split_vouchers, Voucher},
+    MDD_MISSING_VALUE,
+};
+
+use super::mdd::MddData;
+
+/// Typed projection of a species record.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MddRecordTyped {
+    pub id: u32,
+    pub sci_name: String,
+    pub taxon_order: String,
+    pub family: String,
+    pub genus: String,
+    pub specific_epithet: String,
+    /// Year of description; `None` when unknown (stored as 0 in the CSV).
+    pub authority_species_year: Option<u16>,
+    pub authority_parentheses: bool,
+    /// Decimal latitude of the type locality.
+    pub type_locality_latitude: Option<f64>,
+    /// Decimal longitude of the type locality.
+    pub type_locality_longitude: Option<f64>,
+    /// Lowest confidence of the parsed type locality coordinates; `None`
+    /// when neither was read.
+    pub type_locality_confidence: Option<CoordinateConfidence>,
+    /// Parsed Red List category; `None` when blank or unrecognized.
+    pub iucn_status: Option<IucnStatus>,
+    /// Type specimen identifiers; empty when none could be read.
+    pub type_vouchers: Vec<Voucher>,
+    pub extinct: bool,
+    pub domestic: bool,
+    pub flagged: bool,
+    pub diff_since_cmw: bool,
+}
+
+/// A field whose verbatim value could not be converted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldConversionError {
+    /// Serialized (camelCase) field name.
+    pub field: String,
+    /// The verbatim value.
+    pub value: String,
+}
+
+/// Result of `MddData::to_typed`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedConversion {
+    pub record: MddRecordTyped,
+    pub failed_fields: Vec<FieldConversionError>,
+}
+
+impl TypedConversion {
+    /// Returns `true` when every field converted cleanly.
+    pub fn is_lossless(&self) -> bool {
+        self.failed_fields.is_empty()
+    }
+}
+
+impl MddData {
+    /// Convert this record into its typed view, reporting which fields failed.
+    pub fn to_typed(&self) -> TypedConversion {
+        let mut failed = Vec::new();
+        let latitude = to_coordinate(
+            "typeLocalityLatitude",
+            &self.type_locality_latitude,
+            Axis::Latitude,
+            &mut failed,
+        );
+        let longitude = to_coordinate(
+            "typeLocalityLongitude",
+            &self.type_locality_longitude,
+            Axis::Longitude,
+            &mut failed,
+        );
+        let record = MddRecordTyped {
+            id: self.id,
+            sci_name: self.sci_name.clone(),
+            taxon_order: self.taxon_order.clone(),
+            family: self.family.clone(),
+            genus: self.genus.clone(),
+            specific_epithet: self.specific_epithet.clone(),
+            authority_species_year: match self.authority_species_year {
+                0 => None,
+                year => Some(year),
+            },
+            authority_parentheses: to_bool(
+                "authorityParentheses",
+                self.authority_parentheses,
+                &mut failed,
+            ),
+            type_locality_latitude: latitude.map(|(value, _)| value),
+            type_locality_longitude: longitude.map(|(value, _)| value),
+            type_locality_confidence: latitude
+                .into_iter()
+                .chain(longitude)
+                .map(|(_, confidence)| confidence)
+                .max(),
+            iucn_status: to_iucn_status(&self.iucn_status, &mut failed),
+            type_vouchers: to_vouchers(&self.type_voucher, &mut failed),
+            extinct: to_bool("extinct", self.extinct, &mut failed),
+            domestic: to_bool("domestic", self.domestic, &mut failed),
+            flagged: to_bool("flagged", self.flagged, &mut failed),
+            diff_since_cmw: to_bool("diffSinceCMW", self.diff_since_cmw, &mut failed),
+        };
+        TypedConversion {
+            record,
+            failed_fields: failed,
+        }
+    }
+}
+
+fn is_blank(value: &str) -> bool {
+    let value = value.trim();
+    value.is_empty() || value == MDD_MISSING_VALUE
+}
+
+fn push_failure(field: &str, value: impl ToString, failed: &mut Vec<FieldConversionError>) {
+    failed.push(FieldConversionError {
+        field: field.to_string(),
+        value: value.to_string(),
+    });
+}
+
+fn to_bool(field: &str, value: u8, failed: &mut Vec<FieldConversionError>) -> bool {
+    match value {
+        0 => false,
+        1 => true,
+        other => {
+            push_failure(field, other, failed);
+            false
+        }
+    }
+}
+
+fn to_coordinate(
+    field: &str,
+    value: &str,
+    axis: Axis,
+    failed: &mut Vec<FieldConversionError>,
+) -> Option<(f64, CoordinateConfidence)> {
+    match parse_coordinate(value, axis) {
+        Ok(None) => None,
+        Ok(Some(coordinate)) if coordinate.value.abs() <= axis.limit() => {
+            Some((coordinate.value, coordinate.confidence))
+        }
+        _ => {
+            push_failure(field, value, failed);
+            None
+        }
+    }
+}
+
+fn to_iucn_status(value: &str, failed: &mut Vec<FieldConversionError>) -> Option<IucnStatus> {
+    if is_blank(value) {
+        return None;
+    }
+    match value.parse() {
+        Ok(status) => Some(status),
+        Err(_) => {
+            push_failure("iucnStatus", value, failed);
+            None
+        }
+    }
+}
+
+fn to_vouchers(value: &str, failed: &mut Vec<FieldConversionError>) -> Vec<Voucher> {
+    let vouchers: Option<Vec<Voucher>> = split_vouchers(value)
+        .into_iter()
+        .map(Voucher::parse)
+        .collect();
+    vouchers.unwrap_or_else(|| {
+        push_failure("typeVoucher", value, failed);
+        Vec::new()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_typed() {
+        let mut record = MddData::new();
+        record.authority_species_year = 1758;
+        record.type_locality_latitude = "73.45".to_string();
+        record.type_locality_longitude = "NA".to_string();
+        record.iucn_status = "EN".to_string();
+        record.type_voucher = "BM(NH) 1901.2.3.4, USNM:MAMM:93691".to_string();
+        record.extinct = 1;
+        let typed = record.to_typed();
+        assert!(typed.is_lossless());
+        assert_eq!(typed.record.type_vouchers.len(), 2);
+        assert_eq!(typed.record.type_vouchers[0].institution_code, "BMNH");
+        assert_eq!(typed.record.authority_species_year, Some(1758));
+        assert_eq!(typed.record.type_locality_latitude, Some(73.45));
+        assert_eq!(typed.record.type_locality_longitude, None);
+        assert_eq!(
+            typed.record.type_locality_confidence,
+            Some(CoordinateConfidence::Exact)
+        );
+        assert_eq!(typed.record.iucn_status, Some(IucnStatus::EN));
+        assert!(typed.record.extinct);
+    }
+
+    #[test]
+    fn test_to_typed_reports_failures() {
+        let mut record = MddData::new();
+        record.type_locality_latitude = "95.2".to_string();
+        record.type_locality_longitude = "ca. 36°49'E".to_string();
+        record.iucn_status = "unknown".to_string();
+        record.type_voucher = "USNM 1, Laâyoune REF 021/2019 Awserd".to_string();
+        record.flagged = 2;
+        let typed = record.to_typed();
+        let fields: Vec<&str> = typed
+            .failed_fields
+            .iter()
+            .map(|f| f.field.as_str())
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                "typeLocalityLatitude",
+                "iucnStatus",
+                "typeVoucher",
+                "flagged"
+            ]
+        );
+        assert_eq!(typed.record.type_locality_latitude, None);
+        assert_eq!(
+            typed
+                .record
+                .type_locality_longitude
+                .map(|v| (v * 100.0).round()),
+            Some(3682.0)
+        );
+        assert_eq!(
+            typed.record.type_locality_confidence,
+            Some(CoordinateConfidence::Approximate)
+        );
+    }
+}
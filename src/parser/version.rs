@@ -0,0 +1,133 @@
+//! MDD release versions.
+//!
+//! Releases are numbered `<major>.<minor>` (`2.2`, `2024.1`), optionally with
+//! a patch number (`2.2.1`) and a leading `v` as used in file names and git
+//! tags. `ReleaseVersion` compares numerically, so `2.10` sorts after `2.9`,
+//! and a missing patch number equals `0` (`2.2` == `2.2.0`).
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use super::error::MddError;
+
+/// A parsed release version.
+#[derive(Debug, Clone, Copy)]
+pub struct ReleaseVersion {
+    pub major: u32,
+    pub minor: u32,
+    /// Patch number, when written.
+    pub patch: Option<u32>,
+}
+
+impl ReleaseVersion {
+    pub fn new(major: u32, minor: u32, patch: Option<u32>) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Whether this release comes after `other`.
+    pub fn is_newer_than(&self, other: &ReleaseVersion) -> bool {
+        self > other
+    }
+
+    fn key(&self) -> (u32, u32, u32) {
+        (self.major, self.minor, self.patch.unwrap_or(0))
+    }
+}
+
+impl FromStr for ReleaseVersion {
+    type Err = MddError;
+
+    /// Parse `2.2`, `2024.1`, `v2.2.1`, or `V2.2`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let digits = trimmed.strip_prefix(['v', 'V']).unwrap_or(trimmed);
+        let parts = digits
+            .split('.')
+            .map(|part| part.parse::<u32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid_version(s))?;
+        match parts.as_slice() {
+            [major, minor] => Ok(Self::new(*major, *minor, None)),
+            [major, minor, patch] => Ok(Self::new(*major, *minor, Some(*patch))),
+            _ => Err(invalid_version(s)),
+        }
+    }
+}
+
+fn invalid_version(s: &str) -> MddError {
+    MddError::InvalidVersion(format!("'{}'", s))
+}
+
+impl fmt::Display for ReleaseVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)?;
+        if let Some(patch) = self.patch {
+            write!(f, ".{}", patch)?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for ReleaseVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for ReleaseVersion {}
+
+impl Hash for ReleaseVersion {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl PartialOrd for ReleaseVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ReleaseVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_version() {
+        let version: ReleaseVersion = "v2.2.1".parse().unwrap();
+        assert_eq!(version, ReleaseVersion::new(2, 2, Some(1)));
+        assert_eq!(version.to_string(), "2.2.1");
+        assert_eq!(
+            "2.2".parse::<ReleaseVersion>().unwrap(),
+            "2.2.0".parse().unwrap()
+        );
+        assert!("2.2x".parse::<ReleaseVersion>().is_err());
+        assert!(matches!(
+            "2".parse::<ReleaseVersion>(),
+            Err(MddError::InvalidVersion(_))
+        ));
+
+        let mut versions: Vec<ReleaseVersion> = ["2.10", "2024.1", "v2.9", "2.2.1", "2.2"]
+            .iter()
+            .map(|v| v.parse().unwrap())
+            .collect();
+        versions.sort();
+        let sorted: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+        assert_eq!(sorted, ["2.2", "2.2.1", "2.9", "2.10", "2024.1"]);
+        assert!(versions[3].is_newer_than(&versions[2]));
+    }
+}
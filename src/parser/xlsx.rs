@@ -0,0 +1,121 @@
+//! Species and synonym records from spreadsheets (enabled with the `xlsx` feature).
+//!
+//! MDD working files are often shared as Excel workbooks with the species
+//! table and the synonym table on separate sheets. `MddWorkbook` opens any
+//! format `calamine` reads (`.xlsx`, `.xlsm`, `.xls`, `.ods`) and finds both
+//! sheets by their header row rather than by name, so renamed or reordered
+//! sheets still load. Other sheets (notes, READMEs) are ignored.
+//!
+//! Each sheet is converted to CSV text and parsed by the regular CSV readers,
+//! so header validation and record types are the same as for release CSVs.
+//! Numeric cells are written without a trailing `.0` (`1001076`), empty and
+//! error cells (`#N/A`) become empty fields.
+
+use std::path::Path;
+
+use calamine::{open_workbook_auto, Data, Range, Reader};
+
+use super::{error::MddError, mdd::MddData, synonyms::SynonymData};
+
+/// Species and synonym sheets of a workbook.
+#[derive(Debug, Clone)]
+pub struct MddWorkbook {
+    /// Name of the sheet holding the species table.
+    pub species_sheet: String,
+    /// Name of the sheet holding the synonym table, if the workbook has one.
+    pub synonym_sheet: Option<String>,
+    species_csv: String,
+    synonym_csv: Option<String>,
+}
+
+impl MddWorkbook {
+    /// Open `path` and locate the species and synonym sheets.
+    ///
+    /// Fails with `MddError::MissingSheet` when no sheet has the species
+    /// header; a missing synonym sheet is not an error.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, MddError> {
+        let mut workbook = open_workbook_auto(path)?;
+        let mut species = None;
+        let mut synonyms = None;
+        for name in workbook.sheet_names() {
+            if species.is_some() && synonyms.is_some() {
+                break;
+            }
+            let csv_data = range_to_csv(&workbook.worksheet_range(&name)?)?;
+            if species.is_none() && is_compatible(MddData::new().validate_headers(&csv_data)) {
+                species = Some((name, csv_data));
+            } else if synonyms.is_none()
+                && is_compatible(SynonymData::new().validate_headers(&csv_data))
+            {
+                synonyms = Some((name, csv_data));
+            }
+        }
+        let (species_sheet, species_csv) =
+            species.ok_or_else(|| MddError::MissingSheet("MDD species table".to_string()))?;
+        let (synonym_sheet, synonym_csv) = synonyms.unzip();
+        Ok(Self {
+            species_sheet,
+            synonym_sheet,
+            species_csv,
+            synonym_csv,
+        })
+    }
+
+    /// Parse the species sheet.
+    pub fn species(&self) -> Result<Vec<MddData>, MddError> {
+        MddData::new().try_from_csv(&self.species_csv)
+    }
+
+    /// Parse the synonym sheet; empty when the workbook has none.
+    pub fn synonyms(&self) -> Result<Vec<SynonymData>, MddError> {
+        match &self.synonym_csv {
+            Some(csv_data) => SynonymData::new().try_from_csv(csv_data),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+fn is_compatible(diff: Result<super::schema::HeaderDiff, MddError>) -> bool {
+    diff.map(|diff| diff.is_compatible()).unwrap_or(false)
+}
+
+/// Write the used cells of a sheet as CSV text.
+fn range_to_csv(range: &Range<Data>) -> Result<String, MddError> {
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+    for row in range.rows() {
+        wtr.write_record(row.iter().map(cell_to_string))?;
+    }
+    let bytes = wtr.into_inner().map_err(|e| MddError::Io(e.into_error()))?;
+    Ok(String::from_utf8(bytes).expect("Sheet cells are valid UTF-8"))
+}
+
+fn cell_to_string(cell: &Data) -> String {
+    match cell {
+        Data::Empty | Data::Error(_) => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_workbook() {
+        let workbook = MddWorkbook::open("tests/data/test_data.xlsx").unwrap();
+        assert_eq!(workbook.species_sheet, "MDD_v2.2");
+        assert_eq!(workbook.synonym_sheet.as_deref(), Some("Species_Syn_v2.2"));
+
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = workbook.species().unwrap();
+        assert_eq!(species.len(), 112);
+        assert_eq!(species[0].id, 1001076);
+        assert_eq!(
+            species[0].to_json(),
+            MddData::new().from_csv(&csv_data)[0].to_json()
+        );
+        let synonyms = workbook.synonyms().unwrap();
+        assert_eq!(synonyms.len(), 5);
+        assert_eq!(synonyms[0].syn_id, 100022090);
+    }
+}
@@ -0,0 +1,360 @@
+//! Python bindings (enabled with the `python` feature).
+//!
+//! Exposes the parsers and bundles as the `mdd_api` extension module, so
+//! Python workflows read MDD releases with the same rules as the CLI
+//! instead of re-parsing the CSVs. Records convert to plain dicts in the
+//! serde shapes of the JSON bundle; the `*_records` methods return flat
+//! row lists that load straight into a data frame:
+//!
+//! ```python
+//! import pandas as pd
+//! from mdd_api import CountryMDDStats, ReleasedMddData
+//!
+//! release = ReleasedMddData.from_csv(species_csv, synonym_csv, "2.2", "2025-06-01")
+//! species = pd.DataFrame(release.species_records())
+//! countries = pd.DataFrame(CountryMDDStats.from_release(release).to_records())
+//! ```
+//!
+//! Parse errors are raised as `ValueError` carrying the `MddError` message.
+//! Build the wheel with maturin (see `pyproject.toml`), which adds
+//! `pyo3/extension-module`:
+//!
+//! ```text
+//! maturin build --release
+//! ```
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+use serde::Serialize;
+
+use crate::parser::{
+    country::{CountryData, CountryMDDStats},
+    error::MddError,
+    mdd::MddData,
+    synonyms::SynonymData,
+    ReleasedMddData,
+};
+
+impl From<MddError> for PyErr {
+    fn from(err: MddError) -> Self {
+        PyValueError::new_err(err.to_string())
+    }
+}
+
+/// Convert a serializable value to Python objects through `json.loads`, so
+/// dicts carry the JSON bundle keys.
+fn to_python<'py, T: Serialize + ?Sized>(
+    py: Python<'py>,
+    value: &T,
+) -> PyResult<Bound<'py, PyAny>> {
+    let json = serde_json::to_string(value).map_err(MddError::from)?;
+    py.import("json")?.call_method1("loads", (json,))
+}
+
+fn to_json<T: Serialize + ?Sized>(value: &T) -> PyResult<String> {
+    Ok(serde_json::to_string(value).map_err(MddError::from)?)
+}
+
+/// One species record.
+#[pyclass(name = "MddData", module = "mdd_api", frozen)]
+#[derive(Clone)]
+pub struct PyMddData {
+    inner: MddData,
+}
+
+#[pymethods]
+impl PyMddData {
+    /// Parse a species CSV.
+    #[staticmethod]
+    fn from_csv(csv_data: &str) -> PyResult<Vec<Self>> {
+        let species = MddData::new().try_from_csv(csv_data)?;
+        Ok(species.into_iter().map(|inner| Self { inner }).collect())
+    }
+
+    #[getter]
+    fn id(&self) -> u32 {
+        self.inner.id
+    }
+
+    #[getter]
+    fn sci_name(&self) -> &str {
+        &self.inner.sci_name
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json(&self.inner)
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_python(py, &self.inner)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "MddData(id={}, sci_name='{}')",
+            self.inner.id, self.inner.sci_name
+        )
+    }
+}
+
+/// One synonym record.
+#[pyclass(name = "SynonymData", module = "mdd_api", frozen)]
+#[derive(Clone)]
+pub struct PySynonymData {
+    inner: SynonymData,
+}
+
+#[pymethods]
+impl PySynonymData {
+    /// Parse a synonym CSV.
+    #[staticmethod]
+    fn from_csv(csv_data: &str) -> PyResult<Vec<Self>> {
+        let synonyms = SynonymData::new().try_from_csv(csv_data)?;
+        Ok(synonyms.into_iter().map(|inner| Self { inner }).collect())
+    }
+
+    #[getter]
+    fn syn_id(&self) -> u32 {
+        self.inner.syn_id
+    }
+
+    /// MDD id of the accepted species, or `None` for synonym-only names.
+    #[getter]
+    fn species_id(&self) -> Option<u32> {
+        self.inner.species_id
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json(&self.inner)
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_python(py, &self.inner)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SynonymData(syn_id={}, root_name='{}')",
+            self.inner.syn_id,
+            self.inner.root_name()
+        )
+    }
+}
+
+/// Release bundle: metadata, species with their synonyms, and synonym-only
+/// names.
+#[pyclass(name = "ReleasedMddData", module = "mdd_api", frozen)]
+pub struct PyReleasedMddData {
+    inner: ReleasedMddData,
+}
+
+#[pymethods]
+impl PyReleasedMddData {
+    /// Parse species and synonym CSVs into a release bundle.
+    #[staticmethod]
+    fn from_csv(
+        species_csv: &str,
+        synonym_csv: &str,
+        version: &str,
+        release_date: &str,
+    ) -> PyResult<Self> {
+        let species = MddData::new().try_from_csv(species_csv)?;
+        let synonyms = SynonymData::new().try_from_csv(synonym_csv)?;
+        Ok(Self {
+            inner: ReleasedMddData::from_parser(species, synonyms, version, release_date),
+        })
+    }
+
+    /// Load a JSON bundle written by `mdd json`.
+    #[staticmethod]
+    fn from_json(json_data: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: ReleasedMddData::try_from_json(json_data)?,
+        })
+    }
+
+    #[getter]
+    fn version(&self) -> &str {
+        self.inner.get_version()
+    }
+
+    #[getter]
+    fn release_date(&self) -> &str {
+        self.inner.get_release_date()
+    }
+
+    /// Release metadata as a dict.
+    fn metadata<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_python(py, &self.inner.metadata)
+    }
+
+    fn species(&self) -> Vec<PyMddData> {
+        self.species_data()
+            .map(|species| PyMddData {
+                inner: species.clone(),
+            })
+            .collect()
+    }
+
+    /// Synonyms of every species followed by the synonym-only names.
+    fn synonyms(&self) -> Vec<PySynonymData> {
+        self.inner
+            .all_synonyms()
+            .map(|synonym| PySynonymData {
+                inner: synonym.clone(),
+            })
+            .collect()
+    }
+
+    /// One dict per species, without the nested synonyms.
+    fn species_records<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_python(py, &self.species_data().collect::<Vec<_>>())
+    }
+
+    /// One dict per synonym, in the order of `synonyms`.
+    fn synonym_records<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_python(py, &self.inner.all_synonyms().collect::<Vec<_>>())
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json(&self.inner)
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_python(py, &self.inner)
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.data.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ReleasedMddData(version='{}', species={})",
+            self.inner.get_version(),
+            self.inner.data.len()
+        )
+    }
+}
+
+impl PyReleasedMddData {
+    fn species_data(&self) -> impl Iterator<Item = &MddData> {
+        self.inner.species()
+    }
+}
+
+/// Per-country species statistics.
+#[pyclass(name = "CountryMDDStats", module = "mdd_api", frozen)]
+pub struct PyCountryMDDStats {
+    inner: CountryMDDStats,
+}
+
+/// Country statistics row with its code.
+#[derive(Serialize)]
+struct CountryRow<'a> {
+    code: &'a str,
+    #[serde(flatten)]
+    data: &'a CountryData,
+}
+
+#[pymethods]
+impl PyCountryMDDStats {
+    /// Aggregate the species of a species CSV.
+    #[staticmethod]
+    fn from_csv(csv_data: &str) -> PyResult<Self> {
+        let species = MddData::new().try_from_csv(csv_data)?;
+        Ok(Self::from_species(&species))
+    }
+
+    /// Aggregate the species of a release bundle.
+    #[staticmethod]
+    fn from_release(release: &PyReleasedMddData) -> Self {
+        let species: Vec<MddData> = release.species_data().cloned().collect();
+        Self::from_species(&species)
+    }
+
+    #[getter]
+    fn total_countries(&self) -> u32 {
+        self.inner.total_countries
+    }
+
+    /// One dict per country, with its code under `code`.
+    fn to_records<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let rows: Vec<CountryRow> = self
+            .inner
+            .country_data
+            .iter()
+            .map(|(code, data)| CountryRow { code, data })
+            .collect();
+        to_python(py, &rows)
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json(&self.inner)
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_python(py, &self.inner)
+    }
+}
+
+impl PyCountryMDDStats {
+    fn from_species(species: &[MddData]) -> Self {
+        let mut inner = CountryMDDStats::new();
+        inner.parse_country_data(species);
+        Self { inner }
+    }
+}
+
+#[pymodule]
+fn mdd_api(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyMddData>()?;
+    m.add_class::<PySynonymData>()?;
+    m.add_class::<PyReleasedMddData>()?;
+    m.add_class::<PyCountryMDDStats>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::types::{PyDict, PyList};
+
+    use super::*;
+
+    #[test]
+    fn test_python_bindings() {
+        let species_csv = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let synonym_csv = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        Python::initialize();
+        Python::attach(|py| {
+            let species = PyMddData::from_csv(&species_csv).unwrap();
+            assert_eq!(species.len(), 112);
+            let record = species[0].to_dict(py).unwrap();
+            let record = record.cast::<PyDict>().unwrap();
+            let sci_name: String = record
+                .get_item("sciName")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(sci_name, "Bunolagus_monticularis");
+            let err = PyMddData::from_csv("not,a\nspecies,csv").err().unwrap();
+            assert!(err.is_instance_of::<PyValueError>(py));
+
+            let release =
+                PyReleasedMddData::from_csv(&species_csv, &synonym_csv, "2.0", "2025-01-01")
+                    .unwrap();
+            assert_eq!(release.__len__(), 112);
+            let rows = release.species_records(py).unwrap();
+            assert_eq!(rows.cast::<PyList>().unwrap().len(), 112);
+            let restored = PyReleasedMddData::from_json(&release.to_json().unwrap()).unwrap();
+            assert_eq!(restored.version(), "2.0");
+
+            let stats = PyCountryMDDStats::from_release(&release);
+            let rows = stats.to_records(py).unwrap();
+            let rows = rows.cast::<PyList>().unwrap();
+            assert_eq!(rows.len() as u32, stats.total_countries());
+            let first = rows.get_item(0).unwrap();
+            assert!(first.cast::<PyDict>().unwrap().contains("code").unwrap());
+        });
+    }
+}
@@ -0,0 +1,243 @@
+//! Triage report of flagged species (`flagged == 1`).
+//!
+//! MDD flags species whose treatment changed or needs a second look, without
+//! recording why. `FlaggedReport` collects the flagged species grouped by
+//! order and family and guesses a `FlagReason` from `taxonomyNotes`:
+//!
+//! | reason          | notes mention                                        |
+//! |-----------------|------------------------------------------------------|
+//! | `split`         | `split from`, `split`                                |
+//! | `lump`          | `lumped`, `synonymized`, `merged`, `includes`        |
+//! | `newSpecies`    | `newly described`, `recently described`, `new species` |
+//! | `transfer`      | `transferred`, `moved to`, `placed in`               |
+//! | `nameChange`    | `name change`, `spelling`, `emend`, `replacement name` |
+//! | `unknown`       | none of the above, or no notes                       |
+//!
+//! The first matching clause of the notes (split on `;` and sentence ends,
+//! keeping abbreviations such as `S. brasiliensis` together) is kept as
+//! `snippet` so editors see the relevant text without opening the full
+//! record. The report is written as JSON grouped by order and family
+//! and as a flat CSV (`flagged_records.{json,csv}`).
+
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{crosswalk::write_csv_table, helper::MDD_MISSING_VALUE, parser::mdd::MddData};
+
+/// File stem of the flagged record report.
+pub const FLAGGED_FILE: &str = "flagged_records";
+
+/// Keywords of each reason, checked in declaration order.
+const REASON_KEYWORDS: [(FlagReason, &[&str]); 5] = [
+    (FlagReason::Split, &["split"]),
+    (
+        FlagReason::Lump,
+        &["lumped", "synonymized", "synonymised", "merged", "includes"],
+    ),
+    (
+        FlagReason::NewSpecies,
+        &["newly described", "recently described", "new species"],
+    ),
+    (
+        FlagReason::Transfer,
+        &["transferred", "moved to", "placed in"],
+    ),
+    (
+        FlagReason::NameChange,
+        &["name change", "spelling", "emend", "replacement name"],
+    ),
+];
+
+/// Inferred cause of a flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FlagReason {
+    Split,
+    Lump,
+    NewSpecies,
+    Transfer,
+    NameChange,
+    Unknown,
+}
+
+/// One flagged species.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlaggedRecord {
+    pub id: u32,
+    pub sci_name: String,
+    pub order: String,
+    pub family: String,
+    pub reason: FlagReason,
+    /// Clause of `taxonomyNotes` the reason was inferred from (the first
+    /// clause for `unknown`); empty without notes.
+    pub snippet: String,
+    pub taxonomy_notes_citation: String,
+}
+
+impl FlaggedRecord {
+    pub fn from_species(species: &MddData) -> Self {
+        let (reason, snippet) = infer_reason(&species.taxonomy_notes);
+        Self {
+            id: species.id,
+            sci_name: species.sci_name.clone(),
+            order: species.taxon_order.clone(),
+            family: species.family.clone(),
+            reason,
+            snippet: snippet.to_string(),
+            taxonomy_notes_citation: species.taxonomy_notes_citation.clone(),
+        }
+    }
+}
+
+/// Flagged species of a release, grouped by order and family.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlaggedReport {
+    pub total_flagged: u32,
+    /// Flagged species per inferred reason.
+    pub reasons: BTreeMap<FlagReason, u32>,
+    /// Order → family → flagged species, in input order.
+    pub orders: BTreeMap<String, BTreeMap<String, Vec<FlaggedRecord>>>,
+}
+
+impl FlaggedReport {
+    /// Collect the species with `flagged == 1`.
+    pub fn from_species(species: &[MddData]) -> Self {
+        let mut report = Self::default();
+        for record in species.iter().filter(|s| s.flagged == 1) {
+            let record = FlaggedRecord::from_species(record);
+            report.total_flagged += 1;
+            *report.reasons.entry(record.reason).or_insert(0) += 1;
+            report
+                .orders
+                .entry(record.order.clone())
+                .or_default()
+                .entry(record.family.clone())
+                .or_default()
+                .push(record);
+        }
+        report
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total_flagged == 0
+    }
+
+    /// Every flagged species, by order, family, then input order.
+    pub fn records(&self) -> impl Iterator<Item = &FlaggedRecord> {
+        self.orders
+            .values()
+            .flat_map(|families| families.values().flatten())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).expect("Failed to serialize")
+    }
+
+    /// Write `flagged_records.json` (grouped) and `flagged_records.csv`
+    /// (one row per species) into `output_dir`, returning the written paths.
+    pub fn write_files(&self, output_dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        fs::create_dir_all(output_dir)?;
+        let json_path = output_dir.join(FLAGGED_FILE).with_extension("json");
+        fs::write(&json_path, self.to_json())?;
+        let csv_path = write_csv_table(self.records(), output_dir, FLAGGED_FILE)?;
+        Ok(vec![json_path, csv_path])
+    }
+}
+
+/// Guess the reason of a flag from taxonomy notes, with the clause it came from.
+pub fn infer_reason(notes: &str) -> (FlagReason, &str) {
+    let notes = notes.trim();
+    if notes.is_empty() || notes == MDD_MISSING_VALUE {
+        return (FlagReason::Unknown, "");
+    }
+    let clauses: Vec<&str> = notes
+        .split([';', '\n'])
+        .flat_map(split_sentences)
+        .map(str::trim)
+        .filter(|clause| !clause.is_empty())
+        .collect();
+    for (reason, keywords) in REASON_KEYWORDS {
+        let found = clauses.iter().find(|clause| {
+            let clause = clause.to_lowercase();
+            keywords.iter().any(|keyword| clause.contains(keyword))
+        });
+        if let Some(clause) = found {
+            return (reason, clause);
+        }
+    }
+    (
+        FlagReason::Unknown,
+        clauses.first().copied().unwrap_or_default(),
+    )
+}
+
+/// Split at `. ` followed by a capital letter, unless the period ends an
+/// abbreviation (a word of at most two letters, e.g. `S.` or `al.`).
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    for (dot, _) in text.match_indices(". ") {
+        let word = text[start..dot].rsplit(' ').next().unwrap_or_default();
+        let next_is_upper = text[dot + 2..].starts_with(|c: char| c.is_uppercase());
+        if word.chars().count() > 2 && next_is_upper {
+            sentences.push(&text[start..dot]);
+            start = dot + 2;
+        }
+    }
+    sentences.push(&text[start..]);
+    sentences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_reason() {
+        assert_eq!(
+            infer_reason("split from S. brasiliensis"),
+            (FlagReason::Split, "split from S. brasiliensis")
+        );
+        assert_eq!(
+            infer_reason("formerly in Lepus; transferred to Sylvilagus based on mtDNA"),
+            (
+                FlagReason::Transfer,
+                "transferred to Sylvilagus based on mtDNA"
+            )
+        );
+        assert_eq!(
+            infer_reason("Recently described by Smith et al. 2020. Known from S. Peru"),
+            (
+                FlagReason::NewSpecies,
+                "Recently described by Smith et al. 2020"
+            )
+        );
+        assert_eq!(infer_reason("NA"), (FlagReason::Unknown, ""));
+        assert_eq!(
+            infer_reason("see Smith 2020; needs review"),
+            (FlagReason::Unknown, "see Smith 2020")
+        );
+    }
+
+    #[test]
+    fn test_flagged_report() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let report = FlaggedReport::from_species(&species);
+        assert_eq!(report.total_flagged, 12);
+        assert_eq!(report.reasons[&FlagReason::Split], 10);
+        assert_eq!(report.reasons[&FlagReason::NewSpecies], 1);
+        let leporids = &report.orders["Lagomorpha"]["Leporidae"];
+        assert_eq!(leporids.len(), 12);
+        assert_eq!(leporids[0].sci_name, "Sylvilagus_andinus");
+        assert_eq!(report.records().count(), 12);
+    }
+}
@@ -0,0 +1,362 @@
+//! Homonym and duplicate-name detection for editorial review.
+//!
+//! `find_homonyms` groups names that collide and checks the homonymy
+//! declared in the synonym `senior_homonym` column:
+//!
+//! | check                 | severity | finding                                                   |
+//! |-----------------------|----------|-----------------------------------------------------------|
+//! | `duplicate-binomial`  | error    | two species with different ids share a scientific name    |
+//! | `duplicate-synonym`   | warning  | two synonyms share root name, author, and year            |
+//! | `unresolved-senior`   | warning  | `senior_homonym` does not match any synonym               |
+//! | `self-senior`         | error    | a synonym is declared its own senior homonym              |
+//! | `younger-senior`      | warning  | the declared senior homonym was described later           |
+//! | `undeclared-homonym`  | info     | same original combination, different authorship, no link  |
+//!
+//! `senior_homonym` may hold a synonym id or a name, optionally followed by
+//! its authorship (`Mus minutus Pallas, 1771`) and the id in brackets; it is
+//! resolved with `nomenclature::references::ReferenceResolver`. Names are
+//! compared case-insensitively with `_`, commas, and parentheses ignored.
+//! Spelling variants (`variant_of` set) are not reported as undeclared
+//! homonyms.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    nomenclature::references::{
+        authorship, display_name, is_blank, normalize, year, ReferenceResolver,
+    },
+    parser::{mdd::MddData, synonyms::SynonymData},
+};
+
+use super::{Issue, QualityReport, Severity};
+
+/// Records sharing one normalized name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NameGroup {
+    /// Name as written in the first record of the group.
+    pub name: String,
+    /// Species or synonym ids, in input order.
+    pub ids: Vec<u32>,
+}
+
+/// Kind of problem with a (declared or missing) senior homonym link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HomonymConflictKind {
+    Unresolved,
+    SelfReference,
+    YoungerSenior,
+    Undeclared,
+}
+
+impl HomonymConflictKind {
+    /// Stable check identifier used in `QualityReport` issues.
+    pub fn check(&self) -> &'static str {
+        match self {
+            Self::Unresolved => "unresolved-senior",
+            Self::SelfReference => "self-senior",
+            Self::YoungerSenior => "younger-senior",
+            Self::Undeclared => "undeclared-homonym",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::SelfReference => Severity::Error,
+            Self::Unresolved | Self::YoungerSenior => Severity::Warning,
+            Self::Undeclared => Severity::Info,
+        }
+    }
+}
+
+/// A junior name whose homonymy needs an editor's attention.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HomonymConflict {
+    pub kind: HomonymConflictKind,
+    pub junior_id: u32,
+    /// Original combination (or root name) and authorship of the junior name.
+    pub junior_name: String,
+    /// Resolved senior synonym, when found.
+    pub senior_id: Option<u32>,
+    /// `senior_homonym` verbatim, or the presumed senior name for undeclared homonyms.
+    pub senior: String,
+}
+
+/// Name collisions found across species and synonyms.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HomonymReport {
+    pub duplicate_binomials: Vec<NameGroup>,
+    pub duplicate_synonyms: Vec<NameGroup>,
+    pub conflicts: Vec<HomonymConflict>,
+}
+
+impl HomonymReport {
+    pub fn new() -> Self {
+        Self {
+            duplicate_binomials: Vec::new(),
+            duplicate_synonyms: Vec::new(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.duplicate_binomials.is_empty()
+            && self.duplicate_synonyms.is_empty()
+            && self.conflicts.is_empty()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).expect("Failed to serialize")
+    }
+
+    /// Add one issue per duplicate group and conflict to `report`.
+    pub fn push_issues(&self, report: &mut QualityReport) {
+        for group in &self.duplicate_binomials {
+            report.push(
+                Issue::new(
+                    Severity::Error,
+                    "duplicate-binomial",
+                    format!("{} is used by species {}", group.name, join_ids(&group.ids)),
+                )
+                .with_record(group.ids[0])
+                .with_field("sciName"),
+            );
+        }
+        for group in &self.duplicate_synonyms {
+            report.push(
+                Issue::new(
+                    Severity::Warning,
+                    "duplicate-synonym",
+                    format!(
+                        "{} is used by synonyms {}",
+                        group.name,
+                        join_ids(&group.ids)
+                    ),
+                )
+                .with_record(group.ids[0])
+                .with_field("rootName"),
+            );
+        }
+        for conflict in &self.conflicts {
+            let message = match conflict.kind {
+                HomonymConflictKind::Unresolved => format!(
+                    "{} names an unknown senior homonym: {}",
+                    conflict.junior_name, conflict.senior
+                ),
+                HomonymConflictKind::SelfReference => {
+                    format!("{} is its own senior homonym", conflict.junior_name)
+                }
+                HomonymConflictKind::YoungerSenior => format!(
+                    "{} is older than its senior homonym {}",
+                    conflict.junior_name, conflict.senior
+                ),
+                HomonymConflictKind::Undeclared => format!(
+                    "{} is a homonym of {} without a senior_homonym link",
+                    conflict.junior_name, conflict.senior
+                ),
+            };
+            report.push(
+                Issue::new(conflict.kind.severity(), conflict.kind.check(), message)
+                    .with_record(conflict.junior_id)
+                    .with_field("seniorHomonym"),
+            );
+        }
+    }
+}
+
+/// Find duplicate names and senior homonym conflicts.
+pub fn find_homonyms(species: &[MddData], synonyms: &[SynonymData]) -> HomonymReport {
+    let mut report = HomonymReport::new();
+    report.duplicate_binomials = group_duplicates(
+        species
+            .iter()
+            .map(|record| (record.id, record.sci_name.replace('_', " "))),
+    );
+    report.duplicate_synonyms = group_duplicates(synonyms.iter().map(|synonym| {
+        (
+            synonym.syn_id,
+            format!("{} {}", synonym.root_name, authorship(synonym)),
+        )
+    }));
+    check_declared_seniors(synonyms, &mut report);
+    check_undeclared_homonyms(synonyms, &mut report);
+    report
+}
+
+/// Groups of two or more distinct ids sharing a normalized name.
+fn group_duplicates<I>(names: I) -> Vec<NameGroup>
+where
+    I: IntoIterator<Item = (u32, String)>,
+{
+    let mut groups: Vec<NameGroup> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (id, name) in names {
+        let key = normalize(&name);
+        if key.is_empty() {
+            continue;
+        }
+        match index.get(&key) {
+            Some(&i) if !groups[i].ids.contains(&id) => groups[i].ids.push(id),
+            Some(_) => (),
+            None => {
+                index.insert(key, groups.len());
+                groups.push(NameGroup {
+                    name: name.trim().to_string(),
+                    ids: vec![id],
+                });
+            }
+        }
+    }
+    groups.retain(|group| group.ids.len() > 1);
+    groups
+}
+
+fn check_declared_seniors(synonyms: &[SynonymData], report: &mut HomonymReport) {
+    let resolver = ReferenceResolver::new(synonyms);
+    for junior in synonyms {
+        let reference = junior.senior_homonym.trim();
+        if is_blank(reference) {
+            continue;
+        }
+        let senior = resolver.resolve(reference, junior.syn_id);
+        let kind = match senior {
+            None => HomonymConflictKind::Unresolved,
+            Some(senior) if senior.syn_id == junior.syn_id => HomonymConflictKind::SelfReference,
+            Some(senior) => match (year(junior), year(senior)) {
+                (Some(junior_year), Some(senior_year)) if junior_year < senior_year => {
+                    HomonymConflictKind::YoungerSenior
+                }
+                _ => continue,
+            },
+        };
+        report.conflicts.push(HomonymConflict {
+            kind,
+            junior_id: junior.syn_id,
+            junior_name: full_name(junior),
+            senior_id: senior.map(|s| s.syn_id),
+            senior: reference.to_string(),
+        });
+    }
+}
+
+/// Report original combinations used with different authorships when none
+/// of the usages declares a senior homonym.
+fn check_undeclared_homonyms(synonyms: &[SynonymData], report: &mut HomonymReport) {
+    let mut groups: BTreeMap<String, Vec<&SynonymData>> = BTreeMap::new();
+    for synonym in synonyms {
+        if !is_blank(&synonym.variant_of) {
+            continue;
+        }
+        let key = normalize(&synonym.original_combination);
+        if !key.is_empty() {
+            groups.entry(key).or_default().push(synonym);
+        }
+    }
+    for usages in groups.values() {
+        if usages.iter().any(|s| !is_blank(&s.senior_homonym)) {
+            continue;
+        }
+        let senior = usages
+            .iter()
+            .min_by_key(|s| (year(s).unwrap_or(u16::MAX), s.syn_id))
+            .expect("non-empty group");
+        let senior_authorship = normalize(&authorship(senior));
+        for junior in usages {
+            if normalize(&authorship(junior)) == senior_authorship {
+                continue;
+            }
+            report.conflicts.push(HomonymConflict {
+                kind: HomonymConflictKind::Undeclared,
+                junior_id: junior.syn_id,
+                junior_name: full_name(junior),
+                senior_id: Some(senior.syn_id),
+                senior: full_name(senior),
+            });
+        }
+    }
+}
+
+fn full_name(synonym: &SynonymData) -> String {
+    let authorship = authorship(synonym);
+    if authorship.is_empty() {
+        display_name(synonym).trim().to_string()
+    } else {
+        format!("{} {}", display_name(synonym).trim(), authorship)
+    }
+}
+
+fn join_ids(ids: &[u32]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::synonyms::fixtures::synonym;
+
+    #[test]
+    fn test_find_homonyms() {
+        let species = vec![
+            MddData {
+                id: 1,
+                sci_name: "Panthera_leo".to_string(),
+                ..MddData::new()
+            },
+            MddData {
+                id: 2,
+                sci_name: "Panthera leo".to_string(),
+                ..MddData::new()
+            },
+        ];
+        let mut junior = synonym(12, "Mus minutus", "Rafinesque", "1814");
+        junior.senior_homonym = "Mus minutus Pallas, 1771".to_string();
+        let mut self_senior = synonym(13, "Sorex pusillus", "Gmelin", "1774");
+        self_senior.senior_homonym = "13".to_string();
+        let mut younger = synonym(14, "Sorex araneus", "Linnaeus", "1758");
+        younger.senior_homonym = "Mus minutus".to_string();
+        let mut unresolved = synonym(15, "Felis catus", "Linnaeus", "1758");
+        unresolved.senior_homonym = "Felis domestica Erxleben, 1777".to_string();
+        let synonyms = vec![
+            synonym(10, "Mus minutus", "Pallas", "1771"),
+            synonym(11, "Mus minutus", "Pallas", "1771"),
+            junior,
+            self_senior,
+            younger,
+            unresolved,
+            synonym(16, "Lepus timidus", "Linnaeus", "1758"),
+            synonym(17, "Lepus timidus", "Gray", "1843"),
+        ];
+
+        let report = find_homonyms(&species, &synonyms);
+        assert_eq!(report.duplicate_binomials[0].ids, vec![1, 2]);
+        assert_eq!(report.duplicate_synonyms.len(), 1);
+        assert_eq!(report.duplicate_synonyms[0].ids, vec![10, 11]);
+        let kinds: Vec<(HomonymConflictKind, u32, Option<u32>)> = report
+            .conflicts
+            .iter()
+            .map(|c| (c.kind, c.junior_id, c.senior_id))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (HomonymConflictKind::SelfReference, 13, Some(13)),
+                (HomonymConflictKind::YoungerSenior, 14, Some(10)),
+                (HomonymConflictKind::Unresolved, 15, None),
+                (HomonymConflictKind::Undeclared, 17, Some(16)),
+            ]
+        );
+
+        let mut quality = QualityReport::new();
+        report.push_issues(&mut quality);
+        assert_eq!(quality.count(Severity::Error), 2);
+        assert_eq!(quality.count(Severity::Info), 1);
+    }
+}
@@ -0,0 +1,192 @@
+//! Data-quality checks over parsed MDD records.
+//!
+//! Checks produce `Issue`s collected in a `QualityReport`, which serializes
+//! to JSON for tooling and renders a short human summary for editors. Every
+//! issue carries a stable `check` identifier (e.g. `duplicate-id`) so reports
+//! from different releases can be compared.
+//!
+//! `synonym_only` suggests accepted species for synonyms without a
+//! `species_id` and lists the ones it cannot place.
+//!
+//! `type_country` (`geo` feature) checks synonym type coordinates against
+//! country outlines supplied as GeoJSON.
+
+use serde::{Deserialize, Serialize};
+
+use crate::parser::options::ParseReport;
+
+pub mod flagged;
+pub mod homonyms;
+pub mod synonym_only;
+#[cfg(feature = "geo")]
+pub mod type_country;
+pub mod validate;
+
+/// How serious an issue is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    /// Worth a look, but not necessarily wrong.
+    Info,
+    /// Probably wrong; does not break downstream parsing.
+    Warning,
+    /// Inconsistent data that downstream consumers will trip over.
+    Error,
+}
+
+/// A single finding.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Issue {
+    pub severity: Severity,
+    /// Stable identifier of the check that produced the issue.
+    pub check: String,
+    /// MDD species id or synonym id of the offending record, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_id: Option<u32>,
+    /// Serialized (camelCase) field name, when the issue concerns one field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl Issue {
+    pub fn new(severity: Severity, check: &str, message: String) -> Self {
+        Self {
+            severity,
+            check: check.to_string(),
+            record_id: None,
+            field: None,
+            message,
+        }
+    }
+
+    pub fn with_record(mut self, record_id: u32) -> Self {
+        self.record_id = Some(record_id);
+        self
+    }
+
+    pub fn with_field(mut self, field: &str) -> Self {
+        self.field = Some(field.to_string());
+        self
+    }
+}
+
+/// Outcome of a quality run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityReport {
+    pub species_checked: usize,
+    pub synonyms_checked: usize,
+    pub issues: Vec<Issue>,
+}
+
+impl QualityReport {
+    pub fn new() -> Self {
+        Self {
+            species_checked: 0,
+            synonyms_checked: 0,
+            issues: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, issue: Issue) {
+        self.issues.push(issue);
+    }
+
+    /// Record the rows skipped by a lenient parse as `parse-error` issues.
+    ///
+    /// `source` names the input (e.g. `species` or `synonyms`) in the message.
+    pub fn push_skipped_rows(&mut self, source: &str, parse_report: &ParseReport) {
+        for skipped in &parse_report.skipped {
+            let row = skipped
+                .row
+                .map(|r| r.to_string())
+                .unwrap_or_else(|| "?".to_string());
+            let mut issue = Issue::new(
+                Severity::Error,
+                "parse-error",
+                format!(
+                    "{} row {} could not be parsed: {}",
+                    source, row, skipped.cause
+                ),
+            );
+            issue.field = skipped.field.clone();
+            self.push(issue);
+        }
+    }
+
+    /// Highest severity present, or `None` for a clean report.
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.issues.iter().map(|i| i.severity).max()
+    }
+
+    /// Number of issues with the given severity.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == severity)
+            .count()
+    }
+
+    /// Issue counts per check identifier, sorted by identifier.
+    pub fn count_by_check(&self) -> Vec<(String, usize)> {
+        let mut counts = std::collections::BTreeMap::new();
+        for issue in &self.issues {
+            *counts.entry(issue.check.clone()).or_insert(0) += 1;
+        }
+        counts.into_iter().collect()
+    }
+
+    /// Process exit code for the report: `1` when errors were found (or
+    /// warnings, with `deny_warnings`), otherwise `0`.
+    pub fn exit_code(&self, deny_warnings: bool) -> i32 {
+        match self.max_severity() {
+            Some(Severity::Error) => 1,
+            Some(Severity::Warning) if deny_warnings => 1,
+            _ => 0,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).expect("Failed to serialize")
+    }
+
+    /// Short human-readable summary.
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "Checked {} species and {} synonyms: {} errors, {} warnings, {} notes\n",
+            self.species_checked,
+            self.synonyms_checked,
+            self.count(Severity::Error),
+            self.count(Severity::Warning),
+            self.count(Severity::Info),
+        );
+        for (check, count) in self.count_by_check() {
+            summary.push_str(&format!("  {}: {}\n", check, count));
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code() {
+        let mut report = QualityReport::new();
+        assert_eq!(report.exit_code(true), 0);
+        report.push(Issue::new(
+            Severity::Warning,
+            "invalid-iucn",
+            "XX".to_string(),
+        ));
+        assert_eq!(report.exit_code(false), 0);
+        assert_eq!(report.exit_code(true), 1);
+        report.push(Issue::new(Severity::Error, "duplicate-id", "1".to_string()).with_record(1));
+        assert_eq!(report.exit_code(false), 1);
+        assert_eq!(report.count_by_check()[0], ("duplicate-id".to_string(), 1));
+        assert!(report.summary().contains("1 errors, 1 warnings"));
+    }
+}
@@ -0,0 +1,420 @@
+//! Accepted-name candidates for synonym-only entries.
+//!
+//! Synonyms without a `species_id` end up in `ReleasedMddData.synonym_only`
+//! with no link to an accepted species. `SynonymOnlyReport` tries to place
+//! each of them by its `genus` and `specific_epithet` columns (falling back
+//! to the first two words of the original combination) against the species
+//! table:
+//!
+//! | confidence | match                                                  |
+//! |------------|--------------------------------------------------------|
+//! | `high`     | same genus and epithet                                 |
+//! | `medium`   | same genus, epithet differing in its Latin ending      |
+//! | `low`      | same epithet stem in another genus of the same family  |
+//!
+//! Endings are compared without gender and `-i` / `-ii` genitive variants
+//! (`niger` / `nigra` / `nigrum`, `smithi` / `smithii`). Lower tiers are
+//! only tried when no higher tier matched, so a name with an
+//! exact match is not padded with far-fetched ones. Entries without any
+//! candidate are the unresolved residue, reported as `unresolved-synonym`
+//! issues and written as `synonym_only_unresolved.csv` for editors; the full
+//! annotated list is `synonym_only.json`.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    helper::MDD_MISSING_VALUE,
+    parser::{mdd::MddData, synonyms::SynonymData, ReleasedMddData},
+};
+
+use super::{Issue, Severity};
+
+/// File stem of the annotated synonym-only list.
+pub const SYNONYM_ONLY_FILE: &str = "synonym_only";
+/// File stem of the unresolved synonym-only list.
+pub const UNRESOLVED_SYNONYM_FILE: &str = "synonym_only_unresolved";
+
+/// Latin gender and genitive endings, longest first, stripped to compare
+/// epithets (`niger` / `nigra` / `nigrum`, `brevis` / `breve`).
+const EPITHET_ENDINGS: [&str; 9] = ["rum", "ra", "er", "us", "um", "is", "a", "e", "i"];
+
+/// How closely a candidate matches a synonym-only name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchConfidence {
+    /// Same epithet in another genus of the same family.
+    Low,
+    /// Same genus, epithet differing in its ending.
+    Medium,
+    /// Same genus and epithet.
+    High,
+}
+
+/// An accepted species a synonym-only name may belong to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptedCandidate {
+    pub species_id: u32,
+    pub sci_name: String,
+    pub confidence: MatchConfidence,
+}
+
+/// A synonym-only entry with its candidates, best first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SynonymResolution {
+    pub syn_id: u32,
+    pub root_name: String,
+    pub original_combination: String,
+    pub family: String,
+    /// Genus the entry was matched with; empty when it has none.
+    pub genus: String,
+    /// Epithet the entry was matched with; empty when it has none.
+    pub specific_epithet: String,
+    pub candidates: Vec<AcceptedCandidate>,
+}
+
+impl SynonymResolution {
+    /// The best candidate; the first of equally good ones.
+    pub fn best(&self) -> Option<&AcceptedCandidate> {
+        self.candidates.first()
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        !self.candidates.is_empty()
+    }
+}
+
+/// A row of `synonym_only_unresolved.csv`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnresolvedRow {
+    pub syn_id: u32,
+    pub root_name: String,
+    pub original_combination: String,
+    pub family: String,
+    pub genus: String,
+    pub specific_epithet: String,
+}
+
+/// Candidates of every synonym-only entry of a release.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SynonymOnlyReport {
+    pub total: usize,
+    pub resolved: usize,
+    /// Entries with at least one candidate, per confidence of the best one.
+    pub by_confidence: BTreeMap<MatchConfidence, usize>,
+    /// Every entry, in input order.
+    pub entries: Vec<SynonymResolution>,
+}
+
+impl SynonymOnlyReport {
+    /// Match the `synonym_only` rows of `release` against its species.
+    pub fn from_release(release: &ReleasedMddData) -> Self {
+        let species: Vec<&MddData> = release.species().collect();
+        Self::build(&species, release.synonym_only.iter())
+    }
+
+    /// Match the rows of `synonyms` without a `species_id` against `species`.
+    pub fn from_records(species: &[MddData], synonyms: &[SynonymData]) -> Self {
+        let species: Vec<&MddData> = species.iter().collect();
+        Self::build(
+            &species,
+            synonyms
+                .iter()
+                .filter(|synonym| synonym.species_id.is_none()),
+        )
+    }
+
+    fn build<'a>(species: &[&MddData], synonyms: impl Iterator<Item = &'a SynonymData>) -> Self {
+        let table = SpeciesTable::new(species);
+        let mut report = Self::default();
+        for synonym in synonyms {
+            let resolution = table.resolve(synonym);
+            report.total += 1;
+            if let Some(best) = resolution.best() {
+                report.resolved += 1;
+                *report.by_confidence.entry(best.confidence).or_insert(0) += 1;
+            }
+            report.entries.push(resolution);
+        }
+        report
+    }
+
+    /// Entries without any candidate, in input order.
+    pub fn unresolved(&self) -> impl Iterator<Item = &SynonymResolution> {
+        self.entries.iter().filter(|entry| !entry.is_resolved())
+    }
+
+    /// One `unresolved-synonym` note per unresolved entry.
+    pub fn issues(&self) -> Vec<Issue> {
+        self.unresolved()
+            .map(|entry| {
+                Issue::new(
+                    Severity::Info,
+                    "unresolved-synonym",
+                    format!(
+                        "synonym {} ({}) has no species_id and matches no accepted species",
+                        entry.syn_id,
+                        display_name(entry)
+                    ),
+                )
+                .with_record(entry.syn_id)
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).expect("Failed to serialize")
+    }
+
+    /// Write `synonym_only.json` (every entry with its candidates) and
+    /// `synonym_only_unresolved.csv` (the unresolved entries) into
+    /// `output_dir`, returning the written paths.
+    pub fn write_files(&self, output_dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        fs::create_dir_all(output_dir)?;
+        let json_path = output_dir.join(SYNONYM_ONLY_FILE).with_extension("json");
+        fs::write(&json_path, self.to_json())?;
+        let csv_path = output_dir
+            .join(UNRESOLVED_SYNONYM_FILE)
+            .with_extension("csv");
+        let mut wtr = csv::Writer::from_path(&csv_path)?;
+        for entry in self.unresolved() {
+            wtr.serialize(UnresolvedRow {
+                syn_id: entry.syn_id,
+                root_name: entry.root_name.clone(),
+                original_combination: entry.original_combination.clone(),
+                family: entry.family.clone(),
+                genus: entry.genus.clone(),
+                specific_epithet: entry.specific_epithet.clone(),
+            })?;
+        }
+        wtr.flush()?;
+        Ok(vec![json_path, csv_path])
+    }
+}
+
+/// Species keyed by lower-cased genus and by epithet stem.
+struct SpeciesTable<'a> {
+    by_genus: HashMap<String, Vec<&'a MddData>>,
+    by_stem: HashMap<String, Vec<&'a MddData>>,
+}
+
+impl<'a> SpeciesTable<'a> {
+    fn new(species: &[&'a MddData]) -> Self {
+        let mut table = Self {
+            by_genus: HashMap::new(),
+            by_stem: HashMap::new(),
+        };
+        for record in species {
+            table
+                .by_genus
+                .entry(record.genus.trim().to_lowercase())
+                .or_default()
+                .push(record);
+            table
+                .by_stem
+                .entry(epithet_stem(&record.specific_epithet.to_lowercase()))
+                .or_default()
+                .push(record);
+        }
+        table
+    }
+
+    fn resolve(&self, synonym: &SynonymData) -> SynonymResolution {
+        let (genus, epithet) = binomial_parts(synonym);
+        let candidates = if genus.is_empty() || epithet.is_empty() {
+            Vec::new()
+        } else {
+            self.candidates(&genus, &epithet, &clean(&synonym.family))
+        };
+        SynonymResolution {
+            syn_id: synonym.syn_id,
+            root_name: synonym.root_name.clone(),
+            original_combination: synonym.original_combination.clone(),
+            family: synonym.family.clone(),
+            genus,
+            specific_epithet: epithet,
+            candidates,
+        }
+    }
+
+    fn candidates(&self, genus: &str, epithet: &str, family: &str) -> Vec<AcceptedCandidate> {
+        let genus_key = genus.to_lowercase();
+        let epithet_key = epithet.to_lowercase();
+        let stem = epithet_stem(&epithet_key);
+        let congeners = self.by_genus.get(&genus_key).map(Vec::as_slice);
+        let exact = congeners
+            .unwrap_or_default()
+            .iter()
+            .filter(|record| record.specific_epithet.to_lowercase() == epithet_key);
+        let found = candidates(exact, MatchConfidence::High);
+        if !found.is_empty() {
+            return found;
+        }
+        let variants = congeners
+            .unwrap_or_default()
+            .iter()
+            .filter(|record| epithet_stem(&record.specific_epithet.to_lowercase()) == stem);
+        let found = candidates(variants, MatchConfidence::Medium);
+        if !found.is_empty() || family.is_empty() {
+            return found;
+        }
+        let transferred = self
+            .by_stem
+            .get(&stem)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .filter(|record| record.family.eq_ignore_ascii_case(family));
+        candidates(transferred, MatchConfidence::Low)
+    }
+}
+
+fn candidates<'r, 'a: 'r>(
+    records: impl Iterator<Item = &'r &'a MddData>,
+    confidence: MatchConfidence,
+) -> Vec<AcceptedCandidate> {
+    records
+        .map(|record| AcceptedCandidate {
+            species_id: record.id,
+            sci_name: record.sci_name.clone(),
+            confidence,
+        })
+        .collect()
+}
+
+/// Genus and epithet of a synonym: its `genus` / `specific_epithet` columns,
+/// else the first two words of the original combination (skipping a
+/// subgenus in parentheses).
+fn binomial_parts(synonym: &SynonymData) -> (String, String) {
+    let genus = clean(&synonym.genus);
+    let epithet = clean(&synonym.specific_epithet);
+    if !genus.is_empty() && !epithet.is_empty() {
+        return (genus, epithet);
+    }
+    let mut words = synonym
+        .original_combination
+        .split([' ', '_'])
+        .filter(|word| !word.is_empty() && !word.starts_with('('));
+    match (words.next(), words.next()) {
+        (Some(genus), Some(epithet)) => (genus.to_string(), epithet.to_string()),
+        _ => (genus, epithet),
+    }
+}
+
+fn clean(value: &str) -> String {
+    match value.trim() {
+        MDD_MISSING_VALUE => String::new(),
+        value => value.to_string(),
+    }
+}
+
+/// Epithet without its ending, `-ii` read as `-i`. Epithets shorter than
+/// five letters are kept whole so short names do not collapse together.
+fn epithet_stem(epithet: &str) -> String {
+    let epithet = epithet
+        .strip_suffix("ii")
+        .map_or(epithet.to_string(), |s| format!("{}i", s));
+    if epithet.chars().count() < 5 {
+        return epithet;
+    }
+    EPITHET_ENDINGS
+        .iter()
+        .find_map(|ending| epithet.strip_suffix(ending))
+        .unwrap_or(&epithet)
+        .to_string()
+}
+
+fn display_name(entry: &SynonymResolution) -> String {
+    if !entry.original_combination.trim().is_empty() {
+        return entry.original_combination.clone();
+    }
+    entry.root_name.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn species(id: u32, family: &str, genus: &str, epithet: &str) -> MddData {
+        MddData {
+            id,
+            sci_name: format!("{}_{}", genus, epithet),
+            family: family.to_string(),
+            genus: genus.to_string(),
+            specific_epithet: epithet.to_string(),
+            ..MddData::new()
+        }
+    }
+
+    fn synonym(syn_id: u32, family: &str, genus: &str, epithet: &str) -> SynonymData {
+        let mut synonym = SynonymData::new();
+        synonym.syn_id = syn_id;
+        synonym.family = family.to_string();
+        synonym.genus = genus.to_string();
+        synonym.specific_epithet = epithet.to_string();
+        synonym
+    }
+
+    #[test]
+    fn test_synonym_only_report() {
+        let species = [
+            species(1, "Felidae", "Felis", "silvestris"),
+            species(2, "Muridae", "Mus", "niger"),
+            species(3, "Muridae", "Rattus", "tanezumi"),
+        ];
+        let mut by_combination = synonym(13, "Muridae", "", "");
+        by_combination.original_combination = "Mus tanezumi Temminck, 1844".to_string();
+        let mut attached = synonym(15, "Felidae", "Felis", "silvestris");
+        attached.species_id = Some(1);
+        let synonyms = [
+            synonym(10, "Felidae", "Felis", "Silvestris"),
+            synonym(11, "Muridae", "Mus", "nigra"),
+            synonym(12, "Muridae", "Apodemus", "nigrum"),
+            by_combination,
+            synonym(14, "Canidae", "Canis", "dirus"),
+            attached,
+        ];
+        let report = SynonymOnlyReport::from_records(&species, &synonyms);
+        assert_eq!(report.total, 5);
+        assert_eq!(report.resolved, 4);
+
+        let best = |syn_id: u32| {
+            let entry = report.entries.iter().find(|e| e.syn_id == syn_id).unwrap();
+            entry
+                .best()
+                .map(|candidate| (candidate.species_id, candidate.confidence))
+        };
+        assert_eq!(best(10), Some((1, MatchConfidence::High)));
+        assert_eq!(best(11), Some((2, MatchConfidence::Medium)));
+        assert_eq!(best(12), Some((2, MatchConfidence::Low)));
+        // Genus and epithet from the original combination: Rattus tanezumi
+        // is in the same family under another genus.
+        assert_eq!(best(13), Some((3, MatchConfidence::Low)));
+        assert_eq!(best(14), None);
+
+        let unresolved: Vec<u32> = report.unresolved().map(|e| e.syn_id).collect();
+        assert_eq!(unresolved, [14]);
+        let issues = report.issues();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].check, "unresolved-synonym");
+        assert_eq!(issues[0].record_id, Some(14));
+    }
+
+    #[test]
+    fn test_epithet_stem() {
+        assert_eq!(epithet_stem("niger"), epithet_stem("nigrum"));
+        assert_eq!(epithet_stem("brevis"), epithet_stem("breve"));
+        assert_eq!(epithet_stem("smithii"), epithet_stem("smithi"));
+        assert_ne!(epithet_stem("leo"), epithet_stem("lea"));
+    }
+}
@@ -0,0 +1,274 @@
+//! Reverse geocoding check of synonym type localities (`geo` feature).
+//!
+//! | check                   | severity | finding                                          |
+//! |-------------------------|----------|--------------------------------------------------|
+//! | `type-country-mismatch` | warning  | type coordinates fall outside `type_country`     |
+//!
+//! `CountryShapes` holds one `geo::Region` per country, read from a GeoJSON
+//! `FeatureCollection` such as Natural Earth's simplified admin-0 countries.
+//! `CountryShapes::bundled` reads the admin-0 outlines shipped with the crate,
+//! simplified to one box per country (plus one per distant island group), so
+//! it catches sign errors and swapped coordinates but not localities just
+//! across a border; pass detailed outlines for those.
+//! Features are looked up by ISO alpha-2 code (`ISO_A2`, `iso_a2`) and by
+//! name (`NAME`, `ADMIN`, `name`, ...); `type_country` values are matched
+//! through `helper::country_code`, then by name, ignoring case. Countries
+//! without a shape are not checked.
+//!
+//! Simplified outlines miss coastal and border localities, so a point within
+//! `tolerance` degrees (default 0.25) of the outline counts as inside. When
+//! the point falls inside the country with a sign flipped or latitude and
+//! longitude swapped, the issue says so; those are the usual source errors.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{
+    geo::{Area, GeoError, Region},
+    helper::{
+        coords::{MAX_LATITUDE, MAX_LONGITUDE},
+        country_code::get_country_code,
+    },
+    nomenclature::references::{display_name, is_blank},
+    parser::{detail::TypeLocality, synonyms::SynonymData},
+};
+
+use super::{Issue, QualityReport, Severity};
+
+/// Simplified admin-0 outlines bundled with the crate.
+const BUNDLED_SHAPES: &str = include_str!("admin0_bounds.geojson");
+
+/// Default tolerance around country outlines, in degrees.
+pub const DEFAULT_TOLERANCE: f64 = 0.25;
+
+/// GeoJSON properties holding an ISO alpha-2 code.
+const ISO_PROPERTIES: [&str; 4] = ["ISO_A2", "iso_a2", "ISO_A2_EH", "iso_a2_eh"];
+/// GeoJSON properties holding a country name.
+const NAME_PROPERTIES: [&str; 7] = [
+    "NAME",
+    "name",
+    "ADMIN",
+    "admin",
+    "NAME_LONG",
+    "name_long",
+    "name_en",
+];
+
+/// Country outlines keyed by ISO code and lower-case name.
+#[derive(Debug, Clone)]
+pub struct CountryShapes {
+    regions: Vec<Region>,
+    keys: HashMap<String, usize>,
+    tolerance: f64,
+}
+
+impl CountryShapes {
+    /// Read a GeoJSON `FeatureCollection` of countries.
+    pub fn from_geojson(geojson: &str) -> Result<Self, GeoError> {
+        let value: Value =
+            serde_json::from_str(geojson).map_err(|e| GeoError::Json(e.to_string()))?;
+        let features = value
+            .get("features")
+            .and_then(Value::as_array)
+            .ok_or_else(|| GeoError::InvalidGeoJson("expected a FeatureCollection".to_string()))?;
+        let mut shapes = Self {
+            regions: Vec::new(),
+            keys: HashMap::new(),
+            tolerance: DEFAULT_TOLERANCE,
+        };
+        for feature in features {
+            let Some(properties) = feature.get("properties").and_then(Value::as_object) else {
+                continue;
+            };
+            let keys: Vec<String> = ISO_PROPERTIES
+                .iter()
+                .chain(NAME_PROPERTIES.iter())
+                .filter_map(|property| properties.get(*property)?.as_str())
+                .filter(|key| !key.is_empty() && *key != "-99")
+                .map(str::to_lowercase)
+                .collect();
+            if keys.is_empty() {
+                continue;
+            }
+            let region = match Region::from_geojson_value(feature) {
+                Ok(region) => region,
+                // Features without polygons (e.g. points) are not countries.
+                Err(GeoError::InvalidGeoJson(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            let index = shapes.regions.len();
+            shapes.regions.push(region);
+            for key in keys {
+                shapes.keys.entry(key).or_insert(index);
+            }
+        }
+        Ok(shapes)
+    }
+
+    /// The simplified admin-0 outlines bundled with the crate.
+    pub fn bundled() -> Self {
+        Self::from_geojson(BUNDLED_SHAPES).expect("Bundled country outlines are valid GeoJSON")
+    }
+
+    /// Degrees around the outlines that still count as inside.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance.max(0.0);
+        self
+    }
+
+    /// Number of country outlines.
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// The outline of `country`, an MDD country name or ISO alpha-2 code.
+    pub fn get(&self, country: &str) -> Option<&Region> {
+        let country = country.trim();
+        [get_country_code(country), country.to_string()]
+            .iter()
+            .find_map(|key| self.keys.get(&key.to_lowercase()))
+            .map(|&index| &self.regions[index])
+    }
+
+    /// Whether the point is inside `country`, allowing for the tolerance;
+    /// `None` when the country has no outline.
+    pub fn contains(&self, country: &str, latitude: f64, longitude: f64) -> Option<bool> {
+        let region = self.get(country)?;
+        let t = self.tolerance;
+        let offsets = [(0.0, 0.0), (t, 0.0), (-t, 0.0), (0.0, t), (0.0, -t)];
+        Some(
+            offsets
+                .iter()
+                .any(|(dlat, dlon)| region.contains(latitude + dlat, longitude + dlon)),
+        )
+    }
+}
+
+/// Add a `type-country-mismatch` issue for every synonym whose type
+/// coordinates fall outside its `type_country`.
+pub fn check_type_countries(
+    synonyms: &[SynonymData],
+    shapes: &CountryShapes,
+    report: &mut QualityReport,
+) {
+    for synonym in synonyms {
+        if is_blank(&synonym.type_country) {
+            continue;
+        }
+        let country = synonym.type_country.trim();
+        let Some((latitude, longitude)) = TypeLocality::from_synonym(synonym).point() else {
+            continue;
+        };
+        if shapes.contains(country, latitude, longitude) != Some(false) {
+            continue;
+        }
+        let hint = likely_error(shapes, country, latitude, longitude)
+            .map(|fix| format!("; it falls inside with {}", fix))
+            .unwrap_or_default();
+        report.push(
+            Issue::new(
+                Severity::Warning,
+                "type-country-mismatch",
+                format!(
+                    "Synonym {} ({}) type locality {}, {} is outside {}{}",
+                    synonym.syn_id,
+                    display_name(synonym),
+                    synonym.type_latitude.trim(),
+                    synonym.type_longitude.trim(),
+                    country,
+                    hint
+                ),
+            )
+            .with_record(synonym.syn_id)
+            .with_field("typeLatitude"),
+        );
+    }
+}
+
+/// The sign or order change that moves the point into `country`, if any.
+fn likely_error(
+    shapes: &CountryShapes,
+    country: &str,
+    latitude: f64,
+    longitude: f64,
+) -> Option<&'static str> {
+    let candidates = [
+        ("the latitude sign flipped", -latitude, longitude),
+        ("the longitude sign flipped", latitude, -longitude),
+        ("both signs flipped", -latitude, -longitude),
+        ("latitude and longitude swapped", longitude, latitude),
+    ];
+    candidates
+        .into_iter()
+        .filter(|(_, lat, lon)| lat.abs() <= MAX_LATITUDE && lon.abs() <= MAX_LONGITUDE)
+        .find(|(_, lat, lon)| shapes.contains(country, *lat, *lon) == Some(true))
+        .map(|(fix, _, _)| fix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn synonym(syn_id: u32, country: &str, latitude: &str, longitude: &str) -> SynonymData {
+        let mut synonym = SynonymData::new();
+        synonym.syn_id = syn_id;
+        synonym.root_name = format!("name{}", syn_id);
+        synonym.type_country = country.to_string();
+        synonym.type_latitude = latitude.to_string();
+        synonym.type_longitude = longitude.to_string();
+        synonym
+    }
+
+    #[test]
+    fn test_bundled_shapes() {
+        let shapes = CountryShapes::bundled();
+        assert!(shapes.len() > 200);
+        assert_eq!(shapes.contains("Peru", -12.0, -77.0), Some(true));
+        assert_eq!(shapes.contains("Peru", 12.0, -77.0), Some(false));
+        // Split at the antimeridian.
+        assert_eq!(shapes.contains("Russia", 65.0, -175.0), Some(true));
+        assert_eq!(
+            shapes.contains("Russian Federation", 55.75, 37.6),
+            Some(true)
+        );
+        assert_eq!(shapes.contains("Madagascar", 18.9, 47.5), Some(false));
+    }
+
+    #[test]
+    fn test_type_country_mismatch() {
+        // A rough box around Peru.
+        let geojson = r#"{"type": "FeatureCollection", "features": [{
+            "type": "Feature",
+            "properties": {"NAME": "Peru", "ISO_A2": "PE"},
+            "geometry": {"type": "Polygon", "coordinates": [
+                [[-81, -18], [-69, -18], [-69, 0], [-81, 0], [-81, -18]]
+            ]}
+        }]}"#;
+        let shapes = CountryShapes::from_geojson(geojson).unwrap();
+        assert_eq!(shapes.len(), 1);
+        assert!(shapes.get("peru").is_some());
+        assert_eq!(shapes.contains("Peru", -18.1, -75.0), Some(true));
+        assert_eq!(shapes.contains("Chile", -30.0, -70.0), None);
+
+        let synonyms = vec![
+            synonym(1, "Peru", "-12.0", "-77.0"),
+            synonym(2, "Peru", "12.0", "-77.0"),
+            synonym(3, "Peru", "-77.0", "-12.0"),
+            synonym(4, "Peru", "40.0", "10.0"),
+            synonym(5, "Chile", "40.0", "10.0"),
+            synonym(6, "Peru", "NA", "NA"),
+        ];
+        let mut report = QualityReport::new();
+        check_type_countries(&synonyms, &shapes, &mut report);
+        let ids: Vec<Option<u32>> = report.issues.iter().map(|i| i.record_id).collect();
+        assert_eq!(ids, [Some(2), Some(3), Some(4)]);
+        assert!(report.issues[0].message.contains("latitude sign flipped"));
+        assert!(report.issues[1].message.contains("swapped"));
+        assert!(report.issues[2].message.ends_with("is outside Peru"));
+    }
+}
@@ -0,0 +1,349 @@
+//! Consistency checks run by `mdd validate`.
+//!
+//! | check               | severity | finding                                          |
+//! |---------------------|----------|--------------------------------------------------|
+//! | `duplicate-id`      | error    | two species (or two synonyms) share an id        |
+//! | `missing-field`     | error    | a required species field is empty or `NA`        |
+//! | `orphan-synonym`    | error    | `species_id` points at a species not in the file |
+//! | `invalid-iucn`      | warning  | `iucnStatus` is not a Red List category          |
+//! | `coordinate-range`  | error    | type locality latitude/longitude out of range    |
+//! | `coordinate-swapped`| warning  | latitude out of range, but the pair fits swapped |
+//! | `coordinate-format` | warning  | coordinate is unreadable or not decimal degrees  |
+//! | `coordinate-approximate` | info | coordinate is marked approximate (`ca.`, `?`)  |
+//! | `unknown-country`   | warning  | country not in the ISO list or known regions     |
+//!
+//! Coordinates are read with `helper::coords::parse_coordinate`, so DMS and
+//! hemisphere notations are range-checked after conversion.
+//!
+//! The checks of a single species row (`missing-field`, `invalid-iucn`, the
+//! coordinate checks, and `unknown-country`) run on `MddDataRef`, so
+//! `check_species_row` also serves rows streamed by
+//! `MddDataRef::from_csv_borrowed` without building `MddData`.
+//!
+//! Duplicate names and senior homonym conflicts are added by
+//! `homonyms::find_homonyms` (see that module for its checks). Type
+//! coordinates outside the declared `type_country` are reported by
+//! `type_country::check_type_countries` when country outlines are given.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    helper::{
+        coords::{
+            parse_coordinate, validate_coordinates, Axis, CoordinateConfidence, CoordinateIssue,
+        },
+        country_code::is_known_country_region,
+        iucn::IucnStatus,
+        MDD_MISSING_VALUE,
+    },
+    parser::{borrowed::MddDataRef, mdd::MddData, synonyms::SynonymData},
+};
+
+use super::{homonyms::find_homonyms, Issue, QualityReport, Severity};
+
+/// Run all checks over species and synonym records.
+pub fn validate_records(species: &[MddData], synonyms: &[SynonymData]) -> QualityReport {
+    let mut report = QualityReport::new();
+    report.species_checked = species.len();
+    report.synonyms_checked = synonyms.len();
+
+    check_duplicate_ids(species, synonyms, &mut report);
+    for record in species {
+        check_species_row(&record.as_borrowed(), &mut report);
+    }
+    check_orphan_synonyms(species, synonyms, &mut report);
+    find_homonyms(species, synonyms).push_issues(&mut report);
+    report
+}
+
+/// Run the checks that need only `record`: required fields, IUCN status,
+/// type locality coordinates, and countries.
+pub fn check_species_row(record: &MddDataRef<'_>, report: &mut QualityReport) {
+    check_required_fields(record, report);
+    check_iucn_status(record, report);
+    check_coordinates(record, report);
+    check_countries(record, report);
+}
+
+fn is_blank(value: &str) -> bool {
+    let value = value.trim();
+    value.is_empty() || value == MDD_MISSING_VALUE
+}
+
+fn check_duplicate_ids(species: &[MddData], synonyms: &[SynonymData], report: &mut QualityReport) {
+    let mut seen: HashMap<u32, &str> = HashMap::new();
+    for record in species {
+        if let Some(first) = seen.insert(record.id, &record.sci_name) {
+            report.push(
+                Issue::new(
+                    Severity::Error,
+                    "duplicate-id",
+                    format!(
+                        "Species id {} is used by both {} and {}",
+                        record.id, first, record.sci_name
+                    ),
+                )
+                .with_record(record.id)
+                .with_field("id"),
+            );
+        }
+    }
+    let mut seen = HashSet::new();
+    for synonym in synonyms {
+        if !seen.insert(synonym.syn_id) {
+            report.push(
+                Issue::new(
+                    Severity::Error,
+                    "duplicate-id",
+                    format!("Synonym id {} appears more than once", synonym.syn_id),
+                )
+                .with_record(synonym.syn_id)
+                .with_field("synId"),
+            );
+        }
+    }
+}
+
+fn check_required_fields(record: &MddDataRef<'_>, report: &mut QualityReport) {
+    let required = [
+        ("sciName", record.sci_name),
+        ("taxonOrder", record.taxon_order),
+        ("family", record.family),
+        ("genus", record.genus),
+        ("specificEpithet", record.specific_epithet),
+    ];
+    for (field, value) in required {
+        if is_blank(value) {
+            report.push(
+                Issue::new(
+                    Severity::Error,
+                    "missing-field",
+                    format!("{} has no {}", record.sci_name, field),
+                )
+                .with_record(record.id)
+                .with_field(field),
+            );
+        }
+    }
+}
+
+fn check_iucn_status(record: &MddDataRef<'_>, report: &mut QualityReport) {
+    if is_blank(record.iucn_status) {
+        return;
+    }
+    if record.iucn_status.parse::<IucnStatus>().is_err() {
+        report.push(
+            Issue::new(
+                Severity::Warning,
+                "invalid-iucn",
+                format!(
+                    "{} has an unrecognized IUCN status: {}",
+                    record.sci_name, record.iucn_status
+                ),
+            )
+            .with_record(record.id)
+            .with_field("iucnStatus"),
+        );
+    }
+}
+
+fn check_coordinates(record: &MddDataRef<'_>, report: &mut QualityReport) {
+    let coordinates = [
+        (
+            "typeLocalityLatitude",
+            record.type_locality_latitude,
+            Axis::Latitude,
+        ),
+        (
+            "typeLocalityLongitude",
+            record.type_locality_longitude,
+            Axis::Longitude,
+        ),
+    ];
+    let mut parsed = [None, None];
+    for (i, (field, value, axis)) in coordinates.into_iter().enumerate() {
+        match parse_coordinate(value, axis) {
+            Ok(None) => (),
+            Ok(Some(coordinate)) => {
+                parsed[i] = Some(coordinate.value);
+                match coordinate.confidence {
+                    CoordinateConfidence::Exact => (),
+                    CoordinateConfidence::Converted => report.push(
+                        Issue::new(
+                            Severity::Warning,
+                            "coordinate-format",
+                            format!(
+                                "{} {} is not in decimal degrees: {} (reads as {})",
+                                record.sci_name, field, value, coordinate.value
+                            ),
+                        )
+                        .with_record(record.id)
+                        .with_field(field),
+                    ),
+                    CoordinateConfidence::Approximate => report.push(
+                        Issue::new(
+                            Severity::Info,
+                            "coordinate-approximate",
+                            format!("{} {} is approximate: {}", record.sci_name, field, value),
+                        )
+                        .with_record(record.id)
+                        .with_field(field),
+                    ),
+                }
+            }
+            Err(e) => report.push(
+                Issue::new(
+                    Severity::Warning,
+                    "coordinate-format",
+                    format!(
+                        "{} {} cannot be read ({}): {}",
+                        record.sci_name, field, e, value
+                    ),
+                )
+                .with_record(record.id)
+                .with_field(field),
+            ),
+        }
+    }
+    for issue in validate_coordinates(parsed[0], parsed[1]) {
+        let issue = match issue {
+            CoordinateIssue::OutOfRange { axis, value } => {
+                let (field, verbatim, _) = coordinates[usize::from(axis == Axis::Longitude)];
+                Issue::new(
+                    Severity::Error,
+                    "coordinate-range",
+                    format!(
+                        "{} {} is outside ±{}: {} (reads as {})",
+                        record.sci_name,
+                        field,
+                        axis.limit(),
+                        verbatim,
+                        value
+                    ),
+                )
+                .with_field(field)
+            }
+            CoordinateIssue::Swapped => Issue::new(
+                Severity::Warning,
+                "coordinate-swapped",
+                format!(
+                    "{} latitude {} and longitude {} look swapped",
+                    record.sci_name, record.type_locality_latitude, record.type_locality_longitude
+                ),
+            )
+            .with_field("typeLocalityLatitude"),
+        };
+        report.push(issue.with_record(record.id));
+    }
+}
+
+fn check_countries(record: &MddDataRef<'_>, report: &mut QualityReport) {
+    for country in record.countries() {
+        if !is_known_country_region(country.name) {
+            report.push(
+                Issue::new(
+                    Severity::Warning,
+                    "unknown-country",
+                    format!(
+                        "{} lists an unknown country: {}",
+                        record.sci_name, country.name
+                    ),
+                )
+                .with_record(record.id)
+                .with_field("countryDistribution"),
+            );
+        }
+    }
+}
+
+fn check_orphan_synonyms(
+    species: &[MddData],
+    synonyms: &[SynonymData],
+    report: &mut QualityReport,
+) {
+    let ids: HashSet<u32> = species.iter().map(|s| s.id).collect();
+    for synonym in synonyms {
+        if let Some(species_id) = synonym.species_id {
+            if !ids.contains(&species_id) {
+                report.push(
+                    Issue::new(
+                        Severity::Error,
+                        "orphan-synonym",
+                        format!(
+                            "Synonym {} points at missing species {}",
+                            synonym.syn_id, species_id
+                        ),
+                    )
+                    .with_record(synonym.syn_id)
+                    .with_field("speciesId"),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_records() {
+        let valid = MddData {
+            id: 1,
+            sci_name: "Panthera_leo".to_string(),
+            taxon_order: "Carnivora".to_string(),
+            family: "Felidae".to_string(),
+            genus: "Panthera".to_string(),
+            specific_epithet: "leo".to_string(),
+            iucn_status: "VU".to_string(),
+            country_distribution: "Kenya|Tanzania?".to_string(),
+            type_locality_latitude: "-1.5".to_string(),
+            ..MddData::new()
+        };
+        let broken = MddData {
+            sci_name: "Panthera_tigris".to_string(),
+            genus: "NA".to_string(),
+            iucn_status: "XX".to_string(),
+            country_distribution: "Atlantis".to_string(),
+            type_locality_latitude: "95".to_string(),
+            type_locality_longitude: "12°E".to_string(),
+            ..valid.clone()
+        };
+        let mut synonym = SynonymData::new();
+        synonym.species_id = Some(42);
+
+        let report = validate_records(std::slice::from_ref(&valid), &[]);
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+
+        let report = validate_records(&[valid, broken], &[synonym]);
+        let checks: Vec<&str> = report.issues.iter().map(|i| i.check.as_str()).collect();
+        for check in [
+            "duplicate-id",
+            "missing-field",
+            "invalid-iucn",
+            "coordinate-range",
+            "coordinate-swapped",
+            "coordinate-format",
+            "unknown-country",
+            "orphan-synonym",
+        ] {
+            assert!(checks.contains(&check), "missing {}", check);
+        }
+        assert_eq!(report.max_severity(), Some(Severity::Error));
+    }
+
+    #[test]
+    fn test_check_borrowed_rows() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let mut streamed = QualityReport::new();
+        MddDataRef::from_csv_borrowed(csv_data.as_bytes(), |species| {
+            check_species_row(&species, &mut streamed);
+        })
+        .unwrap();
+        let mut owned = QualityReport::new();
+        for species in MddData::new().from_csv(&csv_data) {
+            check_species_row(&species.as_borrowed(), &mut owned);
+        }
+        assert_eq!(streamed.to_json(), owned.to_json());
+    }
+}
@@ -0,0 +1,274 @@
+//! Chainable filters over parsed species records.
+//!
+//! `SpeciesQuery` collects filters with a builder API and applies them lazily
+//! to any source of `MddData` references:
+//!
+//! ```rust, ignore
+//! use mdd_api::query::SpeciesQuery;
+//!
+//! let query = SpeciesQuery::new()
+//!     .family("Felidae")
+//!     .country("Kenya")
+//!     .iucn_in(["EN", "CR"])
+//!     .extinct(false);
+//! for species in query.apply(&records) {
+//!     println!("{}", species.sci_name);
+//! }
+//! ```
+//!
+//! Rank and place names are compared case-insensitively. Country, continent,
+//! and realm filters match predicted (`?`) occurrences too; use
+//! `MddData::countries()` to tell them apart.
+//!
+//! `Page` slices query results for paginated APIs.
+
+use serde::Serialize;
+
+use crate::parser::{mdd::MddData, ReleasedMddData};
+
+/// Page size used when none is requested.
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+/// Largest page size a caller can request.
+pub const MAX_PAGE_SIZE: usize = 500;
+
+/// A single filter condition.
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Order(String),
+    Family(String),
+    Genus(String),
+    Country(String),
+    Continent(String),
+    Realm(String),
+    IucnIn(Vec<String>),
+    Extinct(bool),
+    Domestic(bool),
+}
+
+impl Filter {
+    fn matches(&self, data: &MddData) -> bool {
+        match self {
+            Self::Order(order) => data.taxon_order.eq_ignore_ascii_case(order),
+            Self::Family(family) => data.family.eq_ignore_ascii_case(family),
+            Self::Genus(genus) => data.genus.eq_ignore_ascii_case(genus),
+            Self::Country(country) => data
+                .countries()
+                .iter()
+                .any(|c| c.name.eq_ignore_ascii_case(country)),
+            Self::Continent(continent) => data
+                .continents()
+                .iter()
+                .any(|c| c.value.eq_ignore_ascii_case(continent)),
+            Self::Realm(realm) => data
+                .realms()
+                .iter()
+                .any(|r| r.value.eq_ignore_ascii_case(realm)),
+            Self::IucnIn(codes) => {
+                // Status values may carry qualifiers after the code (e.g. "EN (possibly extinct)").
+                let status = data.iucn_status.split_whitespace().next().unwrap_or("");
+                codes.iter().any(|code| code.eq_ignore_ascii_case(status))
+            }
+            Self::Extinct(extinct) => (data.extinct == 1) == *extinct,
+            Self::Domestic(domestic) => (data.domestic == 1) == *domestic,
+        }
+    }
+}
+
+/// Builder for filtering species records. All filters must match.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpeciesQuery {
+    filters: Vec<Filter>,
+}
+
+impl SpeciesQuery {
+    /// An empty query matching every record.
+    pub fn new() -> Self {
+        Self {
+            filters: Vec::new(),
+        }
+    }
+
+    /// Keep species in the given order.
+    pub fn order(self, order: &str) -> Self {
+        self.with(Filter::Order(order.to_string()))
+    }
+
+    /// Keep species in the given family.
+    pub fn family(self, family: &str) -> Self {
+        self.with(Filter::Family(family.to_string()))
+    }
+
+    /// Keep species in the given genus.
+    pub fn genus(self, genus: &str) -> Self {
+        self.with(Filter::Genus(genus.to_string()))
+    }
+
+    /// Keep species recorded in the given country.
+    pub fn country(self, country: &str) -> Self {
+        self.with(Filter::Country(country.to_string()))
+    }
+
+    /// Keep species recorded on the given continent.
+    pub fn continent(self, continent: &str) -> Self {
+        self.with(Filter::Continent(continent.to_string()))
+    }
+
+    /// Keep species recorded in the given biogeographic realm.
+    pub fn realm(self, realm: &str) -> Self {
+        self.with(Filter::Realm(realm.to_string()))
+    }
+
+    /// Keep species whose IUCN status code is one of `codes`.
+    pub fn iucn_in<I, S>(self, codes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let codes = codes.into_iter().map(|c| c.as_ref().to_string()).collect();
+        self.with(Filter::IucnIn(codes))
+    }
+
+    /// Keep extinct (`true`) or living (`false`) species.
+    pub fn extinct(self, extinct: bool) -> Self {
+        self.with(Filter::Extinct(extinct))
+    }
+
+    /// Keep domestic (`true`) or wild (`false`) species.
+    pub fn domestic(self, domestic: bool) -> Self {
+        self.with(Filter::Domestic(domestic))
+    }
+
+    fn with(mut self, filter: Filter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Returns `true` when `data` passes every filter.
+    pub fn matches(&self, data: &MddData) -> bool {
+        self.filters.iter().all(|f| f.matches(data))
+    }
+
+    /// Lazily filter any iterator of species references.
+    pub fn filter<'a, I>(&'a self, records: I) -> impl Iterator<Item = &'a MddData> + 'a
+    where
+        I: IntoIterator<Item = &'a MddData>,
+        I::IntoIter: 'a,
+    {
+        records.into_iter().filter(move |data| self.matches(data))
+    }
+
+    /// Lazily filter a slice of parsed records.
+    pub fn apply<'a>(&'a self, records: &'a [MddData]) -> impl Iterator<Item = &'a MddData> + 'a {
+        self.filter(records)
+    }
+
+    /// Lazily filter the species of a release bundle.
+    pub fn apply_release<'a>(
+        &'a self,
+        release: &'a ReleasedMddData,
+    ) -> impl Iterator<Item = &'a MddData> + 'a {
+        self.filter(release.species())
+    }
+}
+
+/// One page of results with the total number of matches.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    /// Number of matches before paging.
+    pub total_count: usize,
+    pub offset: usize,
+    pub limit: usize,
+    /// Offset of the next page; `None` on the last page.
+    pub next_offset: Option<usize>,
+    pub items: Vec<T>,
+}
+
+impl<T> Page<T> {
+    /// Take the page at `offset` from all matches. `limit` defaults to
+    /// `DEFAULT_PAGE_SIZE` and is capped at `MAX_PAGE_SIZE`.
+    pub fn new<I>(matches: I, offset: Option<usize>, limit: Option<usize>) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let offset = offset.unwrap_or(0);
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+        let mut total_count = 0;
+        let mut items = Vec::new();
+        for item in matches {
+            if total_count >= offset && items.len() < limit {
+                items.push(item);
+            }
+            total_count += 1;
+        }
+        let next_offset = Some(offset + items.len()).filter(|next| *next < total_count);
+        Self {
+            total_count,
+            offset,
+            limit,
+            next_offset,
+            items,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn species(id: u32, family: &str, countries: &str, iucn: &str, extinct: u8) -> MddData {
+        MddData {
+            id,
+            family: family.to_string(),
+            country_distribution: countries.to_string(),
+            iucn_status: iucn.to_string(),
+            extinct,
+            ..MddData::new()
+        }
+    }
+
+    #[test]
+    fn test_species_query() {
+        let records = vec![
+            species(1, "Felidae", "Kenya|Tanzania", "VU", 0),
+            species(2, "Felidae", "Kenya?", "EN", 0),
+            species(3, "Felidae", "India", "CR", 0),
+            species(4, "Canidae", "Kenya", "EN", 0),
+            species(5, "Felidae", "Kenya", "EX", 1),
+        ];
+        let query = SpeciesQuery::new()
+            .family("felidae")
+            .country("Kenya")
+            .iucn_in(["EN", "CR", "EX"])
+            .extinct(false);
+        let ids: Vec<u32> = query.apply(&records).map(|d| d.id).collect();
+        assert_eq!(ids, vec![2]);
+        assert_eq!(SpeciesQuery::new().apply(&records).count(), records.len());
+    }
+
+    #[test]
+    fn test_page() {
+        let page = Page::new(0..10, Some(8), Some(5));
+        assert_eq!(page.total_count, 10);
+        assert_eq!(page.items, vec![8, 9]);
+        assert_eq!(page.next_offset, None);
+        assert_eq!(Page::new(0..10, Some(2), Some(5)).next_offset, Some(7));
+        let page = Page::new(0..1000, None, Some(10_000));
+        assert_eq!(page.limit, MAX_PAGE_SIZE);
+        assert_eq!(page.items.len(), MAX_PAGE_SIZE);
+        assert_eq!(Page::new(0..100, None, None).items.len(), DEFAULT_PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_query_release() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let release = ReleasedMddData::from_parser(species, Vec::new(), "2.0", "2025-01-01");
+        let query = SpeciesQuery::new().country("South Africa").iucn_in(["CR"]);
+        let hits: Vec<&str> = query
+            .apply_release(&release)
+            .map(|d| d.sci_name.as_str())
+            .collect();
+        assert!(hits.contains(&"Bunolagus_monticularis"));
+    }
+}
@@ -0,0 +1,318 @@
+//! Reconcile external name lists against MDD.
+//!
+//! `Reconciler` takes scientific names as they appear in other sources (e.g.
+//! a museum collection database) and resolves each to the current MDD
+//! species. Names are tried in order:
+//! 1. `exact` – the name is a current MDD species name;
+//! 2. `viaSynonym` – the name is the original combination of a synonym, and
+//!    resolves to the species that synonym belongs to;
+//! 3. `fuzzy` – a species or synonym name within `max_distance` edits
+//!    (typos, gender endings);
+//! 4. `genusOnly` – only the genus is a current MDD genus;
+//! 5. `unmatched`.
+//!
+//! Matching is case-insensitive and treats `_` as a space. Names with more
+//! than two words (trinomials, trailing authorships) fall back to their
+//! first two words when the full name does not match.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    helper::{levenshtein, normalize_name},
+    parser::{mdd::MddData, synonyms::SynonymData, ReleasedMddData},
+    search::DEFAULT_MAX_DISTANCE,
+};
+
+/// How an input name was resolved, best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchType {
+    Exact,
+    ViaSynonym,
+    Fuzzy,
+    GenusOnly,
+    Unmatched,
+}
+
+/// Outcome for one input name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reconciliation {
+    /// Input name, verbatim.
+    pub input: String,
+    pub match_type: MatchType,
+    /// MDD id of the accepted species (none for `genusOnly` and `unmatched`).
+    pub species_id: Option<u32>,
+    /// Accepted MDD species name, or the genus for `genusOnly` matches.
+    pub accepted_name: Option<String>,
+    /// Name in MDD the input matched (species name or synonym combination).
+    pub matched_name: Option<String>,
+    /// Edit distance for `fuzzy` matches.
+    pub distance: Option<usize>,
+}
+
+impl Reconciliation {
+    fn unmatched(input: &str) -> Self {
+        Self {
+            input: input.to_string(),
+            match_type: MatchType::Unmatched,
+            species_id: None,
+            accepted_name: None,
+            matched_name: None,
+            distance: None,
+        }
+    }
+
+    /// Whether the name resolved to a species.
+    pub fn is_resolved(&self) -> bool {
+        self.species_id.is_some()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NameEntry {
+    name: String,
+    species_id: u32,
+    synonym: bool,
+}
+
+/// Name lookup tables built from one set of species and synonyms.
+#[derive(Debug, Clone)]
+pub struct Reconciler {
+    species: HashMap<String, NameEntry>,
+    synonyms: HashMap<String, NameEntry>,
+    accepted: HashMap<u32, String>,
+    genera: HashMap<String, String>,
+    max_distance: usize,
+}
+
+impl Reconciler {
+    /// Build lookups from `species` and the synonyms attached to them.
+    pub fn new(species: &[MddData], synonyms: &[SynonymData]) -> Self {
+        let mut reconciler = Self {
+            species: HashMap::new(),
+            synonyms: HashMap::new(),
+            accepted: HashMap::new(),
+            genera: HashMap::new(),
+            max_distance: DEFAULT_MAX_DISTANCE,
+        };
+        for record in species {
+            let name = record.sci_name.replace('_', " ");
+            reconciler.accepted.insert(record.id, name.clone());
+            reconciler
+                .genera
+                .entry(normalize_name(&record.genus))
+                .or_insert_with(|| record.genus.clone());
+            reconciler.species.insert(
+                normalize_name(&name),
+                NameEntry {
+                    name,
+                    species_id: record.id,
+                    synonym: false,
+                },
+            );
+        }
+        for synonym in synonyms {
+            let species_id = match synonym.species_id {
+                Some(id) if reconciler.accepted.contains_key(&id) => id,
+                _ => continue,
+            };
+            let name = synonym.original_combination().trim();
+            if name.is_empty() {
+                continue;
+            }
+            // The first usage wins when a combination was reused for several species.
+            reconciler
+                .synonyms
+                .entry(normalize_name(name))
+                .or_insert_with(|| NameEntry {
+                    name: name.to_string(),
+                    species_id,
+                    synonym: true,
+                });
+        }
+        reconciler
+    }
+
+    /// Build lookups from a release bundle.
+    pub fn from_release(release: &ReleasedMddData) -> Self {
+        let species: Vec<MddData> = release.species().cloned().collect();
+        let synonyms: Vec<SynonymData> = release
+            .data
+            .iter()
+            .flat_map(|entry| entry.synonyms().iter().cloned())
+            .collect();
+        Self::new(&species, &synonyms)
+    }
+
+    /// Set the number of edits tolerated by fuzzy matching (`0` disables it).
+    pub fn with_max_distance(mut self, max_distance: usize) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    /// Resolve a single name.
+    pub fn reconcile(&self, input: &str) -> Reconciliation {
+        let key = normalize_name(input);
+        if key.is_empty() {
+            return Reconciliation::unmatched(input);
+        }
+        let binomial = binomial(&key);
+        let candidates: Vec<&str> = match &binomial {
+            Some(binomial) => vec![&key, binomial],
+            None => vec![&key],
+        };
+        for candidate in &candidates {
+            if let Some(entry) = self.species.get(*candidate) {
+                return self.resolved(input, MatchType::Exact, entry, None);
+            }
+            if let Some(entry) = self.synonyms.get(*candidate) {
+                return self.resolved(input, MatchType::ViaSynonym, entry, None);
+            }
+        }
+        let fuzzy_key = binomial.as_deref().unwrap_or(&key);
+        if let Some((entry, distance)) = self.closest(fuzzy_key) {
+            return self.resolved(input, MatchType::Fuzzy, entry, Some(distance));
+        }
+        let genus = key.split(' ').next().unwrap_or_default();
+        if let Some(genus) = self.genera.get(genus) {
+            return Reconciliation {
+                match_type: MatchType::GenusOnly,
+                accepted_name: Some(genus.clone()),
+                ..Reconciliation::unmatched(input)
+            };
+        }
+        Reconciliation::unmatched(input)
+    }
+
+    /// Resolve every name of `inputs`, in order.
+    pub fn reconcile_all<'a, I>(&self, inputs: I) -> Vec<Reconciliation>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        inputs
+            .into_iter()
+            .map(|name| self.reconcile(name))
+            .collect()
+    }
+
+    fn resolved(
+        &self,
+        input: &str,
+        match_type: MatchType,
+        entry: &NameEntry,
+        distance: Option<usize>,
+    ) -> Reconciliation {
+        Reconciliation {
+            input: input.to_string(),
+            match_type,
+            species_id: Some(entry.species_id),
+            accepted_name: self.accepted.get(&entry.species_id).cloned(),
+            matched_name: Some(entry.name.clone()),
+            distance,
+        }
+    }
+
+    /// Closest species or synonym name within `max_distance`, preferring
+    /// species names on ties.
+    fn closest(&self, key: &str) -> Option<(&NameEntry, usize)> {
+        if self.max_distance == 0 {
+            return None;
+        }
+        let length = key.chars().count();
+        self.species
+            .iter()
+            .chain(self.synonyms.iter())
+            .filter(|(name, _)| name.chars().count().abs_diff(length) <= self.max_distance)
+            .map(|(name, entry)| (entry, levenshtein(name, key)))
+            .filter(|(_, distance)| *distance <= self.max_distance)
+            .min_by(|(a, da), (b, db)| {
+                (da, a.synonym, a.species_id).cmp(&(db, b.synonym, b.species_id))
+            })
+    }
+}
+
+/// Number of results per match type.
+pub fn summarize(results: &[Reconciliation]) -> BTreeMap<MatchType, usize> {
+    let mut counts = BTreeMap::new();
+    for result in results {
+        *counts.entry(result.match_type).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Distinct species ids resolved by `results`.
+pub fn resolved_species(results: &[Reconciliation]) -> HashSet<u32> {
+    results.iter().filter_map(|r| r.species_id).collect()
+}
+
+/// First two words of a normalized name with more than two words.
+fn binomial(key: &str) -> Option<String> {
+    let words: Vec<&str> = key.split(' ').collect();
+    if words.len() > 2 {
+        Some(words[..2].join(" "))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reconciler() -> Reconciler {
+        let species = vec![
+            MddData {
+                id: 1,
+                sci_name: "Panthera_leo".to_string(),
+                genus: "Panthera".to_string(),
+                ..MddData::new()
+            },
+            MddData {
+                id: 2,
+                sci_name: "Puma_concolor".to_string(),
+                genus: "Puma".to_string(),
+                ..MddData::new()
+            },
+        ];
+        let mut synonym = SynonymData::new();
+        synonym.species_id = Some(2);
+        synonym.original_combination = "Felis concolor".to_string();
+        Reconciler::new(&species, &[synonym])
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let reconciler = reconciler();
+        let results = reconciler.reconcile_all([
+            "Panthera leo",
+            "Felis concolor",
+            "Panthera leo persica",
+            "Felis concolour",
+            "Panthera spelaea",
+            "Homo sapiens",
+        ]);
+        let types: Vec<MatchType> = results.iter().map(|r| r.match_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                MatchType::Exact,
+                MatchType::ViaSynonym,
+                MatchType::Exact,
+                MatchType::Fuzzy,
+                MatchType::GenusOnly,
+                MatchType::Unmatched,
+            ]
+        );
+        assert_eq!(results[1].accepted_name.as_deref(), Some("Puma concolor"));
+        assert_eq!(results[1].matched_name.as_deref(), Some("Felis concolor"));
+        assert_eq!(results[3].species_id, Some(2));
+        assert_eq!(results[3].distance, Some(1));
+        assert_eq!(results[4].accepted_name.as_deref(), Some("Panthera"));
+        assert!(!results[5].is_resolved());
+        assert_eq!(summarize(&results)[&MatchType::Exact], 2);
+        assert_eq!(resolved_species(&results).len(), 2);
+    }
+}
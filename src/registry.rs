@@ -0,0 +1,344 @@
+//! Local collections of MDD releases.
+//!
+//! `ReleaseRegistry::scan` looks through a directory (recursively) for
+//! releases in any of the forms the CLI accepts:
+//! * release zips (`*.zip`), read through `parser::archive::read_zip_metadata`
+//!   without extracting them;
+//! * release TOMLs (`*.toml`) pointing at a species and a synonym CSV;
+//! * bare CSV pairs, `MDD_v<version>*.csv` next to `Species_Syn_v<version>*.csv`.
+//!
+//! Releases are keyed by `ReleaseVersion`, so `versions` and `latest` follow
+//! numeric ordering (`2.10` after `2.9`). When the same version is found more
+//! than once, a release TOML wins over a CSV pair, which wins over a zip;
+//! among sources of the same kind the first path in sorted order is kept.
+//! Files that cannot be read as a release are skipped with a warning.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use crate::parser::{
+    archive::{
+        read_zip_metadata, release_version_from_file_name, MDD_FILE_PREFIX, SYNONYM_FILE_PREFIX,
+    },
+    error::MddError,
+    mdd::MddData,
+    metadata::ReleaseToml,
+    synonyms::SynonymData,
+    version::ReleaseVersion,
+    ReleasedMddData,
+};
+
+/// Where a release in the registry is read from. Variants are ordered by
+/// preference when a version is found in several places.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ReleaseSource {
+    /// Release TOML; its CSV paths are resolved against the TOML's directory.
+    Toml(PathBuf),
+    /// Species and synonym CSVs found side by side.
+    Csv { mdd: PathBuf, synonym: PathBuf },
+    /// Release zip.
+    Zip(PathBuf),
+}
+
+/// One release available in the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryEntry {
+    pub version: ReleaseVersion,
+    /// Release date from the release metadata; empty when unknown.
+    pub release_date: String,
+    pub source: ReleaseSource,
+}
+
+/// Releases found under one directory, ordered by version.
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseRegistry {
+    entries: BTreeMap<ReleaseVersion, RegistryEntry>,
+}
+
+impl ReleaseRegistry {
+    pub fn new() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Scan `dir` and its subdirectories for releases.
+    pub fn scan(dir: &Path) -> Result<Self, MddError> {
+        let mut files = Vec::new();
+        collect_files(dir, &mut files)?;
+        files.sort();
+
+        let mut registry = Self::new();
+        for path in &files {
+            let name = file_name(path);
+            let entry = if name.ends_with(".zip") {
+                zip_entry(path)
+            } else if name.ends_with(".toml") {
+                toml_entry(path)
+            } else if name.starts_with(MDD_FILE_PREFIX) && name.ends_with(".csv") {
+                csv_entry(path, &files)
+            } else {
+                continue;
+            };
+            match entry {
+                Ok(Some(entry)) => registry.insert(entry),
+                Ok(None) => (),
+                Err(e) => log::warn!("Skipping {}: {}", path.display(), e),
+            }
+        }
+        Ok(registry)
+    }
+
+    /// Add `entry`, keeping the preferred source when its version is taken.
+    pub fn insert(&mut self, entry: RegistryEntry) {
+        match self.entries.get(&entry.version) {
+            Some(current) if current.source <= entry.source => {
+                log::debug!(
+                    "Release {} already found in {:?}, ignoring {:?}",
+                    entry.version,
+                    current.source,
+                    entry.source
+                );
+            }
+            _ => {
+                self.entries.insert(entry.version, entry);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Available versions, oldest first.
+    pub fn versions(&self) -> Vec<&ReleaseVersion> {
+        self.entries.keys().collect()
+    }
+
+    /// Available releases, oldest first.
+    pub fn entries(&self) -> impl Iterator<Item = &RegistryEntry> {
+        self.entries.values()
+    }
+
+    pub fn get(&self, version: &ReleaseVersion) -> Option<&RegistryEntry> {
+        self.entries.get(version)
+    }
+
+    /// Newest release.
+    pub fn latest(&self) -> Option<&RegistryEntry> {
+        self.entries.values().next_back()
+    }
+
+    /// Parse the release with `version`, or `None` if the registry lacks it.
+    pub fn load(&self, version: &ReleaseVersion) -> Option<Result<ReleasedMddData, MddError>> {
+        self.get(version).map(RegistryEntry::load)
+    }
+
+    /// Parse the newest release, or `None` if the registry is empty.
+    pub fn load_latest(&self) -> Option<Result<ReleasedMddData, MddError>> {
+        self.latest().map(RegistryEntry::load)
+    }
+}
+
+impl RegistryEntry {
+    /// Parse the release from its source.
+    pub fn load(&self) -> Result<ReleasedMddData, MddError> {
+        match &self.source {
+            ReleaseSource::Zip(path) => ReleasedMddData::from_zip_reader(File::open(path)?),
+            ReleaseSource::Toml(path) => {
+                let meta = ReleaseToml::from_file(path)?.metadata;
+                let base_dir = path.parent().unwrap_or(Path::new(""));
+                let mut release = parse_csvs(
+                    &meta.mdd_path(base_dir),
+                    &meta.synonym_path(base_dir),
+                    &meta.version,
+                    &meta.release_date,
+                )?;
+                if let Some(doi) = meta.doi.as_deref() {
+                    release.set_doi(doi);
+                }
+                Ok(release)
+            }
+            ReleaseSource::Csv { mdd, synonym } => {
+                parse_csvs(mdd, synonym, &self.version.to_string(), &self.release_date)
+            }
+        }
+    }
+}
+
+fn parse_csvs(
+    mdd_path: &Path,
+    synonym_path: &Path,
+    version: &str,
+    release_date: &str,
+) -> Result<ReleasedMddData, MddError> {
+    let species = MddData::new().from_reader(BufReader::new(File::open(mdd_path)?))?;
+    let synonyms = SynonymData::new().from_reader(BufReader::new(File::open(synonym_path)?))?;
+    Ok(ReleasedMddData::from_parser(
+        species,
+        synonyms,
+        version,
+        release_date,
+    ))
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), MddError> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn zip_entry(path: &Path) -> Result<Option<RegistryEntry>, MddError> {
+    let meta = read_zip_metadata(File::open(path)?)?;
+    Ok(Some(RegistryEntry {
+        version: meta.release_version()?,
+        release_date: meta.release_date,
+        source: ReleaseSource::Zip(path.to_path_buf()),
+    }))
+}
+
+/// TOMLs that are not release metadata (e.g. a stray `Cargo.toml`) are
+/// ignored rather than reported.
+fn toml_entry(path: &Path) -> Result<Option<RegistryEntry>, MddError> {
+    let meta = match ReleaseToml::from_file(path) {
+        Ok(toml) => toml.metadata,
+        Err(MddError::TomlDe(_)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    Ok(Some(RegistryEntry {
+        version: meta.release_version()?,
+        release_date: meta.release_date,
+        source: ReleaseSource::Toml(path.to_path_buf()),
+    }))
+}
+
+/// Pair a species CSV with the synonym CSV of the same version in its directory.
+fn csv_entry(path: &Path, files: &[PathBuf]) -> Result<Option<RegistryEntry>, MddError> {
+    let version = release_version_from_file_name(&file_name(path))
+        .ok_or_else(|| MddError::InvalidVersion(format!("no version in {}", path.display())))?;
+    let synonym = files.iter().find(|candidate| {
+        let name = file_name(candidate);
+        candidate.parent() == path.parent()
+            && name.ends_with(".csv")
+            && name
+                .strip_prefix(SYNONYM_FILE_PREFIX)
+                .and_then(|rest| {
+                    release_version_from_file_name(&format!("{}{}", MDD_FILE_PREFIX, rest))
+                })
+                .as_ref()
+                == Some(&version)
+    });
+    match synonym {
+        Some(synonym) => Ok(Some(RegistryEntry {
+            version,
+            release_date: String::new(),
+            source: ReleaseSource::Csv {
+                mdd: path.to_path_buf(),
+                synonym: synonym.clone(),
+            },
+        })),
+        None => {
+            log::warn!(
+                "Skipping {}: no {}{} CSV next to it",
+                path.display(),
+                SYNONYM_FILE_PREFIX,
+                version
+            );
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use tempdir::TempDir;
+    use zip::write::SimpleFileOptions;
+
+    use super::*;
+
+    const MDD_CSV: &str = "tests/data/test_data.csv";
+    const SYN_CSV: &str = "tests/data/syndata.csv";
+
+    #[test]
+    fn test_scan_registry() {
+        let dir = TempDir::new("registry").unwrap();
+        let root = dir.path();
+
+        // v2.1 as a zip without metadata.
+        let mut zip = zip::ZipWriter::new(File::create(root.join("MDD_v2.1.zip")).unwrap());
+        let options = SimpleFileOptions::default();
+        zip.start_file("MDD_v2.1_112species.csv", options).unwrap();
+        zip.write_all(&fs::read(MDD_CSV).unwrap()).unwrap();
+        zip.start_file("Species_Syn_v2.1.csv", options).unwrap();
+        zip.write_all(&fs::read(SYN_CSV).unwrap()).unwrap();
+        zip.finish().unwrap();
+
+        // A second copy of the v2.1 zip is ignored.
+        fs::copy(root.join("MDD_v2.1.zip"), root.join("stale.zip")).unwrap();
+
+        // v2.10 as a bare CSV pair.
+        let csv_dir = root.join("v2.10");
+        fs::create_dir(&csv_dir).unwrap();
+        fs::copy(MDD_CSV, csv_dir.join("MDD_v2.10_112species.csv")).unwrap();
+        fs::copy(SYN_CSV, csv_dir.join("Species_Syn_v2.10.csv")).unwrap();
+
+        // v2.9 through a release TOML; an unpaired species CSV is skipped.
+        let toml_dir = root.join("v2.9");
+        fs::create_dir(&toml_dir).unwrap();
+        fs::copy(MDD_CSV, toml_dir.join("species.csv")).unwrap();
+        fs::copy(SYN_CSV, toml_dir.join("synonyms.csv")).unwrap();
+        fs::write(
+            toml_dir.join("release.toml"),
+            "[metadata]\nname = \"MDD\"\nversion = \"2.9\"\nrelease_date = \"2024-06-01\"\n\
+             mdd_file = \"species.csv\"\nsynonym_file = \"synonyms.csv\"\n",
+        )
+        .unwrap();
+        fs::copy(MDD_CSV, toml_dir.join("MDD_v2.8_112species.csv")).unwrap();
+
+        let registry = ReleaseRegistry::scan(root).unwrap();
+        let versions: Vec<String> = registry.versions().iter().map(|v| v.to_string()).collect();
+        assert_eq!(versions, vec!["2.1", "2.9", "2.10"]);
+
+        let latest = registry.latest().unwrap();
+        assert!(matches!(latest.source, ReleaseSource::Csv { .. }));
+        let release = registry.load_latest().unwrap().unwrap();
+        assert_eq!(release.get_version(), "2.10");
+        assert_eq!(release.data.len(), 112);
+
+        let v2_9: ReleaseVersion = "2.9".parse().unwrap();
+        assert_eq!(registry.get(&v2_9).unwrap().release_date, "2024-06-01");
+        let release = registry.load(&v2_9).unwrap().unwrap();
+        assert_eq!(release.get_release_date(), "2024-06-01");
+
+        let v2_1: ReleaseVersion = "2.1".parse().unwrap();
+        assert_eq!(
+            registry.get(&v2_1).unwrap().source,
+            ReleaseSource::Zip(root.join("MDD_v2.1.zip"))
+        );
+        let release = registry.load(&v2_1).unwrap().unwrap();
+        assert_eq!(release.get_version(), "2.1");
+        assert!(registry.load(&"3.0".parse().unwrap()).is_none());
+    }
+}
@@ -0,0 +1,546 @@
+//! Static species pages (enabled with the `render` feature).
+//!
+//! `PageRenderer` turns every species of a release into a Markdown or HTML
+//! page with Tera templates, for building static species sites:
+//!
+//! ```text
+//! <output_dir>/
+//! ├── index.md            # list of species linking to their pages
+//! └── species/
+//!     ├── 1001076.md      # taxonomy, authority, type, distribution, synonyms, notes
+//!     └── ...
+//! ```
+//!
+//! The built-in templates can be replaced with `with_species_template` and
+//! `with_index_template`. Species templates get a `SpeciesPage` (the verbatim
+//! record as `species` plus display-ready fields with `NA` removed), the
+//! index template an `IndexPage`; see `SPECIES_MARKDOWN` for an example.
+//! HTML templates escape values automatically, Markdown templates do not.
+//!
+//! ```rust, ignore
+//! use mdd_api::render::{PageFormat, PageRenderer};
+//!
+//! let renderer = PageRenderer::new(PageFormat::Html)
+//!     .with_species_template(&std::fs::read_to_string("species.html")?)?;
+//! renderer.write(&release, Path::new("site"))?;
+//! ```
+
+use std::{
+    collections::HashSet,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use tera::{Context, Tera};
+
+use crate::{
+    helper::{split_mdd_list, MDD_MISSING_VALUE},
+    parser::{mdd::MddData, MetaData, ReleasedMddData, SimpleMDD},
+    writer::per_species::{file_stem, SpeciesFileNaming, SPECIES_DIR},
+};
+
+const SPECIES_TEMPLATE: &str = "species";
+const INDEX_TEMPLATE: &str = "index";
+const INDEX_FILE: &str = "index";
+/// Nomenclature status of names without remarks, not shown on the pages.
+const AVAILABLE_STATUS: &str = "available";
+
+/// Built-in Markdown species template.
+pub const SPECIES_MARKDOWN: &str = r#"# *{{ sciName }}* {{ authority }}
+{% if mainCommonName %}
+**{{ mainCommonName }}**{% if otherCommonNames %} (also {{ otherCommonNames | join(sep=", ") }}){% endif %}
+{% endif %}
+MDD id: {{ species.id }}{% if iucnStatus %} · IUCN: {{ iucnStatus }}{% endif %}{% if species.extinct == 1 %} · extinct{% endif %}{% if species.domestic == 1 %} · domestic{% endif %}
+
+## Taxonomy
+
+| Rank | Name |
+|------|------|
+{% for taxon in classification %}| {{ taxon.rank }} | {{ taxon.name }} |
+{% endfor %}{% if originalCombination %}
+Original combination: *{{ originalCombination }}*
+{% endif %}{% if citation %}
+Citation: {{ citation }}
+{% endif %}
+## Type
+{% if typeLocality %}
+Type locality: {{ typeLocality }}{% if typeCoordinates %} ({{ typeCoordinates }}){% endif %}
+{% endif %}{% if typeVoucher %}
+Type voucher: {{ typeVoucher }}{% if typeKind %} ({{ typeKind }}){% endif %}
+{% endif %}
+## Distribution
+{% if countries %}
+Countries: {{ countries | join(sep=", ") }}
+{% endif %}{% if continents %}
+Continents: {{ continents | join(sep=", ") }}
+{% endif %}{% if realms %}
+Biogeographic realms: {{ realms | join(sep=", ") }}
+{% endif %}{% if distributionNotes %}
+{{ distributionNotes }}
+{% endif %}{% if synonyms %}
+## Synonyms
+
+{% for synonym in synonyms %}- *{{ synonym.name }}*{% if synonym.authority %} {{ synonym.authority }}{% endif %}{% if synonym.status %} [{{ synonym.status }}]{% endif %}
+{% endfor %}{% endif %}{% if taxonomyNotes %}
+## Notes
+
+{{ taxonomyNotes }}
+{% endif %}
+---
+MDD v{{ metadata.version }} ({{ metadata.releaseDate }}) · [All species]({{ indexPath }})
+"#;
+
+/// Built-in Markdown index template.
+pub const INDEX_MARKDOWN: &str = r#"# Mammal Diversity Database v{{ metadata.version }}
+
+{{ species | length }} species, released {{ metadata.releaseDate }}.
+
+{% for entry in species %}- [*{{ entry.sciName }}*]({{ entry.path }}){% if entry.mainCommonName %} — {{ entry.mainCommonName }}{% endif %}
+{% endfor %}"#;
+
+/// Built-in HTML species template.
+pub const SPECIES_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{{ sciName }} – MDD v{{ metadata.version }}</title>
+</head>
+<body>
+<h1><em>{{ sciName }}</em> {{ authority }}</h1>
+{% if mainCommonName %}<p><strong>{{ mainCommonName }}</strong>{% if otherCommonNames %} (also {{ otherCommonNames | join(sep=", ") }}){% endif %}</p>
+{% endif %}<p>MDD id: {{ species.id }}{% if iucnStatus %} · IUCN: {{ iucnStatus }}{% endif %}{% if species.extinct == 1 %} · extinct{% endif %}{% if species.domestic == 1 %} · domestic{% endif %}</p>
+<h2>Taxonomy</h2>
+<table>
+{% for taxon in classification %}<tr><th>{{ taxon.rank }}</th><td>{{ taxon.name }}</td></tr>
+{% endfor %}</table>
+{% if originalCombination %}<p>Original combination: <em>{{ originalCombination }}</em></p>
+{% endif %}{% if citation %}<p>Citation: {{ citation }}</p>
+{% endif %}<h2>Type</h2>
+{% if typeLocality %}<p>Type locality: {{ typeLocality }}{% if typeCoordinates %} ({{ typeCoordinates }}){% endif %}</p>
+{% endif %}{% if typeVoucher %}<p>Type voucher: {{ typeVoucher }}{% if typeKind %} ({{ typeKind }}){% endif %}</p>
+{% endif %}<h2>Distribution</h2>
+{% if countries %}<p>Countries: {{ countries | join(sep=", ") }}</p>
+{% endif %}{% if continents %}<p>Continents: {{ continents | join(sep=", ") }}</p>
+{% endif %}{% if realms %}<p>Biogeographic realms: {{ realms | join(sep=", ") }}</p>
+{% endif %}{% if distributionNotes %}<p>{{ distributionNotes }}</p>
+{% endif %}{% if synonyms %}<h2>Synonyms</h2>
+<ul>
+{% for synonym in synonyms %}<li><em>{{ synonym.name }}</em>{% if synonym.authority %} {{ synonym.authority }}{% endif %}{% if synonym.status %} [{{ synonym.status }}]{% endif %}</li>
+{% endfor %}</ul>
+{% endif %}{% if taxonomyNotes %}<h2>Notes</h2>
+<p>{{ taxonomyNotes }}</p>
+{% endif %}<footer>MDD v{{ metadata.version }} ({{ metadata.releaseDate }}) · <a href="{{ indexPath }}">All species</a></footer>
+</body>
+</html>
+"#;
+
+/// Built-in HTML index template.
+pub const INDEX_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Mammal Diversity Database v{{ metadata.version }}</title>
+</head>
+<body>
+<h1>Mammal Diversity Database v{{ metadata.version }}</h1>
+<p>{{ species | length }} species, released {{ metadata.releaseDate }}.</p>
+<ul>
+{% for entry in species %}<li><a href="{{ entry.path }}"><em>{{ entry.sciName }}</em></a>{% if entry.mainCommonName %} – {{ entry.mainCommonName }}{% endif %}</li>
+{% endfor %}</ul>
+</body>
+</html>
+"#;
+
+/// Output format of the pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PageFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl PageFormat {
+    /// File extension, without the leading dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+
+    /// Template name with the format extension; Tera escapes values in
+    /// templates named `*.html`.
+    fn template_name(&self, template: &str) -> String {
+        format!("{}.{}", template, self.extension())
+    }
+
+    fn default_templates(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::Markdown => (SPECIES_MARKDOWN, INDEX_MARKDOWN),
+            Self::Html => (SPECIES_HTML, INDEX_HTML),
+        }
+    }
+}
+
+/// Errors produced while rendering or writing pages.
+#[derive(Debug)]
+pub enum RenderError {
+    Template(Box<tera::Error>),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // Tera reports the failing template and line in its source chain.
+            Self::Template(e) => {
+                write!(f, "Template error: {}", e)?;
+                let mut source = std::error::Error::source(e.as_ref());
+                while let Some(cause) = source {
+                    write!(f, ": {}", cause)?;
+                    source = cause.source();
+                }
+                Ok(())
+            }
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Template(e) => Some(e.as_ref()),
+            Self::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<tera::Error> for RenderError {
+    fn from(err: tera::Error) -> Self {
+        Self::Template(Box::new(err))
+    }
+}
+
+impl From<std::io::Error> for RenderError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// A rank of the classification.
+#[derive(Debug, Clone, Serialize)]
+pub struct Taxon<'a> {
+    pub rank: &'static str,
+    pub name: &'a str,
+}
+
+/// A synonym as listed on a species page.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageSynonym {
+    /// Original combination, or the root name when it is not recorded.
+    pub name: String,
+    pub authority: String,
+    /// Nomenclature status other than `available`.
+    pub status: Option<String>,
+}
+
+/// Template context of a species page.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeciesPage<'a> {
+    /// The verbatim record.
+    pub species: &'a MddData,
+    pub metadata: &'a MetaData,
+    /// Genus and epithet, e.g. `Bunolagus monticularis`.
+    pub sci_name: String,
+    /// Formatted authority, e.g. `(Thomas, 1903)`.
+    pub authority: String,
+    pub main_common_name: Option<&'a str>,
+    pub other_common_names: Vec<&'a str>,
+    /// Ranks from subclass to subgenus that have a value.
+    pub classification: Vec<Taxon<'a>>,
+    pub original_combination: Option<&'a str>,
+    pub citation: Option<&'a str>,
+    pub type_locality: Option<&'a str>,
+    /// `latitude, longitude` when both are recorded.
+    pub type_coordinates: Option<String>,
+    pub type_voucher: Option<&'a str>,
+    pub type_kind: Option<&'a str>,
+    /// Country names; predicted occurrences end with `?`.
+    pub countries: Vec<String>,
+    pub continents: Vec<&'a str>,
+    pub realms: Vec<&'a str>,
+    pub iucn_status: Option<&'a str>,
+    pub distribution_notes: Option<&'a str>,
+    pub taxonomy_notes: Option<&'a str>,
+    pub synonyms: Vec<PageSynonym>,
+    /// Path of the index page relative to the species page.
+    pub index_path: String,
+}
+
+impl<'a> SpeciesPage<'a> {
+    pub fn new(entry: &'a SimpleMDD, metadata: &'a MetaData, format: PageFormat) -> Self {
+        let species = entry.species();
+        let classification = [
+            ("Subclass", &species.subclass),
+            ("Infraclass", &species.infraclass),
+            ("Magnorder", &species.magnorder),
+            ("Superorder", &species.superorder),
+            ("Order", &species.taxon_order),
+            ("Suborder", &species.suborder),
+            ("Infraorder", &species.infraorder),
+            ("Parvorder", &species.parvorder),
+            ("Superfamily", &species.superfamily),
+            ("Family", &species.family),
+            ("Subfamily", &species.subfamily),
+            ("Tribe", &species.tribe),
+            ("Genus", &species.genus),
+            ("Subgenus", &species.subgenus),
+        ]
+        .into_iter()
+        .filter_map(|(rank, name)| value(name).map(|name| Taxon { rank, name }))
+        .collect();
+        let type_coordinates = value(&species.type_locality_latitude)
+            .zip(value(&species.type_locality_longitude))
+            .map(|(latitude, longitude)| format!("{}, {}", latitude, longitude));
+        let synonyms = entry
+            .synonyms()
+            .iter()
+            .map(|synonym| PageSynonym {
+                name: value(synonym.original_combination())
+                    .unwrap_or_else(|| synonym.root_name())
+                    .to_string(),
+                authority: synonym.authorship().to_string(),
+                status: value(synonym.nomenclature_status())
+                    .filter(|status| *status != AVAILABLE_STATUS)
+                    .map(|status| status.to_string()),
+            })
+            .collect();
+        Self {
+            species,
+            metadata,
+            sci_name: format!("{} {}", species.genus, species.specific_epithet),
+            authority: species.authorship().to_string(),
+            main_common_name: value(&species.main_common_name),
+            other_common_names: species.common_names(),
+            classification,
+            original_combination: value(&species.original_name_combination),
+            citation: value(&species.authority_species_citation),
+            type_locality: value(&species.type_locality),
+            type_coordinates,
+            type_voucher: value(&species.type_voucher),
+            type_kind: value(&species.type_kind),
+            countries: species
+                .countries()
+                .iter()
+                .map(|country| {
+                    if country.predicted {
+                        format!("{}?", country.name)
+                    } else {
+                        country.name.to_string()
+                    }
+                })
+                .collect(),
+            continents: split_mdd_list(&species.continent_distribution).collect(),
+            realms: split_mdd_list(&species.biogeographic_realm).collect(),
+            iucn_status: value(&species.iucn_status),
+            distribution_notes: value(&species.distribution_notes),
+            taxonomy_notes: value(&species.taxonomy_notes),
+            synonyms,
+            index_path: format!("../{}.{}", INDEX_FILE, format.extension()),
+        }
+    }
+}
+
+/// A species row of the index page.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexEntry {
+    pub mdd_id: u32,
+    pub sci_name: String,
+    pub main_common_name: Option<String>,
+    pub taxon_order: String,
+    pub family: String,
+    /// Path of the species page relative to the index page.
+    pub path: String,
+}
+
+/// Template context of the index page.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexPage<'a> {
+    pub metadata: &'a MetaData,
+    pub species: Vec<IndexEntry>,
+}
+
+/// Renders species and index pages from Tera templates.
+pub struct PageRenderer {
+    tera: Tera,
+    format: PageFormat,
+    naming: SpeciesFileNaming,
+}
+
+impl PageRenderer {
+    /// Renderer with the built-in templates of `format`.
+    pub fn new(format: PageFormat) -> Self {
+        let (species, index) = format.default_templates();
+        let mut tera = Tera::default();
+        tera.add_raw_templates([
+            (format.template_name(SPECIES_TEMPLATE), species),
+            (format.template_name(INDEX_TEMPLATE), index),
+        ])
+        .expect("Built-in templates are valid");
+        Self {
+            tera,
+            format,
+            naming: SpeciesFileNaming::Id,
+        }
+    }
+
+    /// Replace the species template.
+    pub fn with_species_template(mut self, template: &str) -> Result<Self, RenderError> {
+        let name = self.format.template_name(SPECIES_TEMPLATE);
+        self.tera.add_raw_template(&name, template)?;
+        Ok(self)
+    }
+
+    /// Replace the index template.
+    pub fn with_index_template(mut self, template: &str) -> Result<Self, RenderError> {
+        let name = self.format.template_name(INDEX_TEMPLATE);
+        self.tera.add_raw_template(&name, template)?;
+        Ok(self)
+    }
+
+    /// Name species pages by MDD id (default) or by slug.
+    pub fn with_naming(mut self, naming: SpeciesFileNaming) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// Render the page of one species.
+    pub fn render_species(
+        &self,
+        entry: &SimpleMDD,
+        metadata: &MetaData,
+    ) -> Result<String, RenderError> {
+        let page = SpeciesPage::new(entry, metadata, self.format);
+        self.render(SPECIES_TEMPLATE, &page)
+    }
+
+    /// Render the index page.
+    pub fn render_index(&self, index: &IndexPage) -> Result<String, RenderError> {
+        self.render(INDEX_TEMPLATE, index)
+    }
+
+    /// Write every species page and the index, returning the index path.
+    pub fn write(
+        &self,
+        release: &ReleasedMddData,
+        output_dir: &Path,
+    ) -> Result<PathBuf, RenderError> {
+        let species_dir = output_dir.join(SPECIES_DIR);
+        fs::create_dir_all(&species_dir)?;
+        let extension = self.format.extension();
+        let mut used = HashSet::new();
+        let mut entries = Vec::with_capacity(release.data.len());
+        for entry in &release.data {
+            let file_name = format!("{}.{}", file_stem(self.naming, entry, &mut used), extension);
+            let page = self.render_species(entry, &release.metadata)?;
+            fs::write(species_dir.join(&file_name), page)?;
+            let species = entry.species();
+            entries.push(IndexEntry {
+                mdd_id: entry.mdd_id(),
+                sci_name: format!("{} {}", species.genus, species.specific_epithet),
+                main_common_name: value(&species.main_common_name).map(|s| s.to_string()),
+                taxon_order: species.taxon_order.clone(),
+                family: species.family.clone(),
+                path: format!("{}/{}", SPECIES_DIR, file_name),
+            });
+        }
+        let index = IndexPage {
+            metadata: &release.metadata,
+            species: entries,
+        };
+        let index_path = output_dir.join(INDEX_FILE).with_extension(extension);
+        fs::write(&index_path, self.render_index(&index)?)?;
+        Ok(index_path)
+    }
+
+    fn render<T: Serialize>(&self, template: &str, context: &T) -> Result<String, RenderError> {
+        let context = Context::from_serialize(context)?;
+        Ok(self
+            .tera
+            .render(&self.format.template_name(template), &context)?)
+    }
+}
+
+/// The field value, or `None` when it is empty or `NA`.
+fn value(field: &str) -> Option<&str> {
+    let field = field.trim();
+    (!field.is_empty() && field != MDD_MISSING_VALUE).then_some(field)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::parser::synonyms::SynonymData;
+
+    fn release() -> ReleasedMddData {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let mut species = MddData::new().from_csv(&csv_data);
+        species.truncate(3);
+        species[0].type_locality = "Deelfontein, <Cape Province>".to_string();
+        ReleasedMddData::from_parser(species, Vec::<SynonymData>::new(), "2.0", "2025-01-01")
+    }
+
+    #[test]
+    fn test_render_species_page() {
+        let release = release();
+        let entry = &release.data[0];
+        let renderer = PageRenderer::new(PageFormat::Markdown);
+        let page = renderer.render_species(entry, &release.metadata).unwrap();
+        assert!(page.starts_with("# *Bunolagus monticularis* "));
+        assert!(page.contains("| Family | Leporidae |"));
+        assert!(page.contains("Type locality: Deelfontein, <Cape Province>"));
+        assert!(page.contains("IUCN: CR"));
+        assert!(!page.contains("| NA |"));
+
+        let html = PageRenderer::new(PageFormat::Html)
+            .render_species(entry, &release.metadata)
+            .unwrap();
+        assert!(html.contains("<em>Bunolagus monticularis</em>"));
+        assert!(html.contains("Deelfontein, &lt;Cape Province&gt;"));
+
+        let custom = PageRenderer::new(PageFormat::Markdown)
+            .with_species_template("{{ species.id }}: {{ sciName }}")
+            .unwrap();
+        assert_eq!(
+            custom.render_species(entry, &release.metadata).unwrap(),
+            "1001076: Bunolagus monticularis"
+        );
+        assert!(PageRenderer::new(PageFormat::Markdown)
+            .with_species_template("{% if %}")
+            .is_err());
+    }
+
+    #[test]
+    fn test_write_pages() {
+        let release = release();
+        let output_dir = TempDir::new("render").unwrap();
+        let renderer = PageRenderer::new(PageFormat::Markdown).with_naming(SpeciesFileNaming::Slug);
+        let index_path = renderer.write(&release, output_dir.path()).unwrap();
+        assert_eq!(index_path, output_dir.path().join("index.md"));
+        let index = fs::read_to_string(&index_path).unwrap();
+        assert!(index.contains("3 species"));
+        let species_dir = output_dir.path().join(SPECIES_DIR);
+        assert_eq!(fs::read_dir(&species_dir).unwrap().count(), 3);
+        let first = &release.data[0].species().slug;
+        assert!(index.contains(&format!("(species/{}.md)", first)));
+        assert!(species_dir.join(format!("{}.md", first)).exists());
+    }
+}
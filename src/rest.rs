@@ -0,0 +1,282 @@
+//! Read-only REST API over an in-memory release bundle (enabled with the
+//! `server` feature).
+//!
+//! | Endpoint | Response |
+//! |----------|----------|
+//! | `GET /species` | `Page` of `MddData` |
+//! | `GET /species/{id}` | `SimpleMDD` (species with its synonyms) |
+//! | `GET /synonyms` | `Page` of `SynonymData` |
+//! | `GET /countries/{code}/species` | `Page` of `MddData` |
+//! | `GET /search?q=` | `SearchMatch` list |
+//!
+//! Records use the same serde shapes as the JSON bundle. List endpoints take
+//! `offset` and `limit` (see `query::Page`); `/species` and
+//! `/countries/{code}/species` also take `family`, `order`, `genus`,
+//! `country`, and `iucn` (comma-separated codes) filters, `/synonyms` takes
+//! `speciesId` and `validity`, and `/search` takes `limit`. Country codes are
+//! the keys of `CountryMDDStats`. Unknown ids and codes answer `404`.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+
+use crate::{
+    parser::{
+        country::CountryMDDStats, mdd::MddData, synonyms::SynonymData, ReleasedMddData, SimpleMDD,
+    },
+    query::{Page, SpeciesQuery},
+    search::{SearchIndex, SearchMatch},
+    store::MddStore,
+};
+
+/// Number of search results when `/search` has no `limit`.
+pub const DEFAULT_SEARCH_LIMIT: usize = 10;
+
+/// Release bundle and the indexes the endpoints read from.
+pub struct RestState {
+    store: MddStore,
+    country_stats: CountryMDDStats,
+    search_index: SearchIndex,
+}
+
+impl RestState {
+    pub fn new(release: ReleasedMddData) -> Self {
+        let species: Vec<MddData> = release.species().cloned().collect();
+        let mut country_stats = CountryMDDStats::new();
+        country_stats.parse_country_data(&species);
+        Self {
+            search_index: SearchIndex::from_release(&release),
+            country_stats,
+            store: MddStore::new(release),
+        }
+    }
+
+    fn species(&self) -> impl Iterator<Item = &MddData> {
+        self.store.release().species()
+    }
+}
+
+/// Axum router with every REST endpoint.
+pub fn router(state: Arc<RestState>) -> Router {
+    Router::new()
+        .route("/species", get(list_species))
+        .route("/species/{id}", get(get_species))
+        .route("/synonyms", get(list_synonyms))
+        .route("/countries/{code}/species", get(country_species))
+        .route("/search", get(search))
+        .with_state(state)
+}
+
+/// Serve `router` on `addr` until the process is stopped.
+pub async fn serve(router: Router, addr: SocketAddr) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router).await
+}
+
+/// Query-string filters of the species endpoints.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeciesParams {
+    pub family: Option<String>,
+    pub order: Option<String>,
+    pub genus: Option<String>,
+    pub country: Option<String>,
+    /// Comma-separated IUCN status codes, e.g. `EN,CR`.
+    pub iucn: Option<String>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+impl SpeciesParams {
+    fn to_query(&self) -> SpeciesQuery {
+        let mut query = SpeciesQuery::new();
+        if let Some(family) = &self.family {
+            query = query.family(family);
+        }
+        if let Some(order) = &self.order {
+            query = query.order(order);
+        }
+        if let Some(genus) = &self.genus {
+            query = query.genus(genus);
+        }
+        if let Some(country) = &self.country {
+            query = query.country(country);
+        }
+        if let Some(iucn) = &self.iucn {
+            query = query.iucn_in(iucn.split(',').map(str::trim));
+        }
+        query
+    }
+}
+
+/// Query-string filters of `/synonyms`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SynonymParams {
+    pub species_id: Option<u32>,
+    pub validity: Option<String>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/// Query string of `/search`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchParams {
+    pub q: String,
+    pub limit: Option<usize>,
+}
+
+async fn list_species(
+    State(state): State<Arc<RestState>>,
+    Query(params): Query<SpeciesParams>,
+) -> Json<Page<MddData>> {
+    let query = params.to_query();
+    let matches = state
+        .species()
+        .filter(|species| query.matches(species))
+        .cloned();
+    Json(Page::new(matches, params.offset, params.limit))
+}
+
+async fn get_species(
+    State(state): State<Arc<RestState>>,
+    Path(id): Path<u32>,
+) -> Result<Json<SimpleMDD>, StatusCode> {
+    state
+        .store
+        .get(id)
+        .map(|entry| Json(entry.clone()))
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn list_synonyms(
+    State(state): State<Arc<RestState>>,
+    Query(params): Query<SynonymParams>,
+) -> Json<Page<SynonymData>> {
+    let synonyms: Box<dyn Iterator<Item = &SynonymData>> = match params.species_id {
+        Some(species_id) => Box::new(
+            state
+                .store
+                .synonym_ids(species_id)
+                .iter()
+                .filter_map(|syn_id| state.store.synonym(*syn_id)),
+        ),
+        None => Box::new(state.store.release().all_synonyms()),
+    };
+    let matches = synonyms
+        .filter(|synonym| match &params.validity {
+            Some(validity) => synonym.validity().eq_ignore_ascii_case(validity),
+            None => true,
+        })
+        .cloned();
+    Json(Page::new(matches, params.offset, params.limit))
+}
+
+async fn country_species(
+    State(state): State<Arc<RestState>>,
+    Path(code): Path<String>,
+    Query(params): Query<SpeciesParams>,
+) -> Result<Json<Page<MddData>>, StatusCode> {
+    let country = state
+        .country_stats
+        .country_data
+        .get(&code.to_uppercase())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let query = params.to_query();
+    // Predicted occurrences are stored with a trailing `?`.
+    let matches = country
+        .species_list
+        .iter()
+        .filter_map(|id| id.trim_end_matches('?').parse().ok())
+        .filter_map(|id| state.store.species(id))
+        .filter(|species| query.matches(species))
+        .cloned();
+    Ok(Json(Page::new(matches, params.offset, params.limit)))
+}
+
+async fn search(
+    State(state): State<Arc<RestState>>,
+    Query(params): Query<SearchParams>,
+) -> Json<Vec<SearchMatch>> {
+    let limit = params.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    Json(state.search_index.search(&params.q, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> Arc<RestState> {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let release = ReleasedMddData::from_parser(species, Vec::new(), "2.0", "2025-01-01");
+        Arc::new(RestState::new(release))
+    }
+
+    fn params(query: &str) -> SpeciesParams {
+        Query::try_from_uri(&format!("/species?{}", query).parse().unwrap())
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn test_rest_handlers() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let state = state();
+
+        let list = |query: &str| {
+            let Json(page) =
+                runtime.block_on(list_species(State(state.clone()), Query(params(query))));
+            let ids: Vec<u32> = page.items.iter().map(|species| species.id).collect();
+            (ids, page.total_count, page.next_offset)
+        };
+        assert_eq!(
+            list("family=ochotonidae&iucn=EN,CR&limit=2"),
+            (vec![1001150, 1001160], 4, Some(2))
+        );
+        assert_eq!(
+            list("family=ochotonidae&iucn=EN,CR&limit=2&offset=2"),
+            (vec![1001163, 1001164], 4, None)
+        );
+        assert_eq!(list("family=ochotonidae&iucn=CR"), (Vec::new(), 0, None));
+
+        let id = state.store.release().data[0].mdd_id();
+        let Json(entry) = runtime
+            .block_on(get_species(State(state.clone()), Path(id)))
+            .unwrap();
+        assert_eq!(entry.mdd_id(), id);
+        assert_eq!(
+            runtime
+                .block_on(get_species(State(state.clone()), Path(1)))
+                .unwrap_err(),
+            StatusCode::NOT_FOUND
+        );
+
+        let Json(page) = runtime
+            .block_on(country_species(
+                State(state.clone()),
+                Path("za".to_string()),
+                Query(SpeciesParams::default()),
+            ))
+            .unwrap();
+        assert!(page
+            .items
+            .iter()
+            .any(|species| species.sci_name == "Bunolagus_monticularis"));
+
+        let Json(hits) = runtime.block_on(search(
+            State(state),
+            Query(SearchParams {
+                q: "Bunolagus monticularis".to_string(),
+                limit: None,
+            }),
+        ));
+        assert_eq!(hits[0].sci_name, "Bunolagus_monticularis");
+    }
+}
@@ -0,0 +1,300 @@
+//! In-memory name search over species and synonyms.
+//!
+//! `SearchIndex` indexes scientific names, main and other common names, and
+//! the root names and original combinations of synonyms. Lookups are
+//! case-insensitive and treat the MDD `_` separator as a space, so
+//! `panthera leo`, `Panthera_leo`, and `PANTHERA LEO` are equivalent.
+//!
+//! Each lookup tries, in rank order:
+//! 1. exact matches of the whole name;
+//! 2. prefix matches (`panth` → `Panthera leo`);
+//! 3. fuzzy matches within `max_distance` Levenshtein edits.
+//!
+//! Results keep only the best match per species and report which field matched.
+//!
+//! ```rust, ignore
+//! use mdd_api::search::SearchIndex;
+//!
+//! let index = SearchIndex::new(&species, &synonyms);
+//! for hit in index.search("Pantera leo", 5) {
+//!     println!("{} via {:?} ({:?})", hit.sci_name, hit.field, hit.kind);
+//! }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::{
+    helper::{levenshtein, normalize_name, MDD_MISSING_VALUE},
+    parser::{mdd::MddData, synonyms::SynonymData, ReleasedMddData},
+};
+
+/// Default number of edits tolerated by fuzzy matching.
+pub const DEFAULT_MAX_DISTANCE: usize = 2;
+/// Queries shorter than this (in characters) are not matched fuzzily.
+const MIN_FUZZY_QUERY_LEN: usize = 4;
+
+/// Field a match was found in, in ranking priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchField {
+    SciName,
+    MainCommonName,
+    OtherCommonName,
+    SynonymOriginalCombination,
+    SynonymRootName,
+}
+
+/// How a query matched, best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchKind {
+    Exact,
+    Prefix,
+    Fuzzy,
+}
+
+/// A ranked search result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchMatch {
+    pub species_id: u32,
+    /// Scientific name of the matched species.
+    pub sci_name: String,
+    /// Indexed name that matched, verbatim.
+    pub matched: String,
+    pub field: MatchField,
+    pub kind: MatchKind,
+    /// Levenshtein distance between the query and the matched name (0 unless fuzzy).
+    pub distance: usize,
+}
+
+impl SearchMatch {
+    fn rank(&self) -> (MatchKind, usize, MatchField, usize, u32) {
+        (
+            self.kind,
+            self.distance,
+            self.field,
+            self.matched.len(),
+            self.species_id,
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IndexEntry {
+    key: String,
+    text: String,
+    field: MatchField,
+    species_id: u32,
+}
+
+/// Name index over a set of species and their synonyms.
+#[derive(Debug, Clone)]
+pub struct SearchIndex {
+    entries: Vec<IndexEntry>,
+    sci_names: HashMap<u32, String>,
+    max_distance: usize,
+}
+
+impl SearchIndex {
+    /// Index `species` and the synonyms attached to them.
+    ///
+    /// Synonyms without a `species_id`, or pointing at a species missing
+    /// from `species`, are not indexed.
+    pub fn new(species: &[MddData], synonyms: &[SynonymData]) -> Self {
+        let mut index = Self {
+            entries: Vec::new(),
+            sci_names: HashMap::new(),
+            max_distance: DEFAULT_MAX_DISTANCE,
+        };
+        for record in species {
+            index.add_species(record);
+        }
+        for synonym in synonyms {
+            index.add_synonym(synonym);
+        }
+        index
+    }
+
+    /// Index the species and synonyms of a release.
+    pub fn from_release(release: &ReleasedMddData) -> Self {
+        let species: Vec<MddData> = release.species().cloned().collect();
+        let synonyms: Vec<SynonymData> = release
+            .data
+            .iter()
+            .flat_map(|entry| entry.synonyms().iter().cloned())
+            .collect();
+        Self::new(&species, &synonyms)
+    }
+
+    /// Set the number of edits tolerated by fuzzy matching (`0` disables it).
+    pub fn with_max_distance(mut self, max_distance: usize) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    /// Number of indexed names.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn add_species(&mut self, record: &MddData) {
+        self.sci_names.insert(record.id, record.sci_name.clone());
+        self.push(record.id, MatchField::SciName, &record.sci_name);
+        self.push(
+            record.id,
+            MatchField::MainCommonName,
+            &record.main_common_name,
+        );
+        for name in record.common_names() {
+            self.push(record.id, MatchField::OtherCommonName, name);
+        }
+    }
+
+    fn add_synonym(&mut self, synonym: &SynonymData) {
+        let species_id = match synonym.species_id {
+            Some(id) if self.sci_names.contains_key(&id) => id,
+            _ => return,
+        };
+        self.push(
+            species_id,
+            MatchField::SynonymOriginalCombination,
+            synonym.original_combination(),
+        );
+        self.push(species_id, MatchField::SynonymRootName, synonym.root_name());
+    }
+
+    fn push(&mut self, species_id: u32, field: MatchField, text: &str) {
+        let text = text.trim();
+        if text.is_empty() || text == MDD_MISSING_VALUE {
+            return;
+        }
+        self.entries.push(IndexEntry {
+            key: normalize_name(text),
+            text: text.to_string(),
+            field,
+            species_id,
+        });
+    }
+
+    /// Find up to `limit` species matching `query`, best first.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchMatch> {
+        let query = normalize_name(query);
+        if query.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+        let fuzzy = self.max_distance > 0 && query.chars().count() >= MIN_FUZZY_QUERY_LEN;
+        let mut best: HashMap<u32, SearchMatch> = HashMap::new();
+        for entry in &self.entries {
+            let (kind, distance) = if entry.key == query {
+                (MatchKind::Exact, 0)
+            } else if entry.key.starts_with(&query) {
+                (MatchKind::Prefix, 0)
+            } else if fuzzy && within_length(&entry.key, &query, self.max_distance) {
+                let distance = levenshtein(&entry.key, &query);
+                if distance > self.max_distance {
+                    continue;
+                }
+                (MatchKind::Fuzzy, distance)
+            } else {
+                continue;
+            };
+            let hit = SearchMatch {
+                species_id: entry.species_id,
+                sci_name: self.sci_names[&entry.species_id].clone(),
+                matched: entry.text.clone(),
+                field: entry.field,
+                kind,
+                distance,
+            };
+            match best.get(&entry.species_id) {
+                Some(current) if current.rank() <= hit.rank() => (),
+                _ => {
+                    best.insert(entry.species_id, hit);
+                }
+            }
+        }
+        let mut hits: Vec<SearchMatch> = best.into_values().collect();
+        hits.sort_by_key(|hit| hit.rank());
+        hits.truncate(limit);
+        hits
+    }
+
+    /// Distinct species ids whose names start with `prefix`, useful for autocomplete.
+    pub fn complete(&self, prefix: &str) -> Vec<u32> {
+        let prefix = normalize_name(prefix);
+        let mut seen = HashSet::new();
+        self.entries
+            .iter()
+            .filter(|entry| !prefix.is_empty() && entry.key.starts_with(&prefix))
+            .filter(|entry| seen.insert(entry.species_id))
+            .map(|entry| entry.species_id)
+            .collect()
+    }
+}
+
+/// Cheap pre-filter: names whose lengths differ by more than `max` cannot match.
+fn within_length(a: &str, b: &str, max: usize) -> bool {
+    a.chars().count().abs_diff(b.chars().count()) <= max
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::mdd::fixtures::species;
+
+    fn index() -> SearchIndex {
+        let records = vec![
+            species(1, "Panthera_leo", "Lion", "African Lion|King of Beasts"),
+            species(2, "Panthera_pardus", "Leopard", "NA"),
+            species(3, "Puma_concolor", "Puma", "Cougar|Mountain Lion"),
+        ];
+        let mut synonym = SynonymData::new();
+        synonym.species_id = Some(3);
+        synonym.root_name = "concolor".to_string();
+        synonym.original_combination = "Felis concolor".to_string();
+        SearchIndex::new(&records, &[synonym])
+    }
+
+    #[test]
+    fn test_exact_and_prefix() {
+        let index = index();
+        let hits = index.search("panthera leo", 10);
+        assert_eq!(hits[0].species_id, 1);
+        assert_eq!(hits[0].kind, MatchKind::Exact);
+        assert_eq!(hits[0].field, MatchField::SciName);
+
+        let hits = index.search("Panthera", 10);
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|h| h.kind == MatchKind::Prefix));
+
+        let hits = index.search("cougar", 10);
+        assert_eq!(hits[0].field, MatchField::OtherCommonName);
+        let hits = index.search("Felis concolor", 10);
+        assert_eq!(hits[0].species_id, 3);
+        assert_eq!(hits[0].field, MatchField::SynonymOriginalCombination);
+        assert_eq!(index.complete("pan"), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_fuzzy() {
+        let index = index();
+        let hits = index.search("Pantera_pardis", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].species_id, 2);
+        assert_eq!(hits[0].kind, MatchKind::Fuzzy);
+        assert_eq!(hits[0].distance, 2);
+        assert!(index.search("Pantera_pardis", 1).len() == 1);
+        assert!(index
+            .with_max_distance(1)
+            .search("Pantera_pardis", 10)
+            .is_empty());
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+}
@@ -0,0 +1,195 @@
+//! Description-date and authorship statistics.
+//!
+//! `AuthorityStats` aggregates `authority_species_year` and
+//! `authority_species_author` of every species into:
+//! * the number of species described per year and per decade, with the
+//!   running (cumulative) total per decade for plotting discovery curves;
+//! * a table of the authors who described the most species.
+//!
+//! Author strings are counted verbatim, so a species described by
+//! `Thomas & Wroughton` does not count toward `O. Thomas`. Species without a
+//! year (`0`) are listed in `undated` and left out of the time series.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{helper::MDD_MISSING_VALUE, parser::mdd::MddData};
+
+/// Default number of rows in the top-author table.
+pub const DEFAULT_TOP_AUTHORS: usize = 20;
+
+/// Species described in one decade.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecadeCount {
+    /// First year of the decade, e.g. `1750` for 1750–1759.
+    pub decade: u16,
+    pub described: u32,
+    /// Species described up to and including this decade.
+    pub cumulative: u32,
+}
+
+/// Species described by one author (string).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorCount {
+    pub author: String,
+    pub described: u32,
+    /// Earliest dated description by the author (0 when none is dated).
+    pub first_year: u16,
+    /// Latest dated description by the author (0 when none is dated).
+    pub last_year: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorityStats {
+    /// Number of species with a description year.
+    pub total_dated: u32,
+    /// Year of the earliest description (0 when nothing is dated).
+    pub first_year: u16,
+    /// Year of the latest description (0 when nothing is dated).
+    pub last_year: u16,
+    /// Species described per year.
+    pub per_year: BTreeMap<u16, u32>,
+    /// Species described per decade, without gaps between the first and the last decade.
+    pub per_decade: Vec<DecadeCount>,
+    /// Authors ordered by the number of species described, then by name.
+    pub top_authors: Vec<AuthorCount>,
+    /// IDs of species without a description year.
+    pub undated: Vec<u32>,
+    #[serde(skip)]
+    top_limit: usize,
+}
+
+impl Default for AuthorityStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AuthorityStats {
+    pub fn new() -> Self {
+        Self {
+            total_dated: 0,
+            first_year: 0,
+            last_year: 0,
+            per_year: BTreeMap::new(),
+            per_decade: Vec::new(),
+            top_authors: Vec::new(),
+            undated: Vec::new(),
+            top_limit: DEFAULT_TOP_AUTHORS,
+        }
+    }
+
+    /// Set the number of rows kept in the top-author table.
+    pub fn with_top_authors(mut self, limit: usize) -> Self {
+        self.top_limit = limit;
+        self
+    }
+
+    /// Parses the MDD data and updates the description statistics.
+    pub fn parse_authority_data(&mut self, mdd_data: &[MddData]) {
+        let mut authors: HashMap<&str, AuthorCount> = HashMap::new();
+        for species in mdd_data {
+            let year = species.authority_species_year;
+            if year == 0 {
+                self.undated.push(species.id);
+            } else {
+                *self.per_year.entry(year).or_insert(0) += 1;
+            }
+            let author = species.authority_species_author.trim();
+            if author.is_empty() || author == MDD_MISSING_VALUE {
+                continue;
+            }
+            let count = authors.entry(author).or_insert_with(|| AuthorCount {
+                author: author.to_string(),
+                described: 0,
+                first_year: 0,
+                last_year: 0,
+            });
+            count.described += 1;
+            if year > 0 {
+                if count.first_year == 0 || year < count.first_year {
+                    count.first_year = year;
+                }
+                count.last_year = count.last_year.max(year);
+            }
+        }
+        self.update_series();
+        let mut authors: Vec<AuthorCount> = authors.into_values().collect();
+        authors.sort_by(|a, b| {
+            b.described
+                .cmp(&a.described)
+                .then_with(|| a.author.cmp(&b.author))
+        });
+        authors.truncate(self.top_limit);
+        self.top_authors = authors;
+    }
+
+    fn update_series(&mut self) {
+        self.total_dated = self.per_year.values().sum();
+        let (first, last) = match (
+            self.per_year.keys().next(),
+            self.per_year.keys().next_back(),
+        ) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return,
+        };
+        self.first_year = first;
+        self.last_year = last;
+        let mut per_decade: BTreeMap<u16, u32> = (decade_of(first)..=decade_of(last))
+            .step_by(10)
+            .map(|decade| (decade, 0))
+            .collect();
+        for (year, count) in &self.per_year {
+            *per_decade.entry(decade_of(*year)).or_insert(0) += count;
+        }
+        let mut cumulative = 0;
+        self.per_decade = per_decade
+            .into_iter()
+            .map(|(decade, described)| {
+                cumulative += described;
+                DecadeCount {
+                    decade,
+                    described,
+                    cumulative,
+                }
+            })
+            .collect();
+    }
+}
+
+fn decade_of(year: u16) -> u16 {
+    year - year % 10
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_authority_data() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let mut stats = AuthorityStats::new().with_top_authors(3);
+        stats.parse_authority_data(&species);
+        assert_eq!(stats.total_dated, 112);
+        assert!(stats.undated.is_empty());
+        assert_eq!(stats.first_year, 1758);
+        assert_eq!(stats.per_decade[0].decade, 1750);
+        let last = stats.per_decade.last().unwrap();
+        assert_eq!(last.cumulative, 112);
+        assert_eq!(last.decade, decade_of(stats.last_year));
+        assert_eq!(
+            stats.per_decade.len(),
+            ((last.decade - 1750) / 10 + 1) as usize
+        );
+        assert_eq!(stats.top_authors.len(), 3);
+        assert_eq!(stats.top_authors[0].author, "O. Thomas");
+        assert_eq!(stats.top_authors[0].described, 15);
+        // Ties are ordered by author name.
+        assert_eq!(stats.top_authors[1].author, "J. A. Allen");
+    }
+}
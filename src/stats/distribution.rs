@@ -0,0 +1,210 @@
+//! Continent and biogeographic realm aggregation.
+//!
+//! `RegionStats` groups species by the multi-value `continent_distribution`
+//! or `biogeographic_realm` field, mirroring `CountryMDDStats` for countries:
+//! a species occurring in several continents (realms) counts in each of them.
+//! Uncertain occurrences (values ending with `?`) are included and keep the
+//! `?` on the stored species ID so callers can tell them apart.
+//!
+//! Species marked `Domesticated` are listed in `domesticated`; species
+//! without a value (empty or `NA`) are listed in `unassigned`.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::parser::mdd::{ListToken, MddData};
+
+const DOMESTICATED: &str = "domesticated";
+
+/// Species richness of one continent or realm.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionData {
+    pub total_orders: u32,
+    pub total_families: u32,
+    pub total_genera: u32,
+    pub total_living_species: u32,
+    pub total_extinct_species: u32,
+    /// MDD species IDs in the region, living first; uncertain occurrences end with `?`.
+    pub species_list: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionStats {
+    /// Number of continents or realms (size of `region_data`).
+    pub total_regions: u32,
+    /// IDs of species classified as domesticated.
+    pub domesticated: Vec<u32>,
+    /// IDs of species without a continent or realm.
+    pub unassigned: Vec<u32>,
+    /// Map of continent or realm name to its `RegionData`.
+    pub region_data: BTreeMap<String, RegionData>,
+}
+
+impl Default for RegionStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegionStats {
+    pub fn new() -> Self {
+        Self {
+            total_regions: 0,
+            domesticated: Vec::new(),
+            unassigned: Vec::new(),
+            region_data: BTreeMap::new(),
+        }
+    }
+
+    /// Parses `continent_distribution` of the MDD data.
+    pub fn parse_continent_data(&mut self, mdd_data: &[MddData]) {
+        self.parse_regions(mdd_data, |species| {
+            (
+                species.continent_distribution.as_str(),
+                species.continents(),
+            )
+        });
+    }
+
+    /// Parses `biogeographic_realm` of the MDD data.
+    pub fn parse_realm_data(&mut self, mdd_data: &[MddData]) {
+        self.parse_regions(mdd_data, |species| {
+            (species.biogeographic_realm.as_str(), species.realms())
+        });
+    }
+
+    /// Number of species per region, living and extinct.
+    pub fn richness(&self) -> BTreeMap<&str, u32> {
+        self.region_data
+            .iter()
+            .map(|(name, data)| {
+                (
+                    name.as_str(),
+                    data.total_living_species + data.total_extinct_species,
+                )
+            })
+            .collect()
+    }
+
+    fn parse_regions<F>(&mut self, mdd_data: &[MddData], regions: F)
+    where
+        F: Fn(&MddData) -> (&str, Vec<ListToken<'_>>),
+    {
+        let mut records: BTreeMap<String, RegionRecord> = BTreeMap::new();
+        for species in mdd_data {
+            let (raw, tokens) = regions(species);
+            if raw.trim().eq_ignore_ascii_case(DOMESTICATED) {
+                self.domesticated.push(species.id);
+                continue;
+            }
+            if tokens.is_empty() {
+                self.unassigned.push(species.id);
+                continue;
+            }
+            // "Asia|Asia?" counts once, as certain.
+            let mut uncertain_by_region: BTreeMap<&str, bool> = BTreeMap::new();
+            for token in tokens {
+                *uncertain_by_region.entry(token.value).or_insert(true) &= token.uncertain;
+            }
+            for (region, uncertain) in uncertain_by_region {
+                records
+                    .entry(region.to_string())
+                    .or_default()
+                    .update(species, uncertain);
+            }
+        }
+        self.region_data = records
+            .into_iter()
+            .map(|(name, record)| (name, record.into_data()))
+            .collect();
+        self.total_regions = self.region_data.len() as u32;
+    }
+}
+
+// Tracks unique orders, families, and genera of a region.
+#[derive(Default)]
+struct RegionRecord {
+    orders: HashSet<String>,
+    families: HashSet<String>,
+    genera: HashSet<String>,
+    living_species_ids: Vec<String>,
+    extinct_species_ids: Vec<String>,
+}
+
+impl RegionRecord {
+    fn update(&mut self, species: &MddData, uncertain: bool) {
+        let id = if uncertain {
+            format!("{}?", species.id)
+        } else {
+            species.id.to_string()
+        };
+        if species.extinct == 1 {
+            self.extinct_species_ids.push(id);
+        } else {
+            self.living_species_ids.push(id);
+        }
+        self.orders.insert(species.taxon_order.clone());
+        self.families.insert(species.family.clone());
+        self.genera.insert(species.genus.clone());
+    }
+
+    fn into_data(self) -> RegionData {
+        RegionData {
+            total_orders: self.orders.len() as u32,
+            total_families: self.families.len() as u32,
+            total_genera: self.genera.len() as u32,
+            total_living_species: self.living_species_ids.len() as u32,
+            total_extinct_species: self.extinct_species_ids.len() as u32,
+            species_list: self
+                .living_species_ids
+                .into_iter()
+                .chain(self.extinct_species_ids)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_regions() {
+        let species = vec![
+            MddData {
+                id: 1,
+                continent_distribution: "Asia|Europe?".to_string(),
+                biogeographic_realm: "Palearctic|Palearctic?".to_string(),
+                ..MddData::new()
+            },
+            MddData {
+                id: 2,
+                extinct: 1,
+                continent_distribution: "Asia".to_string(),
+                biogeographic_realm: "NA".to_string(),
+                ..MddData::new()
+            },
+            MddData {
+                id: 3,
+                continent_distribution: "Domesticated".to_string(),
+                ..MddData::new()
+            },
+        ];
+        let mut continents = RegionStats::new();
+        continents.parse_continent_data(&species);
+        assert_eq!(continents.total_regions, 2);
+        assert_eq!(continents.domesticated, vec![3]);
+        assert_eq!(continents.region_data["Asia"].species_list, vec!["1", "2"]);
+        assert_eq!(continents.region_data["Asia"].total_extinct_species, 1);
+        assert_eq!(continents.region_data["Europe"].species_list, vec!["1?"]);
+        assert_eq!(continents.richness()["Asia"], 2);
+
+        let mut realms = RegionStats::new();
+        realms.parse_realm_data(&species);
+        assert_eq!(realms.region_data["Palearctic"].species_list, vec!["1"]);
+        assert_eq!(realms.unassigned, vec![2, 3]);
+    }
+}
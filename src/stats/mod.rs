@@ -0,0 +1,12 @@
+//! Summary statistics derived from MDD species records.
+//!
+//! * `authority` – species described per year and decade, the cumulative
+//!   description curve, and the most prolific describing authors.
+//! * `distribution` – species richness and species lists per continent and
+//!   per biogeographic realm.
+//! * `summary` – headline counts (species, orders, families, IUCN, countries)
+//!   printed by `mdd stats`.
+
+pub mod authority;
+pub mod distribution;
+pub mod summary;
@@ -0,0 +1,188 @@
+//! Headline numbers of a species list.
+//!
+//! `DatasetSummary` is the quick overview printed by `mdd stats`: species,
+//! extinct, domestic, and flagged counts, species per order and family, the
+//! IUCN category breakdown, and the number of countries covered. It only
+//! needs the species CSV, not a full release bundle;
+//! `DatasetSummary::from_csv_borrowed` streams it without building records.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Read,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    helper::iucn::IucnStatus,
+    parser::{
+        borrowed::MddDataRef, error::MddError, iucn::CategoryCounts, mdd::MddData,
+        options::CsvDialect,
+    },
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetSummary {
+    pub total_species: u32,
+    pub extinct: u32,
+    pub domestic: u32,
+    pub flagged: u32,
+    pub total_genera: u32,
+    /// Species per order.
+    pub per_order: BTreeMap<String, u32>,
+    /// Species per family.
+    pub per_family: BTreeMap<String, u32>,
+    /// Species per IUCN category code; annotated statuses count under their category.
+    pub iucn: CategoryCounts,
+    /// Species whose IUCN status is empty or not a category.
+    pub iucn_unrecognized: u32,
+    /// Distinct countries in `countryDistribution` (predicted occurrences included).
+    pub total_countries: u32,
+}
+
+impl Default for DatasetSummary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DatasetSummary {
+    pub fn new() -> Self {
+        Self {
+            total_species: 0,
+            extinct: 0,
+            domestic: 0,
+            flagged: 0,
+            total_genera: 0,
+            per_order: BTreeMap::new(),
+            per_family: BTreeMap::new(),
+            iucn: BTreeMap::new(),
+            iucn_unrecognized: 0,
+            total_countries: 0,
+        }
+    }
+
+    /// Parses the MDD data and updates the summary.
+    pub fn parse_summary_data(&mut self, mdd_data: &[MddData]) {
+        let mut genera = BTreeSet::new();
+        let mut countries = BTreeSet::new();
+        for species in mdd_data {
+            self.add_species(&species.as_borrowed(), &mut genera, &mut countries);
+        }
+        self.total_genera += genera.len() as u32;
+        self.total_countries += countries.len() as u32;
+    }
+
+    /// Summarize a species CSV row by row, without collecting `MddData`.
+    pub fn from_csv_borrowed<R: Read>(reader: R, dialect: &CsvDialect) -> Result<Self, MddError> {
+        let mut summary = Self::new();
+        let mut genera = BTreeSet::new();
+        let mut countries = BTreeSet::new();
+        MddDataRef::from_csv_borrowed_with(reader, dialect, |species| {
+            summary.add_species(&species, &mut genera, &mut countries);
+        })?;
+        summary.total_genera = genera.len() as u32;
+        summary.total_countries = countries.len() as u32;
+        Ok(summary)
+    }
+
+    fn add_species(
+        &mut self,
+        species: &MddDataRef<'_>,
+        genera: &mut BTreeSet<String>,
+        countries: &mut BTreeSet<String>,
+    ) {
+        self.total_species += 1;
+        self.extinct += u32::from(species.extinct == 1);
+        self.domestic += u32::from(species.domestic == 1);
+        self.flagged += u32::from(species.flagged == 1);
+        insert_new(genera, species.genus);
+        count(&mut self.per_order, species.taxon_order);
+        count(&mut self.per_family, species.family);
+        match species.iucn_status.parse::<IucnStatus>() {
+            Ok(status) => count(&mut self.iucn, status.code()),
+            Err(_) => self.iucn_unrecognized += 1,
+        }
+        for country in species.countries() {
+            insert_new(countries, country.name);
+        }
+    }
+
+    /// Short human-readable summary.
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} species ({} extinct, {} domestic, {} flagged)\n",
+            self.total_species, self.extinct, self.domestic, self.flagged
+        );
+        summary.push_str(&format!(
+            "{} orders, {} families, {} genera, {} countries\n",
+            self.per_order.len(),
+            self.per_family.len(),
+            self.total_genera,
+            self.total_countries
+        ));
+        let iucn: Vec<String> = self
+            .iucn
+            .iter()
+            .map(|(code, count)| format!("{} {}", code, count))
+            .collect();
+        summary.push_str(&format!("IUCN: {}", iucn.join(", ")));
+        if self.iucn_unrecognized > 0 {
+            summary.push_str(&format!(", unrecognized {}", self.iucn_unrecognized));
+        }
+        summary.push('\n');
+        for (title, counts) in [("Orders", &self.per_order), ("Families", &self.per_family)] {
+            summary.push_str(&format!("{}:\n", title));
+            for (name, count) in counts {
+                summary.push_str(&format!("  {}: {}\n", name, count));
+            }
+        }
+        summary
+    }
+}
+
+/// Increment the count of `key`, allocating only for a new key.
+fn count(counts: &mut BTreeMap<String, u32>, key: &str) {
+    match counts.get_mut(key) {
+        Some(n) => *n += 1,
+        None => {
+            counts.insert(key.to_string(), 1);
+        }
+    }
+}
+
+/// Insert `value` into `set`, allocating only when it is new.
+fn insert_new(set: &mut BTreeSet<String>, value: &str) {
+    if !set.contains(value) {
+        set.insert(value.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_summary_data() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let mut summary = DatasetSummary::new();
+        summary.parse_summary_data(&species);
+        assert_eq!(summary.total_species, 112);
+        assert_eq!(summary.extinct, 1);
+        assert_eq!(summary.flagged, 12);
+        assert_eq!(summary.per_order["Lagomorpha"], 112);
+        assert_eq!(summary.per_family.values().sum::<u32>(), 112);
+        assert_eq!(summary.iucn["LC"], 62);
+        assert!(summary.total_countries > 0);
+        assert!(summary
+            .summary()
+            .starts_with("112 species (1 extinct, 0 domestic, 12 flagged)\n"));
+
+        let streamed =
+            DatasetSummary::from_csv_borrowed(csv_data.as_bytes(), &CsvDialect::default()).unwrap();
+        assert_eq!(streamed.summary(), summary.summary());
+        assert_eq!(streamed.total_countries, summary.total_countries);
+    }
+}
@@ -0,0 +1,246 @@
+//! Release bundle with prebuilt lookup indexes.
+//!
+//! `ReleasedMddData` keeps species and synonyms in vectors, so every lookup
+//! scans the release. `MddStore` owns a release and indexes it once:
+//!
+//! | Lookup | Index |
+//! |--------|-------|
+//! | species id → entry | `HashMap` |
+//! | scientific name → species id | `HashMap` |
+//! | genus / family → species ids | `BTreeMap` |
+//! | species id → synonym ids | `HashMap` |
+//! | synonym id → synonym | `HashMap` |
+//!
+//! Names are matched case-insensitively with `_` and spaces equivalent, as
+//! in `search`. Id lists keep release order.
+//!
+//! ```rust, ignore
+//! use mdd_api::store::MddStore;
+//!
+//! let store = MddStore::new(release);
+//! let lion = store.species_by_name("Panthera leo").unwrap();
+//! for id in store.genus_ids("Panthera") {
+//!     println!("{}", store.species(*id).unwrap().sci_name);
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    helper::normalize_name,
+    parser::{mdd::MddData, synonyms::SynonymData, ReleasedMddData, SimpleMDD},
+};
+
+/// Where a synonym lives in the release.
+#[derive(Debug, Clone, Copy)]
+enum SynonymSlot {
+    /// Entry position and position within its synonyms.
+    Attached(usize, usize),
+    /// Position in `synonym_only`.
+    Unattached(usize),
+}
+
+/// Release bundle indexed by id and name.
+#[derive(Debug, Clone)]
+pub struct MddStore {
+    release: ReleasedMddData,
+    by_id: HashMap<u32, usize>,
+    by_name: HashMap<String, u32>,
+    by_genus: BTreeMap<String, Vec<u32>>,
+    by_family: BTreeMap<String, Vec<u32>>,
+    synonyms_by_species: HashMap<u32, Vec<u32>>,
+    synonyms: HashMap<u32, SynonymSlot>,
+}
+
+impl MddStore {
+    /// Index `release`.
+    pub fn new(release: ReleasedMddData) -> Self {
+        let mut store = Self {
+            release,
+            by_id: HashMap::new(),
+            by_name: HashMap::new(),
+            by_genus: BTreeMap::new(),
+            by_family: BTreeMap::new(),
+            synonyms_by_species: HashMap::new(),
+            synonyms: HashMap::new(),
+        };
+        store.build();
+        store
+    }
+
+    fn build(&mut self) {
+        for (position, entry) in self.release.data.iter().enumerate() {
+            let species = entry.species();
+            self.by_id.insert(species.id, position);
+            self.by_name
+                .insert(normalize_name(&species.sci_name), species.id);
+            self.by_genus
+                .entry(normalize_name(&species.genus))
+                .or_default()
+                .push(species.id);
+            self.by_family
+                .entry(normalize_name(&species.family))
+                .or_default()
+                .push(species.id);
+            for (slot, synonym) in entry.synonyms().iter().enumerate() {
+                self.synonyms
+                    .insert(synonym.syn_id, SynonymSlot::Attached(position, slot));
+                self.synonyms_by_species
+                    .entry(species.id)
+                    .or_default()
+                    .push(synonym.syn_id);
+            }
+        }
+        for (slot, synonym) in self.release.synonym_only.iter().enumerate() {
+            self.synonyms
+                .insert(synonym.syn_id, SynonymSlot::Unattached(slot));
+        }
+    }
+
+    /// The indexed release.
+    pub fn release(&self) -> &ReleasedMddData {
+        &self.release
+    }
+
+    /// Give back the release, dropping the indexes.
+    pub fn into_release(self) -> ReleasedMddData {
+        self.release
+    }
+
+    /// Number of species.
+    pub fn len(&self) -> usize {
+        self.release.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.release.data.is_empty()
+    }
+
+    /// Species entry (record and synonyms) by MDD id.
+    pub fn get(&self, id: u32) -> Option<&SimpleMDD> {
+        self.by_id
+            .get(&id)
+            .map(|&position| &self.release.data[position])
+    }
+
+    /// Species record by MDD id.
+    pub fn species(&self, id: u32) -> Option<&MddData> {
+        self.get(id).map(|entry| entry.species())
+    }
+
+    /// MDD id of the species with scientific name `sci_name`.
+    pub fn id_by_name(&self, sci_name: &str) -> Option<u32> {
+        self.by_name.get(&normalize_name(sci_name)).copied()
+    }
+
+    /// Species record by scientific name.
+    pub fn species_by_name(&self, sci_name: &str) -> Option<&MddData> {
+        self.id_by_name(sci_name).and_then(|id| self.species(id))
+    }
+
+    /// Ids of the species in `genus`; empty for an unknown genus.
+    pub fn genus_ids(&self, genus: &str) -> &[u32] {
+        self.by_genus
+            .get(&normalize_name(genus))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Ids of the species in `family`; empty for an unknown family.
+    pub fn family_ids(&self, family: &str) -> &[u32] {
+        self.by_family
+            .get(&normalize_name(family))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Ids of the synonyms attached to species `species_id`.
+    pub fn synonym_ids(&self, species_id: u32) -> &[u32] {
+        self.synonyms_by_species
+            .get(&species_id)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Synonym by MDD synonym id, attached or synonym-only.
+    pub fn synonym(&self, syn_id: u32) -> Option<&SynonymData> {
+        self.synonyms.get(&syn_id).map(|slot| match *slot {
+            SynonymSlot::Attached(position, slot) => &self.release.data[position].synonyms()[slot],
+            SynonymSlot::Unattached(slot) => &self.release.synonym_only[slot],
+        })
+    }
+
+    /// Genera in alphabetical order, lower-cased.
+    pub fn genera(&self) -> impl Iterator<Item = &str> {
+        self.by_genus.keys().map(String::as_str)
+    }
+
+    /// Families in alphabetical order, lower-cased.
+    pub fn families(&self) -> impl Iterator<Item = &str> {
+        self.by_family.keys().map(String::as_str)
+    }
+}
+
+#[cfg(feature = "db")]
+impl MddStore {
+    /// Load and index a SQLite export (see `db::open_release`).
+    pub fn from_sqlite<P: AsRef<std::path::Path>>(
+        path: P,
+    ) -> Result<Self, crate::parser::error::MddError> {
+        crate::db::open_release(path).map(Self::new)
+    }
+}
+
+impl From<ReleasedMddData> for MddStore {
+    fn from(release: ReleasedMddData) -> Self {
+        Self::new(release)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> MddStore {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let syn_data = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let mut synonyms = SynonymData::new().from_csv(&syn_data);
+        synonyms[0].species_id = Some(species[0].id);
+        for synonym in synonyms.iter_mut().skip(1) {
+            synonym.species_id = None;
+        }
+        MddStore::new(ReleasedMddData::from_parser(
+            species,
+            synonyms,
+            "2.0",
+            "2025-01-01",
+        ))
+    }
+
+    #[test]
+    fn test_store_lookups() {
+        let store = store();
+        assert_eq!(store.len(), 112);
+
+        let species = store.species(1001076).unwrap();
+        assert_eq!(species.sci_name, "Bunolagus_monticularis");
+        assert_eq!(store.id_by_name("bunolagus  Monticularis"), Some(1001076));
+        assert_eq!(
+            store.species_by_name("Bunolagus_monticularis").unwrap().id,
+            1001076
+        );
+        assert!(store.get(1).is_none());
+
+        assert_eq!(store.genus_ids("Bunolagus"), &[1001076]);
+        let family = store.family_ids("ochotonidae");
+        assert!(!family.is_empty());
+        assert!(family
+            .iter()
+            .all(|id| store.species(*id).unwrap().family == "Ochotonidae"));
+        assert!(store.family_ids("Felidae").is_empty());
+        assert_eq!(store.families().count(), 3);
+
+        assert_eq!(store.synonym_ids(1001076), &[100022090]);
+        assert_eq!(store.synonym(100022090).unwrap().root_name(), "latidens");
+        let unattached = store.release().synonym_only[0].syn_id;
+        assert!(store.synonym(unattached).unwrap().species_id.is_none());
+    }
+}
@@ -0,0 +1,230 @@
+//! Vernacular (common) name table and lookup.
+//!
+//! `VernacularIndex` splits `mainCommonName` and the `|`-separated
+//! `otherCommonNames` of every species into one `VernacularName` row each.
+//! MDD common names are English and untagged; a name may carry an explicit
+//! language tag as a trailing ISO 639 code in brackets or parentheses
+//! (`Lièvre d'Europe [fr]`, `Feldhase (de)`), which is stripped from the name
+//! and kept in `language`.
+//!
+//! Lookups ignore case, diacritics, and punctuation, so `lievre d europe`
+//! finds `Lièvre d'Europe`. The table is written as CSV and JSON
+//! (`vernacular_names.{csv,json}`) for search frontends.
+//!
+//! ```rust, ignore
+//! use mdd_api::vernacular::VernacularIndex;
+//!
+//! let index = VernacularIndex::from_species(&species);
+//! assert_eq!(index.lookup("snowshoe rabbit"), &[1001090]);
+//! index.write_files(Path::new("."))?;
+//! ```
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    crosswalk::write_table,
+    helper::{slug::fold_ascii, split_mdd_list, MDD_MISSING_VALUE},
+    parser::mdd::MddData,
+};
+
+/// File stem of the vernacular name table.
+pub const VERNACULAR_FILE: &str = "vernacular_names";
+
+/// One common name of a species.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VernacularName {
+    pub species_id: u32,
+    pub sci_name: String,
+    /// The name without its language tag.
+    pub name: String,
+    /// ISO 639 code of an explicit language tag; `None` for untagged names.
+    pub language: Option<String>,
+    /// Whether the name is the `mainCommonName`.
+    pub main: bool,
+}
+
+/// Common names of a release with a folded-name index.
+#[derive(Debug, Clone, Default)]
+pub struct VernacularIndex {
+    names: Vec<VernacularName>,
+    by_key: HashMap<String, Vec<u32>>,
+}
+
+impl VernacularIndex {
+    /// Collect the common names of `species`, main name first, in input order.
+    pub fn from_species(species: &[MddData]) -> Self {
+        let mut index = Self::default();
+        for record in species {
+            let main = record.main_common_name.trim();
+            let main = (!main.is_empty() && main != MDD_MISSING_VALUE).then_some((main, true));
+            let others = split_mdd_list(&record.other_common_names).map(|name| (name, false));
+            for (token, main) in main.into_iter().chain(others) {
+                let (name, language) = split_language_tag(token);
+                index.push(VernacularName {
+                    species_id: record.id,
+                    sci_name: record.sci_name.clone(),
+                    name: name.to_string(),
+                    language: language.map(str::to_string),
+                    main,
+                });
+            }
+        }
+        index
+    }
+
+    fn push(&mut self, name: VernacularName) {
+        let ids = self.by_key.entry(fold_name(&name.name)).or_default();
+        if !ids.contains(&name.species_id) {
+            ids.push(name.species_id);
+        }
+        self.names.push(name);
+    }
+
+    /// Every name row, in input order.
+    pub fn names(&self) -> &[VernacularName] {
+        &self.names
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Ids of the species with the common name `name`, ignoring case,
+    /// diacritics, and punctuation.
+    pub fn lookup(&self, name: &str) -> &[u32] {
+        self.by_key
+            .get(&fold_name(name))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Name rows of one species.
+    pub fn names_of(&self, species_id: u32) -> impl Iterator<Item = &VernacularName> {
+        self.names
+            .iter()
+            .filter(move |name| name.species_id == species_id)
+    }
+
+    /// Explicit language tags present in the table.
+    pub fn languages(&self) -> BTreeSet<&str> {
+        self.names
+            .iter()
+            .filter_map(|name| name.language.as_deref())
+            .collect()
+    }
+
+    /// The table as a JSON array.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&self.names)
+    }
+
+    /// Write `vernacular_names.csv` and `vernacular_names.json` into
+    /// `output_dir`, returning the written paths.
+    pub fn write_files(&self, output_dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        fs::create_dir_all(output_dir)?;
+        write_table(&self.names, output_dir, VERNACULAR_FILE)
+    }
+}
+
+/// Split a trailing `[xx]` or `(xx)` ISO 639 tag (two or three lower-case
+/// letters) off a common name.
+fn split_language_tag(token: &str) -> (&str, Option<&str>) {
+    let token = token.trim();
+    for (open, close) in [('[', ']'), ('(', ')')] {
+        let Some(inner) = token.strip_suffix(close) else {
+            continue;
+        };
+        let Some((name, tag)) = inner.rsplit_once(open) else {
+            continue;
+        };
+        let is_code = (2..=3).contains(&tag.len()) && tag.chars().all(|c| c.is_ascii_lowercase());
+        if is_code && !name.trim().is_empty() {
+            return (name.trim_end(), Some(tag));
+        }
+    }
+    (token, None)
+}
+
+/// Lookup key of a name: lower-cased, accented Latin letters folded to ASCII,
+/// and runs of punctuation or whitespace collapsed to one space. Letters
+/// without an ASCII folding (e.g. CJK) are kept.
+fn fold_name(name: &str) -> String {
+    let mut key = String::with_capacity(name.len());
+    let mut pending_space = false;
+    for c in name.chars().flat_map(char::to_lowercase) {
+        let folded = fold_ascii(c);
+        if folded.is_empty() && !c.is_alphanumeric() {
+            pending_space = !key.is_empty();
+            continue;
+        }
+        if pending_space {
+            key.push(' ');
+            pending_space = false;
+        }
+        if folded.is_empty() {
+            key.push(c);
+        } else {
+            key.push_str(folded);
+        }
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::mdd::fixtures::species;
+
+    #[test]
+    fn test_vernacular_index() {
+        let index = VernacularIndex::from_species(&[
+            species(
+                1,
+                "Lepus_europaeus",
+                "European Hare",
+                "Brown Hare|Lièvre d'Europe [fr]|Feldhase (de)",
+            ),
+            species(2, "Lepus_capensis", "Cape Hare", "Brown Hare"),
+            species(3, "Lepus_flavigularis", "Tehuantepec Jackrabbit", "NA"),
+        ]);
+        assert_eq!(index.len(), 7);
+        assert_eq!(index.lookup("brown  hare"), &[1, 2]);
+        assert_eq!(index.lookup("LIEVRE D EUROPE"), &[1]);
+        assert!(index.lookup("Hare").is_empty());
+        assert_eq!(
+            index.languages().into_iter().collect::<Vec<_>>(),
+            ["de", "fr"]
+        );
+
+        let names: Vec<_> = index.names_of(1).collect();
+        assert!(names[0].main);
+        assert_eq!(names[2].name, "Lièvre d'Europe");
+        assert_eq!(names[3].language.as_deref(), Some("de"));
+        assert_eq!(index.names_of(3).count(), 1);
+    }
+
+    #[test]
+    fn test_split_language_tag() {
+        assert_eq!(
+            split_language_tag("Feldhase (de)"),
+            ("Feldhase", Some("de"))
+        );
+        assert_eq!(
+            split_language_tag("Allen's Hare (Mexico)"),
+            ("Allen's Hare (Mexico)", None)
+        );
+        assert_eq!(split_language_tag("[en]"), ("[en]", None));
+    }
+}
@@ -0,0 +1,128 @@
+//! JavaScript bindings (enabled with the `wasm` feature).
+//!
+//! Built for `wasm32-unknown-unknown` with `wasm-bindgen`, these wrappers let
+//! browser apps parse and search MDD data client-side without a server.
+//! Records cross the boundary as JSON text in the serde shapes of the JSON
+//! bundle, so the result is read with `JSON.parse`:
+//!
+//! ```js
+//! import init, { parse_mdd_csv, MddSearch } from "./mdd_api.js";
+//!
+//! await init();
+//! const species = JSON.parse(parse_mdd_csv(speciesCsv));
+//! const index = new MddSearch(speciesCsv, synonymCsv);
+//! const hits = JSON.parse(index.search("pantera leo", 10));
+//! ```
+//!
+//! Parse errors are thrown as JavaScript `Error`s carrying the `MddError`
+//! message. Build without default features (`archive`, `db`, and `zstd` need
+//! native code):
+//!
+//! ```text
+//! cargo build --lib --target wasm32-unknown-unknown --no-default-features --features wasm
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    parser::{error::MddError, mdd::MddData, synonyms::SynonymData},
+    search::SearchIndex,
+};
+
+/// Parse a species CSV into a JSON array of `MddData` records.
+#[wasm_bindgen]
+pub fn parse_mdd_csv(csv_data: &str) -> Result<String, JsError> {
+    Ok(species_json(csv_data)?)
+}
+
+/// Parse a synonym CSV into a JSON array of `SynonymData` records.
+#[wasm_bindgen]
+pub fn parse_synonyms_csv(csv_data: &str) -> Result<String, JsError> {
+    Ok(synonyms_json(csv_data)?)
+}
+
+/// Search species and synonym names, returning a JSON array of `SearchMatch`
+/// results. Indexes the CSVs on every call; use `MddSearch` for repeated
+/// queries.
+#[wasm_bindgen]
+pub fn search(
+    species_csv: &str,
+    synonym_csv: &str,
+    query: &str,
+    limit: usize,
+) -> Result<String, JsError> {
+    Ok(MddSearch::build(species_csv, synonym_csv)?.search_json(query, limit)?)
+}
+
+/// Search index built once from species and synonym CSVs.
+#[wasm_bindgen]
+pub struct MddSearch {
+    index: SearchIndex,
+}
+
+#[wasm_bindgen]
+impl MddSearch {
+    #[wasm_bindgen(constructor)]
+    pub fn new(species_csv: &str, synonym_csv: &str) -> Result<MddSearch, JsError> {
+        Ok(Self::build(species_csv, synonym_csv)?)
+    }
+
+    /// Ranked matches for `query` as a JSON array of `SearchMatch` results.
+    pub fn search(&self, query: &str, limit: usize) -> Result<String, JsError> {
+        Ok(self.search_json(query, limit)?)
+    }
+
+    /// Number of indexed names.
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> usize {
+        self.index.len()
+    }
+}
+
+impl MddSearch {
+    fn build(species_csv: &str, synonym_csv: &str) -> Result<Self, MddError> {
+        let species = MddData::new().try_from_csv(species_csv)?;
+        let synonyms = SynonymData::new().try_from_csv(synonym_csv)?;
+        Ok(Self {
+            index: SearchIndex::new(&species, &synonyms),
+        })
+    }
+
+    fn search_json(&self, query: &str, limit: usize) -> Result<String, MddError> {
+        Ok(serde_json::to_string(&self.index.search(query, limit))?)
+    }
+}
+
+fn species_json(csv_data: &str) -> Result<String, MddError> {
+    let species = MddData::new().try_from_csv(csv_data)?;
+    Ok(serde_json::to_string(&species)?)
+}
+
+fn synonyms_json(csv_data: &str) -> Result<String, MddError> {
+    let synonyms = SynonymData::new().try_from_csv(csv_data)?;
+    Ok(serde_json::to_string(&synonyms)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_wrappers() {
+        let species_csv = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let synonym_csv = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+
+        let species: serde_json::Value =
+            serde_json::from_str(&species_json(&species_csv).unwrap()).unwrap();
+        assert_eq!(species.as_array().unwrap().len(), 112);
+        let synonyms: serde_json::Value =
+            serde_json::from_str(&synonyms_json(&synonym_csv).unwrap()).unwrap();
+        assert_eq!(synonyms[0]["synId"], 100022090);
+        assert!(species_json("not,a\nspecies,csv").is_err());
+
+        let index = MddSearch::build(&species_csv, &synonym_csv).unwrap();
+        let hits: serde_json::Value =
+            serde_json::from_str(&index.search_json("bunolagus", 1).unwrap()).unwrap();
+        assert_eq!(hits[0]["sciName"], "Bunolagus_monticularis");
+    }
+}
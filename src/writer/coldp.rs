@@ -0,0 +1,471 @@
+//! Catalogue of Life Data Package (ColDP) export.
+//!
+//! Packages a release as the tab separated ColDP tables the Catalogue of Life
+//! ChecklistBank imports, plus a `metadata.yaml` built from the release
+//! metadata, in one zip archive:
+//!
+//! * `NameUsage.tsv` – accepted species with their classification, and their
+//!   synonyms (`parentID` pointing at the species).
+//! * `Reference.tsv` – distinct authority citations, linked from name usages
+//!   through `referenceID`.
+//! * `Distribution.tsv` – countries per species, as ISO 3166-1 alpha-2 codes
+//!   when the name resolves; predicted occurrences (`?`) are `uncertain`.
+//! * `VernacularName.tsv` – English common names, the main one `preferred`.
+//!
+//! Ids follow the Darwin Core export (`writer::dwca`): species use their MDD
+//! id and synonyms `syn:<MDD_syn_ID>`. Synonym rows whose validity is
+//! `species` describe the accepted name itself and are skipped.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Seek, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use zip::write::SimpleFileOptions;
+
+use super::dwca::{display_name, synonym_name, verbatim, SYNONYM_ID_PREFIX};
+use crate::{
+    helper::country_code::{CountryRegionCode, IsoCodeForm},
+    parser::{mdd::MddData, metadata::ReleaseMetadata, synonyms::SynonymData, ReleasedMddData},
+};
+
+const NAME_USAGE_FILE: &str = "NameUsage.tsv";
+const REFERENCE_FILE: &str = "Reference.tsv";
+const DISTRIBUTION_FILE: &str = "Distribution.tsv";
+const VERNACULAR_FILE: &str = "VernacularName.tsv";
+const METADATA_FILE: &str = "metadata.yaml";
+const REFERENCE_ID_PREFIX: &str = "ref:";
+const ZOOLOGICAL_CODE: &str = "zoological";
+/// ISO 639-3 code of the MDD common names.
+const ENGLISH: &str = "eng";
+
+/// One row of `NameUsage.tsv`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColdpNameUsage {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "parentID")]
+    pub parent_id: String,
+    pub status: String,
+    pub rank: String,
+    pub scientific_name: String,
+    pub authorship: String,
+    pub generic_name: String,
+    pub specific_epithet: String,
+    pub infraspecific_epithet: String,
+    pub code: String,
+    #[serde(rename = "referenceID")]
+    pub reference_id: String,
+    pub link: String,
+    pub extinct: String,
+    pub class: String,
+    pub subclass: String,
+    pub order: String,
+    pub suborder: String,
+    pub superfamily: String,
+    pub family: String,
+    pub subfamily: String,
+    pub tribe: String,
+    pub genus: String,
+    pub subgenus: String,
+    pub remarks: String,
+}
+
+/// One row of `Reference.tsv`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColdpReference {
+    #[serde(rename = "ID")]
+    pub id: String,
+    pub citation: String,
+}
+
+/// One row of `Distribution.tsv`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColdpDistribution {
+    #[serde(rename = "taxonID")]
+    pub taxon_id: String,
+    #[serde(rename = "areaID")]
+    pub area_id: String,
+    pub area: String,
+    /// `iso` when `area_id` holds an ISO code, `text` otherwise.
+    pub gazetteer: String,
+    pub status: String,
+}
+
+/// One row of `VernacularName.tsv`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColdpVernacularName {
+    #[serde(rename = "taxonID")]
+    pub taxon_id: String,
+    pub name: String,
+    pub language: String,
+    pub preferred: bool,
+}
+
+/// The four ColDP tables of a release.
+#[derive(Debug, Clone, Default)]
+pub struct ColdpTables {
+    pub name_usages: Vec<ColdpNameUsage>,
+    pub references: Vec<ColdpReference>,
+    pub distributions: Vec<ColdpDistribution>,
+    pub vernacular_names: Vec<ColdpVernacularName>,
+}
+
+/// Writer for a zipped Catalogue of Life Data Package.
+pub struct ColdpWriter<'a> {
+    metadata: &'a ReleaseMetadata,
+}
+
+impl<'a> ColdpWriter<'a> {
+    /// Create a writer using release metadata for `metadata.yaml`.
+    pub fn new(metadata: &'a ReleaseMetadata) -> Self {
+        Self { metadata }
+    }
+
+    /// Map every species and synonym in the release to ColDP rows.
+    pub fn tables(&self, release: &ReleasedMddData) -> ColdpTables {
+        let countries = CountryRegionCode::new();
+        let mut references = References::default();
+        let mut tables = ColdpTables::default();
+        for entry in &release.data {
+            let species = entry.species();
+            tables
+                .name_usages
+                .push(species_usage(species, &mut references));
+            tables.name_usages.extend(
+                entry
+                    .synonyms()
+                    .iter()
+                    .filter(|synonym| synonym.validity != "species")
+                    .map(|synonym| synonym_usage(synonym, &mut references)),
+            );
+            tables
+                .distributions
+                .extend(distributions(species, &countries));
+            tables.vernacular_names.extend(vernacular_names(species));
+        }
+        tables.name_usages.extend(
+            release
+                .synonym_only
+                .iter()
+                .map(|synonym| synonym_usage(synonym, &mut references)),
+        );
+        tables.references = references.rows;
+        tables
+    }
+
+    /// Write the archive to `output_path` and return the path.
+    pub fn write_to_file(
+        &self,
+        release: &ReleasedMddData,
+        output_path: &Path,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = fs::File::create(output_path)?;
+        self.write_archive(release, file)?;
+        Ok(output_path.to_path_buf())
+    }
+
+    /// Write the zipped package (four tables and `metadata.yaml`) to `writer`.
+    pub fn write_archive<W: Write + Seek>(
+        &self,
+        release: &ReleasedMddData,
+        writer: W,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let tables = self.tables(release);
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let mut zip = zip::ZipWriter::new(writer);
+        zip.start_file(NAME_USAGE_FILE, options)?;
+        zip.write_all(&tsv_table(&tables.name_usages)?)?;
+        zip.start_file(REFERENCE_FILE, options)?;
+        zip.write_all(&tsv_table(&tables.references)?)?;
+        zip.start_file(DISTRIBUTION_FILE, options)?;
+        zip.write_all(&tsv_table(&tables.distributions)?)?;
+        zip.start_file(VERNACULAR_FILE, options)?;
+        zip.write_all(&tsv_table(&tables.vernacular_names)?)?;
+        zip.start_file(METADATA_FILE, options)?;
+        zip.write_all(self.metadata_yaml().as_bytes())?;
+        zip.finish()?;
+        Ok(())
+    }
+
+    /// ColDP `metadata.yaml` built from the release metadata.
+    pub fn metadata_yaml(&self) -> String {
+        let meta = self.metadata;
+        let mut yaml = format!(
+            "title: {}\nalias: MDD\nversion: {}\n",
+            yaml_string(&meta.name),
+            yaml_string(&meta.version)
+        );
+        if !meta.release_date.is_empty() {
+            yaml.push_str(&format!("issued: {}\n", yaml_string(&meta.release_date)));
+        }
+        if let Some(doi) = meta.doi.as_deref() {
+            yaml.push_str(&format!("doi: {}\n", yaml_string(doi)));
+        }
+        if let Some(remarks) = meta.remarks.as_deref() {
+            yaml.push_str(&format!("description: {}\n", yaml_string(remarks)));
+        }
+        yaml.push_str(
+            "contact:\n  organisation: Mammal Diversity Database\n\
+             creator:\n  - organisation: Mammal Diversity Database\n\
+             url: https://www.mammaldiversity.org\n\
+             taxonomicScope: Mammalia\n\
+             geographicScope: global\n",
+        );
+        yaml
+    }
+}
+
+/// Distinct citations, numbered in order of first use.
+#[derive(Default)]
+struct References {
+    ids: HashMap<String, String>,
+    rows: Vec<ColdpReference>,
+}
+
+impl References {
+    /// Id of `citation`, added on first use; empty for missing citations.
+    fn id(&mut self, citation: &str) -> String {
+        let citation = text(citation);
+        if citation.is_empty() {
+            return citation;
+        }
+        if let Some(id) = self.ids.get(&citation) {
+            return id.clone();
+        }
+        let id = format!("{}{}", REFERENCE_ID_PREFIX, self.rows.len() + 1);
+        self.ids.insert(citation.clone(), id.clone());
+        self.rows.push(ColdpReference {
+            id: id.clone(),
+            citation,
+        });
+        id
+    }
+}
+
+fn species_usage(data: &MddData, references: &mut References) -> ColdpNameUsage {
+    ColdpNameUsage {
+        id: data.id.to_string(),
+        status: "accepted".to_string(),
+        rank: "species".to_string(),
+        scientific_name: display_name(&data.sci_name),
+        authorship: data.authorship().to_string(),
+        generic_name: text(&data.genus),
+        specific_epithet: text(&data.specific_epithet),
+        code: ZOOLOGICAL_CODE.to_string(),
+        reference_id: references.id(&data.authority_species_citation),
+        link: text(&data.authority_species_link),
+        extinct: (data.extinct == 1).to_string(),
+        class: "Mammalia".to_string(),
+        subclass: text(&data.subclass),
+        order: text(&data.taxon_order),
+        suborder: text(&data.suborder),
+        superfamily: text(&data.superfamily),
+        family: text(&data.family),
+        subfamily: text(&data.subfamily),
+        tribe: text(&data.tribe),
+        genus: text(&data.genus),
+        subgenus: text(&data.subgenus),
+        remarks: text(&data.taxonomy_notes),
+        ..ColdpNameUsage::default()
+    }
+}
+
+fn synonym_usage(data: &SynonymData, references: &mut References) -> ColdpNameUsage {
+    // Names not attached to a species (nomina dubia, nomina nuda, ...) have
+    // no accepted usage to point to.
+    let (parent_id, status) = match data.species_id {
+        Some(id) => (id.to_string(), "synonym"),
+        None => (String::new(), "bare name"),
+    };
+    ColdpNameUsage {
+        id: format!("{}{}", SYNONYM_ID_PREFIX, data.syn_id),
+        parent_id,
+        status: status.to_string(),
+        rank: text(&data.original_rank),
+        scientific_name: text(&synonym_name(data)),
+        authorship: data.authorship().to_string(),
+        generic_name: text(&data.genus),
+        specific_epithet: text(&data.specific_epithet),
+        infraspecific_epithet: text(&data.subspecific_epithet),
+        code: ZOOLOGICAL_CODE.to_string(),
+        reference_id: references.id(&data.authority_citation),
+        link: text(&data.authority_link),
+        remarks: text(&data.comments),
+        ..ColdpNameUsage::default()
+    }
+}
+
+fn distributions(data: &MddData, countries: &CountryRegionCode) -> Vec<ColdpDistribution> {
+    data.countries()
+        .iter()
+        .map(|country| {
+            let (area_id, gazetteer) =
+                match countries.code_for_name(country.name, IsoCodeForm::Alpha2) {
+                    Some(code) => (code.to_string(), "iso"),
+                    None => (String::new(), "text"),
+                };
+            let status = if country.predicted {
+                "uncertain"
+            } else {
+                "native"
+            };
+            ColdpDistribution {
+                taxon_id: data.id.to_string(),
+                area_id,
+                area: text(country.name),
+                gazetteer: gazetteer.to_string(),
+                status: status.to_string(),
+            }
+        })
+        .collect()
+}
+
+fn vernacular_names(data: &MddData) -> Vec<ColdpVernacularName> {
+    let main = text(&data.main_common_name);
+    let main = (!main.is_empty()).then_some((main, true));
+    let others = data
+        .common_names()
+        .into_iter()
+        .map(|name| (text(name), false));
+    main.into_iter()
+        .chain(others)
+        .filter(|(name, _)| !name.is_empty())
+        .map(|(name, preferred)| ColdpVernacularName {
+            taxon_id: data.id.to_string(),
+            name,
+            language: ENGLISH.to_string(),
+            preferred,
+        })
+        .collect()
+}
+
+/// Tab separated table content, including the header row.
+fn tsv_table<T: Serialize>(rows: &[T]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut wtr = csv::WriterBuilder::new()
+        .delimiter(b'\t')
+        .quote_style(csv::QuoteStyle::Never)
+        .from_writer(Vec::new());
+    for row in rows {
+        wtr.serialize(row)?;
+    }
+    Ok(wtr.into_inner()?)
+}
+
+/// A verbatim value without tabs and line breaks, which would break the
+/// unquoted tables.
+fn text(value: &str) -> String {
+    let value = verbatim(value);
+    if value.contains(['\t', '\n', '\r']) {
+        value.replace(['\t', '\n', '\r'], " ")
+    } else {
+        value
+    }
+}
+
+/// A double-quoted YAML scalar.
+fn yaml_string(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use super::*;
+    use crate::writer::fixtures::test_release;
+
+    #[test]
+    fn test_tables() {
+        let metadata = ReleaseMetadata::default();
+        let tables = ColdpWriter::new(&metadata).tables(&test_release());
+        let species = tables
+            .name_usages
+            .iter()
+            .find(|usage| usage.id == "1001076")
+            .unwrap();
+        assert_eq!(species.scientific_name, "Bunolagus monticularis");
+        assert_eq!(species.status, "accepted");
+        assert_eq!(species.order, "Lagomorpha");
+        let reference = tables
+            .references
+            .iter()
+            .find(|reference| reference.id == species.reference_id)
+            .unwrap();
+        assert!(!reference.citation.is_empty());
+
+        assert!(!tables
+            .name_usages
+            .iter()
+            .any(|usage| usage.id == "syn:100022090"));
+        let synonym = tables
+            .name_usages
+            .iter()
+            .find(|usage| usage.id == "syn:100040650")
+            .unwrap();
+        assert_eq!(synonym.parent_id, "1001076");
+        assert_eq!(synonym.status, "synonym");
+
+        let distribution = tables
+            .distributions
+            .iter()
+            .find(|distribution| distribution.taxon_id == "1001076")
+            .unwrap();
+        assert_eq!(distribution.area_id, "ZA");
+        assert_eq!(distribution.gazetteer, "iso");
+        assert!(tables
+            .vernacular_names
+            .iter()
+            .any(|name| name.taxon_id == "1001076" && name.preferred));
+    }
+
+    #[test]
+    fn test_write_archive() {
+        let metadata = ReleaseMetadata {
+            name: "Mammal Diversity Database".to_string(),
+            version: "2.2".to_string(),
+            release_date: "2025-01-01".to_string(),
+            doi: Some("10.5281/zenodo.1".to_string()),
+            ..Default::default()
+        };
+        let writer = ColdpWriter::new(&metadata);
+        let mut buffer = Cursor::new(Vec::new());
+        writer.write_archive(&test_release(), &mut buffer).unwrap();
+
+        let mut archive = zip::ZipArchive::new(buffer).unwrap();
+        let mut name_usage = String::new();
+        archive
+            .by_name(NAME_USAGE_FILE)
+            .unwrap()
+            .read_to_string(&mut name_usage)
+            .unwrap();
+        assert!(name_usage.starts_with("ID\tparentID\tstatus\trank\tscientificName\t"));
+        for file in [REFERENCE_FILE, DISTRIBUTION_FILE, VERNACULAR_FILE] {
+            assert!(archive.by_name(file).is_ok());
+        }
+        let mut yaml = String::new();
+        archive
+            .by_name(METADATA_FILE)
+            .unwrap()
+            .read_to_string(&mut yaml)
+            .unwrap();
+        assert!(yaml.contains("version: \"2.2\"\n"));
+        assert!(yaml.contains("doi: \"10.5281/zenodo.1\"\n"));
+    }
+}
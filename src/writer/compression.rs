@@ -0,0 +1,327 @@
+//! Output compression.
+//!
+//! `Compression` selects how serialized output is encoded: uncompressed,
+//! gzip (the default, widest support), Zstandard (fast, preferred by data
+//! pipelines), or Brotli (smallest, preferred for static web hosting). Each
+//! variant carries its level; `Compression::gzip()` and friends use the
+//! default level.
+//!
+//! Compressed files get a second extension (`data.json.gz`, `data.json.zst`,
+//! `data.json.br`); `Compression::from_path` maps it back for reading.
+//!
+//! Zstandard needs the `zstd` feature (a C library, unavailable on
+//! `wasm32-unknown-unknown`) and Brotli the `brotli` feature; without them,
+//! encoding or decoding `Zstd` or `Brotli` returns an `Unsupported` I/O
+//! error. Streams are always finished explicitly, so errors of the final
+//! write are returned rather than lost when an encoder is dropped.
+
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+};
+
+/// Default gzip level (0–9).
+pub const DEFAULT_GZIP_LEVEL: u32 = 6;
+/// Default Zstandard level (1–22).
+pub const DEFAULT_ZSTD_LEVEL: u32 = 3;
+/// Default Brotli quality (0–11). Quality 11 is several times slower for a
+/// few percent smaller output.
+pub const DEFAULT_BROTLI_LEVEL: u32 = 9;
+
+#[cfg(feature = "brotli")]
+const BROTLI_BUFFER_SIZE: usize = 4096;
+#[cfg(feature = "brotli")]
+const BROTLI_WINDOW_BITS: u32 = 22;
+
+/// Compression format and level of an output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip(u32),
+    Zstd(u32),
+    Brotli(u32),
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self::gzip()
+    }
+}
+
+impl Compression {
+    pub fn gzip() -> Self {
+        Self::Gzip(DEFAULT_GZIP_LEVEL)
+    }
+
+    pub fn zstd() -> Self {
+        Self::Zstd(DEFAULT_ZSTD_LEVEL)
+    }
+
+    pub fn brotli() -> Self {
+        Self::Brotli(DEFAULT_BROTLI_LEVEL)
+    }
+
+    /// Same format with `level`, clamped to the range of the format.
+    pub fn with_level(self, level: u32) -> Self {
+        match self {
+            Self::None => Self::None,
+            Self::Gzip(_) => Self::Gzip(level.min(9)),
+            Self::Zstd(_) => Self::Zstd(level.clamp(1, 22)),
+            Self::Brotli(_) => Self::Brotli(level.min(11)),
+        }
+    }
+
+    /// Extension appended to compressed files (`gz`, `zst`, `br`).
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Gzip(_) => Some("gz"),
+            Self::Zstd(_) => Some("zst"),
+            Self::Brotli(_) => Some("br"),
+        }
+    }
+
+    /// Compression of a file, detected from its last extension (default levels).
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => Self::gzip(),
+            Some("zst") => Self::zstd(),
+            Some("br") => Self::brotli(),
+            _ => Self::None,
+        }
+    }
+
+    /// Wrap `reader` in the matching decoder.
+    pub fn decoder<'a, R: Read + 'a>(&self, reader: R) -> io::Result<Box<dyn Read + 'a>> {
+        let decoder: Box<dyn Read + 'a> = match self {
+            Self::None => Box::new(reader),
+            Self::Gzip(_) => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(_) => Box::new(zstd::Decoder::new(reader)?),
+            #[cfg(not(feature = "zstd"))]
+            Self::Zstd(_) => return Err(zstd_unsupported()),
+            #[cfg(feature = "brotli")]
+            Self::Brotli(_) => Box::new(brotli::Decompressor::new(reader, BROTLI_BUFFER_SIZE)),
+            #[cfg(not(feature = "brotli"))]
+            Self::Brotli(_) => return Err(brotli_unsupported()),
+        };
+        Ok(decoder)
+    }
+
+    /// Compress `data` into a new buffer.
+    pub fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        self.write_with(Vec::new(), |encoder| encoder.write_all(data))
+    }
+
+    /// Compress whatever `write` produces straight into `sink` and finish the
+    /// stream, returning the sink. The uncompressed output is never held in
+    /// memory as a whole.
+    pub fn write_with<W, F>(&self, mut sink: W, write: F) -> io::Result<W>
+    where
+        W: Write,
+        F: FnOnce(&mut dyn Write) -> io::Result<()>,
+    {
+        match *self {
+            Self::None => {
+                write(&mut sink)?;
+                sink.flush()?;
+                Ok(sink)
+            }
+            Self::Gzip(level) => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(sink, flate2::Compression::new(level));
+                write(&mut encoder)?;
+                encoder.finish()
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd(level) => {
+                let mut encoder = zstd::Encoder::new(sink, level as i32)?;
+                write(&mut encoder)?;
+                encoder.finish()
+            }
+            #[cfg(not(feature = "zstd"))]
+            Self::Zstd(_) => Err(zstd_unsupported()),
+            #[cfg(feature = "brotli")]
+            Self::Brotli(level) => {
+                let mut encoder = brotli::CompressorWriter::new(
+                    TrackErrors::new(sink),
+                    BROTLI_BUFFER_SIZE,
+                    level,
+                    BROTLI_WINDOW_BITS,
+                );
+                write(&mut encoder)?;
+                encoder.flush()?;
+                // `into_inner` writes the end of the stream but discards its errors.
+                encoder.into_inner().into_result()
+            }
+            #[cfg(not(feature = "brotli"))]
+            Self::Brotli(_) => Err(brotli_unsupported()),
+        }
+    }
+
+    /// Decompress `data` into a new buffer.
+    pub fn decompress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.decoder(data)?.read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Compress `data` and write it to `path`.
+    pub fn write_file(&self, data: &[u8], path: &Path) -> io::Result<()> {
+        self.create_file_with(path, |encoder| encoder.write_all(data))
+    }
+
+    /// Create `path` and stream whatever `write` produces into it through the
+    /// encoder (see `write_with`).
+    pub fn create_file_with<F>(&self, path: &Path, write: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut dyn Write) -> io::Result<()>,
+    {
+        let file = io::BufWriter::new(fs::File::create(path)?);
+        self.write_with(file, write)?.flush()
+    }
+}
+
+/// Writer keeping the first error of `inner`, for encoders that swallow the
+/// errors of their final write.
+#[cfg(feature = "brotli")]
+struct TrackErrors<W> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+#[cfg(feature = "brotli")]
+impl<W: Write> TrackErrors<W> {
+    fn new(inner: W) -> Self {
+        Self { inner, error: None }
+    }
+
+    fn track<T>(&mut self, result: io::Result<T>) -> io::Result<T> {
+        if let Err(e) = &result {
+            self.error
+                .get_or_insert_with(|| io::Error::new(e.kind(), e.to_string()));
+        }
+        result
+    }
+
+    /// The inner writer, or the first error written to it.
+    fn into_result(self) -> io::Result<W> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.inner),
+        }
+    }
+}
+
+#[cfg(feature = "brotli")]
+impl<W: Write> Write for TrackErrors<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let result = self.inner.write(buf);
+        self.track(result)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let result = self.inner.flush();
+        self.track(result)
+    }
+}
+
+#[cfg(not(feature = "brotli"))]
+fn brotli_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Brotli compression requires the `brotli` feature",
+    )
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Zstandard compression requires the `zstd` feature",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let data = br#"{"version":"2.0","data":[]}"#.repeat(100);
+        let mut compressions = vec![Compression::None, Compression::gzip()];
+        if cfg!(feature = "zstd") {
+            compressions.push(Compression::zstd().with_level(19));
+        } else {
+            assert!(Compression::zstd().compress(&data).is_err());
+        }
+        if cfg!(feature = "brotli") {
+            compressions.push(Compression::brotli());
+        } else {
+            assert!(Compression::brotli().compress(&data).is_err());
+        }
+        for compression in compressions {
+            let compressed = compression.compress(&data).unwrap();
+            assert_eq!(compression.decompress(&compressed).unwrap(), data);
+        }
+        assert_eq!(Compression::zstd().with_level(40), Compression::Zstd(22));
+
+        let release = crate::parser::ReleasedMddData::new();
+        let streamed = Compression::gzip()
+            .write_with(Vec::new(), |encoder| {
+                release.write_json(encoder).map_err(io::Error::other)
+            })
+            .unwrap();
+        assert_eq!(
+            Compression::gzip().decompress(&streamed).unwrap(),
+            release.to_json().into_bytes()
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("data.json.br")),
+            Compression::brotli()
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("data.json")),
+            Compression::None
+        );
+    }
+
+    /// Sink accepting `capacity` bytes, then failing every write.
+    struct FullSink {
+        capacity: usize,
+    }
+
+    impl Write for FullSink {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.capacity == 0 {
+                return Err(io::Error::new(io::ErrorKind::StorageFull, "sink is full"));
+            }
+            let written = buf.len().min(self.capacity);
+            self.capacity -= written;
+            Ok(written)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_finish_errors_are_returned() {
+        let mut compressions = vec![Compression::gzip()];
+        if cfg!(feature = "zstd") {
+            compressions.push(Compression::zstd());
+        }
+        if cfg!(feature = "brotli") {
+            compressions.push(Compression::brotli());
+        }
+        for compression in compressions {
+            // Nothing fits into the sink; the error must surface at the
+            // latest when the stream is finished.
+            let result = compression
+                .write_with(FullSink { capacity: 0 }, |encoder| encoder.write_all(b"{}"));
+            assert!(result.is_err(), "{:?}", compression);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Species CSV output in the original MDD column layout.
+//!
+//! `MddCsvWriter` writes `MddData` records back to CSV using the release
+//! header names and column order from `parser::schema::MDD_COLUMNS`
+//! (`sciName` first, `order` rather than `taxonOrder`, `typeVoucherURIs`,
+//! `CMW_sciName`, ...), so records edited through the crate can be submitted
+//! upstream without diff noise. Fields that are not MDD columns, such as
+//! `slug`, are left out.
+//!
+//! Text is written verbatim and quoted only when needed; parsing a release
+//! CSV and writing it back reproduces the file byte for byte.
+//! `with_dialect` writes TSV and other `CsvDialect`s instead.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::parser::{mdd::MddData, options::CsvDialect, schema::MDD_COLUMNS};
+
+/// File extension used for CSV output.
+pub const CSV_EXTENSION: &str = "csv";
+
+/// MDD column whose serde field name differs from the header.
+const ORDER_COLUMN: &str = "order";
+const ORDER_FIELD: &str = "taxonOrder";
+
+/// Writes `MddData` records as MDD-layout CSV to any `Write` sink.
+pub struct MddCsvWriter<W: Write> {
+    writer: ::csv::Writer<W>,
+    records: usize,
+}
+
+impl<W: Write> MddCsvWriter<W> {
+    /// Create the writer and write the header row.
+    pub fn new(writer: W) -> io::Result<Self> {
+        Self::with_dialect(writer, &CsvDialect::default())
+    }
+
+    /// Create the writer for `dialect` and write the header row.
+    pub fn with_dialect(writer: W, dialect: &CsvDialect) -> io::Result<Self> {
+        let mut writer = dialect.writer_builder().from_writer(writer);
+        writer.write_record(MDD_COLUMNS)?;
+        Ok(Self { writer, records: 0 })
+    }
+
+    /// Write one species record.
+    pub fn write_record(&mut self, record: &MddData) -> io::Result<()> {
+        let fields = match serde_json::to_value(record)? {
+            Value::Object(map) => map,
+            _ => unreachable!("MddData serializes to a JSON object"),
+        };
+        let row = MDD_COLUMNS.iter().map(|column| {
+            let field = if *column == ORDER_COLUMN {
+                ORDER_FIELD
+            } else {
+                column
+            };
+            match fields.get(field) {
+                Some(Value::String(text)) => text.clone(),
+                Some(Value::Null) | None => String::new(),
+                Some(other) => other.to_string(),
+            }
+        });
+        self.writer.write_record(row)?;
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Write every record of `records`, returning how many were written.
+    pub fn write_records<'a, I>(&mut self, records: I) -> io::Result<usize>
+    where
+        I: IntoIterator<Item = &'a MddData>,
+    {
+        let start = self.records;
+        for record in records {
+            self.write_record(record)?;
+        }
+        Ok(self.records - start)
+    }
+
+    /// Number of records written so far.
+    pub fn record_count(&self) -> usize {
+        self.records
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn into_inner(self) -> io::Result<W> {
+        self.writer
+            .into_inner()
+            .map_err(|e| io::Error::other(e.to_string()))
+    }
+}
+
+/// Write `records` to `<output_dir>/<output_filename>.csv`.
+pub fn write_to_file(
+    records: &[MddData],
+    output_dir: &Path,
+    output_filename: &str,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    fs::create_dir_all(output_dir)?;
+    let output_path = output_dir
+        .join(output_filename)
+        .with_extension(CSV_EXTENSION);
+    let mut writer = MddCsvWriter::new(io::BufWriter::new(fs::File::create(&output_path)?))?;
+    writer.write_records(records)?;
+    writer.into_inner()?.flush()?;
+    Ok(output_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{options::ParseOptions, reader::MddCsvReader};
+
+    #[test]
+    fn test_round_trip() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let records = MddData::new().from_csv(&csv_data);
+        let mut writer = MddCsvWriter::new(Vec::new()).unwrap();
+        assert_eq!(writer.write_records(&records).unwrap(), 112);
+        let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert!(output.starts_with("sciName,id,phylosort,"));
+        assert_eq!(output, csv_data);
+    }
+
+    #[test]
+    fn test_tsv_round_trip() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let records = MddData::new().from_csv(&csv_data);
+        let mut writer = MddCsvWriter::with_dialect(Vec::new(), &CsvDialect::tsv()).unwrap();
+        writer.write_records(&records).unwrap();
+        let tsv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert!(tsv.starts_with("sciName\tid\tphylosort\t"));
+
+        let options = ParseOptions::default().with_dialect(CsvDialect::tsv());
+        let (parsed, report) = MddData::new()
+            .from_csv_with(&format!("\u{feff}{}", tsv), &options)
+            .unwrap();
+        assert!(report.is_clean());
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap(),
+            serde_json::to_value(&records).unwrap()
+        );
+        let streamed: Vec<MddData> = MddCsvReader::with_dialect(tsv.as_bytes(), &CsvDialect::tsv())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(streamed.len(), 112);
+    }
+}
@@ -0,0 +1,437 @@
+//! Darwin Core Archive (DwC-A) export.
+//!
+//! Maps accepted species (`MddData`) and their synonyms (`SynonymData`) to a
+//! single Darwin Core Taxon core file (`taxon.txt`, tab separated), and
+//! packages it with a generated `meta.xml` descriptor and `eml.xml` dataset
+//! metadata into a zip archive that GBIF's IPT and validator accept.
+//!
+//! Mapping notes:
+//! * Accepted species use their MDD id as `taxonID`. Synonyms use
+//!   `syn:<MDD_syn_ID>` so the two id spaces never collide, and point at
+//!   their species through `acceptedNameUsageID`.
+//! * Synonym rows whose validity is `species` describe the accepted name
+//!   itself and are skipped when that species is part of the export.
+//! * `scientificName` uses spaces instead of the MDD `_` separator; the
+//!   authorship is written separately in `scientificNameAuthorship`.
+
+use std::{
+    fs,
+    io::{Seek, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use zip::write::SimpleFileOptions;
+
+use crate::{
+    helper::MDD_MISSING_VALUE,
+    parser::{mdd::MddData, metadata::ReleaseMetadata, synonyms::SynonymData, ReleasedMddData},
+};
+
+use super::xml_escape;
+
+const TAXON_FILE: &str = "taxon.txt";
+const META_FILE: &str = "meta.xml";
+const EML_FILE: &str = "eml.xml";
+const DWC_NS: &str = "http://rs.tdwg.org/dwc/terms/";
+const DCTERMS_NS: &str = "http://purl.org/dc/terms/";
+pub(crate) const SYNONYM_ID_PREFIX: &str = "syn:";
+
+/// Darwin Core terms written to `taxon.txt`, in column order.
+/// Must match the field order of `DwcTaxon`.
+pub const DWC_TAXON_TERMS: [&str; 19] = [
+    "taxonID",
+    "scientificName",
+    "scientificNameAuthorship",
+    "acceptedNameUsageID",
+    "acceptedNameUsage",
+    "originalNameUsage",
+    "taxonRank",
+    "taxonomicStatus",
+    "nomenclaturalStatus",
+    "kingdom",
+    "phylum",
+    "class",
+    "order",
+    "family",
+    "genus",
+    "specificEpithet",
+    "infraspecificEpithet",
+    "namePublishedIn",
+    "references",
+];
+
+/// One row of the Darwin Core Taxon core.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DwcTaxon {
+    #[serde(rename = "taxonID")]
+    pub taxon_id: String,
+    pub scientific_name: String,
+    pub scientific_name_authorship: String,
+    #[serde(rename = "acceptedNameUsageID")]
+    pub accepted_name_usage_id: String,
+    pub accepted_name_usage: String,
+    pub original_name_usage: String,
+    pub taxon_rank: String,
+    pub taxonomic_status: String,
+    pub nomenclatural_status: String,
+    pub kingdom: String,
+    pub phylum: String,
+    pub class: String,
+    pub order: String,
+    pub family: String,
+    pub genus: String,
+    pub specific_epithet: String,
+    pub infraspecific_epithet: String,
+    pub name_published_in: String,
+    pub references: String,
+}
+
+impl DwcTaxon {
+    /// Map an accepted MDD species.
+    pub fn from_species(data: &MddData) -> Self {
+        Self {
+            taxon_id: data.id.to_string(),
+            scientific_name: display_name(&data.sci_name),
+            scientific_name_authorship: authorship(
+                &data.authority_species_author,
+                &data.authority_species_year.to_string(),
+                data.authority_parentheses == 1,
+            ),
+            original_name_usage: verbatim(&data.original_name_combination),
+            taxon_rank: "species".to_string(),
+            taxonomic_status: "accepted".to_string(),
+            order: verbatim(&data.taxon_order),
+            family: verbatim(&data.family),
+            genus: verbatim(&data.genus),
+            specific_epithet: verbatim(&data.specific_epithet),
+            name_published_in: verbatim(&data.authority_species_citation),
+            references: verbatim(&data.authority_species_link),
+            ..Self::mammalia()
+        }
+    }
+
+    /// Map a synonym (or synonym-only) name usage.
+    pub fn from_synonym(data: &SynonymData) -> Self {
+        Self {
+            taxon_id: format!("{}{}", SYNONYM_ID_PREFIX, data.syn_id),
+            scientific_name: synonym_name(data),
+            scientific_name_authorship: authorship(
+                &data.author,
+                &data.year,
+                data.authority_parentheses == 1,
+            ),
+            accepted_name_usage_id: data.species_id.map(|id| id.to_string()).unwrap_or_default(),
+            accepted_name_usage: verbatim(&data.species),
+            taxon_rank: verbatim(&data.original_rank),
+            taxonomic_status: taxonomic_status(&data.validity, data.species_id.is_some()),
+            nomenclatural_status: verbatim(&data.nomenclature_status),
+            order: verbatim(&data.taxon_order),
+            family: verbatim(&data.family),
+            genus: verbatim(&data.genus),
+            specific_epithet: verbatim(&data.specific_epithet),
+            infraspecific_epithet: verbatim(&data.subspecific_epithet),
+            name_published_in: verbatim(&data.authority_citation),
+            references: verbatim(&data.authority_link),
+            ..Self::mammalia()
+        }
+    }
+
+    fn mammalia() -> Self {
+        Self {
+            kingdom: "Animalia".to_string(),
+            phylum: "Chordata".to_string(),
+            class: "Mammalia".to_string(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Writer for a zipped Darwin Core Archive.
+pub struct DwcaWriter<'a> {
+    metadata: &'a ReleaseMetadata,
+}
+
+impl<'a> DwcaWriter<'a> {
+    /// Create a writer using release metadata for the EML document.
+    pub fn new(metadata: &'a ReleaseMetadata) -> Self {
+        Self { metadata }
+    }
+
+    /// Map every species and synonym in the release to Darwin Core rows.
+    pub fn taxon_rows(&self, release: &ReleasedMddData) -> Vec<DwcTaxon> {
+        let mut rows = Vec::new();
+        for entry in &release.data {
+            rows.push(DwcTaxon::from_species(entry.species()));
+            rows.extend(
+                entry
+                    .synonyms()
+                    .iter()
+                    .filter(|syn| syn.validity != "species")
+                    .map(DwcTaxon::from_synonym),
+            );
+        }
+        rows.extend(release.synonym_only.iter().map(DwcTaxon::from_synonym));
+        rows
+    }
+
+    /// Write the archive to `output_path` and return the path.
+    pub fn write_to_file(
+        &self,
+        release: &ReleasedMddData,
+        output_path: &Path,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = fs::File::create(output_path)?;
+        self.write_archive(release, file)?;
+        Ok(output_path.to_path_buf())
+    }
+
+    /// Write the zipped archive (`taxon.txt`, `meta.xml`, `eml.xml`) to `writer`.
+    pub fn write_archive<W: Write + Seek>(
+        &self,
+        release: &ReleasedMddData,
+        writer: W,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let mut zip = zip::ZipWriter::new(writer);
+        zip.start_file(TAXON_FILE, options)?;
+        zip.write_all(&self.taxon_table(release)?)?;
+        zip.start_file(META_FILE, options)?;
+        zip.write_all(self.meta_xml().as_bytes())?;
+        zip.start_file(EML_FILE, options)?;
+        zip.write_all(self.eml_xml().as_bytes())?;
+        zip.finish()?;
+        Ok(())
+    }
+
+    /// Tab separated `taxon.txt` content, including the header row.
+    pub fn taxon_table(
+        &self,
+        release: &ReleasedMddData,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut wtr = csv::WriterBuilder::new()
+            .delimiter(b'\t')
+            .quote_style(csv::QuoteStyle::Never)
+            .from_writer(Vec::new());
+        for row in self.taxon_rows(release) {
+            wtr.serialize(sanitize(row))?;
+        }
+        Ok(wtr.into_inner()?)
+    }
+
+    /// Archive descriptor mapping `taxon.txt` columns to Darwin Core terms.
+    pub fn meta_xml(&self) -> String {
+        let fields = DWC_TAXON_TERMS
+            .iter()
+            .enumerate()
+            .map(|(index, term)| {
+                let namespace = if *term == "references" {
+                    DCTERMS_NS
+                } else {
+                    DWC_NS
+                };
+                format!(
+                    "    <field index=\"{}\" term=\"{}{}\"/>\n",
+                    index, namespace, term
+                )
+            })
+            .collect::<String>();
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+<archive xmlns=\"http://rs.tdwg.org/dwc/text/\" metadata=\"{EML_FILE}\">\n\
+  <core encoding=\"UTF-8\" fieldsTerminatedBy=\"\\t\" linesTerminatedBy=\"\\n\" \
+fieldsEnclosedBy=\"\" ignoreHeaderLines=\"1\" rowType=\"{DWC_NS}Taxon\">\n\
+    <files>\n      <location>{TAXON_FILE}</location>\n    </files>\n\
+    <id index=\"0\"/>\n\
+{fields}  </core>\n\
+</archive>\n"
+        )
+    }
+
+    /// Minimal EML 2.1.1 dataset document built from the release metadata.
+    pub fn eml_xml(&self) -> String {
+        let meta = self.metadata;
+        let title = xml_escape(&format!("{} v{}", meta.name, meta.version));
+        let identifier = meta
+            .doi
+            .as_deref()
+            .map(|doi| {
+                format!(
+                    "    <alternateIdentifier>{}</alternateIdentifier>\n",
+                    xml_escape(doi)
+                )
+            })
+            .unwrap_or_default();
+        let remarks = meta
+            .remarks
+            .as_deref()
+            .map(|remarks| {
+                format!(
+                    "    <abstract>\n      <para>{}</para>\n    </abstract>\n",
+                    xml_escape(remarks)
+                )
+            })
+            .unwrap_or_default();
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+<eml:eml xmlns:eml=\"eml://ecoinformatics.org/eml-2.1.1\" \
+packageId=\"{package_id}\" system=\"http://mammaldiversity.org\" xml:lang=\"en\">\n\
+  <dataset>\n\
+{identifier}\
+    <title xml:lang=\"en\">{title}</title>\n\
+    <creator>\n      <organizationName>Mammal Diversity Database</organizationName>\n    </creator>\n\
+    <pubDate>{pub_date}</pubDate>\n\
+    <language>en</language>\n\
+{remarks}\
+    <contact>\n      <organizationName>Mammal Diversity Database</organizationName>\n    </contact>\n\
+  </dataset>\n\
+</eml:eml>\n",
+            package_id = xml_escape(meta.doi.as_deref().unwrap_or(&meta.version)),
+            pub_date = xml_escape(&meta.release_date),
+        )
+    }
+}
+
+/// Treat the MDD missing value marker as an empty Darwin Core value.
+pub(crate) fn verbatim(value: &str) -> String {
+    let value = value.trim();
+    if value == MDD_MISSING_VALUE {
+        String::new()
+    } else {
+        value.to_string()
+    }
+}
+
+pub(crate) fn display_name(sci_name: &str) -> String {
+    sci_name.replace('_', " ")
+}
+
+/// The original combination of a synonym, or its genus and epithets when
+/// none is recorded.
+pub(crate) fn synonym_name(data: &SynonymData) -> String {
+    match verbatim(&data.original_combination) {
+        name if !name.is_empty() => name,
+        _ => [
+            &data.genus,
+            &data.specific_epithet,
+            &data.subspecific_epithet,
+        ]
+        .iter()
+        .map(|part| verbatim(part))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" "),
+    }
+}
+
+/// Format `Author, Year`, parenthesized when the name is no longer in its original genus.
+fn authorship(author: &str, year: &str, parentheses: bool) -> String {
+    let author = verbatim(author);
+    let year = match verbatim(year) {
+        year if year == "0" => String::new(),
+        year => year,
+    };
+    let text = match (author.is_empty(), year.is_empty()) {
+        (true, true) => return String::new(),
+        (false, true) => author,
+        (true, false) => year,
+        (false, false) => format!("{}, {}", author, year),
+    };
+    if parentheses {
+        format!("({})", text)
+    } else {
+        text
+    }
+}
+
+/// Map MDD synonym validity to a GBIF taxonomic status vocabulary term.
+fn taxonomic_status(validity: &str, has_accepted: bool) -> String {
+    match validity {
+        "nomen_dubium" | "species_inquirenda" => "doubtful",
+        _ if has_accepted => "synonym",
+        _ => "doubtful",
+    }
+    .to_string()
+}
+
+/// Tabs and line breaks would break the unquoted tab-separated core file.
+fn sanitize(mut row: DwcTaxon) -> DwcTaxon {
+    for field in [
+        &mut row.scientific_name,
+        &mut row.scientific_name_authorship,
+        &mut row.accepted_name_usage,
+        &mut row.original_name_usage,
+        &mut row.name_published_in,
+        &mut row.references,
+    ] {
+        if field.contains(['\t', '\n', '\r']) {
+            *field = field.replace(['\t', '\n', '\r'], " ");
+        }
+    }
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use super::*;
+    use crate::writer::fixtures::test_release;
+
+    #[test]
+    fn test_taxon_rows() {
+        let metadata = ReleaseMetadata::default();
+        let writer = DwcaWriter::new(&metadata);
+        let rows = writer.taxon_rows(&test_release());
+        let species = rows.iter().find(|r| r.taxon_id == "1001076").unwrap();
+        assert_eq!(species.scientific_name, "Bunolagus monticularis");
+        assert_eq!(species.scientific_name_authorship, "(O. Thomas, 1903)");
+        assert_eq!(species.taxonomic_status, "accepted");
+        assert_eq!(species.order, "Lagomorpha");
+        assert_eq!(species.infraspecific_epithet, "");
+
+        // The `species` validity row is the accepted name itself and is skipped.
+        assert!(!rows.iter().any(|r| r.taxon_id == "syn:100022090"));
+        let synonym = rows.iter().find(|r| r.taxon_id == "syn:100040650").unwrap();
+        assert_eq!(synonym.accepted_name_usage_id, "1001076");
+        assert_eq!(synonym.taxonomic_status, "synonym");
+        assert_eq!(synonym.scientific_name_authorship, "Musser, 1982");
+    }
+
+    #[test]
+    fn test_write_archive() {
+        let metadata = ReleaseMetadata {
+            name: "MDD".to_string(),
+            version: "2.2".to_string(),
+            release_date: "2025-01-01".to_string(),
+            doi: Some("10.5281/zenodo.1".to_string()),
+            ..Default::default()
+        };
+        let writer = DwcaWriter::new(&metadata);
+        let mut buffer = Cursor::new(Vec::new());
+        writer.write_archive(&test_release(), &mut buffer).unwrap();
+
+        let mut archive = zip::ZipArchive::new(buffer).unwrap();
+        let mut taxon = String::new();
+        archive
+            .by_name(TAXON_FILE)
+            .unwrap()
+            .read_to_string(&mut taxon)
+            .unwrap();
+        let header: Vec<&str> = taxon.lines().next().unwrap().split('\t').collect();
+        assert_eq!(header, DWC_TAXON_TERMS);
+        assert!(archive.by_name(META_FILE).is_ok());
+        let mut eml = String::new();
+        archive
+            .by_name(EML_FILE)
+            .unwrap()
+            .read_to_string(&mut eml)
+            .unwrap();
+        assert!(eml.contains("<title xml:lang=\"en\">MDD v2.2</title>"));
+    }
+}
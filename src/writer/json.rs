@@ -0,0 +1,171 @@
+//! JSON formatting: pretty printing and stable key order.
+//!
+//! Records serialize their fields in declaration order, so compact output
+//! already repeats between runs of the same crate version. `JsonStyle` with
+//! `with_sorted_keys(true)` goes further and orders the keys of every object
+//! alphabetically, which keeps release files diffable in git even when fields
+//! are added or moved between crate versions. `JsonStyle::stable()` combines
+//! that with two-space indentation, one field per line.
+//! `with_field_names` renames the keys to snake_case or the MDD CSV headers
+//! (see `naming::FieldNaming`).
+
+use std::io::Write;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::naming::FieldNaming;
+
+/// Layout of serialized JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct JsonStyle {
+    pretty: bool,
+    sorted_keys: bool,
+    field_names: FieldNaming,
+}
+
+impl JsonStyle {
+    /// Compact output with keys in declaration order.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pretty-printed output with alphabetically sorted keys.
+    pub fn stable() -> Self {
+        Self {
+            pretty: true,
+            sorted_keys: true,
+            field_names: FieldNaming::CamelCase,
+        }
+    }
+
+    /// Indent nested values, one field per line.
+    pub fn with_pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// Sort the keys of every object alphabetically.
+    pub fn with_sorted_keys(mut self, sorted_keys: bool) -> Self {
+        self.sorted_keys = sorted_keys;
+        self
+    }
+
+    /// Spell the keys with `field_names` instead of the serde names.
+    pub fn with_field_names(mut self, field_names: FieldNaming) -> Self {
+        self.field_names = field_names;
+        self
+    }
+
+    pub fn field_names(&self) -> FieldNaming {
+        self.field_names
+    }
+
+    /// Serialize `value` in this style.
+    pub fn to_string<T: Serialize + ?Sized>(&self, value: &T) -> serde_json::Result<String> {
+        if !self.needs_value() {
+            return self.format(value);
+        }
+        self.format(&self.styled_value(value)?)
+    }
+
+    /// Serialize `value` in this style into `writer`. Only sorted keys and
+    /// renamed fields need an intermediate `serde_json::Value`; the text is
+    /// never built in memory.
+    pub fn write<W: Write, T: Serialize + ?Sized>(
+        &self,
+        writer: W,
+        value: &T,
+    ) -> serde_json::Result<()> {
+        if !self.needs_value() {
+            return self.format_to(writer, value);
+        }
+        self.format_to(writer, &self.styled_value(value)?)
+    }
+
+    fn needs_value(&self) -> bool {
+        self.sorted_keys || self.field_names != FieldNaming::CamelCase
+    }
+
+    fn styled_value<T: Serialize + ?Sized>(&self, value: &T) -> serde_json::Result<Value> {
+        let mut value = serde_json::to_value(value)?;
+        self.field_names.rename_keys(&mut value);
+        if self.sorted_keys {
+            sort_keys(&mut value);
+        }
+        Ok(value)
+    }
+
+    fn format_to<W: Write, T: Serialize + ?Sized>(
+        &self,
+        writer: W,
+        value: &T,
+    ) -> serde_json::Result<()> {
+        if self.pretty {
+            serde_json::to_writer_pretty(writer, value)
+        } else {
+            serde_json::to_writer(writer, value)
+        }
+    }
+
+    fn format<T: Serialize + ?Sized>(&self, value: &T) -> serde_json::Result<String> {
+        if self.pretty {
+            serde_json::to_string_pretty(value)
+        } else {
+            serde_json::to_string(value)
+        }
+    }
+}
+
+/// Recursively order the keys of every object in `value` alphabetically.
+pub fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.sort_keys();
+            map.values_mut().for_each(sort_keys);
+        }
+        Value::Array(values) => values.iter_mut().for_each(sort_keys),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Record {
+        zeta: u8,
+        alpha: Vec<Inner>,
+    }
+
+    #[derive(Serialize)]
+    struct Inner {
+        b: u8,
+        a: u8,
+    }
+
+    #[test]
+    fn test_json_style() {
+        let record = Record {
+            zeta: 1,
+            alpha: vec![Inner { b: 2, a: 3 }],
+        };
+        assert_eq!(
+            JsonStyle::new().to_string(&record).unwrap(),
+            r#"{"zeta":1,"alpha":[{"b":2,"a":3}]}"#
+        );
+        assert_eq!(
+            JsonStyle::new()
+                .with_sorted_keys(true)
+                .to_string(&record)
+                .unwrap(),
+            r#"{"alpha":[{"a":3,"b":2}],"zeta":1}"#
+        );
+        let stable = JsonStyle::stable().to_string(&record).unwrap();
+        assert!(stable.starts_with("{\n  \"alpha\": ["));
+        let mut streamed = Vec::new();
+        JsonStyle::stable().write(&mut streamed, &record).unwrap();
+        assert_eq!(String::from_utf8(streamed).unwrap(), stable);
+    }
+}
@@ -0,0 +1,74 @@
+//! JSON Schemas of the documents the crate writes (`schema` feature).
+//!
+//! Schemas are derived from the serde definitions with `schemars`, so they
+//! always follow the field names and types of the JSON output. Each schema is
+//! a standalone JSON Schema (draft 2020-12) document; nested types are listed
+//! under `$defs`.
+//!
+//! | Schema | Output |
+//! |--------|--------|
+//! | `ReleasedMddData` | release bundle (`data.json`) |
+//! | `MddData` | a species record |
+//! | `SynonymData` | a synonym record |
+//! | `CountryMDDStats` | `country_stats.json` |
+
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use schemars::{schema_for, Schema};
+
+use crate::parser::{
+    country::CountryMDDStats, mdd::MddData, synonyms::SynonymData, ReleasedMddData,
+};
+
+/// Extension of written schema files, after the type name.
+pub const SCHEMA_EXTENSION: &str = "schema.json";
+
+/// Schemas of every public output structure, keyed by type name.
+pub fn output_schemas() -> Vec<(&'static str, Schema)> {
+    vec![
+        ("ReleasedMddData", schema_for!(ReleasedMddData)),
+        ("MddData", schema_for!(MddData)),
+        ("SynonymData", schema_for!(SynonymData)),
+        ("CountryMDDStats", schema_for!(CountryMDDStats)),
+    ]
+}
+
+/// Write every schema to `<output_dir>/<TypeName>.schema.json`, returning the
+/// written paths.
+pub fn write_schemas(output_dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    fs::create_dir_all(output_dir)?;
+    let mut paths = Vec::new();
+    for (name, schema) in output_schemas() {
+        let path = output_dir.join(format!("{}.{}", name, SCHEMA_EXTENSION));
+        fs::write(&path, serde_json::to_string_pretty(&schema)?)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_output_schemas() {
+        let schemas = output_schemas();
+        let (_, release) = &schemas[0];
+        let release = release.as_value();
+        assert_eq!(release["title"], "ReleasedMddData");
+        let required = release["required"].as_array().unwrap();
+        assert!(required.contains(&"synonymOnly".into()));
+        assert!(release["$defs"]["MddData"]["properties"]["sciName"].is_object());
+
+        let output_dir = TempDir::new("json_schema").unwrap();
+        let paths = write_schemas(output_dir.path()).unwrap();
+        assert_eq!(paths.len(), 4);
+        assert!(output_dir.path().join("MddData.schema.json").exists());
+    }
+}
@@ -0,0 +1,266 @@
+//! KML export of type localities for Google Earth.
+//!
+//! `KmlWriter` writes one placemark per species type locality and, unless
+//! `with_synonyms(false)` is set, per synonym type locality, in a `Species`
+//! and a `Synonyms` folder. The balloon of each placemark shows the name,
+//! authority, and verbatim locality description; synonyms also name the
+//! species they are assigned to. Records without a parseable, in-range
+//! latitude and longitude (see `parser::detail::TypeLocality`) are left out.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::{
+    nomenclature::references::{display_name, is_blank},
+    parser::{detail::TypeLocality, mdd::MddData, synonyms::SynonymData, ReleasedMddData},
+};
+
+use super::xml_escape;
+
+/// File extension used for KML output.
+pub const KML_EXTENSION: &str = "kml";
+
+/// Kind of record a placemark was made from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlacemarkKind {
+    Species,
+    Synonym,
+}
+
+/// A type locality with its balloon text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placemark {
+    pub kind: PlacemarkKind,
+    /// MDD species id or synonym id.
+    pub id: u32,
+    pub name: String,
+    pub authority: String,
+    /// Verbatim type locality description.
+    pub locality: String,
+    /// Valid species name of a synonym; empty for species.
+    pub species: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Placemark {
+    pub fn from_species(species: &MddData) -> Option<Self> {
+        let (latitude, longitude) = TypeLocality::from_species(species).point()?;
+        Some(Self {
+            kind: PlacemarkKind::Species,
+            id: species.id,
+            name: species.sci_name.replace('_', " "),
+            authority: species.authorship().to_string(),
+            locality: verbatim(&species.type_locality).to_string(),
+            species: String::new(),
+            latitude,
+            longitude,
+        })
+    }
+
+    /// Placemark of a synonym; the locality is the original type locality,
+    /// or the emended or unchecked one when that is missing.
+    pub fn from_synonym(synonym: &SynonymData) -> Option<Self> {
+        let (latitude, longitude) = TypeLocality::from_synonym(synonym).point()?;
+        let locality = [
+            &synonym.original_type_locality,
+            &synonym.emended_type_locality,
+            &synonym.unchecked_type_locality,
+        ]
+        .into_iter()
+        .map(|locality| verbatim(locality))
+        .find(|locality| !locality.is_empty())
+        .unwrap_or_default();
+        Some(Self {
+            kind: PlacemarkKind::Synonym,
+            id: synonym.syn_id,
+            name: display_name(synonym).replace('_', " "),
+            authority: synonym.authorship().to_string(),
+            locality: locality.to_string(),
+            species: verbatim(&synonym.species).replace('_', " "),
+            latitude,
+            longitude,
+        })
+    }
+
+    fn push_kml(&self, kml: &mut String) {
+        let id_label = match self.kind {
+            PlacemarkKind::Species => "MDD id",
+            PlacemarkKind::Synonym => "MDD synonym id",
+        };
+        // The balloon is HTML, escaped once more as the KML text.
+        let mut balloon = format!(
+            "<p><i>{}</i> {}</p>",
+            xml_escape(&self.name),
+            xml_escape(&self.authority)
+        );
+        if !self.species.is_empty() {
+            balloon.push_str(&format!(
+                "<p>Synonym of <i>{}</i></p>",
+                xml_escape(&self.species)
+            ));
+        }
+        if !self.locality.is_empty() {
+            balloon.push_str(&format!("<p>{}</p>", xml_escape(&self.locality)));
+        }
+        balloon.push_str(&format!("<p>{}: {}</p>", id_label, self.id));
+        kml.push_str(&format!(
+            "<Placemark>\n<name>{}</name>\n<description>{}</description>\n\
+             <Point><coordinates>{},{}</coordinates></Point>\n</Placemark>\n",
+            xml_escape(&self.name),
+            xml_escape(&balloon),
+            self.longitude,
+            self.latitude
+        ));
+    }
+}
+
+/// Writes the type localities of a release as a KML document.
+pub struct KmlWriter<'a> {
+    release: &'a ReleasedMddData,
+    synonyms: bool,
+}
+
+impl<'a> KmlWriter<'a> {
+    pub fn new(release: &'a ReleasedMddData) -> Self {
+        Self {
+            release,
+            synonyms: true,
+        }
+    }
+
+    /// Include synonym type localities (default `true`).
+    pub fn with_synonyms(mut self, synonyms: bool) -> Self {
+        self.synonyms = synonyms;
+        self
+    }
+
+    /// Species placemarks, in release order.
+    pub fn species_placemarks(&self) -> Vec<Placemark> {
+        self.release
+            .species()
+            .filter_map(Placemark::from_species)
+            .collect()
+    }
+
+    /// Synonym placemarks, attached synonyms first, then synonym-only rows;
+    /// empty when synonyms are excluded.
+    pub fn synonym_placemarks(&self) -> Vec<Placemark> {
+        if !self.synonyms {
+            return Vec::new();
+        }
+        self.release
+            .all_synonyms()
+            .filter_map(Placemark::from_synonym)
+            .collect()
+    }
+
+    /// The KML document.
+    pub fn to_kml(&self) -> String {
+        let mut kml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n",
+        );
+        kml.push_str(&format!(
+            "<name>MDD v{} type localities</name>\n",
+            xml_escape(self.release.get_version())
+        ));
+        push_folder(&mut kml, "Species", &self.species_placemarks());
+        if self.synonyms {
+            push_folder(&mut kml, "Synonyms", &self.synonym_placemarks());
+        }
+        kml.push_str("</Document>\n</kml>\n");
+        kml
+    }
+
+    /// Write the KML document to any `Write` sink.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_kml().as_bytes())
+    }
+
+    /// Write the KML document to `path`.
+    pub fn write_file(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_kml())
+    }
+}
+
+fn push_folder(kml: &mut String, name: &str, placemarks: &[Placemark]) {
+    kml.push_str(&format!("<Folder>\n<name>{}</name>\n", name));
+    for placemark in placemarks {
+        placemark.push_kml(kml);
+    }
+    kml.push_str("</Folder>\n");
+}
+
+fn verbatim(value: &str) -> &str {
+    if is_blank(value) {
+        ""
+    } else {
+        value.trim()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kml() {
+        let species = MddData {
+            id: 1,
+            sci_name: "Ochotona_princeps".to_string(),
+            authority_species_author: "Richardson".to_string(),
+            authority_species_year: 1828,
+            type_locality: "Rocky Mountains, near the Athabasca River".to_string(),
+            type_locality_latitude: "52.5".to_string(),
+            type_locality_longitude: "-117.5".to_string(),
+            ..MddData::new()
+        };
+        let unmapped = MddData {
+            id: 2,
+            sci_name: "Ochotona_collaris".to_string(),
+            ..MddData::new()
+        };
+        let mut synonym = SynonymData::new();
+        synonym.syn_id = 10;
+        synonym.species_id = Some(1);
+        synonym.species = "Ochotona_princeps".to_string();
+        synonym.original_combination = "Lepus (Lagomys) princeps".to_string();
+        synonym.author = "Richardson".to_string();
+        synonym.year = "1828".to_string();
+        synonym.original_type_locality = "NA".to_string();
+        synonym.emended_type_locality = "Athabasca & Columbia rivers".to_string();
+        synonym.type_latitude = "52°30'N".to_string();
+        synonym.type_longitude = "117°30'W".to_string();
+        let release = ReleasedMddData::from_parser(
+            vec![species, unmapped],
+            vec![synonym],
+            "2.0",
+            "2025-01-01",
+        );
+
+        let writer = KmlWriter::new(&release);
+        assert_eq!(writer.species_placemarks().len(), 1);
+        let synonyms = writer.synonym_placemarks();
+        assert_eq!(synonyms[0].locality, "Athabasca & Columbia rivers");
+        assert_eq!(
+            (synonyms[0].latitude, synonyms[0].longitude),
+            (52.5, -117.5)
+        );
+
+        let kml = writer.to_kml();
+        assert!(kml.starts_with("<?xml"));
+        assert!(kml.contains("<name>MDD v2.0 type localities</name>"));
+        assert_eq!(kml.matches("<Placemark>").count(), 2);
+        assert!(kml.contains("<coordinates>-117.5,52.5</coordinates>"));
+        assert!(kml.contains("Synonym of &lt;i&gt;Ochotona princeps&lt;/i&gt;"));
+        assert!(kml.contains("Athabasca &amp;amp; Columbia rivers"));
+
+        let species_only = KmlWriter::new(&release).with_synonyms(false).to_kml();
+        assert_eq!(species_only.matches("<Placemark>").count(), 1);
+        assert!(!species_only.contains("Synonyms"));
+    }
+}
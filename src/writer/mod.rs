@@ -0,0 +1,402 @@
+//! Output helpers for serializing parsed MDD data into JSON or CSV.
+//!
+//! The writers in this module accept JSON strings containing either an
+//! aggregated `AllMddData` structure (species + synonym bundle) or a vector of
+//! `MddData` rows and persist them to disk. `AllMddWriter` and `MddWriter` write
+//! the order key as `order` rather than `taxonOrder` by default for
+//! interoperability with tools expecting the original header, keeping the
+//! other keys in camelCase; `AllMddWriter::with_field_names` selects another
+//! `naming::FieldNaming`. Its CSV output uses the MDD column layout of
+//! `csv::MddCsvWriter`. Keys are renamed on the parsed document, so values
+//! are never rewritten.
+//!
+//! Design notes:
+//! * Conversion routines keep memory usage modest by streaming writes via
+//!   `csv::Writer` where applicable.
+//! * Gzipped JSON (`*.json.gz`) can be ingested and converted using
+//!   `AllMddWriter::write_from_gz`.
+//! * Both writers expose a `to_csv` flag; when false, raw JSON is written
+//!   unchanged.
+//! * Both implement the public `Writer` trait, which writes to any
+//!   `std::io::Write` sink (`write_to`), with helpers for files, stdout, and
+//!   in-memory buffers.
+//! * `compression::Compression` selects gzip, Zstandard, Brotli, or no
+//!   compression (with a level) for JSON and NDJSON output.
+//! * `csv::MddCsvWriter` writes species in the original MDD column layout,
+//!   round-tripping release CSVs byte for byte.
+//! * `coldp` packages a release as a Catalogue of Life Data Package
+//!   (`archive` feature).
+//! * `dwca` packages a release as a Darwin Core Archive for GBIF (`archive`
+//!   feature).
+//! * `json::JsonStyle` selects pretty printing and alphabetically sorted keys
+//!   for release files that diff cleanly in git.
+//! * `kml::KmlWriter` writes species and synonym type localities as KML
+//!   placemarks for Google Earth.
+//! * `naming::FieldNaming` renames JSON keys to snake_case or the MDD CSV
+//!   headers, selected through `JsonStyle::with_field_names`.
+//! * `ndjson` streams records as newline-delimited JSON, one per line.
+//! * `package::ReleasePackage` assembles the `MDD_v<version>.zip` release
+//!   archive read by `parser::archive` (`archive` feature).
+//! * `per_species` writes one JSON file per species plus an `index.json`
+//!   manifest for static sites.
+//! * `sharded` splits a release into one JSON file per order or family plus a
+//!   `shards.json` index mapping taxa to files.
+//! * `taxon_summary::TaxonSummaries` writes `families.json` and
+//!   `genera.json` (order, counts, a representative species, and IUCN
+//!   categories per taxon) for browse pages.
+//! * `phylo` writes the taxonomy tree as Newick or phyloXML constraint trees.
+//! * `provenance` records the release and crate version behind generated
+//!   files (`ProvenanceHeader`, sidecar `manifest.json`).
+//! * `release_index` lists the releases of a batch run, oldest first, in one
+//!   `releases.json`.
+//! * `parquet` writes species and synonym tables as Apache Parquet (`parquet`
+//!   feature).
+//! * `json_schema` emits JSON Schemas of the release bundle, records, and country
+//!   statistics (`schema` feature).
+
+use std::{
+    error::Error,
+    fs,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use flate2::bufread::MultiGzDecoder;
+
+use crate::parser::{mdd::MddData, AllMddData};
+use naming::FieldNaming;
+
+#[cfg(feature = "archive")]
+pub mod coldp;
+pub mod compression;
+pub mod csv;
+#[cfg(feature = "archive")]
+pub mod dwca;
+pub mod json;
+#[cfg(feature = "schema")]
+pub mod json_schema;
+pub mod kml;
+pub mod naming;
+pub mod ndjson;
+#[cfg(feature = "archive")]
+pub mod package;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+pub mod per_species;
+pub mod phylo;
+pub mod provenance;
+pub mod release_index;
+pub mod sharded;
+pub mod taxon_summary;
+
+const CSV_EXTENSION: &str = "csv";
+const JSON_EXTENSION: &str = "json";
+
+/// Common behavior for writer implementations.
+///
+/// A writer converts a JSON document into its output format and writes it to
+/// any `std::io::Write` sink: a file, standard output, an in-memory buffer, or
+/// a network stream. The trait is object safe, so writers can be selected at
+/// runtime as `Box<dyn Writer>`.
+pub trait Writer {
+    /// Convert `json_data` and write it to `sink`.
+    fn write_to(&self, json_data: &str, sink: &mut dyn Write) -> Result<(), Box<dyn Error>>;
+
+    /// Extension of the produced files, without the leading dot.
+    fn get_extension(&self) -> &str;
+
+    /// Write to the file at `path`, creating or truncating it.
+    fn write_to_path(&self, json_data: &str, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut file = BufWriter::new(fs::File::create(path)?);
+        self.write_to(json_data, &mut file)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Write to standard output.
+    fn write_to_stdout(&self, json_data: &str) -> Result<(), Box<dyn Error>> {
+        let mut stdout = io::stdout().lock();
+        self.write_to(json_data, &mut stdout)?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    /// Write into a new in-memory buffer.
+    fn write_to_buffer(&self, json_data: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        self.write_to(json_data, &mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+/// Write data structure for full MDD + synonym bundle (`AllMddData`).
+pub struct AllMddWriter<'a> {
+    pub output_dir: &'a Path,
+    pub output_filename: &'a str,
+    pub to_csv: bool,
+    /// Key spelling of the JSON output. `None` (the default) keeps the
+    /// camelCase keys except `taxonOrder`, which becomes `order`.
+    pub field_names: Option<FieldNaming>,
+}
+
+impl Writer for AllMddWriter<'_> {
+    fn write_to(&self, json_data: &str, sink: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        if self.to_csv {
+            let records: AllMddData = serde_json::from_str(json_data)?;
+            let mut writer = csv::MddCsvWriter::new(sink)?;
+            writer.write_records(records.get_species())?;
+            writer.into_inner()?.flush()?;
+            Ok(())
+        } else if self.field_names == Some(FieldNaming::CamelCase) {
+            sink.write_all(json_data.as_bytes())?;
+            Ok(())
+        } else {
+            let mut value: serde_json::Value = serde_json::from_str(json_data)?;
+            match self.field_names {
+                Some(field_names) => field_names.rename_keys(&mut value),
+                None => naming::rename_order_keys(&mut value),
+            }
+            serde_json::to_writer(sink, &value)?;
+            Ok(())
+        }
+    }
+
+    fn get_extension(&self) -> &str {
+        output_extension(self.to_csv)
+    }
+}
+
+impl<'a> AllMddWriter<'a> {
+    /// Create a new writer.
+    pub fn new(output_dir: &'a Path, output_filename: &'a str, to_csv: bool) -> Self {
+        AllMddWriter {
+            output_dir,
+            output_filename,
+            to_csv,
+            field_names: None,
+        }
+    }
+
+    /// Spell the JSON keys with `field_names`.
+    pub fn with_field_names(mut self, field_names: FieldNaming) -> Self {
+        self.field_names = Some(field_names);
+        self
+    }
+
+    /// Persist provided JSON (`AllMddData`) to disk in JSON or CSV form.
+    pub fn write(&self, json_data: &str) -> Result<PathBuf, Box<dyn Error>> {
+        fs::create_dir_all(self.output_dir)?;
+        let output_path = self.create_output_path();
+        self.write_to_path(json_data, &output_path)?;
+        Ok(output_path)
+    }
+
+    /// Read a gzipped JSON file (e.g., produced by distribution pipeline),
+    /// decompress, and write it out in the configured format (JSON or CSV).
+    pub fn write_from_gz(&self, json_path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        let file = fs::File::open(json_path)?;
+        let inner = BufReader::new(file);
+        let mut json_data = MultiGzDecoder::new(inner);
+        let mut buf = String::new();
+        json_data.read_to_string(&mut buf)?;
+        self.write(&buf)
+    }
+
+    fn create_output_path(&self) -> PathBuf {
+        self.output_dir
+            .join(self.output_filename)
+            .with_extension(self.get_extension())
+    }
+}
+
+/// Write data structure for MDD data only (`Vec<MddData>` JSON array).
+pub struct MddWriter<'a> {
+    pub output_dir: &'a Path,
+    pub output_filename: &'a str,
+    pub to_csv: bool,
+}
+
+impl Writer for MddWriter<'_> {
+    fn write_to(&self, json_data: &str, sink: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        if self.to_csv {
+            let records: Vec<MddData> = serde_json::from_str(json_data)?;
+            let mut writer = csv::MddCsvWriter::new(sink)?;
+            writer.write_records(&records)?;
+            writer.into_inner()?.flush()?;
+            Ok(())
+        } else {
+            let mut value: serde_json::Value = serde_json::from_str(json_data)?;
+            naming::rename_order_keys(&mut value);
+            serde_json::to_writer(sink, &value)?;
+            Ok(())
+        }
+    }
+
+    fn get_extension(&self) -> &str {
+        output_extension(self.to_csv)
+    }
+}
+
+impl<'a> MddWriter<'a> {
+    /// Construct a new writer for species-only datasets.
+    pub fn new(output_dir: &'a Path, output_filename: &'a str, to_csv: bool) -> Self {
+        Self {
+            output_dir,
+            output_filename,
+            to_csv,
+        }
+    }
+
+    /// Persist provided JSON (array of `MddData`) to disk in JSON or CSV form.
+    pub fn write(&self, json_data: &str) -> Result<PathBuf, Box<dyn Error>> {
+        fs::create_dir_all(self.output_dir)?;
+        let output_path = self.create_output_path();
+        self.write_to_path(json_data, &output_path)?;
+        Ok(output_path)
+    }
+
+    fn create_output_path(&self) -> PathBuf {
+        self.output_dir
+            .join(self.output_filename)
+            .with_extension(self.get_extension())
+    }
+}
+
+fn output_extension(to_csv: bool) -> &'static str {
+    if to_csv {
+        CSV_EXTENSION
+    } else {
+        JSON_EXTENSION
+    }
+}
+
+/// Escape the XML special characters of text and attribute values.
+pub(crate) fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Release shared by the tests of the archive writers.
+#[cfg(all(test, feature = "archive"))]
+pub(crate) mod fixtures {
+    use crate::parser::{mdd::MddData, synonyms::SynonymData, ReleasedMddData};
+
+    /// The test species and synonyms, with the first two synonym usages
+    /// attached to a species present in the test data.
+    pub(crate) fn test_release() -> ReleasedMddData {
+        let species =
+            MddData::new().from_csv(&std::fs::read_to_string("tests/data/test_data.csv").unwrap());
+        let mut synonyms = SynonymData::new()
+            .from_csv(&std::fs::read_to_string("tests/data/syndata.csv").unwrap());
+        synonyms[0].species_id = Some(1001076);
+        synonyms[1].species_id = Some(1001076);
+        ReleasedMddData::from_parser(species, synonyms, "2.2", "2025-01-01")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::parser::synonyms::SynonymData;
+
+    #[test]
+    fn test_write_json() {
+        let json_mdd: &str = r#"[{"id":1,"phylosort":1,"subclass":"Theria"}]"#;
+        let output_dir = TempDir::new("output").unwrap();
+        let output_dir = env::current_dir().unwrap().join(output_dir.path());
+        let filename = "output";
+        let parser = AllMddWriter::new(&output_dir, filename, false);
+        parser.write(json_mdd).unwrap();
+        let json_result = output_dir.join(filename).with_extension(JSON_EXTENSION);
+        assert!(json_result.exists());
+    }
+
+    // #[test]
+    // fn test_write_csv() {
+    //     let input_path = Path::new("../assets/data/data.json.gz");
+    //     let output_dir = TempDir::new("output").unwrap();
+    //     let output_dir = env::current_dir().unwrap().join(output_dir.path());
+    //     let filename = "output";
+    //     let parser = AllMddWriter::new(&output_dir, filename, true);
+    //     parser.write_from_gz(input_path).unwrap();
+    // }
+
+    #[test]
+    fn test_write_to_sinks() {
+        let record = MddData {
+            id: 1,
+            taxon_order: "Carnivora".to_string(),
+            ..MddData::new()
+        };
+        let json_mdd = serde_json::to_string(&vec![record]).unwrap();
+        let json_mdd = json_mdd.as_str();
+        let output_dir = Path::new("unused");
+        let writers: Vec<Box<dyn Writer>> = vec![
+            Box::new(MddWriter::new(output_dir, "output", false)),
+            Box::new(MddWriter::new(output_dir, "output", true)),
+        ];
+        let json = writers[0].write_to_buffer(json_mdd).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(json[0]["order"], "Carnivora");
+        assert!(json[0].get("taxonOrder").is_none());
+        let csv = String::from_utf8(writers[1].write_to_buffer(json_mdd).unwrap()).unwrap();
+        let mut lines = csv.lines();
+        let header = lines.next().unwrap();
+        assert!(header.starts_with("sciName,id,phylosort,"));
+        assert!(header.contains(",order,"));
+        assert!(!header.contains("taxonOrder") && !header.contains("slug"));
+        assert!(lines.next().unwrap().contains(",Carnivora,"));
+        assert_eq!(writers[1].get_extension(), CSV_EXTENSION);
+    }
+
+    #[test]
+    fn test_all_mdd_writer_keeps_values() {
+        let record = MddData {
+            id: 1,
+            taxon_order: "Carnivora".to_string(),
+            taxonomy_notes: "taxonOrder was renamed".to_string(),
+            ..MddData::new()
+        };
+        let synonym = SynonymData {
+            syn_id: 7,
+            species_id: Some(1),
+            ..SynonymData::new()
+        };
+        let data = AllMddData::from_parser(vec![record], vec![synonym]);
+        let output_dir = Path::new("unused");
+
+        let json = AllMddWriter::new(output_dir, "output", false)
+            .write_to_buffer(&data.to_json())
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(json["data"][0]["order"], "Carnivora");
+        assert_eq!(json["data"][0]["taxonomyNotes"], "taxonOrder was renamed");
+        let synonym = &json["synonyms"][0];
+        assert_eq!(synonym["synId"], 7);
+        assert!(synonym.get("rootName").is_some());
+        assert!(synonym.get("MDD_syn_ID").is_none() && synonym.get("MDD_root_name").is_none());
+
+        let csv = AllMddWriter::new(output_dir, "output", true)
+            .write_to_buffer(&data.to_json())
+            .unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("sciName,id,phylosort,"));
+        assert!(csv.contains("taxonOrder was renamed"));
+    }
+
+    #[test]
+    fn check_filename() {
+        let output_dir = TempDir::new("output").unwrap();
+        let output_dir = env::current_dir().unwrap().join(output_dir.path());
+        let filename = "output";
+        let parser = AllMddWriter::new(&output_dir, filename, false);
+        let output_path = parser.create_output_path();
+        assert_eq!(output_path, output_dir.join("output.json"));
+    }
+}
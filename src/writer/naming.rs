@@ -0,0 +1,198 @@
+//! Field name profiles for JSON output.
+//!
+//! Records serialize with camelCase keys (`sciName`, `taxonOrder`). Systems
+//! downstream of MDD expect other spellings, so `FieldNaming` renames the keys
+//! of an already serialized `serde_json::Value`:
+//! * `CamelCase` keeps the serde names;
+//! * `SnakeCase` turns every key into snake_case (`sciName` → `sci_name`,
+//!   `typeVoucherURIs` → `type_voucher_uris`);
+//! * `Original` uses the MDD CSV headers for species and synonym records
+//!   (`order`, `MDD_syn_ID`, `MDD_original_combination`) and keeps other keys,
+//!   such as those of the bundle metadata, in camelCase.
+//!
+//! Every object key is renamed, so the profiles suit records and bundles,
+//! not maps keyed by data such as country statistics. Only camelCase output
+//! can be read back by the parser types.
+
+use std::{fmt, str::FromStr};
+
+use serde_json::{Map, Value};
+
+use crate::parser::schema::{normalize_synonym_column, SYNONYM_COLUMNS};
+
+/// Serde name and (normalized) CSV header of the order column.
+const ORDER_FIELD: &str = "taxonOrder";
+const ORDER_COLUMN: &str = "order";
+/// Keys identifying a serialized `SynonymData`.
+const SYNONYM_KEYS: [&str; 2] = ["synId", "rootName"];
+
+/// Spelling of the keys in JSON output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FieldNaming {
+    /// Serde names, e.g. `sciName` (default).
+    #[default]
+    CamelCase,
+    /// snake_case names, e.g. `sci_name`.
+    SnakeCase,
+    /// MDD CSV headers for species and synonym records, e.g. `MDD_root_name`.
+    Original,
+}
+
+impl FieldNaming {
+    /// Rename the keys of every object in `value`, recursively.
+    pub fn rename_keys(&self, value: &mut Value) {
+        if *self == Self::CamelCase {
+            return;
+        }
+        match value {
+            Value::Object(map) => {
+                let renamed = self.rename_object(std::mem::take(map));
+                *map = renamed;
+            }
+            Value::Array(values) => values.iter_mut().for_each(|value| self.rename_keys(value)),
+            _ => {}
+        }
+    }
+
+    fn rename_object(&self, map: Map<String, Value>) -> Map<String, Value> {
+        let is_synonym = SYNONYM_KEYS.iter().all(|key| map.contains_key(*key));
+        map.into_iter()
+            .map(|(key, mut value)| {
+                self.rename_keys(&mut value);
+                let key = match self {
+                    Self::CamelCase => key,
+                    Self::SnakeCase => to_snake_case(&key),
+                    Self::Original if is_synonym => synonym_column(&key).unwrap_or(key),
+                    Self::Original if key == ORDER_FIELD => ORDER_COLUMN.to_string(),
+                    Self::Original => key,
+                };
+                (key, value)
+            })
+            .collect()
+    }
+}
+
+/// Rename every `taxonOrder` key in `value` to `order`, recursively, keeping
+/// the other keys in camelCase.
+pub(crate) fn rename_order_keys(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            *map = std::mem::take(map)
+                .into_iter()
+                .map(|(key, mut value)| {
+                    rename_order_keys(&mut value);
+                    if key == ORDER_FIELD {
+                        (ORDER_COLUMN.to_string(), value)
+                    } else {
+                        (key, value)
+                    }
+                })
+                .collect();
+        }
+        Value::Array(values) => values.iter_mut().for_each(rename_order_keys),
+        _ => {}
+    }
+}
+
+impl fmt::Display for FieldNaming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CamelCase => write!(f, "camel"),
+            Self::SnakeCase => write!(f, "snake"),
+            Self::Original => write!(f, "original"),
+        }
+    }
+}
+
+impl FromStr for FieldNaming {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "camel" | "camelcase" => Ok(Self::CamelCase),
+            "snake" | "snake_case" => Ok(Self::SnakeCase),
+            "original" => Ok(Self::Original),
+            _ => Err(format!("Unknown field naming: '{}'", s)),
+        }
+    }
+}
+
+/// The synonym CSV header whose normalized name is `key`.
+fn synonym_column(key: &str) -> Option<String> {
+    let key = if key == ORDER_FIELD {
+        ORDER_COLUMN
+    } else {
+        key
+    };
+    SYNONYM_COLUMNS
+        .iter()
+        .find(|column| normalize_synonym_column(column) == key)
+        .map(|column| column.to_string())
+}
+
+/// snake_case of a camelCase key. A run of capitals stays one word
+/// (`URIs` → `uris`, `MSW3` → `msw3`).
+fn to_snake_case(key: &str) -> String {
+    let mut snake = String::with_capacity(key.len() + 4);
+    let mut previous: Option<char> = None;
+    for c in key.chars() {
+        if c.is_uppercase() && previous.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit()) {
+            snake.push('_');
+        }
+        snake.extend(c.to_lowercase());
+        previous = Some(c);
+    }
+    snake
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_field_naming() {
+        let bundle = json!({
+            "metadata": {"totalSpecies": 1},
+            "data": [{
+                "speciesData": {"sciName": "Panthera_leo", "taxonOrder": "Carnivora", "typeVoucherURIs": "", "CMW_sciName": "", "diffSinceMSW3": 0},
+                "synonyms": [{"synId": 1, "rootName": "leo", "originalCombination": "Felis leo", "taxonOrder": "Carnivora", "hespId": 2}]
+            }]
+        });
+
+        let mut snake = bundle.clone();
+        FieldNaming::SnakeCase.rename_keys(&mut snake);
+        let species = &snake["data"][0]["species_data"];
+        assert_eq!(species["sci_name"], "Panthera_leo");
+        assert!(species.get("type_voucher_uris").is_some());
+        assert!(species.get("cmw_sci_name").is_some());
+        assert!(species.get("diff_since_msw3").is_some());
+        assert_eq!(snake["metadata"]["total_species"], 1);
+
+        let mut original = bundle.clone();
+        FieldNaming::Original.rename_keys(&mut original);
+        let entry = &original["data"][0];
+        assert_eq!(entry["speciesData"]["order"], "Carnivora");
+        assert_eq!(entry["speciesData"]["sciName"], "Panthera_leo");
+        let synonym = &entry["synonyms"][0];
+        assert_eq!(synonym["MDD_syn_ID"], 1);
+        assert_eq!(synonym["MDD_original_combination"], "Felis leo");
+        assert_eq!(synonym["MDD_order"], "Carnivora");
+        assert_eq!(synonym["Hesp_id"], 2);
+        assert_eq!(original["metadata"]["totalSpecies"], 1);
+
+        let mut camel = bundle.clone();
+        FieldNaming::CamelCase.rename_keys(&mut camel);
+        assert_eq!(camel, bundle);
+        assert_eq!("snake".parse(), Ok(FieldNaming::SnakeCase));
+
+        let mut order_only = bundle.clone();
+        rename_order_keys(&mut order_only);
+        let entry = &order_only["data"][0];
+        assert_eq!(entry["speciesData"]["order"], "Carnivora");
+        assert_eq!(entry["synonyms"][0]["order"], "Carnivora");
+        assert_eq!(entry["synonyms"][0]["synId"], 1);
+        assert!(entry["synonyms"][0].get("taxonOrder").is_none());
+    }
+}
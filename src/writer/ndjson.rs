@@ -0,0 +1,111 @@
+//! Newline-delimited JSON (NDJSON) output.
+//!
+//! Each record is serialized on its own line, so the output can be piped
+//! through `jq`, loaded into BigQuery, or consumed by streaming ETL without
+//! parsing one large JSON document. A release is written as one line per
+//! species entry (`SimpleMDD`: MDD id, species record, and its synonyms);
+//! synonym-only names are not part of the species stream.
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{parser::ReleasedMddData, writer::compression::Compression};
+
+/// File extension used for NDJSON output.
+pub const NDJSON_EXTENSION: &str = "ndjson";
+
+/// Writes serializable records as NDJSON to any `Write` sink.
+pub struct NdjsonWriter<W: Write> {
+    writer: W,
+    records: usize,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer, records: 0 }
+    }
+
+    /// Serialize one record followed by a newline.
+    pub fn write_record<T: Serialize>(&mut self, record: &T) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.writer, record)?;
+        self.writer.write_all(b"\n")?;
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Serialize every record of `records`, returning how many were written.
+    pub fn write_records<'a, T, I>(&mut self, records: I) -> std::io::Result<usize>
+    where
+        T: Serialize + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        let start = self.records;
+        for record in records {
+            self.write_record(record)?;
+        }
+        Ok(self.records - start)
+    }
+
+    /// Write one line per species entry of `release`.
+    pub fn write_release(&mut self, release: &ReleasedMddData) -> std::io::Result<usize> {
+        self.write_records(&release.data)
+    }
+
+    /// Number of records written so far.
+    pub fn record_count(&self) -> usize {
+        self.records
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn into_inner(mut self) -> std::io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Write the species entries of `release` to `<output_dir>/<output_filename>.ndjson`,
+/// with the compression extension appended (e.g. `.ndjson.zst`).
+pub fn write_release_to_file(
+    release: &ReleasedMddData,
+    output_dir: &Path,
+    output_filename: &str,
+    compression: Compression,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    fs::create_dir_all(output_dir)?;
+    let extension = match compression.extension() {
+        Some(ext) => format!("{}.{}", NDJSON_EXTENSION, ext),
+        None => NDJSON_EXTENSION.to_string(),
+    };
+    let output_path = output_dir.join(output_filename).with_extension(extension);
+    compression.create_file_with(&output_path, |writer| {
+        NdjsonWriter::new(writer).write_release(release).map(|_| ())
+    })?;
+    Ok(output_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{mdd::MddData, synonyms::SynonymData, SimpleMDD};
+
+    #[test]
+    fn test_write_release_ndjson() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let release =
+            ReleasedMddData::from_parser(species, Vec::<SynonymData>::new(), "2.0", "2025-01-01");
+
+        let mut writer = NdjsonWriter::new(Vec::new());
+        assert_eq!(writer.write_release(&release).unwrap(), 112);
+        let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 112);
+        let first: SimpleMDD = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first.mdd_id(), 1001076);
+    }
+}
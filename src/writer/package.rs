@@ -0,0 +1,275 @@
+//! Release archive packaging, the publish side of `parser::archive`.
+//!
+//! `ReleasePackage` takes a release TOML and the CSVs it references and
+//! writes the canonical `MDD_v<version>.zip`:
+//!
+//! ```text
+//! MDD/MDD_v<version>_<n>species.csv
+//! MDD/Species_Syn_v<version>.csv
+//! MDD/release.toml
+//! MDD/<extra files>
+//! ```
+//!
+//! The CSVs are renamed to the patterns `parser::archive` and `mdd zip`
+//! look for, and the packaged `release.toml` points at the renamed entries
+//! with fresh SHA-256 digests. Extra files (e.g. generated JSON) are added
+//! next to them, and are rejected when their name would be mistaken for a
+//! release entry.
+
+use std::{
+    collections::HashSet,
+    fs,
+    io::{Seek, Write},
+    path::{Path, PathBuf},
+};
+
+use zip::write::SimpleFileOptions;
+
+use crate::{
+    helper::checksum::sha256_hex,
+    parser::{
+        archive::{
+            is_species_file_name, is_synonym_file_name, release_version_from_file_name,
+            MDD_FILE_PREFIX, RELEASE_DIR, RELEASE_TOML_FILE, SYNONYM_FILE_PREFIX,
+        },
+        error::MddError,
+        metadata::ReleaseToml,
+        version::ReleaseVersion,
+    },
+};
+
+/// A release TOML plus the files to package with it.
+#[derive(Debug, Clone)]
+pub struct ReleasePackage {
+    toml: ReleaseToml,
+    /// Directory the CSV paths of the TOML resolve against.
+    base_dir: PathBuf,
+    version: ReleaseVersion,
+    extra_files: Vec<PathBuf>,
+}
+
+impl ReleasePackage {
+    /// Package the release described by `toml`, whose CSV paths resolve
+    /// against `base_dir`. Fails with `MddError::InvalidPackage` when the
+    /// version cannot be written into the release file names.
+    pub fn new(toml: ReleaseToml, base_dir: &Path) -> Result<Self, MddError> {
+        let version = toml
+            .release_version()
+            .map_err(|e| MddError::InvalidPackage(format!("cannot name release files: {}", e)))?;
+        Ok(Self {
+            toml,
+            base_dir: base_dir.to_path_buf(),
+            version,
+            extra_files: Vec::new(),
+        })
+    }
+
+    /// Read the release TOML at `path`; its CSV paths resolve against the
+    /// directory holding it.
+    pub fn from_toml_file(path: &Path) -> Result<Self, MddError> {
+        let toml = ReleaseToml::from_file(path)?;
+        let base_dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        Self::new(toml, base_dir)
+    }
+
+    /// The release TOML as read, before its file names are rewritten.
+    pub fn toml(&self) -> &ReleaseToml {
+        &self.toml
+    }
+
+    /// Also package `path` under `MDD/` with its file name.
+    pub fn with_file(mut self, path: &Path) -> Self {
+        self.extra_files.push(path.to_path_buf());
+        self
+    }
+
+    /// File name of the archive, e.g. `MDD_v2.2.zip`.
+    pub fn archive_name(&self) -> String {
+        format!("{}{}.zip", MDD_FILE_PREFIX, self.version)
+    }
+
+    /// Entry name of the species CSV, e.g. `MDD/MDD_v2.2_6815species.csv`.
+    /// Counts the records of the species CSV.
+    pub fn species_entry_name(&self) -> Result<String, MddError> {
+        let path = self.toml.metadata.mdd_path(&self.base_dir);
+        let mut reader = csv::Reader::from_path(&path).map_err(|e| MddError::from_csv(e, None))?;
+        let mut count = 0;
+        for record in reader.records() {
+            record.map_err(|e| MddError::from_csv(e, None))?;
+            count += 1;
+        }
+        Ok(entry_name(&format!(
+            "{}{}_{}species.csv",
+            MDD_FILE_PREFIX, self.version, count
+        )))
+    }
+
+    /// Entry name of the synonym CSV, e.g. `MDD/Species_Syn_v2.2.csv`.
+    pub fn synonym_entry_name(&self) -> String {
+        entry_name(&format!("{}{}.csv", SYNONYM_FILE_PREFIX, self.version))
+    }
+
+    /// Write `archive_name()` into `output_dir` and return its path.
+    pub fn write_to_dir(&self, output_dir: &Path) -> Result<PathBuf, MddError> {
+        fs::create_dir_all(output_dir)?;
+        let output_path = output_dir.join(self.archive_name());
+        let file = fs::File::create(&output_path)?;
+        self.write_archive(file)?;
+        Ok(output_path)
+    }
+
+    /// Write the zipped release to `writer` and return the packaged TOML.
+    pub fn write_archive<W: Write + Seek>(&self, writer: W) -> Result<ReleaseToml, MddError> {
+        let species_entry = self.species_entry_name()?;
+        let synonym_entry = self.synonym_entry_name();
+        self.check_entry_names(&species_entry, &synonym_entry)?;
+        let extra_entries = self.extra_entries()?;
+
+        let species_path = self.toml.metadata.mdd_path(&self.base_dir);
+        let synonym_path = self.toml.metadata.synonym_path(&self.base_dir);
+        let mut packaged = self.toml.clone();
+        packaged.metadata.mdd_file = base_name(&species_entry).to_string();
+        packaged.metadata.synonym_file = base_name(&synonym_entry).to_string();
+
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let mut zip = zip::ZipWriter::new(writer);
+        let species = fs::read(&species_path)?;
+        packaged.metadata.mdd_sha256 = Some(sha256_hex(&species));
+        zip.start_file(&species_entry, options)?;
+        zip.write_all(&species)?;
+        let synonyms = fs::read(&synonym_path)?;
+        packaged.metadata.synonym_sha256 = Some(sha256_hex(&synonyms));
+        zip.start_file(&synonym_entry, options)?;
+        zip.write_all(&synonyms)?;
+        zip.start_file(entry_name(RELEASE_TOML_FILE), options)?;
+        zip.write_all(packaged.try_to_toml()?.as_bytes())?;
+        for (path, entry) in self.extra_files.iter().zip(&extra_entries) {
+            zip.start_file(entry, options)?;
+            zip.write_all(&fs::read(path)?)?;
+        }
+        zip.finish()?;
+        Ok(packaged)
+    }
+
+    /// Guard against the release names drifting from what readers expect.
+    fn check_entry_names(&self, species_entry: &str, synonym_entry: &str) -> Result<(), MddError> {
+        let species_name = base_name(species_entry);
+        if !is_species_file_name(species_name)
+            || release_version_from_file_name(species_name) != Some(self.version)
+        {
+            return Err(MddError::InvalidPackage(format!(
+                "{} would not be read as the v{} species file",
+                species_name, self.version
+            )));
+        }
+        if !is_synonym_file_name(base_name(synonym_entry)) {
+            return Err(MddError::InvalidPackage(format!(
+                "{} would not be read as the synonym file",
+                synonym_entry
+            )));
+        }
+        Ok(())
+    }
+
+    /// Entry names of the extra files, rejecting names a reader would take
+    /// for a release entry and duplicates.
+    fn extra_entries(&self) -> Result<Vec<String>, MddError> {
+        let mut seen = HashSet::new();
+        self.extra_files
+            .iter()
+            .map(|path| {
+                let name = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .ok_or_else(|| {
+                        MddError::InvalidPackage(format!("{:?} has no file name", path))
+                    })?;
+                if is_species_file_name(name)
+                    || is_synonym_file_name(name)
+                    || name == RELEASE_TOML_FILE
+                {
+                    return Err(MddError::InvalidPackage(format!(
+                        "{} would be read as a release file",
+                        name
+                    )));
+                }
+                if !seen.insert(name) {
+                    return Err(MddError::InvalidPackage(format!(
+                        "{} is packaged twice",
+                        name
+                    )));
+                }
+                Ok(entry_name(name))
+            })
+            .collect()
+    }
+}
+
+/// Path of `file_name` inside the release folder.
+fn entry_name(file_name: &str) -> String {
+    format!("{}/{}", RELEASE_DIR, file_name)
+}
+
+fn base_name(entry: &str) -> &str {
+    entry.rsplit('/').next().unwrap_or(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::helper::checksum::file_sha256;
+    use crate::parser::{archive::read_zip_metadata, metadata::ReleaseMetadata, ReleasedMddData};
+
+    fn test_package() -> ReleasePackage {
+        let toml = ReleaseToml {
+            metadata: ReleaseMetadata {
+                name: "MDD".to_string(),
+                version: "2.2.1".to_string(),
+                release_date: "2024-06-01".to_string(),
+                mdd_file: "test_data.csv".to_string(),
+                synonym_file: "syndata.csv".to_string(),
+                ..Default::default()
+            },
+        };
+        ReleasePackage::new(toml, Path::new("tests/data")).unwrap()
+    }
+
+    #[test]
+    fn test_write_archive() {
+        let package = test_package();
+        assert_eq!(package.archive_name(), "MDD_v2.2.1.zip");
+        let mut buffer = Cursor::new(Vec::new());
+        let packaged = package.write_archive(&mut buffer).unwrap();
+        assert_eq!(packaged.metadata.mdd_file, "MDD_v2.2.1_112species.csv");
+        assert_eq!(
+            packaged.metadata.mdd_sha256,
+            Some(file_sha256(Path::new("tests/data/test_data.csv")).unwrap())
+        );
+
+        // The consuming side finds every entry of the package.
+        let meta = read_zip_metadata(Cursor::new(buffer.get_ref().clone())).unwrap();
+        assert_eq!(meta.version, "2.2.1");
+        assert_eq!(meta.mdd_file, "MDD/MDD_v2.2.1_112species.csv");
+        assert_eq!(meta.synonym_file, "MDD/Species_Syn_v2.2.1.csv");
+        let release = ReleasedMddData::from_zip_reader(buffer).unwrap();
+        assert_eq!(release.data.len(), 112);
+    }
+
+    #[test]
+    fn test_invalid_package() {
+        let mut toml = test_package().toml;
+        toml.metadata.version = "2024".to_string();
+        let result = ReleasePackage::new(toml, Path::new("tests/data"));
+        assert!(matches!(result, Err(MddError::InvalidPackage(_))));
+
+        let package = test_package().with_file(Path::new("tests/data/release.toml"));
+        let result = package.write_archive(Cursor::new(Vec::new()));
+        assert!(matches!(result, Err(MddError::InvalidPackage(_))));
+    }
+}
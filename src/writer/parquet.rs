@@ -0,0 +1,418 @@
+//! Apache Parquet export (enabled with the `parquet` feature).
+//!
+//! Species (`MddData`) and synonyms (`SynonymData`) are written to separate
+//! files with one column per serde field, named exactly like the JSON keys
+//! (`sciName`, `MDD_syn_ID`, ...). Column types follow the Rust field types:
+//! * text fields – required `BYTE_ARRAY` annotated as UTF-8 strings;
+//! * numeric fields – required `INT64`;
+//! * optional fields (`speciesId`, `basionymSynonymId`) – optional `INT64` or
+//!   UTF-8 string, typed by the first record that sets them (`INT64` when no
+//!   record does), null when missing.
+//!
+//! The schema is derived from the record layout, so it only changes when the
+//! parser's fields change, and pandas, Polars, or DuckDB can read the files
+//! without any JSON flattening.
+
+use std::{
+    error::Error,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use parquet::{
+    basic::{Compression, LogicalType, Repetition, Type as PhysicalType},
+    data_type::{ByteArray, ByteArrayType, Int64Type},
+    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    schema::types::Type,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::parser::{mdd::MddData, synonyms::SynonymData, ReleasedMddData};
+
+/// File extension used for Parquet output.
+pub const PARQUET_EXTENSION: &str = "parquet";
+/// Default file name (without extension) of the species table.
+pub const SPECIES_FILE: &str = "species";
+/// Default file name (without extension) of the synonym table.
+pub const SYNONYM_FILE: &str = "synonyms";
+
+/// Maximum number of rows per row group.
+const ROW_GROUP_SIZE: usize = 8192;
+
+/// Parquet column type derived from a serde field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Text,
+    Integer,
+    OptionalText,
+    OptionalInteger,
+}
+
+/// A column of the Parquet schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParquetColumn {
+    /// Serde (JSON) field name.
+    pub name: String,
+    pub kind: ColumnKind,
+}
+
+/// Columns of the species table of `records`, in file order.
+pub fn species_columns(records: &[MddData]) -> Result<Vec<ParquetColumn>, serde_json::Error> {
+    columns_of(&MddData::new(), records)
+}
+
+/// Columns of the synonym table of `records`, in file order.
+pub fn synonym_columns(records: &[SynonymData]) -> Result<Vec<ParquetColumn>, serde_json::Error> {
+    columns_of(&SynonymData::new(), records)
+}
+
+/// Write species records as a Parquet file to `writer`.
+pub fn write_species<W: Write + Send>(
+    writer: W,
+    records: &[MddData],
+) -> Result<(), Box<dyn Error>> {
+    write_records(writer, "mdd_species", &species_columns(records)?, records)
+}
+
+/// Write synonym records as a Parquet file to `writer`.
+pub fn write_synonyms<W: Write + Send>(
+    writer: W,
+    records: &[SynonymData],
+) -> Result<(), Box<dyn Error>> {
+    write_records(writer, "mdd_synonyms", &synonym_columns(records)?, records)
+}
+
+/// Write `species.parquet` and `synonyms.parquet` for a release into `output_dir`.
+///
+/// The synonym table contains both the synonyms attached to species and the
+/// synonym-only names. Returns the paths of the written files.
+pub fn write_release_to_dir(
+    release: &ReleasedMddData,
+    output_dir: &Path,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    fs::create_dir_all(output_dir)?;
+    let species: Vec<MddData> = release.species().cloned().collect();
+    let synonyms: Vec<SynonymData> = release
+        .data
+        .iter()
+        .flat_map(|entry| entry.synonyms().iter().cloned())
+        .chain(release.synonym_only.iter().cloned())
+        .collect();
+
+    let species_path = output_dir
+        .join(SPECIES_FILE)
+        .with_extension(PARQUET_EXTENSION);
+    write_species(fs::File::create(&species_path)?, &species)?;
+    let synonym_path = output_dir
+        .join(SYNONYM_FILE)
+        .with_extension(PARQUET_EXTENSION);
+    write_synonyms(fs::File::create(&synonym_path)?, &synonyms)?;
+    Ok(vec![species_path, synonym_path])
+}
+
+fn to_field_map<T: Serialize>(record: &T) -> Result<Map<String, Value>, serde_json::Error> {
+    match serde_json::to_value(record)? {
+        Value::Object(map) => Ok(map),
+        _ => unreachable!("records serialize to JSON objects"),
+    }
+}
+
+/// Columns named and ordered like the fields of `template`. Optional fields
+/// serialize as null in the template, so they take the type of the first
+/// record setting them.
+fn columns_of<T: Serialize>(
+    template: &T,
+    records: &[T],
+) -> Result<Vec<ParquetColumn>, serde_json::Error> {
+    let template = to_field_map(template)?;
+    let mut optional: Map<String, Value> = template
+        .iter()
+        .filter(|(_, value)| value.is_null())
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    for record in records {
+        if optional.values().all(|value| !value.is_null()) {
+            break;
+        }
+        for (name, value) in to_field_map(record)? {
+            if let Some(field) = optional.get_mut(&name) {
+                if field.is_null() {
+                    *field = value;
+                }
+            }
+        }
+    }
+    let columns = template
+        .into_iter()
+        .map(|(name, value)| {
+            let kind = match optional.get(&name) {
+                Some(Value::String(_)) => ColumnKind::OptionalText,
+                Some(_) => ColumnKind::OptionalInteger,
+                None if value.is_string() => ColumnKind::Text,
+                None => ColumnKind::Integer,
+            };
+            ParquetColumn { name, kind }
+        })
+        .collect();
+    Ok(columns)
+}
+
+fn build_schema(name: &str, columns: &[ParquetColumn]) -> Result<Arc<Type>, Box<dyn Error>> {
+    let fields = columns
+        .iter()
+        .map(|column| {
+            let builder = match column.kind {
+                ColumnKind::Text => {
+                    Type::primitive_type_builder(&column.name, PhysicalType::BYTE_ARRAY)
+                        .with_logical_type(Some(LogicalType::String))
+                        .with_repetition(Repetition::REQUIRED)
+                }
+                ColumnKind::Integer => {
+                    Type::primitive_type_builder(&column.name, PhysicalType::INT64)
+                        .with_repetition(Repetition::REQUIRED)
+                }
+                ColumnKind::OptionalText => {
+                    Type::primitive_type_builder(&column.name, PhysicalType::BYTE_ARRAY)
+                        .with_logical_type(Some(LogicalType::String))
+                        .with_repetition(Repetition::OPTIONAL)
+                }
+                ColumnKind::OptionalInteger => {
+                    Type::primitive_type_builder(&column.name, PhysicalType::INT64)
+                        .with_repetition(Repetition::OPTIONAL)
+                }
+            };
+            builder.build().map(Arc::new)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let schema = Type::group_type_builder(name).with_fields(fields).build()?;
+    Ok(Arc::new(schema))
+}
+
+fn write_records<W, T>(
+    writer: W,
+    name: &str,
+    columns: &[ParquetColumn],
+    records: &[T],
+) -> Result<(), Box<dyn Error>>
+where
+    W: Write + Send,
+    T: Serialize,
+{
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_created_by(format!("mdd_api {}", env!("CARGO_PKG_VERSION")))
+        .build();
+    let mut file_writer =
+        SerializedFileWriter::new(writer, build_schema(name, columns)?, Arc::new(properties))?;
+
+    for chunk in records.chunks(ROW_GROUP_SIZE) {
+        let rows = chunk
+            .iter()
+            .map(to_field_map)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut row_group = file_writer.next_row_group()?;
+        for column in columns {
+            let mut column_writer = row_group
+                .next_column()?
+                .ok_or_else(|| format!("Missing Parquet column writer for {}", column.name))?;
+            let values = rows.iter().map(|row| row.get(&column.name));
+            match column.kind {
+                ColumnKind::Text => {
+                    let values = values
+                        .map(|value| match value {
+                            Some(Value::String(s)) => Ok(ByteArray::from(s.as_str())),
+                            other => Err(type_error(&column.name, other)),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    column_writer
+                        .typed::<ByteArrayType>()
+                        .write_batch(&values, None, None)?;
+                }
+                ColumnKind::Integer => {
+                    let values = values
+                        .map(|value| {
+                            value
+                                .and_then(Value::as_i64)
+                                .ok_or_else(|| type_error(&column.name, value))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    column_writer
+                        .typed::<Int64Type>()
+                        .write_batch(&values, None, None)?;
+                }
+                ColumnKind::OptionalText => {
+                    let mut present = Vec::with_capacity(rows.len());
+                    let mut def_levels = Vec::with_capacity(rows.len());
+                    for value in values {
+                        match value {
+                            Some(Value::Null) | None => def_levels.push(0),
+                            Some(Value::String(s)) => {
+                                present.push(ByteArray::from(s.as_str()));
+                                def_levels.push(1);
+                            }
+                            other => return Err(type_error(&column.name, other)),
+                        }
+                    }
+                    column_writer.typed::<ByteArrayType>().write_batch(
+                        &present,
+                        Some(&def_levels),
+                        None,
+                    )?;
+                }
+                ColumnKind::OptionalInteger => {
+                    let mut present = Vec::with_capacity(rows.len());
+                    let mut def_levels = Vec::with_capacity(rows.len());
+                    for value in values {
+                        match value {
+                            Some(Value::Null) | None => def_levels.push(0),
+                            Some(v) => {
+                                let v =
+                                    v.as_i64().ok_or_else(|| type_error(&column.name, value))?;
+                                present.push(v);
+                                def_levels.push(1);
+                            }
+                        }
+                    }
+                    column_writer.typed::<Int64Type>().write_batch(
+                        &present,
+                        Some(&def_levels),
+                        None,
+                    )?;
+                }
+            }
+            column_writer.close()?;
+        }
+        row_group.close()?;
+    }
+    file_writer.close()?;
+    Ok(())
+}
+
+fn type_error(column: &str, value: Option<&Value>) -> Box<dyn Error> {
+    format!(
+        "Unexpected value for Parquet column {}: {:?}",
+        column, value
+    )
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use parquet::{
+        file::reader::{FileReader, SerializedFileReader},
+        record::RowAccessor,
+    };
+    use tempdir::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_write_release_parquet() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let syn_csv = std::fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let mut synonyms = SynonymData::new().from_csv(&syn_csv);
+        synonyms[0].species_id = Some(1001076);
+        synonyms[1].species_id = None;
+        let release = ReleasedMddData::from_parser(species, synonyms, "2.0", "2025-01-01");
+
+        let output_dir = TempDir::new("parquet").unwrap();
+        let paths = write_release_to_dir(&release, output_dir.path()).unwrap();
+
+        let reader = SerializedFileReader::new(fs::File::open(&paths[0]).unwrap()).unwrap();
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), 112);
+        let schema = metadata.schema_descr();
+        assert_eq!(
+            schema.num_columns(),
+            species_columns(&release.species().cloned().collect::<Vec<_>>())
+                .unwrap()
+                .len()
+        );
+        assert_eq!(schema.column(0).name(), "id");
+        let first = reader.get_row_iter(None).unwrap().next().unwrap().unwrap();
+        assert_eq!(first.get_long(0).unwrap(), 1001076);
+
+        let reader = SerializedFileReader::new(fs::File::open(&paths[1]).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        let species_id = synonym_columns(&[])
+            .unwrap()
+            .iter()
+            .position(|c| c.kind == ColumnKind::OptionalInteger)
+            .unwrap();
+        let rows: Vec<_> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| row.unwrap().get_long(species_id).ok())
+            .collect();
+        assert!(rows.contains(&Some(1001076)));
+        assert!(rows.contains(&None));
+    }
+
+    #[derive(Serialize)]
+    struct Optional {
+        id: u32,
+        note: Option<String>,
+        count: Option<u32>,
+        unset: Option<String>,
+    }
+
+    #[test]
+    fn test_optional_columns_follow_first_value() {
+        let template = Optional {
+            id: 0,
+            note: None,
+            count: None,
+            unset: None,
+        };
+        let records = [
+            Optional {
+                id: 1,
+                note: None,
+                count: Some(3),
+                unset: None,
+            },
+            Optional {
+                id: 2,
+                note: Some("holotype".to_string()),
+                count: None,
+                unset: None,
+            },
+        ];
+        let kinds: Vec<ColumnKind> = columns_of(&template, &records)
+            .unwrap()
+            .into_iter()
+            .map(|column| column.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ColumnKind::Integer,
+                ColumnKind::OptionalText,
+                ColumnKind::OptionalInteger,
+                ColumnKind::OptionalInteger,
+            ]
+        );
+
+        let output_dir = TempDir::new("parquet").unwrap();
+        let path = output_dir.path().join("optional.parquet");
+        let columns = columns_of(&template, &records).unwrap();
+        write_records(
+            fs::File::create(&path).unwrap(),
+            "optional",
+            &columns,
+            &records,
+        )
+        .unwrap();
+        let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+        let notes: Vec<Option<String>> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| row.unwrap().get_string(1).ok().cloned())
+            .collect();
+        assert_eq!(notes, [None, Some("holotype".to_string())]);
+    }
+}
@@ -0,0 +1,182 @@
+//! One JSON file per species for static-site hosting.
+//!
+//! `PerSpeciesWriter` splits a release into small files that a static web
+//! frontend can lazy-load:
+//!
+//! ```text
+//! <output_dir>/
+//! ├── index.json          # release metadata + one summary row per species
+//! └── species/
+//!     ├── 1001076.json    # SimpleMDD entry (species record + synonyms)
+//!     └── ...
+//! ```
+//!
+//! Files are named by MDD id by default, or by the species slug
+//! (`bunolagus-monticularis-1903.json`, see `helper::slug`) with
+//! `SpeciesFileNaming::Slug`. The index
+//! lists the relative path of every file so clients never guess names.
+
+use std::{
+    collections::HashSet,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{
+    helper::slug::species_slug,
+    parser::{MetaData, ReleasedMddData, SimpleMDD},
+};
+
+/// Name of the manifest written at the root of the output directory.
+pub const INDEX_FILE: &str = "index.json";
+/// Directory (relative to the output directory) holding the species files.
+pub const SPECIES_DIR: &str = "species";
+
+const JSON_EXTENSION: &str = "json";
+
+/// How species files are named.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpeciesFileNaming {
+    /// `<mdd id>.json`
+    #[default]
+    Id,
+    /// Species slug, e.g. `panthera-leo-1758.json`.
+    Slug,
+}
+
+/// Summary row of a species in the index manifest.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeciesIndexEntry {
+    pub mdd_id: u32,
+    pub sci_name: String,
+    pub main_common_name: String,
+    pub taxon_order: String,
+    pub family: String,
+    pub extinct: u8,
+    /// Path of the species file relative to the output directory.
+    pub file: String,
+}
+
+/// Manifest written to `index.json`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeciesIndex<'a> {
+    pub metadata: &'a MetaData,
+    pub species: Vec<SpeciesIndexEntry>,
+}
+
+/// Writes a release as one JSON file per species plus an index manifest.
+pub struct PerSpeciesWriter<'a> {
+    pub output_dir: &'a Path,
+    pub naming: SpeciesFileNaming,
+}
+
+impl<'a> PerSpeciesWriter<'a> {
+    pub fn new(output_dir: &'a Path, naming: SpeciesFileNaming) -> Self {
+        Self { output_dir, naming }
+    }
+
+    /// Write every species file and the index, returning the index path.
+    pub fn write(&self, release: &ReleasedMddData) -> Result<PathBuf, Box<dyn Error>> {
+        let species_dir = self.output_dir.join(SPECIES_DIR);
+        fs::create_dir_all(&species_dir)?;
+        let mut used = HashSet::new();
+        let mut entries = Vec::with_capacity(release.data.len());
+        for entry in &release.data {
+            let file_name = self.file_name(entry, &mut used);
+            fs::write(species_dir.join(&file_name), serde_json::to_string(entry)?)?;
+            let species = entry.species();
+            entries.push(SpeciesIndexEntry {
+                mdd_id: entry.mdd_id(),
+                sci_name: species.sci_name.clone(),
+                main_common_name: species.main_common_name.clone(),
+                taxon_order: species.taxon_order.clone(),
+                family: species.family.clone(),
+                extinct: species.extinct,
+                file: format!("{}/{}", SPECIES_DIR, file_name),
+            });
+        }
+        let index = SpeciesIndex {
+            metadata: &release.metadata,
+            species: entries,
+        };
+        let index_path = self.output_dir.join(INDEX_FILE);
+        fs::write(&index_path, serde_json::to_string(&index)?)?;
+        Ok(index_path)
+    }
+
+    /// File name of a species entry.
+    fn file_name(&self, entry: &SimpleMDD, used: &mut HashSet<String>) -> String {
+        format!("{}.{}", file_stem(self.naming, entry, used), JSON_EXTENSION)
+    }
+}
+
+/// File stem of a species entry under `naming`. Records without an assigned
+/// slug fall back to their base slug, with the MDD id appended on collisions.
+pub(crate) fn file_stem(
+    naming: SpeciesFileNaming,
+    entry: &SimpleMDD,
+    used: &mut HashSet<String>,
+) -> String {
+    let stem = match naming {
+        SpeciesFileNaming::Id => entry.mdd_id().to_string(),
+        SpeciesFileNaming::Slug => {
+            let species = entry.species();
+            let slug = if species.slug.is_empty() {
+                species_slug(species)
+            } else {
+                species.slug.clone()
+            };
+            if used.contains(&slug) || slug.is_empty() {
+                format!("{}-{}", slug, entry.mdd_id())
+            } else {
+                slug
+            }
+        }
+    };
+    used.insert(stem.clone());
+    stem
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::parser::{mdd::MddData, synonyms::SynonymData};
+
+    #[test]
+    fn test_write_per_species() {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let release =
+            ReleasedMddData::from_parser(species, Vec::<SynonymData>::new(), "2.0", "2025-01-01");
+        let output_dir = TempDir::new("per_species").unwrap();
+
+        let writer = PerSpeciesWriter::new(output_dir.path(), SpeciesFileNaming::Slug);
+        let index_path = writer.write(&release).unwrap();
+        let index: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(index_path).unwrap()).unwrap();
+        let rows = index["species"].as_array().unwrap();
+        assert_eq!(rows.len(), 112);
+        assert_eq!(rows[0]["file"], "species/bunolagus-monticularis-1903.json");
+        let entry: SimpleMDD = serde_json::from_str(
+            &fs::read_to_string(
+                output_dir
+                    .path()
+                    .join("species/bunolagus-monticularis-1903.json"),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(entry.mdd_id(), 1001076);
+
+        let writer = PerSpeciesWriter::new(output_dir.path(), SpeciesFileNaming::Id);
+        writer.write(&release).unwrap();
+        assert!(output_dir.path().join("species/1001076.json").exists());
+    }
+}
@@ -0,0 +1,261 @@
+//! Newick and phyloXML export of the taxonomic hierarchy.
+//!
+//! `PhyloWriter` writes a `TaxonomyTree` as a rank-only constraint tree:
+//! every subclass, order, family, and genus becomes a clade and every
+//! species a tip, below a single `Mammalia` root. The trees carry no branch
+//! lengths; they are scaffolds for comparative analyses, not phylogenies.
+//!
+//! Clades keep MDD phylogenetic order (`phylosort`) unless
+//! `with_order(ChildOrder::Alphabetical)` is set. Newick tips use the MDD
+//! `sciName` (`Panthera_leo`, read as `Panthera leo` by Newick parsers);
+//! phyloXML clades carry the rank and, on species, the MDD id.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::parser::taxonomy::{TaxonNode, TaxonRank, TaxonomyTree};
+
+use super::xml_escape;
+
+/// File extension used for Newick output.
+pub const NEWICK_EXTENSION: &str = "nwk";
+/// File extension used for phyloXML output.
+pub const PHYLOXML_EXTENSION: &str = "xml";
+
+/// Name of the root clade joining the subclasses.
+const ROOT_NAME: &str = "Mammalia";
+/// phyloXML rank of the root clade.
+const ROOT_RANK: &str = "class";
+/// phyloXML `id` provider of MDD species ids.
+const MDD_PROVIDER: &str = "mdd";
+/// Characters that need a quoted Newick label.
+const NEWICK_SPECIAL: &[char] = &['(', ')', '[', ']', '\'', ':', ';', ',', ' ', '\t'];
+
+/// Order of the children of each clade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChildOrder {
+    /// MDD phylogenetic order, as built by `TaxonomyTree::from_mdd`.
+    #[default]
+    Phylosort,
+    /// Alphabetical by name at every rank.
+    Alphabetical,
+}
+
+/// Writes a `TaxonomyTree` as Newick or phyloXML.
+pub struct PhyloWriter<'a> {
+    tree: &'a TaxonomyTree,
+    order: ChildOrder,
+    internal_labels: bool,
+}
+
+impl<'a> PhyloWriter<'a> {
+    pub fn new(tree: &'a TaxonomyTree) -> Self {
+        Self {
+            tree,
+            order: ChildOrder::default(),
+            internal_labels: true,
+        }
+    }
+
+    /// Order children by phylosort (default) or alphabetically.
+    pub fn with_order(mut self, order: ChildOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Label Newick clades with their taxon name (default `true`); some
+    /// tools only accept labels on tips.
+    pub fn with_internal_labels(mut self, internal_labels: bool) -> Self {
+        self.internal_labels = internal_labels;
+        self
+    }
+
+    /// The tree in Newick format, ending with `;`.
+    pub fn to_newick(&self) -> String {
+        let mut newick = String::new();
+        newick.push('(');
+        for (i, root) in self.children(&self.tree.roots).into_iter().enumerate() {
+            if i > 0 {
+                newick.push(',');
+            }
+            self.push_newick(&mut newick, root);
+        }
+        newick.push(')');
+        if self.internal_labels {
+            newick.push_str(ROOT_NAME);
+        }
+        newick.push_str(";\n");
+        newick
+    }
+
+    /// The tree as a phyloXML document.
+    pub fn to_phyloxml(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <phyloxml xmlns=\"http://www.phyloxml.org\">\n\
+             <phylogeny rooted=\"true\">\n",
+        );
+        xml.push_str(&format!("<name>{}</name>\n", ROOT_NAME));
+        xml.push_str(&format!(
+            "<clade>\n<name>{0}</name>\n<taxonomy><scientific_name>{0}</scientific_name><rank>{1}</rank></taxonomy>\n",
+            ROOT_NAME, ROOT_RANK
+        ));
+        for root in self.children(&self.tree.roots) {
+            self.push_phyloxml(&mut xml, root);
+        }
+        xml.push_str("</clade>\n</phylogeny>\n</phyloxml>\n");
+        xml
+    }
+
+    /// Write the Newick tree to any `Write` sink.
+    pub fn write_newick<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_newick().as_bytes())
+    }
+
+    /// Write the phyloXML document to any `Write` sink.
+    pub fn write_phyloxml<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_phyloxml().as_bytes())
+    }
+
+    /// Write the Newick tree to `path`.
+    pub fn write_newick_file(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_newick())
+    }
+
+    /// Write the phyloXML document to `path`.
+    pub fn write_phyloxml_file(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_phyloxml())
+    }
+
+    fn children<'n>(&self, nodes: &'n [TaxonNode]) -> Vec<&'n TaxonNode> {
+        let mut children: Vec<&TaxonNode> = nodes.iter().collect();
+        if self.order == ChildOrder::Alphabetical {
+            children.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        children
+    }
+
+    fn push_newick(&self, newick: &mut String, node: &TaxonNode) {
+        if node.children.is_empty() {
+            newick.push_str(&newick_label(&node.name));
+            return;
+        }
+        newick.push('(');
+        for (i, child) in self.children(&node.children).into_iter().enumerate() {
+            if i > 0 {
+                newick.push(',');
+            }
+            self.push_newick(newick, child);
+        }
+        newick.push(')');
+        if self.internal_labels {
+            newick.push_str(&newick_label(&node.name));
+        }
+    }
+
+    fn push_phyloxml(&self, xml: &mut String, node: &TaxonNode) {
+        let name = if node.rank == TaxonRank::Species {
+            node.name.replace('_', " ")
+        } else {
+            node.name.clone()
+        };
+        let name = xml_escape(&name);
+        xml.push_str(&format!("<clade>\n<name>{}</name>\n<taxonomy>", name));
+        if let Some(id) = node.id {
+            xml.push_str(&format!("<id provider=\"{}\">{}</id>", MDD_PROVIDER, id));
+        }
+        xml.push_str(&format!(
+            "<scientific_name>{}</scientific_name><rank>{}</rank></taxonomy>\n",
+            name, node.rank
+        ));
+        for child in self.children(&node.children) {
+            self.push_phyloxml(xml, child);
+        }
+        xml.push_str("</clade>\n");
+    }
+}
+
+/// A Newick label, quoted (with `'` doubled) when it has special characters.
+fn newick_label(name: &str) -> String {
+    if name.contains(NEWICK_SPECIAL) {
+        format!("'{}'", name.replace('\'', "''"))
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::mdd::MddData;
+
+    fn tree() -> TaxonomyTree {
+        let csv_data = std::fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let data = MddData::new().from_csv(&csv_data);
+        TaxonomyTree::from_mdd(&data)
+    }
+
+    #[test]
+    fn test_newick() {
+        let tree = tree();
+        let newick = PhyloWriter::new(&tree).to_newick();
+        assert!(newick.ends_with(")Mammalia;\n"));
+        assert_eq!(newick.matches('(').count(), newick.matches(')').count());
+        assert!(newick.contains("(Bunolagus_monticularis)Bunolagus"));
+
+        let tips = PhyloWriter::new(&tree)
+            .with_internal_labels(false)
+            .to_newick();
+        assert!(!tips.contains("Leporidae"));
+        assert!(tips.contains("(Bunolagus_monticularis)"));
+
+        assert_eq!(newick_label("Panthera leo"), "'Panthera leo'");
+        assert_eq!(newick_label("O'Brien"), "'O''Brien'");
+    }
+
+    #[test]
+    fn test_child_order() {
+        let record = |id, phylosort, genus: &str, sci_name: &str| MddData {
+            id,
+            phylosort,
+            subclass: "Theria".to_string(),
+            taxon_order: "Carnivora".to_string(),
+            family: "Felidae".to_string(),
+            genus: genus.to_string(),
+            sci_name: sci_name.to_string(),
+            ..MddData::new()
+        };
+        let tree = TaxonomyTree::from_mdd(&[
+            record(3, 2, "Felis", "Felis_silvestris"),
+            record(1, 1, "Panthera", "Panthera_tigris"),
+            record(2, 1, "Panthera", "Panthera_leo"),
+        ]);
+        let writer = PhyloWriter::new(&tree).with_internal_labels(false);
+        assert_eq!(
+            writer.to_newick(),
+            "(((((Panthera_tigris,Panthera_leo),(Felis_silvestris)))));\n"
+        );
+        assert_eq!(
+            writer.with_order(ChildOrder::Alphabetical).to_newick(),
+            "(((((Felis_silvestris),(Panthera_leo,Panthera_tigris)))));\n"
+        );
+    }
+
+    #[test]
+    fn test_phyloxml() {
+        let tree = tree();
+        let xml = PhyloWriter::new(&tree).to_phyloxml();
+        assert!(xml.starts_with("<?xml"));
+        assert_eq!(
+            xml.matches("<clade>").count(),
+            xml.matches("</clade>").count()
+        );
+        assert_eq!(xml.matches("<rank>species</rank>").count(), 112);
+        assert!(xml.contains(
+            "<id provider=\"mdd\">1001076</id><scientific_name>Bunolagus monticularis</scientific_name>"
+        ));
+    }
+}
@@ -0,0 +1,191 @@
+//! Provenance of generated artifacts.
+//!
+//! Only the release bundle carries the MDD version and date in its own
+//! content. `ProvenanceHeader` records which release produced an output, when,
+//! and with which crate version:
+//! * the SQLite export embeds it in `release_metadata` (`generated_at`,
+//!   `generator`, `crate_version`, next to the release keys);
+//! * file outputs whose layout cannot change (statistics JSON, region codes,
+//!   shards, species pages, crosswalk and common name tables, ...) are listed
+//!   in a sidecar `manifest.json` with their sizes and SHA-256 hashes, written
+//!   by `Manifest`, so deployments can verify the files and bust caches when a
+//!   hash changes.
+
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{helper::checksum::file_sha256, parser::ReleasedMddData};
+
+/// Name of the sidecar manifest written to an output directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+/// Name recorded as the generator of every artifact.
+pub const GENERATOR: &str = "mdd_api";
+
+/// Release and build information attached to generated artifacts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvenanceHeader {
+    /// MDD release version.
+    pub version: String,
+    /// MDD release date.
+    pub release_date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    /// Generation time, RFC 3339 in UTC.
+    pub generated_at: String,
+    pub generator: String,
+    /// Version of this crate that produced the artifact.
+    pub crate_version: String,
+}
+
+impl ProvenanceHeader {
+    /// Header for `version` / `release_date`, generated now.
+    pub fn new(version: &str, release_date: &str) -> Self {
+        Self {
+            version: version.to_string(),
+            release_date: release_date.to_string(),
+            doi: None,
+            generated_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            generator: GENERATOR.to_string(),
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Header for the release of `release`, including its DOI.
+    pub fn from_release(release: &ReleasedMddData) -> Self {
+        let header = Self::new(release.get_version(), release.get_release_date());
+        match release.get_doi() {
+            Some(doi) => header.with_doi(doi),
+            None => header,
+        }
+    }
+
+    pub fn with_doi(mut self, doi: &str) -> Self {
+        self.doi = Some(doi.to_string());
+        self
+    }
+
+    /// Override the generation time, e.g. for reproducible builds.
+    pub fn with_generated_at(mut self, generated_at: &str) -> Self {
+        self.generated_at = generated_at.to_string();
+        self
+    }
+}
+
+/// A generated file listed in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestFile {
+    /// Path relative to the output directory, `/`-separated.
+    pub path: String,
+    pub bytes: u64,
+    /// SHA-256 digest of the file, as hex.
+    pub sha256: String,
+}
+
+/// Sidecar `manifest.json` tying the files of an output directory to their release.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub provenance: ProvenanceHeader,
+    pub files: Vec<ManifestFile>,
+}
+
+impl Manifest {
+    pub fn new(provenance: ProvenanceHeader) -> Self {
+        Self {
+            provenance,
+            files: Vec::new(),
+        }
+    }
+
+    /// Add the file at `path`, recorded relative to `output_dir` when inside it.
+    pub fn add_file(&mut self, output_dir: &Path, path: &Path) -> std::io::Result<()> {
+        let bytes = fs::metadata(path)?.len();
+        let sha256 = file_sha256(path)?;
+        let relative = path.strip_prefix(output_dir).unwrap_or(path);
+        let path = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        self.files.push(ManifestFile {
+            path,
+            bytes,
+            sha256,
+        });
+        Ok(())
+    }
+
+    /// Add every file under `dir`, recursively and in file name order.
+    pub fn add_dir(&mut self, output_dir: &Path, dir: &Path) -> std::io::Result<()> {
+        let mut entries = fs::read_dir(dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() {
+                self.add_dir(output_dir, &entry)?;
+            } else {
+                self.add_file(output_dir, &entry)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the manifest to `<output_dir>/manifest.json`.
+    pub fn write(&self, output_dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        fs::create_dir_all(output_dir)?;
+        let output_path = output_dir.join(MANIFEST_FILE);
+        fs::write(&output_path, serde_json::to_string_pretty(self)?)?;
+        Ok(output_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_write_manifest() {
+        let output_dir = TempDir::new("manifest").unwrap();
+        let stats = output_dir.path().join("country_stats.json");
+        fs::write(&stats, "{}").unwrap();
+        let shard_dir = output_dir.path().join("shards");
+        fs::create_dir_all(&shard_dir).unwrap();
+        fs::write(shard_dir.join("rodentia.json"), "[]").unwrap();
+        fs::write(shard_dir.join("lagomorpha.json"), "[]").unwrap();
+        let provenance = ProvenanceHeader::new("2.0", "2025-01-01")
+            .with_doi("10.5281/zenodo.1")
+            .with_generated_at("2025-01-02T00:00:00Z");
+        assert_eq!(provenance.crate_version, env!("CARGO_PKG_VERSION"));
+        let mut manifest = Manifest::new(provenance);
+        manifest.add_file(output_dir.path(), &stats).unwrap();
+        manifest.add_dir(output_dir.path(), &shard_dir).unwrap();
+        let path = manifest.write(output_dir.path()).unwrap();
+        let written: Manifest = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(written, manifest);
+        assert_eq!(written.files[0].path, "country_stats.json");
+        assert_eq!(written.files[0].bytes, 2);
+        assert_eq!(
+            written.files[0].sha256,
+            "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+        let paths: Vec<_> = written.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "country_stats.json",
+                "shards/lagomorpha.json",
+                "shards/rodentia.json"
+            ]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Combined index of several processed releases.
+//!
+//! `mdd batch` writes every release of a directory into its own `v<version>`
+//! subdirectory. `ReleaseIndex` lists them in one `releases.json` so sites and
+//! scripts can find each version (and the latest one) without scanning the
+//! output tree. Entries are ordered by `ReleaseVersion`, oldest first; versions
+//! that do not parse sort before the others, by name.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::parser::{version::ReleaseVersion, ReleasedMddData};
+
+/// Name of the index written to the batch output directory.
+pub const RELEASE_INDEX_FILE: &str = "releases.json";
+
+/// One processed release.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseIndexEntry {
+    pub version: String,
+    pub release_date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    pub total_species: usize,
+    pub total_synonym_only: usize,
+    /// File name of the release zip or TOML the entry was parsed from.
+    pub source: String,
+    /// Output directory of the release, relative to the index, `/`-separated.
+    pub path: String,
+}
+
+impl ReleaseIndexEntry {
+    /// Entry for `release`, parsed from `source` and written to `path`.
+    pub fn from_release(release: &ReleasedMddData, source: &str, path: &str) -> Self {
+        Self {
+            version: release.get_version().to_string(),
+            release_date: release.get_release_date().to_string(),
+            doi: release.get_doi().map(str::to_string),
+            total_species: release.data.len(),
+            total_synonym_only: release.synonym_only.len(),
+            source: source.to_string(),
+            path: path.to_string(),
+        }
+    }
+
+    fn sort_key(&self) -> (Option<ReleaseVersion>, &str) {
+        (self.version.parse().ok(), &self.version)
+    }
+}
+
+/// The `releases.json` index of a batch run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseIndex {
+    /// Newest version in the index.
+    pub latest: Option<String>,
+    pub releases: Vec<ReleaseIndexEntry>,
+}
+
+impl ReleaseIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an entry, keeping the releases ordered by version.
+    pub fn add(&mut self, entry: ReleaseIndexEntry) {
+        self.releases.push(entry);
+        self.releases
+            .sort_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+        self.latest = self.releases.last().map(|entry| entry.version.clone());
+    }
+
+    /// The entry of `version`, if indexed.
+    pub fn get(&self, version: &str) -> Option<&ReleaseIndexEntry> {
+        self.releases.iter().find(|entry| entry.version == version)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).expect("Failed to serialize")
+    }
+
+    /// Write `releases.json` into `output_dir`, returning its path.
+    pub fn write(&self, output_dir: &Path) -> std::io::Result<PathBuf> {
+        fs::create_dir_all(output_dir)?;
+        let path = output_dir.join(RELEASE_INDEX_FILE);
+        fs::write(&path, self.to_json())?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(version: &str) -> ReleaseIndexEntry {
+        let release = ReleasedMddData::from_parser(Vec::new(), Vec::new(), version, "2025-01-01");
+        ReleaseIndexEntry::from_release(&release, "release.toml", &format!("v{}", version))
+    }
+
+    #[test]
+    fn test_release_index() {
+        let mut index = ReleaseIndex::new();
+        index.add(entry("2.10"));
+        index.add(entry("2.9"));
+        index.add(entry("2.2.1"));
+        let versions: Vec<&str> = index.releases.iter().map(|e| e.version.as_str()).collect();
+        assert_eq!(versions, ["2.2.1", "2.9", "2.10"]);
+        assert_eq!(index.latest.as_deref(), Some("2.10"));
+        assert_eq!(index.get("2.9").unwrap().path, "v2.9");
+
+        let json = index.to_json();
+        assert!(json.starts_with(r#"{"latest":"2.10","releases":[{"version":"2.2.1""#));
+        let parsed: ReleaseIndex = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, index);
+    }
+}
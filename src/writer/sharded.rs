@@ -0,0 +1,243 @@
+//! Release bundle split into one JSON file per order or family.
+//!
+//! `ShardedWriter` is a middle ground between the single `data.json` bundle
+//! and `per_species` output: clients load the index, then only the shards of
+//! the taxa they show.
+//!
+//! ```text
+//! <output_dir>/
+//! ├── shards.json              # release metadata + taxon → shard file
+//! └── shards/
+//!     ├── lagomorpha.json      # { "taxon": "Lagomorpha", "data": [SimpleMDD, ...] }
+//!     ├── ...
+//!     └── synonym-only.json    # synonyms not attached to any species
+//! ```
+//!
+//! Shards are listed in the order their taxon first appears in the release
+//! (MDD phylogenetic order). File names are taxon slugs (see
+//! `helper::slug::slugify`); species with a blank order or family go to
+//! `unassigned.json`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{
+    helper::{slug::slugify, MDD_MISSING_VALUE},
+    parser::{synonyms::SynonymData, MetaData, ReleasedMddData, SimpleMDD},
+};
+
+/// Name of the index written at the root of the output directory.
+pub const SHARD_INDEX_FILE: &str = "shards.json";
+/// Directory (relative to the output directory) holding the shard files.
+pub const SHARD_DIR: &str = "shards";
+/// Stem of the shard holding synonyms without a species.
+pub const SYNONYM_ONLY_SHARD: &str = "synonym-only";
+
+const UNASSIGNED_SHARD: &str = "unassigned";
+const JSON_EXTENSION: &str = "json";
+
+/// Taxonomic rank the bundle is split by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShardKey {
+    #[default]
+    Order,
+    Family,
+}
+
+impl ShardKey {
+    fn taxon<'a>(&self, entry: &'a SimpleMDD) -> &'a str {
+        let species = entry.species();
+        let taxon = match self {
+            Self::Order => species.taxon_order.trim(),
+            Self::Family => species.family.trim(),
+        };
+        if taxon == MDD_MISSING_VALUE {
+            ""
+        } else {
+            taxon
+        }
+    }
+}
+
+/// One shard in the index.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShardIndexEntry {
+    /// Order or family name; empty for the unassigned shard.
+    pub taxon: String,
+    /// Order of the family, for family shards.
+    pub parent: Option<String>,
+    /// Path of the shard relative to the output directory.
+    pub file: String,
+    pub species_count: usize,
+}
+
+/// Index written to `shards.json`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShardIndex<'a> {
+    pub metadata: &'a MetaData,
+    pub split_by: ShardKey,
+    pub shards: Vec<ShardIndexEntry>,
+    /// Path of the synonym-only shard relative to the output directory.
+    pub synonym_only: String,
+}
+
+#[derive(Serialize)]
+struct Shard<'a> {
+    taxon: &'a str,
+    data: Vec<&'a SimpleMDD>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SynonymOnlyShard<'a> {
+    synonym_only: &'a [SynonymData],
+}
+
+/// Writes a release as one JSON file per order or family plus an index.
+pub struct ShardedWriter<'a> {
+    pub output_dir: &'a Path,
+    pub split_by: ShardKey,
+}
+
+impl<'a> ShardedWriter<'a> {
+    pub fn new(output_dir: &'a Path, split_by: ShardKey) -> Self {
+        Self {
+            output_dir,
+            split_by,
+        }
+    }
+
+    /// Write every shard and the index, returning the index path.
+    pub fn write(&self, release: &ReleasedMddData) -> Result<PathBuf, Box<dyn Error>> {
+        let shard_dir = self.output_dir.join(SHARD_DIR);
+        fs::create_dir_all(&shard_dir)?;
+
+        let mut shards: Vec<Shard> = Vec::new();
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        for entry in &release.data {
+            let taxon = self.split_by.taxon(entry);
+            let position = *positions.entry(taxon).or_insert_with(|| {
+                shards.push(Shard {
+                    taxon,
+                    data: Vec::new(),
+                });
+                shards.len() - 1
+            });
+            shards[position].data.push(entry);
+        }
+
+        let mut used = HashSet::from([SYNONYM_ONLY_SHARD.to_string()]);
+        let mut entries = Vec::with_capacity(shards.len());
+        for shard in &shards {
+            let file_name = shard_file_name(shard.taxon, &mut used);
+            fs::write(shard_dir.join(&file_name), serde_json::to_string(shard)?)?;
+            entries.push(ShardIndexEntry {
+                taxon: shard.taxon.to_string(),
+                parent: match self.split_by {
+                    ShardKey::Order => None,
+                    ShardKey::Family => Some(shard.data[0].species().taxon_order.clone()),
+                },
+                file: format!("{}/{}", SHARD_DIR, file_name),
+                species_count: shard.data.len(),
+            });
+        }
+
+        let synonym_file = format!("{}.{}", SYNONYM_ONLY_SHARD, JSON_EXTENSION);
+        let synonym_shard = SynonymOnlyShard {
+            synonym_only: &release.synonym_only,
+        };
+        fs::write(
+            shard_dir.join(&synonym_file),
+            serde_json::to_string(&synonym_shard)?,
+        )?;
+
+        let index = ShardIndex {
+            metadata: &release.metadata,
+            split_by: self.split_by,
+            shards: entries,
+            synonym_only: format!("{}/{}", SHARD_DIR, synonym_file),
+        };
+        let index_path = self.output_dir.join(SHARD_INDEX_FILE);
+        fs::write(&index_path, serde_json::to_string(&index)?)?;
+        Ok(index_path)
+    }
+}
+
+/// Unique file name for a taxon; a repeated slug gets a numeric suffix.
+fn shard_file_name(taxon: &str, used: &mut HashSet<String>) -> String {
+    let slug = slugify(taxon);
+    let base = if slug.is_empty() {
+        UNASSIGNED_SHARD.to_string()
+    } else {
+        slug
+    };
+    let mut stem = base.clone();
+    let mut suffix = 2;
+    while used.contains(&stem) {
+        stem = format!("{}-{}", base, suffix);
+        suffix += 1;
+    }
+    used.insert(stem.clone());
+    format!("{}.{}", stem, JSON_EXTENSION)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::parser::mdd::MddData;
+
+    #[test]
+    fn test_write_sharded() {
+        let csv_data = fs::read_to_string("tests/data/test_data.csv").unwrap();
+        let species = MddData::new().from_csv(&csv_data);
+        let syn_data = fs::read_to_string("tests/data/syndata.csv").unwrap();
+        let synonyms = SynonymData::new().from_csv(&syn_data);
+        let release = ReleasedMddData::from_parser(species, synonyms, "2.0", "2025-01-01");
+        let output_dir = TempDir::new("sharded").unwrap();
+
+        let writer = ShardedWriter::new(output_dir.path(), ShardKey::Family);
+        let index_path = writer.write(&release).unwrap();
+        let index: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(index_path).unwrap()).unwrap();
+        assert_eq!(index["splitBy"], "family");
+        let shards = index["shards"].as_array().unwrap();
+        assert_eq!(shards.len(), 3);
+        assert_eq!(shards[0]["taxon"], "Leporidae");
+        assert_eq!(shards[0]["parent"], "Lagomorpha");
+        assert_eq!(shards[0]["file"], "shards/leporidae.json");
+        let total: u64 = shards
+            .iter()
+            .map(|s| s["speciesCount"].as_u64().unwrap())
+            .sum();
+        assert_eq!(total, 112);
+
+        let shard: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(output_dir.path().join("shards/ochotonidae.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            shard["data"].as_array().unwrap().len() as u64,
+            shards[1]["speciesCount"].as_u64().unwrap()
+        );
+        assert!(output_dir.path().join("shards/synonym-only.json").exists());
+    }
+
+    #[test]
+    fn test_shard_file_name() {
+        let mut used = HashSet::from([SYNONYM_ONLY_SHARD.to_string()]);
+        assert_eq!(shard_file_name("Carnivora", &mut used), "carnivora.json");
+        assert_eq!(shard_file_name("carnivora", &mut used), "carnivora-2.json");
+        assert_eq!(shard_file_name("", &mut used), "unassigned.json");
+    }
+}
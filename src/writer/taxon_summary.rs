@@ -0,0 +1,261 @@
+//! Per-family and per-genus summaries for browse pages.
+//!
+//! `TaxonSummaries` aggregates the species of a release into
+//! `families.json` and `genera.json`, so a "browse by family" page can list
+//! taxa with their counts without downloading and aggregating the full
+//! bundle. Each family lists its order, genera, species count, a
+//! representative species, and species per IUCN category; each genus lists
+//! its order and family with the same counts.
+//!
+//! The representative species is the earliest described living species of
+//! the taxon (ties broken by `phylosort`), which is often the type species
+//! of the genus, or of the type genus of the family. Taxa with extinct
+//! species only fall back to their earliest described species. IUCN counts
+//! follow `IucnStats`: species without a recognized category are left out.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    helper::iucn::IucnStatus,
+    parser::{iucn::CategoryCounts, mdd::MddData, ReleasedMddData},
+};
+
+/// File stem of the family summaries.
+pub const FAMILIES_FILE: &str = "families";
+/// File stem of the genus summaries.
+pub const GENERA_FILE: &str = "genera";
+
+/// The species shown for a family or genus.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepresentativeSpecies {
+    pub id: u32,
+    pub sci_name: String,
+    pub main_common_name: String,
+}
+
+/// One family of `families.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FamilySummary {
+    pub order: String,
+    /// Genera of the family, sorted.
+    pub genera: Vec<String>,
+    pub genus_count: u32,
+    pub species_count: u32,
+    pub extinct_count: u32,
+    pub representative: Option<RepresentativeSpecies>,
+    /// Species per IUCN category code.
+    pub iucn: CategoryCounts,
+    /// Species listed as VU, EN, or CR.
+    pub threatened_count: u32,
+}
+
+/// One genus of `genera.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenusSummary {
+    pub order: String,
+    pub family: String,
+    pub species_count: u32,
+    pub extinct_count: u32,
+    pub representative: Option<RepresentativeSpecies>,
+    /// Species per IUCN category code.
+    pub iucn: CategoryCounts,
+    /// Species listed as VU, EN, or CR.
+    pub threatened_count: u32,
+}
+
+/// Family and genus summaries of a release, keyed by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxonSummaries {
+    pub families: BTreeMap<String, FamilySummary>,
+    pub genera: BTreeMap<String, GenusSummary>,
+}
+
+impl TaxonSummaries {
+    pub fn from_release(release: &ReleasedMddData) -> Self {
+        let species: Vec<&MddData> = release.species().collect();
+        Self::build(&species)
+    }
+
+    pub fn from_species(species: &[MddData]) -> Self {
+        let species: Vec<&MddData> = species.iter().collect();
+        Self::build(&species)
+    }
+
+    fn build(species: &[&MddData]) -> Self {
+        let mut by_family: BTreeMap<&str, Vec<&MddData>> = BTreeMap::new();
+        let mut by_genus: BTreeMap<&str, Vec<&MddData>> = BTreeMap::new();
+        for record in species {
+            by_family.entry(&record.family).or_default().push(record);
+            by_genus.entry(&record.genus).or_default().push(record);
+        }
+        let families = by_family
+            .into_iter()
+            .map(|(family, members)| {
+                let genera: BTreeSet<&str> =
+                    members.iter().map(|record| record.genus.as_str()).collect();
+                let counts = Counts::new(&members);
+                let summary = FamilySummary {
+                    order: members[0].taxon_order.clone(),
+                    genus_count: genera.len() as u32,
+                    genera: genera.into_iter().map(str::to_string).collect(),
+                    species_count: members.len() as u32,
+                    extinct_count: counts.extinct,
+                    representative: representative(&members),
+                    iucn: counts.iucn,
+                    threatened_count: counts.threatened,
+                };
+                (family.to_string(), summary)
+            })
+            .collect();
+        let genera = by_genus
+            .into_iter()
+            .map(|(genus, members)| {
+                let counts = Counts::new(&members);
+                let summary = GenusSummary {
+                    order: members[0].taxon_order.clone(),
+                    family: members[0].family.clone(),
+                    species_count: members.len() as u32,
+                    extinct_count: counts.extinct,
+                    representative: representative(&members),
+                    iucn: counts.iucn,
+                    threatened_count: counts.threatened,
+                };
+                (genus.to_string(), summary)
+            })
+            .collect();
+        Self { families, genera }
+    }
+
+    pub fn families_json(&self) -> String {
+        serde_json::to_string(&self.families).expect("Failed to serialize")
+    }
+
+    pub fn genera_json(&self) -> String {
+        serde_json::to_string(&self.genera).expect("Failed to serialize")
+    }
+
+    /// Write `families.json` and `genera.json` into `output_dir`, returning
+    /// the written paths.
+    pub fn write(&self, output_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(output_dir)?;
+        let families_path = output_dir.join(FAMILIES_FILE).with_extension("json");
+        fs::write(&families_path, self.families_json())?;
+        let genera_path = output_dir.join(GENERA_FILE).with_extension("json");
+        fs::write(&genera_path, self.genera_json())?;
+        Ok(vec![families_path, genera_path])
+    }
+}
+
+/// Extinct, IUCN, and threatened counts of a group of species.
+struct Counts {
+    extinct: u32,
+    iucn: CategoryCounts,
+    threatened: u32,
+}
+
+impl Counts {
+    fn new(species: &[&MddData]) -> Self {
+        let mut counts = Self {
+            extinct: 0,
+            iucn: CategoryCounts::new(),
+            threatened: 0,
+        };
+        for record in species {
+            if record.extinct == 1 {
+                counts.extinct += 1;
+            }
+            let Ok(status) = record.iucn_status.parse::<IucnStatus>() else {
+                continue;
+            };
+            *counts.iucn.entry(status.code().to_string()).or_insert(0) += 1;
+            if status.is_threatened() {
+                counts.threatened += 1;
+            }
+        }
+        counts
+    }
+}
+
+/// Earliest described living species, else earliest described species;
+/// undated species (year 0) sort last.
+fn representative(species: &[&MddData]) -> Option<RepresentativeSpecies> {
+    species
+        .iter()
+        .min_by_key(|record| {
+            (
+                record.extinct == 1,
+                record.authority_species_year == 0,
+                record.authority_species_year,
+                record.phylosort,
+            )
+        })
+        .map(|record| RepresentativeSpecies {
+            id: record.id,
+            sci_name: record.sci_name.clone(),
+            main_common_name: record.main_common_name.clone(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn species(id: u32, genus: &str, year: u16, iucn: &str, extinct: u8) -> MddData {
+        MddData {
+            id,
+            sci_name: format!("{}_sp{}", genus, id),
+            taxon_order: "Carnivora".to_string(),
+            family: "Felidae".to_string(),
+            genus: genus.to_string(),
+            authority_species_year: year,
+            iucn_status: iucn.to_string(),
+            extinct,
+            phylosort: id as u16,
+            ..MddData::new()
+        }
+    }
+
+    #[test]
+    fn test_taxon_summaries() {
+        let records = [
+            species(1, "Panthera", 1758, "VU", 0),
+            species(2, "Panthera", 1816, "EN", 0),
+            species(3, "Felis", 1777, "LC", 0),
+            species(4, "Felis", 1700, "EX", 1),
+            species(5, "Felis", 0, "", 0),
+        ];
+        let summaries = TaxonSummaries::from_species(&records);
+
+        let felidae = &summaries.families["Felidae"];
+        assert_eq!(felidae.order, "Carnivora");
+        assert_eq!(felidae.genera, ["Felis", "Panthera"]);
+        assert_eq!(felidae.genus_count, 2);
+        assert_eq!(felidae.species_count, 5);
+        assert_eq!(felidae.extinct_count, 1);
+        assert_eq!(felidae.threatened_count, 2);
+        assert_eq!(felidae.iucn.values().sum::<u32>(), 4);
+        assert_eq!(felidae.representative.as_ref().unwrap().id, 1);
+
+        let felis = &summaries.genera["Felis"];
+        assert_eq!(felis.family, "Felidae");
+        assert_eq!(felis.species_count, 3);
+        assert_eq!(felis.iucn.get("EX"), Some(&1));
+        // The extinct species was described first, but a living one is shown.
+        assert_eq!(felis.representative.as_ref().unwrap().id, 3);
+
+        let json = summaries.families_json();
+        assert!(
+            json.starts_with(r#"{"Felidae":{"order":"Carnivora","genera":["Felis","Panthera"]"#)
+        );
+    }
+}